@@ -0,0 +1,15 @@
+contract test {
+    uint64 constant FEE = 25;
+
+    function fee(uint64 amount) internal pure returns (uint64) {
+        return (amount * FEE) / 1000;
+    }
+
+    function withFee(uint64 amount) public pure returns (uint64) {
+        return amount + fee(amount);
+    }
+
+    function fixedFee() public pure returns (uint64) {
+        return fee(2000);
+    }
+}
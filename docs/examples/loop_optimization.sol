@@ -0,0 +1,9 @@
+contract loops {
+    function total(uint64[] memory values) public pure returns (uint64 sum) {
+        for (uint32 i = 0; i < values.length; i++) {
+            unchecked {
+                sum += values[i] + uint64(i) * 3;
+            }
+        }
+    }
+}
@@ -0,0 +1,7 @@
+contract Implementation layout at 0x1000 {
+    uint64 counter;
+
+    function increment() public {
+        counter += 1;
+    }
+}
@@ -0,0 +1,10 @@
+contract Token {
+    bool paused;
+    uint8 decimals = 18;
+    int16 fee;
+    uint256 totalSupply;
+
+    function pause() public {
+        paused = true;
+    }
+}
@@ -0,0 +1,9 @@
+contract counter {
+    uint64 count;
+
+    function add(uint64[] memory values) public {
+        for (uint i = 0; i < values.length; i++) {
+            count += values[i];
+        }
+    }
+}
@@ -71,6 +71,13 @@ impl Display for pt::Base {
     }
 }
 
+impl Display for pt::StorageLayoutSpecifier {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str("layout at ")?;
+        self.base_slot.fmt(f)
+    }
+}
+
 impl Display for pt::ContractDefinition {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         self.ty.fmt(f)?;
@@ -83,6 +90,11 @@ impl Display for pt::ContractDefinition {
             f.write_char(' ')?;
         }
 
+        if let Some(layout) = &self.layout {
+            layout.fmt(f)?;
+            f.write_char(' ')?;
+        }
+
         f.write_char('{')?;
         write_separated(&self.parts, f, " ")?;
         f.write_char('}')
@@ -1827,6 +1839,7 @@ mod tests {
                 ty: pt::ContractTy::Contract(loc!()),
                 name: Some(id("name")),
                 base: vec![],
+                layout: None,
                 parts: vec![],
             } => "contract name {}",
             pt::ContractDefinition {
@@ -1837,6 +1850,7 @@ mod tests {
                     name: idp!("base"),
                     args: None
                 }],
+                layout: None,
                 parts: vec![],
             } => "contract name base {}",
             pt::ContractDefinition {
@@ -1847,6 +1861,7 @@ mod tests {
                     name: idp!("base"),
                     args: Some(vec![])
                 }],
+                layout: None,
                 parts: vec![],
             } => "contract name base() {}",
             pt::ContractDefinition {
@@ -1857,6 +1872,7 @@ mod tests {
                     name: idp!("base"),
                     args: Some(vec![expr!(expr)])
                 }],
+                layout: None,
                 parts: vec![],
             } => "contract name base(expr) {}",
             pt::ContractDefinition {
@@ -1874,8 +1890,19 @@ mod tests {
                         args: None
                     },
                 ],
+                layout: None,
                 parts: vec![],
             } => "contract name base1 base2 {}",
+            pt::ContractDefinition {
+                ty: pt::ContractTy::Contract(loc!()),
+                name: Some(id("name")),
+                base: vec![],
+                layout: Some(pt::StorageLayoutSpecifier {
+                    loc: loc!(),
+                    base_slot: expr!(42),
+                }),
+                parts: vec![],
+            } => "contract name layout at 42 {}",
 
             pt::EnumDefinition {
                 name: Some(id("name")),
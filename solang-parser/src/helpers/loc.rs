@@ -115,6 +115,7 @@ impl_optional_for_pt!(
     pt::IdentifierPath,
     pt::NamedArgument,
     pt::Parameter,
+    pt::StorageLayoutSpecifier,
     pt::StringLiteral,
     pt::StructDefinition,
     pt::TypeDefinition,
@@ -228,6 +229,7 @@ impl_for_structs!(
     pt::IdentifierPath,
     pt::NamedArgument,
     pt::Parameter,
+    pt::StorageLayoutSpecifier,
     pt::StringLiteral,
     pt::StructDefinition,
     pt::TypeDefinition,
@@ -482,6 +484,7 @@ impl_for_enums! {
         | Self::InvalidCharacterInHexLiteral(l, _)
         | Self::UnrecognisedToken(l, _)
         | Self::ExpectedFrom(l, _)
+        | Self::ExpectedKeyword(l, ..)
         | Self::MissingExponent(l) => l,
     }
 }
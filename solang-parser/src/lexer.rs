@@ -382,6 +382,9 @@ pub enum LexicalError {
 
     #[error("'{1}' found where 'from' expected")]
     ExpectedFrom(Loc, String),
+
+    #[error("'{1}' found where '{2}' expected")]
+    ExpectedKeyword(Loc, String, &'static str),
 }
 
 /// Returns whether `word` is a keyword in Solidity.
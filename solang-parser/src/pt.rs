@@ -833,9 +833,21 @@ pub struct Base {
     pub args: Option<Vec<Expression>>,
 }
 
+/// A storage layout specifier (see [ContractDefinition]).
+///
+/// `layout at <base_slot>`
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "pt-serde", derive(Serialize, Deserialize))]
+pub struct StorageLayoutSpecifier {
+    /// The code location.
+    pub loc: Loc,
+    /// The expression for the first storage slot.
+    pub base_slot: Expression,
+}
+
 /// A contract definition.
 ///
-/// `<ty> <name> [<base>,*] { <parts>,* }`
+/// `<ty> <name> [<base>,*] [layout at <expr>] { <parts>,* }`
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[cfg_attr(feature = "pt-serde", derive(Serialize, Deserialize))]
 pub struct ContractDefinition {
@@ -849,6 +861,8 @@ pub struct ContractDefinition {
     pub name: Option<Identifier>,
     /// The list of inheritance specifiers.
     pub base: Vec<Base>,
+    /// The optional storage layout specifier.
+    pub layout: Option<StorageLayoutSpecifier>,
     /// The list of contract parts.
     pub parts: Vec<ContractPart>,
 }
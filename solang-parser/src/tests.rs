@@ -115,6 +115,7 @@ fn parse_test() {
                 name: "foo".to_string(),
             }),
             base: Vec::new(),
+            layout: None,
             parts: vec![
                 ContractPart::StructDefinition(Box::new(StructDefinition {
                     name: Some(Identifier {
@@ -470,6 +471,7 @@ fn parse_error_test() {
                 name: "TestToken".to_string(),
             }),
             base: vec![],
+            layout: None,
             parts: vec![
                 ContractPart::ErrorDefinition(Box::new(ErrorDefinition {
                     loc: Loc::File(0, 102, 120),
@@ -1028,6 +1030,7 @@ fn parse_revert_test() {
                 name: "TestToken".to_string(),
             }),
             base: vec![],
+            layout: None,
             parts: vec![
                 ContractPart::ErrorDefinition(Box::new(ErrorDefinition {
                     loc: Loc::File(0, 42, 59),
@@ -1122,6 +1125,7 @@ fn parse_user_defined_value_type() {
                 name: "TestToken".to_string(),
             }),
             base: vec![],
+            layout: None,
             parts: vec![ContractPart::TypeDefinition(Box::new(TypeDefinition {
                 loc: Loc::File(0, 75, 98),
                 name: Identifier {
@@ -1136,6 +1140,80 @@ fn parse_user_defined_value_type() {
     assert_eq!(actual_parse_tree, expected_parse_tree);
 }
 
+#[test]
+fn parse_storage_layout_specifier() {
+    let src = r#"
+        contract A layout at 0x1000 + 2 * SLOT {}
+        contract B is A layout at 42 {}
+        contract C layout at uint256(keccak256("C")) is A, B {}
+        contract layout {}
+        "#;
+
+    let (actual_parse_tree, _) = crate::parse(src, 0).unwrap();
+    assert_eq!(actual_parse_tree.0.len(), 4);
+
+    let layouts: Vec<_> = actual_parse_tree
+        .0
+        .iter()
+        .map(|part| match part {
+            SourceUnitPart::ContractDefinition(c) => (
+                c.name.as_ref().unwrap().name.as_str(),
+                c.base.len(),
+                c.layout.as_ref().map(|l| l.base_slot.to_string()),
+            ),
+            _ => unreachable!(),
+        })
+        .collect();
+
+    assert_eq!(
+        layouts,
+        vec![
+            ("A", 0, Some("0x1000 + 2 * SLOT".to_string())),
+            ("B", 1, Some("42".to_string())),
+            ("C", 2, Some("uint256(keccak256(\"C\"))".to_string())),
+            ("layout", 0, None),
+        ]
+    );
+
+    let errors = crate::parse("contract D layout on 1 {}", 0).unwrap_err();
+    assert_eq!(
+        errors,
+        vec![Diagnostic {
+            loc: File(0, 18, 20),
+            level: Error,
+            ty: ParserError,
+            message: "'on' found where 'at' expected".to_string(),
+            notes: vec![]
+        }]
+    );
+
+    let (actual_parse_tree, _) = crate::parse("contract A layout at 1 {}", 0).unwrap();
+
+    let expected_parse_tree = SourceUnit(vec![SourceUnitPart::ContractDefinition(Box::new(
+        ContractDefinition {
+            loc: Loc::File(0, 0, 25),
+            ty: ContractTy::Contract(Loc::File(0, 0, 8)),
+            name: Some(Identifier {
+                loc: Loc::File(0, 9, 10),
+                name: "A".to_string(),
+            }),
+            base: vec![],
+            layout: Some(StorageLayoutSpecifier {
+                loc: Loc::File(0, 11, 22),
+                base_slot: Expression::NumberLiteral(
+                    Loc::File(0, 21, 22),
+                    "1".to_string(),
+                    "".to_string(),
+                    None,
+                ),
+            }),
+            parts: vec![],
+        },
+    ))]);
+
+    assert_eq!(actual_parse_tree, expected_parse_tree);
+}
+
 #[test]
 fn parse_no_parameters_yul_function() {
     let src = r#"
@@ -1294,6 +1372,7 @@ contract MyTest {
                 name: "MyTest".to_string(),
             }),
             base: vec![],
+            layout: None,
             parts: vec![ContractPart::FunctionDefinition(
                 FunctionDefinition {
                     loc_prototype: File(0, 23, 55),
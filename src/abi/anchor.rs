@@ -21,13 +21,15 @@ use solang_parser::pt::FunctionTy;
 /// value anchor uses to dispatch function calls on. This should match
 /// anchor's behaviour - we need to match the discriminator exactly
 pub fn function_discriminator(name: &str) -> Vec<u8> {
-    // must match snake-case npm library, see
-    // https://github.com/coral-xyz/anchor/blob/master/ts/packages/anchor/src/coder/borsh/instruction.ts#L389
-    let normalized = name
-        .from_case(Case::Camel)
+    discriminator("global", &snake_case(name))
+}
+
+/// Convert a name to snake case. This must match the snake-case npm library, see
+/// https://github.com/coral-xyz/anchor/blob/master/ts/packages/anchor/src/coder/borsh/instruction.ts#L389
+pub(super) fn snake_case(name: &str) -> String {
+    name.from_case(Case::Camel)
         .without_boundaries(&[Boundary::LowerDigit])
-        .to_case(Case::Snake);
-    discriminator("global", &normalized)
+        .to_case(Case::Snake)
 }
 
 /// Generate discriminator based on the name of the event. This is the 8 byte
@@ -43,13 +45,35 @@ fn discriminator(namespace: &'static str, name: &str) -> Vec<u8> {
     hasher.finalize()[..8].to_vec()
 }
 
+/// The layout of the Anchor IDL
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IdlSpec {
+    /// The layout used before Anchor 0.30
+    #[default]
+    Legacy,
+    /// The layout of Anchor 0.30 and later, see [`super::anchor_spec`]
+    Anchor030,
+}
+
 /// Generate an Anchor IDL for a Solidity contract.
 pub fn generate_anchor_idl(contract_no: usize, ns: &Namespace, contract_version: &str) -> Idl {
+    anchor_idl(contract_no, ns, contract_version).0
+}
+
+/// Generate an Anchor IDL for a Solidity contract, and the function of each instruction. The
+/// default constructor has no function.
+pub(super) fn anchor_idl(
+    contract_no: usize,
+    ns: &Namespace,
+    contract_version: &str,
+) -> (Idl, Vec<Option<usize>>) {
     let contract = &ns.contracts[contract_no];
     let docs = idl_docs(&contract.tags);
     let mut type_manager = TypeManager::new(ns, contract_no);
 
-    let instructions = idl_instructions(contract_no, contract, &mut type_manager, ns);
+    let (functions, instructions) = idl_instructions(contract_no, contract, &mut type_manager, ns)
+        .into_iter()
+        .unzip();
 
     let events = idl_events(contract, &mut type_manager, ns);
 
@@ -58,7 +82,7 @@ pub fn generate_anchor_idl(contract_no: usize, ns: &Namespace, contract_version:
         .as_ref()
         .map(|id| json!({"address": id.to_base58()}));
 
-    Idl {
+    let idl = Idl {
         version: Version::parse(contract_version).unwrap().to_string(),
         name: ns.contracts[contract_no].id.name.clone(),
         docs,
@@ -69,7 +93,9 @@ pub fn generate_anchor_idl(contract_no: usize, ns: &Namespace, contract_version:
         events,
         errors: None,
         metadata,
-    }
+    };
+
+    (idl, functions)
 }
 
 /// Generate IDL events for a contract.
@@ -105,31 +131,34 @@ fn idl_events(
     }
 }
 
-/// Generate the IDL instructions for a contract.
+/// Generate the IDL instructions for a contract, with the function of each.
 fn idl_instructions(
     contract_no: usize,
     contract: &Contract,
     type_manager: &mut TypeManager,
     ns: &Namespace,
-) -> Vec<IdlInstruction> {
-    let mut instructions: Vec<IdlInstruction> = Vec::new();
+) -> Vec<(Option<usize>, IdlInstruction)> {
+    let mut instructions: Vec<(Option<usize>, IdlInstruction)> = Vec::new();
 
     if contract.constructors(ns).is_empty() {
-        instructions.push(IdlInstruction {
-            name: "new".to_string(),
-            docs: None,
-            accounts: vec![IdlAccountItem::IdlAccount(IdlAccount {
-                name: "dataAccount".to_string(),
-                is_mut: true,
-                is_signer: false,
-                is_optional: Some(false),
+        instructions.push((
+            None,
+            IdlInstruction {
+                name: "new".to_string(),
                 docs: None,
-                pda: None,
-                relations: vec![],
-            })],
-            args: vec![],
-            returns: None,
-        })
+                accounts: vec![IdlAccountItem::IdlAccount(IdlAccount {
+                    name: "dataAccount".to_string(),
+                    is_mut: true,
+                    is_signer: false,
+                    is_optional: Some(false),
+                    docs: None,
+                    pda: None,
+                    relations: vec![],
+                })],
+                args: vec![],
+                returns: None,
+            },
+        ))
     }
 
     for func_no in contract.all_functions.keys() {
@@ -194,13 +223,16 @@ fn idl_instructions(
             Some(type_manager.build_struct_for_return(func, &name))
         };
 
-        instructions.push(IdlInstruction {
-            name,
-            docs: tags,
-            accounts,
-            args,
-            returns,
-        });
+        instructions.push((
+            Some(*func_no),
+            IdlInstruction {
+                name,
+                docs: tags,
+                accounts,
+                args,
+                returns,
+            },
+        ));
     }
 
     instructions
@@ -0,0 +1,380 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Anchor IDL in the layout of Anchor 0.30 and later. It is converted from the legacy IDL,
+//! with the instruction and event discriminators, the addresses of well-known accounts, and
+//! the program derived address of the data account created by a constructor.
+//!
+//! See <https://github.com/coral-xyz/anchor/tree/master/idl/spec> for the specification.
+
+use super::anchor::{anchor_idl, event_discriminator, function_discriminator, snake_case};
+use crate::sema::ast::{Expression, Function, Namespace};
+use crate::sema::solana_accounts::BuiltinAccounts;
+use anchor_syn::idl::types as legacy;
+use base58::ToBase58;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// The version of the IDL specification which is generated
+pub const IDL_SPEC: &str = "0.1.0";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Idl {
+    /// The program id, or empty if the contract has no `@program_id`
+    pub address: String,
+    pub metadata: IdlMetadata,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub docs: Vec<String>,
+    pub instructions: Vec<IdlInstruction>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<IdlEvent>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub types: Vec<IdlTypeDef>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IdlMetadata {
+    pub name: String,
+    pub version: String,
+    pub spec: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IdlInstruction {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub docs: Vec<String>,
+    pub discriminator: Vec<u8>,
+    pub accounts: Vec<IdlInstructionAccount>,
+    pub args: Vec<IdlField>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub returns: Option<IdlType>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IdlInstructionAccount {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub docs: Vec<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub writable: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub signer: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub optional: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pda: Option<IdlPda>,
+}
+
+/// The seeds of a program derived address. The bump is not part of it; clients find the
+/// canonical bump.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IdlPda {
+    pub seeds: Vec<IdlSeed>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum IdlSeed {
+    /// A constant sequence of bytes
+    Const { value: Vec<u8> },
+    /// The value of an instruction argument
+    Arg { path: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IdlEvent {
+    /// The fields of the event are in the type with the same name
+    pub name: String,
+    pub discriminator: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IdlField {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub docs: Vec<String>,
+    #[serde(rename = "type")]
+    pub ty: IdlType,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IdlTypeDef {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub docs: Vec<String>,
+    #[serde(rename = "type")]
+    pub ty: IdlTypeDefTy,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum IdlTypeDefTy {
+    Struct { fields: Vec<IdlField> },
+    Enum { variants: Vec<IdlEnumVariant> },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IdlEnumVariant {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum IdlType {
+    Bool,
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    U128,
+    I128,
+    U256,
+    I256,
+    Bytes,
+    String,
+    Pubkey,
+    Option(Box<IdlType>),
+    Vec(Box<IdlType>),
+    Array(Box<IdlType>, usize),
+    Defined { name: String },
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+/// Generate an Anchor IDL in the layout of Anchor 0.30 for a Solidity contract.
+pub fn generate_anchor_idl(contract_no: usize, ns: &Namespace, contract_version: &str) -> Idl {
+    let (idl, functions) = anchor_idl(contract_no, ns, contract_version);
+    let contract = &ns.contracts[contract_no];
+
+    let instructions = idl
+        .instructions
+        .into_iter()
+        .zip(functions)
+        .map(|(instruction, function_no)| {
+            let func = function_no.map(|function_no| &ns.functions[function_no]);
+
+            let discriminator = match func {
+                Some(func) => func.selector(ns, &contract_no),
+                None => function_discriminator("new"),
+            };
+
+            // the legacy names of the arguments lose the word boundaries of camel case, so
+            // they are converted from the declared names of the parameters instead
+            let args: Vec<IdlField> = instruction
+                .args
+                .into_iter()
+                .enumerate()
+                .map(|(arg_no, arg)| {
+                    let name = func
+                        .and_then(|func| func.params[arg_no].id.as_ref())
+                        .filter(|id| !id.name.is_empty())
+                        .map_or(arg.name, |id| snake_case(&id.name));
+
+                    IdlField {
+                        name,
+                        docs: arg.docs.unwrap_or_default(),
+                        ty: convert(arg.ty),
+                    }
+                })
+                .collect();
+
+            let accounts = instruction
+                .accounts
+                .into_iter()
+                .filter_map(|item| match item {
+                    legacy::IdlAccountItem::IdlAccount(account) => Some(account),
+                    // composite accounts are never generated
+                    legacy::IdlAccountItem::IdlAccounts(_) => None,
+                })
+                .map(|account| {
+                    let builtin = BuiltinAccounts::from_str(&account.name).ok();
+
+                    let pda = match (&builtin, func) {
+                        (Some(BuiltinAccounts::DataAccount), Some(func))
+                            if func.is_constructor() =>
+                        {
+                            pda(func, |arg_no| args.get(arg_no).map(|arg| arg.name.clone()))
+                        }
+                        _ => None,
+                    };
+
+                    IdlInstructionAccount {
+                        name: snake_case(&account.name),
+                        docs: account.docs.unwrap_or_default(),
+                        writable: account.is_mut,
+                        signer: account.is_signer,
+                        optional: account.is_optional.unwrap_or(false),
+                        address: builtin
+                            .and_then(|builtin| builtin.address())
+                            .map(String::from),
+                        pda,
+                    }
+                })
+                .collect();
+
+            IdlInstruction {
+                name: snake_case(&instruction.name),
+                docs: instruction.docs.unwrap_or_default(),
+                discriminator,
+                accounts,
+                args,
+                returns: instruction.returns.map(convert),
+            }
+        })
+        .collect();
+
+    let mut types: Vec<IdlTypeDef> = idl.types.into_iter().map(convert_type_def).collect();
+    let mut events = Vec::new();
+
+    // the fields of an event are described by a type with the same name
+    for event in idl.events.unwrap_or_default() {
+        events.push(IdlEvent {
+            discriminator: event_discriminator(&event.name),
+            name: event.name.clone(),
+        });
+
+        types.push(IdlTypeDef {
+            name: event.name,
+            docs: Vec::new(),
+            ty: IdlTypeDefTy::Struct {
+                fields: event
+                    .fields
+                    .into_iter()
+                    .map(|field| IdlField {
+                        name: field.name,
+                        docs: Vec::new(),
+                        ty: convert(field.ty),
+                    })
+                    .collect(),
+            },
+        });
+    }
+
+    Idl {
+        address: contract
+            .program_id
+            .as_ref()
+            .map(|id| id.to_base58())
+            .unwrap_or_default(),
+        metadata: IdlMetadata {
+            name: snake_case(&idl.name),
+            version: idl.version,
+            spec: IDL_SPEC.to_owned(),
+        },
+        docs: idl.docs.unwrap_or_default(),
+        instructions,
+        events,
+        types,
+    }
+}
+
+/// Describe the address of the data account from the `@seed` annotations of the constructor.
+/// This is only possible if every seed is a constant or an argument.
+/// The name of an argument is given by `arg_name`.
+pub(super) fn pda(func: &Function, arg_name: impl Fn(usize) -> Option<String>) -> Option<IdlPda> {
+    if func.annotations.seeds.is_empty() {
+        return None;
+    }
+
+    let seeds = func
+        .annotations
+        .seeds
+        .iter()
+        .map(|(_, seed)| match seed {
+            Expression::AllocDynamicBytes {
+                init: Some(value), ..
+            }
+            | Expression::BytesLiteral { value, .. } => Some(IdlSeed::Const {
+                value: value.clone(),
+            }),
+            Expression::Cast { expr, .. } | Expression::BytesCast { expr, .. } => {
+                let Expression::Variable { var_no, .. } = expr.as_ref() else {
+                    return None;
+                };
+
+                let arg_no = func
+                    .symtable
+                    .arguments
+                    .iter()
+                    .position(|arg| *arg == Some(*var_no))?;
+
+                Some(IdlSeed::Arg {
+                    path: arg_name(arg_no)?,
+                })
+            }
+            _ => None,
+        })
+        .collect::<Option<Vec<IdlSeed>>>()?;
+
+    Some(IdlPda { seeds })
+}
+
+fn convert_type_def(def: legacy::IdlTypeDefinition) -> IdlTypeDef {
+    let ty = match def.ty {
+        legacy::IdlTypeDefinitionTy::Struct { fields } => IdlTypeDefTy::Struct {
+            fields: fields
+                .into_iter()
+                .map(|field| IdlField {
+                    name: field.name,
+                    docs: field.docs.unwrap_or_default(),
+                    ty: convert(field.ty),
+                })
+                .collect(),
+        },
+        legacy::IdlTypeDefinitionTy::Enum { variants } => IdlTypeDefTy::Enum {
+            variants: variants
+                .into_iter()
+                .map(|variant| IdlEnumVariant { name: variant.name })
+                .collect(),
+        },
+        legacy::IdlTypeDefinitionTy::Alias { .. } => {
+            unreachable!("type aliases are not generated")
+        }
+    };
+
+    IdlTypeDef {
+        name: def.name,
+        docs: def.docs.unwrap_or_default(),
+        ty,
+    }
+}
+
+fn convert(ty: legacy::IdlType) -> IdlType {
+    match ty {
+        legacy::IdlType::Bool => IdlType::Bool,
+        legacy::IdlType::U8 => IdlType::U8,
+        legacy::IdlType::I8 => IdlType::I8,
+        legacy::IdlType::U16 => IdlType::U16,
+        legacy::IdlType::I16 => IdlType::I16,
+        legacy::IdlType::U32 => IdlType::U32,
+        legacy::IdlType::I32 => IdlType::I32,
+        legacy::IdlType::U64 => IdlType::U64,
+        legacy::IdlType::I64 => IdlType::I64,
+        legacy::IdlType::U128 => IdlType::U128,
+        legacy::IdlType::I128 => IdlType::I128,
+        legacy::IdlType::U256 => IdlType::U256,
+        legacy::IdlType::I256 => IdlType::I256,
+        legacy::IdlType::Bytes => IdlType::Bytes,
+        legacy::IdlType::String => IdlType::String,
+        legacy::IdlType::PublicKey => IdlType::Pubkey,
+        legacy::IdlType::Defined(name) => IdlType::Defined { name },
+        legacy::IdlType::Option(ty) => IdlType::Option(Box::new(convert(*ty))),
+        legacy::IdlType::Vec(ty) => IdlType::Vec(Box::new(convert(*ty))),
+        legacy::IdlType::Array(ty, len) => IdlType::Array(Box::new(convert(*ty)), len),
+        legacy::IdlType::F32
+        | legacy::IdlType::F64
+        | legacy::IdlType::GenericLenArray(..)
+        | legacy::IdlType::Generic(_)
+        | legacy::IdlType::DefinedWithTypeArgs { .. } => {
+            unreachable!("type is not generated for Solidity")
+        }
+    }
+}
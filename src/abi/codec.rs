@@ -0,0 +1,882 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Encode and decode the call data, return data, events and errors of a contract, for example
+//! to inspect the data of a failed transaction. On Polkadot and Solana, the SCALE and Borsh
+//! encoders of codegen are used, so the data is encoded exactly like the contract encodes
+//! and decodes it. On EVM the data is Ethereum ABI encoded.
+//!
+//! Values are written as text: numbers in decimal or hex, `bytes` and `bytesN` in hex,
+//! addresses in hex or base58 on Solana, enums by the name of the variant, and arrays and
+//! structs as JSON arrays or objects.
+
+use crate::abi::anchor::{event_discriminator, function_discriminator};
+use crate::codegen::encoding::create_encoder;
+use crate::codegen::revert::{SolidityError, ERROR_SELECTOR, PANIC_SELECTOR};
+use crate::codegen::Expression;
+use crate::sema::ast::{ArrayLength, Namespace, Parameter, Type};
+use crate::Target;
+use base58::{FromBase58, ToBase58};
+use num_bigint::{BigInt, Sign};
+use num_traits::{One, Signed, Zero};
+use serde_json::Value as JsonValue;
+use solang_parser::pt::{FunctionTy, Identifier, Loc::Codegen};
+
+/// What the data of a message is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// The call data of a function or constructor
+    Call,
+    /// The return data of a function
+    Return,
+    /// The data of an event
+    Event,
+    /// The data returned when reverting with an error
+    Error,
+}
+
+/// A function, event or error of a contract, and how its data is laid out
+#[derive(Debug)]
+pub struct Message {
+    pub kind: Kind,
+    pub name: String,
+    /// The selector, discriminator or event index which comes before the encoded values
+    pub prefix: Vec<u8>,
+    /// The values of the data
+    pub params: Vec<Parameter<Type>>,
+}
+
+impl Message {
+    /// Find the function, constructor, event or error of the contract with the given name.
+    /// If `returns` is set, the message is the return data of the function.
+    pub fn find(
+        ns: &Namespace,
+        contract_no: usize,
+        name: &str,
+        returns: bool,
+    ) -> Result<Message, String> {
+        if let Some(message) = function(ns, contract_no, name, returns)? {
+            return Ok(message);
+        }
+
+        if returns {
+            return Err(format!("no function '{name}' found"));
+        }
+
+        if let Some(message) = event(ns, contract_no, name) {
+            return Ok(message);
+        }
+
+        if let Some(message) = error(ns, name)? {
+            return Ok(message);
+        }
+
+        Err(format!("no function, event or error '{name}' found"))
+    }
+
+    /// Encode the data from the values given as text
+    pub fn encode(&self, args: &[String], ns: &Namespace) -> Result<Vec<u8>, String> {
+        if args.len() != self.params.len() {
+            return Err(format!(
+                "'{}' expects {} values, {} given",
+                self.name,
+                self.params.len(),
+                args.len()
+            ));
+        }
+
+        let values = self
+            .params
+            .iter()
+            .zip(args)
+            .map(|(param, arg)| {
+                // values of arrays and structs are json, anything else is given as is
+                let json = match param.ty.clone().unwrap_user_type(ns) {
+                    Type::Array(..) | Type::Slice(_) | Type::Struct(_) => serde_json::from_str(arg)
+                        .map_err(|err| {
+                            format!("{}: {arg} is not valid json: {err}", param.name_as_str())
+                        })?,
+                    _ => JsonValue::String(arg.to_owned()),
+                };
+
+                parse_value(&param.ty, &json, ns)
+                    .map_err(|err| format!("{}: {err}", param.name_as_str()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut data = self.prefix.clone();
+
+        if ns.target == Target::EVM {
+            ethereum::encode(&values, ns, &mut data);
+        } else {
+            data.extend(
+                create_encoder(ns, false)
+                    .const_encode(&values, ns)
+                    .ok_or("value cannot be encoded")?,
+            );
+        }
+
+        Ok(data)
+    }
+
+    /// Decode the data, and return the name and value as text of each value
+    pub fn decode(&self, data: &[u8], ns: &Namespace) -> Result<Vec<(String, String)>, String> {
+        let Some(mut data) = data.strip_prefix(self.prefix.as_slice()) else {
+            return Err(format!(
+                "data does not start with {} of '{}'",
+                hex::encode(&self.prefix),
+                self.name
+            ));
+        };
+
+        let values = if ns.target == Target::EVM {
+            let tys: Vec<Type> = self.params.iter().map(|param| param.ty.clone()).collect();
+
+            ethereum::decode(&tys, data, ns).ok_or("data is not valid for the types")?
+        } else {
+            let encoder = create_encoder(ns, false);
+
+            let values = self
+                .params
+                .iter()
+                .map(|param| {
+                    encoder
+                        .const_decode(&param.ty, &mut data, ns)
+                        .ok_or_else(|| format!("{}: data is not valid", param.name_as_str()))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if !data.is_empty() {
+                return Err(format!("{} bytes left over after decoding", data.len()));
+            }
+
+            values
+        };
+
+        Ok(self
+            .params
+            .iter()
+            .zip(values)
+            .enumerate()
+            .map(|(no, (param, value))| {
+                let name = match param.name_as_str() {
+                    "" => no.to_string(),
+                    name => name.to_owned(),
+                };
+
+                (name, format_value(&value, ns))
+            })
+            .collect())
+    }
+}
+
+fn function(
+    ns: &Namespace,
+    contract_no: usize,
+    name: &str,
+    returns: bool,
+) -> Result<Option<Message>, String> {
+    let contract = &ns.contracts[contract_no];
+
+    let functions: Vec<usize> = contract
+        .all_functions
+        .keys()
+        .copied()
+        .filter(|func_no| {
+            let func = &ns.functions[*func_no];
+
+            let func_name = if func.ty == FunctionTy::Constructor && func.id.name.is_empty() {
+                "new"
+            } else {
+                &func.id.name
+            };
+
+            (func_name == name || func.mangled_name == name)
+                && matches!(func.ty, FunctionTy::Function | FunctionTy::Constructor)
+                && ns.function_externally_callable(contract_no, Some(*func_no))
+        })
+        .collect();
+
+    let func_no = match functions.as_slice() {
+        [] => {
+            // Solana contracts without a constructor can still be created
+            if ns.target == Target::Solana && name == "new" && contract.instantiable {
+                return Ok(Some(Message {
+                    kind: if returns { Kind::Return } else { Kind::Call },
+                    name: name.to_owned(),
+                    prefix: if returns {
+                        Vec::new()
+                    } else {
+                        function_discriminator("new")
+                    },
+                    params: Vec::new(),
+                }));
+            }
+
+            return Ok(None);
+        }
+        [func_no] => *func_no,
+        _ => {
+            return Err(format!(
+                "'{name}' is overloaded, use one of: {}",
+                functions
+                    .iter()
+                    .map(|func_no| ns.functions[*func_no].mangled_name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+        }
+    };
+
+    let func = &ns.functions[func_no];
+
+    Ok(Some(if returns {
+        Message {
+            kind: Kind::Return,
+            name: name.to_owned(),
+            prefix: Vec::new(),
+            params: func.returns.to_vec(),
+        }
+    } else {
+        Message {
+            kind: Kind::Call,
+            name: name.to_owned(),
+            prefix: func.selector(ns, &contract_no),
+            params: func.params.to_vec(),
+        }
+    }))
+}
+
+fn event(ns: &Namespace, contract_no: usize, name: &str) -> Option<Message> {
+    let emits_events = &ns.contracts[contract_no].emits_events;
+
+    // interfaces do not emit events, so use all the events declared
+    let events: Vec<usize> = if emits_events.is_empty() {
+        (0..ns.events.len()).collect()
+    } else {
+        emits_events.clone()
+    };
+
+    let (index, event_no) = events
+        .iter()
+        .enumerate()
+        .find(|(_, event_no)| ns.events[**event_no].id.name == name)?;
+
+    let event = &ns.events[*event_no];
+
+    let (prefix, params) = match ns.target {
+        Target::Solana => (event_discriminator(name), event.fields.clone()),
+        Target::EVM => (
+            Vec::new(),
+            // the indexed fields are topics, not data
+            event
+                .fields
+                .iter()
+                .filter(|field| !field.indexed)
+                .cloned()
+                .collect(),
+        ),
+        // ink! v5 identifies the event by its signature topic, which is not part of the data
+        _ if ns.is_ink_v5() => (Vec::new(), event.fields.clone()),
+        _ => (vec![index as u8], event.fields.clone()),
+    };
+
+    Some(Message {
+        kind: Kind::Event,
+        name: name.to_owned(),
+        prefix,
+        params,
+    })
+}
+
+fn error(ns: &Namespace, name: &str) -> Result<Option<Message>, String> {
+    let builtin = |selector: [u8; 4], field: &str, ty| Message {
+        kind: Kind::Error,
+        name: name.to_owned(),
+        prefix: selector.to_vec(),
+        params: vec![Parameter {
+            id: Some(Identifier {
+                loc: Codegen,
+                name: field.to_owned(),
+            }),
+            ..Parameter::new_default(ty)
+        }],
+    };
+
+    let message = match name {
+        "Error" => builtin(ERROR_SELECTOR, "message", Type::String),
+        "Panic" => builtin(PANIC_SELECTOR, "code", Type::Uint(256)),
+        _ => {
+            let Some(error_no) = ns.errors.iter().position(|error| error.name == name) else {
+                return Ok(None);
+            };
+
+            let exprs = Vec::new();
+
+            Message {
+                kind: Kind::Error,
+                name: name.to_owned(),
+                prefix: SolidityError::Custom { error_no, exprs }
+                    .selector(ns)
+                    .to_vec(),
+                params: ns.errors[error_no].fields.clone(),
+            }
+        }
+    };
+
+    if ns.target == Target::Solana {
+        return Err("Solana programs do not return data when they fail".into());
+    }
+
+    Ok(Some(message))
+}
+
+/// Convert a value given as json into a literal of the given type
+fn parse_value(ty: &Type, value: &JsonValue, ns: &Namespace) -> Result<Expression, String> {
+    let number = |value| Expression::NumberLiteral {
+        loc: Codegen,
+        ty: ty.clone(),
+        value,
+    };
+
+    match ty.clone().unwrap_user_type(ns) {
+        Type::Bool => match value {
+            JsonValue::Bool(value) => Ok(*value),
+            JsonValue::String(s) if s == "true" => Ok(true),
+            JsonValue::String(s) if s == "false" => Ok(false),
+            _ => Err(format!("{value} is not a bool")),
+        }
+        .map(|value| Expression::BoolLiteral {
+            loc: Codegen,
+            value,
+        }),
+        Type::Uint(width) => parse_int(value, false, width).map(number),
+        Type::Int(width) => parse_int(value, true, width).map(number),
+        Type::Value => parse_int(value, false, ns.value_length as u16 * 8).map(number),
+        Type::Enum(enum_no) => {
+            let values = &ns.enums[enum_no].values;
+
+            match value {
+                JsonValue::String(s) if values.contains_key(s) => values.get_index_of(s),
+                JsonValue::String(s) => s.parse().ok().filter(|no| *no < values.len()),
+                JsonValue::Number(n) => n
+                    .as_u64()
+                    .map(|no| no as usize)
+                    .filter(|no| *no < values.len()),
+                _ => None,
+            }
+            .map(|no| number(no.into()))
+            .ok_or_else(|| format!("{value} is not a value of enum {}", ns.enums[enum_no]))
+        }
+        Type::Bytes(length) => {
+            let bytes = parse_hex(value)?;
+
+            if bytes.len() != length as usize {
+                return Err(format!("bytes{length} needs {length} bytes of hex"));
+            }
+
+            Ok(number(BigInt::from_bytes_be(Sign::Plus, &bytes)))
+        }
+        Type::Address(_) | Type::Contract(_) => {
+            let bytes = match value {
+                JsonValue::String(s) if ns.target == Target::Solana && !s.starts_with("0x") => s
+                    .from_base58()
+                    .map_err(|_| format!("{value} is not a base58 address"))?,
+                _ => parse_hex(value)?,
+            };
+
+            if bytes.len() != ns.address_length {
+                return Err(format!(
+                    "an address is {} bytes, {} given",
+                    ns.address_length,
+                    bytes.len()
+                ));
+            }
+
+            Ok(number(BigInt::from_bytes_be(Sign::Plus, &bytes)))
+        }
+        Type::String => match value {
+            JsonValue::String(s) => Ok(Expression::BytesLiteral {
+                loc: Codegen,
+                ty: Type::String,
+                value: s.as_bytes().to_vec(),
+            }),
+            _ => Err(format!("{value} is not a string")),
+        },
+        Type::DynamicBytes => Ok(Expression::BytesLiteral {
+            loc: Codegen,
+            ty: Type::DynamicBytes,
+            value: parse_hex(value)?,
+        }),
+        Type::Struct(struct_ty) => {
+            let fields = &struct_ty.definition(ns).fields;
+
+            let values: Vec<&JsonValue> = match value {
+                JsonValue::Array(values) if values.len() == fields.len() => values.iter().collect(),
+                JsonValue::Object(values) if values.len() == fields.len() => fields
+                    .iter()
+                    .map(|field| {
+                        values
+                            .get(field.name_as_str())
+                            .ok_or_else(|| format!("field '{}' missing", field.name_as_str()))
+                    })
+                    .collect::<Result<_, _>>()?,
+                _ => {
+                    return Err(format!(
+                        "{value} is not a struct {} with {} fields",
+                        struct_ty.definition(ns).id,
+                        fields.len()
+                    ))
+                }
+            };
+
+            Ok(Expression::StructLiteral {
+                loc: Codegen,
+                ty: ty.clone(),
+                values: fields
+                    .iter()
+                    .zip(values)
+                    .map(|(field, value)| parse_value(&field.ty, value, ns))
+                    .collect::<Result<_, _>>()?,
+            })
+        }
+        Type::Array(..) | Type::Slice(_) => {
+            let (elem_ty, length) = array_element(ty, ns);
+
+            let JsonValue::Array(values) = value else {
+                return Err(format!("{value} is not an array"));
+            };
+
+            if length.is_some_and(|length| length != values.len()) {
+                return Err(format!(
+                    "array needs {} elements, {} given",
+                    length.unwrap(),
+                    values.len()
+                ));
+            }
+
+            Ok(Expression::ArrayLiteral {
+                loc: Codegen,
+                ty: ty.clone(),
+                dimensions: vec![values.len() as u32],
+                values: values
+                    .iter()
+                    .map(|value| parse_value(&elem_ty, value, ns))
+                    .collect::<Result<_, _>>()?,
+            })
+        }
+        _ => Err(format!(
+            "values of type {} are not supported",
+            ty.to_string(ns)
+        )),
+    }
+}
+
+/// Parse a decimal or hex integer, and check it fits
+fn parse_int(value: &JsonValue, signed: bool, width: u16) -> Result<BigInt, String> {
+    let n = match value {
+        JsonValue::Number(n) if n.is_i64() || n.is_u64() => n.to_string().parse().ok(),
+        JsonValue::String(s) => {
+            let (negative, digits) = match s.strip_prefix('-') {
+                Some(digits) => (true, digits),
+                None => (false, s.as_str()),
+            };
+
+            let n = match digits.strip_prefix("0x") {
+                Some(hex) => BigInt::parse_bytes(hex.as_bytes(), 16),
+                None => BigInt::parse_bytes(digits.as_bytes(), 10),
+            };
+
+            n.map(|n| if negative { -n } else { n })
+        }
+        _ => None,
+    }
+    .ok_or_else(|| format!("{value} is not an integer"))?;
+
+    let (min, max) = if signed {
+        let max = BigInt::one() << (width - 1);
+        (-max.clone(), max - 1)
+    } else {
+        (BigInt::zero(), (BigInt::one() << width) - 1)
+    };
+
+    if n < min || n > max {
+        return Err(format!(
+            "{n} does not fit into {}{width}",
+            if signed { "int" } else { "uint" }
+        ));
+    }
+
+    Ok(n)
+}
+
+fn parse_hex(value: &JsonValue) -> Result<Vec<u8>, String> {
+    match value {
+        JsonValue::String(s) => hex::decode(s.strip_prefix("0x").unwrap_or(s)).ok(),
+        _ => None,
+    }
+    .ok_or_else(|| format!("{value} is not hex"))
+}
+
+/// The element type of an array and its outer dimension, which is the last one
+fn array_element(ty: &Type, ns: &Namespace) -> (Type, Option<usize>) {
+    match ty.clone().unwrap_user_type(ns) {
+        Type::Array(elem_ty, mut dims) => {
+            let length = match dims.pop() {
+                Some(ArrayLength::Fixed(length)) => length.try_into().ok(),
+                _ => None,
+            };
+
+            if dims.is_empty() {
+                (*elem_ty, length)
+            } else {
+                (Type::Array(elem_ty, dims), length)
+            }
+        }
+        Type::Slice(elem_ty) => (*elem_ty, None),
+        _ => unreachable!(),
+    }
+}
+
+/// Format a decoded value as text, in the same syntax as the values are given
+fn format_value(expr: &Expression, ns: &Namespace) -> String {
+    match expr {
+        Expression::BoolLiteral { value, .. } => value.to_string(),
+        Expression::NumberLiteral { ty, value, .. } => match ty.clone().unwrap_user_type(ns) {
+            Type::Enum(enum_no) => {
+                let values = &ns.enums[enum_no].values;
+                let no: usize = value.try_into().unwrap();
+
+                format!("\"{}\"", values.get_index(no).unwrap().0)
+            }
+            Type::Bytes(length) => format!("\"0x{}\"", hex_bytes(value, length as usize)),
+            Type::Address(_) | Type::Contract(_) => {
+                let bytes = hex_bytes(value, ns.address_length);
+
+                if ns.target == Target::Solana {
+                    format!("\"{}\"", hex::decode(bytes).unwrap().to_base58())
+                } else {
+                    format!("\"0x{bytes}\"")
+                }
+            }
+            _ => value.to_string(),
+        },
+        Expression::BytesLiteral {
+            ty: Type::String,
+            value,
+            ..
+        } => JsonValue::String(String::from_utf8_lossy(value).into_owned()).to_string(),
+        Expression::BytesLiteral { value, .. } => format!("\"0x{}\"", hex::encode(value)),
+        Expression::StructLiteral { ty, values, .. } => {
+            let Type::Struct(struct_ty) = ty.clone().unwrap_user_type(ns) else {
+                unreachable!();
+            };
+
+            let fields = struct_ty
+                .definition(ns)
+                .fields
+                .iter()
+                .zip(values)
+                .map(|(field, value)| {
+                    format!("\"{}\": {}", field.name_as_str(), format_value(value, ns))
+                })
+                .collect::<Vec<_>>();
+
+            format!("{{{}}}", fields.join(", "))
+        }
+        Expression::ArrayLiteral { values, .. } => {
+            let values = values
+                .iter()
+                .map(|value| format_value(value, ns))
+                .collect::<Vec<_>>();
+
+            format!("[{}]", values.join(", "))
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// The bytes of an unsigned value as hex, zero padded to the given length
+fn hex_bytes(value: &BigInt, length: usize) -> String {
+    let bytes = value.to_bytes_be().1;
+    let mut padded = vec![0; length.saturating_sub(bytes.len())];
+    padded.extend_from_slice(&bytes[bytes.len().saturating_sub(length)..]);
+
+    hex::encode(padded)
+}
+
+/// Ethereum ABI encoding of values. Every value takes 32 bytes in the head, and dynamic
+/// values are stored in the tail with their offset in the head.
+mod ethereum {
+    use super::{array_element, BigInt, One, Sign, Signed, Zero};
+    use crate::codegen::Expression;
+    use crate::sema::ast::{ArrayLength, Namespace, RetrieveType, Type};
+    use solang_parser::pt::Loc::Codegen;
+
+    pub(super) fn encode(values: &[Expression], ns: &Namespace, data: &mut Vec<u8>) {
+        let values: Vec<(Type, &Expression)> = values.iter().map(|v| (v.ty(), v)).collect();
+
+        data.extend(encode_tuple(&values, ns));
+    }
+
+    pub(super) fn decode(tys: &[Type], data: &[u8], ns: &Namespace) -> Option<Vec<Expression>> {
+        decode_tuple(tys, data, ns)
+    }
+
+    fn is_dynamic(ty: &Type, ns: &Namespace) -> bool {
+        match ty.clone().unwrap_user_type(ns) {
+            Type::String | Type::DynamicBytes | Type::Slice(_) => true,
+            Type::Array(elem_ty, dims) => {
+                dims.iter().any(|dim| !matches!(dim, ArrayLength::Fixed(_)))
+                    || is_dynamic(&elem_ty, ns)
+            }
+            Type::Struct(struct_ty) => struct_ty
+                .definition(ns)
+                .fields
+                .iter()
+                .any(|field| is_dynamic(&field.ty, ns)),
+            _ => false,
+        }
+    }
+
+    /// The number of bytes of a static type in the head
+    fn head_size(ty: &Type, ns: &Namespace) -> usize {
+        if is_dynamic(ty, ns) {
+            return 32;
+        }
+
+        match ty.clone().unwrap_user_type(ns) {
+            Type::Array(..) => {
+                let (elem_ty, length) = array_element(ty, ns);
+                head_size(&elem_ty, ns) * length.unwrap()
+            }
+            Type::Struct(struct_ty) => struct_ty
+                .definition(ns)
+                .fields
+                .iter()
+                .map(|field| head_size(&field.ty, ns))
+                .sum(),
+            _ => 32,
+        }
+    }
+
+    fn word(value: &BigInt) -> Vec<u8> {
+        let fill = if value.is_negative() { 0xff } else { 0 };
+        let bytes = value.to_signed_bytes_be();
+        let mut word = vec![fill; 32usize.saturating_sub(bytes.len())];
+        word.extend_from_slice(&bytes[bytes.len().saturating_sub(32)..]);
+        word
+    }
+
+    fn encode_tuple(values: &[(Type, &Expression)], ns: &Namespace) -> Vec<u8> {
+        let mut head = Vec::new();
+        let mut tail = Vec::new();
+        let head_length: usize = values.iter().map(|(ty, _)| head_size(ty, ns)).sum();
+
+        for (ty, value) in values {
+            if is_dynamic(ty, ns) {
+                head.extend(word(&(head_length + tail.len()).into()));
+                tail.extend(encode_value(ty, value, ns));
+            } else {
+                head.extend(encode_value(ty, value, ns));
+            }
+        }
+
+        head.extend(tail);
+        head
+    }
+
+    fn encode_value(ty: &Type, value: &Expression, ns: &Namespace) -> Vec<u8> {
+        match value {
+            Expression::BoolLiteral { value, .. } => word(&BigInt::from(*value as u8)),
+            Expression::NumberLiteral { value, .. } => match ty.clone().unwrap_user_type(ns) {
+                // bytesN is left aligned
+                Type::Bytes(length) => {
+                    let mut bytes = value.to_bytes_be().1;
+                    bytes.splice(0..0, vec![0; (length as usize).saturating_sub(bytes.len())]);
+                    bytes.resize(32, 0);
+                    bytes
+                }
+                _ => word(value),
+            },
+            Expression::BytesLiteral { value, .. } => {
+                let mut data = word(&value.len().into());
+                data.extend_from_slice(value);
+                data.resize(data.len() + (32 - value.len() % 32) % 32, 0);
+                data
+            }
+            Expression::StructLiteral { values, .. } => {
+                let Type::Struct(struct_ty) = ty.clone().unwrap_user_type(ns) else {
+                    unreachable!();
+                };
+
+                let values: Vec<(Type, &Expression)> = struct_ty
+                    .definition(ns)
+                    .fields
+                    .iter()
+                    .map(|field| field.ty.clone())
+                    .zip(values)
+                    .collect();
+
+                encode_tuple(&values, ns)
+            }
+            Expression::ArrayLiteral { values, .. } => {
+                let (elem_ty, length) = array_element(ty, ns);
+
+                let values: Vec<(Type, &Expression)> = values
+                    .iter()
+                    .map(|value| (elem_ty.clone(), value))
+                    .collect();
+
+                let mut data = Vec::new();
+
+                if length.is_none() {
+                    data.extend(word(&values.len().into()));
+                }
+
+                data.extend(encode_tuple(&values, ns));
+                data
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn decode_tuple(tys: &[Type], data: &[u8], ns: &Namespace) -> Option<Vec<Expression>> {
+        let mut offset = 0;
+
+        tys.iter()
+            .map(|ty| {
+                let value = if is_dynamic(ty, ns) {
+                    let start = read_usize(data, offset)?;
+                    decode_value(ty, data.get(start..)?, ns)
+                } else {
+                    decode_value(ty, data.get(offset..)?, ns)
+                };
+
+                offset += head_size(ty, ns);
+
+                value
+            })
+            .collect()
+    }
+
+    fn read_word(data: &[u8], offset: usize) -> Option<&[u8]> {
+        data.get(offset..offset + 32)
+    }
+
+    fn read_usize(data: &[u8], offset: usize) -> Option<usize> {
+        BigInt::from_bytes_be(Sign::Plus, read_word(data, offset)?)
+            .try_into()
+            .ok()
+    }
+
+    fn decode_value(ty: &Type, data: &[u8], ns: &Namespace) -> Option<Expression> {
+        let number = |value| Expression::NumberLiteral {
+            loc: Codegen,
+            ty: ty.clone(),
+            value,
+        };
+
+        let value = match ty.clone().unwrap_user_type(ns) {
+            Type::Bool => {
+                let value = BigInt::from_bytes_be(Sign::Plus, read_word(data, 0)?);
+
+                if value > BigInt::one() {
+                    return None;
+                }
+
+                Expression::BoolLiteral {
+                    loc: Codegen,
+                    value: value.is_one(),
+                }
+            }
+            Type::Int(width) => {
+                let value = BigInt::from_signed_bytes_be(read_word(data, 0)?);
+                let max = BigInt::one() << (width - 1);
+
+                if value >= max || value < -max {
+                    return None;
+                }
+
+                number(value)
+            }
+            Type::Uint(width) => {
+                let value = BigInt::from_bytes_be(Sign::Plus, read_word(data, 0)?);
+
+                if value >= BigInt::one() << width {
+                    return None;
+                }
+
+                number(value)
+            }
+            Type::Enum(enum_no) => {
+                let value = BigInt::from_bytes_be(Sign::Plus, read_word(data, 0)?);
+
+                if value >= ns.enums[enum_no].values.len().into() {
+                    return None;
+                }
+
+                number(value)
+            }
+            Type::Address(_) | Type::Contract(_) => {
+                let word = read_word(data, 0)?;
+
+                if !word[..32 - ns.address_length].iter().all(Zero::is_zero) {
+                    return None;
+                }
+
+                number(BigInt::from_bytes_be(Sign::Plus, word))
+            }
+            Type::Bytes(length) => {
+                let word = read_word(data, 0)?;
+
+                number(BigInt::from_bytes_be(Sign::Plus, &word[..length as usize]))
+            }
+            Type::String | Type::DynamicBytes => {
+                let length = read_usize(data, 0)?;
+
+                Expression::BytesLiteral {
+                    loc: Codegen,
+                    ty: ty.clone(),
+                    value: data.get(32..32usize.checked_add(length)?)?.to_vec(),
+                }
+            }
+            Type::Struct(struct_ty) => {
+                let tys: Vec<Type> = struct_ty
+                    .definition(ns)
+                    .fields
+                    .iter()
+                    .map(|field| field.ty.clone())
+                    .collect();
+
+                Expression::StructLiteral {
+                    loc: Codegen,
+                    ty: ty.clone(),
+                    values: decode_tuple(&tys, data, ns)?,
+                }
+            }
+            Type::Array(..) | Type::Slice(_) => {
+                let (elem_ty, length) = array_element(ty, ns);
+
+                let (length, data) = match length {
+                    Some(length) => (length, data),
+                    None => (read_usize(data, 0)?, data.get(32..)?),
+                };
+
+                // every element takes at least one word, so the length cannot be larger
+                if length > data.len() / 32 {
+                    return None;
+                }
+
+                Expression::ArrayLiteral {
+                    loc: Codegen,
+                    ty: ty.clone(),
+                    dimensions: vec![length as u32],
+                    values: decode_tuple(&vec![elem_ty; length], data, ns)?,
+                }
+            }
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
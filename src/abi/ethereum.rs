@@ -1,7 +1,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 // ethereum style ABIs
-use crate::sema::ast::{Namespace, Parameter, StructType, Type};
+use super::anchor_spec::{pda, IdlSeed};
+use crate::codegen::events::event_selector;
+use crate::sema::ast::{Function, Namespace, Parameter, StructType, Type};
+use crate::Target;
 use serde::Serialize;
 use solang_parser::pt;
 
@@ -35,6 +38,29 @@ pub struct ABI {
     pub mutability: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub anonymous: Option<bool>,
+    /// The selector of the function or the topic of the event on Polkadot
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+    /// The discriminator of the instruction or event on Solana
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discriminator: Option<String>,
+    /// The accounts of the instruction on Solana
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accounts: Option<Vec<ABIAccount>>,
+    /// The `@payer` account of the constructor on Solana
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payer: Option<String>,
+    /// The `@seed` annotations of the constructor on Solana
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seeds: Option<Vec<IdlSeed>>,
+}
+
+#[derive(Serialize)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct ABIAccount {
+    pub name: String,
+    pub writable: bool,
+    pub signer: bool,
 }
 
 #[allow(clippy::trivially_copy_pass_by_ref)]
@@ -110,6 +136,25 @@ pub fn gen_abi(contract_no: usize, ns: &Namespace) -> Vec<ABI> {
                 None
             },
             anonymous: None,
+            selector: selector(ns.target.is_polkadot(), func, contract_no, ns),
+            discriminator: selector(ns.target == Target::Solana, func, contract_no, ns),
+            accounts: solana_accounts(func, ns),
+            payer: if ns.target == Target::Solana {
+                func.annotations
+                    .payer
+                    .as_ref()
+                    .map(|(_, name)| name.clone())
+            } else {
+                None
+            },
+            seeds: if ns.target == Target::Solana && func.is_constructor() {
+                pda(func, |arg_no| {
+                    func.params.get(arg_no).map(|p| p.name_as_str().to_owned())
+                })
+                .map(|pda| pda.seeds)
+            } else {
+                None
+            },
         })
         .chain(
             ns.contracts[contract_no]
@@ -131,8 +176,58 @@ pub fn gen_abi(contract_no: usize, ns: &Namespace) -> Vec<ABI> {
                         outputs: None,
                         ty: "event".to_owned(),
                         anonymous: Some(event.anonymous),
+                        selector: if ns.target.is_polkadot() && !event.anonymous {
+                            Some(hex_string(&event_selector(*event_no, contract_no, ns)))
+                        } else {
+                            None
+                        },
+                        discriminator: if ns.target == Target::Solana {
+                            Some(hex_string(&event_selector(*event_no, contract_no, ns)))
+                        } else {
+                            None
+                        },
+                        accounts: None,
+                        payer: None,
+                        seeds: None,
                     }
                 }),
         )
         .collect()
 }
+
+/// The selector of a function or constructor, if the extension field is generated for the target
+fn selector(enabled: bool, func: &Function, contract_no: usize, ns: &Namespace) -> Option<String> {
+    if enabled
+        && matches!(
+            func.ty,
+            pt::FunctionTy::Function | pt::FunctionTy::Constructor
+        )
+    {
+        Some(hex_string(&func.selector(ns, &contract_no)))
+    } else {
+        None
+    }
+}
+
+/// The accounts which the instruction needs on Solana
+fn solana_accounts(func: &Function, ns: &Namespace) -> Option<Vec<ABIAccount>> {
+    if ns.target != Target::Solana {
+        return None;
+    }
+
+    Some(
+        func.solana_accounts
+            .borrow()
+            .iter()
+            .map(|(name, account)| ABIAccount {
+                name: name.clone(),
+                writable: account.is_writer,
+                signer: account.is_signer,
+            })
+            .collect(),
+    )
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
@@ -4,7 +4,10 @@ use crate::sema::ast::Namespace;
 use crate::Target;
 
 pub mod anchor;
+pub mod anchor_spec;
+pub mod codec;
 pub mod ethereum;
+pub mod natspec;
 pub mod polkadot;
 mod tests;
 
@@ -15,6 +18,7 @@ pub fn generate_abi(
     verbose: bool,
     default_authors: &[String],
     version: &str,
+    idl_spec: anchor::IdlSpec,
 ) -> (String, &'static str) {
     match ns.target {
         Target::Polkadot { .. } => {
@@ -37,9 +41,16 @@ pub fn generate_abi(
                 );
             }
 
-            let idl = anchor::generate_anchor_idl(contract_no, ns, version);
+            let idl = match idl_spec {
+                anchor::IdlSpec::Legacy => serde_json::to_string_pretty(
+                    &anchor::generate_anchor_idl(contract_no, ns, version),
+                ),
+                anchor::IdlSpec::Anchor030 => serde_json::to_string_pretty(
+                    &anchor_spec::generate_anchor_idl(contract_no, ns, version),
+                ),
+            };
 
-            (serde_json::to_string_pretty(&idl).unwrap(), "json")
+            (idl.unwrap(), "json")
         }
         _ => {
             if verbose {
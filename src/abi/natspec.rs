@@ -0,0 +1,373 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The NatSpec documentation of a contract in the `userdoc` and `devdoc` format of solc. The
+//! userdoc has the `@notice` tags for users of the contract, and the devdoc has all the other
+//! tags. Tags inherited with `@inheritdoc` are included.
+
+use crate::sema::ast::{Diagnostic, Namespace, Parameter, Tag, Type};
+use crate::sema::diagnostics::Diagnostics;
+use crate::sema::tags::{function_tags, variable_tags};
+use serde::Serialize;
+use solang_parser::pt;
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct UserDoc {
+    pub kind: &'static str,
+    pub version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notice: Option<String>,
+    pub methods: BTreeMap<String, UserDocEntry>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub events: BTreeMap<String, UserDocEntry>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub errors: BTreeMap<String, Vec<UserDocEntry>>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct UserDocEntry {
+    pub notice: String,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct DevDoc {
+    pub kind: &'static str,
+    pub version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    pub methods: BTreeMap<String, DevDocEntry>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub events: BTreeMap<String, DevDocEntry>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub errors: BTreeMap<String, Vec<DevDocEntry>>,
+    #[serde(rename = "stateVariables", skip_serializing_if = "BTreeMap::is_empty")]
+    pub state_variables: BTreeMap<String, DevDocEntry>,
+    /// The `@custom:...` tags
+    #[serde(flatten)]
+    pub custom: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Debug, Default, PartialEq, Eq)]
+pub struct DevDocEntry {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub returns: BTreeMap<String, String>,
+    #[serde(flatten)]
+    pub custom: BTreeMap<String, String>,
+}
+
+/// Generate the userdoc of the contract
+pub fn userdoc(contract_no: usize, ns: &Namespace) -> UserDoc {
+    let notice = |tags: &[&Tag]| {
+        tag(tags, "notice").map(|notice| UserDocEntry {
+            notice: notice.to_owned(),
+        })
+    };
+
+    let contract = &ns.contracts[contract_no];
+
+    let mut doc = UserDoc {
+        kind: "user",
+        version: 1,
+        notice: tag(&contract.tags.iter().collect::<Vec<_>>(), "notice").map(str::to_owned),
+        methods: BTreeMap::new(),
+        events: BTreeMap::new(),
+        errors: BTreeMap::new(),
+    };
+
+    for (key, function_no) in methods(contract_no, ns) {
+        if let Some(entry) = notice(&method_tags(function_no, ns)) {
+            doc.methods.insert(key, entry);
+        }
+    }
+
+    for event_no in events(contract_no, ns) {
+        let event = &ns.events[event_no];
+
+        if let Some(entry) = notice(&event.tags.iter().collect::<Vec<_>>()) {
+            doc.events.insert(event.signature.clone(), entry);
+        }
+    }
+
+    for error_no in errors(contract_no, ns) {
+        let error = &ns.errors[error_no];
+
+        if let Some(entry) = notice(&error.tags.iter().collect::<Vec<_>>()) {
+            doc.errors
+                .insert(ns.signature(&error.name, &error.fields), vec![entry]);
+        }
+    }
+
+    doc
+}
+
+/// Generate the devdoc of the contract
+pub fn devdoc(contract_no: usize, ns: &Namespace) -> DevDoc {
+    let contract = &ns.contracts[contract_no];
+    let tags: Vec<&Tag> = contract.tags.iter().collect();
+
+    let mut doc = DevDoc {
+        kind: "dev",
+        version: 1,
+        title: tag(&tags, "title").map(str::to_owned),
+        author: tag(&tags, "author").map(str::to_owned),
+        details: tag(&tags, "dev").map(str::to_owned),
+        methods: BTreeMap::new(),
+        events: BTreeMap::new(),
+        errors: BTreeMap::new(),
+        state_variables: BTreeMap::new(),
+        custom: custom(&tags),
+    };
+
+    for (key, function_no) in methods(contract_no, ns) {
+        let func = &ns.functions[function_no];
+
+        // the accessor function of a state variable is documented as a state variable
+        if func.is_accessor {
+            continue;
+        }
+
+        let entry = dev_entry(&method_tags(function_no, ns), &func.params, &func.returns);
+
+        if entry != DevDocEntry::default() {
+            doc.methods.insert(key, entry);
+        }
+    }
+
+    for event_no in events(contract_no, ns) {
+        let event = &ns.events[event_no];
+        let entry = dev_entry(&event.tags.iter().collect::<Vec<_>>(), &event.fields, &[]);
+
+        if entry != DevDocEntry::default() {
+            doc.events.insert(event.signature.clone(), entry);
+        }
+    }
+
+    for error_no in errors(contract_no, ns) {
+        let error = &ns.errors[error_no];
+        let entry = dev_entry(&error.tags.iter().collect::<Vec<_>>(), &error.fields, &[]);
+
+        if entry != DevDocEntry::default() {
+            doc.errors
+                .insert(ns.signature(&error.name, &error.fields), vec![entry]);
+        }
+    }
+
+    for (var_no, var) in contract.variables.iter().enumerate() {
+        let entry = dev_entry(&variable_tags(contract_no, var_no, ns), &[], &[]);
+
+        if entry != DevDocEntry::default() {
+            doc.state_variables.insert(var.name.clone(), entry);
+        }
+    }
+
+    doc
+}
+
+/// Check that the functions which can be called externally are documented, with a notice or
+/// dev tag and each of their named parameters and return values.
+pub fn check_documented(ns: &mut Namespace) {
+    let mut diagnostics = Diagnostics::default();
+
+    for contract_no in 0..ns.contracts.len() {
+        if ns.contracts[contract_no].loc.try_file_no() != Some(ns.top_file_no()) {
+            continue;
+        }
+
+        for (_, function_no) in methods(contract_no, ns) {
+            let func = &ns.functions[function_no];
+
+            // inherited functions are checked with the contract which declares them
+            if func.contract_no != Some(contract_no) {
+                continue;
+            }
+
+            let tags = method_tags(function_no, ns);
+            let name = if func.is_accessor {
+                format!("state variable '{}'", func.id)
+            } else if func.is_constructor() {
+                "constructor".to_owned()
+            } else {
+                format!("function '{}'", func.id)
+            };
+
+            if tag(&tags, "notice").is_none() && tag(&tags, "dev").is_none() {
+                diagnostics.push(Diagnostic::warning(
+                    func.loc_prototype,
+                    format!("{name} is not documented"),
+                ));
+                continue;
+            }
+
+            if func.is_accessor {
+                continue;
+            }
+
+            for (no, param) in func.params.iter().enumerate() {
+                if param.id.is_some() && !tags.iter().any(|e| e.tag == "param" && e.no == no) {
+                    diagnostics.push(Diagnostic::warning(
+                        param.loc,
+                        format!("{name} has no tag '@param' for '{}'", param.name_as_str()),
+                    ));
+                }
+            }
+
+            for (no, param) in func.returns.iter().enumerate() {
+                if !tags.iter().any(|e| e.tag == "return" && e.no == no) {
+                    diagnostics.push(Diagnostic::warning(
+                        param.loc,
+                        format!("{name} has no tag '@return' for return value {}", no + 1),
+                    ));
+                }
+            }
+        }
+    }
+
+    ns.diagnostics.extend(diagnostics);
+}
+
+/// The functions which can be called externally, by their key in the documentation
+fn methods(contract_no: usize, ns: &Namespace) -> Vec<(String, usize)> {
+    ns.contracts[contract_no]
+        .all_functions
+        .keys()
+        .filter(|function_no| ns.function_externally_callable(contract_no, Some(**function_no)))
+        .filter_map(|function_no| {
+            let func = &ns.functions[*function_no];
+
+            match func.ty {
+                pt::FunctionTy::Constructor => Some(("constructor".to_owned(), *function_no)),
+                pt::FunctionTy::Function => Some((func.signature.clone(), *function_no)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// The tags of a function, or of the state variable for an accessor function
+fn method_tags(function_no: usize, ns: &Namespace) -> Vec<&Tag> {
+    let func = &ns.functions[function_no];
+
+    if func.is_accessor {
+        if let Some(contract_no) = func.contract_no {
+            if let Some(var_no) = ns.contracts[contract_no]
+                .variables
+                .iter()
+                .position(|var| var.name == func.id.name)
+            {
+                return variable_tags(contract_no, var_no, ns);
+            }
+        }
+    }
+
+    function_tags(function_no, ns)
+}
+
+/// The events which are declared in the contract or its bases, or are emitted by it
+fn events(contract_no: usize, ns: &Namespace) -> Vec<usize> {
+    let bases = ns.contract_bases(contract_no);
+
+    let mut events: Vec<usize> = (0..ns.events.len())
+        .filter(|event_no| {
+            ns.events[*event_no]
+                .contract
+                .is_some_and(|event_contract_no| bases.contains(&event_contract_no))
+        })
+        .collect();
+
+    for event_no in &ns.contracts[contract_no].emits_events {
+        if !events.contains(event_no) {
+            events.push(*event_no);
+        }
+    }
+
+    events
+}
+
+/// The errors which are declared in the contract or its bases
+fn errors(contract_no: usize, ns: &Namespace) -> Vec<usize> {
+    let bases = ns.contract_bases(contract_no);
+
+    (0..ns.errors.len())
+        .filter(|error_no| {
+            ns.errors[*error_no]
+                .contract
+                .is_some_and(|error_contract_no| bases.contains(&error_contract_no))
+        })
+        .collect()
+}
+
+fn dev_entry(
+    tags: &[&Tag],
+    params: &[Parameter<Type>],
+    returns: &[Parameter<Type>],
+) -> DevDocEntry {
+    let mut entry = DevDocEntry {
+        details: tag(tags, "dev").map(str::to_owned),
+        custom: custom(tags),
+        ..Default::default()
+    };
+
+    for tag in tags {
+        match tag.tag.as_str() {
+            "param" => {
+                if let Some(param) = params.get(tag.no) {
+                    entry
+                        .params
+                        .insert(param.name_as_str().to_owned(), tag.value.clone());
+                }
+            }
+            "return" => {
+                if let Some(param) = returns.get(tag.no) {
+                    let (name, value) = match &param.id {
+                        // a single return value may be documented with its name
+                        Some(id) => (
+                            id.name.clone(),
+                            tag.value
+                                .strip_prefix(id.name.as_str())
+                                .filter(|rest| rest.starts_with(char::is_whitespace))
+                                .map_or(tag.value.as_str(), str::trim_start)
+                                .to_owned(),
+                        ),
+                        None => (format!("_{}", tag.no), tag.value.clone()),
+                    };
+
+                    entry.returns.insert(name, value);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    entry
+}
+
+fn tag<'a>(tags: &[&'a Tag], name: &str) -> Option<&'a str> {
+    tags.iter()
+        .find(|e| e.tag == name)
+        .map(|e| e.value.as_str())
+}
+
+/// The custom tags; a tag given more than once is joined with newlines
+fn custom(tags: &[&Tag]) -> BTreeMap<String, String> {
+    let mut custom: BTreeMap<String, String> = BTreeMap::new();
+
+    for tag in tags.iter().filter(|e| e.tag.starts_with("custom:")) {
+        custom
+            .entry(tag.tag.clone())
+            .and_modify(|value| {
+                value.push('\n');
+                value.push_str(&tag.value);
+            })
+            .or_insert_with(|| tag.value.clone());
+    }
+
+    custom
+}
@@ -9,7 +9,7 @@ use ink_metadata::{
     MessageParamSpec, MessageSpec, ReturnTypeSpec, TypeSpec,
 };
 
-use serde_json::Value;
+use serde_json::{json, Value};
 
 use num_bigint::BigInt;
 use num_traits::ToPrimitive;
@@ -21,13 +21,36 @@ use semver::Version;
 use solang_parser::pt;
 
 use crate::{
-    codegen::revert::{SolidityError, ERROR_SELECTOR, PANIC_SELECTOR},
+    codegen::{
+        packed_size,
+        revert::{SolidityError, ERROR_SELECTOR, PANIC_SELECTOR},
+    },
     sema::{
         ast::{self, ArrayLength, EventDecl, Function},
-        tags::render,
+        tags::{function_tags, render},
     },
 };
 
+/// The version of the ink! metadata. With ink! v5, events are identified by their signature
+/// topic rather than by their index, and the topics of indexed fields are not prefixed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MetadataVersion {
+    #[default]
+    V4,
+    V5,
+}
+
+/// The static buffer size of ink! v5, which is the default of `cargo-contract`
+const STATIC_BUFFER_SIZE: u32 = 16384;
+
+/// The signature topic of an event with ink! v5 metadata, which is the hash of its signature
+pub fn signature_topic(event: &EventDecl) -> [u8; 32] {
+    blake2_rfc::blake2b::blake2b(32, &[], event.signature.as_bytes())
+        .as_bytes()
+        .try_into()
+        .unwrap()
+}
+
 macro_rules! path {
     ($( $segments:expr ),*) => {
         Path::from_segments_unchecked([$($segments),*].iter().map(ToString::to_string))
@@ -291,21 +314,80 @@ pub fn gen_project(contract_no: usize, ns: &ast::Namespace) -> InkProject {
     // This is only used by off-chain tooling. At the moment there is no such tooling available yet.
     // So it is not exactly clear yet what this should look like.
     // For now it just contains all root layouts (you get all storage keys in use).
-    let fields: Vec<FieldLayout<PortableForm>> = ns.contracts[contract_no]
-        .layout
+    // Variables which are packed into the same storage slot are listed as a struct in a single root
+    // layout, like ink! does for the fields of a struct which are stored together.
+    let mut slots: Vec<Vec<&ast::Layout>> = Vec::new();
+
+    for layout in &ns.contracts[contract_no].layout {
+        match slots.last_mut() {
+            Some(last) if layout.packed.is_some() && last[0].slot == layout.slot => {
+                last.push(layout)
+            }
+            _ => slots.push(vec![layout]),
+        }
+    }
+
+    let fields: Vec<FieldLayout<PortableForm>> = slots
         .iter()
-        .filter_map(|layout| {
-            let var = &ns.contracts[layout.contract_no].variables[layout.var_no];
-            if let Some(slot) = layout.slot.to_u32() {
-                let ty = resolve_ast(&layout.ty, ns, &mut registry);
-                let layout_key = LayoutKey::new(slot);
+        .filter_map(|layouts| {
+            let slot = layouts[0].slot.to_u32()?;
+            let layout_key = LayoutKey::new(slot);
+
+            let mut fields: Vec<(String, Layout<PortableForm>)> = layouts
+                .iter()
+                .map(|layout| {
+                    let var = &ns.contracts[layout.contract_no].variables[layout.var_no];
+                    let ty = resolve_ast(&layout.ty, ns, &mut registry);
+
+                    (
+                        var.name.clone(),
+                        type_to_storage_layout(ty, layout_key, &registry),
+                    )
+                })
+                .collect();
+
+            if fields.len() == 1 {
+                let (name, layout) = fields.remove(0);
+
+                Some(FieldLayout::new(name, RootLayout::new(layout_key, layout)))
+            } else {
+                let name = fields
+                    .iter()
+                    .map(|(name, _)| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join("_");
+                // the storage slot holds an integer which can be larger than the packed
+                // variables, so describe the remaining bytes as padding
+                let last = layouts.last().unwrap();
+                let packed = last.packed.as_ref().unwrap();
+                let used = packed.offset + packed_size(&last.ty).unwrap();
+                let padding = packed.slot_ty.bits(ns) as u32 / 8 - used;
+
+                if padding > 0 {
+                    let ty = resolve_ast(
+                        &ast::Type::Array(
+                            Box::new(ast::Type::Uint(8)),
+                            vec![ArrayLength::Fixed(BigInt::from(padding))],
+                        ),
+                        ns,
+                        &mut registry,
+                    );
+
+                    fields.push((
+                        "_padding".to_string(),
+                        type_to_storage_layout(ty, layout_key, &registry),
+                    ));
+                }
+
+                let fields = fields
+                    .into_iter()
+                    .map(|(name, layout)| FieldLayout::new(name, layout));
                 let root = RootLayout::new(
                     layout_key,
-                    type_to_storage_layout(ty, layout_key, &registry),
+                    Layout::Struct(StructLayout::new("Packed".to_string(), fields)),
                 );
-                Some(FieldLayout::new(var.name.clone(), root))
-            } else {
-                None
+
+                Some(FieldLayout::new(name, root))
             }
         })
         .collect();
@@ -365,7 +447,8 @@ pub fn gen_project(contract_no: usize, ns: &ast::Namespace) -> InkProject {
         .map(constructor_spec)
         .collect::<Vec<ConstructorSpec<PortableForm>>>();
 
-    let message_spec = |f: &Function| -> MessageSpec<PortableForm> {
+    let mut message_spec = |function_no: usize| -> MessageSpec<PortableForm> {
+        let f = &ns.functions[function_no];
         let payable = matches!(f.mutability, ast::Mutability::Payable(_));
         let mutates = matches!(
             f.mutability,
@@ -432,7 +515,7 @@ pub fn gen_project(contract_no: usize, ns: &ast::Namespace) -> InkProject {
             .payable(payable)
             .args(args)
             .returns(ret_type)
-            .docs(vec![render(&f.tags)])
+            .docs(vec![render(&function_tags(function_no, ns))])
             .done()
     };
 
@@ -440,23 +523,29 @@ pub fn gen_project(contract_no: usize, ns: &ast::Namespace) -> InkProject {
         .all_functions
         .keys()
         .filter(|i| ns.function_externally_callable(contract_no, Some(**i)))
-        .map(|i| &ns.functions[*i])
-        .filter(|f| !f.is_constructor())
-        .map(message_spec)
+        .filter(|i| !ns.functions[**i].is_constructor())
+        .map(|i| message_spec(*i))
         .collect::<Vec<MessageSpec<PortableForm>>>();
 
     let mut event_spec = |e: &EventDecl| -> EventSpec<PortableForm> {
         let args = e
             .fields
             .iter()
-            .map(|p| {
+            .enumerate()
+            .map(|(no, p)| {
                 let ty = resolve_ast(&p.ty, ns, &mut registry);
                 let path = registry.get(ty).unwrap().path.clone();
                 let spec = TypeSpec::new(ty.into(), path);
+                let docs = e
+                    .tags
+                    .iter()
+                    .filter(|tag| tag.tag == "param" && tag.no == no)
+                    .map(|tag| tag.value.clone())
+                    .collect::<Vec<_>>();
                 EventParamSpec::new(p.name_as_str().into())
                     .of_type(spec)
                     .indexed(p.indexed)
-                    .docs(vec![])
+                    .docs(docs)
                     .done()
             })
             .collect::<Vec<_>>();
@@ -550,6 +639,34 @@ fn tags(contract_no: usize, tagname: &str, ns: &ast::Namespace) -> Vec<String> {
         .collect()
 }
 
+/// ink! v5 metadata has the same layout as v4, with the signature topic and module path of each
+/// event, and the static buffer size of the environment
+fn upgrade_to_v5(project: &mut Value, contract_no: usize, ns: &ast::Namespace) {
+    project["version"] = json!(5);
+
+    let spec = &mut project["spec"];
+
+    spec["environment"]["staticBufferSize"] = json!(STATIC_BUFFER_SIZE);
+
+    let events = spec["events"].as_array_mut().unwrap();
+
+    for (spec, event_no) in events
+        .iter_mut()
+        .zip(&ns.contracts[contract_no].emits_events)
+    {
+        let event = &ns.events[*event_no];
+        // For freestanding events the name of the emitting contract is used
+        let module_path = &ns.contracts[event.contract.unwrap_or(contract_no)].id.name;
+
+        spec["module_path"] = json!(module_path);
+        spec["signature_topic"] = if event.anonymous {
+            Value::Null
+        } else {
+            json!(format!("0x{}", hex::encode(signature_topic(event))))
+        };
+    }
+}
+
 /// Generate the metadata for Substrate 4.0
 pub fn metadata(
     contract_no: usize,
@@ -588,7 +705,12 @@ pub fn metadata(
     builder.version(Version::parse(contract_version).unwrap());
     let contract = builder.build().unwrap();
 
-    let project_json = serde_json::to_value(gen_project(contract_no, ns)).unwrap();
+    let mut project_json = serde_json::to_value(gen_project(contract_no, ns)).unwrap();
+
+    if ns.is_ink_v5() {
+        upgrade_to_v5(&mut project_json, contract_no, ns);
+    }
+
     let abi = serde_json::from_value(project_json).unwrap();
 
     serde_json::to_value(ContractMetadata::new(source, contract, None, abi)).unwrap()
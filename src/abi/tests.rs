@@ -2,10 +2,12 @@
 
 #![cfg(test)]
 
-use crate::abi::anchor::generate_anchor_idl;
+use crate::abi::anchor::{event_discriminator, function_discriminator, generate_anchor_idl};
+use crate::abi::codec::Message;
 use crate::codegen::{codegen, Options};
 use crate::file_resolver::FileResolver;
 use crate::sema::ast::Namespace;
+use crate::sema::tests::resolve_and_codegen;
 use crate::{codegen, parse_and_resolve, Target};
 use anchor_syn::idl::types::{
     IdlAccount, IdlAccountItem, IdlEnumVariant, IdlEvent, IdlEventField, IdlField, IdlType,
@@ -2128,3 +2130,730 @@ fn idl_account(name: &str, is_mut: bool, is_signer: bool) -> IdlAccountItem {
         relations: vec![],
     })
 }
+
+#[test]
+fn polkadot_storage_layout_base() {
+    let src = r#"
+abstract contract Base {
+    uint64 a;
+}
+
+contract Impl is Base layout at 0x1000 + 2 {
+    bool b;
+    int32 c;
+
+    function get() public view returns (uint64) {
+        return a;
+    }
+}"#;
+
+    let ns = resolve_and_codegen(src, Target::default_polkadot(), &Options::default());
+    assert!(!ns.diagnostics.any_errors());
+
+    let project = crate::abi::polkadot::gen_project(1, &ns);
+
+    let ink_metadata::layout::Layout::Struct(storage) = project.layout() else {
+        panic!("storage layout should be a struct");
+    };
+
+    let keys: Vec<(&str, u32)> = storage
+        .fields()
+        .iter()
+        .map(|field| match field.layout() {
+            ink_metadata::layout::Layout::Root(root) => {
+                (field.name().as_str(), *root.root_key().key())
+            }
+            _ => panic!("storage field should be a root layout"),
+        })
+        .collect();
+
+    assert_eq!(keys, vec![("a", 0x1002), ("b", 0x1003), ("c", 0x1004)]);
+
+    // the last storage key of the metadata is used
+    let src = src.replace("0x1000 + 2", "2**32 - 3");
+    let ns = resolve_and_codegen(&src, Target::default_polkadot(), &Options::default());
+    assert!(!ns.diagnostics.any_errors());
+
+    let project = crate::abi::polkadot::gen_project(1, &ns);
+
+    let ink_metadata::layout::Layout::Struct(storage) = project.layout() else {
+        panic!("storage layout should be a struct");
+    };
+
+    let keys: Vec<u32> = storage
+        .fields()
+        .iter()
+        .map(|field| match field.layout() {
+            ink_metadata::layout::Layout::Root(root) => *root.root_key().key(),
+            _ => panic!("storage field should be a root layout"),
+        })
+        .collect();
+
+    assert_eq!(keys, vec![u32::MAX - 2, u32::MAX - 1, u32::MAX]);
+}
+
+#[test]
+fn polkadot_storage_packing() {
+    let src = r#"
+contract Packed {
+    bool a;
+    uint8 b;
+    uint256 c;
+    int32 d;
+    int64 e;
+    uint128 f;
+
+    function get() public view returns (bool) {
+        return a;
+    }
+}"#;
+
+    let ns = resolve_and_codegen(
+        src,
+        Target::default_polkadot(),
+        &Options {
+            pack_storage: true,
+            ..Default::default()
+        },
+    );
+    assert!(!ns.diagnostics.any_errors());
+
+    let project = crate::abi::polkadot::gen_project(0, &ns);
+
+    let ink_metadata::layout::Layout::Struct(storage) = project.layout() else {
+        panic!("storage layout should be a struct");
+    };
+
+    let keys: Vec<(&str, u32, Vec<&str>)> = storage
+        .fields()
+        .iter()
+        .map(|field| match field.layout() {
+            ink_metadata::layout::Layout::Root(root) => {
+                let members = match root.layout() {
+                    ink_metadata::layout::Layout::Struct(packed) => packed
+                        .fields()
+                        .iter()
+                        .map(|field| {
+                            let ink_metadata::layout::Layout::Leaf(leaf) = field.layout() else {
+                                panic!("packed variable should be a leaf layout");
+                            };
+                            assert_eq!(leaf.key(), root.root_key());
+                            field.name().as_str()
+                        })
+                        .collect(),
+                    _ => vec![],
+                };
+
+                (field.name().as_str(), *root.root_key().key(), members)
+            }
+            _ => panic!("storage field should be a root layout"),
+        })
+        .collect();
+
+    assert_eq!(
+        keys,
+        vec![
+            ("a_b", 0, vec!["a", "b"]),
+            ("c", 1, vec![]),
+            ("d_e_f", 2, vec!["d", "e", "f", "_padding"]),
+        ]
+    );
+
+    // d, e and f use 28 bytes of the uint256 storage slot
+    let ink_metadata::layout::Layout::Root(root) = storage.fields()[2].layout() else {
+        panic!("storage field should be a root layout");
+    };
+    let ink_metadata::layout::Layout::Struct(packed) = root.layout() else {
+        panic!("packed variables should be a struct layout");
+    };
+    assert_eq!(packed.name(), "Packed");
+    let ink_metadata::layout::Layout::Leaf(padding) = packed.fields()[3].layout() else {
+        panic!("padding should be a leaf layout");
+    };
+    let padding = project.registry().resolve(padding.ty().id).unwrap();
+    let scale_info::TypeDef::Array(padding) = &padding.type_def else {
+        panic!("padding should be an array");
+    };
+    assert_eq!(padding.len, 4);
+
+    let layout = &ns.contracts[0].layout;
+    let packed = layout[5].packed.as_ref().unwrap();
+
+    assert_eq!(packed.offset, 12);
+    assert_eq!(packed.slot_ty, crate::sema::ast::Type::Uint(256));
+}
+
+#[test]
+fn anchor_030_spec() {
+    let src = r#"
+@program_id("Foo5mMfYo5RhRcWa4NZ2bwFn4Kdhe8rNK5jchxsKrivA")
+contract counter_pda {
+    struct Point { uint8 x; }
+    event Moved(uint64 steps, Point to);
+
+    @payer(payer)
+    @seed("counter")
+    constructor(@seed bytes name, @bump bytes1 b) {}
+
+    function moveIt(uint64 stepCount) public {
+        emit Moved(stepCount, Point(1));
+    }
+
+    @selector([1, 2, 3, 4, 5, 6, 7, 8])
+    function custom() public pure {}
+}"#;
+
+    let mut ns = generate_namespace(src);
+    codegen(&mut ns, &Options::default());
+    let idl = crate::abi::anchor_spec::generate_anchor_idl(0, &ns, "0.1.0");
+    let idl = serde_json::to_value(idl).unwrap();
+
+    assert_eq!(
+        idl["address"],
+        "Foo5mMfYo5RhRcWa4NZ2bwFn4Kdhe8rNK5jchxsKrivA"
+    );
+    assert_eq!(
+        idl["metadata"],
+        json!({"name": "counter_pda", "version": "0.1.0", "spec": "0.1.0"})
+    );
+
+    let new = &idl["instructions"][0];
+
+    assert_eq!(new["name"], "new");
+    assert_eq!(new["discriminator"], json!(function_discriminator("new")));
+    assert_eq!(
+        new["accounts"],
+        json!([
+            {
+                "name": "data_account",
+                "writable": true,
+                "pda": {
+                    "seeds": [
+                        {"kind": "const", "value": b"counter"},
+                        {"kind": "arg", "path": "name"}
+                    ]
+                }
+            },
+            {"name": "payer", "writable": true, "signer": true},
+            {"name": "system_program", "address": "11111111111111111111111111111111"}
+        ])
+    );
+
+    let move_it = &idl["instructions"][1];
+
+    assert_eq!(move_it["name"], "move_it");
+    assert_eq!(
+        move_it["discriminator"],
+        json!(function_discriminator("moveIt"))
+    );
+    assert_eq!(
+        move_it["args"],
+        json!([{"name": "step_count", "type": "u64"}])
+    );
+
+    assert_eq!(idl["instructions"][2]["name"], "custom");
+    assert_eq!(
+        idl["instructions"][2]["discriminator"],
+        json!([1, 2, 3, 4, 5, 6, 7, 8])
+    );
+
+    // the fields of the event are in a type with the same name
+    assert_eq!(
+        idl["events"],
+        json!([{"name": "Moved", "discriminator": event_discriminator("Moved")}])
+    );
+    assert_eq!(
+        idl["types"],
+        json!([
+            {
+                "name": "Point",
+                "type": {"kind": "struct", "fields": [{"name": "x", "type": "u8"}]}
+            },
+            {
+                "name": "Moved",
+                "type": {
+                    "kind": "struct",
+                    "fields": [
+                        {"name": "steps", "type": "u64"},
+                        {"name": "to", "type": {"defined": {"name": "Point"}}}
+                    ]
+                }
+            }
+        ])
+    );
+}
+
+fn encode(ns: &Namespace, name: &str, args: &[&str]) -> Result<String, String> {
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    Message::find(ns, 0, name, false)?
+        .encode(&args, ns)
+        .map(hex::encode)
+}
+
+fn decode(ns: &Namespace, name: &str, returns: bool, data: &str) -> Result<Vec<String>, String> {
+    Message::find(ns, 0, name, returns)?
+        .decode(&hex::decode(data).unwrap(), ns)
+        .map(|values| {
+            values
+                .into_iter()
+                .map(|(name, value)| format!("{name}: {value}"))
+                .collect()
+        })
+}
+
+#[test]
+fn codec_polkadot() {
+    let src = r#"
+    contract Shapes {
+        enum Kind { Circle, Square }
+        struct Shape { Kind kind; int32[] points; }
+        event Drawn(address indexed by, Shape shape);
+        error TooBig(uint16 size);
+
+        function draw(Shape s, bytes4 tag, string name) public returns (bool, uint128) {
+            emit Drawn(address(this), s);
+            if (s.points.length > 100) {
+                revert TooBig(uint16(s.points.length));
+            }
+            return (tag != 0, bytes(name).length);
+        }
+    }"#;
+
+    let ns = resolve_and_codegen(src, Target::default_polkadot(), &Options::default());
+
+    let call = encode(
+        &ns,
+        "draw",
+        &[
+            r#"{"kind": "Square", "points": [-1, 2]}"#,
+            "0x01020304",
+            "hi",
+        ],
+    )
+    .unwrap();
+    assert_eq!(call[8..], *"0108ffffffff0200000001020304086869");
+    assert_eq!(
+        decode(&ns, "draw", false, &call).unwrap(),
+        [
+            r#"s: {"kind": "Square", "points": [-1, 2]}"#,
+            r#"tag: "0x01020304""#,
+            r#"name: "hi""#
+        ]
+    );
+
+    assert_eq!(
+        decode(&ns, "draw", true, "0102000000000000000000000000000000").unwrap(),
+        ["0: true", "1: 2"]
+    );
+
+    let event = encode(
+        &ns,
+        "Drawn",
+        &[&format!("0x{}", "ab".repeat(32)), "[0, []]"],
+    )
+    .unwrap();
+    assert_eq!(event, format!("00{}0000", "ab".repeat(32)));
+
+    let error = encode(&ns, "TooBig", &["300"]).unwrap();
+    assert_eq!(error[8..], *"2c01");
+    assert_eq!(decode(&ns, "TooBig", false, &error).unwrap(), ["size: 300"]);
+    assert_eq!(
+        decode(&ns, "Error", false, "08c379a00c666f6f").unwrap(),
+        [r#"message: "foo""#]
+    );
+
+    assert_eq!(
+        encode(&ns, "draw", &["[0, []]", "0x01", "x"]),
+        Err("tag: bytes4 needs 4 bytes of hex".into())
+    );
+    assert_eq!(
+        encode(&ns, "TooBig", &["65536"]),
+        Err("size: 65536 does not fit into uint16".into())
+    );
+    assert_eq!(
+        decode(&ns, "TooBig", false, "00000000"),
+        Err("data does not start with dae8c82f of 'TooBig'".into())
+    );
+}
+
+#[test]
+fn codec_solana() {
+    let src = r#"
+    contract Counter {
+        event Added(uint64 value, address by);
+
+        function add(uint64 value, string[] notes) public {
+            emit Added(value, address(this));
+        }
+    }"#;
+
+    let ns = resolve_and_codegen(src, Target::Solana, &Options::default());
+
+    let call = encode(&ns, "add", &["0x10", r#"["a"]"#]).unwrap();
+    assert_eq!(
+        call,
+        format!(
+            "{}1000000000000000010000000100000061",
+            hex::encode(function_discriminator("add"))
+        )
+    );
+
+    let by = "11111111111111111111111111111112";
+    let event = encode(&ns, "Added", &["5", by]).unwrap();
+    assert_eq!(
+        decode(&ns, "Added", false, &event).unwrap(),
+        ["value: 5", &format!("by: \"{by}\"")]
+    );
+    assert!(event.starts_with(&hex::encode(event_discriminator("Added"))));
+
+    assert_eq!(
+        encode(&ns, "new", &[]).unwrap(),
+        hex::encode(function_discriminator("new"))
+    );
+    assert_eq!(
+        encode(&ns, "Error", &["x"]),
+        Err("Solana programs do not return data when they fail".into())
+    );
+}
+
+#[test]
+fn codec_ethereum() {
+    let src = r#"
+    contract Token {
+        struct Transfer { address to; uint256 amount; }
+
+        function transfer(address to, uint256 amount) public returns (bool) {
+            return true;
+        }
+
+        function batch(Transfer[] transfers, bytes memo) public {}
+    }"#;
+
+    let ns = resolve_and_codegen(src, Target::EVM, &Options::default());
+
+    let to = "0x00000000000000000000000000000000000000aa";
+    assert_eq!(
+        encode(&ns, "transfer", &[to, "-1"]),
+        Err("amount: -1 does not fit into uint256".into())
+    );
+    assert_eq!(
+        encode(&ns, "transfer", &[to, "1000"]).unwrap(),
+        format!("a9059cbb{:0>64}{:0>64}", "aa", "3e8")
+    );
+
+    let batch = encode(&ns, "batch", &[&format!(r#"[["{to}", "1"]]"#), "0x1234"]).unwrap();
+    assert_eq!(
+        batch[8..],
+        format!(
+            "{:0>64}{:0>64}{:0>64}{:0>64}{:0>64}{:0>64}{:0<64}",
+            "40", "a0", "1", "aa", "1", "2", "1234"
+        )
+    );
+    assert_eq!(
+        decode(&ns, "batch", false, &batch).unwrap(),
+        [
+            format!(r#"transfers: [{{"to": "{to}", "amount": 1}}]"#),
+            r#"memo: "0x1234""#.to_string()
+        ]
+    );
+}
+
+#[test]
+fn polkadot_metadata_v5() {
+    let src = r#"
+event Frozen(address indexed account);
+
+contract Token {
+    event Transfer(address indexed from, address indexed to, uint128 value);
+    event Log(string message) anonymous;
+
+    function transfer(address to, uint128 value) public {
+        emit Transfer(msg.sender, to, value);
+        emit Log("transfer");
+        emit Frozen(to);
+    }
+}"#;
+
+    let ns = resolve_and_codegen(
+        src,
+        Target::default_polkadot(),
+        &Options {
+            metadata_version: crate::abi::polkadot::MetadataVersion::V5,
+            ..Default::default()
+        },
+    );
+    assert!(!ns.diagnostics.any_errors());
+
+    let metadata = crate::abi::polkadot::metadata(0, &[], &ns, &["x".into()], "0.1.0");
+
+    assert_eq!(metadata["version"], json!(5));
+    assert_eq!(
+        metadata["spec"]["environment"]["staticBufferSize"],
+        json!(16384)
+    );
+
+    let events: Vec<(&str, &str, Option<String>)> = metadata["spec"]["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|event| {
+            (
+                event["label"].as_str().unwrap(),
+                event["module_path"].as_str().unwrap(),
+                event["signature_topic"].as_str().map(str::to_owned),
+            )
+        })
+        .collect();
+
+    let topic = |signature: &str| {
+        Some(format!(
+            "0x{}",
+            hex::encode(blake2_rfc::blake2b::blake2b(32, &[], signature.as_bytes()))
+        ))
+    };
+
+    assert_eq!(
+        events,
+        vec![
+            (
+                "Transfer",
+                "Token",
+                topic("Transfer(address,address,uint128)")
+            ),
+            ("Log", "Token", None),
+            ("Frozen", "Token", topic("Frozen(address)")),
+        ]
+    );
+
+    let ns = resolve_and_codegen(src, Target::default_polkadot(), &Options::default());
+    let metadata = crate::abi::polkadot::metadata(0, &[], &ns, &["x".into()], "0.1.0");
+
+    assert_eq!(metadata["version"], json!("4"));
+    assert!(metadata["spec"]["events"][0]
+        .get("signature_topic")
+        .is_none());
+}
+
+#[test]
+fn natspec() {
+    let src = r#"
+/// @title Token
+/// @author Alice
+/// @notice A simple token
+/// @custom:security-contact security@example.com
+abstract contract Base {
+    /// @notice Transfer tokens
+    /// @dev Emits Transfer
+    /// @param to The receiver
+    /// @param value The amount
+    /// @return ok Whether the transfer succeeded
+    function transfer(address to, uint128 value) public virtual returns (bool ok);
+}
+
+contract Token is Base {
+    /// @notice Transfer happened
+    /// @param from The sender
+    event Transfer(address indexed from, address indexed to, uint128 value);
+
+    /// @notice Not enough balance
+    /// @param available The balance
+    error Insufficient(uint128 available);
+
+    /// @dev The balance of the contract
+    uint128 public total;
+
+    /// @inheritdoc Base
+    function transfer(address to, uint128 value) public override returns (bool ok) {
+        emit Transfer(address(this), to, value);
+        ok = true;
+    }
+
+    function undocumented(int32 a) public pure returns (int32) {
+        return a;
+    }
+}"#;
+
+    let mut ns = resolve_and_codegen(src, Target::default_polkadot(), &Options::default());
+    let contract_no = ns
+        .contracts
+        .iter()
+        .position(|contract| contract.id.name == "Token")
+        .unwrap();
+
+    let userdoc = crate::abi::natspec::userdoc(contract_no, &ns);
+    assert_eq!(
+        serde_json::to_value(&userdoc).unwrap(),
+        json!({
+            "kind": "user",
+            "version": 1,
+            "methods": {
+                "transfer(address,uint128)": { "notice": "Transfer tokens" }
+            },
+            "events": {
+                "Transfer(address,address,uint128)": { "notice": "Transfer happened" }
+            },
+            "errors": {
+                "Insufficient(uint128)": [ { "notice": "Not enough balance" } ]
+            }
+        })
+    );
+
+    let devdoc = crate::abi::natspec::devdoc(0, &ns);
+    assert_eq!(
+        serde_json::to_value(&devdoc).unwrap(),
+        json!({
+            "kind": "dev",
+            "version": 1,
+            "title": "Token",
+            "author": "Alice",
+            "custom:security-contact": "security@example.com",
+            "methods": {
+                "transfer(address,uint128)": {
+                    "details": "Emits Transfer",
+                    "params": { "to": "The receiver", "value": "The amount" },
+                    "returns": { "ok": "Whether the transfer succeeded" }
+                }
+            }
+        })
+    );
+
+    let devdoc = crate::abi::natspec::devdoc(contract_no, &ns);
+    assert_eq!(
+        serde_json::to_value(&devdoc).unwrap(),
+        json!({
+            "kind": "dev",
+            "version": 1,
+            "methods": {
+                "transfer(address,uint128)": {
+                    "details": "Emits Transfer",
+                    "params": { "to": "The receiver", "value": "The amount" },
+                    "returns": { "ok": "Whether the transfer succeeded" }
+                }
+            },
+            "events": {
+                "Transfer(address,address,uint128)": {
+                    "params": { "from": "The sender" }
+                }
+            },
+            "errors": {
+                "Insufficient(uint128)": [ { "params": { "available": "The balance" } } ]
+            },
+            "stateVariables": {
+                "total": { "details": "The balance of the contract" }
+            }
+        })
+    );
+
+    crate::abi::natspec::check_documented(&mut ns);
+
+    let warnings: Vec<&str> = ns
+        .diagnostics
+        .iter()
+        .filter(|diag| diag.level == crate::sema::ast::Level::Warning)
+        .map(|diag| diag.message.as_str())
+        .filter(|message| message.contains("documented") || message.contains("has no tag"))
+        .collect();
+
+    assert_eq!(warnings, vec!["function 'undocumented' is not documented"]);
+}
+
+#[test]
+fn eth_abi_extensions() {
+    let src = r#"
+contract counter_pda {
+    event Moved(uint64 steps);
+
+    @payer(payer)
+    @seed("counter")
+    constructor(@seed bytes name, @bump bytes1 b) {}
+
+    function moveIt(uint64 stepCount) public {
+        emit Moved(stepCount);
+    }
+}"#;
+
+    let mut ns = generate_namespace(src);
+    codegen(&mut ns, &Options::default());
+    let abi = serde_json::to_value(crate::abi::ethereum::gen_abi(0, &ns)).unwrap();
+    let hex = |bytes: Vec<u8>| format!("0x{}", hex::encode(bytes));
+
+    let new = abi
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["type"] == "constructor")
+        .unwrap();
+
+    assert_eq!(new["discriminator"], hex(function_discriminator("new")));
+    assert_eq!(new["payer"], "payer");
+    assert_eq!(
+        new["seeds"],
+        json!([
+            {"kind": "const", "value": b"counter"},
+            {"kind": "arg", "path": "name"}
+        ])
+    );
+    assert_eq!(
+        new["accounts"],
+        json!([
+            {"name": "dataAccount", "writable": true, "signer": false},
+            {"name": "payer", "writable": true, "signer": true},
+            {"name": "systemProgram", "writable": false, "signer": false}
+        ])
+    );
+    assert!(new.get("selector").is_none());
+
+    let move_it = abi
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["name"] == "moveIt")
+        .unwrap();
+
+    assert_eq!(
+        move_it["discriminator"],
+        hex(function_discriminator("moveIt"))
+    );
+    assert!(move_it.get("payer").is_none());
+    assert!(move_it.get("seeds").is_none());
+
+    let moved = abi
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["type"] == "event")
+        .unwrap();
+
+    assert_eq!(moved["discriminator"], hex(event_discriminator("Moved")));
+
+    let src = r#"
+contract Token {
+    event Transfer(address indexed to, uint128 value);
+
+    function transfer(address to, uint128 value) public {
+        emit Transfer(to, value);
+    }
+}"#;
+
+    let ns = resolve_and_codegen(src, Target::default_polkadot(), &Options::default());
+    let abi = serde_json::to_value(crate::abi::ethereum::gen_abi(0, &ns)).unwrap();
+
+    assert_eq!(abi[0]["name"], "transfer");
+    assert_eq!(
+        abi[0]["selector"],
+        hex(ns.functions[ns.contracts[0].functions[0]].selector(&ns, &0))
+    );
+    assert!(abi[0].get("discriminator").is_none());
+    assert!(abi[0].get("accounts").is_none());
+    assert_eq!(abi[1]["type"], "event");
+    assert_eq!(
+        abi[1]["selector"],
+        hex(crate::codegen::events::event_selector(0, 0, &ns))
+    );
+
+    let ns = resolve_and_codegen(src, Target::EVM, &Options::default());
+    let abi = serde_json::to_value(crate::abi::ethereum::gen_abi(0, &ns)).unwrap();
+
+    assert!(abi[0].get("selector").is_none());
+    assert!(abi[1].get("selector").is_none());
+}
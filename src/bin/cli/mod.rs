@@ -12,6 +12,7 @@ use itertools::Itertools;
 use semver::Version;
 use serde::Deserialize;
 use solang::{
+    abi::{anchor::IdlSpec, polkadot::MetadataVersion},
     codegen::{OptimizationLevel, Options},
     file_resolver::FileResolver,
     Target,
@@ -34,6 +35,23 @@ pub enum Commands {
     #[command(about = "Generate documention for contracts using doc comments")]
     Doc(Doc),
 
+    #[command(about = "Check Solidity source files for common security problems")]
+    Lint(Lint),
+
+    #[command(about = "Prove that asserts, arithmetic and contract invariants cannot fail")]
+    Verify(Verify),
+
+    #[command(about = "Generate typed TypeScript or Rust client bindings for contracts")]
+    Bindings(Bindings),
+
+    #[command(about = "Encode or decode call data, return data, events and errors")]
+    Abi(Abi),
+
+    #[command(
+        about = "Check that the storage layout of a contract is compatible with the deployed version"
+    )]
+    UpgradeCheck(UpgradeCheck),
+
     #[command(about = "Print shell completion for various shells to STDOUT")]
     ShellComplete(ShellComplete),
 
@@ -41,7 +59,9 @@ pub enum Commands {
     #[command(about = "Start LSP language server on stdin/stdout")]
     LanguageServer(LanguageServerCommand),
 
-    #[command(about = "Generate Solidity interface files from Anchor IDL files")]
+    #[command(
+        about = "Generate Solidity interface files from Anchor IDL, Ethereum ABI or Polkadot metadata"
+    )]
     Idl(IdlCommand),
 
     #[command(about = "Create a new Solang project")]
@@ -59,7 +79,7 @@ pub struct New {
 
 #[derive(Args)]
 pub struct IdlCommand {
-    #[arg(name = "INPUT", help = "Convert IDL files", required= true, value_parser = ValueParser::os_string(), num_args = 1..)]
+    #[arg(name = "INPUT", help = "Convert Anchor IDL, Ethereum ABI or Polkadot metadata files", required= true, value_parser = ValueParser::os_string(), num_args = 1..)]
     pub input: Vec<OsString>,
 
     #[arg(name = "OUTPUT",help = "output file", short = 'o', long = "output", num_args = 1, value_parser =ValueParser::path_buf())]
@@ -74,7 +94,7 @@ pub struct LanguageServerCommand {
     #[arg(name = "IMPORTPATH", help = "Directory to search for solidity files", value_parser = ValueParser::path_buf(), action = ArgAction::Append, long = "importpath", short = 'I', num_args = 1)]
     pub import_path: Option<Vec<PathBuf>>,
 
-    #[arg(name = "IMPORTMAP", help = "Map directory to search for solidity files [format: map=path]",value_parser = ValueParser::new(parse_import_map), action = ArgAction::Append, long = "importmap", short = 'm', num_args = 1)]
+    #[arg(name = "IMPORTMAP", help = "Map directory to search for solidity files [format: [context:]map=path]",value_parser = ValueParser::new(parse_import_map), action = ArgAction::Append, long = "importmap", short = 'm', num_args = 1)]
     pub import_map: Option<Vec<(String, PathBuf)>>,
 }
 
@@ -95,8 +115,163 @@ pub struct Doc {
     #[arg(name = "VERBOSE" ,help = "show debug messages", short = 'v', action = ArgAction::SetTrue, long = "verbose")]
     pub verbose: bool,
 
-    #[arg(name = "OUTPUT",help = "output directory", short = 'o', long = "output", num_args = 1, value_parser =ValueParser::string())]
+    #[arg(name = "OUTPUT",help = "output directory", short = 'o', long = "output", num_args = 1, value_parser = ValueParser::os_string())]
     pub output_directory: Option<OsString>,
+
+    #[arg(name = "FORMAT", help = "Output format", long = "format", num_args = 1, value_parser = ["html", "markdown", "json"], default_value = "html")]
+    pub format: String,
+
+    #[arg(name = "TEMPLATE", help = "Handlebars template for the html page or the markdown contract pages", long = "template", num_args = 1, value_parser = ValueParser::path_buf())]
+    pub template: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct Lint {
+    #[clap(flatten)]
+    pub package: DocPackage,
+
+    #[clap(flatten)]
+    pub target: TargetArg,
+
+    #[arg(name = "VERBOSE" ,help = "show debug messages", short = 'v', action = ArgAction::SetTrue, long = "verbose")]
+    pub verbose: bool,
+
+    #[arg(name = "FORMAT", help = "Output format", long = "format", num_args = 1, value_parser = ["text", "sarif"], default_value = "text")]
+    pub format: String,
+
+    #[arg(name = "DETECTOR", help = "Only run the given detectors", long = "detector", action = ArgAction::Append, value_delimiter = ',', num_args = 1)]
+    pub detectors: Option<Vec<String>>,
+
+    #[arg(name = "OUTPUT",help = "output file for SARIF", short = 'o', long = "output", num_args = 1, value_parser =ValueParser::path_buf())]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct Verify {
+    #[clap(flatten)]
+    pub package: DocPackage,
+
+    #[clap(flatten)]
+    pub target: TargetArg,
+
+    #[arg(name = "VERBOSE" ,help = "show debug messages", short = 'v', action = ArgAction::SetTrue, long = "verbose")]
+    pub verbose: bool,
+
+    #[arg(name = "SOLVER", help = "SMT solver executable, which must accept SMT-LIB like z3", long = "solver", num_args = 1, value_parser = ValueParser::path_buf(), default_value = "z3")]
+    pub solver: PathBuf,
+
+    #[arg(name = "TIMEOUT", help = "Give up on a check after this many milliseconds", long = "timeout", num_args = 1, value_parser = value_parser!(u64))]
+    pub timeout: Option<u64>,
+}
+
+#[derive(Args)]
+pub struct Bindings {
+    #[clap(flatten)]
+    pub package: DocPackage,
+
+    #[clap(flatten)]
+    pub target: TargetArg,
+
+    #[arg(name = "VERBOSE" ,help = "show debug messages", short = 'v', action = ArgAction::SetTrue, long = "verbose")]
+    pub verbose: bool,
+
+    #[arg(name = "LANG", help = "Language of the bindings", long = "lang", num_args = 1, value_parser = ["ts", "rust"], default_value = "ts")]
+    pub language: String,
+
+    #[arg(name = "OUTPUT",help = "output directory", short = 'o', long = "output", num_args = 1, value_parser =ValueParser::path_buf())]
+    pub output_directory: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct UpgradeCheck {
+    #[arg(name = "OLD", help = "Storage layout of the deployed contract, written by --emit storage-layout", value_parser = ValueParser::path_buf(), num_args = 1, required = true)]
+    pub old: PathBuf,
+
+    #[clap(flatten)]
+    pub package: DocPackage,
+
+    #[clap(flatten)]
+    pub target: TargetArg,
+
+    #[arg(name = "PACKSTORAGE", help = "Pack small state variables into shared storage slots (Polkadot only)", long = "pack-storage", action = ArgAction::SetTrue)]
+    pub pack_storage: bool,
+}
+
+#[derive(Args)]
+pub struct Abi {
+    #[command(subcommand)]
+    pub command: AbiCommand,
+}
+
+#[derive(Subcommand)]
+pub enum AbiCommand {
+    #[command(about = "Encode the call data of a function, or the data of an event or error")]
+    Encode(AbiEncode),
+
+    #[command(
+        about = "Decode the call data or return data of a function, or the data of an event or error"
+    )]
+    Decode(AbiDecode),
+}
+
+#[derive(Args)]
+pub struct AbiEncode {
+    #[clap(flatten)]
+    pub package: AbiPackage,
+
+    #[clap(flatten)]
+    pub target: TargetArg,
+
+    #[arg(name = "VALUES", help = "Values to encode, arrays and structs are written as json", num_args = 0.., allow_negative_numbers = true)]
+    pub values: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct AbiDecode {
+    #[clap(flatten)]
+    pub package: AbiPackage,
+
+    #[clap(flatten)]
+    pub target: TargetArg,
+
+    #[arg(
+        name = "DATA",
+        help = "Data to decode in hex",
+        required = true,
+        num_args = 1
+    )]
+    pub data: String,
+
+    #[arg(name = "RETURNS", help = "Decode the return data of the function", long = "returns", action = ArgAction::SetTrue)]
+    pub returns: bool,
+}
+
+#[derive(Args)]
+pub struct AbiPackage {
+    #[arg(name = "INPUT", help = "Solidity source file, or Anchor IDL, Ethereum ABI or Polkadot metadata", value_parser = ValueParser::path_buf(), action = ArgAction::Set, num_args = 1, required = true)]
+    pub input: Vec<PathBuf>,
+
+    #[arg(
+        name = "NAME",
+        help = "Name of the function, event or error",
+        num_args = 1,
+        required = true
+    )]
+    pub name: String,
+
+    #[arg(
+        name = "CONTRACT",
+        help = "Contract which the function, event or error belongs to",
+        long = "contract",
+        num_args = 1
+    )]
+    pub contract: Option<String>,
+
+    #[arg(name = "IMPORTPATH", help = "Directory to search for solidity files",value_parser = ValueParser::path_buf(), action = ArgAction::Append, long = "importpath", short = 'I', num_args = 1)]
+    pub import_path: Option<Vec<PathBuf>>,
+
+    #[arg(name = "IMPORTMAP", help = "Map directory to search for solidity files [format: [context:]map=path]",value_parser = ValueParser::new(parse_import_map), action = ArgAction::Append, long = "importmap", short = 'm', num_args = 1)]
+    pub import_map: Option<Vec<(String, PathBuf)>>,
 }
 
 #[derive(Args, Deserialize, Debug, PartialEq)]
@@ -178,6 +353,13 @@ impl Compile {
                 "VERBOSE" => {
                     self.compiler_output.verbose = *matches.get_one::<bool>("VERBOSE").unwrap()
                 }
+                "IDLSPEC" => {
+                    self.compiler_output.idl_spec = matches.get_one::<String>("IDLSPEC").cloned()
+                }
+                "STRICT_NATSPEC" => {
+                    self.compiler_output.strict_natspec =
+                        *matches.get_one::<bool>("STRICT_NATSPEC").unwrap()
+                }
 
                 // DebugFeatures args
                 "NOLOGRUNTIMEERRORS" => {
@@ -217,6 +399,23 @@ impl Compile {
                         .get_one::<bool>("COMMONSUBEXPRESSIONELIMINATION")
                         .unwrap()
                 }
+                "INLINE" => self.optimizations.inline = *matches.get_one::<bool>("INLINE").unwrap(),
+                "STORAGECACHE" => {
+                    self.optimizations.storage_cache =
+                        *matches.get_one::<bool>("STORAGECACHE").unwrap()
+                }
+                "LOOPOPTIMIZATION" => {
+                    self.optimizations.loop_optimization =
+                        *matches.get_one::<bool>("LOOPOPTIMIZATION").unwrap()
+                }
+                "DEADFUNCTIONELIMINATION" => {
+                    self.optimizations.dead_function_elimination =
+                        *matches.get_one::<bool>("DEADFUNCTIONELIMINATION").unwrap()
+                }
+                "PACKSTORAGE" => {
+                    self.optimizations.pack_storage =
+                        *matches.get_one::<bool>("PACKSTORAGE").unwrap()
+                }
                 "OPT" => self.optimizations.opt_level = matches.get_one::<String>("OPT").cloned(),
 
                 "TARGET" => self.target_arg.name = matches.get_one::<String>("TARGET").cloned(),
@@ -227,6 +426,10 @@ impl Compile {
                 "VALUE_LENGTH" => {
                     self.target_arg.value_length = matches.get_one::<u64>("VALUE_LENGTH").copied()
                 }
+                "METADATA_VERSION" => {
+                    self.target_arg.metadata_version =
+                        matches.get_one::<u64>("METADATA_VERSION").copied()
+                }
 
                 _ => {}
             }
@@ -238,7 +441,7 @@ impl Compile {
 
 #[derive(Args, Deserialize, Default, Debug, PartialEq)]
 pub struct CompilerOutput {
-    #[arg(name = "EMIT", help = "Emit compiler state at early stage", long = "emit", num_args = 1, value_parser = ["ast-dot", "cfg", "llvm-ir", "llvm-bc", "object", "asm"])]
+    #[arg(name = "EMIT", help = "Emit compiler state at early stage", long = "emit", num_args = 1, value_parser = ["ast-dot", "cfg", "cost-report", "storage-layout", "natspec", "eth-abi", "size-report", "llvm-ir", "llvm-bc", "object", "asm"])]
     #[serde(deserialize_with = "deserialize_emit", default)]
     pub emit: Option<String>,
 
@@ -257,6 +460,14 @@ pub struct CompilerOutput {
     #[arg(name = "VERBOSE" ,help = "show debug messages", short = 'v', action = ArgAction::SetTrue, long = "verbose")]
     #[serde(default)]
     pub verbose: bool,
+
+    #[arg(name = "IDLSPEC", help = "Layout of the Anchor IDL for Solana [default: legacy]", long = "idl-spec", num_args = 1, value_parser = ["legacy", "0.30"])]
+    #[serde(deserialize_with = "deserialize_idl_spec", default)]
+    pub idl_spec: Option<String>,
+
+    #[arg(name = "STRICT_NATSPEC", help = "Warn about public functions without doc comments", long = "strict-natspec", action = ArgAction::SetTrue)]
+    #[serde(default)]
+    pub strict_natspec: bool,
 }
 
 #[derive(Args)]
@@ -269,6 +480,9 @@ pub struct TargetArg {
 
     #[arg(name = "VALUE_LENGTH", help = "Value length on the Polkadot Parachain", long = "value-length", num_args = 1, value_parser = value_parser!(u64).range(4..1024))]
     pub value_length: Option<u64>,
+
+    #[arg(name = "METADATA_VERSION", help = "Version of the ink! metadata on Polkadot [default: 4]", long = "metadata-version", num_args = 1, value_parser = value_parser!(u64).range(4..=5))]
+    pub metadata_version: Option<u64>,
 }
 
 #[derive(Args, Deserialize, Debug, PartialEq)]
@@ -281,6 +495,9 @@ pub struct CompileTargetArg {
 
     #[arg(name = "VALUE_LENGTH", help = "Value length on the Polkadot Parachain", long = "value-length", num_args = 1, value_parser = value_parser!(u64).range(4..1024))]
     pub value_length: Option<u64>,
+
+    #[arg(name = "METADATA_VERSION", help = "Version of the ink! metadata on Polkadot [default: 4]", long = "metadata-version", num_args = 1, value_parser = value_parser!(u64).range(4..=5))]
+    pub metadata_version: Option<u64>,
 }
 
 #[derive(Args)]
@@ -294,7 +511,7 @@ pub struct DocPackage {
     #[arg(name = "IMPORTPATH", help = "Directory to search for solidity files",value_parser = ValueParser::path_buf(), action = ArgAction::Append, long = "importpath", short = 'I', num_args = 1)]
     pub import_path: Option<Vec<PathBuf>>,
 
-    #[arg(name = "IMPORTMAP", help = "Map directory to search for solidity files [format: map=path]",value_parser = ValueParser::new(parse_import_map), action = ArgAction::Append, long = "importmap", short = 'm', num_args = 1)]
+    #[arg(name = "IMPORTMAP", help = "Map directory to search for solidity files [format: [context:]map=path]",value_parser = ValueParser::new(parse_import_map), action = ArgAction::Append, long = "importmap", short = 'm', num_args = 1)]
     pub import_map: Option<Vec<(String, PathBuf)>>,
 }
 
@@ -310,7 +527,7 @@ pub struct CompilePackage {
     #[arg(name = "IMPORTPATH", help = "Directory to search for solidity files", value_parser = ValueParser::path_buf(), action = ArgAction::Append, long = "importpath", short = 'I', num_args = 1)]
     pub import_path: Option<Vec<PathBuf>>,
 
-    #[arg(name = "IMPORTMAP", help = "Map directory to search for solidity files [format: map=path]",value_parser = ValueParser::new(parse_import_map), action = ArgAction::Append, long = "importmap", short = 'm', num_args = 1)]
+    #[arg(name = "IMPORTMAP", help = "Map directory to search for solidity files [format: [context:]map=path]",value_parser = ValueParser::new(parse_import_map), action = ArgAction::Append, long = "importmap", short = 'm', num_args = 1)]
     #[serde(deserialize_with = "deserialize_inline_table", default)]
     pub import_map: Option<Vec<(String, PathBuf)>>,
 
@@ -378,6 +595,29 @@ pub struct Optimizations {
     )]
     pub common_subexpression_elimination: bool,
 
+    #[arg(name = "INLINE", help = "Disable inlining of internal function calls", long = "no-inline", action = ArgAction::SetFalse, display_order = 6)]
+    #[serde(default = "default_true")]
+    pub inline: bool,
+
+    #[arg(name = "STORAGECACHE", help = "Disable caching of contract storage in local variables", long = "no-storage-cache", action = ArgAction::SetFalse, display_order = 7)]
+    #[serde(default = "default_true", rename(deserialize = "storage-cache"))]
+    pub storage_cache: bool,
+
+    #[arg(name = "LOOPOPTIMIZATION", help = "Disable loop invariant code motion and induction variable strength reduction", long = "no-loop-optimization", action = ArgAction::SetFalse, display_order = 8)]
+    #[serde(default = "default_true", rename(deserialize = "loop-optimization"))]
+    pub loop_optimization: bool,
+
+    #[arg(name = "DEADFUNCTIONELIMINATION", help = "Disable removal of functions which are never called", long = "no-dead-function-elimination", action = ArgAction::SetFalse, display_order = 9)]
+    #[serde(
+        default = "default_true",
+        rename(deserialize = "dead-function-elimination")
+    )]
+    pub dead_function_elimination: bool,
+
+    #[arg(name = "PACKSTORAGE", help = "Pack small state variables into shared storage slots (Polkadot only)", long = "pack-storage", action = ArgAction::SetTrue, display_order = 10)]
+    #[serde(default, rename(deserialize = "pack-storage"))]
+    pub pack_storage: bool,
+
     #[arg(name = "OPT", help = "Set llvm optimizer level ", short = 'O', default_value = "default", value_parser = ["none", "less", "default", "aggressive"], num_args = 1)]
     #[serde(rename(deserialize = "llvm-IR-optimization-level"))]
     pub opt_level: Option<String>,
@@ -397,6 +637,7 @@ pub trait TargetArgTrait {
     fn get_name(&self) -> &String;
     fn get_address_length(&self) -> &Option<u64>;
     fn get_value_length(&self) -> &Option<u64>;
+    fn get_metadata_version(&self) -> &Option<u64>;
 }
 
 impl TargetArgTrait for TargetArg {
@@ -411,6 +652,10 @@ impl TargetArgTrait for TargetArg {
     fn get_value_length(&self) -> &Option<u64> {
         &self.value_length
     }
+
+    fn get_metadata_version(&self) -> &Option<u64> {
+        &self.metadata_version
+    }
 }
 
 impl TargetArgTrait for CompileTargetArg {
@@ -430,6 +675,10 @@ impl TargetArgTrait for CompileTargetArg {
     fn get_value_length(&self) -> &Option<u64> {
         &self.value_length
     }
+
+    fn get_metadata_version(&self) -> &Option<u64> {
+        &self.metadata_version
+    }
 }
 
 pub(crate) fn target_arg<T: TargetArgTrait>(target_arg: &T) -> Target {
@@ -445,6 +694,11 @@ pub(crate) fn target_arg<T: TargetArgTrait>(target_arg: &T) -> Target {
             eprintln!("error: value length cannot be modified except for polkadot target");
             exit(1);
         }
+
+        if target_arg.get_metadata_version().is_some() {
+            eprintln!("error: metadata version cannot be modified except for polkadot target");
+            exit(1);
+        }
     }
 
     let target = match target_name.as_str() {
@@ -461,6 +715,18 @@ pub(crate) fn target_arg<T: TargetArgTrait>(target_arg: &T) -> Target {
     target
 }
 
+/// The version of the ink! metadata, which is passed to codegen in the options
+pub(crate) fn metadata_version_arg<T: TargetArgTrait>(target_arg: &T) -> MetadataVersion {
+    match target_arg.get_metadata_version() {
+        None | Some(4) => MetadataVersion::V4,
+        Some(5) => MetadataVersion::V5,
+        Some(version) => {
+            eprintln!("error: metadata version {version} is not supported, use 4 or 5");
+            exit(1);
+        }
+    }
+}
+
 /// This trait is used to avoid code repetition when dealing with two implementations of the Package type:
 /// `CompilePackage` and `DocPackage`. Each struct represents a group of arguments for the compile and doc commands.
 /// Throughout the code, these two structs are treated the same, and this trait allows for unified handling.
@@ -505,6 +771,20 @@ impl PackageTrait for DocPackage {
     }
 }
 
+impl PackageTrait for AbiPackage {
+    fn get_input(&self) -> &Vec<PathBuf> {
+        &self.input
+    }
+
+    fn get_import_path(&self) -> &Option<Vec<PathBuf>> {
+        &self.import_path
+    }
+
+    fn get_import_map(&self) -> &Option<Vec<(String, PathBuf)>> {
+        &self.import_map
+    }
+}
+
 pub fn imports_arg<T: PackageTrait>(package: &T) -> FileResolver {
     let mut resolver = FileResolver::default();
 
@@ -526,6 +806,24 @@ pub fn imports_arg<T: PackageTrait>(package: &T) -> FileResolver {
 
     if let Some(maps) = package.get_import_map() {
         for (map, path) in maps {
+            if let Some((context, prefix)) = map.split_once(':') {
+                let context = OsString::from(context);
+                let prefix = OsString::from(prefix);
+                if let Some((_, _, existing_path)) = resolver
+                    .get_context_import_maps()
+                    .iter()
+                    .find(|(c, m, _)| *c == context && *m == prefix)
+                {
+                    eprintln!(
+                        "warning: mapping '{}' to '{}' is overwritten",
+                        map,
+                        existing_path.display()
+                    )
+                }
+                resolver.add_context_import_map(context, prefix, path.clone());
+                continue;
+            }
+
             let os_map = OsString::from(map);
             if let Some((_, existing_path)) = resolver
                 .get_import_paths()
@@ -545,6 +843,13 @@ pub fn imports_arg<T: PackageTrait>(package: &T) -> FileResolver {
     resolver
 }
 
+pub fn idl_spec_arg(compiler_output: &CompilerOutput) -> IdlSpec {
+    match compiler_output.idl_spec.as_deref() {
+        Some("0.30") => IdlSpec::Anchor030,
+        _ => IdlSpec::Legacy,
+    }
+}
+
 pub fn options_arg(debug: &DebugFeatures, optimizations: &Optimizations) -> Options {
     let opt_level = if let Some(level) = &optimizations.opt_level {
         match level.as_str() {
@@ -564,6 +869,13 @@ pub fn options_arg(debug: &DebugFeatures, optimizations: &Optimizations) -> Opti
         strength_reduce: optimizations.strength_reduce,
         vector_to_slice: optimizations.vector_to_slice,
         common_subexpression_elimination: optimizations.common_subexpression_elimination,
+        inline: optimizations.inline,
+        storage_cache: optimizations.storage_cache,
+        loop_optimization: optimizations.loop_optimization,
+        dead_function_elimination: optimizations.dead_function_elimination,
+        pack_storage: optimizations.pack_storage,
+        // this is a target argument, see `metadata_version_arg()`
+        metadata_version: MetadataVersion::default(),
         generate_debug_information: debug.generate_debug_info,
         opt_level,
         log_runtime_errors: debug.log_runtime_errors && !debug.release,
@@ -648,16 +960,32 @@ where
     match str {
         Some(value) => {
             match value.as_str() {
-                "ast-dot"|"cfg"|"llvm-ir"|"llvm-bc"|"object"|"asm" =>
+                "ast-dot"|"cfg"|"cost-report"|"storage-layout"|"natspec"|"eth-abi"|"size-report"|"llvm-ir"|"llvm-bc"|"object"|"asm" =>
                     Ok(Some(value))
                 ,
-                _ => Err(serde::de::Error::custom("Invalid option for `emit`. Valid options are: `ast-dot`, `cfg`, `llvm-ir`, `llvm-bc`, `object`, `asm`"))
+                _ => Err(serde::de::Error::custom("Invalid option for `emit`. Valid options are: `ast-dot`, `cfg`, `cost-report`, `storage-layout`, `natspec`, `eth-abi`, `size-report`, `llvm-ir`, `llvm-bc`, `object`, `asm`"))
             }
         }
         None => Ok(None),
     }
 }
 
+fn deserialize_idl_spec<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let str: Option<String> = Option::deserialize(deserializer)?;
+    match str {
+        Some(value) => match value.as_str() {
+            "legacy" | "0.30" => Ok(Some(value)),
+            _ => Err(serde::de::Error::custom(
+                "Invalid option for `idl_spec`. Valid options are: `legacy`, `0.30`",
+            )),
+        },
+        None => Ok(None),
+    }
+}
+
 fn default_true() -> bool {
     true
 }
@@ -15,7 +15,7 @@ mod tests {
 
     #[test]
     fn parse_compile_options() {
-        let mut command: Vec<&str> = "solang compile flipper.sol --target polkadot --value-length=31 --address-length=33 --no-dead-storage --no-constant-folding --no-strength-reduce --no-vector-to-slice --no-cse -O aggressive".split(' ').collect();
+        let mut command: Vec<&str> = "solang compile flipper.sol --target polkadot --value-length=31 --address-length=33 --no-dead-storage --no-constant-folding --no-strength-reduce --no-vector-to-slice --no-cse --no-inline --no-storage-cache --no-loop-optimization --no-dead-function-elimination --pack-storage -O aggressive".split(' ').collect();
         let mut cli = Cli::parse_from(command);
 
         if let Commands::Compile(compile_args) = cli.command {
@@ -31,6 +31,11 @@ mod tests {
             assert!(!compile_args.optimizations.dead_storage);
             assert!(!compile_args.optimizations.vector_to_slice);
             assert!(!compile_args.optimizations.strength_reduce);
+            assert!(!compile_args.optimizations.inline);
+            assert!(!compile_args.optimizations.storage_cache);
+            assert!(!compile_args.optimizations.loop_optimization);
+            assert!(!compile_args.optimizations.dead_function_elimination);
+            assert!(compile_args.optimizations.pack_storage);
             assert_eq!(compile_args.optimizations.opt_level.unwrap(), "aggressive");
         }
 
@@ -45,6 +50,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_doc_options() {
+        let command = "solang doc flipper.sol --target polkadot -o docs --format markdown --template page.hbs".split(' ');
+        let cli = Cli::parse_from(command);
+
+        if let Commands::Doc(doc_args) = cli.command {
+            assert_eq!(doc_args.output_directory.unwrap(), "docs");
+            assert_eq!(doc_args.format, "markdown");
+            assert_eq!(doc_args.template.unwrap(), PathBuf::from("page.hbs"));
+        } else {
+            unreachable!();
+        }
+
+        let cli = Cli::parse_from(["solang", "doc", "flipper.sol", "--target", "solana"]);
+
+        if let Commands::Doc(doc_args) = cli.command {
+            assert_eq!(doc_args.format, "html");
+            assert!(doc_args.template.is_none());
+        } else {
+            unreachable!();
+        }
+    }
+
     #[test]
     fn parse_package_from_toml() {
         let mut package_toml = r#"
@@ -111,6 +139,11 @@ mod tests {
         strength-reduce = false
         vector-to-slice = false
         common-subexpression-elimination = true
+        inline = false
+        storage-cache = false
+        loop-optimization = false
+        dead-function-elimination = false
+        pack-storage = true
         llvm-IR-optimization-level = "aggressive""#;
 
         let opt: cli::Optimizations = toml::from_str(opt_toml).unwrap();
@@ -120,6 +153,11 @@ mod tests {
         assert!(!opt.constant_folding);
         assert!(!opt.strength_reduce);
         assert!(!opt.vector_to_slice);
+        assert!(!opt.inline);
+        assert!(!opt.storage_cache);
+        assert!(!opt.loop_optimization);
+        assert!(!opt.dead_function_elimination);
+        assert!(opt.pack_storage);
         assert_eq!(opt.opt_level.unwrap(), "aggressive");
     }
 
@@ -137,13 +175,15 @@ mod tests {
         let target_toml = r#"
         name = "polkadot"  # Valid targets are "solana" and "polkadot"
         address_length = 32
-        value_length = 16"#;
+        value_length = 16
+        metadata_version = 5"#;
 
         let target: cli::CompileTargetArg = toml::from_str(target_toml).unwrap();
 
         assert_eq!(target.name.unwrap(), "polkadot");
         assert_eq!(target.address_length.unwrap(), 32);
         assert_eq!(target.value_length.unwrap(), 16);
+        assert_eq!(target.metadata_version.unwrap(), 5);
     }
 
     #[test]
@@ -154,6 +194,8 @@ mod tests {
         emit = "ast-dot"
         output_directory = "output"
         output_meta = "metadata"
+        idl_spec = "0.30"
+        strict_natspec = true
         "#;
 
         let out: cli::CompilerOutput = toml::from_str(compiler_out).unwrap();
@@ -163,11 +205,14 @@ mod tests {
         assert_eq!(out.emit, Some("ast-dot".to_owned()));
         assert_eq!(out.output_directory, Some("output".to_owned()));
         assert_eq!(out.output_meta, Some("metadata".to_owned()));
+        assert_eq!(out.idl_spec, Some("0.30".to_owned()));
+        assert!(out.strict_natspec);
 
         let default_out: cli::CompilerOutput = toml::from_str("").unwrap();
 
         assert!(!default_out.verbose);
         assert!(!default_out.std_json_output);
+        assert!(!default_out.strict_natspec);
     }
 
     #[test]
@@ -192,12 +237,15 @@ mod tests {
                     std_json_output: false,
                     output_directory: None,
                     output_meta: None,
-                    verbose: false
+                    verbose: false,
+                    idl_spec: None,
+                    strict_natspec: false
                 },
                 target_arg: cli::CompileTargetArg {
                     name: Some("solana".to_owned()),
                     address_length: None,
-                    value_length: None
+                    value_length: None,
+                    metadata_version: None
                 },
                 debug_features: cli::DebugFeatures {
                     log_runtime_errors: true,
@@ -211,6 +259,11 @@ mod tests {
                     strength_reduce: true,
                     vector_to_slice: true,
                     common_subexpression_elimination: true,
+                    inline: true,
+                    storage_cache: true,
+                    loop_optimization: true,
+                    dead_function_elimination: true,
+                    pack_storage: false,
                     opt_level: Some("aggressive".to_owned()),
                     #[cfg(feature = "wasm_opt")]
                     wasm_opt_passes: None
@@ -218,7 +271,7 @@ mod tests {
             }
         );
 
-        let command = "solang compile flipper.sol sesa.sol --config-file solang.toml --contract-authors not_sesa --target polkadot --value-length=31 --address-length=33 --no-dead-storage --no-constant-folding --no-strength-reduce --no-vector-to-slice --no-cse -O aggressive".split(' ');
+        let command = "solang compile flipper.sol sesa.sol --config-file solang.toml --contract-authors not_sesa --target polkadot --value-length=31 --address-length=33 --metadata-version=5 --strict-natspec --no-dead-storage --no-constant-folding --no-strength-reduce --no-vector-to-slice --no-cse --no-inline --no-storage-cache --no-loop-optimization --no-dead-function-elimination --pack-storage -O aggressive".split(' ');
 
         let matches = Cli::command().get_matches_from(command);
 
@@ -246,12 +299,15 @@ mod tests {
                     std_json_output: false,
                     output_directory: None,
                     output_meta: None,
-                    verbose: false
+                    verbose: false,
+                    idl_spec: None,
+                    strict_natspec: true
                 },
                 target_arg: cli::CompileTargetArg {
                     name: Some("polkadot".to_owned()),
                     address_length: Some(33),
-                    value_length: Some(31)
+                    value_length: Some(31),
+                    metadata_version: Some(5)
                 },
                 debug_features: cli::DebugFeatures {
                     log_runtime_errors: true,
@@ -265,6 +321,11 @@ mod tests {
                     strength_reduce: false,
                     vector_to_slice: false,
                     common_subexpression_elimination: false,
+                    inline: false,
+                    storage_cache: false,
+                    loop_optimization: false,
+                    dead_function_elimination: false,
+                    pack_storage: true,
                     opt_level: Some("aggressive".to_owned()),
                     #[cfg(feature = "wasm_opt")]
                     wasm_opt_passes: None
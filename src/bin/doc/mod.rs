@@ -7,7 +7,12 @@ use std::fs::File;
 use std::io::prelude::*;
 use std::path::Path;
 
-use solang::sema::ast;
+use solang::codegen::{events::event_selector, revert::SolidityError};
+use solang::sema::{
+    ast,
+    tags::{function_tags, variable_tags},
+};
+use solang::Target;
 use solang_parser::pt;
 
 #[derive(Serialize)]
@@ -45,6 +50,28 @@ struct EventDecl<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
     contract: Option<&'a str>,
     anonymous: bool,
+    /// The first topic, which depends on the emitting contract
+    #[serde(skip_serializing_if = "Option::is_none")]
+    selector: Option<String>,
+    field: Vec<Field<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    notice: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    author: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dev: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct ErrorDecl<'a> {
+    #[serde(skip_serializing)]
+    loc: pt::Loc,
+    name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    contract: Option<&'a str>,
+    selector: String,
     field: Vec<Field<'a>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     title: Option<&'a str>,
@@ -80,10 +107,16 @@ struct Contract<'a> {
     loc: pt::Loc,
     name: &'a str,
     ty: String,
+    /// The base contracts, from the most derived to the most base
+    inheritance: Vec<&'a str>,
     variables: Vec<Variable<'a>>,
     base_variables: Vec<Variable<'a>>,
     functions: Vec<Function<'a>>,
     base_functions: Vec<Function<'a>>,
+    /// The events emitted by the contract
+    events: Vec<EventDecl<'a>>,
+    /// The errors declared in the contract or its bases
+    errors: Vec<ErrorDecl<'a>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     title: Option<&'a str>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -116,6 +149,10 @@ struct Variable<'a> {
 struct Function<'a> {
     name: &'a str,
     ty: String,
+    signature: &'a str,
+    /// The selector, if the function can be called externally
+    #[serde(skip_serializing_if = "Option::is_none")]
+    selector: Option<String>,
     visibility: String,
     mutability: String,
     params: Vec<Field<'a>>,
@@ -138,63 +175,209 @@ struct Function<'a> {
 struct Top<'a> {
     contracts: Vec<Contract<'a>>,
     events: Vec<EventDecl<'a>>,
+    errors: Vec<ErrorDecl<'a>>,
     structs: Vec<StructDecl<'a>>,
     enums: Vec<EnumDecl<'a>>,
 }
 
-fn get_tag<'a>(name: &str, tags: &'a [ast::Tag]) -> Option<&'a str> {
-    tags.iter()
+fn get_tag<'a>(name: &str, tags: impl IntoIterator<Item = &'a ast::Tag>) -> Option<&'a str> {
+    tags.into_iter()
         .find(|e| e.tag == name)
         .map(|e| &e.value as &str)
 }
 
-fn get_tag_no<'a>(name: &str, no: usize, tags: &'a [ast::Tag]) -> Option<&'a str> {
-    tags.iter()
+fn get_tag_no<'a>(
+    name: &str,
+    no: usize,
+    tags: impl IntoIterator<Item = &'a ast::Tag>,
+) -> Option<&'a str> {
+    tags.into_iter()
         .find(|e| e.tag == name && e.no == no)
         .map(|e| &e.value as &str)
 }
 
-/// Generate documentation from the doccomments. This may be replaced with force-doc
-/// one day (once it exists)
-pub fn generate_docs(outdir: &OsString, files: &[ast::Namespace], verbose: bool) {
+fn hex_selector(selector: &[u8]) -> String {
+    format!("0x{}", hex::encode(selector))
+}
+
+/// An event, with the selector it has when emitted by the given contract
+fn map_event(
+    file: &ast::Namespace,
+    event_no: usize,
+    emitting_contract_no: Option<usize>,
+) -> EventDecl<'_> {
+    let event_decl = &file.events[event_no];
+
+    let field = event_decl
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| Field {
+            name: f.name_as_str(),
+            ty: f.ty.to_string(file),
+            indexed: f.indexed,
+            doc: get_tag_no("param", i, &event_decl.tags),
+        })
+        .collect();
+
+    EventDecl {
+        name: &event_decl.id.name,
+        contract: event_decl
+            .contract
+            .map(|contract_no| file.contracts[contract_no].id.name.as_str()),
+        title: get_tag("title", &event_decl.tags),
+        notice: get_tag("notice", &event_decl.tags),
+        author: get_tag("author", &event_decl.tags),
+        dev: get_tag("dev", &event_decl.tags),
+        anonymous: event_decl.anonymous,
+        selector: emitting_contract_no
+            .filter(|_| !event_decl.anonymous && file.target != Target::Soroban)
+            .map(|contract_no| hex_selector(&event_selector(event_no, contract_no, file))),
+        loc: event_decl.id.loc,
+        field,
+    }
+}
+
+fn map_error(file: &ast::Namespace, error_no: usize) -> ErrorDecl<'_> {
+    let error_decl = &file.errors[error_no];
+
+    let field = error_decl
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| Field {
+            name: f.name_as_str(),
+            ty: f.ty.to_string(file),
+            indexed: false,
+            doc: get_tag_no("param", i, &error_decl.tags),
+        })
+        .collect();
+
+    let selector = SolidityError::Custom {
+        error_no,
+        exprs: Vec::new(),
+    }
+    .selector(file);
+
+    ErrorDecl {
+        name: &error_decl.name,
+        contract: error_decl
+            .contract
+            .map(|contract_no| file.contracts[contract_no].id.name.as_str()),
+        selector: hex_selector(&selector),
+        title: get_tag("title", &error_decl.tags),
+        notice: get_tag("notice", &error_decl.tags),
+        author: get_tag("author", &error_decl.tags),
+        dev: get_tag("dev", &error_decl.tags),
+        loc: error_decl.loc,
+        field,
+    }
+}
+
+fn map_var<'a>(
+    file: &'a ast::Namespace,
+    contract_no: usize,
+    var_no: usize,
+    base_contract: Option<&'a str>,
+) -> Variable<'a> {
+    let var = &file.contracts[contract_no].variables[var_no];
+    let tags = variable_tags(contract_no, var_no, file);
+
+    Variable {
+        name: &var.name,
+        ty: var.ty.to_string(file),
+        base_contract,
+        title: get_tag("title", tags.iter().copied()),
+        notice: get_tag("notice", tags.iter().copied()),
+        author: get_tag("author", tags.iter().copied()),
+        dev: get_tag("dev", tags.iter().copied()),
+        constant: var.constant,
+        visibility: format!("{}", var.visibility),
+    }
+}
+
+fn map_func<'a>(
+    file: &'a ast::Namespace,
+    contract_no: usize,
+    function_no: usize,
+    base_contract: Option<&'a str>,
+) -> Function<'a> {
+    let func = &file.functions[function_no];
+    // resolve @inheritdoc
+    let tags = function_tags(function_no, file);
+
+    let mut params = Vec::new();
+
+    for (i, f) in func.params.iter().enumerate() {
+        params.push(Field {
+            name: f.name_as_str(),
+            ty: f.ty.to_string(file),
+            indexed: false,
+            doc: get_tag_no("param", i, tags.iter().copied()),
+        });
+    }
+
+    let mut returns = Vec::new();
+
+    for (i, f) in func.returns.iter().enumerate() {
+        returns.push(Field {
+            name: f.name_as_str(),
+            ty: f.ty.to_string(file),
+            indexed: false,
+            doc: get_tag_no("return", i, tags.iter().copied()),
+        });
+    }
+
+    Function {
+        name: &func.id.name,
+        ty: format!("{}", func.ty),
+        signature: &func.signature,
+        selector: func
+            .is_public()
+            .then(|| hex_selector(&func.selector(file, &contract_no))),
+        mutability: format!("{}", func.mutability),
+        base_contract,
+        title: get_tag("title", tags.iter().copied()),
+        notice: get_tag("notice", tags.iter().copied()),
+        author: get_tag("author", tags.iter().copied()),
+        dev: get_tag("dev", tags.iter().copied()),
+        visibility: format!("{}", func.visibility),
+        returns,
+        params,
+        is_virtual: func.is_virtual,
+        is_override: func.is_override.is_some(),
+    }
+}
+
+/// Collect the documentation of all the files
+fn collect(files: &[ast::Namespace]) -> Top<'_> {
     let mut top = Top {
         contracts: Vec::new(),
         events: Vec::new(),
+        errors: Vec::new(),
         structs: Vec::new(),
         enums: Vec::new(),
     };
 
     for file in files {
         // events
-        for event_decl in &file.events {
-            if top.events.iter().any(|e| e.loc == event_decl.loc) {
+        for (event_no, event_decl) in file.events.iter().enumerate() {
+            if top.events.iter().any(|e| e.loc == event_decl.id.loc) {
                 continue;
             }
 
-            let mut field = Vec::new();
+            // freestanding events have a selector per emitting contract
+            top.events
+                .push(map_event(file, event_no, event_decl.contract));
+        }
 
-            for (i, f) in event_decl.fields.iter().enumerate() {
-                field.push(Field {
-                    name: f.name_as_str(),
-                    ty: f.ty.to_string(file),
-                    indexed: f.indexed,
-                    doc: get_tag_no("param", i, &event_decl.tags),
-                });
+        // errors
+        for (error_no, error_decl) in file.errors.iter().enumerate() {
+            if top.errors.iter().any(|e| e.loc == error_decl.loc) {
+                continue;
             }
 
-            top.events.push(EventDecl {
-                name: &event_decl.id.name,
-                contract: event_decl
-                    .contract
-                    .map(|contract_no| file.contracts[contract_no].id.name.as_str()),
-                title: get_tag("title", &event_decl.tags),
-                notice: get_tag("notice", &event_decl.tags),
-                author: get_tag("author", &event_decl.tags),
-                dev: get_tag("dev", &event_decl.tags),
-                anonymous: event_decl.anonymous,
-                loc: event_decl.id.loc,
-                field,
-            });
+            top.errors.push(map_error(file, error_no));
         }
 
         // structs
@@ -259,86 +442,15 @@ pub fn generate_docs(outdir: &OsString, files: &[ast::Namespace], verbose: bool)
                 continue;
             }
 
-            fn map_var<'a>(
-                file: &'a ast::Namespace,
-                base_contract: Option<&'a str>,
-                var: &'a ast::Variable,
-            ) -> Variable<'a> {
-                Variable {
-                    name: &var.name,
-                    ty: var.ty.to_string(file),
-                    base_contract,
-                    title: get_tag("title", &var.tags),
-                    notice: get_tag("notice", &var.tags),
-                    author: get_tag("author", &var.tags),
-                    dev: get_tag("dev", &var.tags),
-                    constant: var.constant,
-                    visibility: format!("{}", var.visibility),
-                }
-            }
-
-            fn map_func<'a>(
-                file: &'a ast::Namespace,
-                base_contract: Option<&'a str>,
-                func: &'a ast::Function,
-            ) -> Function<'a> {
-                let mut params = Vec::new();
-
-                for (i, f) in func.params.iter().enumerate() {
-                    params.push(Field {
-                        name: f.name_as_str(),
-                        ty: f.ty.to_string(file),
-                        indexed: false,
-                        doc: get_tag_no("param", i, &func.tags),
-                    });
-                }
-
-                let mut returns = Vec::new();
-
-                for (i, f) in func.returns.iter().enumerate() {
-                    returns.push(Field {
-                        name: f.name_as_str(),
-                        ty: f.ty.to_string(file),
-                        indexed: false,
-                        doc: get_tag_no("return", i, &func.tags),
-                    });
-                }
-
-                Function {
-                    name: &func.id.name,
-                    ty: format!("{}", func.ty),
-                    mutability: format!("{}", func.mutability),
-                    base_contract,
-                    title: get_tag("title", &func.tags),
-                    notice: get_tag("notice", &func.tags),
-                    author: get_tag("author", &func.tags),
-                    dev: get_tag("dev", &func.tags),
-                    visibility: format!("{}", func.visibility),
-                    returns,
-                    params,
-                    is_virtual: func.is_virtual,
-                    is_override: func.is_override.is_some(),
-                }
-            }
-
-            let variables = contract
-                .variables
-                .iter()
-                .map(|var| map_var(file, None, var))
+            let variables = (0..contract.variables.len())
+                .map(|var_no| map_var(file, contract_no, var_no, None))
                 .collect();
 
             let functions = contract
                 .functions
                 .iter()
-                .filter_map(|function_no| {
-                    let f = &file.functions[*function_no];
-
-                    if f.has_body {
-                        Some(map_func(file, None, f))
-                    } else {
-                        None
-                    }
-                })
+                .filter(|function_no| file.functions[**function_no].has_body)
+                .map(|function_no| map_func(file, contract_no, *function_no, None))
                 .collect();
 
             let bases = file.contract_bases(contract_no);
@@ -346,38 +458,57 @@ pub fn generate_docs(outdir: &OsString, files: &[ast::Namespace], verbose: bool)
             let mut base_variables = Vec::new();
             let mut base_functions = Vec::new();
 
-            for base_no in bases {
-                if contract_no == base_no {
+            for base_no in &bases {
+                if contract_no == *base_no {
                     continue;
                 }
 
-                let base = &file.contracts[base_no];
+                let base = &file.contracts[*base_no];
 
-                for var in base
-                    .variables
-                    .iter()
-                    .map(|var| map_var(file, Some(&base.id.name), var))
-                {
-                    base_variables.push(var);
+                for var_no in 0..base.variables.len() {
+                    base_variables.push(map_var(file, *base_no, var_no, Some(&base.id.name)));
                 }
 
-                for func in base.functions.iter().filter_map(|function_no| {
-                    let f = &file.functions[*function_no];
-
-                    if f.has_body {
-                        Some(map_func(file, Some(&base.id.name), f))
-                    } else {
-                        None
+                for function_no in &base.functions {
+                    if file.functions[*function_no].has_body {
+                        base_functions.push(map_func(
+                            file,
+                            contract_no,
+                            *function_no,
+                            Some(&base.id.name),
+                        ));
                     }
-                }) {
-                    base_functions.push(func);
                 }
             }
 
+            let events = contract
+                .emits_events
+                .iter()
+                .map(|event_no| map_event(file, *event_no, Some(contract_no)))
+                .collect();
+
+            let errors = file
+                .errors
+                .iter()
+                .enumerate()
+                .filter(|(_, error_decl)| {
+                    error_decl
+                        .contract
+                        .is_some_and(|error_contract_no| bases.contains(&error_contract_no))
+                })
+                .map(|(error_no, _)| map_error(file, error_no))
+                .collect();
+
             top.contracts.push(Contract {
                 loc: contract.loc,
                 name: &contract.id.name,
                 ty: format!("{}", contract.ty),
+                inheritance: bases
+                    .iter()
+                    .rev()
+                    .filter(|base_no| **base_no != contract_no)
+                    .map(|base_no| file.contracts[*base_no].id.name.as_str())
+                    .collect(),
                 title: get_tag("title", &contract.tags),
                 notice: get_tag("notice", &contract.tags),
                 author: get_tag("author", &contract.tags),
@@ -386,98 +517,101 @@ pub fn generate_docs(outdir: &OsString, files: &[ast::Namespace], verbose: bool)
                 base_variables,
                 functions,
                 base_functions,
+                events,
+                errors,
             });
         }
     }
 
+    top
+}
+
+/// Generate documentation from the doccomments. This may be replaced with force-doc
+/// one day (once it exists)
+///
+/// The format is either `html`, which is a single page, `markdown`, which is a page per contract
+/// and an index, or `json`. A handlebars template can be given to replace the template of the
+/// html page or the markdown contract pages.
+pub fn generate_docs(
+    outdir: &OsString,
+    files: &[ast::Namespace],
+    format: &str,
+    template: Option<&str>,
+    verbose: bool,
+) -> Result<(), String> {
+    let top = collect(files);
+
     let mut reg = Handlebars::new();
 
     reg.set_strict_mode(true);
 
-    reg.register_template_string(
-        "soldoc",
-        r#"<!doctype html><head><title>soldoc</title><meta charset="utf-8"></head><body>
-<h2>Contracts</h2>
-{{#each contracts}}
-<h3>{{ty}} {{name}}</h3>
-{{#if title}}{{title}}<p>{{/if}}
-{{#if notice}}{{notice}}<p>{{/if}}
-{{#if dev}}Development note: {{dev}}<p>{{/if}}
-{{#if author}}Author: {{author}}<p>{{/if}}
-<h4>Functions</h4>
-{{#each functions}}
-<h5>{{ty}} {{name}}({{#each params}}{{ty}} {{name}}{{#unless @last}}, {{/unless}}{{/each}})</h5>
-{{visibility}} {{#if is_virtual}}virtual{{/if}} {{#if is_override}}override{{/if}}
-<p>
-{{#if title}}{{title}}<p>{{/if}}
-{{#if notice}}{{notice}}<p>{{/if}}
-{{#if dev}}Development note: {{dev}}<p>{{/if}}
-{{#if author}}Author: {{author}}<p>{{/if}}
-Parameters:<ul>{{#each params}}<li>{{ty}} {{name}} {{#if doc}}<p>{{doc}}{{/if}}{{/each}}</ul>
-Returns:<ul>{{#each returns}}<li>{{ty}} {{name}} {{#if doc}}<p>{{doc}}{{/if}}{{/each}}</ul>
-{{/each}}
-<h4>Variables</h4>
-{{#each variables}}
-<h5>{{#if constants}}constant{{/if}} {{ty}} {{visibility}} {{name}}</h5>
-{{#if title}}{{title}}<p>{{/if}}
-{{#if notice}}{{notice}}<p>{{/if}}
-{{#if dev}}Development note: {{dev}}<p>{{/if}}
-{{#if author}}Author: {{author}}<p>{{/if}}
-{{/each}}
-<h4>Inherited Variables</h4>
-{{#each base_variables}}
-<h5>{{#if constant}}constant{{/if}} {{ty}} {{visibility}} {{name}}</h5>
-Base contract: {{base_contract}}<p>
-{{#if title}}{{title}}<p>{{/if}}
-{{#if notice}}{{notice}}<p>{{/if}}
-{{#if dev}}Development note: {{dev}}<p>{{/if}}
-{{#if author}}Author: {{author}}<p>{{/if}}
-{{/each}}
-{{/each}}
-<h2>Events</h2>
-{{#each events}}
-<h3>{{#if contract}}{{contract}}.{{/if}}{{name}}</h3>
-{{#if title}}{{title}}<p>{{/if}}
-{{#if notice}}{{notice}}<p>{{/if}}
-{{#if dev}}Development note: {{dev}}<p>{{/if}}
-{{#if author}}Author: {{author}}<p>{{/if}}
-Fields:<dl>
-{{#each field}}
-<dt><code>{{ty}} {{#if indexed}}indexed{{/if}}</code> {{name}}</dt>
-{{#if doc}}<dd>{{doc}}</dd>{{/if}}
-{{/each}}</dl>
-Anonymous: {{#if anonymous}}true{{else}}false{{/if}}
-{{/each}}
-<h2>Structs</h2>
-{{#each structs}}
-<h3>{{#if contract}}{{contract}}.{{/if}}{{name}}</h3>
-{{#if title}}{{title}}<p>{{/if}}
-{{#if notice}}{{notice}}<p>{{/if}}
-{{#if dev}}Development note: {{dev}}<p>{{/if}}
-{{#if author}}Author: {{author}}<p>{{/if}}
-Fields:<dl>
-{{#each field}}
-<dt><code>{{ty}}</code> {{name}}</dt>
-{{#if doc}}<dd>{{doc}}</dd>{{/if}}
-{{/each}}</dl>
-{{/each}}
-<h2>Enums</h2>
-{{#each enums}}
-<h3>{{#if contract}}{{contract}}.{{/if}}{{name}}</h3>
-{{#if title}}{{title}}<p>{{/if}}
-{{#if notice}}{{notice}}<p>{{/if}}
-{{#if dev}}Development note: {{dev}}<p>{{/if}}
-{{#if author}}Author: {{author}}<p>{{/if}}
-Values: {{field}}
-{{/each}}
-</body></html>"#,
-    )
-    .expect("template should be good");
-
-    let res = reg.render("soldoc", &top).expect("template should render");
-
-    let filename = Path::new(outdir).join("soldoc.html");
+    match format {
+        "json" => {
+            let json = serde_json::to_string_pretty(&top).unwrap();
+
+            write_file(&Path::new(outdir).join("soldoc.json"), &json, verbose);
+        }
+        "markdown" => {
+            // markdown is not html, so nothing should be escaped
+            reg.register_escape_fn(handlebars::no_escape);
+
+            for (name, partial) in [
+                ("function", include_str!("templates/function.md.hbs")),
+                ("variable", include_str!("templates/variable.md.hbs")),
+                ("event", include_str!("templates/event.md.hbs")),
+                ("error", include_str!("templates/error.md.hbs")),
+            ] {
+                reg.register_partial(name, partial)
+                    .expect("template should be good");
+            }
+
+            reg.register_template_string("index", include_str!("templates/index.md.hbs"))
+                .expect("template should be good");
+
+            register_template(
+                &mut reg,
+                "contract",
+                template.unwrap_or(include_str!("templates/contract.md.hbs")),
+            )?;
+
+            for contract in &top.contracts {
+                let res = reg
+                    .render("contract", contract)
+                    .map_err(|err| err.to_string())?;
+
+                write_file(
+                    &Path::new(outdir).join(format!("{}.md", contract.name)),
+                    &res,
+                    verbose,
+                );
+            }
+
+            let res = reg.render("index", &top).expect("template should render");
+
+            write_file(&Path::new(outdir).join("index.md"), &res, verbose);
+        }
+        _ => {
+            register_template(
+                &mut reg,
+                "soldoc",
+                template.unwrap_or(include_str!("templates/soldoc.html.hbs")),
+            )?;
+
+            let res = reg.render("soldoc", &top).map_err(|err| err.to_string())?;
+
+            write_file(&Path::new(outdir).join("soldoc.html"), &res, verbose);
+        }
+    }
+
+    Ok(())
+}
+
+fn register_template(reg: &mut Handlebars, name: &str, template: &str) -> Result<(), String> {
+    reg.register_template_string(name, template)
+        .map_err(|err| format!("template: {err}"))
+}
 
+fn write_file(filename: &Path, contents: &str, verbose: bool) {
     if verbose {
         println!(
             "debug: writing documentation to '{}'",
@@ -485,8 +619,9 @@ Values: {{field}}
         );
     }
 
-    let mut file = File::create(filename).expect("cannot create soldoc.html");
+    let mut file = File::create(filename)
+        .unwrap_or_else(|_| panic!("cannot create {}", filename.to_string_lossy()));
 
-    file.write_all(res.as_bytes())
+    file.write_all(contents.as_bytes())
         .expect("should be able to write");
 }
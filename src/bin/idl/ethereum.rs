@@ -0,0 +1,463 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{docs, rename_keywords};
+use itertools::Itertools;
+use serde::Deserialize;
+use serde_json::{Map, Value as JsonValue};
+use solang_parser::lexer::is_keyword;
+use std::io::Write;
+use tiny_keccak::{Hasher, Keccak};
+
+/// An entry of an Ethereum ABI JSON file, see
+/// https://docs.soliditylang.org/en/latest/abi-spec.html#json
+#[derive(Deserialize)]
+pub struct AbiEntry {
+    #[serde(rename = "type", default = "default_entry_type")]
+    pub ty: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub inputs: Vec<AbiParam>,
+    #[serde(default)]
+    pub outputs: Vec<AbiParam>,
+    #[serde(rename = "stateMutability", default)]
+    pub state_mutability: Option<String>,
+    /// Older ABIs have `constant` and `payable` instead of `stateMutability`
+    #[serde(default)]
+    pub constant: bool,
+    #[serde(default)]
+    pub payable: bool,
+    #[serde(default)]
+    pub anonymous: bool,
+}
+
+#[derive(Deserialize)]
+pub struct AbiParam {
+    #[serde(default)]
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+    #[serde(rename = "internalType", default)]
+    pub internal_type: Option<String>,
+    #[serde(default)]
+    pub components: Vec<AbiParam>,
+    #[serde(default)]
+    pub indexed: bool,
+}
+
+fn default_entry_type() -> String {
+    "function".into()
+}
+
+/// The Ethereum ABI of a contract, with the NatSpec documentation if the file has it
+pub struct Abi {
+    pub name: String,
+    pub entries: Vec<AbiEntry>,
+    pub userdoc: Map<String, JsonValue>,
+    pub devdoc: Map<String, JsonValue>,
+}
+
+impl Abi {
+    /// Read the ABI from either a plain ABI array, or from a build artifact like those of
+    /// hardhat, truffle and foundry, which has it in the `abi` field.
+    pub fn from_json(name: String, json: JsonValue) -> Result<Abi, serde_json::Error> {
+        match json {
+            JsonValue::Object(mut artifact) => {
+                let name = match artifact.get("contractName") {
+                    Some(JsonValue::String(name)) => name.clone(),
+                    _ => name,
+                };
+
+                // foundry puts the documentation in the metadata
+                let output = artifact
+                    .get("metadata")
+                    .and_then(|metadata| metadata.get("output"))
+                    .cloned()
+                    .unwrap_or_default();
+
+                let natspec = |kind: &str| match artifact.get(kind).or(output.get(kind)) {
+                    Some(JsonValue::Object(doc)) => doc.clone(),
+                    _ => Map::new(),
+                };
+
+                let userdoc = natspec("userdoc");
+                let devdoc = natspec("devdoc");
+
+                Ok(Abi {
+                    name,
+                    entries: serde_json::from_value(artifact.remove("abi").unwrap_or_default())?,
+                    userdoc,
+                    devdoc,
+                })
+            }
+            json => Ok(Abi {
+                name,
+                entries: serde_json::from_value(json)?,
+                userdoc: Map::new(),
+                devdoc: Map::new(),
+            }),
+        }
+    }
+
+    /// The NatSpec documentation of a function, event or error, with the given signature
+    fn natspec(&self, kind: &str, signature: &str, params: &[AbiParam]) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        let user = self.userdoc.get(kind).and_then(|docs| docs.get(signature));
+        let dev = self.devdoc.get(kind).and_then(|docs| docs.get(signature));
+
+        // errors are documented as a list, as there can be more than one with the same
+        // signature
+        let (user, dev) = match (user, dev) {
+            (Some(JsonValue::Array(user)), dev) => (user.first(), dev),
+            (user, Some(JsonValue::Array(dev))) => (user, dev.first()),
+            (user, dev) => (user, dev),
+        };
+
+        if let Some(JsonValue::String(notice)) = user.and_then(|user| user.get("notice")) {
+            tag_lines(&mut lines, "notice", notice);
+        }
+
+        if let Some(dev) = dev {
+            if let Some(JsonValue::String(details)) = dev.get("details") {
+                tag_lines(&mut lines, "dev", details);
+            }
+
+            if let Some(JsonValue::Object(docs)) = dev.get("params") {
+                for param in params {
+                    if let Some(JsonValue::String(doc)) = docs.get(&param.name) {
+                        tag_lines(&mut lines, "param", &format!("{} {doc}", param.name));
+                    }
+                }
+            }
+
+            if let Some(JsonValue::Object(docs)) = dev.get("returns") {
+                for doc in docs.values() {
+                    if let JsonValue::String(doc) = doc {
+                        tag_lines(&mut lines, "return", doc);
+                    }
+                }
+            }
+        }
+
+        lines
+    }
+
+    /// The NatSpec documentation of the contract
+    fn contract_natspec(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        for (doc, tag, field) in [
+            (&self.devdoc, "title", "title"),
+            (&self.devdoc, "author", "author"),
+            (&self.userdoc, "notice", "notice"),
+            (&self.devdoc, "dev", "details"),
+        ] {
+            if let Some(JsonValue::String(text)) = doc.get(field) {
+                tag_lines(&mut lines, tag, text);
+            }
+        }
+
+        lines
+    }
+}
+
+/// Add a doc comment tag, which may span multiple lines
+fn tag_lines(lines: &mut Vec<String>, tag: &str, text: &str) {
+    for (no, line) in text.lines().enumerate() {
+        if no == 0 {
+            lines.push(format!("@{tag} {line}"));
+        } else {
+            lines.push(line.to_owned());
+        }
+    }
+}
+
+/// A struct used in the ABI, which is described by the components of a tuple
+struct StructDef<'a> {
+    name: String,
+    /// The type of the tuple, to tell different structs with the same name apart
+    canonical: String,
+    fields: &'a [AbiParam],
+}
+
+/// Generate a Solidity interface for an Ethereum ABI. Structs are named after the
+/// `internalType` of the tuple. Enums and contract types are not described by the ABI, so
+/// they are replaced by their ABI type.
+pub fn write_solidity(abi: &Abi, mut f: impl Write) -> Result<(), std::io::Error> {
+    let mut structs: Vec<StructDef> = Vec::new();
+
+    for entry in &abi.entries {
+        for param in entry.inputs.iter().chain(&entry.outputs) {
+            collect_structs(param, &mut structs);
+        }
+    }
+
+    let mut struct_names = structs
+        .iter()
+        .map(|def| (def.name.clone(), def.name.clone()))
+        .collect::<Vec<(String, String)>>();
+
+    rename_keywords(&mut struct_names);
+
+    for (no, def) in structs.iter().enumerate() {
+        let mut name_map = def
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(no, field)| (field.name.clone(), field_name(&field.name, no)))
+            .collect::<Vec<(String, String)>>();
+
+        rename_keywords(&mut name_map);
+
+        writeln!(f, "struct {} {{", struct_names[no].1)?;
+
+        for (no, field) in def.fields.iter().enumerate() {
+            writeln!(
+                f,
+                "\t{}\t{};",
+                abitype_to_solidity(field, &structs, &struct_names),
+                name_map[no].1
+            )?;
+        }
+
+        writeln!(f, "}}")?;
+    }
+
+    for entry in abi.entries.iter().filter(|entry| entry.ty == "event") {
+        let signature = signature(entry);
+
+        docs(&mut f, 0, &abi.natspec("events", &signature, &entry.inputs))?;
+
+        // Solang requires names for indexed fields
+        let params = params(&entry.inputs, &structs, &struct_names, true);
+
+        writeln!(
+            f,
+            "event {}({}){};",
+            escape(&entry.name),
+            params.join(", "),
+            if entry.anonymous { " anonymous" } else { "" }
+        )?;
+    }
+
+    for entry in abi.entries.iter().filter(|entry| entry.ty == "error") {
+        let signature = signature(entry);
+
+        docs(&mut f, 0, &abi.natspec("errors", &signature, &entry.inputs))?;
+
+        writeln!(
+            f,
+            "error {}({});",
+            escape(&entry.name),
+            params(&entry.inputs, &structs, &struct_names, false).join(", ")
+        )?;
+    }
+
+    docs(&mut f, 0, &abi.contract_natspec())?;
+
+    writeln!(f, "interface {} {{", abi.name)?;
+
+    for entry in abi.entries.iter().filter(|entry| entry.ty == "function") {
+        let signature = signature(entry);
+
+        docs(
+            &mut f,
+            1,
+            &abi.natspec("methods", &signature, &entry.inputs),
+        )?;
+
+        // a function which is renamed keeps its selector
+        if is_keyword(&entry.name) {
+            let mut hasher = Keccak::v256();
+            let mut hash = [0u8; 32];
+            hasher.update(signature.as_bytes());
+            hasher.finalize(&mut hash);
+
+            writeln!(
+                f,
+                "\t@selector([{}])",
+                hash[..4].iter().map(|v| format!("{v:#04x}")).join(",")
+            )?;
+        }
+
+        let mutability = match entry.state_mutability.as_deref() {
+            Some("view") => " view",
+            Some("pure") => " pure",
+            Some("payable") => " payable",
+            Some(_) => "",
+            None if entry.constant => " view",
+            None if entry.payable => " payable",
+            None => "",
+        };
+
+        write!(
+            f,
+            "\tfunction {}({}) external{mutability}",
+            escape(&entry.name),
+            params(&entry.inputs, &structs, &struct_names, false).join(", ")
+        )?;
+
+        if entry.outputs.is_empty() {
+            writeln!(f, ";")?;
+        } else {
+            writeln!(
+                f,
+                " returns ({});",
+                params(&entry.outputs, &structs, &struct_names, false).join(", ")
+            )?;
+        }
+    }
+
+    writeln!(f, "}}")?;
+
+    Ok(())
+}
+
+/// Find the structs used by a parameter, with the structs of their fields before them
+fn collect_structs<'a>(param: &'a AbiParam, structs: &mut Vec<StructDef<'a>>) {
+    if !param.ty.starts_with("tuple") {
+        return;
+    }
+
+    for field in &param.components {
+        collect_structs(field, structs);
+    }
+
+    let canonical = tuple_type(param);
+
+    if structs.iter().any(|def| def.canonical == canonical) {
+        return;
+    }
+
+    let base = param
+        .internal_type
+        .as_deref()
+        .and_then(|ty| ty.strip_prefix("struct "))
+        .map(|ty| {
+            let ty = ty.split('[').next().unwrap();
+            ty.rsplit('.').next().unwrap().to_owned()
+        })
+        .unwrap_or_else(|| format!("Struct{}", structs.len()));
+
+    let mut name = base.clone();
+    let mut no = 0;
+
+    while structs.iter().any(|def| def.name == name) {
+        no += 1;
+        name = format!("{base}_{no}");
+    }
+
+    structs.push(StructDef {
+        name,
+        canonical,
+        fields: &param.components,
+    });
+}
+
+/// The type of a parameter as used in a signature, e.g. `(uint256,bool)[]`
+fn canonical_type(param: &AbiParam) -> String {
+    if let Some(dims) = param.ty.strip_prefix("tuple") {
+        format!("{}{dims}", tuple_type(param))
+    } else {
+        param.ty.clone()
+    }
+}
+
+/// The type of a tuple without its array dimensions, e.g. `(uint256,bool)`
+fn tuple_type(param: &AbiParam) -> String {
+    format!(
+        "({})",
+        param.components.iter().map(canonical_type).join(",")
+    )
+}
+
+/// The signature of a function, event or error, e.g. `transfer(address,uint256)`
+fn signature(entry: &AbiEntry) -> String {
+    format!(
+        "{}({})",
+        entry.name,
+        entry.inputs.iter().map(canonical_type).join(",")
+    )
+}
+
+fn abitype_to_solidity(
+    param: &AbiParam,
+    structs: &[StructDef],
+    struct_names: &[(String, String)],
+) -> String {
+    if let Some(dims) = param.ty.strip_prefix("tuple") {
+        let canonical = tuple_type(param);
+
+        let no = structs
+            .iter()
+            .position(|def| def.canonical == canonical)
+            .unwrap();
+
+        format!("{}{dims}", struct_names[no].1)
+    } else {
+        param.ty.clone()
+    }
+}
+
+/// Format the parameters. Names which are Solidity keywords are escaped, and unnamed
+/// parameters are given a name if required.
+fn params(
+    params: &[AbiParam],
+    structs: &[StructDef],
+    struct_names: &[(String, String)],
+    named: bool,
+) -> Vec<String> {
+    let mut name_map = params
+        .iter()
+        .enumerate()
+        .map(|(no, param)| {
+            let name = if named {
+                field_name(&param.name, no)
+            } else {
+                param.name.clone()
+            };
+
+            (param.name.clone(), name)
+        })
+        .collect::<Vec<(String, String)>>();
+
+    rename_keywords(&mut name_map);
+
+    params
+        .iter()
+        .enumerate()
+        .map(|(no, param)| {
+            let mut decl = abitype_to_solidity(param, structs, struct_names);
+
+            if param.indexed {
+                decl.push_str(" indexed");
+            }
+
+            if !name_map[no].1.is_empty() {
+                decl.push(' ');
+                decl.push_str(&name_map[no].1);
+            }
+
+            decl
+        })
+        .collect()
+}
+
+fn field_name(name: &str, no: usize) -> String {
+    if name.is_empty() {
+        format!("field_{no}")
+    } else {
+        name.to_owned()
+    }
+}
+
+/// Names of functions, events and errors cannot be renamed without changing their selector,
+/// but Solidity keywords must be escaped
+fn escape(name: &str) -> String {
+    if is_keyword(name) {
+        format!("_{name}")
+    } else {
+        name.to_owned()
+    }
+}
@@ -6,11 +6,23 @@ use itertools::Itertools;
 use serde_json::Value as JsonValue;
 use solang::abi::anchor::function_discriminator;
 use solang_parser::lexer::is_keyword;
-use std::{ffi::OsStr, fs::File, io::Write, path::PathBuf, process::exit};
-
-/// This subcommand generates a Solidity interface file from Anchor IDL file.
-/// The IDL file is json and lists all the instructions, events, structs, enums,
-/// etc. We have to avoid the numerous Solidity keywords, and retain any documentation.
+use std::{
+    ffi::OsStr,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    process::exit,
+};
+
+mod ethereum;
+mod polkadot;
+#[cfg(test)]
+mod tests;
+
+/// This subcommand generates a Solidity interface file from Anchor IDL file, Ethereum ABI
+/// json or Polkadot contract metadata. These files are json and list all the instructions,
+/// events, structs, enums, etc. We have to avoid the numerous Solidity keywords, and retain
+/// any documentation.
 pub fn idl(idl_args: &IdlCommand) {
     for file in &idl_args.input {
         idl_file(file, &idl_args.output);
@@ -18,23 +30,9 @@ pub fn idl(idl_args: &IdlCommand) {
 }
 
 fn idl_file(file: &OsStr, output: &Option<PathBuf>) {
-    let f = match File::open(file) {
-        Ok(s) => s,
-        Err(e) => {
-            eprintln!("{}: error: {}", file.to_string_lossy(), e);
-            exit(1);
-        }
-    };
+    let interface = read_interface(file);
 
-    let idl: Idl = match serde_json::from_reader(f) {
-        Ok(idl) => idl,
-        Err(e) => {
-            eprintln!("{}: error: {}", file.to_string_lossy(), e);
-            exit(1);
-        }
-    };
-
-    let filename = format!("{}.sol", idl.name);
+    let filename = format!("{}.sol", interface.name());
 
     let path = if let Some(base) = output {
         base.join(filename)
@@ -56,13 +54,115 @@ fn idl_file(file: &OsStr, output: &Option<PathBuf>) {
         }
     };
 
-    if let Err(e) = write_solidity(&idl, f) {
+    if let Err(e) = interface.write_solidity(f) {
         eprintln!("{}: error: {}", path.display(), e);
         exit(1);
     }
 }
 
-fn write_solidity(idl: &Idl, mut f: File) -> Result<(), std::io::Error> {
+/// Generate the Solidity interface for an Anchor IDL, Ethereum ABI or Polkadot metadata file,
+/// so other subcommands can resolve it like a Solidity source file
+pub fn solidity_interface(file: &OsStr) -> String {
+    let mut out = Vec::new();
+
+    // writing to a vector cannot fail
+    read_interface(file).write_solidity(&mut out).unwrap();
+
+    String::from_utf8(out).unwrap()
+}
+
+fn read_interface(file: &OsStr) -> Interface {
+    let f = match File::open(file) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("{}: error: {}", file.to_string_lossy(), e);
+            exit(1);
+        }
+    };
+
+    let json: JsonValue = match serde_json::from_reader(f) {
+        Ok(json) => json,
+        Err(e) => {
+            eprintln!("{}: error: {}", file.to_string_lossy(), e);
+            exit(1);
+        }
+    };
+
+    match Interface::from_json(file, json) {
+        Ok(interface) => interface,
+        Err(e) => {
+            eprintln!("{}: error: {}", file.to_string_lossy(), e);
+            exit(1);
+        }
+    }
+}
+
+/// The formats which we can generate a Solidity interface for
+enum Interface {
+    Anchor(Box<Idl>),
+    Ethereum(ethereum::Abi),
+    Polkadot(Box<polkadot::Metadata>),
+}
+
+impl Interface {
+    /// Detect the format of the json. Ethereum ABI is either an array or a compiler artifact
+    /// with an `abi` field, and Polkadot metadata has the `spec` and `types` fields.
+    fn from_json(file: &OsStr, json: JsonValue) -> Result<Interface, serde_json::Error> {
+        match &json {
+            JsonValue::Array(_) => {
+                ethereum::Abi::from_json(contract_name(file), json).map(Interface::Ethereum)
+            }
+            JsonValue::Object(obj) if obj.get("abi").is_some_and(JsonValue::is_array) => {
+                ethereum::Abi::from_json(contract_name(file), json).map(Interface::Ethereum)
+            }
+            JsonValue::Object(obj) if obj.contains_key("spec") && obj.contains_key("types") => {
+                serde_json::from_value(json).map(|metadata| Interface::Polkadot(Box::new(metadata)))
+            }
+            _ => serde_json::from_value(json).map(|idl| Interface::Anchor(Box::new(idl))),
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            Interface::Anchor(idl) => &idl.name,
+            Interface::Ethereum(abi) => &abi.name,
+            Interface::Polkadot(metadata) => &metadata.contract.name,
+        }
+    }
+
+    fn write_solidity(&self, f: impl Write) -> Result<(), std::io::Error> {
+        match self {
+            Interface::Anchor(idl) => write_solidity(idl, f),
+            Interface::Ethereum(abi) => ethereum::write_solidity(abi, f),
+            Interface::Polkadot(metadata) => polkadot::write_solidity(metadata, f),
+        }
+    }
+}
+
+/// Ethereum ABI files do not always include the name of the contract, so use the file name
+/// up to the first dot, e.g. `ERC20.abi.json` is `ERC20`
+fn contract_name(file: &OsStr) -> String {
+    let name = Path::new(file)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let name: String = name
+        .split('.')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    match name.chars().next() {
+        None => "Contract".into(),
+        Some(c) if c.is_ascii_digit() => format!("_{name}"),
+        Some(_) => name,
+    }
+}
+
+fn write_solidity(idl: &Idl, mut f: impl Write) -> Result<(), std::io::Error> {
     let mut ty_names = idl
         .types
         .iter()
@@ -93,7 +193,7 @@ fn write_solidity(idl: &Idl, mut f: File) -> Result<(), std::io::Error> {
 
             rename_keywords(&mut name_map);
 
-            docs(&mut f, 0, &ty_def.docs)?;
+            docs(&mut f, 0, ty_def.docs.as_deref().unwrap_or_default())?;
 
             let name = &ty_names.iter().find(|e| *e.0 == ty_def.name).unwrap().1;
 
@@ -129,14 +229,14 @@ fn write_solidity(idl: &Idl, mut f: File) -> Result<(), std::io::Error> {
 
                 rename_keywords(&mut name_map);
 
-                docs(&mut f, 0, &ty_def.docs)?;
+                docs(&mut f, 0, ty_def.docs.as_deref().unwrap_or_default())?;
 
                 let name = &ty_names.iter().find(|e| *e.0 == ty_def.name).unwrap().1;
 
                 writeln!(f, "struct {name} {{")?;
 
                 for (no, field) in fields.iter().enumerate() {
-                    docs(&mut f, 1, &field.docs)?;
+                    docs(&mut f, 1, field.docs.as_deref().unwrap_or_default())?;
 
                     writeln!(
                         f,
@@ -202,7 +302,7 @@ fn write_solidity(idl: &Idl, mut f: File) -> Result<(), std::io::Error> {
         }
     }
 
-    docs(&mut f, 0, &idl.docs)?;
+    docs(&mut f, 0, idl.docs.as_deref().unwrap_or_default())?;
 
     if let Some(program_id) = program_id(idl) {
         writeln!(f, "@program_id(\"{}\")", program_id)?;
@@ -227,7 +327,7 @@ fn write_solidity(idl: &Idl, mut f: File) -> Result<(), std::io::Error> {
 }
 
 fn instruction(
-    f: &mut File,
+    f: &mut impl Write,
     instr: &IdlInstruction,
     instruction_names: &[(String, String)],
     ty_names: &[(String, String)],
@@ -245,7 +345,7 @@ fn instruction(
     }
 
     if badtys.is_empty() {
-        docs(f, 1, &instr.docs)?;
+        docs(f, 1, instr.docs.as_deref().unwrap_or_default())?;
 
         let name = &instruction_names
             .iter()
@@ -312,12 +412,14 @@ fn mutable_account_exists(accounts: &[IdlAccountItem]) -> bool {
     })
 }
 
-fn docs(f: &mut File, indent: usize, docs: &Option<Vec<String>>) -> std::io::Result<()> {
-    if let Some(docs) = docs {
-        for doc in docs {
-            for _ in 0..indent {
-                write!(f, "\t")?;
-            }
+fn docs(f: &mut impl Write, indent: usize, docs: &[String]) -> std::io::Result<()> {
+    for doc in docs {
+        for _ in 0..indent {
+            write!(f, "\t")?;
+        }
+        if doc.is_empty() {
+            writeln!(f, "///")?;
+        } else {
             writeln!(f, "/// {doc}")?;
         }
     }
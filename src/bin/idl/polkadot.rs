@@ -0,0 +1,602 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{docs, rename_keywords};
+use itertools::Itertools;
+use serde::Deserialize;
+use std::io::Write;
+
+/// The parts of ink! contract metadata (version 4 or later) which describe the interface of
+/// the contract, see https://use.ink/basics/metadata
+#[derive(Deserialize)]
+pub struct Metadata {
+    pub contract: ContractInfo,
+    pub spec: Spec,
+    pub types: Vec<PortableType>,
+}
+
+#[derive(Deserialize)]
+pub struct ContractInfo {
+    pub name: String,
+}
+
+#[derive(Deserialize)]
+pub struct Spec {
+    #[serde(default)]
+    pub docs: Vec<String>,
+    #[serde(default)]
+    pub messages: Vec<Message>,
+    #[serde(default)]
+    pub events: Vec<Event>,
+    pub lang_error: Option<TypeRef>,
+}
+
+#[derive(Deserialize)]
+pub struct Message {
+    pub label: String,
+    pub selector: String,
+    #[serde(default)]
+    pub args: Vec<Arg>,
+    #[serde(rename = "returnType")]
+    pub return_type: Option<TypeRef>,
+    #[serde(default)]
+    pub mutates: bool,
+    #[serde(default)]
+    pub payable: bool,
+    #[serde(default)]
+    pub docs: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct Event {
+    pub label: String,
+    #[serde(default)]
+    pub args: Vec<Arg>,
+    #[serde(default)]
+    pub docs: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct Arg {
+    pub label: String,
+    #[serde(rename = "type")]
+    pub ty: TypeRef,
+    #[serde(default)]
+    pub indexed: bool,
+}
+
+#[derive(Deserialize)]
+pub struct TypeRef {
+    #[serde(rename = "type")]
+    pub id: usize,
+}
+
+#[derive(Deserialize)]
+pub struct PortableType {
+    pub id: usize,
+    #[serde(rename = "type")]
+    pub ty: TypeInfo,
+}
+
+#[derive(Deserialize)]
+pub struct TypeInfo {
+    #[serde(default)]
+    pub path: Vec<String>,
+    pub def: TypeDef,
+    #[serde(default)]
+    pub docs: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TypeDef {
+    Composite {
+        #[serde(default)]
+        fields: Vec<Field>,
+    },
+    Variant {
+        #[serde(default)]
+        variants: Vec<Variant>,
+    },
+    Sequence {
+        #[serde(rename = "type")]
+        id: usize,
+    },
+    Array {
+        len: u32,
+        #[serde(rename = "type")]
+        id: usize,
+    },
+    Tuple(Vec<usize>),
+    Primitive(String),
+    Compact {
+        #[serde(rename = "type")]
+        id: usize,
+    },
+    BitSequence(serde_json::Value),
+}
+
+#[derive(Deserialize)]
+pub struct Field {
+    pub name: Option<String>,
+    #[serde(rename = "type")]
+    pub id: usize,
+    #[serde(default)]
+    pub docs: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct Variant {
+    pub name: String,
+    #[serde(default)]
+    pub fields: Vec<Field>,
+    #[serde(default)]
+    pub docs: Vec<String>,
+}
+
+/// The Solidity declarations of the types in the metadata, by type id
+struct Types<'a> {
+    metadata: &'a Metadata,
+    /// The name of each struct or enum, after resolving conflicts and keywords
+    names: Vec<(usize, String)>,
+}
+
+impl<'a> Types<'a> {
+    fn new(metadata: &'a Metadata) -> Self {
+        let mut names = Vec::new();
+
+        for ty in &metadata.types {
+            if let Some(name) = user_type_name(&ty.ty) {
+                let mut unique = name.to_owned();
+                let mut no = 0;
+
+                while names.iter().any(|(_, name)| *name == unique) {
+                    no += 1;
+                    unique = format!("{name}_{no}");
+                }
+
+                names.push((ty.id, unique));
+            }
+        }
+
+        let mut name_map = names
+            .iter()
+            .map(|(_, name)| (name.clone(), name.clone()))
+            .collect::<Vec<(String, String)>>();
+
+        rename_keywords(&mut name_map);
+
+        for (no, (_, name)) in names.iter_mut().enumerate() {
+            *name = name_map[no].1.clone();
+        }
+
+        Types { metadata, names }
+    }
+
+    fn get(&self, id: usize) -> Option<&'a TypeInfo> {
+        self.metadata
+            .types
+            .iter()
+            .find(|ty| ty.id == id)
+            .map(|ty| &ty.ty)
+    }
+
+    fn name(&self, id: usize) -> Option<&str> {
+        self.names
+            .iter()
+            .find(|(type_id, _)| *type_id == id)
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// The Solidity type for a type id, or a description of the type if it cannot be
+    /// represented in Solidity
+    fn solidity(&self, id: usize) -> Result<String, String> {
+        let Some(ty) = self.get(id) else {
+            return Err(format!("type {id} not found"));
+        };
+
+        match ty.path.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+            [.., "AccountId"] => return Ok("address".into()),
+            [.., "Hash"] => return Ok("bytes32".into()),
+            _ => (),
+        }
+
+        match &ty.def {
+            TypeDef::Primitive(primitive) => match primitive.as_str() {
+                "bool" => Ok("bool".into()),
+                "str" => Ok("string".into()),
+                "u8" | "u16" | "u32" | "u64" | "u128" | "u256" => {
+                    Ok(format!("uint{}", &primitive[1..]))
+                }
+                "i8" | "i16" | "i32" | "i64" | "i128" | "i256" => {
+                    Ok(format!("int{}", &primitive[1..]))
+                }
+                _ => Err(primitive.clone()),
+            },
+            TypeDef::Composite { .. } | TypeDef::Variant { .. } => match self.name(id) {
+                Some(name) => Ok(name.into()),
+                None => Err(ty.path.join("::")),
+            },
+            TypeDef::Sequence { id } if self.is_u8(*id) => Ok("bytes".into()),
+            TypeDef::Sequence { id } => match self.solidity(*id) {
+                Ok(ty) => Ok(format!("{ty}[]")),
+                Err(ty) => Err(format!("Vec<{ty}>")),
+            },
+            TypeDef::Array { len, id } if self.is_u8(*id) && (1..=32).contains(len) => {
+                Ok(format!("bytes{len}"))
+            }
+            TypeDef::Array { len, id } => match self.solidity(*id) {
+                Ok(ty) => Ok(format!("{ty}[{len}]")),
+                Err(ty) => Err(format!("[{ty}; {len}]")),
+            },
+            TypeDef::Tuple(fields) => Err(format!(
+                "({})",
+                fields
+                    .iter()
+                    .map(|id| self.solidity(*id).unwrap_or_else(|ty| ty))
+                    .join(", ")
+            )),
+            TypeDef::Compact { id } => Err(format!(
+                "Compact<{}>",
+                self.solidity(*id).unwrap_or_else(|ty| ty)
+            )),
+            TypeDef::BitSequence(_) => Err("BitVec".into()),
+        }
+    }
+
+    fn is_u8(&self, id: usize) -> bool {
+        matches!(self.get(id), Some(TypeInfo { def: TypeDef::Primitive(primitive), .. }) if primitive == "u8")
+    }
+
+    /// The return values of a message. Messages of ink! contracts return a result, which is
+    /// only an error if the message could not be dispatched. Solang returns multiple values
+    /// as a tuple.
+    fn returns(&self, id: usize) -> Result<Vec<String>, String> {
+        let Some(ty) = self.get(id) else {
+            return Err(format!("type {id} not found"));
+        };
+
+        match &ty.def {
+            TypeDef::Variant { variants } if ty.path == ["Result"] => {
+                let is_lang_error = |variant: &Variant| {
+                    variant.name == "Err"
+                        && variant.fields.iter().all(|field| {
+                            self.get(field.id)
+                                .is_some_and(|ty| ty.path.last().is_some_and(|n| n == "LangError"))
+                        })
+                };
+
+                match &variants[..] {
+                    [ok, err] if ok.name == "Ok" && ok.fields.len() == 1 && is_lang_error(err) => {
+                        self.returns(ok.fields[0].id)
+                    }
+                    _ => Err(ty.path.join("::")),
+                }
+            }
+            TypeDef::Tuple(fields) => fields.iter().map(|id| self.solidity(*id)).collect(),
+            _ => self.solidity(id).map(|ty| vec![ty]),
+        }
+    }
+}
+
+/// Structs and enums are declared in Solidity, except for the types of the ink! environment
+/// and generic types like `Option` and `Result`
+fn user_type_name(ty: &TypeInfo) -> Option<&str> {
+    let name = ty.path.last()?;
+
+    match (&ty.def, name.as_str()) {
+        (_, "AccountId" | "Hash" | "Option" | "Result" | "LangError") => None,
+        (TypeDef::Composite { fields }, _) if fields.iter().all(|field| field.name.is_some()) => {
+            Some(name)
+        }
+        (TypeDef::Variant { variants }, _)
+            if variants.iter().all(|variant| variant.fields.is_empty()) =>
+        {
+            Some(name)
+        }
+        _ => None,
+    }
+}
+
+/// Doc comments in ink! metadata keep the space after `///`
+fn trim_docs(docs: &[String]) -> Vec<String> {
+    let docs: Vec<String> = docs
+        .iter()
+        .flat_map(|doc| doc.lines())
+        .map(|line| line.strip_prefix(' ').unwrap_or(line).to_owned())
+        .collect();
+
+    if docs.iter().all(|line| line.trim().is_empty()) {
+        Vec::new()
+    } else {
+        docs
+    }
+}
+
+/// Generate a Solidity interface for the ink! metadata of a Polkadot contract. The messages
+/// keep the selector from the metadata. Constructors are not part of the interface.
+pub fn write_solidity(metadata: &Metadata, mut f: impl Write) -> Result<(), std::io::Error> {
+    let types = Types::new(metadata);
+
+    for (id, name) in &types.names {
+        let ty = types.get(*id).unwrap();
+
+        if let TypeDef::Variant { variants } = &ty.def {
+            let mut name_map = variants
+                .iter()
+                .map(|variant| (variant.name.clone(), variant.name.clone()))
+                .collect::<Vec<(String, String)>>();
+
+            rename_keywords(&mut name_map);
+
+            docs(&mut f, 0, &trim_docs(&ty.docs))?;
+
+            writeln!(f, "enum {name} {{")?;
+            writeln!(
+                f,
+                "{}",
+                name_map
+                    .iter()
+                    .map(|(_, name)| format!("\t{name}"))
+                    .join(",\n")
+            )?;
+            writeln!(f, "}}")?;
+        }
+    }
+
+    for (id, name) in &types.names {
+        let ty = types.get(*id).unwrap();
+
+        if let TypeDef::Composite { fields } = &ty.def {
+            let badtys: Vec<String> = fields
+                .iter()
+                .filter_map(|field| types.solidity(field.id).err())
+                .collect();
+
+            if !badtys.is_empty() {
+                eprintln!(
+                    "struct {name} has fields of type {} which is not supported on Solidity",
+                    badtys.join(", ")
+                );
+                continue;
+            }
+
+            let mut name_map = fields
+                .iter()
+                .map(|field| {
+                    let name = field.name.clone().unwrap_or_default();
+                    (name.clone(), name)
+                })
+                .collect::<Vec<(String, String)>>();
+
+            rename_keywords(&mut name_map);
+
+            docs(&mut f, 0, &trim_docs(&ty.docs))?;
+
+            writeln!(f, "struct {name} {{")?;
+
+            for (no, field) in fields.iter().enumerate() {
+                docs(&mut f, 1, &trim_docs(&field.docs))?;
+
+                writeln!(
+                    f,
+                    "\t{}\t{};",
+                    types.solidity(field.id).unwrap(),
+                    name_map[no].1
+                )?;
+            }
+
+            writeln!(f, "}}")?;
+        }
+    }
+
+    for event in &metadata.spec.events {
+        let badtys: Vec<String> = event
+            .args
+            .iter()
+            .filter_map(|arg| types.solidity(arg.ty.id).err())
+            .collect();
+
+        if !badtys.is_empty() {
+            eprintln!(
+                "event {} has fields of type {} which is not supported on Solidity",
+                event.label,
+                badtys.join(", ")
+            );
+            continue;
+        }
+
+        let mut name_map = event
+            .args
+            .iter()
+            .map(|arg| (arg.label.clone(), arg.label.clone()))
+            .collect::<Vec<(String, String)>>();
+
+        rename_keywords(&mut name_map);
+
+        docs(&mut f, 0, &trim_docs(&event.docs))?;
+
+        writeln!(
+            f,
+            "event {}({});",
+            event.label,
+            event
+                .args
+                .iter()
+                .enumerate()
+                .map(|(no, arg)| format!(
+                    "{}{} {}",
+                    types.solidity(arg.ty.id).unwrap(),
+                    if arg.indexed { " indexed" } else { "" },
+                    name_map[no].1
+                ))
+                .join(", ")
+        )?;
+    }
+
+    errors(metadata, &types, &mut f)?;
+
+    docs(&mut f, 0, &trim_docs(&metadata.spec.docs))?;
+
+    writeln!(f, "interface {} {{", metadata.contract.name)?;
+
+    // messages of traits are prefixed with the name of the trait, like `PSP22::transfer`
+    let mut message_names = metadata
+        .spec
+        .messages
+        .iter()
+        .map(|message| {
+            let name = message.label.replace("::", "_");
+            (name.clone(), name)
+        })
+        .collect::<Vec<(String, String)>>();
+
+    rename_keywords(&mut message_names);
+
+    for (no, message) in metadata.spec.messages.iter().enumerate() {
+        let mut badtys: Vec<String> = message
+            .args
+            .iter()
+            .filter_map(|arg| types.solidity(arg.ty.id).err())
+            .collect();
+
+        let returns = match &message.return_type {
+            Some(ty) => types.returns(ty.id).unwrap_or_else(|ty| {
+                badtys.push(ty);
+                Vec::new()
+            }),
+            None => Vec::new(),
+        };
+
+        if !badtys.is_empty() {
+            eprintln!(
+                "message {} has arguments of type {} which is not supported on Solidity",
+                message.label,
+                badtys.join(", ")
+            );
+            continue;
+        }
+
+        let selector = message.selector.trim_start_matches("0x");
+
+        if selector.len() != 8 || !selector.chars().all(|c| c.is_ascii_hexdigit()) {
+            eprintln!(
+                "message {} has invalid selector '{}'",
+                message.label, message.selector
+            );
+            continue;
+        }
+
+        let mut arg_names = message
+            .args
+            .iter()
+            .map(|arg| (arg.label.clone(), arg.label.clone()))
+            .collect::<Vec<(String, String)>>();
+
+        rename_keywords(&mut arg_names);
+
+        docs(&mut f, 1, &trim_docs(&message.docs))?;
+
+        writeln!(
+            f,
+            "\t@selector([{}])",
+            (0..4)
+                .map(|i| format!("0x{}", &selector[i * 2..i * 2 + 2]))
+                .join(",")
+        )?;
+
+        write!(
+            f,
+            "\tfunction {}({}) external{}",
+            message_names[no].1,
+            message
+                .args
+                .iter()
+                .enumerate()
+                .map(|(no, arg)| format!(
+                    "{} {}",
+                    types.solidity(arg.ty.id).unwrap(),
+                    arg_names[no].1
+                ))
+                .join(", "),
+            if message.payable {
+                " payable"
+            } else if !message.mutates {
+                " view"
+            } else {
+                ""
+            }
+        )?;
+
+        if returns.is_empty() {
+            writeln!(f, ";")?;
+        } else {
+            writeln!(f, " returns ({});", returns.join(", "))?;
+        }
+    }
+
+    writeln!(f, "}}")?;
+
+    Ok(())
+}
+
+/// Solang describes the custom errors of a contract in the `lang_error` type. The
+/// built-in `Error(string)` and `Panic(uint256)` are not declared.
+fn errors(metadata: &Metadata, types: &Types, f: &mut impl Write) -> std::io::Result<()> {
+    let Some(lang_error) = metadata
+        .spec
+        .lang_error
+        .as_ref()
+        .and_then(|ty| types.get(ty.id))
+    else {
+        return Ok(());
+    };
+
+    let TypeDef::Variant { variants } = &lang_error.def else {
+        return Ok(());
+    };
+
+    if lang_error.path != ["SolidityError"] {
+        return Ok(());
+    }
+
+    for variant in variants {
+        if matches!(variant.name.as_str(), "Error" | "Panic") {
+            continue;
+        }
+
+        // each error is a variant with a struct of its fields
+        let fields = match &variant.fields[..] {
+            [field] => match types.get(field.id) {
+                Some(TypeInfo {
+                    def: TypeDef::Composite { fields },
+                    ..
+                }) => fields,
+                _ => continue,
+            },
+            _ => continue,
+        };
+
+        let params: Result<Vec<String>, String> = fields
+            .iter()
+            .map(|field| {
+                types.solidity(field.id).map(|ty| match &field.name {
+                    Some(name) => format!("{ty} {name}"),
+                    None => ty,
+                })
+            })
+            .collect();
+
+        match params {
+            Ok(params) => {
+                docs(f, 0, &trim_docs(&variant.docs))?;
+                writeln!(f, "error {}({});", variant.name, params.join(", "))?;
+            }
+            Err(ty) => eprintln!(
+                "error {} has fields of type {ty} which is not supported on Solidity",
+                variant.name
+            ),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,235 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{contract_name, Interface};
+use serde_json::json;
+use std::ffi::OsStr;
+
+fn solidity(file: &str, json: serde_json::Value) -> (String, String) {
+    let interface = Interface::from_json(OsStr::new(file), json).unwrap();
+
+    let mut out = Vec::new();
+
+    interface.write_solidity(&mut out).unwrap();
+
+    (interface.name().to_owned(), String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn ethereum_abi() {
+    let abi = json!([
+        { "type": "constructor", "inputs": [{ "name": "supply", "type": "uint256" }] },
+        {
+            "type": "function",
+            "name": "transfer",
+            "inputs": [
+                { "name": "to", "type": "address" },
+                { "name": "amount", "type": "uint256" }
+            ],
+            "outputs": [{ "name": "", "type": "bool" }],
+            "stateMutability": "nonpayable"
+        },
+        {
+            "type": "function",
+            "name": "delete",
+            "inputs": [{
+                "name": "positions",
+                "type": "tuple[]",
+                "internalType": "struct Token.Position[]",
+                "components": [
+                    { "name": "x", "type": "int32" },
+                    { "name": "data", "type": "bytes" }
+                ]
+            }],
+            "outputs": [
+                { "name": "kind", "type": "uint8", "internalType": "enum Token.Kind" }
+            ],
+            "stateMutability": "pure"
+        },
+        { "type": "function", "name": "deposit", "inputs": [], "outputs": [], "stateMutability": "payable" },
+        {
+            "type": "event",
+            "name": "Transfer",
+            "inputs": [
+                { "name": "from", "type": "address", "indexed": true },
+                { "name": "", "type": "uint256", "indexed": false }
+            ],
+            "anonymous": false
+        },
+        { "type": "error", "name": "Insufficient", "inputs": [{ "name": "needed", "type": "uint256" }] },
+        { "type": "receive", "stateMutability": "payable" }
+    ]);
+
+    let (name, solidity) = solidity("Token.abi.json", abi);
+
+    assert_eq!(name, "Token");
+    assert_eq!(
+        solidity,
+        r#"struct Position {
+	int32	x;
+	bytes	data;
+}
+event Transfer(address indexed from, uint256 field_1);
+error Insufficient(uint256 needed);
+interface Token {
+	function transfer(address to, uint256 amount) external returns (bool);
+	@selector([0xa0,0xa8,0xca,0xf6])
+	function _delete(Position[] positions) external pure returns (uint8 kind);
+	function deposit() external payable;
+}
+"#
+    );
+}
+
+#[test]
+fn ethereum_artifact() {
+    let artifact = json!({
+        "contractName": "Counter",
+        "abi": [{
+            "type": "function",
+            "name": "add",
+            "inputs": [{ "name": "value", "type": "uint64" }],
+            "outputs": [],
+            "stateMutability": "nonpayable"
+        }],
+        "userdoc": {
+            "notice": "Counts things",
+            "methods": { "add(uint64)": { "notice": "Add to the counter" } }
+        },
+        "devdoc": {
+            "title": "A counter",
+            "methods": { "add(uint64)": { "params": { "value": "the amount to add" } } }
+        }
+    });
+
+    let (name, solidity) = solidity("out/Counter.json", artifact);
+
+    assert_eq!(name, "Counter");
+    assert_eq!(
+        solidity,
+        r#"/// @title A counter
+/// @notice Counts things
+interface Counter {
+	/// @notice Add to the counter
+	/// @param value the amount to add
+	function add(uint64 value) external;
+}
+"#
+    );
+}
+
+#[test]
+fn polkadot_metadata() {
+    let metadata = json!({
+        "contract": { "name": "Flipper", "version": "0.0.1" },
+        "spec": {
+            "constructors": [],
+            "docs": ["Flips a value"],
+            "events": [{
+                "label": "Flipped",
+                "args": [
+                    { "label": "who", "indexed": true, "type": { "type": 7, "displayName": [] }, "docs": [] },
+                    { "label": "point", "indexed": false, "type": { "type": 4, "displayName": [] }, "docs": [] }
+                ],
+                "docs": [""]
+            }],
+            "lang_error": { "type": 12, "displayName": ["SolidityError"] },
+            "messages": [
+                {
+                    "label": "flip",
+                    "selector": "0x6351acb7",
+                    "args": [
+                        { "label": "from", "type": { "type": 4, "displayName": ["Point"] } },
+                        { "label": "hash", "type": { "type": 6, "displayName": [] } }
+                    ],
+                    "returnType": { "type": 8, "displayName": [] },
+                    "mutates": true,
+                    "payable": false,
+                    "docs": [" Flip the value"]
+                },
+                {
+                    "label": "Flip::get",
+                    "selector": "0x6d4ce63c",
+                    "args": [],
+                    "returnType": { "type": 9, "displayName": [] },
+                    "mutates": false,
+                    "payable": false,
+                    "docs": [""]
+                },
+                {
+                    "label": "maybe",
+                    "selector": "0x1b9265b8",
+                    "args": [{ "label": "v", "type": { "type": 10, "displayName": [] } }],
+                    "returnType": null,
+                    "mutates": true,
+                    "payable": true,
+                    "docs": []
+                }
+            ]
+        },
+        "types": [
+            { "id": 0, "type": { "def": { "primitive": "bool" } } },
+            { "id": 1, "type": { "def": { "primitive": "i64" } } },
+            { "id": 2, "type": { "def": { "variant": { "variants": [{ "name": "A" }, { "name": "B" }] } }, "path": ["flipper", "Kind"] } },
+            { "id": 3, "type": { "def": { "primitive": "u8" } } },
+            { "id": 4, "type": { "def": { "composite": { "fields": [
+                { "name": "x", "type": 1 },
+                { "name": "kind", "type": 2 },
+                { "name": "data", "type": 5 }
+            ] } }, "path": ["flipper", "Point"] } },
+            { "id": 5, "type": { "def": { "sequence": { "type": 3 } } } },
+            { "id": 6, "type": { "def": { "array": { "len": 32, "type": 3 } } } },
+            { "id": 7, "type": { "def": { "composite": { "fields": [{ "type": 6 }] } }, "path": ["ink_primitives", "types", "AccountId"] } },
+            { "id": 8, "type": { "def": { "variant": { "variants": [
+                { "name": "Ok", "fields": [{ "type": 4 }] },
+                { "name": "Err", "fields": [{ "type": 11 }] }
+            ] } }, "path": ["Result"] } },
+            { "id": 9, "type": { "def": { "tuple": [0, 5] } } },
+            { "id": 10, "type": { "def": { "variant": { "variants": [
+                { "name": "None" },
+                { "name": "Some", "fields": [{ "type": 0 }] }
+            ] } }, "path": ["Option"] } },
+            { "id": 11, "type": { "def": { "variant": { "variants": [{ "name": "CouldNotReadInput" }] } }, "path": ["ink_primitives", "LangError"] } },
+            { "id": 12, "type": { "def": { "variant": { "variants": [
+                { "name": "Error", "fields": [{ "type": 13 }] },
+                { "name": "Denied", "fields": [{ "type": 14 }] }
+            ] } }, "path": ["SolidityError"] } },
+            { "id": 13, "type": { "def": { "composite": { "fields": [{ "type": 15 }] } }, "path": ["0x08c379a0"] } },
+            { "id": 14, "type": { "def": { "composite": { "fields": [{ "type": 1 }] } }, "path": ["0x8210dfe8"] } },
+            { "id": 15, "type": { "def": { "primitive": "str" } } }
+        ]
+    });
+
+    let (name, solidity) = solidity("flipper.contract", metadata);
+
+    assert_eq!(name, "Flipper");
+    assert_eq!(
+        solidity,
+        r#"enum Kind {
+	A,
+	B
+}
+struct Point {
+	int64	x;
+	Kind	kind;
+	bytes	data;
+}
+event Flipped(address indexed who, Point point);
+error Denied(int64);
+/// Flips a value
+interface Flipper {
+	/// Flip the value
+	@selector([0x63,0x51,0xac,0xb7])
+	function flip(Point from, bytes32 hash) external returns (Point);
+	@selector([0x6d,0x4c,0xe6,0x3c])
+	function Flip_get() external view returns (bool, bytes);
+}
+"#
+    );
+}
+
+#[test]
+fn file_names() {
+    assert_eq!(contract_name(OsStr::new("build/ERC20.abi.json")), "ERC20");
+    assert_eq!(contract_name(OsStr::new("my-token.json")), "my_token");
+    assert_eq!(contract_name(OsStr::new("1inch.json")), "_1inch");
+}
@@ -253,7 +253,15 @@ impl SolangServer {
             }
 
             for (map, path) in &self.importmaps {
-                resolver.add_import_map(OsString::from(map), PathBuf::from(path));
+                if let Some((context, map)) = map.split_once(':') {
+                    resolver.add_context_import_map(
+                        OsString::from(context),
+                        OsString::from(map),
+                        PathBuf::from(path),
+                    );
+                } else {
+                    resolver.add_import_map(OsString::from(map), PathBuf::from(path));
+                }
             }
 
             let os_str = path.file_name().unwrap();
@@ -5,12 +5,21 @@ use clap::{Command, CommandFactory, FromArgMatches};
 use clap_complete::generate;
 use cli::PackageTrait;
 use itertools::Itertools;
+use normalize_path::NormalizePath;
 use solang::{
-    abi,
-    codegen::{codegen, Options},
+    abi::{self, codec::Message, natspec},
+    bindings,
+    codegen::{
+        codegen,
+        cost::cost_report,
+        size_report::size_report,
+        storage_layout::{self, storage_layout, StorageLayout},
+        verify, Options,
+    },
     emit::Generate,
     file_resolver::FileResolver,
-    sema::{ast::Namespace, file::PathDisplay},
+    lint,
+    sema::{ast::Namespace, diagnostics::Diagnostics, file::PathDisplay},
     standard_json::{EwasmContract, JsonContract, JsonResult},
 };
 use std::{
@@ -23,8 +32,9 @@ use std::{
 };
 
 use crate::cli::{
-    imports_arg, options_arg, target_arg, Cli, Commands, Compile, CompilerOutput, Doc, New,
-    ShellComplete,
+    idl_spec_arg, imports_arg, metadata_version_arg, options_arg, target_arg, Abi, AbiCommand,
+    AbiPackage, Bindings, Cli, Commands, Compile, CompilerOutput, Doc, Lint, New, ShellComplete,
+    TargetArg, Verify,
 };
 
 mod cli;
@@ -40,6 +50,11 @@ fn main() {
 
     match cli.command {
         Commands::Doc(doc_args) => doc(doc_args),
+        Commands::Lint(lint_args) => lint(lint_args),
+        Commands::Verify(verify_args) => verify(verify_args),
+        Commands::Bindings(bindings_args) => bindings(bindings_args),
+        Commands::Abi(abi_args) => abi_codec(abi_args),
+        Commands::UpgradeCheck(upgrade_args) => upgrade_check(upgrade_args),
         Commands::Compile(compile_args) => {
             // Read config from configuration file. If extra args exist, only overwrite the fields that the user explicitly provides.
             let config = if let Some(conf_file) = &compile_args.configuration_file {
@@ -128,6 +143,18 @@ fn doc(doc_args: Doc) {
     let mut success = true;
     let mut files = Vec::new();
 
+    let template = doc_args.template.as_ref().map(|path| {
+        if doc_args.format == "json" {
+            eprintln!("error: a template cannot be used with the json format");
+            exit(1);
+        }
+
+        fs::read_to_string(path).unwrap_or_else(|err| {
+            eprintln!("{}: error: {}", path.display(), err);
+            exit(1);
+        })
+    });
+
     for filename in doc_args.package.input {
         let ns = solang::parse_and_resolve(filename.as_os_str(), &mut resolver, target);
 
@@ -145,14 +172,419 @@ fn doc(doc_args: Doc) {
 
     if success {
         // generate docs
-        doc::generate_docs(
+        if let Err(err) = doc::generate_docs(
             &doc_args
                 .output_directory
                 .unwrap_or_else(|| OsString::from(".")),
             &files,
+            &doc_args.format,
+            template.as_deref(),
             verbose,
+        ) {
+            eprintln!("error: {err}");
+            exit(1);
+        }
+    }
+}
+
+fn lint(lint_args: Lint) {
+    let target = target_arg(&lint_args.target);
+    let mut resolver: FileResolver = imports_arg(&lint_args.package);
+
+    let mut detectors = lint::detectors();
+
+    if let Some(ids) = &lint_args.detectors {
+        for id in ids {
+            if !detectors.iter().any(|detector| detector.id() == id) {
+                eprintln!(
+                    "error: unknown detector '{id}', available detectors are: {}",
+                    detectors.iter().map(|detector| detector.id()).join(", ")
+                );
+                exit(1);
+            }
+        }
+
+        detectors.retain(|detector| ids.iter().any(|id| id == detector.id()));
+    }
+
+    let mut success = true;
+    let mut namespaces = Vec::new();
+    let mut findings = Vec::new();
+
+    for filename in &lint_args.package.input {
+        let mut ns = solang::parse_and_resolve(filename.as_os_str(), &mut resolver, target);
+
+        if ns.diagnostics.any_errors() {
+            ns.print_diagnostics(&resolver, lint_args.verbose);
+            success = false;
+            continue;
+        }
+
+        let file_findings = lint::lint(&ns, &detectors);
+
+        if lint_args.format == "text" {
+            // print the findings like compiler warnings, with the detector which found them
+            ns.diagnostics = Diagnostics::default();
+
+            for finding in &file_findings {
+                let mut diagnostic = finding.diagnostic.clone();
+                diagnostic.message = format!("{} [{}]", diagnostic.message, finding.detector);
+                ns.diagnostics.push(diagnostic);
+            }
+
+            ns.print_diagnostics(&resolver, lint_args.verbose);
+        }
+
+        namespaces.push(ns);
+        findings.push(file_findings);
+    }
+
+    if !success {
+        exit(1);
+    }
+
+    if lint_args.format == "sarif" {
+        let reports: Vec<_> = namespaces.iter().zip(findings).collect();
+        let log = serde_json::to_string_pretty(&lint::sarif_log(&reports, &detectors)).unwrap();
+
+        if let Some(output) = &lint_args.output {
+            if let Err(err) = fs::write(output, log) {
+                eprintln!("error: cannot write '{}': {err}", output.display());
+                exit(1);
+            }
+        } else {
+            println!("{log}");
+        }
+    }
+}
+
+fn verify(verify_args: Verify) {
+    let target = target_arg(&verify_args.target);
+    let mut resolver: FileResolver = imports_arg(&verify_args.package);
+
+    let solver = verify::Solver {
+        path: verify_args.solver.clone(),
+        timeout: verify_args.timeout,
+    };
+
+    // the runtime checks are recognized by the errors they log
+    let opt = Options {
+        log_runtime_errors: true,
+        ..Default::default()
+    };
+
+    let mut success = true;
+
+    for filename in &verify_args.package.input {
+        let mut ns = solang::parse_and_resolve(filename.as_os_str(), &mut resolver, target);
+
+        if ns.diagnostics.any_errors() {
+            ns.print_diagnostics(&resolver, verify_args.verbose);
+            success = false;
+            continue;
+        }
+
+        codegen(&mut ns, &opt);
+
+        for contract_no in 0..ns.contracts.len() {
+            let contract = &ns.contracts[contract_no];
+
+            if !contract.instantiable
+                || !verify_args
+                    .package
+                    .contracts
+                    .as_ref()
+                    .map_or(true, |names| names.contains(&contract.id.name))
+            {
+                continue;
+            }
+
+            if verify_args.verbose {
+                eprintln!("info: verifying contract {}", contract.id.name);
+            }
+
+            match verify::verify(contract_no, &mut ns, &opt, &solver) {
+                Ok(verdicts) => {
+                    for verdict in &verdicts {
+                        if let Some(diagnostic) = verify::diagnostic(verdict, contract_no, &ns) {
+                            ns.diagnostics.push(diagnostic);
+                        }
+                    }
+                }
+                Err(err) => {
+                    eprintln!("error: {err}");
+                    exit(1);
+                }
+            }
+        }
+
+        ns.diagnostics.sort_and_dedup();
+        ns.print_diagnostics(&resolver, verify_args.verbose);
+
+        if ns.diagnostics.any_errors() {
+            success = false;
+        }
+    }
+
+    if !success {
+        exit(1);
+    }
+}
+
+fn bindings(bindings_args: Bindings) {
+    let target = target_arg(&bindings_args.target);
+    let mut resolver: FileResolver = imports_arg(&bindings_args.package);
+
+    let language = match bindings_args.language.as_str() {
+        "rust" => bindings::Language::Rust,
+        _ => bindings::Language::TypeScript,
+    };
+
+    let output_directory = bindings_args
+        .output_directory
+        .unwrap_or_else(|| PathBuf::from("."));
+
+    let mut success = true;
+
+    for filename in &bindings_args.package.input {
+        let mut ns = solang::parse_and_resolve(filename.as_os_str(), &mut resolver, target);
+
+        ns.print_diagnostics(&resolver, bindings_args.verbose);
+
+        if ns.diagnostics.any_errors() {
+            success = false;
+            continue;
+        }
+
+        // the accounts of Solana instructions are found during codegen
+        codegen(
+            &mut ns,
+            &Options {
+                metadata_version: metadata_version_arg(&bindings_args.target),
+                ..Default::default()
+            },
         );
+
+        for contract_no in 0..ns.contracts.len() {
+            let contract = &ns.contracts[contract_no];
+
+            if !contract.instantiable
+                || !bindings_args
+                    .package
+                    .contracts
+                    .as_ref()
+                    .map_or(true, |names| names.contains(&contract.id.name))
+            {
+                continue;
+            }
+
+            let source = match bindings::generate_bindings(contract_no, &ns, language) {
+                Ok(source) => source,
+                Err(err) => {
+                    eprintln!("error: {err}");
+                    exit(1);
+                }
+            };
+
+            let path =
+                output_directory.join(format!("{}.{}", contract.id.name, language.extension()));
+
+            if bindings_args.verbose {
+                eprintln!(
+                    "info: Saving bindings {} for contract {}",
+                    path.display(),
+                    contract.id.name
+                );
+            }
+
+            if let Err(err) = create_file(&path).write_all(source.as_bytes()) {
+                eprintln!("error: cannot write file '{}': {}", path.display(), err);
+                exit(1);
+            }
+        }
+    }
+
+    if !success {
+        exit(1);
+    }
+}
+
+fn upgrade_check(upgrade_args: UpgradeCheck) {
+    let old: StorageLayout = match File::open(&upgrade_args.old)
+        .map_err(|err| err.to_string())
+        .and_then(|file| serde_json::from_reader(file).map_err(|err| err.to_string()))
+    {
+        Ok(layout) => layout,
+        Err(err) => {
+            eprintln!("{}: error: {}", upgrade_args.old.display(), err);
+            exit(1);
+        }
+    };
+
+    let target = target_arg(&upgrade_args.target);
+    let mut resolver: FileResolver = imports_arg(&upgrade_args.package);
+
+    let opt = Options {
+        pack_storage: upgrade_args.pack_storage,
+        ..Default::default()
+    };
+
+    let name = upgrade_args
+        .package
+        .contracts
+        .as_ref()
+        .and_then(|names| names.first())
+        .unwrap_or(&old.contract);
+
+    for filename in &upgrade_args.package.input {
+        let mut ns = solang::parse_and_resolve(filename.as_os_str(), &mut resolver, target);
+
+        if ns.diagnostics.any_errors() {
+            ns.print_diagnostics(&resolver, false);
+            exit(1);
+        }
+
+        // the storage is laid out during codegen
+        codegen(&mut ns, &opt);
+
+        let Some(contract_no) = ns
+            .contracts
+            .iter()
+            .position(|contract| contract.id.name == *name)
+        else {
+            continue;
+        };
+
+        let problems = storage_layout::upgrade_check(&old, &storage_layout(contract_no, &ns));
+
+        if problems.is_empty() {
+            println!("storage layout of contract {name} is compatible");
+            return;
+        }
+
+        for problem in problems {
+            eprintln!("error: {problem}");
+        }
+
+        exit(1);
     }
+
+    eprintln!("error: contract {name} not found");
+    exit(1);
+}
+
+fn abi_codec(abi_args: Abi) {
+    match abi_args.command {
+        AbiCommand::Encode(encode_args) => {
+            let (ns, contract_no) = abi_contract(&encode_args.package, &encode_args.target);
+
+            let data = Message::find(&ns, contract_no, &encode_args.package.name, false)
+                .and_then(|message| message.encode(&encode_args.values, &ns));
+
+            match data {
+                Ok(data) => println!("0x{}", hex::encode(data)),
+                Err(err) => {
+                    eprintln!("error: {err}");
+                    exit(1);
+                }
+            }
+        }
+        AbiCommand::Decode(decode_args) => {
+            let (ns, contract_no) = abi_contract(&decode_args.package, &decode_args.target);
+
+            let data = decode_args.data.trim();
+
+            let Ok(data) = hex::decode(data.strip_prefix("0x").unwrap_or(data)) else {
+                eprintln!("error: '{}' is not hex", decode_args.data);
+                exit(1);
+            };
+
+            let name = &decode_args.package.name;
+
+            let values = Message::find(&ns, contract_no, name, decode_args.returns)
+                .and_then(|message| message.decode(&data, &ns));
+
+            match values {
+                Ok(values) => {
+                    for (name, value) in values {
+                        println!("{name}: {value}");
+                    }
+                }
+                Err(err) => {
+                    eprintln!("error: {err}");
+                    exit(1);
+                }
+            }
+        }
+    }
+}
+
+/// Resolve the Solidity file, or the interface of the metadata file, and find the contract
+fn abi_contract(package: &AbiPackage, target: &TargetArg) -> (Namespace, usize) {
+    let metadata_version = metadata_version_arg(target);
+    let target = target_arg(target);
+    let mut resolver: FileResolver = imports_arg(package);
+    let input = &package.input[0];
+
+    let filename = if input
+        .extension()
+        .is_some_and(|ext| ext == "json" || ext == "contract")
+    {
+        let source = idl::solidity_interface(input.as_os_str());
+
+        // the file resolver looks up the normalized path in its cache
+        let filename = input.normalize();
+
+        resolver.set_file_contents(&filename.to_string_lossy(), source);
+
+        filename
+    } else {
+        input.clone()
+    };
+
+    let mut ns = solang::parse_and_resolve(filename.as_os_str(), &mut resolver, target);
+
+    if ns.diagnostics.any_errors() {
+        ns.print_diagnostics(&resolver, false);
+        exit(1);
+    }
+
+    // there is no codegen, so set how the events are encoded directly
+    ns.metadata_version = metadata_version;
+
+    let contract_no = if let Some(name) = &package.contract {
+        ns.contracts
+            .iter()
+            .position(|contract| contract.id.name == *name)
+    } else if ns.contracts.len() == 1 {
+        Some(0)
+    } else {
+        let instantiable: Vec<usize> = (0..ns.contracts.len())
+            .filter(|contract_no| ns.contracts[*contract_no].instantiable)
+            .collect();
+
+        match instantiable.as_slice() {
+            [contract_no] => Some(*contract_no),
+            _ => {
+                eprintln!(
+                    "error: {} has more than one contract, use --contract",
+                    filename.display()
+                );
+                exit(1);
+            }
+        }
+    };
+
+    let Some(contract_no) = contract_no else {
+        eprintln!(
+            "error: contract '{}' not found in {}",
+            package.contract.as_deref().unwrap_or_default(),
+            filename.display()
+        );
+        exit(1);
+    };
+
+    (ns, contract_no)
 }
 
 fn compile(compile_args: &Compile) {
@@ -171,7 +603,10 @@ fn compile(compile_args: &Compile) {
 
     let mut resolver = imports_arg(&compile_args.package);
 
-    let opt = options_arg(&compile_args.debug_features, &compile_args.optimizations);
+    let opt = Options {
+        metadata_version: metadata_version_arg(&compile_args.target_arg),
+        ..options_arg(&compile_args.debug_features, &compile_args.optimizations)
+    };
 
     let mut namespaces = Vec::new();
 
@@ -321,6 +756,10 @@ fn process_file(
     // resolve phase
     let mut ns = solang::parse_and_resolve(filepath.as_os_str(), resolver, target);
 
+    if compiler_output.strict_natspec {
+        natspec::check_documented(&mut ns);
+    }
+
     // codegen all the contracts; some additional errors/warnings will be detected here
     codegen(&mut ns, opt);
 
@@ -389,6 +828,129 @@ fn contract_results(
         return;
     }
 
+    if let Some("cost-report") = compiler_output.emit.as_deref() {
+        let Some(report) = cost_report(contract_no, ns) else {
+            eprintln!(
+                "error: cost report is not supported for target {}",
+                ns.target
+            );
+            exit(1);
+        };
+
+        print!("{report}");
+
+        let filename = output_file(
+            compiler_output,
+            &resolved_contract.id.name,
+            "cost.json",
+            false,
+        );
+
+        if verbose {
+            eprintln!(
+                "info: Saving cost report {} for contract {}",
+                filename.display(),
+                resolved_contract.id
+            );
+        }
+
+        let mut file = create_file(&filename);
+
+        if let Err(err) = file.write_all(serde_json::to_string_pretty(&report).unwrap().as_bytes())
+        {
+            eprintln!("{}: error: {}", filename.display(), err);
+            exit(1);
+        }
+
+        return;
+    }
+
+    if let Some("storage-layout") = compiler_output.emit.as_deref() {
+        let layout = storage_layout(contract_no, ns);
+
+        print!("{layout}");
+
+        let filename = output_file(
+            compiler_output,
+            &resolved_contract.id.name,
+            "storage.json",
+            false,
+        );
+
+        if verbose {
+            eprintln!(
+                "info: Saving storage layout {} for contract {}",
+                filename.display(),
+                resolved_contract.id
+            );
+        }
+
+        let mut file = create_file(&filename);
+
+        if let Err(err) = file.write_all(serde_json::to_string_pretty(&layout).unwrap().as_bytes())
+        {
+            eprintln!("{}: error: {}", filename.display(), err);
+            exit(1);
+        }
+
+        return;
+    }
+
+    if let Some("natspec") = compiler_output.emit.as_deref() {
+        for (ext, doc) in [
+            (
+                "userdoc.json",
+                serde_json::to_string_pretty(&natspec::userdoc(contract_no, ns)).unwrap(),
+            ),
+            (
+                "devdoc.json",
+                serde_json::to_string_pretty(&natspec::devdoc(contract_no, ns)).unwrap(),
+            ),
+        ] {
+            let filename = output_file(compiler_output, &resolved_contract.id.name, ext, false);
+
+            if verbose {
+                eprintln!(
+                    "info: Saving NatSpec {} for contract {}",
+                    filename.display(),
+                    resolved_contract.id
+                );
+            }
+
+            let mut file = create_file(&filename);
+
+            if let Err(err) = file.write_all(doc.as_bytes()) {
+                eprintln!("{}: error: {}", filename.display(), err);
+                exit(1);
+            }
+        }
+
+        return;
+    }
+
+    if let Some("eth-abi") = compiler_output.emit.as_deref() {
+        let filename = output_file(compiler_output, &resolved_contract.id.name, "abi", false);
+
+        if verbose {
+            eprintln!(
+                "info: Saving Ethereum ABI {} for contract {}",
+                filename.display(),
+                resolved_contract.id
+            );
+        }
+
+        let abi = serde_json::to_string(&abi::ethereum::gen_abi(contract_no, ns)).unwrap();
+
+        let mut file = create_file(&filename);
+
+        if let Err(err) = file.write_all(abi.as_bytes()) {
+            eprintln!("{}: error: {}", filename.display(), err);
+            exit(1);
+        }
+
+        return;
+    }
+
     if verbose {
         if ns.target == solang::Target::Solana {
             eprintln!(
@@ -421,6 +983,43 @@ fn contract_results(
         );
     }
 
+    if let Some("size-report") = compiler_output.emit.as_deref() {
+        let report = match size_report(contract_no, ns, &code, &binary.stdlib_functions()) {
+            Ok(report) => report,
+            Err(err) => {
+                eprintln!("error: {err}");
+                exit(1);
+            }
+        };
+
+        print!("{report}");
+
+        let filename = output_file(
+            compiler_output,
+            &resolved_contract.id.name,
+            "size.json",
+            false,
+        );
+
+        if verbose {
+            eprintln!(
+                "info: Saving size report {} for contract {}",
+                filename.display(),
+                resolved_contract.id
+            );
+        }
+
+        let mut file = create_file(&filename);
+
+        if let Err(err) = file.write_all(serde_json::to_string_pretty(&report).unwrap().as_bytes())
+        {
+            eprintln!("{}: error: {}", filename.display(), err);
+            exit(1);
+        }
+
+        return;
+    }
+
     if std_json {
         json_contracts.insert(
             binary.name,
@@ -430,6 +1029,8 @@ fn contract_results(
                     wasm: hex::encode_upper(code),
                 }),
                 minimum_space: None,
+                userdoc: natspec::userdoc(contract_no, ns),
+                devdoc: natspec::devdoc(contract_no, ns),
             },
         );
     } else {
@@ -452,8 +1053,15 @@ fn contract_results(
 
         file.write_all(&code).unwrap();
 
-        let (metadata, meta_ext) =
-            abi::generate_abi(contract_no, ns, &code, verbose, default_authors, version);
+        let (metadata, meta_ext) = abi::generate_abi(
+            contract_no,
+            ns,
+            &code,
+            verbose,
+            default_authors,
+            version,
+            idl_spec_arg(compiler_output),
+        );
         let meta_filename = output_file(compiler_output, &binary.name, meta_ext, true);
 
         if verbose {
@@ -0,0 +1,411 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Typed client bindings for calling a compiled contract. The bindings are generated from the
+//! resolved namespace, and encode call data, and decode return values, events and errors
+//! following the same rules as the contract itself: SCALE on Polkadot and Borsh on Solana.
+//! Since the Solana accounts of each function are found during codegen, codegen must have run
+//! on the namespace before generating bindings for Solana.
+
+mod rust;
+mod tests;
+mod typescript;
+
+use crate::abi::anchor::{event_discriminator, function_discriminator};
+use crate::abi::polkadot::signature_topic;
+use crate::codegen::revert::{SolidityError, ERROR_SELECTOR, PANIC_SELECTOR};
+use crate::sema::ast::{ArrayLength, Function, Namespace, Parameter, StructType, Tag, Type};
+use crate::sema::solana_accounts::BuiltinAccounts;
+use crate::Target;
+use solang_parser::pt::FunctionTy;
+use std::str::FromStr;
+
+/// The language to generate bindings for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    /// TypeScript, for polkadot.js on Polkadot and `@solana/web3.js` on Solana
+    TypeScript,
+    /// Rust, for subxt on Polkadot and `solana-program` on Solana
+    Rust,
+}
+
+impl Language {
+    /// The file extension of the generated source file
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Language::TypeScript => "ts",
+            Language::Rust => "rs",
+        }
+    }
+}
+
+/// Generate the bindings for a contract as source code in the given language. Only Polkadot
+/// and Solana are supported.
+pub fn generate_bindings(
+    contract_no: usize,
+    ns: &Namespace,
+    language: Language,
+) -> Result<String, String> {
+    if !(ns.target == Target::Solana || ns.target.is_polkadot()) {
+        return Err(format!("bindings are not supported on {}", ns.target));
+    }
+
+    let bindings = Bindings::new(contract_no, ns);
+
+    Ok(match language {
+        Language::TypeScript => typescript::generate(&bindings),
+        Language::Rust => rust::generate(&bindings),
+    })
+}
+
+/// Everything about a contract which is needed to generate its bindings
+struct Bindings<'a> {
+    ns: &'a Namespace,
+    name: &'a str,
+    docs: Vec<String>,
+    constructors: Vec<Method>,
+    functions: Vec<Method>,
+    events: Vec<Event>,
+    /// Errors are only returned on Polkadot, Solana does not have return data on failure
+    errors: Vec<Error>,
+    /// The structs used by the interface, with their unique names. A struct comes after the
+    /// structs of its fields.
+    structs: Vec<(StructType, String)>,
+    /// The enums used by the interface, with their unique names
+    enums: Vec<(usize, String)>,
+}
+
+/// A constructor or function
+struct Method {
+    name: String,
+    selector: Vec<u8>,
+    params: Vec<Param>,
+    returns: Vec<Param>,
+    docs: Vec<String>,
+    /// The accounts of the instruction on Solana
+    accounts: Vec<Account>,
+}
+
+struct Param {
+    name: String,
+    ty: Type,
+}
+
+struct Account {
+    name: String,
+    is_signer: bool,
+    is_writer: bool,
+    /// Builtin accounts like the system program have a fixed address
+    address: Option<&'static str>,
+}
+
+struct Event {
+    name: String,
+    /// The event discriminator on Solana, or the index of the event on Polkadot. With ink! v5
+    /// metadata, this is the signature topic, which anonymous events do not have.
+    selector: Vec<u8>,
+    fields: Vec<Param>,
+    docs: Vec<String>,
+}
+
+struct Error {
+    name: String,
+    selector: [u8; 4],
+    fields: Vec<Param>,
+}
+
+impl<'a> Bindings<'a> {
+    fn new(contract_no: usize, ns: &'a Namespace) -> Self {
+        let contract = &ns.contracts[contract_no];
+
+        let mut bindings = Bindings {
+            ns,
+            name: &contract.id.name,
+            docs: docs(&contract.tags),
+            constructors: Vec::new(),
+            functions: Vec::new(),
+            events: Vec::new(),
+            errors: Vec::new(),
+            structs: Vec::new(),
+            enums: Vec::new(),
+        };
+
+        let constructors = contract.constructors(ns);
+
+        if ns.target == Target::Solana && constructors.is_empty() {
+            // the default constructor only needs the data account, like in the IDL
+            bindings.constructors.push(Method {
+                name: "new".into(),
+                selector: function_discriminator("new"),
+                params: Vec::new(),
+                returns: Vec::new(),
+                docs: Vec::new(),
+                accounts: vec![Account {
+                    name: BuiltinAccounts::DataAccount.to_string(),
+                    is_signer: false,
+                    is_writer: true,
+                    address: None,
+                }],
+            });
+        } else {
+            let default_constructor = contract.default_constructor.as_ref().map(|(f, _)| f);
+
+            for func in constructors
+                .iter()
+                .filter(|func_no| ns.function_externally_callable(contract_no, Some(**func_no)))
+                .map(|func_no| &ns.functions[*func_no])
+                .chain(default_constructor)
+            {
+                let name = if func.id.name.is_empty() || ns.target == Target::Solana {
+                    "new".into()
+                } else {
+                    func.id.name.clone()
+                };
+
+                bindings
+                    .constructors
+                    .push(method(name, func, contract_no, ns));
+            }
+        }
+
+        for func_no in contract.all_functions.keys() {
+            let func = &ns.functions[*func_no];
+
+            if !ns.function_externally_callable(contract_no, Some(*func_no))
+                || matches!(
+                    func.ty,
+                    FunctionTy::Constructor
+                        | FunctionTy::Fallback
+                        | FunctionTy::Receive
+                        | FunctionTy::Modifier
+                )
+            {
+                continue;
+            }
+
+            let name = if func.mangled_name_contracts.contains(&contract_no) {
+                func.mangled_name.clone()
+            } else {
+                func.id.name.clone()
+            };
+
+            bindings.functions.push(method(name, func, contract_no, ns));
+        }
+
+        for (no, event_no) in contract.emits_events.iter().enumerate() {
+            let event = &ns.events[*event_no];
+
+            bindings.events.push(Event {
+                name: event.id.name.clone(),
+                selector: if ns.target == Target::Solana {
+                    event_discriminator(&event.id.name)
+                } else if !ns.is_ink_v5() {
+                    vec![no as u8]
+                } else if event.anonymous {
+                    Vec::new()
+                } else {
+                    signature_topic(event).into()
+                },
+                fields: params(&event.fields, "field"),
+                docs: docs(&event.tags),
+            });
+        }
+
+        if ns.target.is_polkadot() {
+            let builtin = |name: &str, selector, field: &str, ty| Error {
+                name: name.into(),
+                selector,
+                fields: vec![Param {
+                    name: field.into(),
+                    ty,
+                }],
+            };
+
+            bindings
+                .errors
+                .push(builtin("Error", ERROR_SELECTOR, "message", Type::String));
+            bindings
+                .errors
+                .push(builtin("Panic", PANIC_SELECTOR, "code", Type::Uint(256)));
+
+            for (error_no, error) in ns.errors.iter().enumerate() {
+                let exprs = Vec::new();
+
+                bindings.errors.push(Error {
+                    name: error.name.clone(),
+                    selector: SolidityError::Custom { error_no, exprs }.selector(ns),
+                    fields: params(&error.fields, "field"),
+                });
+            }
+        }
+
+        bindings.collect_types();
+
+        bindings
+    }
+
+    /// Find all the structs and enums used by the interface
+    fn collect_types(&mut self) {
+        let mut types = Vec::new();
+
+        for method in self.constructors.iter().chain(&self.functions) {
+            types.extend(method.params.iter().chain(&method.returns).map(|p| &p.ty));
+        }
+
+        for event in &self.events {
+            types.extend(event.fields.iter().map(|p| &p.ty));
+        }
+
+        for error in &self.errors {
+            types.extend(error.fields.iter().map(|p| &p.ty));
+        }
+
+        let mut structs = Vec::new();
+        let mut enums = Vec::new();
+
+        for ty in types {
+            collect_type(ty, self.ns, &mut structs, &mut enums);
+        }
+
+        let mut names: Vec<String> = Vec::new();
+
+        let mut unique = |name: &str| {
+            let mut unique = name.to_owned();
+            let mut no = 0;
+
+            while names.contains(&unique) {
+                no += 1;
+                unique = format!("{name}_{no}");
+            }
+
+            names.push(unique.clone());
+
+            unique
+        };
+
+        self.enums = enums
+            .into_iter()
+            .map(|enum_no| (enum_no, unique(&self.ns.enums[enum_no].id.name)))
+            .collect();
+
+        self.structs = structs
+            .into_iter()
+            .map(|struct_ty| (struct_ty, unique(&struct_ty.definition(self.ns).id.name)))
+            .collect();
+    }
+
+    fn struct_name(&self, struct_ty: &StructType) -> &str {
+        &self
+            .structs
+            .iter()
+            .find(|(ty, _)| ty == struct_ty)
+            .unwrap()
+            .1
+    }
+
+    fn enum_name(&self, enum_no: usize) -> &str {
+        &self.enums.iter().find(|(no, _)| *no == enum_no).unwrap().1
+    }
+
+    /// The number of bytes of a selector
+    fn selector_length(&self) -> usize {
+        self.ns.target.selector_length() as usize
+    }
+}
+
+fn method(name: String, func: &Function, contract_no: usize, ns: &Namespace) -> Method {
+    let accounts = func
+        .solana_accounts
+        .borrow()
+        .iter()
+        .map(|(name, account)| Account {
+            name: name.clone(),
+            is_signer: account.is_signer,
+            is_writer: account.is_writer,
+            address: BuiltinAccounts::from_str(name)
+                .ok()
+                .and_then(|account| account.address()),
+        })
+        .collect();
+
+    Method {
+        name,
+        selector: func.selector(ns, &contract_no),
+        params: params(&func.params, "arg"),
+        returns: params(&func.returns, "ret"),
+        docs: docs(&func.tags),
+        accounts,
+    }
+}
+
+/// Give each parameter a name, using the prefix and its position if it has none
+fn params(params: &[Parameter<Type>], prefix: &str) -> Vec<Param> {
+    params
+        .iter()
+        .enumerate()
+        .map(|(no, param)| Param {
+            name: match param.name_as_str() {
+                "" => format!("{prefix}{no}"),
+                name => name.to_owned(),
+            },
+            ty: param.ty.clone(),
+        })
+        .collect()
+}
+
+/// The notice and dev tags are used as documentation
+fn docs(tags: &[Tag]) -> Vec<String> {
+    tags.iter()
+        .filter(|tag| matches!(tag.tag.as_str(), "notice" | "dev" | "title"))
+        .flat_map(|tag| tag.value.lines().map(str::to_owned))
+        .collect()
+}
+
+fn collect_type(ty: &Type, ns: &Namespace, structs: &mut Vec<StructType>, enums: &mut Vec<usize>) {
+    match ty.clone().unwrap_user_type(ns) {
+        Type::Enum(enum_no) if !enums.contains(&enum_no) => {
+            enums.push(enum_no);
+        }
+        Type::Struct(struct_ty) if !structs.contains(&struct_ty) => {
+            for field in &struct_ty.definition(ns).fields {
+                collect_type(&field.ty, ns, structs, enums);
+            }
+
+            // a struct cannot contain itself, so it was not added by its fields
+            structs.push(struct_ty);
+        }
+        Type::Array(elem_ty, _) | Type::Slice(elem_ty) => {
+            collect_type(&elem_ty, ns, structs, enums)
+        }
+        _ => (),
+    }
+}
+
+/// The element type of an array, and its outer dimension. The last dimension of a Solidity
+/// array is the outer one, e.g. `int[2][]` is a dynamic array of `int[2]`.
+fn array_element(ty: &Type) -> Option<(Type, Option<usize>)> {
+    match ty {
+        Type::Array(elem_ty, dims) => {
+            let (outer, inner) = dims.split_last().unwrap();
+
+            let elem = if inner.is_empty() {
+                *elem_ty.clone()
+            } else {
+                Type::Array(elem_ty.clone(), inner.to_vec())
+            };
+
+            let len = match outer {
+                ArrayLength::Fixed(len) => Some(len.try_into().unwrap()),
+                _ => None,
+            };
+
+            Some((elem, len))
+        }
+        Type::Slice(elem_ty) => Some((*elem_ty.clone(), None)),
+        _ => None,
+    }
+}
+
+/// The number of bytes an integer of the given width is encoded in
+fn int_size(width: u16) -> usize {
+    width.next_power_of_two() as usize / 8
+}
@@ -0,0 +1,607 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{array_element, int_size, Bindings, Method, Param};
+use crate::sema::ast::Type;
+use crate::Target;
+use convert_case::{Boundary, Case, Casing};
+use itertools::Itertools;
+use std::fmt::Write;
+
+/// Keywords which cannot be used as identifiers in Rust, and the names of the locals of the
+/// generated functions
+const RESERVED: &[&str] = &[
+    "abstract",
+    "as",
+    "async",
+    "await",
+    "become",
+    "box",
+    "break",
+    "const",
+    "continue",
+    "crate",
+    "do",
+    "dyn",
+    "else",
+    "enum",
+    "extern",
+    "false",
+    "final",
+    "fn",
+    "for",
+    "if",
+    "impl",
+    "in",
+    "let",
+    "loop",
+    "macro",
+    "match",
+    "mod",
+    "move",
+    "mut",
+    "override",
+    "priv",
+    "pub",
+    "ref",
+    "return",
+    "self",
+    "Self",
+    "static",
+    "struct",
+    "super",
+    "trait",
+    "true",
+    "try",
+    "type",
+    "typeof",
+    "unsafe",
+    "unsized",
+    "use",
+    "virtual",
+    "where",
+    "while",
+    "yield",
+    "data",
+    "accounts",
+    "program_id",
+];
+
+/// Generate Rust bindings. The types derive the SCALE codec on Polkadot, so the call data
+/// can be passed to subxt, and Borsh on Solana, where each function has a builder for its
+/// `Instruction`.
+pub(super) fn generate(bindings: &Bindings) -> String {
+    let ns = bindings.ns;
+    let solana = ns.target == Target::Solana;
+    let (codec, derive) = if solana {
+        ("borsh", "BorshSerialize, BorshDeserialize")
+    } else {
+        ("parity_scale_codec", "Encode, Decode")
+    };
+    let mut out = String::new();
+
+    writeln!(
+        out,
+        "// Bindings for contract {}, generated by Solang. Do not edit.\n",
+        bindings.name
+    )
+    .unwrap();
+    docs(&mut out, "//! ", &bindings.docs);
+    writeln!(
+        out,
+        "#![allow(dead_code, unused_mut, non_camel_case_types, non_snake_case, clippy::all)]\n"
+    )
+    .unwrap();
+
+    if solana {
+        writeln!(
+            out,
+            "use borsh::{{BorshDeserialize, BorshSerialize}};\nuse solana_program::instruction::{{AccountMeta, Instruction}};\nuse solana_program::pubkey::Pubkey;"
+        )
+        .unwrap();
+    } else {
+        writeln!(out, "use parity_scale_codec::{{Decode, Encode}};").unwrap();
+    }
+
+    writeln!(out, "use std::marker::PhantomData;\n").unwrap();
+
+    if !solana {
+        writeln!(out, "pub type Address = [u8; {}];\n", ns.address_length).unwrap();
+    }
+
+    // integers wider than 128 bits are little endian bytes, like their encoding
+    for (name, signed) in [("U256", "unsigned"), ("I256", "signed")] {
+        writeln!(
+            out,
+            "/// A 256 bit {signed} integer, as little endian bytes\n#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, {derive})]\npub struct {name}(pub [u8; 32]);\n"
+        )
+        .unwrap();
+    }
+
+    writeln!(
+        out,
+        "#[derive(Clone, Copy, Debug, PartialEq, Eq, {derive})]\npub struct ExternalFunction {{\n{}\n}}\n",
+        if solana {
+            "    pub selector: [u8; 8],\n    pub address: Pubkey,".to_owned()
+        } else {
+            "    pub address: Address,\n    pub selector: [u8; 4],".to_owned()
+        }
+    )
+    .unwrap();
+
+    for (enum_no, name) in &bindings.enums {
+        let decl = &ns.enums[*enum_no];
+
+        docs(&mut out, "/// ", &super::docs(&decl.tags));
+        writeln!(
+            out,
+            "#[derive(Clone, Copy, Debug, PartialEq, Eq, {derive})]\npub enum {name} {{\n{}\n}}\n",
+            decl.values.keys().map(|v| format!("    {v},")).join("\n")
+        )
+        .unwrap();
+    }
+
+    for (struct_ty, name) in &bindings.structs {
+        let decl = struct_ty.definition(ns);
+
+        docs(&mut out, "/// ", &super::docs(&decl.tags));
+        writeln!(
+            out,
+            "#[derive(Clone, Debug, PartialEq, Eq, {derive})]\npub struct {name} {{\n{}\n}}\n",
+            decl.fields
+                .iter()
+                .map(|field| format!(
+                    "    pub {}: {},",
+                    identifier(field.name_as_str()),
+                    rust_type(&field.ty, bindings)
+                ))
+                .join("\n")
+        )
+        .unwrap();
+    }
+
+    if solana {
+        writeln!(
+            out,
+            "/// The instruction for a function, and the type of its return data\npub struct Call<T> {{\n    pub instruction: Instruction,\n    output: PhantomData<T>,\n}}\n"
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "impl<T: BorshDeserialize> Call<T> {{\n    fn new(instruction: Instruction) -> Self {{\n        Call {{ instruction, output: PhantomData }}\n    }}\n\n    /// Decode the return data of the instruction\n    pub fn decode_output(&self, output: &[u8]) -> std::io::Result<T> {{\n        T::try_from_slice(output)\n    }}\n}}\n"
+        )
+        .unwrap();
+
+        writeln!(out, "pub mod instructions {{\n    use super::*;").unwrap();
+
+        for method in bindings.constructors.iter().chain(&bindings.functions) {
+            instruction(&mut out, method, bindings);
+        }
+
+        writeln!(out, "}}").unwrap();
+    } else {
+        writeln!(
+            out,
+            "/// The call data of a message, and the type of its return value\npub struct Call<T> {{\n    pub data: Vec<u8>,\n    output: PhantomData<T>,\n}}\n"
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "impl<T: Decode> Call<T> {{\n    fn new(data: Vec<u8>) -> Self {{\n        Call {{ data, output: PhantomData }}\n    }}\n\n    /// Decode the output of the message\n    pub fn decode_output(&self, mut output: &[u8]) -> Result<T, parity_scale_codec::Error> {{\n        T::decode(&mut output)\n    }}\n}}\n"
+        )
+        .unwrap();
+
+        writeln!(
+            out,
+            "/// The data for instantiating {} with each of its constructors\npub mod constructors {{\n    use super::*;",
+            bindings.name
+        )
+        .unwrap();
+
+        for method in &bindings.constructors {
+            writeln!(out).unwrap();
+            docs(&mut out, "    /// ", &method.docs);
+            writeln!(
+                out,
+                "    pub fn {}({}) -> Vec<u8> {{",
+                identifier(&snake_case(&method.name)),
+                params(&method.params, bindings)
+            )
+            .unwrap();
+            encode_call(&mut out, method);
+            writeln!(out, "        data\n    }}").unwrap();
+        }
+
+        writeln!(out, "}}\n").unwrap();
+
+        writeln!(
+            out,
+            "/// The calls of the messages of {}\npub mod messages {{\n    use super::*;",
+            bindings.name
+        )
+        .unwrap();
+
+        for method in &bindings.functions {
+            writeln!(out).unwrap();
+            docs(&mut out, "    /// ", &method.docs);
+            writeln!(
+                out,
+                "    pub fn {}({}) -> Call<{}> {{",
+                identifier(&snake_case(&method.name)),
+                params(&method.params, bindings),
+                returns_type(&method.returns, bindings)
+            )
+            .unwrap();
+            encode_call(&mut out, method);
+            writeln!(out, "        Call::new(data)\n    }}").unwrap();
+        }
+
+        writeln!(out, "}}").unwrap();
+    }
+
+    if !bindings.events.is_empty() {
+        events(&mut out, bindings, codec);
+    }
+
+    if !bindings.errors.is_empty() {
+        errors(&mut out, bindings);
+    }
+
+    out
+}
+
+fn instruction(out: &mut String, method: &Method, bindings: &Bindings) {
+    let name = identifier(&snake_case(&method.name));
+    let accounts_ty = format!("{}Accounts", method.name.to_case(Case::Pascal));
+
+    let accounts = method
+        .accounts
+        .iter()
+        .filter(|account| account.address.is_none())
+        .collect::<Vec<_>>();
+
+    let mut params = vec!["program_id: Pubkey".to_owned()];
+
+    writeln!(out).unwrap();
+
+    if !accounts.is_empty() {
+        writeln!(
+            out,
+            "    /// The accounts of `{name}` which do not have a fixed address\n    pub struct {accounts_ty} {{\n{}\n    }}\n",
+            accounts
+                .iter()
+                .map(|account| format!("        pub {}: Pubkey,", identifier(&snake_case(&account.name))))
+                .join("\n")
+        )
+        .unwrap();
+
+        params.push(format!("accounts: &{accounts_ty}"));
+    }
+
+    params.extend(method.params.iter().map(|param| {
+        format!(
+            "{}: {}",
+            identifier(&snake_case(&param.name)),
+            rust_type(&param.ty, bindings)
+        )
+    }));
+
+    docs(out, "    /// ", &method.docs);
+    writeln!(
+        out,
+        "    pub fn {name}({}) -> Call<{}> {{",
+        params.join(", "),
+        returns_type(&method.returns, bindings)
+    )
+    .unwrap();
+
+    encode_call(out, method);
+
+    writeln!(
+        out,
+        "        let accounts = vec![\n{}\n        ];",
+        method
+            .accounts
+            .iter()
+            .map(|account| {
+                let pubkey = match account.address {
+                    Some(address) => format!("solana_program::pubkey!(\"{address}\")"),
+                    None => format!("accounts.{}", identifier(&snake_case(&account.name))),
+                };
+
+                if account.is_writer {
+                    format!(
+                        "            AccountMeta::new({pubkey}, {}),",
+                        account.is_signer
+                    )
+                } else {
+                    format!(
+                        "            AccountMeta::new_readonly({pubkey}, {}),",
+                        account.is_signer
+                    )
+                }
+            })
+            .join("\n")
+    )
+    .unwrap();
+
+    writeln!(
+        out,
+        "        Call::new(Instruction {{ program_id, accounts, data }})\n    }}"
+    )
+    .unwrap();
+}
+
+/// Encode the selector and arguments of a call into `data`
+fn encode_call(out: &mut String, method: &Method) {
+    let solana = method.selector.len() == 8;
+
+    writeln!(
+        out,
+        "        {}data = vec![{}];",
+        if method.params.is_empty() {
+            "let "
+        } else {
+            "let mut "
+        },
+        bytes(&method.selector)
+    )
+    .unwrap();
+
+    for param in &method.params {
+        let name = identifier(&snake_case(&param.name));
+
+        if solana {
+            writeln!(out, "        {name}.serialize(&mut data).unwrap();").unwrap();
+        } else {
+            writeln!(out, "        {name}.encode_to(&mut data);").unwrap();
+        }
+    }
+}
+
+fn events(out: &mut String, bindings: &Bindings, codec: &str) {
+    let name = bindings.name;
+    let solana = bindings.ns.target == Target::Solana;
+
+    writeln!(
+        out,
+        "\n/// The events emitted by {name}\n#[derive(Clone, Debug, PartialEq, Eq)]\npub enum {name}Event {{"
+    )
+    .unwrap();
+
+    for event in &bindings.events {
+        docs(out, "    /// ", &event.docs);
+        writeln!(
+            out,
+            "    {} {{ {} }},",
+            event.name,
+            fields_type(&event.fields, bindings)
+        )
+        .unwrap();
+    }
+
+    writeln!(out, "}}\n\nimpl {name}Event {{").unwrap();
+
+    if solana {
+        writeln!(
+            out,
+            "    /// Decode the data of an event\n    pub fn decode(data: &[u8]) -> std::io::Result<Self> {{\n        if data.len() < 8 {{\n            return Err(std::io::ErrorKind::InvalidData.into());\n        }}\n        let (selector, mut data) = data.split_at(8);\n        match selector {{"
+        )
+        .unwrap();
+
+        for event in &bindings.events {
+            writeln!(
+                out,
+                "            [{}] => Ok({name}Event::{} {{ {} }}),",
+                bytes(&event.selector),
+                event.name,
+                fields_decoder(&event.fields, "BorshDeserialize::deserialize_reader")
+            )
+            .unwrap();
+        }
+
+        writeln!(
+            out,
+            "            _ => Err(std::io::ErrorKind::InvalidData.into()),\n        }}\n    }}\n}}"
+        )
+        .unwrap();
+    } else if bindings.ns.is_ink_v5() {
+        writeln!(
+            out,
+            "    /// Decode the data of an event, given its first topic\n    pub fn decode(topic: &[u8], mut data: &[u8]) -> Result<Self, {codec}::Error> {{\n        match topic {{"
+        )
+        .unwrap();
+
+        // anonymous events have no signature topic, so they cannot be identified
+        for event in bindings.events.iter().filter(|e| !e.selector.is_empty()) {
+            writeln!(
+                out,
+                "            [{}] => Ok({name}Event::{} {{ {} }}),",
+                bytes(&event.selector),
+                event.name,
+                fields_decoder(&event.fields, "Decode::decode")
+            )
+            .unwrap();
+        }
+
+        writeln!(
+            out,
+            "            _ => Err(\"unknown event\".into()),\n        }}\n    }}\n}}"
+        )
+        .unwrap();
+    } else {
+        writeln!(
+            out,
+            "    /// Decode the data of an event\n    pub fn decode(mut data: &[u8]) -> Result<Self, {codec}::Error> {{\n        match u8::decode(&mut data)? {{"
+        )
+        .unwrap();
+
+        for event in &bindings.events {
+            writeln!(
+                out,
+                "            {} => Ok({name}Event::{} {{ {} }}),",
+                event.selector[0],
+                event.name,
+                fields_decoder(&event.fields, "Decode::decode")
+            )
+            .unwrap();
+        }
+
+        writeln!(
+            out,
+            "            _ => Err(\"unknown event\".into()),\n        }}\n    }}\n}}"
+        )
+        .unwrap();
+    }
+}
+
+fn errors(out: &mut String, bindings: &Bindings) {
+    let name = bindings.name;
+
+    writeln!(
+        out,
+        "\n/// The errors {name} can revert with\n#[derive(Clone, Debug, PartialEq, Eq)]\npub enum {name}Error {{"
+    )
+    .unwrap();
+
+    for error in &bindings.errors {
+        writeln!(
+            out,
+            "    {} {{ {} }},",
+            error.name,
+            fields_type(&error.fields, bindings)
+        )
+        .unwrap();
+    }
+
+    writeln!(
+        out,
+        "}}\n\nimpl {name}Error {{\n    /// Decode the data returned when the contract reverts\n    pub fn decode(data: &[u8]) -> Result<Self, parity_scale_codec::Error> {{\n        if data.len() < 4 {{\n            return Err(\"no error selector\".into());\n        }}\n        let (selector, mut data) = data.split_at(4);\n        match selector {{"
+    )
+    .unwrap();
+
+    for error in &bindings.errors {
+        writeln!(
+            out,
+            "            [{}] => Ok({name}Error::{} {{ {} }}),",
+            bytes(&error.selector),
+            error.name,
+            fields_decoder(&error.fields, "Decode::decode")
+        )
+        .unwrap();
+    }
+
+    writeln!(
+        out,
+        "            _ => Err(\"unknown error\".into()),\n        }}\n    }}\n}}"
+    )
+    .unwrap();
+}
+
+fn params(params: &[Param], bindings: &Bindings) -> String {
+    params
+        .iter()
+        .map(|param| {
+            format!(
+                "{}: {}",
+                identifier(&snake_case(&param.name)),
+                rust_type(&param.ty, bindings)
+            )
+        })
+        .join(", ")
+}
+
+fn fields_type(fields: &[Param], bindings: &Bindings) -> String {
+    fields
+        .iter()
+        .map(|field| {
+            format!(
+                "{}: {}",
+                identifier(&snake_case(&field.name)),
+                rust_type(&field.ty, bindings)
+            )
+        })
+        .join(", ")
+}
+
+/// Struct fields are evaluated in order, so each field is decoded from the data in turn
+fn fields_decoder(fields: &[Param], decode: &str) -> String {
+    fields
+        .iter()
+        .map(|field| {
+            format!(
+                "{}: {decode}(&mut data)?",
+                identifier(&snake_case(&field.name))
+            )
+        })
+        .join(", ")
+}
+
+/// Multiple return values are returned as a tuple
+fn returns_type(returns: &[Param], bindings: &Bindings) -> String {
+    match returns {
+        [ret] => rust_type(&ret.ty, bindings),
+        _ => format!(
+            "({})",
+            returns
+                .iter()
+                .map(|ret| rust_type(&ret.ty, bindings))
+                .join(", ")
+        ),
+    }
+}
+
+fn rust_type(ty: &Type, bindings: &Bindings) -> String {
+    let ns = bindings.ns;
+
+    if let Some((elem_ty, len)) = array_element(ty) {
+        let elem = rust_type(&elem_ty, bindings);
+
+        return match len {
+            Some(len) => format!("[{elem}; {len}]"),
+            None => format!("Vec<{elem}>"),
+        };
+    }
+
+    match ty.clone().unwrap_user_type(ns) {
+        Type::Bool => "bool".into(),
+        Type::Uint(width) if int_size(width) > 16 => "U256".into(),
+        Type::Int(width) if int_size(width) > 16 => "I256".into(),
+        Type::Uint(width) => format!("u{}", int_size(width) * 8),
+        Type::Int(width) => format!("i{}", int_size(width) * 8),
+        Type::Value => format!("u{}", ns.value_length * 8),
+        Type::Address(_) | Type::Contract(_) if ns.target == Target::Solana => "Pubkey".into(),
+        Type::Address(_) | Type::Contract(_) => "Address".into(),
+        Type::Bytes(len) => format!("[u8; {len}]"),
+        Type::FunctionSelector => format!("[u8; {}]", bindings.selector_length()),
+        Type::String => "String".into(),
+        Type::DynamicBytes => "Vec<u8>".into(),
+        Type::Enum(enum_no) => bindings.enum_name(enum_no).into(),
+        Type::Struct(struct_ty) => bindings.struct_name(&struct_ty).into(),
+        Type::ExternalFunction { .. } => "ExternalFunction".into(),
+        ty => unreachable!("{ty:?} cannot be a parameter"),
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    name.from_case(Case::Camel)
+        .without_boundaries(&[Boundary::LowerDigit, Boundary::UpperDigit])
+        .to_case(Case::Snake)
+}
+
+fn identifier(name: &str) -> String {
+    if RESERVED.contains(&name) {
+        format!("{name}_")
+    } else {
+        name.to_owned()
+    }
+}
+
+fn bytes(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:#04x}")).join(", ")
+}
+
+fn docs(out: &mut String, prefix: &str, docs: &[String]) {
+    for line in docs {
+        writeln!(out, "{prefix}{line}").unwrap();
+    }
+}
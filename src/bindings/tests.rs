@@ -0,0 +1,144 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(test)]
+
+use super::{generate_bindings, Language};
+use crate::codegen::Options;
+use crate::sema::tests::resolve_and_codegen;
+use crate::Target;
+
+fn bindings(src: &str, target: Target, language: Language) -> String {
+    let ns = resolve_and_codegen(src, target, &Options::default());
+
+    let contract_no = ns.contracts.iter().position(|c| c.instantiable).unwrap();
+
+    generate_bindings(contract_no, &ns, language).unwrap()
+}
+
+const FLIPPER: &str = r#"
+    contract Flipper {
+        enum Kind { A, B, C }
+        struct Point { int64 x; uint32[] ys; Kind k; }
+        event Flipped(address indexed who, bool value, Point p);
+        error Denied(uint32 code);
+
+        bool value;
+
+        constructor(bool v) { value = v; }
+
+        /// Flip it
+        function flip(Point p, bytes32 h) public returns (Point) {
+            value = !value;
+            emit Flipped(address(this), value, p);
+            return p;
+        }
+
+        function get() public view returns (bool, string) {
+            if (!value) {
+                revert Denied(1);
+            }
+            return (value, "flipped");
+        }
+    }"#;
+
+#[test]
+fn polkadot_typescript() {
+    let ts = bindings(FLIPPER, Target::default_polkadot(), Language::TypeScript);
+
+    assert!(ts.contains(
+        r#"export enum Kind {
+    A,
+    B,
+    C,
+}"#
+    ));
+    assert!(ts.contains(
+        r#"function encodePoint($e: Encoder, v: Point) {
+    $e.int(v.x, 8);
+    $e.vector(v.ys, (x0) => { $e.int(x0, 4); });
+    $e.int(v.k, 1);
+}"#
+    ));
+    assert!(ts.contains("$e.fixed(new Uint8Array([0x58, 0xb3, 0xd7, 0x9c]), 4);"));
+    assert!(ts.contains("    flip(p: Point, h: Uint8Array): Call<Point> {"));
+    assert!(ts.contains("return [$d.bool(), $d.string()];"));
+    assert!(ts.contains(
+        "return { event: 'Flipped', args: { who: $d.fixed(32), value: $d.bool(), p: decodePoint($d) } };"
+    ));
+    assert!(ts.contains("if (equal(selector, [0x08, 0xc3, 0x79, 0xa0])) {"));
+    assert!(ts.contains("return { error: 'Denied', args: { code: $d.number(4, false) } };"));
+}
+
+#[test]
+fn polkadot_rust() {
+    let rs = bindings(FLIPPER, Target::default_polkadot(), Language::Rust);
+
+    assert!(rs.contains("use parity_scale_codec::{Decode, Encode};"));
+    assert!(rs.contains("pub type Address = [u8; 32];"));
+    assert!(rs.contains(
+        r#"pub struct Point {
+    pub x: i64,
+    pub ys: Vec<u32>,
+    pub k: Kind,
+}"#
+    ));
+    assert!(rs.contains("pub fn flip(p: Point, h: [u8; 32]) -> Call<Point> {"));
+    assert!(rs.contains("let mut data = vec![0x5d, 0x5a, 0x5c, 0x76];"));
+    assert!(rs.contains("Denied { code: u32 },"));
+}
+
+#[test]
+fn solana() {
+    let src = r#"
+        contract Counter {
+            struct Entry { uint64 v; address who; }
+            event Added(uint64 indexed v, address who);
+
+            uint64 count;
+
+            @payer(payer)
+            constructor(uint64 start) { count = start; }
+
+            function add(uint64 v) public returns (Entry) {
+                count += v;
+                emit Added(v, address(this));
+                return Entry(count, address(this));
+            }
+        }"#;
+
+    let ts = bindings(src, Target::Solana, Language::TypeScript);
+
+    assert!(ts.contains("new(programId: PublicKey, accounts: { dataAccount: PublicKey; payer: PublicKey }, start: bigint): Call<void> {"));
+    assert!(ts.contains(
+        "$e.fixed(new Uint8Array([0x87, 0x2c, 0xcd, 0xc6, 0x19, 0x01, 0x48, 0xbc]), 8);"
+    ));
+    assert!(ts.contains("{ pubkey: new PublicKey('11111111111111111111111111111111'), isSigner: false, isWritable: false },"));
+    assert!(ts.contains("{ pubkey: accounts.dataAccount, isSigner: false, isWritable: true },"));
+    assert!(ts.contains("who: new PublicKey($d.fixed(32))"));
+
+    let rs = bindings(src, Target::Solana, Language::Rust);
+
+    assert!(rs.contains("pub struct NewAccounts {"));
+    assert!(rs.contains("AccountMeta::new(accounts.payer, true),"));
+    assert!(rs.contains(
+        "AccountMeta::new_readonly(solana_program::pubkey!(\"11111111111111111111111111111111\"), false),"
+    ));
+    assert!(rs.contains(
+        "pub fn add(program_id: Pubkey, accounts: &AddAccounts, v: u64) -> Call<Entry> {"
+    ));
+    assert!(rs.contains("Added { v: u64, who: Pubkey },"));
+}
+
+#[test]
+fn unsupported_target() {
+    let ns = resolve_and_codegen(
+        "contract C { function f() public {} }",
+        Target::EVM,
+        &Options::default(),
+    );
+
+    assert_eq!(
+        generate_bindings(0, &ns, Language::TypeScript),
+        Err("bindings are not supported on EVM".to_string())
+    );
+}
@@ -0,0 +1,840 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{array_element, int_size, Bindings, Method, Param};
+use crate::sema::ast::Type;
+use crate::Target;
+use itertools::Itertools;
+use std::fmt::Write;
+
+/// Identifiers which cannot be used for parameters in TypeScript, and the names of the locals
+/// of the generated functions
+const RESERVED: &[&str] = &[
+    "arguments",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "eval",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+    "programId",
+    "accounts",
+    "$e",
+    "$d",
+];
+
+/// Encoding and decoding of the primitive types. The length of dynamic arrays and strings
+/// is a compact integer in SCALE, and a 32 bit integer in Borsh.
+const PRELUDE: &str = r#"class Encoder {
+    private buf: number[] = [];
+
+    bool(v: boolean) {
+        this.buf.push(v ? 1 : 0);
+    }
+
+    int(v: number | bigint, size: number) {
+        let n = BigInt.asUintN(size * 8, BigInt(v));
+        for (let i = 0; i < size; i++) {
+            this.buf.push(Number(n & 0xffn));
+            n >>= 8n;
+        }
+    }
+
+    fixed(v: Uint8Array, size: number) {
+        if (v.length !== size) {
+            throw new Error(`expected ${size} bytes, got ${v.length}`);
+        }
+        this.buf.push(...v);
+    }
+
+    bytes(v: Uint8Array) {
+        this.length(v.length);
+        this.buf.push(...v);
+    }
+
+    string(v: string) {
+        this.bytes(new TextEncoder().encode(v));
+    }
+
+    array<T>(v: T[], length: number, f: (x: T) => void) {
+        if (v.length !== length) {
+            throw new Error(`expected ${length} elements, got ${v.length}`);
+        }
+        v.forEach(f);
+    }
+
+    vector<T>(v: T[], f: (x: T) => void) {
+        this.length(v.length);
+        v.forEach(f);
+    }
+
+    finish(): Uint8Array {
+        return new Uint8Array(this.buf);
+    }
+"#;
+
+const SCALE_LENGTH: &str = r#"
+    length(n: number) {
+        if (n < 1 << 6) {
+            this.int(n << 2, 1);
+        } else if (n < 1 << 14) {
+            this.int((n << 2) | 1, 2);
+        } else if (n < 1 << 30) {
+            this.int((n << 2) | 2, 4);
+        } else {
+            this.int(3, 1);
+            this.int(n, 4);
+        }
+    }
+}
+
+class Decoder {
+    private offset = 0;
+
+    constructor(private data: Uint8Array) {}
+
+    length(): number {
+        const mode = this.data[this.offset] & 3;
+        if (mode === 0) {
+            return this.number(1, false) >> 2;
+        } else if (mode === 1) {
+            return this.number(2, false) >> 2;
+        } else if (mode === 2) {
+            return this.number(4, false) >>> 2;
+        }
+        this.offset += 1;
+        return this.number(4, false);
+    }
+"#;
+
+const BORSH_LENGTH: &str = r#"
+    length(n: number) {
+        this.int(n, 4);
+    }
+}
+
+class Decoder {
+    private offset = 0;
+
+    constructor(private data: Uint8Array) {}
+
+    length(): number {
+        return this.number(4, false);
+    }
+"#;
+
+const DECODER: &str = r#"
+    bool(): boolean {
+        return this.fixed(1)[0] !== 0;
+    }
+
+    bigint(size: number, signed: boolean): bigint {
+        const bytes = this.fixed(size);
+        let n = 0n;
+        for (let i = size - 1; i >= 0; i--) {
+            n = (n << 8n) | BigInt(bytes[i]);
+        }
+        return signed ? BigInt.asIntN(size * 8, n) : n;
+    }
+
+    number(size: number, signed: boolean): number {
+        return Number(this.bigint(size, signed));
+    }
+
+    fixed(size: number): Uint8Array {
+        if (this.offset + size > this.data.length) {
+            throw new Error('unexpected end of data');
+        }
+        const v = this.data.slice(this.offset, this.offset + size);
+        this.offset += size;
+        return v;
+    }
+
+    bytes(): Uint8Array {
+        return this.fixed(this.length());
+    }
+
+    string(): string {
+        return new TextDecoder().decode(this.bytes());
+    }
+
+    array<T>(length: number, f: () => T): T[] {
+        return Array.from({ length }, f);
+    }
+
+    vector<T>(f: () => T): T[] {
+        return this.array(this.length(), f);
+    }
+}
+
+function equal(a: Uint8Array, b: number[]): boolean {
+    return a.length === b.length && a.every((v, i) => v === b[i]);
+}
+"#;
+
+const POLKADOT_CALL: &str = r#"
+/** The call data of a message, and the decoder for its return value */
+export interface Call<T> {
+    data: Uint8Array;
+    decode(data: Uint8Array): T;
+}
+
+export interface CallOptions {
+    value?: bigint;
+    gasLimit?: unknown;
+    storageDepositLimit?: bigint | null;
+}
+
+/** The contract reverted. The data can be decoded with the error decoder of the contract */
+export class Revert extends Error {
+    constructor(readonly data: Uint8Array) {
+        super('contract reverted');
+    }
+}
+
+/** Create a transaction which calls a message of the contract */
+export function tx<T>(api: ApiPromise, address: string, call: Call<T>, gasLimit: unknown, options: CallOptions = {}) {
+    return api.tx.contracts.call(address, options.value ?? 0n, gasLimit as never, options.storageDepositLimit ?? null, call.data);
+}
+
+/** Call a message of the contract without submitting a transaction, and decode its return value */
+export async function query<T>(api: ApiPromise, origin: string, address: string, call: Call<T>, options: CallOptions = {}): Promise<T> {
+    const { result } = await api.call.contractsApi.call(origin, address, options.value ?? 0n, (options.gasLimit ?? null) as never, options.storageDepositLimit ?? null, call.data);
+    if (result.isErr) {
+        throw new Error(result.asErr.toString());
+    }
+    const data = result.asOk.data.toU8a(true);
+    if (result.asOk.flags.isRevert) {
+        throw new Revert(data);
+    }
+    return call.decode(data);
+}
+"#;
+
+const SOLANA_CALL: &str = r#"
+/** The instruction for a function, and the decoder for its return data */
+export interface Call<T> {
+    instruction: TransactionInstruction;
+    decode(data: Uint8Array): T;
+}
+"#;
+
+/// Generate TypeScript bindings. On Polkadot, these produce the call data for polkadot.js,
+/// and on Solana the `TransactionInstruction` for `@solana/web3.js`.
+pub(super) fn generate(bindings: &Bindings) -> String {
+    let ns = bindings.ns;
+    let solana = ns.target == Target::Solana;
+    let mut out = String::new();
+
+    writeln!(
+        out,
+        "// Bindings for contract {}, generated by Solang. Do not edit.\n",
+        bindings.name
+    )
+    .unwrap();
+
+    if solana {
+        out.push_str("import { PublicKey, TransactionInstruction } from '@solana/web3.js';\n\n");
+    } else {
+        out.push_str("import type { ApiPromise } from '@polkadot/api';\n\n");
+    }
+
+    out.push_str(PRELUDE);
+    out.push_str(if solana { BORSH_LENGTH } else { SCALE_LENGTH });
+    out.push_str(DECODER);
+    out.push_str(if solana { SOLANA_CALL } else { POLKADOT_CALL });
+
+    writeln!(
+        out,
+        "\n/** An external function, which is encoded as its {} */",
+        if solana {
+            "selector and program id"
+        } else {
+            "address and selector"
+        }
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "export interface ExternalFunction {{\n    address: {};\n    selector: Uint8Array;\n}}",
+        if solana { "PublicKey" } else { "Uint8Array" }
+    )
+    .unwrap();
+
+    for (enum_no, name) in &bindings.enums {
+        let decl = &ns.enums[*enum_no];
+
+        writeln!(out).unwrap();
+        docs(&mut out, 0, &super::docs(&decl.tags));
+        writeln!(
+            out,
+            "export enum {name} {{\n{}\n}}",
+            decl.values.keys().map(|v| format!("    {v},")).join("\n")
+        )
+        .unwrap();
+    }
+
+    for (struct_ty, name) in &bindings.structs {
+        let decl = struct_ty.definition(ns);
+
+        writeln!(out).unwrap();
+        docs(&mut out, 0, &super::docs(&decl.tags));
+        writeln!(out, "export interface {name} {{").unwrap();
+
+        for field in &decl.fields {
+            writeln!(
+                out,
+                "    {}: {};",
+                field.name_as_str(),
+                ts_type(&field.ty, bindings)
+            )
+            .unwrap();
+        }
+
+        writeln!(out, "}}\n").unwrap();
+
+        writeln!(out, "function encode{name}($e: Encoder, v: {name}) {{").unwrap();
+
+        for field in &decl.fields {
+            writeln!(
+                out,
+                "    {}",
+                encode(
+                    &field.ty,
+                    &format!("v.{}", field.name_as_str()),
+                    0,
+                    bindings
+                )
+            )
+            .unwrap();
+        }
+
+        writeln!(out, "}}\n").unwrap();
+
+        writeln!(
+            out,
+            "function decode{name}($d: Decoder): {name} {{\n    return {{\n{}\n    }};\n}}",
+            decl.fields
+                .iter()
+                .map(|field| format!(
+                    "        {}: {},",
+                    field.name_as_str(),
+                    decode(&field.ty, bindings)
+                ))
+                .join("\n")
+        )
+        .unwrap();
+    }
+
+    writeln!(out).unwrap();
+
+    if solana {
+        docs(&mut out, 0, &bindings.docs);
+        writeln!(out, "export const {}Instructions = {{", bindings.name).unwrap();
+
+        for method in bindings.constructors.iter().chain(&bindings.functions) {
+            instruction(&mut out, method, bindings);
+        }
+
+        writeln!(out, "}};").unwrap();
+    } else {
+        writeln!(
+            out,
+            "/** The data for instantiating {} with each of its constructors */",
+            bindings.name
+        )
+        .unwrap();
+        writeln!(out, "export const {}Constructors = {{", bindings.name).unwrap();
+
+        for method in &bindings.constructors {
+            docs(&mut out, 1, &method.docs);
+            writeln!(
+                out,
+                "    {}({}): Uint8Array {{",
+                method.name,
+                params(&method.params, bindings)
+            )
+            .unwrap();
+            encode_call(&mut out, method, bindings);
+            writeln!(out, "        return $e.finish();\n    }},").unwrap();
+        }
+
+        writeln!(out, "}};\n").unwrap();
+
+        docs(&mut out, 0, &bindings.docs);
+        writeln!(out, "export const {}Messages = {{", bindings.name).unwrap();
+
+        for method in &bindings.functions {
+            docs(&mut out, 1, &method.docs);
+            writeln!(
+                out,
+                "    {}({}): Call<{}> {{",
+                method.name,
+                params(&method.params, bindings),
+                returns_type(&method.returns, bindings)
+            )
+            .unwrap();
+            encode_call(&mut out, method, bindings);
+            writeln!(
+                out,
+                "        return {{ data: $e.finish(), decode: {} }};\n    }},",
+                returns_decoder(&method.returns, bindings)
+            )
+            .unwrap();
+        }
+
+        writeln!(out, "}};").unwrap();
+    }
+
+    if !bindings.events.is_empty() {
+        events(&mut out, bindings);
+    }
+
+    if !bindings.errors.is_empty() {
+        errors(&mut out, bindings);
+    }
+
+    out
+}
+
+fn instruction(out: &mut String, method: &Method, bindings: &Bindings) {
+    let accounts = method
+        .accounts
+        .iter()
+        .filter(|account| account.address.is_none())
+        .map(|account| format!("{}: PublicKey", account.name))
+        .join("; ");
+
+    let mut params = vec!["programId: PublicKey".to_owned()];
+
+    if !accounts.is_empty() {
+        params.push(format!("accounts: {{ {accounts} }}"));
+    }
+
+    params.extend(method.params.iter().map(|param| {
+        format!(
+            "{}: {}",
+            identifier(&param.name),
+            ts_type(&param.ty, bindings)
+        )
+    }));
+
+    docs(out, 1, &method.docs);
+    writeln!(
+        out,
+        "    {}({}): Call<{}> {{",
+        method.name,
+        params.join(", "),
+        returns_type(&method.returns, bindings)
+    )
+    .unwrap();
+
+    encode_call(out, method, bindings);
+
+    writeln!(
+        out,
+        "        const keys = [\n{}\n        ];",
+        method
+            .accounts
+            .iter()
+            .map(|account| format!(
+                "            {{ pubkey: {}, isSigner: {}, isWritable: {} }},",
+                match account.address {
+                    Some(address) => format!("new PublicKey('{address}')"),
+                    None => format!("accounts.{}", account.name),
+                },
+                account.is_signer,
+                account.is_writer
+            ))
+            .join("\n")
+    )
+    .unwrap();
+
+    writeln!(
+        out,
+        "        const instruction = new TransactionInstruction({{ programId, keys, data: Buffer.from($e.finish()) }});"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "        return {{ instruction, decode: {} }};\n    }},",
+        returns_decoder(&method.returns, bindings)
+    )
+    .unwrap();
+}
+
+/// Encode the selector and arguments of a call into `$e`
+fn encode_call(out: &mut String, method: &Method, bindings: &Bindings) {
+    writeln!(out, "        const $e = new Encoder();").unwrap();
+    writeln!(
+        out,
+        "        $e.fixed(new Uint8Array([{}]), {});",
+        bytes(&method.selector),
+        method.selector.len()
+    )
+    .unwrap();
+
+    for param in &method.params {
+        writeln!(
+            out,
+            "        {}",
+            encode(&param.ty, &identifier(&param.name), 0, bindings)
+        )
+        .unwrap();
+    }
+}
+
+fn events(out: &mut String, bindings: &Bindings) {
+    let name = bindings.name;
+
+    writeln!(
+        out,
+        "\nexport type {name}Event =\n{};",
+        bindings
+            .events
+            .iter()
+            .map(|event| format!(
+                "    | {{ event: '{}'; args: {{ {} }} }}",
+                event.name,
+                fields_type(&event.fields, bindings)
+            ))
+            .join("\n")
+    )
+    .unwrap();
+
+    if bindings.ns.is_ink_v5() {
+        writeln!(
+            out,
+            "\n/** Decode the data of an event emitted by {name}, given its first topic */\nexport function decode{name}Event(data: Uint8Array, topic: Uint8Array): {name}Event | undefined {{\n    const $d = new Decoder(data);"
+        )
+        .unwrap();
+    } else {
+        writeln!(
+            out,
+            "\n/** Decode the data of an event emitted by {name} */\nexport function decode{name}Event(data: Uint8Array): {name}Event | undefined {{\n    const $d = new Decoder(data);"
+        )
+        .unwrap();
+    }
+
+    if bindings.ns.is_ink_v5() {
+        // anonymous events have no signature topic, so they cannot be identified
+        for event in bindings.events.iter().filter(|e| !e.selector.is_empty()) {
+            writeln!(
+                out,
+                "    if (equal(topic, [{}])) {{\n        return {{ event: '{}', args: {{ {} }} }};\n    }}",
+                bytes(&event.selector),
+                event.name,
+                fields_decoder(&event.fields, bindings)
+            )
+            .unwrap();
+        }
+    } else if bindings.ns.target == Target::Solana {
+        writeln!(out, "    const selector = $d.fixed(8);").unwrap();
+
+        for event in &bindings.events {
+            writeln!(
+                out,
+                "    if (equal(selector, [{}])) {{\n        return {{ event: '{}', args: {{ {} }} }};\n    }}",
+                bytes(&event.selector),
+                event.name,
+                fields_decoder(&event.fields, bindings)
+            )
+            .unwrap();
+        }
+    } else {
+        writeln!(out, "    switch ($d.number(1, false)) {{").unwrap();
+
+        for event in &bindings.events {
+            writeln!(
+                out,
+                "        case {}:\n            return {{ event: '{}', args: {{ {} }} }};",
+                event.selector[0],
+                event.name,
+                fields_decoder(&event.fields, bindings)
+            )
+            .unwrap();
+        }
+
+        writeln!(out, "    }}").unwrap();
+    }
+
+    writeln!(out, "    return undefined;\n}}").unwrap();
+}
+
+fn errors(out: &mut String, bindings: &Bindings) {
+    let name = bindings.name;
+
+    writeln!(
+        out,
+        "\nexport type {name}Error =\n{};",
+        bindings
+            .errors
+            .iter()
+            .map(|error| format!(
+                "    | {{ error: '{}'; args: {{ {} }} }}",
+                error.name,
+                fields_type(&error.fields, bindings)
+            ))
+            .join("\n")
+    )
+    .unwrap();
+
+    writeln!(
+        out,
+        "\n/** Decode the data returned by {name} when it reverts */\nexport function decode{name}Error(data: Uint8Array): {name}Error | undefined {{\n    const $d = new Decoder(data);\n    const selector = $d.fixed(4);"
+    )
+    .unwrap();
+
+    for error in &bindings.errors {
+        writeln!(
+            out,
+            "    if (equal(selector, [{}])) {{\n        return {{ error: '{}', args: {{ {} }} }};\n    }}",
+            bytes(&error.selector),
+            error.name,
+            fields_decoder(&error.fields, bindings)
+        )
+        .unwrap();
+    }
+
+    writeln!(out, "    return undefined;\n}}").unwrap();
+}
+
+fn params(params: &[Param], bindings: &Bindings) -> String {
+    params
+        .iter()
+        .map(|param| {
+            format!(
+                "{}: {}",
+                identifier(&param.name),
+                ts_type(&param.ty, bindings)
+            )
+        })
+        .join(", ")
+}
+
+fn fields_type(fields: &[Param], bindings: &Bindings) -> String {
+    fields
+        .iter()
+        .map(|field| format!("{}: {}", field.name, ts_type(&field.ty, bindings)))
+        .join("; ")
+}
+
+fn fields_decoder(fields: &[Param], bindings: &Bindings) -> String {
+    fields
+        .iter()
+        .map(|field| format!("{}: {}", field.name, decode(&field.ty, bindings)))
+        .join(", ")
+}
+
+/// Multiple return values are returned as a tuple
+fn returns_type(returns: &[Param], bindings: &Bindings) -> String {
+    match returns {
+        [] => "void".into(),
+        [ret] => ts_type(&ret.ty, bindings),
+        _ => format!(
+            "[{}]",
+            returns
+                .iter()
+                .map(|ret| ts_type(&ret.ty, bindings))
+                .join(", ")
+        ),
+    }
+}
+
+fn returns_decoder(returns: &[Param], bindings: &Bindings) -> String {
+    let decoders = returns
+        .iter()
+        .map(|ret| decode(&ret.ty, bindings))
+        .collect::<Vec<_>>();
+
+    match &decoders[..] {
+        [] => "() => undefined".into(),
+        [decoder] => {
+            format!("(data: Uint8Array) => {{ const $d = new Decoder(data); return {decoder}; }}")
+        }
+        _ => format!(
+            "(data: Uint8Array): {} => {{ const $d = new Decoder(data); return [{}]; }}",
+            returns_type(returns, bindings),
+            decoders.join(", ")
+        ),
+    }
+}
+
+fn ts_type(ty: &Type, bindings: &Bindings) -> String {
+    let ns = bindings.ns;
+
+    if let Some((elem_ty, _)) = array_element(ty) {
+        return format!("{}[]", ts_type(&elem_ty, bindings));
+    }
+
+    match ty.clone().unwrap_user_type(ns) {
+        Type::Bool => "boolean".into(),
+        Type::Uint(width) | Type::Int(width) if int_size(width) <= 4 => "number".into(),
+        Type::Uint(_) | Type::Int(_) | Type::Value => "bigint".into(),
+        Type::Address(_) | Type::Contract(_) if ns.target == Target::Solana => "PublicKey".into(),
+        Type::Address(_)
+        | Type::Contract(_)
+        | Type::Bytes(_)
+        | Type::DynamicBytes
+        | Type::FunctionSelector => "Uint8Array".into(),
+        Type::String => "string".into(),
+        Type::Enum(enum_no) => bindings.enum_name(enum_no).into(),
+        Type::Struct(struct_ty) => bindings.struct_name(&struct_ty).into(),
+        Type::ExternalFunction { .. } => "ExternalFunction".into(),
+        ty => unreachable!("{ty:?} cannot be a parameter"),
+    }
+}
+
+/// The statement which encodes `value` into `$e`
+fn encode(ty: &Type, value: &str, depth: usize, bindings: &Bindings) -> String {
+    let ns = bindings.ns;
+    let solana = ns.target == Target::Solana;
+
+    if let Some((elem_ty, len)) = array_element(ty) {
+        let x = format!("x{depth}");
+        let elem = encode(&elem_ty, &x, depth + 1, bindings);
+
+        return match len {
+            Some(len) => format!("$e.array({value}, {len}, ({x}) => {{ {elem} }});"),
+            None => format!("$e.vector({value}, ({x}) => {{ {elem} }});"),
+        };
+    }
+
+    match ty.clone().unwrap_user_type(ns) {
+        Type::Bool => format!("$e.bool({value});"),
+        Type::Uint(width) | Type::Int(width) => format!("$e.int({value}, {});", int_size(width)),
+        Type::Value => format!("$e.int({value}, {});", ns.value_length),
+        Type::Address(_) | Type::Contract(_) if solana => {
+            format!("$e.fixed({value}.toBytes(), 32);")
+        }
+        Type::Address(_) | Type::Contract(_) => {
+            format!("$e.fixed({value}, {});", ns.address_length)
+        }
+        Type::Bytes(len) => format!("$e.fixed({value}, {len});"),
+        Type::FunctionSelector => format!("$e.fixed({value}, {});", bindings.selector_length()),
+        Type::String => format!("$e.string({value});"),
+        Type::DynamicBytes => format!("$e.bytes({value});"),
+        Type::Enum(_) => format!("$e.int({value}, 1);"),
+        Type::Struct(struct_ty) => {
+            format!("encode{}($e, {value});", bindings.struct_name(&struct_ty))
+        }
+        Type::ExternalFunction { .. } if solana => {
+            format!("$e.fixed({value}.selector, 8); $e.fixed({value}.address.toBytes(), 32);")
+        }
+        Type::ExternalFunction { .. } => format!(
+            "$e.fixed({value}.address, {}); $e.fixed({value}.selector, 4);",
+            ns.address_length
+        ),
+        ty => unreachable!("{ty:?} cannot be a parameter"),
+    }
+}
+
+/// The expression which decodes a value from `$d`
+fn decode(ty: &Type, bindings: &Bindings) -> String {
+    let ns = bindings.ns;
+    let solana = ns.target == Target::Solana;
+
+    if let Some((elem_ty, len)) = array_element(ty) {
+        let elem = decode(&elem_ty, bindings);
+
+        return match len {
+            Some(len) => format!("$d.array({len}, () => {elem})"),
+            None => format!("$d.vector(() => {elem})"),
+        };
+    }
+
+    match ty.clone().unwrap_user_type(ns) {
+        Type::Bool => "$d.bool()".into(),
+        Type::Uint(width) if int_size(width) <= 4 => {
+            format!("$d.number({}, false)", int_size(width))
+        }
+        Type::Int(width) if int_size(width) <= 4 => format!("$d.number({}, true)", int_size(width)),
+        Type::Uint(width) => format!("$d.bigint({}, false)", int_size(width)),
+        Type::Int(width) => format!("$d.bigint({}, true)", int_size(width)),
+        Type::Value => format!("$d.bigint({}, false)", ns.value_length),
+        Type::Address(_) | Type::Contract(_) if solana => "new PublicKey($d.fixed(32))".into(),
+        Type::Address(_) | Type::Contract(_) => format!("$d.fixed({})", ns.address_length),
+        Type::Bytes(len) => format!("$d.fixed({len})"),
+        Type::FunctionSelector => format!("$d.fixed({})", bindings.selector_length()),
+        Type::String => "$d.string()".into(),
+        Type::DynamicBytes => "$d.bytes()".into(),
+        Type::Enum(_) => "$d.number(1, false)".into(),
+        Type::Struct(struct_ty) => format!("decode{}($d)", bindings.struct_name(&struct_ty)),
+        Type::ExternalFunction { .. } if solana => {
+            "{ selector: $d.fixed(8), address: new PublicKey($d.fixed(32)) }".into()
+        }
+        Type::ExternalFunction { .. } => format!(
+            "{{ address: $d.fixed({}), selector: $d.fixed(4) }}",
+            ns.address_length
+        ),
+        ty => unreachable!("{ty:?} cannot be a parameter"),
+    }
+}
+
+fn identifier(name: &str) -> String {
+    if RESERVED.contains(&name) {
+        format!("{name}_")
+    } else {
+        name.to_owned()
+    }
+}
+
+fn bytes(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:#04x}")).join(", ")
+}
+
+fn docs(out: &mut String, indent: usize, docs: &[String]) {
+    if docs.is_empty() {
+        return;
+    }
+
+    let indent = " ".repeat(indent * 4);
+
+    writeln!(out, "{indent}/**").unwrap();
+
+    for line in docs {
+        writeln!(out, "{indent} * {line}").unwrap();
+    }
+
+    writeln!(out, "{indent} */").unwrap();
+}
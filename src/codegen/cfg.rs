@@ -4,15 +4,15 @@ use super::statements::{statement, LoopScopes};
 use super::{
     constant_folding, dead_storage,
     expression::expression,
-    reaching_definitions, strength_reduce,
+    inline, loops, reaching_definitions, storage_cache, strength_reduce,
     vartable::{Vars, Vartable},
     vector_to_slice, Options,
 };
 use crate::codegen::subexpression_elimination::common_sub_expression_elimination;
 use crate::codegen::{undefined_variable, Expression, LLVMName};
 use crate::sema::ast::{
-    CallTy, Contract, ExternalCallAccounts, FunctionAttributes, Namespace, Parameter, RetrieveType,
-    Statement, StringLocation, StructType, Type,
+    CallTy, Contract, ExternalCallAccounts, FunctionAttributes, Layout, Namespace, Parameter,
+    RetrieveType, Statement, StringLocation, StructType, Type,
 };
 use crate::sema::{contracts::collect_base_args, diagnostics::Diagnostics, Recurse};
 use crate::{sema::ast, Target};
@@ -1469,6 +1469,7 @@ pub fn generate_cfg(
     function_no: Option<usize>,
     cfg_no: usize,
     all_cfgs: &mut Vec<ControlFlowGraph>,
+    inline_bodies: &mut HashMap<usize, ControlFlowGraph>,
     ns: &mut Namespace,
     opt: &Options,
 ) {
@@ -1480,6 +1481,8 @@ pub fn generate_cfg(
     let ast_fn = function_no
         .map(ASTFunction::SolidityFunction)
         .unwrap_or(ASTFunction::None);
+    // the inliner needs the cfg before the arguments are propagated by the optimization passes
+    let mut body = inline::enabled(opt).then(|| cfg.clone());
     optimize_and_check_cfg(&mut cfg, ns, ast_fn, opt);
 
     if let Some(func_no) = function_no {
@@ -1498,6 +1501,10 @@ pub fn generate_cfg(
 
                 all_cfgs.push(cfg);
 
+                if let Some(body) = body {
+                    inline_bodies.insert(modifier_cfg_no, body);
+                }
+
                 cfg = generate_modifier_dispatch(
                     contract_no,
                     func_no,
@@ -1506,6 +1513,7 @@ pub fn generate_cfg(
                     ns,
                     opt,
                 );
+                body = inline::enabled(opt).then(|| cfg.clone());
                 optimize_and_check_cfg(&mut cfg, ns, ast_fn, opt);
             }
 
@@ -1516,6 +1524,10 @@ pub fn generate_cfg(
         }
     }
 
+    if let Some(body) = body {
+        inline_bodies.insert(cfg_no, body);
+    }
+
     all_cfgs[cfg_no] = cfg;
 }
 
@@ -1573,9 +1585,15 @@ pub fn optimize_and_check_cfg(
     if opt.strength_reduce {
         strength_reduce::strength_reduce(cfg, ns);
     }
+    if opt.storage_cache {
+        storage_cache::storage_cache(cfg, ns);
+    }
     if opt.dead_storage {
         dead_storage::dead_storage(cfg, ns);
     }
+    if opt.loop_optimization {
+        loops::loop_optimization(cfg, ns);
+    }
 
     // If the function is a default constructor, there is nothing to optimize.
     if opt.common_subexpression_elimination && func_no != ASTFunction::None {
@@ -2110,6 +2128,13 @@ impl Contract {
             panic!("get_storage_slot called on non-storage variable");
         }
     }
+
+    /// Get the layout of a variable, if it is packed with other variables into a single storage slot
+    pub fn get_packed_layout(&self, var_contract_no: usize, var_no: usize) -> Option<&Layout> {
+        self.layout
+            .iter()
+            .find(|l| l.contract_no == var_contract_no && l.var_no == var_no && l.packed.is_some())
+    }
 }
 
 impl Namespace {
@@ -1187,6 +1187,15 @@ fn zero_ext(
             },
             true,
         )
+    } else if let Expression::BoolLiteral { value, .. } = expr.0 {
+        (
+            Expression::NumberLiteral {
+                loc: *loc,
+                ty: ty.clone(),
+                value: BigInt::from(value as u8),
+            },
+            true,
+        )
     } else {
         (
             Expression::ZeroExt {
@@ -0,0 +1,620 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Estimate the cost of executing the public functions of a contract, without running them.
+//! Each instruction is given a cost by the cost model of the target. The cost of a loop is the
+//! cost of its body multiplied by the number of iterations, and the cost of a function is the
+//! cost of the most expensive path through it. When the number of iterations depends on a value
+//! which is only known at runtime, like the length of an array, the cost is a formula over that
+//! value.
+
+mod model;
+mod tests;
+
+use super::cfg::{ASTFunction, ControlFlowGraph, Instr, InternalCallTy};
+use super::loops::{Loop, TripCount};
+use super::{reaching_definitions, Expression};
+use crate::codegen::Builtin;
+use crate::sema::ast::{Contract, Namespace};
+use model::CostModel;
+use num_traits::ToPrimitive;
+use serde::Serialize;
+use solang_parser::pt::{CodeLocation, FunctionTy, Loc};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+/// A cost which may depend on values which are only known at runtime. The cost is the sum of
+/// the terms; each term is its coefficient multiplied by the values of its symbols. The term
+/// without any symbols is the constant part.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cost {
+    terms: BTreeMap<Vec<String>, u64>,
+    /// There is no upper bound, e.g. because a function is recursive
+    unbounded: bool,
+}
+
+impl Cost {
+    pub fn constant(value: u64) -> Self {
+        let mut cost = Cost::default();
+
+        cost.add_term(Vec::new(), value);
+
+        cost
+    }
+
+    pub fn unbounded() -> Self {
+        Cost {
+            terms: BTreeMap::new(),
+            unbounded: true,
+        }
+    }
+
+    fn add_term(&mut self, symbols: Vec<String>, coefficient: u64) {
+        if coefficient != 0 {
+            let entry = self.terms.entry(symbols).or_default();
+
+            *entry = entry.saturating_add(coefficient);
+        }
+    }
+
+    pub fn add(&mut self, other: &Cost) {
+        self.unbounded |= other.unbounded;
+
+        for (symbols, coefficient) in &other.terms {
+            self.add_term(symbols.clone(), *coefficient);
+        }
+    }
+
+    /// Make this cost an upper bound of both costs, by taking the largest coefficient of
+    /// each term
+    pub fn max(&mut self, other: &Cost) {
+        self.unbounded |= other.unbounded;
+
+        for (symbols, coefficient) in &other.terms {
+            let entry = self.terms.entry(symbols.clone()).or_default();
+
+            *entry = (*entry).max(*coefficient);
+        }
+    }
+
+    /// The cost of executing this a fixed number of times
+    pub fn scale(&self, count: u64) -> Cost {
+        let mut cost = Cost {
+            terms: BTreeMap::new(),
+            unbounded: self.unbounded,
+        };
+
+        for (symbols, coefficient) in &self.terms {
+            cost.add_term(symbols.clone(), coefficient.saturating_mul(count));
+        }
+
+        cost
+    }
+
+    /// The cost of executing this as many times as the value of the symbol
+    pub fn times(&self, symbol: &str) -> Cost {
+        let mut cost = Cost {
+            terms: BTreeMap::new(),
+            unbounded: self.unbounded,
+        };
+
+        for (symbols, coefficient) in &self.terms {
+            let mut symbols = symbols.clone();
+
+            symbols.push(symbol.to_owned());
+            symbols.sort();
+
+            cost.add_term(symbols, *coefficient);
+        }
+
+        cost
+    }
+
+    /// Rename all the symbols
+    fn rename(&self, f: impl Fn(&str) -> String) -> Cost {
+        let mut cost = Cost {
+            terms: BTreeMap::new(),
+            unbounded: self.unbounded,
+        };
+
+        for (symbols, coefficient) in &self.terms {
+            let mut symbols: Vec<String> = symbols.iter().map(|symbol| f(symbol)).collect();
+
+            symbols.sort();
+
+            cost.add_term(symbols, *coefficient);
+        }
+
+        cost
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        self.unbounded
+    }
+
+    /// The cost, if it does not depend on any runtime values
+    pub fn constant_value(&self) -> Option<u64> {
+        if self.unbounded || self.terms.keys().any(|symbols| !symbols.is_empty()) {
+            None
+        } else {
+            Some(self.terms.get(&Vec::new()).copied().unwrap_or_default())
+        }
+    }
+
+    /// The runtime values the cost depends on
+    pub fn symbols(&self) -> BTreeSet<&str> {
+        self.terms
+            .keys()
+            .flat_map(|symbols| symbols.iter().map(String::as_str))
+            .collect()
+    }
+}
+
+impl fmt::Display for Cost {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.unbounded {
+            return write!(f, "unbounded");
+        }
+
+        if self.terms.is_empty() {
+            return write!(f, "0");
+        }
+
+        // the constant first, then the terms with the fewest symbols
+        let mut terms: Vec<_> = self.terms.iter().collect();
+        terms.sort_by_key(|(symbols, _)| symbols.len());
+
+        for (term_no, (symbols, coefficient)) in terms.into_iter().enumerate() {
+            if term_no != 0 {
+                write!(f, " + ")?;
+            }
+
+            write!(f, "{coefficient}")?;
+
+            for symbol in symbols {
+                write!(f, " * {symbol}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The estimated cost of every public function of a contract
+#[derive(Debug, Serialize)]
+pub struct CostReport {
+    pub contract: String,
+    pub target: String,
+    pub unit: String,
+    pub functions: Vec<FunctionCost>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FunctionCost {
+    pub function: String,
+    /// The worst case cost, if it does not depend on any values only known at runtime
+    pub cost: Option<u64>,
+    /// The cost as a formula over the values which bound the loops
+    pub estimate: String,
+    /// The values which bound the loops
+    pub symbols: Vec<String>,
+    /// The function calls itself, so no bound can be given
+    pub unbounded: bool,
+}
+
+impl fmt::Display for CostReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "contract {} ({}, {})",
+            self.contract, self.target, self.unit
+        )?;
+
+        let width = self
+            .functions
+            .iter()
+            .map(|function| function.function.len())
+            .chain(std::iter::once("function".len()))
+            .max()
+            .unwrap_or_default();
+
+        writeln!(f, "  {:width$}  cost", "function")?;
+
+        for function in &self.functions {
+            writeln!(f, "  {:width$}  {}", function.function, function.estimate)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Estimate the cost of every public function of the contract. Returns `None` if there is no
+/// cost model for the target.
+pub fn cost_report(contract_no: usize, ns: &Namespace) -> Option<CostReport> {
+    let model = CostModel::new(ns.target)?;
+    let contract = &ns.contracts[contract_no];
+    let mut estimator = Estimator::new(model, contract, ns);
+    let mut functions = Vec::new();
+
+    for (cfg_no, cfg) in contract.cfg.iter().enumerate() {
+        if !cfg.public || cfg.is_placeholder() {
+            continue;
+        }
+
+        let ASTFunction::SolidityFunction(func_no) = cfg.function_no else {
+            continue;
+        };
+
+        let func = &ns.functions[func_no];
+
+        let function = match func.ty {
+            FunctionTy::Fallback | FunctionTy::Receive => func.ty.to_string(),
+            FunctionTy::Constructor if func.id.name.is_empty() => {
+                format!("constructor{}", func.signature)
+            }
+            _ => func.signature.clone(),
+        };
+
+        let cost = estimator.function(cfg_no);
+
+        functions.push(FunctionCost {
+            function,
+            cost: cost.constant_value(),
+            estimate: cost.to_string(),
+            symbols: cost.symbols().into_iter().map(str::to_owned).collect(),
+            unbounded: cost.is_unbounded(),
+        });
+    }
+
+    Some(CostReport {
+        contract: contract.id.name.clone(),
+        target: ns.target.to_string(),
+        unit: model.unit().to_owned(),
+        functions,
+    })
+}
+
+struct Estimator<'a> {
+    model: CostModel,
+    contract: &'a Contract,
+    ns: &'a Namespace,
+    /// The cost of the functions which have been estimated
+    costs: HashMap<usize, Cost>,
+    /// The functions which are being estimated; calling one of these again is recursion
+    visiting: HashSet<usize>,
+    /// The functions which can be called through a function pointer
+    pointer_targets: BTreeSet<usize>,
+}
+
+impl<'a> Estimator<'a> {
+    fn new(model: CostModel, contract: &'a Contract, ns: &'a Namespace) -> Self {
+        let mut pointer_targets = BTreeSet::new();
+
+        for instr in contract
+            .cfg
+            .iter()
+            .flat_map(|cfg| cfg.blocks.iter())
+            .flat_map(|block| block.instr.iter())
+        {
+            instr.recurse_expressions(&mut pointer_targets, function_pointers);
+        }
+
+        Estimator {
+            model,
+            contract,
+            ns,
+            costs: HashMap::new(),
+            visiting: HashSet::new(),
+            pointer_targets,
+        }
+    }
+
+    /// The cost of executing the function, including the functions it calls
+    fn function(&mut self, cfg_no: usize) -> Cost {
+        if let Some(cost) = self.costs.get(&cfg_no) {
+            return cost.clone();
+        }
+
+        if !self.visiting.insert(cfg_no) {
+            return Cost::unbounded();
+        }
+
+        let mut cfg = self.contract.cfg[cfg_no].clone();
+
+        reaching_definitions::refresh(&mut cfg);
+
+        let blocks = cfg
+            .blocks
+            .iter()
+            .map(|block| {
+                let mut cost = Cost::default();
+
+                for instr in &block.instr {
+                    cost.add(&Cost::constant(self.model.instruction(instr, self.ns)));
+
+                    match instr {
+                        Instr::Call {
+                            call: InternalCallTy::Static { cfg_no },
+                            ..
+                        } => cost.add(&self.call(*cfg_no)),
+                        // any function whose address was taken might be called
+                        Instr::Call {
+                            call: InternalCallTy::Dynamic(_),
+                            ..
+                        } => {
+                            let mut worst = Cost::default();
+
+                            for cfg_no in self.pointer_targets.clone() {
+                                worst.max(&self.call(cfg_no));
+                            }
+
+                            cost.add(&worst);
+                        }
+                        _ => (),
+                    }
+                }
+
+                cost
+            })
+            .collect();
+
+        let cost = self.paths(&cfg, blocks);
+
+        self.visiting.remove(&cfg_no);
+        self.costs.insert(cfg_no, cost.clone());
+
+        cost
+    }
+
+    /// The cost of calling another function. The symbols of the callee are qualified with its
+    /// name, since they refer to its parameters and variables.
+    fn call(&mut self, cfg_no: usize) -> Cost {
+        let cost = self.function(cfg_no);
+
+        let name = match self.contract.cfg[cfg_no].function_no {
+            ASTFunction::SolidityFunction(func_no) => self.ns.functions[func_no].id.name.clone(),
+            ASTFunction::YulFunction(func_no) => self.ns.yul_functions[func_no].name.clone(),
+            ASTFunction::None => self.contract.cfg[cfg_no].name.clone(),
+        };
+
+        cost.rename(|symbol| {
+            if symbol.starts_with("loop@") {
+                symbol.to_owned()
+            } else {
+                format!("{name}.{symbol}")
+            }
+        })
+    }
+
+    /// Collapse each loop into its header, innermost loops first, so that the header has the
+    /// cost of the whole loop. Then find the most expensive path from the entry block.
+    fn paths(&self, cfg: &ControlFlowGraph, mut costs: Vec<Cost>) -> Cost {
+        let succs: Vec<Vec<usize>> = cfg.blocks.iter().map(|block| block.successors()).collect();
+        // the header of the outermost loop which has been collapsed, which each block is part of
+        let mut rep: Vec<usize> = (0..cfg.blocks.len()).collect();
+
+        for lp in cfg.loops() {
+            let members: BTreeSet<usize> =
+                lp.blocks.iter().map(|block_no| rep[*block_no]).collect();
+
+            let edges = edges(&succs, &rep, |from, to| {
+                from != to && to != lp.header && members.contains(&from) && members.contains(&to)
+            });
+
+            let iteration = longest_path(lp.header, &costs, &edges);
+
+            // the loop condition is evaluated once more when the loop finishes
+            let mut total = self.repeat(cfg, &lp, &iteration);
+            total.add(&iteration);
+
+            costs[lp.header] = total;
+
+            for block_no in &lp.blocks {
+                rep[*block_no] = lp.header;
+            }
+        }
+
+        let edges = edges(&succs, &rep, |from, to| from != to);
+
+        longest_path(rep[0], &costs, &edges)
+    }
+
+    /// The cost of executing every iteration of the loop
+    fn repeat(&self, cfg: &ControlFlowGraph, lp: &Loop, iteration: &Cost) -> Cost {
+        match lp.trip_count(cfg) {
+            TripCount::Constant(count) => iteration.scale(count.to_u64().unwrap_or(u64::MAX)),
+            TripCount::Bounded(expr, extra) => {
+                let symbol = self
+                    .describe(cfg, lp, &expr, 0)
+                    .unwrap_or_else(|| self.loop_symbol(cfg, lp));
+
+                let mut cost = iteration.times(&symbol);
+
+                cost.add(&iteration.scale(extra.to_u64().unwrap_or_default()));
+
+                cost
+            }
+            TripCount::Unknown => iteration.times(&self.loop_symbol(cfg, lp)),
+        }
+    }
+
+    /// A readable name for the value of the expression, like the name of the parameter or
+    /// the array whose length it is
+    fn describe(
+        &self,
+        cfg: &ControlFlowGraph,
+        lp: &Loop,
+        expr: &Expression,
+        depth: usize,
+    ) -> Option<String> {
+        if depth > 8 {
+            return None;
+        }
+
+        match expr {
+            Expression::FunctionArg { arg_no, .. } => {
+                let name = cfg.params.get(*arg_no)?.name_as_str();
+
+                (!name.is_empty()).then(|| name.to_owned())
+            }
+            Expression::Variable { var_no, .. } => {
+                // follow the variable to its only definition
+                let defs = cfg.blocks[lp.header].defs.get(var_no);
+
+                if let Some(def) = defs
+                    .filter(|defs| defs.len() == 1)
+                    .and_then(|defs| defs.keys().next())
+                {
+                    let described = match &cfg.blocks[def.block_no].instr[def.instr_no] {
+                        Instr::Set { expr, .. } => self.describe(cfg, lp, expr, depth + 1),
+                        Instr::LoadStorage { storage, .. } => self.storage_variable(storage),
+                        _ => None,
+                    };
+
+                    if described.is_some() {
+                        return described;
+                    }
+                }
+
+                let name = &cfg.vars.get(var_no)?.id.name;
+
+                (!name.contains('.')).then(|| name.clone())
+            }
+            Expression::Builtin {
+                kind: Builtin::ArrayLength,
+                args,
+                ..
+            } => self
+                .describe(cfg, lp, &args[0], depth + 1)
+                .map(|name| format!("{name}.length")),
+            Expression::StorageArrayLength { array, .. } => self
+                .storage_variable(array)
+                .map(|name| format!("{name}.length")),
+            Expression::ZeroExt { expr, .. }
+            | Expression::SignExt { expr, .. }
+            | Expression::Trunc { expr, .. }
+            | Expression::Cast { expr, .. }
+            | Expression::Load { expr, .. } => self.describe(cfg, lp, expr, depth + 1),
+            _ => None,
+        }
+    }
+
+    /// The name of the state variable at a storage slot
+    fn storage_variable(&self, storage: &Expression) -> Option<String> {
+        let Expression::NumberLiteral { value, .. } = storage else {
+            return None;
+        };
+
+        self.contract
+            .layout
+            .iter()
+            .find(|layout| layout.slot == *value && layout.packed.is_none())
+            .map(|layout| {
+                self.ns.contracts[layout.contract_no].variables[layout.var_no]
+                    .name
+                    .clone()
+            })
+    }
+
+    /// A name for the number of iterations of a loop which cannot be described otherwise,
+    /// based on the line of the loop condition
+    fn loop_symbol(&self, cfg: &ControlFlowGraph, lp: &Loop) -> String {
+        let loc = std::iter::once(lp.header)
+            .chain(lp.latches.iter().copied())
+            .filter_map(|block_no| match cfg.blocks[block_no].instr.last() {
+                Some(Instr::BranchCond { cond, .. }) => Some(cond.loc()),
+                _ => None,
+            })
+            .chain(lp.blocks.iter().flat_map(|block_no| {
+                cfg.blocks[*block_no]
+                    .instr
+                    .iter()
+                    .filter_map(|instr| match instr {
+                        Instr::Set { loc, .. } => Some(*loc),
+                        _ => None,
+                    })
+            }))
+            .find(|loc| matches!(loc, Loc::File(..)));
+
+        match loc {
+            Some(Loc::File(file_no, start, _)) => {
+                let (line, _) = self.ns.files[file_no].offset_to_line_column(start);
+
+                format!("loop@{}", line + 1)
+            }
+            _ => format!("loop@{}", lp.header),
+        }
+    }
+}
+
+/// The edges between the blocks which represent the collapsed loops
+fn edges(
+    succs: &[Vec<usize>],
+    rep: &[usize],
+    keep: impl Fn(usize, usize) -> bool,
+) -> BTreeMap<usize, BTreeSet<usize>> {
+    let mut edges: BTreeMap<usize, BTreeSet<usize>> = BTreeMap::new();
+
+    for (block_no, succs) in succs.iter().enumerate() {
+        for succ in succs {
+            let (from, to) = (rep[block_no], rep[*succ]);
+
+            if keep(from, to) {
+                edges.entry(from).or_default().insert(to);
+            }
+        }
+    }
+
+    edges
+}
+
+/// The most expensive path from the start block. If the edges still contain a cycle, the
+/// cost is unbounded.
+fn longest_path(start: usize, costs: &[Cost], edges: &BTreeMap<usize, BTreeSet<usize>>) -> Cost {
+    fn visit(
+        block_no: usize,
+        costs: &[Cost],
+        edges: &BTreeMap<usize, BTreeSet<usize>>,
+        done: &mut HashMap<usize, Cost>,
+        on_path: &mut HashSet<usize>,
+    ) -> Cost {
+        if let Some(cost) = done.get(&block_no) {
+            return cost.clone();
+        }
+
+        if !on_path.insert(block_no) {
+            return Cost::unbounded();
+        }
+
+        let mut worst = Cost::default();
+
+        for succ in edges.get(&block_no).into_iter().flatten() {
+            worst.max(&visit(*succ, costs, edges, done, on_path));
+        }
+
+        on_path.remove(&block_no);
+
+        let mut cost = costs[block_no].clone();
+
+        cost.add(&worst);
+
+        done.insert(block_no, cost.clone());
+
+        cost
+    }
+
+    visit(
+        start,
+        costs,
+        edges,
+        &mut HashMap::new(),
+        &mut HashSet::new(),
+    )
+}
+
+fn function_pointers(expr: &Expression, targets: &mut BTreeSet<usize>) -> bool {
+    if let Expression::InternalFunctionCfg { cfg_no, .. } = expr {
+        targets.insert(*cfg_no);
+    }
+
+    true
+}
@@ -0,0 +1,387 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::codegen::cfg::Instr;
+use crate::codegen::{Builtin, Expression};
+use crate::sema::ast::{Namespace, RetrieveType, Type};
+use crate::Target;
+
+/// The size in bytes assumed for values whose length is only known at runtime, like the
+/// arguments to a hash function
+const DYNAMIC_BYTES: u64 = 64;
+
+// Solana compute unit costs, from the default compute budget of the Solana runtime
+const SOLANA_INSTRUCTION: u64 = 1;
+const SOLANA_SYSCALL: u64 = 100;
+const SOLANA_HASH: u64 = 85;
+const SOLANA_HASH_BYTES_PER_UNIT: u64 = 2;
+const SOLANA_MEM_OP: u64 = 10;
+const SOLANA_LOG: u64 = 100;
+const SOLANA_CLOCK_SYSVAR: u64 = 140;
+const SOLANA_INVOKE: u64 = 1_000;
+const SOLANA_CREATE_PROGRAM_ADDRESS: u64 = 1_500;
+/// Signature verification reads the ed25519 instruction from the instructions sysvar
+const SOLANA_SIGNATURE_VERIFY: u64 = 500;
+
+// Polkadot ref_time in picoseconds, estimated from the benchmarks of the contracts pallet
+const POLKADOT_INSTRUCTION: u64 = 1_000;
+const POLKADOT_HOST_FUNCTION: u64 = 350_000;
+const POLKADOT_GET_STORAGE: u64 = 25_000_000;
+const POLKADOT_SET_STORAGE: u64 = 100_000_000;
+const POLKADOT_CLEAR_STORAGE: u64 = 100_000_000;
+const POLKADOT_STORAGE_BYTE: u64 = 1_000;
+const POLKADOT_HASH: u64 = 500_000;
+const POLKADOT_HASH_BYTE: u64 = 3_500;
+const POLKADOT_CALL: u64 = 300_000_000;
+const POLKADOT_INSTANTIATE: u64 = 1_000_000_000;
+const POLKADOT_TRANSFER: u64 = 150_000_000;
+const POLKADOT_TERMINATE: u64 = 1_000_000_000;
+const POLKADOT_DEPOSIT_EVENT: u64 = 3_000_000;
+const POLKADOT_EVENT_TOPIC: u64 = 3_000_000;
+const POLKADOT_SIGNATURE_VERIFY: u64 = 50_000_000;
+
+/// The cost of executing instructions on a target. Internal function calls are not included,
+/// the cost of the callee is added by the caller of the model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CostModel {
+    /// Compute units of the Solana runtime
+    Solana,
+    /// Weight (ref_time) of the contracts pallet
+    Polkadot,
+}
+
+impl CostModel {
+    pub fn new(target: Target) -> Option<Self> {
+        match target {
+            Target::Solana => Some(CostModel::Solana),
+            Target::Polkadot { .. } => Some(CostModel::Polkadot),
+            Target::EVM | Target::Soroban => None,
+        }
+    }
+
+    /// The unit in which the costs are given
+    pub fn unit(self) -> &'static str {
+        match self {
+            CostModel::Solana => "compute units",
+            CostModel::Polkadot => "ref_time (ps)",
+        }
+    }
+
+    /// The cost of an instruction and all the expressions in it
+    pub fn instruction(self, instr: &Instr, ns: &Namespace) -> u64 {
+        let mut tally = Tally {
+            model: self,
+            ns,
+            total: 0,
+        };
+
+        instr.recurse_expressions(&mut tally, expression);
+
+        tally.total.saturating_add(self.own_instruction(instr, ns))
+    }
+
+    /// The cost of the instruction itself, without the expressions
+    fn own_instruction(self, instr: &Instr, ns: &Namespace) -> u64 {
+        match (self, instr) {
+            (_, Instr::Switch { cases, .. }) => self.ops(cases.len() as u64 + 1),
+            (_, Instr::Call { args, .. }) => self.ops(args.len() as u64 + 2),
+
+            (CostModel::Solana, Instr::LoadStorage { ty, .. })
+            | (CostModel::Solana, Instr::SetStorage { ty, .. })
+            | (CostModel::Solana, Instr::ClearStorage { ty, .. }) => {
+                if ty.is_dynamic(ns) {
+                    SOLANA_MEM_OP + self.ops(20)
+                } else {
+                    self.ops(words(ty, ns) + 2)
+                }
+            }
+            (CostModel::Polkadot, Instr::LoadStorage { ty, .. }) => {
+                POLKADOT_GET_STORAGE + POLKADOT_STORAGE_BYTE * bytes(ty, ns)
+            }
+            (CostModel::Polkadot, Instr::SetStorage { ty, .. }) => {
+                POLKADOT_SET_STORAGE + POLKADOT_STORAGE_BYTE * bytes(ty, ns)
+            }
+            (CostModel::Polkadot, Instr::ClearStorage { .. }) => POLKADOT_CLEAR_STORAGE,
+
+            (CostModel::Solana, Instr::SetStorageBytes { .. }) => self.ops(4),
+            (CostModel::Polkadot, Instr::SetStorageBytes { .. }) => {
+                POLKADOT_GET_STORAGE + POLKADOT_SET_STORAGE
+            }
+
+            // pushing and popping changes the size of the account data
+            (CostModel::Solana, Instr::PushStorage { .. })
+            | (CostModel::Solana, Instr::PopStorage { .. }) => SOLANA_MEM_OP + self.ops(20),
+            // the length and the element are stored separately
+            (CostModel::Polkadot, Instr::PushStorage { ty, .. })
+            | (CostModel::Polkadot, Instr::PopStorage { ty, .. }) => {
+                POLKADOT_GET_STORAGE
+                    + 2 * POLKADOT_SET_STORAGE
+                    + POLKADOT_STORAGE_BYTE * bytes(ty, ns)
+            }
+
+            (CostModel::Solana, Instr::PushMemory { .. })
+            | (CostModel::Solana, Instr::PopMemory { .. }) => SOLANA_MEM_OP + self.ops(20),
+            (CostModel::Polkadot, Instr::PushMemory { .. })
+            | (CostModel::Polkadot, Instr::PopMemory { .. }) => self.ops(100),
+
+            (CostModel::Solana, Instr::MemCopy { bytes, .. }) => {
+                SOLANA_MEM_OP + constant_bytes(bytes) / 250
+            }
+            (CostModel::Polkadot, Instr::MemCopy { bytes, .. }) => {
+                self.ops(constant_bytes(bytes).div_ceil(8) + 4)
+            }
+
+            // creating an account and calling the constructor are two cross program invocations
+            (CostModel::Solana, Instr::Constructor { seeds, .. }) => {
+                let mut cost = 2 * SOLANA_INVOKE;
+
+                if seeds.is_some() {
+                    cost += SOLANA_CREATE_PROGRAM_ADDRESS;
+                }
+
+                cost
+            }
+            (CostModel::Polkadot, Instr::Constructor { .. }) => POLKADOT_INSTANTIATE,
+
+            (CostModel::Solana, Instr::ExternalCall { seeds, .. }) => {
+                let mut cost = SOLANA_INVOKE;
+
+                if seeds.is_some() {
+                    cost += SOLANA_CREATE_PROGRAM_ADDRESS;
+                }
+
+                cost
+            }
+            (CostModel::Polkadot, Instr::ExternalCall { .. }) => POLKADOT_CALL,
+
+            (CostModel::Solana, Instr::ValueTransfer { .. }) => self.ops(10),
+            (CostModel::Polkadot, Instr::ValueTransfer { .. }) => POLKADOT_TRANSFER,
+
+            (CostModel::Polkadot, Instr::SelfDestruct { .. }) => POLKADOT_TERMINATE,
+
+            (CostModel::Solana, Instr::EmitEvent { topics, .. }) => {
+                SOLANA_SYSCALL + SOLANA_MEM_OP * (topics.len() as u64 + 1)
+            }
+            (CostModel::Polkadot, Instr::EmitEvent { topics, .. }) => {
+                POLKADOT_DEPOSIT_EVENT + POLKADOT_EVENT_TOPIC * topics.len() as u64
+            }
+
+            (CostModel::Solana, Instr::Print { .. }) => SOLANA_LOG,
+            (CostModel::Solana, Instr::ReturnData { .. })
+            | (
+                CostModel::Solana,
+                Instr::AssertFailure {
+                    encoded_args: Some(_),
+                },
+            ) => SOLANA_SYSCALL,
+            (CostModel::Polkadot, Instr::Print { .. })
+            | (CostModel::Polkadot, Instr::ReturnData { .. })
+            | (CostModel::Polkadot, Instr::AssertFailure { .. }) => POLKADOT_HOST_FUNCTION,
+
+            _ => self.ops(1),
+        }
+    }
+
+    /// The cost of the expression itself, without its operands
+    fn operation(self, expr: &Expression, ns: &Namespace) -> u64 {
+        match expr {
+            Expression::NumberLiteral { .. }
+            | Expression::BoolLiteral { .. }
+            | Expression::BytesLiteral { .. }
+            | Expression::RationalNumberLiteral { .. }
+            | Expression::FunctionArg { .. }
+            | Expression::Variable { .. }
+            | Expression::InternalFunctionCfg { .. }
+            | Expression::Undefined { .. }
+            | Expression::Poison => 0,
+
+            Expression::Add {
+                ty, overflowing, ..
+            }
+            | Expression::Subtract {
+                ty, overflowing, ..
+            } => {
+                let words = words(ty, ns);
+
+                if *overflowing {
+                    self.ops(words)
+                } else {
+                    self.ops(2 * words)
+                }
+            }
+            Expression::Multiply {
+                ty, overflowing, ..
+            } => {
+                let words = words(ty, ns);
+
+                if *overflowing {
+                    self.ops(words * words)
+                } else {
+                    self.ops(2 * words * words)
+                }
+            }
+            Expression::UnsignedDivide { ty, .. }
+            | Expression::SignedDivide { ty, .. }
+            | Expression::UnsignedModulo { ty, .. }
+            | Expression::SignedModulo { ty, .. } => self.ops(division(words(ty, ns))),
+            // one multiplication for every bit of the exponent
+            Expression::Power { ty, .. } => {
+                let words = words(ty, ns);
+
+                self.ops(words * 64 * (words * words + 2))
+            }
+            Expression::Equal { left, .. }
+            | Expression::NotEqual { left, .. }
+            | Expression::Less { left, .. }
+            | Expression::LessEqual { left, .. }
+            | Expression::More { left, .. }
+            | Expression::MoreEqual { left, .. } => self.ops(words(&left.ty(), ns)),
+            Expression::BitwiseAnd { ty, .. }
+            | Expression::BitwiseOr { ty, .. }
+            | Expression::BitwiseXor { ty, .. }
+            | Expression::BitwiseNot { ty, .. }
+            | Expression::ShiftLeft { ty, .. }
+            | Expression::ShiftRight { ty, .. }
+            | Expression::Negate { ty, .. }
+            | Expression::ZeroExt { ty, .. }
+            | Expression::SignExt { ty, .. }
+            | Expression::Trunc { ty, .. } => self.ops(words(ty, ns)),
+
+            Expression::Keccak256 { exprs, .. } => {
+                self.hash(exprs.iter().map(|expr| bytes(&expr.ty(), ns)).sum())
+            }
+
+            Expression::AllocDynamicBytes { initializer, .. } => {
+                let copy = initializer
+                    .as_ref()
+                    .map(|init| init.len() as u64 / 8)
+                    .unwrap_or_default();
+
+                self.ops(20 + copy)
+            }
+            Expression::ArrayLiteral { values, .. }
+            | Expression::ConstArrayLiteral { values, .. }
+            | Expression::StructLiteral { values, .. } => self.ops(values.len() as u64 + 20),
+            Expression::StringCompare { .. } => self.ops(20),
+            Expression::FormatString { args, .. } => self.ops(50 * args.len() as u64),
+
+            Expression::Builtin { kind, args, .. } => self.builtin(*kind, args, ns),
+
+            _ => self.ops(1),
+        }
+    }
+
+    fn builtin(self, kind: Builtin, args: &[Expression], ns: &Namespace) -> u64 {
+        match (self, kind) {
+            (_, Builtin::Sha256)
+            | (_, Builtin::Keccak256)
+            | (_, Builtin::Ripemd160)
+            | (_, Builtin::Blake2_128)
+            | (_, Builtin::Blake2_256) => {
+                self.hash(args.iter().map(|arg| bytes(&arg.ty(), ns)).sum())
+            }
+            (_, Builtin::AddMod) | (_, Builtin::MulMod) => self.ops(division(8)),
+            (_, Builtin::Concat) => self.ops(20 + 8 * args.len() as u64),
+            (_, Builtin::WriteBytes) => self.ops(8),
+
+            (CostModel::Solana, Builtin::Timestamp)
+            | (CostModel::Solana, Builtin::BlockNumber)
+            | (CostModel::Solana, Builtin::Slot) => SOLANA_CLOCK_SYSVAR,
+            (CostModel::Solana, Builtin::Gasleft) => SOLANA_SYSCALL,
+            (CostModel::Solana, Builtin::SignatureVerify) => SOLANA_SIGNATURE_VERIFY,
+
+            (CostModel::Polkadot, Builtin::SignatureVerify) => POLKADOT_SIGNATURE_VERIFY,
+            (CostModel::Polkadot, Builtin::Balance)
+            | (CostModel::Polkadot, Builtin::BlockHash)
+            | (CostModel::Polkadot, Builtin::BlockNumber)
+            | (CostModel::Polkadot, Builtin::Calldata)
+            | (CostModel::Polkadot, Builtin::ChainId)
+            | (CostModel::Polkadot, Builtin::Gasleft)
+            | (CostModel::Polkadot, Builtin::GetAddress)
+            | (CostModel::Polkadot, Builtin::MinimumBalance)
+            | (CostModel::Polkadot, Builtin::Origin)
+            | (CostModel::Polkadot, Builtin::Sender)
+            | (CostModel::Polkadot, Builtin::Timestamp)
+            | (CostModel::Polkadot, Builtin::Value) => POLKADOT_HOST_FUNCTION,
+
+            _ => self.ops(2),
+        }
+    }
+
+    fn hash(self, bytes: u64) -> u64 {
+        match self {
+            CostModel::Solana => {
+                SOLANA_SYSCALL.max(SOLANA_HASH + bytes.div_ceil(SOLANA_HASH_BYTES_PER_UNIT))
+            }
+            CostModel::Polkadot => {
+                POLKADOT_HOST_FUNCTION + POLKADOT_HASH + POLKADOT_HASH_BYTE * bytes
+            }
+        }
+    }
+
+    /// The cost of executing a number of simple operations
+    fn ops(self, count: u64) -> u64 {
+        match self {
+            CostModel::Solana => count * SOLANA_INSTRUCTION,
+            CostModel::Polkadot => count * POLKADOT_INSTRUCTION,
+        }
+    }
+}
+
+struct Tally<'a> {
+    model: CostModel,
+    ns: &'a Namespace,
+    total: u64,
+}
+
+fn expression(expr: &Expression, tally: &mut Tally) -> bool {
+    tally.total = tally
+        .total
+        .saturating_add(tally.model.operation(expr, tally.ns));
+
+    true
+}
+
+/// The number of 64 bit words needed for a value of the type
+fn words(ty: &Type, ns: &Namespace) -> u64 {
+    match ty {
+        Type::Int(_)
+        | Type::Uint(_)
+        | Type::Bytes(_)
+        | Type::Address(_)
+        | Type::Contract(_)
+        | Type::Value => (ty.bits(ns) as u64).div_ceil(64),
+        _ => 1,
+    }
+}
+
+/// The number of bytes of a value of the type
+fn bytes(ty: &Type, ns: &Namespace) -> u64 {
+    match ty {
+        Type::Int(_)
+        | Type::Uint(_)
+        | Type::Bytes(_)
+        | Type::Address(_)
+        | Type::Contract(_)
+        | Type::Value => ty.bytes(ns) as u64,
+        Type::Bool | Type::Enum(_) => 1,
+        _ => DYNAMIC_BYTES,
+    }
+}
+
+/// The length of a copy, if it is known at compile time
+fn constant_bytes(bytes: &Expression) -> u64 {
+    match bytes {
+        Expression::NumberLiteral { value, .. } => value.try_into().unwrap_or(u64::MAX),
+        _ => DYNAMIC_BYTES,
+    }
+}
+
+/// Long division of a value of this many words, one bit at a time. Values which fit into
+/// a single word use the divide instruction.
+fn division(words: u64) -> u64 {
+    if words == 1 {
+        1
+    } else {
+        words * 64 * 4
+    }
+}
@@ -0,0 +1,39 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(test)]
+
+use super::Cost;
+
+#[test]
+fn cost_formula() {
+    let body = Cost::constant(12);
+
+    let mut cost = Cost::constant(30);
+    cost.add(&body.times("n"));
+    cost.add(&body.times("n").times("m"));
+
+    assert_eq!(cost.to_string(), "30 + 12 * n + 12 * m * n");
+    assert_eq!(cost.constant_value(), None);
+    assert_eq!(
+        cost.symbols().into_iter().collect::<Vec<_>>(),
+        vec!["m", "n"]
+    );
+
+    // the largest coefficient of each term
+    let mut worst = Cost::constant(50);
+    worst.add(&Cost::constant(4).times("n"));
+    worst.max(&cost);
+
+    assert_eq!(worst.to_string(), "50 + 12 * n + 12 * m * n");
+
+    let mut fixed = body.scale(10);
+    fixed.add(&Cost::constant(5));
+
+    assert_eq!(fixed.constant_value(), Some(125));
+
+    fixed.add(&Cost::unbounded());
+
+    assert!(fixed.is_unbounded());
+    assert_eq!(fixed.constant_value(), None);
+    assert_eq!(fixed.to_string(), "unbounded");
+}
@@ -0,0 +1,106 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::cfg::{ASTFunction, ControlFlowGraph, Instr, InternalCallTy};
+use super::Expression;
+use crate::sema::ast::{Diagnostic, Namespace};
+use crate::sema::diagnostics::Diagnostics;
+use crate::sema::Recurse;
+use std::collections::BTreeSet;
+
+/// Remove the control flow graphs of a contract which can never be executed. Starting from the
+/// public functions, the dispatchers and the storage initializer, follow every internal call and
+/// every internal function pointer. Anything which was not found is replaced with a placeholder,
+/// so that it is not emitted. The cfg numbers of the remaining functions do not change.
+pub(super) fn dead_function_elimination(contract_no: usize, roots: &[usize], ns: &mut Namespace) {
+    let contract = &ns.contracts[contract_no];
+
+    let mut reachable = BTreeSet::new();
+    let mut worklist: Vec<usize> = contract
+        .cfg
+        .iter()
+        .enumerate()
+        .filter(|(_, cfg)| cfg.public)
+        .map(|(cfg_no, _)| cfg_no)
+        .chain(roots.iter().copied())
+        .chain(contract.initializer)
+        .collect();
+
+    while let Some(cfg_no) = worklist.pop() {
+        if reachable.insert(cfg_no) {
+            worklist.extend(callees(&contract.cfg[cfg_no]));
+        }
+    }
+
+    let mut diagnostics = Diagnostics::default();
+
+    for (cfg_no, cfg) in contract.cfg.iter().enumerate() {
+        if cfg.is_placeholder() || reachable.contains(&cfg_no) {
+            continue;
+        }
+
+        let (loc, name) = match cfg.function_no {
+            ASTFunction::SolidityFunction(func_no) => {
+                let func = &ns.functions[func_no];
+
+                (func.loc, format!("{} '{}'", func.ty, func.id))
+            }
+            ASTFunction::YulFunction(func_no) => {
+                let func = &ns.yul_functions[func_no];
+
+                (func.loc, format!("assembly function '{}'", func.name))
+            }
+            ASTFunction::None => (contract.loc, format!("'{}'", cfg.name)),
+        };
+
+        diagnostics.push(Diagnostic::debug(
+            loc,
+            format!(
+                "{} has no remaining callers and has been removed from contract '{}'",
+                name, contract.id
+            ),
+        ));
+    }
+
+    ns.diagnostics.extend(diagnostics);
+
+    for (cfg_no, cfg) in ns.contracts[contract_no].cfg.iter_mut().enumerate() {
+        if !reachable.contains(&cfg_no) {
+            *cfg = ControlFlowGraph::placeholder();
+        }
+    }
+}
+
+/// All the functions which a cfg calls, or takes a function pointer of
+fn callees(cfg: &ControlFlowGraph) -> BTreeSet<usize> {
+    let mut callees = BTreeSet::new();
+
+    for instr in cfg.blocks.iter().flat_map(|block| block.instr.iter()) {
+        match instr {
+            Instr::Call {
+                call: InternalCallTy::Static { cfg_no },
+                ..
+            } => {
+                callees.insert(*cfg_no);
+            }
+            Instr::Call {
+                call: InternalCallTy::Dynamic(expr),
+                ..
+            } => {
+                expr.recurse(&mut callees, function_pointers);
+            }
+            _ => (),
+        }
+
+        instr.recurse_expressions(&mut callees, function_pointers);
+    }
+
+    callees
+}
+
+fn function_pointers(expr: &Expression, callees: &mut BTreeSet<usize>) -> bool {
+    if let Expression::InternalFunctionCfg { cfg_no, .. } = expr {
+        callees.insert(*cfg_no);
+    }
+
+    true
+}
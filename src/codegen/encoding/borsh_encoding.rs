@@ -211,6 +211,21 @@ impl AbiEncoding for BorshEncoding {
     fn is_packed(&self) -> bool {
         self.packed_encoder
     }
+
+    fn const_encode_size(&self, length: usize, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(&(length as u32).to_le_bytes());
+    }
+
+    fn const_decode_size(&self, data: &mut &[u8]) -> Option<usize> {
+        if data.len() < 4 {
+            return None;
+        }
+
+        let (length, rest) = data.split_at(4);
+        *data = rest;
+
+        Some(u32::from_le_bytes(length.try_into().unwrap()) as usize)
+    }
 }
 
 impl BorshEncoding {
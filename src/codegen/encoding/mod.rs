@@ -20,7 +20,7 @@ use crate::codegen::vartable::Vartable;
 use crate::codegen::{Builtin, Expression};
 use crate::sema::ast::{ArrayLength, Namespace, RetrieveType, StructType, Type, Type::Uint};
 use crate::Target;
-use num_bigint::BigInt;
+use num_bigint::{BigInt, Sign};
 use num_integer::Integer;
 use num_traits::{One, Zero};
 use solang_parser::pt::{Loc, Loc::Codegen};
@@ -1734,12 +1734,227 @@ pub(crate) trait AbiEncoding {
     /// Returns if the we are packed encoding
     fn is_packed(&self) -> bool;
 
+    /// Encode the length of a dynamic array, string or bytes at compile time.
+    fn const_encode_size(&self, length: usize, buffer: &mut Vec<u8>);
+
+    /// Decode the length of a dynamic array, string or bytes from the start of `data`.
+    ///
+    /// Returns `None` if `data` is too short.
+    fn const_decode_size(&self, data: &mut &[u8]) -> Option<usize>;
+
     /// Encode constant data at compile time.
     ///
     /// Returns `None` if the data can not be encoded at compile time.
-    fn const_encode(&self, _args: &[Expression]) -> Option<Vec<u8>> {
-        None
+    fn const_encode(&self, args: &[Expression], ns: &Namespace) -> Option<Vec<u8>> {
+        let mut buffer = Vec::new();
+
+        for arg in args {
+            self.const_encode_value(arg, ns, &mut buffer)?;
+        }
+
+        Some(buffer)
+    }
+
+    /// Encode a literal `expr` into `buffer`, the same way `encode()` would at runtime.
+    ///
+    /// Returns `None` if the expression is not a literal.
+    fn const_encode_value(
+        &self,
+        expr: &Expression,
+        ns: &Namespace,
+        buffer: &mut Vec<u8>,
+    ) -> Option<()> {
+        match expr {
+            Expression::BoolLiteral { value, .. } => buffer.push(*value as u8),
+            Expression::NumberLiteral { ty, value, .. } => match ty.clone().unwrap_user_type(ns) {
+                Type::Uint(width) | Type::Int(width) => {
+                    const_encode_int(value, width.next_power_of_two() as usize / 8, buffer)
+                }
+                Type::Value => const_encode_int(value, ns.value_length, buffer),
+                Type::Enum(_) => const_encode_int(value, 1, buffer),
+                Type::Bytes(length) => const_encode_bytes(value, length as usize, buffer),
+                Type::FunctionSelector => {
+                    const_encode_bytes(value, ns.target.selector_length() as usize, buffer)
+                }
+                Type::Address(_) | Type::Contract(_) => {
+                    const_encode_bytes(value, ns.address_length, buffer)
+                }
+                _ => return None,
+            },
+            Expression::BytesLiteral { ty, value, .. }
+            | Expression::AllocDynamicBytes {
+                ty,
+                initializer: Some(value),
+                ..
+            } => match ty {
+                Type::Bytes(_) => buffer.extend_from_slice(value),
+                Type::String | Type::DynamicBytes => {
+                    if !self.is_packed() {
+                        self.const_encode_size(value.len(), buffer);
+                    }
+                    buffer.extend_from_slice(value);
+                }
+                // A slice of bytes is data which is already encoded
+                Type::Slice(elem_ty) if matches!(**elem_ty, Type::Bytes(1) | Type::Uint(8)) => {
+                    buffer.extend_from_slice(value)
+                }
+                _ => return None,
+            },
+            Expression::StructLiteral { values, .. } => {
+                for value in values {
+                    self.const_encode_value(value, ns, buffer)?;
+                }
+            }
+            Expression::ArrayLiteral {
+                ty,
+                dimensions,
+                values,
+                ..
+            } if dimensions.len() == 1 => {
+                let dynamic = match ty {
+                    Type::Array(_, dims) => dims.last() == Some(&ArrayLength::Dynamic),
+                    Type::Slice(_) => true,
+                    _ => return None,
+                };
+
+                if dynamic && !self.is_packed() {
+                    self.const_encode_size(values.len(), buffer);
+                }
+
+                for value in values {
+                    self.const_encode_value(value, ns, buffer)?;
+                }
+            }
+            _ => return None,
+        }
+
+        Some(())
+    }
+
+    /// Decode a value of type `ty` from the start of `data` as a literal, which is the inverse
+    /// of `const_encode_value()`. Arrays are decoded as an array literal of their outer
+    /// dimension.
+    ///
+    /// Returns `None` if `data` is too short or not valid for the type.
+    fn const_decode(&self, ty: &Type, data: &mut &[u8], ns: &Namespace) -> Option<Expression> {
+        let number = |value| Expression::NumberLiteral {
+            loc: Codegen,
+            ty: ty.clone(),
+            value,
+        };
+
+        let value = match ty.clone().unwrap_user_type(ns) {
+            Type::Bool => match const_decode_bytes(data, 1)? {
+                [0] => Expression::BoolLiteral {
+                    loc: Codegen,
+                    value: false,
+                },
+                [1] => Expression::BoolLiteral {
+                    loc: Codegen,
+                    value: true,
+                },
+                _ => return None,
+            },
+            Type::Uint(width) => {
+                let bytes = const_decode_bytes(data, width.next_power_of_two() as usize / 8)?;
+                number(BigInt::from_bytes_le(Sign::Plus, bytes))
+            }
+            Type::Int(width) => {
+                let bytes = const_decode_bytes(data, width.next_power_of_two() as usize / 8)?;
+                number(BigInt::from_signed_bytes_le(bytes))
+            }
+            Type::Value => {
+                let bytes = const_decode_bytes(data, ns.value_length)?;
+                number(BigInt::from_bytes_le(Sign::Plus, bytes))
+            }
+            Type::Enum(enum_no) => {
+                let value = const_decode_bytes(data, 1)?[0];
+                if value as usize >= ns.enums[enum_no].values.len() {
+                    return None;
+                }
+                number(value.into())
+            }
+            Type::Bytes(length) => {
+                let bytes = const_decode_bytes(data, length as usize)?;
+                number(BigInt::from_bytes_be(Sign::Plus, bytes))
+            }
+            Type::Address(_) | Type::Contract(_) => {
+                let bytes = const_decode_bytes(data, ns.address_length)?;
+                number(BigInt::from_bytes_be(Sign::Plus, bytes))
+            }
+            Type::String | Type::DynamicBytes => {
+                let length = self.const_decode_size(data)?;
+                Expression::BytesLiteral {
+                    loc: Codegen,
+                    ty: ty.clone(),
+                    value: const_decode_bytes(data, length)?.to_vec(),
+                }
+            }
+            Type::Struct(struct_ty) => Expression::StructLiteral {
+                loc: Codegen,
+                ty: ty.clone(),
+                values: struct_ty
+                    .definition(ns)
+                    .fields
+                    .iter()
+                    .map(|field| self.const_decode(&field.ty, data, ns))
+                    .collect::<Option<_>>()?,
+            },
+            Type::Array(elem_ty, dims) => {
+                let (outer, inner) = dims.split_last()?;
+
+                let length = match outer {
+                    ArrayLength::Fixed(length) => length.try_into().ok()?,
+                    _ => self.const_decode_size(data)?,
+                };
+
+                let elem_ty = if inner.is_empty() {
+                    *elem_ty
+                } else {
+                    Type::Array(elem_ty, inner.to_vec())
+                };
+
+                Expression::ArrayLiteral {
+                    loc: Codegen,
+                    ty: ty.clone(),
+                    dimensions: vec![length as u32],
+                    values: (0..length)
+                        .map(|_| self.const_decode(&elem_ty, data, ns))
+                        .collect::<Option<_>>()?,
+                }
+            }
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+/// Write `value` as a little endian two's complement integer of `size` bytes
+fn const_encode_int(value: &BigInt, size: usize, buffer: &mut Vec<u8>) {
+    let mut bytes = value.to_signed_bytes_le();
+    let fill = if value.sign() == Sign::Minus { 0xff } else { 0 };
+    bytes.resize(size, fill);
+    buffer.extend_from_slice(&bytes);
+}
+
+/// Write `value` as big endian bytes of `size` bytes, e.g. `bytes4` or an address
+fn const_encode_bytes(value: &BigInt, size: usize, buffer: &mut Vec<u8>) {
+    let bytes = value.to_bytes_be().1;
+    buffer.resize(buffer.len() + size.saturating_sub(bytes.len()), 0);
+    buffer.extend_from_slice(&bytes[bytes.len().saturating_sub(size)..]);
+}
+
+/// Take `size` bytes from the start of `data`
+fn const_decode_bytes<'a>(data: &mut &'a [u8], size: usize) -> Option<&'a [u8]> {
+    if data.len() < size {
+        return None;
     }
+
+    let (bytes, rest) = data.split_at(size);
+    *data = rest;
+
+    Some(bytes)
 }
 
 /// This function should return the correct encoder, given the target
@@ -6,8 +6,7 @@ use crate::codegen::vartable::Vartable;
 use crate::codegen::{Builtin, Expression};
 use crate::sema::ast::StructType;
 use crate::sema::ast::{Namespace, Type, Type::Uint};
-use parity_scale_codec::Encode;
-use primitive_types::U256;
+use parity_scale_codec::{Compact, Decode, Encode};
 use solang_parser::pt::Loc::Codegen;
 use std::collections::HashMap;
 
@@ -581,45 +580,14 @@ impl AbiEncoding for ScaleEncoding {
         self.packed_encoder
     }
 
-    /// TODO: This is used and tested for error data (Error and Panic) only.
-    fn const_encode(&self, args: &[Expression]) -> Option<Vec<u8>> {
-        let mut result = vec![];
-        for arg in args {
-            match arg {
-                Expression::AllocDynamicBytes {
-                    initializer: Some(data),
-                    ty: Type::String | Type::DynamicBytes,
-                    ..
-                } => result.extend_from_slice(&data.encode()),
-                Expression::AllocDynamicBytes {
-                    initializer: Some(data),
-                    ty: Type::Slice(inner),
-                    ..
-                } if matches!(**inner, Type::Bytes(1)) => result.extend_from_slice(data),
-                Expression::NumberLiteral {
-                    ty: Type::Bytes(4),
-                    value,
-                    ..
-                } => {
-                    let bytes = value.to_bytes_be().1;
-                    if bytes.len() < 4 {
-                        let buf = vec![0; 4 - bytes.len()];
-                        result.extend_from_slice(&buf);
-                    }
-                    result.extend_from_slice(&bytes[..]);
-                }
-                Expression::NumberLiteral {
-                    ty: Type::Uint(256),
-                    value,
-                    ..
-                } => {
-                    let bytes = value.to_bytes_be().1;
-                    result.extend_from_slice(&U256::from_big_endian(&bytes).encode()[..]);
-                }
-                _ => return None,
-            }
-        }
-        result.into()
+    fn const_encode_size(&self, length: usize, buffer: &mut Vec<u8>) {
+        Compact(length as u32).encode_to(buffer);
+    }
+
+    fn const_decode_size(&self, data: &mut &[u8]) -> Option<usize> {
+        Compact::<u32>::decode(data)
+            .ok()
+            .map(|length| length.0 as usize)
     }
 }
 
@@ -628,32 +596,36 @@ mod tests {
     use num_bigint::{BigInt, Sign};
     use parity_scale_codec::Encode;
     use primitive_types::U256;
+    use solang_parser::pt::Loc::Codegen;
 
     use crate::{
         codegen::{
             encoding::{scale_encoding::ScaleEncoding, AbiEncoding},
             Expression,
         },
-        sema::ast::Type,
+        sema::ast::{ArrayLength, Namespace, Type},
+        Target,
     };
 
     #[test]
     fn const_encode_dynamic_bytes() {
         let data = vec![0x41, 0x41];
         let encoder = ScaleEncoding::new(false);
+        let ns = Namespace::new(Target::default_polkadot());
         let expr = Expression::AllocDynamicBytes {
             loc: Default::default(),
             ty: Type::DynamicBytes,
             size: Expression::Poison.into(),
             initializer: data.clone().into(),
         };
-        let encoded = encoder.const_encode(&[expr]).unwrap();
+        let encoded = encoder.const_encode(&[expr], &ns).unwrap();
         assert_eq!(encoded, data.encode());
     }
 
     #[test]
     fn const_encode_uint() {
         let encoder = ScaleEncoding::new(false);
+        let ns = Namespace::new(Target::default_polkadot());
         for value in [U256::MAX, U256::zero(), U256::one()] {
             let mut bytes = [0u8; 32].to_vec();
             value.to_big_endian(&mut bytes);
@@ -663,7 +635,7 @@ mod tests {
                 ty: Type::Uint(256),
                 value: data,
             };
-            let encoded = encoder.const_encode(&[expr]).unwrap();
+            let encoded = encoder.const_encode(&[expr], &ns).unwrap();
             assert_eq!(encoded, value.encode());
         }
     }
@@ -671,6 +643,7 @@ mod tests {
     #[test]
     fn const_encode_bytes4() {
         let encoder = ScaleEncoding::new(false);
+        let ns = Namespace::new(Target::default_polkadot());
         for value in [
             [0x00, 0x00, 0xff, 0xff],
             [0x00, 0xff, 0xff, 0x00],
@@ -686,7 +659,53 @@ mod tests {
                 value: BigInt::from_bytes_be(Sign::Plus, &value),
                 loc: Default::default(),
             };
-            assert_eq!(&encoder.const_encode(&[expr]).unwrap(), &value.encode());
+            assert_eq!(
+                &encoder.const_encode(&[expr], &ns).unwrap(),
+                &value.encode()
+            );
         }
     }
+
+    #[test]
+    fn const_decode_round_trip() {
+        let encoder = ScaleEncoding::new(false);
+        let ns = Namespace::new(Target::default_polkadot());
+        let ty = Type::Array(Type::Int(24).into(), vec![ArrayLength::Dynamic]);
+        let values: Vec<Expression> = [-2, 70000]
+            .into_iter()
+            .map(|value| Expression::NumberLiteral {
+                loc: Codegen,
+                ty: Type::Int(24),
+                value: value.into(),
+            })
+            .collect();
+        let args = [
+            Expression::ArrayLiteral {
+                loc: Codegen,
+                ty: ty.clone(),
+                dimensions: vec![2],
+                values,
+            },
+            Expression::BytesLiteral {
+                loc: Codegen,
+                ty: Type::String,
+                value: b"abc".to_vec(),
+            },
+        ];
+
+        let encoded = encoder.const_encode(&args, &ns).unwrap();
+        assert_eq!(encoded, (vec![-2i32, 70000], String::from("abc")).encode());
+
+        let mut data = &encoded[..];
+        assert_eq!(
+            encoder.const_decode(&ty, &mut data, &ns).as_ref(),
+            Some(&args[0])
+        );
+        assert_eq!(
+            encoder.const_decode(&Type::String, &mut data, &ns).as_ref(),
+            Some(&args[1])
+        );
+        assert!(data.is_empty());
+        assert_eq!(encoder.const_decode(&Type::Bool, &mut data, &ns), None);
+    }
 }
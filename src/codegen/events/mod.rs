@@ -54,3 +54,8 @@ pub(super) fn new_event_emitter<'a>(
         Target::Soroban => todo!(),
     }
 }
+
+/// The selector of an event, which is the value of `Event.selector` in the emitting contract
+pub fn event_selector(event_no: usize, emitting_contract_no: usize, ns: &Namespace) -> Vec<u8> {
+    new_event_emitter(&pt::Loc::Codegen, event_no, &[], ns).selector(emitting_contract_no)
+}
@@ -3,6 +3,7 @@
 use std::collections::VecDeque;
 use std::vec;
 
+use crate::abi::polkadot::signature_topic;
 use crate::codegen::cfg::{ControlFlowGraph, Instr};
 use crate::codegen::encoding::abi_encode;
 use crate::codegen::events::EventEmitter;
@@ -26,6 +27,11 @@ pub(super) struct PolkadotEventEmitter<'a> {
 impl EventEmitter for PolkadotEventEmitter<'_> {
     fn selector(&self, emitting_contract_no: usize) -> Vec<u8> {
         let event = &self.ns.events[self.event_no];
+
+        if self.ns.is_ink_v5() {
+            return signature_topic(event).into();
+        }
+
         // For freestanding events the name of the emitting contract is used
         let contract_name = &self.ns.contracts[event.contract.unwrap_or(emitting_contract_no)]
             .id
@@ -68,11 +74,16 @@ impl EventEmitter for PolkadotEventEmitter<'_> {
             .iter()
             .position(|e| *e == self.event_no)
             .expect("contract emits this event");
-        let mut data = vec![Expression::NumberLiteral {
-            loc,
-            ty: Type::Uint(8),
-            value: id.into(),
-        }];
+        // With ink! v5 the event is identified by its signature topic rather than its index
+        let mut data = if self.ns.is_ink_v5() {
+            vec![]
+        } else {
+            vec![Expression::NumberLiteral {
+                loc,
+                ty: Type::Uint(8),
+                value: id.into(),
+            }]
+        };
         let mut topics = vec![];
 
         // Events that are not anonymous always have themselves as a topic.
@@ -125,22 +136,29 @@ impl EventEmitter for PolkadotEventEmitter<'_> {
 
             let encoded = abi_encode(&loc, vec![value], self.ns, vartab, cfg, false).0;
             let first_prefix = topic_prefixes.pop_front().unwrap();
-            let prefix = Expression::AllocDynamicBytes {
-                loc,
-                ty: Type::Slice(Type::Bytes(1).into()),
-                size: Expression::NumberLiteral {
+
+            // ink! v5 does not prefix the topics of fields
+            let concatenated = if self.ns.is_ink_v5() {
+                encoded
+            } else {
+                let prefix = Expression::AllocDynamicBytes {
+                    loc,
+                    ty: Type::Slice(Type::Bytes(1).into()),
+                    size: Expression::NumberLiteral {
+                        loc,
+                        ty: Type::Uint(32),
+                        value: first_prefix.len().into(),
+                    }
+                    .into(),
+                    initializer: Some(first_prefix),
+                };
+
+                Expression::Builtin {
                     loc,
-                    ty: Type::Uint(32),
-                    value: first_prefix.len().into(),
+                    kind: Builtin::Concat,
+                    tys: vec![Type::DynamicBytes],
+                    args: vec![prefix, encoded],
                 }
-                .into(),
-                initializer: Some(first_prefix),
-            };
-            let concatenated = Expression::Builtin {
-                loc,
-                kind: Builtin::Concat,
-                tys: vec![Type::DynamicBytes],
-                args: vec![prefix, encoded],
             };
 
             vartab.new_dirty_tracker();
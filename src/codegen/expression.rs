@@ -5,7 +5,8 @@ use super::revert::{
     assert_failure, expr_assert, log_runtime_error, require, PanicCode, SolidityError,
 };
 use super::storage::{
-    array_offset, array_pop, array_push, storage_slots_array_pop, storage_slots_array_push,
+    array_offset, array_pop, array_push, load_packed, packed_variable, storage_slots_array_pop,
+    storage_slots_array_push, store_packed,
 };
 use super::{
     cfg::{ControlFlowGraph, Instr, InternalCallTy},
@@ -58,6 +59,10 @@ pub fn expression(
             ns.contracts[contract_no].get_storage_slot(*loc, *var_contract_no, *var_no, ns, None)
         }
         ast::Expression::StorageLoad { loc, ty, expr } => {
+            if let Some(layout) = packed_variable(expr, contract_no, ns) {
+                return load_packed(loc, layout, cfg, vartab, ns);
+            }
+
             let storage = expression(expr, cfg, contract_no, func, ns, vartab, opt);
 
             load_storage(loc, ty, storage, cfg, vartab)
@@ -1222,7 +1227,10 @@ fn post_incdec(
             ty: ty.as_ref().clone(),
             expr: Box::new(v),
         },
-        Type::StorageRef(_, ty) => load_storage(&var.loc(), ty.as_ref(), v, cfg, vartab),
+        Type::StorageRef(_, ty) => match packed_variable(var, contract_no, ns) {
+            Some(layout) => load_packed(&var.loc(), layout, cfg, vartab, ns),
+            None => load_storage(&var.loc(), ty.as_ref(), v, cfg, vartab),
+        },
         _ => v,
     };
     cfg.add(
@@ -1288,18 +1296,24 @@ fn post_incdec(
 
             match var.ty() {
                 Type::StorageRef(..) => {
-                    cfg.add(
-                        vartab,
-                        Instr::SetStorage {
-                            value: Expression::Variable {
-                                loc: *loc,
+                    let value = Expression::Variable {
+                        loc: *loc,
+                        ty: ty.clone(),
+                        var_no: res,
+                    };
+
+                    if let Some(layout) = packed_variable(var, contract_no, ns) {
+                        store_packed(loc, layout, value, cfg, vartab, ns);
+                    } else {
+                        cfg.add(
+                            vartab,
+                            Instr::SetStorage {
+                                value,
                                 ty: ty.clone(),
-                                var_no: res,
+                                storage: dest,
                             },
-                            ty: ty.clone(),
-                            storage: dest,
-                        },
-                    );
+                        );
+                    }
                 }
                 Type::Ref(_) => {
                     cfg.add(
@@ -1346,7 +1360,10 @@ fn pre_incdec(
             ty: ty.as_ref().clone(),
             expr: Box::new(v),
         },
-        Type::StorageRef(_, ty) => load_storage(&var.loc(), ty.as_ref(), v, cfg, vartab),
+        Type::StorageRef(_, ty) => match packed_variable(var, contract_no, ns) {
+            Some(layout) => load_packed(&var.loc(), layout, cfg, vartab, ns),
+            None => load_storage(&var.loc(), ty.as_ref(), v, cfg, vartab),
+        },
         _ => v,
     };
     let one = Box::new(Expression::NumberLiteral {
@@ -1399,18 +1416,24 @@ fn pre_incdec(
 
             match var.ty() {
                 Type::StorageRef(..) => {
-                    cfg.add(
-                        vartab,
-                        Instr::SetStorage {
-                            value: Expression::Variable {
-                                loc: *loc,
+                    let value = Expression::Variable {
+                        loc: *loc,
+                        ty: ty.clone(),
+                        var_no: res,
+                    };
+
+                    if let Some(layout) = packed_variable(var, contract_no, ns) {
+                        store_packed(loc, layout, value, cfg, vartab, ns);
+                    } else {
+                        cfg.add(
+                            vartab,
+                            Instr::SetStorage {
+                                value,
                                 ty: ty.clone(),
-                                var_no: res,
+                                storage: dest,
                             },
-                            ty: ty.clone(),
-                            storage: dest,
-                        },
-                    );
+                        );
+                    }
                 }
                 Type::Ref(_) => {
                     cfg.add(
@@ -2684,18 +2707,24 @@ pub fn assign_single(
                     }
                 }
                 Type::StorageRef(..) => {
-                    cfg.add(
-                        vartab,
-                        Instr::SetStorage {
-                            value: Expression::Variable {
-                                loc: left.loc(),
-                                ty: ty.clone(),
-                                var_no: pos,
+                    let value = Expression::Variable {
+                        loc: left.loc(),
+                        ty: ty.clone(),
+                        var_no: pos,
+                    };
+
+                    if let Some(layout) = packed_variable(left, contract_no, ns) {
+                        store_packed(&left.loc(), layout, value, cfg, vartab, ns);
+                    } else {
+                        cfg.add(
+                            vartab,
+                            Instr::SetStorage {
+                                value,
+                                ty: ty.deref_any().clone(),
+                                storage: dest,
                             },
-                            ty: ty.deref_any().clone(),
-                            storage: dest,
-                        },
-                    );
+                        );
+                    }
                 }
                 Type::Ref(_) => {
                     cfg.add(
@@ -3718,6 +3747,37 @@ fn code(loc: &Loc, contract_no: usize, ns: &Namespace, opt: &Options) -> Express
     }
 }
 
+/// The size in bytes of the code of the given contract
+#[cfg(feature = "llvm")]
+pub(crate) fn code_size(
+    loc: &Loc,
+    contract_no: usize,
+    ns: &Namespace,
+    opt: &Options,
+) -> Expression {
+    let code = ns.contracts[contract_no].emit(ns, opt, contract_no);
+
+    Expression::NumberLiteral {
+        loc: *loc,
+        ty: Type::Uint(256),
+        value: code.len().into(),
+    }
+}
+
+#[cfg(not(feature = "llvm"))]
+pub(crate) fn code_size(
+    loc: &Loc,
+    _contract_no: usize,
+    _ns: &Namespace,
+    _opt: &Options,
+) -> Expression {
+    Expression::NumberLiteral {
+        loc: *loc,
+        ty: Type::Uint(256),
+        value: b"code placeholder".len().into(),
+    }
+}
+
 #[cfg(not(feature = "llvm"))]
 fn code(loc: &Loc, _contract_no: usize, _ns: &Namespace, _opt: &Options) -> Expression {
     let code = b"code placeholder".to_vec();
@@ -0,0 +1,426 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::cfg::{optimize_and_check_cfg, BasicBlock, ControlFlowGraph, Instr, InternalCallTy};
+use super::{Expression, OptimizationLevel, Options};
+use crate::sema::ast::Namespace;
+use crate::Target;
+use solang_parser::pt;
+use std::collections::{BTreeSet, HashMap};
+
+/// Callers are not grown beyond this number of instructions by inlining
+const MAX_CALLER_SIZE: usize = 2048;
+
+/// A function which is only called from a single place and is not public is inlined if it is
+/// up to this many times larger than the threshold for the optimization level
+const SINGLE_CALL_SITE_FACTOR: usize = 4;
+
+/// The maximum number of instructions a function may have in order to be inlined
+fn size_threshold(opt_level: OptimizationLevel) -> usize {
+    match opt_level {
+        OptimizationLevel::None => 0,
+        OptimizationLevel::Less => 12,
+        OptimizationLevel::Default => 40,
+        OptimizationLevel::Aggressive => 120,
+    }
+}
+
+/// Are functions inlined with these options
+pub(super) fn enabled(opt: &Options) -> bool {
+    opt.inline && size_threshold(opt.opt_level) > 0
+}
+
+/// Replace calls to small internal functions with the body of the function. This runs once all
+/// the function cfgs of a contract have been generated, and before the dispatch functions are
+/// generated. The bodies which are inlined are the cfgs as they were before optimization, since
+/// the optimization passes propagate the function arguments into the body. Any cfg which has had
+/// calls inlined is passed through the optimization passes again, so that constant folding,
+/// strength reduction, dead storage and common subexpression elimination can see through the call.
+///
+/// Only calls which have been resolved statically are inlined; calls through function pointers
+/// and builtins are left alone. Virtual functions have already been resolved to the overriding
+/// function at this point. Recursive functions are never inlined.
+pub(super) fn inline(
+    all_cfg: &mut [ControlFlowGraph],
+    bodies: &HashMap<usize, ControlFlowGraph>,
+    ns: &mut Namespace,
+    opt: &Options,
+) {
+    if !enabled(opt) || ns.diagnostics.any_errors() {
+        return;
+    }
+
+    let threshold = size_threshold(opt.opt_level);
+
+    let callees: Vec<BTreeSet<usize>> = all_cfg.iter().map(static_callees).collect();
+    let recursive: Vec<bool> = (0..all_cfg.len())
+        .map(|cfg_no| calls_itself(cfg_no, &callees))
+        .collect();
+
+    let mut call_sites = vec![0; all_cfg.len()];
+
+    for cfg in all_cfg.iter() {
+        for instr in cfg.blocks.iter().flat_map(|block| block.instr.iter()) {
+            if let Instr::Call {
+                call: InternalCallTy::Static { cfg_no },
+                ..
+            } = instr
+            {
+                call_sites[*cfg_no] += 1;
+            }
+        }
+    }
+
+    let inlinable: Vec<bool> = (0..all_cfg.len())
+        .map(|cfg_no| {
+            !recursive[cfg_no]
+                && bodies
+                    .get(&cfg_no)
+                    .map(|body| can_inline(body, ns))
+                    .unwrap_or(false)
+        })
+        .collect();
+
+    // Inline into the callees first, so that the cost of a callee includes anything inlined into it
+    let mut order = Vec::new();
+    let mut visited = vec![false; all_cfg.len()];
+
+    for cfg_no in 0..all_cfg.len() {
+        post_order(cfg_no, &callees, &mut visited, &mut order);
+    }
+
+    for caller_no in order {
+        if all_cfg[caller_no].is_placeholder() {
+            continue;
+        }
+
+        let mut size = cfg_size(&all_cfg[caller_no]);
+        let mut changed = false;
+
+        // The inlined bodies may contain calls themselves, so repeat until there is nothing left
+        // to inline. This terminates since recursive functions are not inlined.
+        loop {
+            let mut sites = Vec::new();
+
+            for (block_no, block) in all_cfg[caller_no].blocks.iter().enumerate() {
+                for (instr_no, instr) in block.instr.iter().enumerate() {
+                    let Instr::Call {
+                        call: InternalCallTy::Static { cfg_no },
+                        ..
+                    } = instr
+                    else {
+                        continue;
+                    };
+
+                    if *cfg_no == caller_no || !inlinable[*cfg_no] {
+                        continue;
+                    }
+
+                    let callee_size = cfg_size(&all_cfg[*cfg_no]);
+
+                    let limit = if call_sites[*cfg_no] == 1 && !all_cfg[*cfg_no].public {
+                        threshold * SINGLE_CALL_SITE_FACTOR
+                    } else {
+                        threshold
+                    };
+
+                    if callee_size <= limit && size + callee_size <= MAX_CALLER_SIZE {
+                        size += callee_size;
+                        sites.push((block_no, instr_no, *cfg_no));
+                    }
+                }
+            }
+
+            if sites.is_empty() {
+                break;
+            }
+
+            all_cfg[caller_no] = inline_calls(&all_cfg[caller_no], &sites, bodies);
+            changed = true;
+        }
+
+        if !changed {
+            continue;
+        }
+
+        let cfg = &mut all_cfg[caller_no];
+
+        for block in &mut cfg.blocks {
+            block.defs.clear();
+            block.transfers.clear();
+            block.loop_reaching_variables.clear();
+        }
+
+        // The callee has already been checked, and constant folding has reported any errors in it.
+        // Any problems found in the inlined code now only occur for the particular arguments at
+        // this call site, and those should remain runtime errors, so do not report them.
+        let diagnostics = std::mem::take(&mut ns.diagnostics);
+
+        optimize_and_check_cfg(cfg, ns, cfg.function_no, opt);
+
+        ns.diagnostics = diagnostics;
+    }
+}
+
+/// The set of functions which are called statically from a cfg
+fn static_callees(cfg: &ControlFlowGraph) -> BTreeSet<usize> {
+    cfg.blocks
+        .iter()
+        .flat_map(|block| block.instr.iter())
+        .filter_map(|instr| match instr {
+            Instr::Call {
+                call: InternalCallTy::Static { cfg_no },
+                ..
+            } => Some(*cfg_no),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Can the function reach itself through static calls
+fn calls_itself(cfg_no: usize, callees: &[BTreeSet<usize>]) -> bool {
+    let mut visited = vec![false; callees.len()];
+    let mut todo: Vec<usize> = callees[cfg_no].iter().copied().collect();
+
+    while let Some(no) = todo.pop() {
+        if no == cfg_no {
+            return true;
+        }
+
+        if !visited[no] {
+            visited[no] = true;
+            todo.extend(callees[no].iter().copied());
+        }
+    }
+
+    false
+}
+
+fn post_order(
+    cfg_no: usize,
+    callees: &[BTreeSet<usize>],
+    visited: &mut Vec<bool>,
+    order: &mut Vec<usize>,
+) {
+    if visited[cfg_no] {
+        return;
+    }
+
+    visited[cfg_no] = true;
+
+    for callee in &callees[cfg_no] {
+        post_order(*callee, callees, visited, order);
+    }
+
+    order.push(cfg_no);
+}
+
+/// The cost of a function is its number of instructions
+fn cfg_size(cfg: &ControlFlowGraph) -> usize {
+    cfg.blocks
+        .iter()
+        .flat_map(|block| block.instr.iter())
+        .filter(|instr| !matches!(instr, Instr::Nop))
+        .count()
+}
+
+/// Check that the function arguments are only read by the instructions at the start of the entry
+/// block which copy them into the parameter variables. Those instructions can be replaced by
+/// assignments from the call arguments. On Solana, functions which access accounts are not inlined,
+/// since the accounts are resolved per function.
+fn can_inline(cfg: &ControlFlowGraph, ns: &Namespace) -> bool {
+    let mut args_copied = true;
+    // Every argument must be copied, else the argument expression would not be evaluated
+    let mut copied = vec![false; cfg.params.len()];
+
+    for (block_no, block) in cfg.blocks.iter().enumerate() {
+        for instr in &block.instr {
+            if ns.target == Target::Solana && matches!(instr, Instr::AccountAccess { .. }) {
+                return false;
+            }
+
+            if let Instr::Set {
+                expr: Expression::FunctionArg { arg_no, .. },
+                ..
+            } = instr
+            {
+                if block_no == 0 && args_copied && *arg_no < copied.len() {
+                    copied[*arg_no] = true;
+                    continue;
+                }
+
+                return false;
+            }
+
+            args_copied = false;
+
+            let mut reads_arg = false;
+
+            instr.recurse_expressions(&mut reads_arg, |expr, reads_arg| {
+                if matches!(expr, Expression::FunctionArg { .. }) {
+                    *reads_arg = true;
+                }
+                !*reads_arg
+            });
+
+            if reads_arg {
+                return false;
+            }
+        }
+    }
+
+    copied.iter().all(|copied| *copied)
+}
+
+/// Create a copy of the caller with the calls at the given sites replaced by the body of the callee
+fn inline_calls(
+    caller: &ControlFlowGraph,
+    sites: &[(usize, usize, usize)],
+    bodies: &HashMap<usize, ControlFlowGraph>,
+) -> ControlFlowGraph {
+    // The blocks of the callee are placed directly after the block containing the call, followed
+    // by a new block which continues after the call. This keeps the blocks in execution order,
+    // which the reaching definitions pass relies on to detect loops.
+    let mut block_map = Vec::with_capacity(caller.blocks.len());
+    let mut block_no = 0;
+
+    for old_block_no in 0..caller.blocks.len() {
+        block_map.push(block_no);
+
+        block_no += 1;
+
+        for (_, _, callee_no) in sites.iter().filter(|site| site.0 == old_block_no) {
+            block_no += bodies[callee_no].blocks.len() + 1;
+        }
+    }
+
+    let mut cfg = caller.clone();
+
+    cfg.blocks = Vec::with_capacity(block_no);
+
+    for (old_block_no, old_block) in caller.blocks.iter().enumerate() {
+        let mut current = BasicBlock {
+            phis: old_block.phis.clone(),
+            name: old_block.name.clone(),
+            ..Default::default()
+        };
+
+        for (instr_no, instr) in old_block.instr.iter().enumerate() {
+            let callee_no = sites
+                .iter()
+                .find(|site| site.0 == old_block_no && site.1 == instr_no)
+                .map(|site| site.2);
+
+            let (Some(callee_no), Instr::Call { res, args, .. }) = (callee_no, instr) else {
+                let mut instr = instr.clone();
+                renumber_blocks(&mut instr, |block_no| block_map[block_no]);
+                current.instr.push(instr);
+                continue;
+            };
+
+            let callee = &bodies[&callee_no];
+            let offset = cfg.blocks.len() + 1;
+            let continuation = offset + callee.blocks.len();
+
+            current.instr.push(Instr::Branch { block: offset });
+
+            cfg.blocks.push(current);
+
+            for (callee_block_no, callee_block) in callee.blocks.iter().enumerate() {
+                let mut block = BasicBlock {
+                    phis: callee_block.phis.clone(),
+                    name: callee_block.name.clone(),
+                    ..Default::default()
+                };
+
+                for instr in &callee_block.instr {
+                    match instr {
+                        Instr::Set {
+                            loc,
+                            res,
+                            expr: Expression::FunctionArg { arg_no, .. },
+                        } if callee_block_no == 0 => {
+                            block.instr.push(Instr::Set {
+                                loc: *loc,
+                                res: *res,
+                                expr: args[*arg_no].clone(),
+                            });
+                        }
+                        Instr::Return { value } => {
+                            for (res, expr) in res.iter().zip(value) {
+                                block.instr.push(Instr::Set {
+                                    loc: pt::Loc::Codegen,
+                                    res: *res,
+                                    expr: expr.clone(),
+                                });
+                            }
+
+                            block.instr.push(Instr::Branch {
+                                block: continuation,
+                            });
+                        }
+                        _ => {
+                            let mut instr = instr.clone();
+                            renumber_blocks(&mut instr, |block_no| block_no + offset);
+                            block.instr.push(instr);
+                        }
+                    }
+                }
+
+                cfg.blocks.push(block);
+            }
+
+            for (var_no, var) in &callee.vars {
+                cfg.vars.entry(*var_no).or_insert_with(|| var.clone());
+            }
+
+            for (array, length) in &callee.array_lengths_temps {
+                cfg.array_lengths_temps.entry(*array).or_insert(*length);
+            }
+
+            let returns = callee
+                .blocks
+                .iter()
+                .flat_map(|block| block.instr.iter())
+                .filter(|instr| matches!(instr, Instr::Return { .. }))
+                .count();
+
+            current = BasicBlock {
+                // the return values are merged if the callee returns from more than one place
+                phis: if returns > 1 && !res.is_empty() {
+                    Some(res.iter().copied().collect())
+                } else {
+                    None
+                },
+                name: format!("{}_return", callee.name),
+                ..Default::default()
+            };
+        }
+
+        cfg.blocks.push(current);
+    }
+
+    cfg
+}
+
+/// Renumber the destination blocks of a branch instruction
+pub(super) fn renumber_blocks<F: Fn(usize) -> usize>(instr: &mut Instr, map: F) {
+    match instr {
+        Instr::Branch { block } => *block = map(*block),
+        Instr::BranchCond {
+            true_block,
+            false_block,
+            ..
+        } => {
+            *true_block = map(*true_block);
+            *false_block = map(*false_block);
+        }
+        Instr::Switch { default, cases, .. } => {
+            *default = map(*default);
+
+            for (_, goto) in cases {
+                *goto = map(*goto);
+            }
+        }
+        _ => (),
+    }
+}
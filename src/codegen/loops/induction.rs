@@ -0,0 +1,444 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{assigned, expressions_mut, operands_mut, without_locations, Loop};
+use crate::codegen::cfg::{ControlFlowGraph, Instr};
+use crate::codegen::vartable::{Storage, Variable};
+use crate::codegen::Expression;
+use crate::sema::ast::{Namespace, RetrieveType, Type};
+use num_bigint::BigInt;
+use num_traits::{One, ToPrimitive, Zero};
+use solang_parser::pt::{Identifier, Loc};
+use std::collections::{HashMap, HashSet};
+
+/// A variable which is changed by a constant amount once in every iteration of the loop, e.g.
+/// `i = i + 1`.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct Basic {
+    pub ty: Type,
+    /// The amount added in each iteration
+    pub step: BigInt,
+    /// The update fails on overflow, so the variable never wraps around
+    pub checked: bool,
+    /// The instruction which updates the variable
+    pub block_no: usize,
+    pub instr_no: usize,
+}
+
+/// A variable which is assigned the value of a basic induction variable once in every
+/// iteration of the loop.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct Copied {
+    pub basic: usize,
+    pub block_no: usize,
+    pub instr_no: usize,
+}
+
+#[derive(Default)]
+pub(super) struct InductionVariables {
+    pub basic: HashMap<usize, Basic>,
+    pub copies: HashMap<usize, Copied>,
+    /// Variables which hold the value of a multiplication of an induction variable. The
+    /// expression is the multiplication, and the first field is the induction variable.
+    reduced: Vec<(usize, Expression, usize)>,
+    next_id: usize,
+}
+
+impl InductionVariables {
+    /// Find the induction variables of the loop
+    pub(super) fn new(cfg: &ControlFlowGraph, lp: &Loop, next_id: usize) -> Self {
+        let mut defs: HashMap<usize, Vec<(usize, usize)>> = HashMap::new();
+
+        for block_no in &lp.blocks {
+            for (instr_no, instr) in cfg.blocks[*block_no].instr.iter().enumerate() {
+                for var_no in assigned(instr) {
+                    defs.entry(var_no).or_default().push((*block_no, instr_no));
+                }
+            }
+        }
+
+        // The only definition in the loop, if it is a Set instruction
+        let single_set = |var_no: usize| -> Option<(usize, usize, &Expression)> {
+            match defs.get(&var_no).map(Vec::as_slice) {
+                Some([(block_no, instr_no)]) => match &cfg.blocks[*block_no].instr[*instr_no] {
+                    Instr::Set { expr, .. } => Some((*block_no, *instr_no, expr)),
+                    _ => None,
+                },
+                _ => None,
+            }
+        };
+
+        let mut ivs = InductionVariables {
+            next_id,
+            ..Default::default()
+        };
+
+        for var_no in defs.keys() {
+            let Some((block_no, instr_no, expr)) = single_set(*var_no) else {
+                continue;
+            };
+
+            let (ty, checked, src, step) = match expr {
+                Expression::Add {
+                    ty,
+                    overflowing,
+                    left,
+                    right,
+                    ..
+                } => match (left.as_ref(), right.as_ref()) {
+                    (
+                        Expression::Variable { var_no: src, .. },
+                        Expression::NumberLiteral { value, .. },
+                    ) => (ty, !overflowing, *src, value.clone()),
+                    _ => continue,
+                },
+                Expression::Subtract {
+                    ty,
+                    overflowing,
+                    left,
+                    right,
+                    ..
+                } => match (left.as_ref(), right.as_ref()) {
+                    (
+                        Expression::Variable { var_no: src, .. },
+                        Expression::NumberLiteral { value, .. },
+                    ) => (ty, !overflowing, *src, -value),
+                    _ => continue,
+                },
+                _ => continue,
+            };
+
+            if !matches!(ty, Type::Int(_) | Type::Uint(_)) {
+                continue;
+            }
+
+            // The old value may have been copied to a temporary first
+            let is_copy = src == *var_no
+                || matches!(single_set(src), Some((src_block_no, src_instr_no, Expression::Variable { var_no: copied, .. }))
+                    if src_block_no == block_no && src_instr_no < instr_no && copied == var_no);
+
+            if is_copy {
+                ivs.basic.insert(
+                    *var_no,
+                    Basic {
+                        ty: ty.clone(),
+                        step,
+                        checked,
+                        block_no,
+                        instr_no,
+                    },
+                );
+            }
+        }
+
+        for var_no in defs.keys() {
+            if let Some((block_no, instr_no, Expression::Variable { var_no: basic, .. })) =
+                single_set(*var_no)
+            {
+                if ivs.basic.contains_key(basic) && !ivs.basic.contains_key(var_no) {
+                    ivs.copies.insert(
+                        *var_no,
+                        Copied {
+                            basic: *basic,
+                            block_no,
+                            instr_no,
+                        },
+                    );
+                }
+            }
+        }
+
+        ivs
+    }
+
+    /// If the expression multiplies an induction variable by a constant, return the variable and
+    /// the expression without locations, so that equal expressions compare equal.
+    fn multiplication(&self, expr: &Expression) -> Option<(usize, Expression)> {
+        let (ty, operand, right) = match expr {
+            Expression::Multiply {
+                ty,
+                overflowing: true,
+                left,
+                right,
+                ..
+            }
+            | Expression::ShiftLeft {
+                ty, left, right, ..
+            } => (ty, left, right),
+            _ => return None,
+        };
+
+        if !matches!(ty, Type::Int(_) | Type::Uint(_))
+            || !matches!(right.as_ref(), Expression::NumberLiteral { .. })
+        {
+            return None;
+        }
+
+        // The induction variable may be truncated, which wraps around in the same way. It may
+        // also be extended, if the variable itself never wraps around.
+        let mut casts = Vec::new();
+        let mut operand = operand.as_ref();
+
+        let var_no = loop {
+            match operand {
+                Expression::Trunc { expr, .. }
+                | Expression::ZeroExt { expr, .. }
+                | Expression::SignExt { expr, .. } => {
+                    casts.push(operand);
+                    operand = expr;
+                }
+                Expression::Variable { var_no, .. } => break *var_no,
+                _ => return None,
+            }
+        };
+
+        let basic = match self.copies.get(&var_no) {
+            Some(copy) => &self.basic[&copy.basic],
+            None => self.basic.get(&var_no)?,
+        };
+
+        let valid = casts
+            .iter()
+            .all(|cast| matches!(cast, Expression::Trunc { .. }))
+            || (basic.checked
+                && casts.iter().all(|cast| match basic.ty {
+                    Type::Uint(_) => matches!(cast, Expression::ZeroExt { .. }),
+                    _ => matches!(cast, Expression::SignExt { .. }),
+                }));
+
+        if !valid {
+            return None;
+        }
+
+        Some((var_no, without_locations(expr)))
+    }
+
+    /// Replace multiplications of induction variables with variables which are updated when the
+    /// induction variable changes
+    pub(super) fn reduce(&mut self, expr: &mut Expression) {
+        if let Some((var_no, normalized)) = self.multiplication(expr) {
+            let reduced = self.reduced_var(var_no, normalized);
+
+            *expr = Expression::Variable {
+                loc: Loc::Codegen,
+                ty: expr.ty(),
+                var_no: reduced,
+            };
+        } else {
+            for operand in operands_mut(expr) {
+                self.reduce(operand);
+            }
+        }
+    }
+
+    fn reduced_var(&mut self, var_no: usize, expr: Expression) -> usize {
+        if let Some((_, _, reduced)) = self
+            .reduced
+            .iter()
+            .find(|(v, e, _)| *v == var_no && *e == expr)
+        {
+            return *reduced;
+        }
+
+        // a copy needs the value for its basic induction variable
+        if let Some(copy) = self.copies.get(&var_no) {
+            let basic = copy.basic;
+            let expr = replace_var(&expr, basic);
+            self.reduced_var(basic, expr);
+        }
+
+        let reduced = self.next_id;
+        self.next_id += 1;
+
+        self.reduced.push((var_no, expr, reduced));
+
+        reduced
+    }
+}
+
+/// Use another induction variable in the multiplication
+fn replace_var(expr: &Expression, var_no: usize) -> Expression {
+    let mut expr = expr.clone();
+    let mut operand = &mut expr;
+
+    loop {
+        match operand {
+            Expression::Variable { var_no: v, .. } => {
+                *v = var_no;
+                break expr;
+            }
+            Expression::Multiply { left, .. } | Expression::ShiftLeft { left, .. } => {
+                operand = left;
+            }
+            Expression::Trunc { expr, .. }
+            | Expression::ZeroExt { expr, .. }
+            | Expression::SignExt { expr, .. } => {
+                operand = expr;
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// The amount the multiplication changes when the induction variable changes by step, as a
+/// literal of the given type
+fn step_literal(expr: &Expression, step: &BigInt) -> Expression {
+    let (Expression::Multiply { ty, right, .. } | Expression::ShiftLeft { ty, right, .. }) = expr
+    else {
+        unreachable!();
+    };
+
+    let (Type::Int(bits) | Type::Uint(bits), Expression::NumberLiteral { value, .. }) =
+        (ty, right.as_ref())
+    else {
+        unreachable!();
+    };
+
+    let modulus = BigInt::one() << *bits;
+
+    let factor = match expr {
+        Expression::ShiftLeft { .. } => match value.to_u16() {
+            Some(shift) if shift < *bits => BigInt::one() << shift,
+            _ => BigInt::zero(),
+        },
+        _ => value.clone(),
+    };
+
+    let mut value = ((step * factor) % &modulus + &modulus) % &modulus;
+
+    if matches!(ty, Type::Int(_)) && value >= &modulus >> 1 {
+        value -= &modulus;
+    }
+
+    Expression::NumberLiteral {
+        loc: Loc::Codegen,
+        ty: ty.clone(),
+        value,
+    }
+}
+
+/// Replace multiplications of induction variables by constants with additions. A new variable
+/// is initialized in the preheader, and updated whenever the induction variable changes.
+pub(super) fn reduce_induction_variables(
+    cfg: &mut ControlFlowGraph,
+    lp: &Loop,
+    preheader: usize,
+    ns: &mut Namespace,
+) {
+    let mut ivs = InductionVariables::new(cfg, lp, ns.next_id);
+
+    if ivs.basic.is_empty() {
+        return;
+    }
+
+    for block_no in &lp.blocks {
+        for instr in &mut cfg.blocks[*block_no].instr {
+            for expr in expressions_mut(instr) {
+                ivs.reduce(expr);
+            }
+        }
+    }
+
+    ns.next_id = ivs.next_id;
+
+    let assigned_outside: HashSet<usize> = cfg
+        .blocks
+        .iter()
+        .enumerate()
+        .filter(|(block_no, _)| !lp.blocks.contains(block_no))
+        .flat_map(|(_, block)| block.instr.iter().flat_map(assigned))
+        .collect();
+
+    let mut updates = Vec::new();
+    let mut init = Vec::new();
+
+    for (var_no, expr, reduced) in &ivs.reduced {
+        let ty = expr.ty();
+
+        cfg.vars.insert(
+            *reduced,
+            Variable {
+                id: Identifier {
+                    loc: Loc::Codegen,
+                    name: format!("induction.temp.{reduced}"),
+                },
+                ty: ty.clone(),
+                storage: Storage::Local,
+            },
+        );
+
+        // A copy which is not assigned before the loop has the default value until it is
+        // assigned in the loop, and so does the new variable.
+        if !ivs.copies.contains_key(var_no) || assigned_outside.contains(var_no) {
+            init.push(Instr::Set {
+                loc: Loc::Codegen,
+                res: *reduced,
+                expr: expr.clone(),
+            });
+        }
+
+        let update = if let Some(basic) = ivs.basic.get(var_no) {
+            Instr::Set {
+                loc: Loc::Codegen,
+                res: *reduced,
+                expr: Expression::Add {
+                    loc: Loc::Codegen,
+                    ty: ty.clone(),
+                    overflowing: true,
+                    left: Box::new(Expression::Variable {
+                        loc: Loc::Codegen,
+                        ty,
+                        var_no: *reduced,
+                    }),
+                    right: Box::new(step_literal(expr, &basic.step)),
+                },
+            }
+        } else {
+            let copy = &ivs.copies[var_no];
+            let basic_expr = replace_var(expr, copy.basic);
+            let (_, _, basic_reduced) = ivs
+                .reduced
+                .iter()
+                .find(|(v, e, _)| *v == copy.basic && *e == basic_expr)
+                .unwrap();
+
+            Instr::Set {
+                loc: Loc::Codegen,
+                res: *reduced,
+                expr: Expression::Variable {
+                    loc: Loc::Codegen,
+                    ty,
+                    var_no: *basic_reduced,
+                },
+            }
+        };
+
+        let (block_no, instr_no) = match ivs.basic.get(var_no) {
+            Some(basic) => (basic.block_no, basic.instr_no),
+            None => (ivs.copies[var_no].block_no, ivs.copies[var_no].instr_no),
+        };
+
+        updates.push((block_no, instr_no, update));
+
+        // the new variable has to be merged wherever the induction variable is
+        for block_no in &lp.blocks {
+            if let Some(phis) = &mut cfg.blocks[*block_no].phis {
+                if phis.contains(var_no) {
+                    phis.insert(*reduced);
+                }
+            }
+        }
+    }
+
+    // insert the updates from the end, so that the instruction numbers remain valid
+    updates.sort_by_key(|(block_no, instr_no, _)| (*block_no, *instr_no));
+
+    for (block_no, instr_no, update) in updates.into_iter().rev() {
+        cfg.blocks[block_no].instr.insert(instr_no + 1, update);
+    }
+
+    let block = &mut cfg.blocks[preheader];
+    let branch = block.instr.pop().unwrap();
+
+    block.instr.extend(init);
+    block.instr.push(branch);
+}
@@ -0,0 +1,267 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{assigned, expressions_mut, operands_mut, without_locations, Loop};
+use crate::codegen::cfg::{ControlFlowGraph, Instr};
+use crate::codegen::vartable::{Storage, Variable};
+use crate::codegen::{Builtin, Expression};
+use crate::sema::ast::{Namespace, RetrieveType, Type};
+use num_bigint::BigInt;
+use num_traits::Zero;
+use solang_parser::pt::{Identifier, Loc};
+use std::collections::HashSet;
+
+/// What the loop modifies, which decides which expressions do not change while it executes
+pub(super) struct Invariants {
+    /// Variables which are assigned in the loop
+    assigned: HashSet<usize>,
+    /// The loop writes to contract storage, or calls code which might
+    writes_storage: bool,
+    /// The loop might change the length or location of memory arrays
+    resizes_arrays: bool,
+    /// The expressions which are moved to the preheader, and the variable which holds the value
+    hoisted: Vec<(Expression, usize)>,
+    next_id: usize,
+}
+
+impl Invariants {
+    pub(super) fn new(cfg: &ControlFlowGraph, lp: &Loop, next_id: usize) -> Self {
+        let mut invariants = Invariants {
+            assigned: HashSet::new(),
+            writes_storage: false,
+            resizes_arrays: false,
+            hoisted: Vec::new(),
+            next_id,
+        };
+
+        for instr in lp
+            .blocks
+            .iter()
+            .flat_map(|block_no| cfg.blocks[*block_no].instr.iter())
+        {
+            invariants.assigned.extend(assigned(instr));
+
+            match instr {
+                Instr::SetStorage { .. }
+                | Instr::SetStorageBytes { .. }
+                | Instr::ClearStorage { .. }
+                | Instr::PushStorage { .. }
+                | Instr::PopStorage { .. }
+                | Instr::ExternalCall { .. }
+                | Instr::Constructor { .. }
+                | Instr::ValueTransfer { .. }
+                | Instr::SelfDestruct { .. } => {
+                    invariants.writes_storage = true;
+                }
+                Instr::Call { .. } => {
+                    invariants.writes_storage = true;
+                    invariants.resizes_arrays = true;
+                }
+                Instr::PushMemory { .. } | Instr::PopMemory { .. } | Instr::MemCopy { .. } => {
+                    invariants.resizes_arrays = true;
+                }
+                // Inline assembly can write anywhere in memory, including the length of an array
+                Instr::Store { dest, .. }
+                    if !matches!(
+                        dest,
+                        Expression::Subscript { .. } | Expression::StructMember { .. }
+                    ) =>
+                {
+                    invariants.resizes_arrays = true;
+                }
+                _ => (),
+            }
+        }
+
+        invariants
+    }
+
+    /// Does the expression evaluate to the same value in every iteration of the loop, without
+    /// side effects or the possibility of failing. Those expressions can be evaluated before
+    /// the loop, even if the loop body is never executed.
+    pub(super) fn invariant(&self, expr: &Expression) -> bool {
+        match expr {
+            Expression::NumberLiteral { .. }
+            | Expression::BoolLiteral { .. }
+            | Expression::FunctionArg { .. } => true,
+            Expression::Variable { var_no, .. } => !self.assigned.contains(var_no),
+            Expression::Add {
+                overflowing: true,
+                left,
+                right,
+                ..
+            }
+            | Expression::Subtract {
+                overflowing: true,
+                left,
+                right,
+                ..
+            }
+            | Expression::Multiply {
+                overflowing: true,
+                left,
+                right,
+                ..
+            }
+            | Expression::BitwiseAnd { left, right, .. }
+            | Expression::BitwiseOr { left, right, .. }
+            | Expression::BitwiseXor { left, right, .. }
+            | Expression::ShiftLeft { left, right, .. }
+            | Expression::ShiftRight { left, right, .. } => {
+                self.invariant(left) && self.invariant(right)
+            }
+            // The division by zero check is part of the instruction
+            Expression::UnsignedDivide { left, right, .. }
+            | Expression::SignedDivide { left, right, .. }
+            | Expression::UnsignedModulo { left, right, .. }
+            | Expression::SignedModulo { left, right, .. } => {
+                matches!(right.as_ref(), Expression::NumberLiteral { value, .. } if value > &BigInt::zero())
+                    && self.invariant(left)
+            }
+            Expression::Equal { left, right, .. }
+            | Expression::NotEqual { left, right, .. }
+            | Expression::Less { left, right, .. }
+            | Expression::LessEqual { left, right, .. }
+            | Expression::More { left, right, .. }
+            | Expression::MoreEqual { left, right, .. } => {
+                left.ty().is_primitive() && self.invariant(left) && self.invariant(right)
+            }
+            Expression::ZeroExt { expr, .. }
+            | Expression::SignExt { expr, .. }
+            | Expression::Trunc { expr, .. }
+            | Expression::Not { expr, .. }
+            | Expression::BitwiseNot { expr, .. } => self.invariant(expr),
+            Expression::Cast { ty, expr, .. } => {
+                ty.is_primitive() && expr.ty().is_primitive() && self.invariant(expr)
+            }
+            Expression::Keccak256 { exprs, .. } => exprs
+                .iter()
+                .all(|expr| expr.ty().is_primitive() && self.invariant(expr)),
+            // These do not change during a call
+            Expression::Builtin {
+                kind:
+                    Builtin::Sender
+                    | Builtin::Value
+                    | Builtin::Timestamp
+                    | Builtin::BlockNumber
+                    | Builtin::Origin
+                    | Builtin::ChainId,
+                args,
+                ..
+            } => args.is_empty(),
+            Expression::Builtin {
+                kind: Builtin::ArrayLength,
+                args,
+                ..
+            } => {
+                !self.resizes_arrays
+                    && !args[0].ty().is_contract_storage()
+                    && self.invariant(&args[0])
+            }
+            Expression::StorageArrayLength { array, .. } => {
+                !self.writes_storage && self.invariant(array)
+            }
+            // The address of an array element in memory; the bounds have already been checked
+            Expression::Subscript {
+                ty: Type::Ref(_),
+                array_ty,
+                expr,
+                index,
+                ..
+            } => {
+                !self.resizes_arrays
+                    && array_ty.is_dynamic_memory()
+                    && self.invariant(expr)
+                    && self.invariant(index)
+            }
+            _ => false,
+        }
+    }
+
+    /// Replace the invariant parts of an expression with variables which are set in the preheader
+    pub(super) fn hoist(&mut self, expr: &mut Expression) {
+        if trivial(expr) {
+            return;
+        }
+
+        if self.invariant(expr) {
+            let hoisted = without_locations(expr);
+
+            let var_no = match self.hoisted.iter().find(|(e, _)| *e == hoisted) {
+                Some((_, var_no)) => *var_no,
+                None => {
+                    let var_no = self.next_id;
+                    self.next_id += 1;
+                    self.hoisted.push((hoisted, var_no));
+                    var_no
+                }
+            };
+
+            *expr = Expression::Variable {
+                loc: Loc::Codegen,
+                ty: expr.ty(),
+                var_no,
+            };
+        } else {
+            for operand in operands_mut(expr) {
+                self.hoist(operand);
+            }
+        }
+    }
+}
+
+/// Nothing is gained by moving these
+fn trivial(expr: &Expression) -> bool {
+    matches!(
+        expr,
+        Expression::Variable { .. }
+            | Expression::NumberLiteral { .. }
+            | Expression::BoolLiteral { .. }
+            | Expression::FunctionArg { .. }
+    )
+}
+
+/// Move the expressions which do not change in the loop to the preheader, so they are only
+/// evaluated once.
+pub(super) fn hoist_invariants(
+    cfg: &mut ControlFlowGraph,
+    lp: &Loop,
+    preheader: usize,
+    ns: &mut Namespace,
+) {
+    let mut invariants = Invariants::new(cfg, lp, ns.next_id);
+
+    for block_no in &lp.blocks {
+        for instr in &mut cfg.blocks[*block_no].instr {
+            for expr in expressions_mut(instr) {
+                invariants.hoist(expr);
+            }
+        }
+    }
+
+    ns.next_id = invariants.next_id;
+
+    let block = &mut cfg.blocks[preheader];
+    let branch = block.instr.pop().unwrap();
+
+    for (expr, var_no) in invariants.hoisted {
+        cfg.vars.insert(
+            var_no,
+            Variable {
+                id: Identifier {
+                    loc: Loc::Codegen,
+                    name: format!("invariant.temp.{var_no}"),
+                },
+                ty: expr.ty(),
+                storage: Storage::Local,
+            },
+        );
+
+        block.instr.push(Instr::Set {
+            loc: Loc::Codegen,
+            res: var_no,
+            expr,
+        });
+    }
+
+    block.instr.push(branch);
+}
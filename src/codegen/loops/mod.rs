@@ -0,0 +1,504 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Loop optimizations. Loops are found in the control flow graph, and each loop is given a
+//! preheader: a block which is executed once before entering the loop. Expressions which do not
+//! change while the loop executes are moved to the preheader, and multiplications of induction
+//! variables are replaced with additions.
+
+mod induction;
+mod invariant;
+mod tests;
+mod trip_count;
+
+pub use trip_count::TripCount;
+
+use super::cfg::{BasicBlock, ControlFlowGraph, Instr, InternalCallTy};
+use super::inline::renumber_blocks;
+use super::reaching_definitions;
+use crate::codegen::Expression;
+use crate::sema::ast::{ExternalCallAccounts, Namespace, StringLocation};
+use solang_parser::pt::Loc;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A natural loop in the control flow graph
+#[derive(Debug, PartialEq, Eq)]
+pub struct Loop {
+    /// The block through which the loop is entered
+    pub header: usize,
+    /// The blocks which branch back to the header
+    pub latches: Vec<usize>,
+    /// All the blocks of the loop, including the header
+    pub blocks: BTreeSet<usize>,
+}
+
+impl Loop {
+    /// If the loop is entered from a single block which only branches to the loop header, then
+    /// that block is the preheader
+    pub fn preheader(&self, cfg: &ControlFlowGraph, preds: &[Vec<usize>]) -> Option<usize> {
+        let mut outside = preds[self.header]
+            .iter()
+            .filter(|block_no| !self.blocks.contains(block_no));
+
+        match (outside.next(), outside.next()) {
+            (Some(block_no), None)
+                if cfg.blocks[*block_no]
+                    .successors()
+                    .iter()
+                    .all(|succ| *succ == self.header) =>
+            {
+                Some(*block_no)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl ControlFlowGraph {
+    /// The blocks which can branch to each block
+    pub fn predecessors(&self) -> Vec<Vec<usize>> {
+        let mut preds = vec![Vec::new(); self.blocks.len()];
+
+        for (block_no, block) in self.blocks.iter().enumerate() {
+            for succ in block.successors() {
+                if !preds[succ].contains(&block_no) {
+                    preds[succ].push(block_no);
+                }
+            }
+        }
+
+        preds
+    }
+
+    /// Find the edges which jump back to a block which is being executed, i.e. the edges which
+    /// form a loop. The second field is the loop header.
+    pub fn back_edges(&self) -> Vec<(usize, usize)> {
+        let mut edges = Vec::new();
+        let mut visited = vec![false; self.blocks.len()];
+        let mut on_stack = vec![false; self.blocks.len()];
+        let mut stack = vec![(0, self.blocks[0].successors(), 0)];
+
+        visited[0] = true;
+        on_stack[0] = true;
+
+        while let Some((block_no, succs, next)) = stack.last_mut() {
+            if let Some(succ) = succs.get(*next).cloned() {
+                *next += 1;
+
+                if on_stack[succ] {
+                    edges.push((*block_no, succ));
+                } else if !visited[succ] {
+                    visited[succ] = true;
+                    on_stack[succ] = true;
+                    stack.push((succ, self.blocks[succ].successors(), 0));
+                }
+            } else {
+                on_stack[*block_no] = false;
+                stack.pop();
+            }
+        }
+
+        edges
+    }
+
+    /// Find the natural loops. Back edges to the same header are merged into a single loop. An
+    /// inner loop has fewer blocks than the loop which contains it, so the innermost loops
+    /// are returned first.
+    pub fn loops(&self) -> Vec<Loop> {
+        let preds = self.predecessors();
+        let mut loops: BTreeMap<usize, Loop> = BTreeMap::new();
+
+        for (latch, header) in self.back_edges() {
+            let lp = loops.entry(header).or_insert_with(|| Loop {
+                header,
+                latches: Vec::new(),
+                blocks: BTreeSet::from([header]),
+            });
+
+            lp.latches.push(latch);
+
+            let mut todo = vec![latch];
+
+            while let Some(block_no) = todo.pop() {
+                if lp.blocks.insert(block_no) {
+                    todo.extend(&preds[block_no]);
+                }
+            }
+        }
+
+        let mut loops: Vec<Loop> = loops.into_values().collect();
+
+        loops.sort_by_key(|lp| lp.blocks.len());
+
+        loops
+    }
+}
+
+/// Move loop invariant expressions to the loop preheader, and reduce the strength of expressions
+/// using induction variables.
+pub fn loop_optimization(cfg: &mut ControlFlowGraph, ns: &mut Namespace) {
+    if cfg.back_edges().is_empty() {
+        return;
+    }
+
+    insert_preheaders(cfg);
+
+    let preds = cfg.predecessors();
+
+    for lp in cfg.loops() {
+        let Some(preheader) = lp.preheader(cfg, &preds) else {
+            continue;
+        };
+
+        invariant::hoist_invariants(cfg, &lp, preheader, ns);
+        induction::reduce_induction_variables(cfg, &lp, preheader, ns);
+    }
+
+    // the instructions have changed, so the reaching definitions have to be calculated again
+    reaching_definitions::refresh(cfg);
+}
+
+/// Make sure that every loop has a preheader. The new block is inserted just before the loop
+/// header, so that the blocks remain in execution order.
+fn insert_preheaders(cfg: &mut ControlFlowGraph) {
+    loop {
+        let preds = cfg.predecessors();
+
+        // The entry block cannot be preceded by another block
+        let Some(lp) = cfg.loops().into_iter().find(|lp| {
+            lp.header != 0
+                && preds[lp.header]
+                    .iter()
+                    .any(|block_no| !lp.blocks.contains(block_no))
+                && lp.preheader(cfg, &preds).is_none()
+        }) else {
+            break;
+        };
+
+        let header = lp.header;
+        let outside: Vec<usize> = preds[header]
+            .iter()
+            .filter(|block_no| !lp.blocks.contains(block_no))
+            .map(|block_no| {
+                if *block_no >= header {
+                    block_no + 1
+                } else {
+                    *block_no
+                }
+            })
+            .collect();
+
+        for block in &mut cfg.blocks {
+            for instr in &mut block.instr {
+                renumber_blocks(instr, |block_no| {
+                    if block_no >= header {
+                        block_no + 1
+                    } else {
+                        block_no
+                    }
+                });
+            }
+        }
+
+        for block_no in &outside {
+            for instr in &mut cfg.blocks[*block_no].instr {
+                renumber_blocks(instr, |block_no| {
+                    if block_no == header + 1 {
+                        header
+                    } else {
+                        block_no
+                    }
+                });
+            }
+        }
+
+        let preheader = BasicBlock {
+            // the loop may be entered from more than one place
+            phis: if outside.len() > 1 {
+                cfg.blocks[header].phis.clone()
+            } else {
+                None
+            },
+            name: "preheader".to_owned(),
+            instr: vec![Instr::Branch { block: header + 1 }],
+            ..Default::default()
+        };
+
+        cfg.blocks.insert(header, preheader);
+    }
+}
+
+/// The variables which are given a new value by the instruction
+pub(super) fn assigned(instr: &Instr) -> Vec<usize> {
+    match instr {
+        Instr::Set { res, .. }
+        | Instr::LoadStorage { res, .. }
+        | Instr::PushStorage { res, .. }
+        | Instr::PopStorage { res: Some(res), .. }
+        | Instr::AccountAccess { var_no: res, .. } => vec![*res],
+        Instr::Call { res, .. } => res.clone(),
+        Instr::PushMemory { res, array, .. } | Instr::PopMemory { res, array, .. } => {
+            vec![*res, *array]
+        }
+        Instr::Constructor { success, res, .. } => {
+            let mut vars = vec![*res];
+            vars.extend(success);
+            vars
+        }
+        Instr::ExternalCall { success, .. } | Instr::ValueTransfer { success, .. } => {
+            success.iter().copied().collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// All the expressions used by an instruction
+fn expressions_mut(instr: &mut Instr) -> Vec<&mut Expression> {
+    match instr {
+        Instr::Set { expr, .. }
+        | Instr::BranchCond { cond: expr, .. }
+        | Instr::Print { expr }
+        | Instr::AssertFailure {
+            encoded_args: Some(expr),
+        }
+        | Instr::LoadStorage { storage: expr, .. }
+        | Instr::ClearStorage { storage: expr, .. }
+        | Instr::PopStorage { storage: expr, .. }
+        | Instr::SelfDestruct { recipient: expr }
+        | Instr::Switch { cond: expr, .. } => vec![expr],
+        Instr::PushMemory { value, .. } => vec![value.as_mut()],
+        Instr::Call { args, call, .. } => {
+            let mut exprs: Vec<&mut Expression> = args.iter_mut().collect();
+
+            if let InternalCallTy::Dynamic(expr) = call {
+                exprs.push(expr);
+            }
+
+            exprs
+        }
+        Instr::Return { value } => value.iter_mut().collect(),
+        Instr::Store { dest, data } => vec![dest, data],
+        Instr::SetStorage { value, storage, .. } => vec![value, storage],
+        Instr::SetStorageBytes {
+            value,
+            storage,
+            offset,
+        } => vec![value, storage, offset],
+        Instr::PushStorage { value, storage, .. } => {
+            let mut exprs = vec![storage];
+            exprs.extend(value);
+            exprs
+        }
+        Instr::Constructor {
+            encoded_args,
+            value,
+            gas,
+            salt,
+            address,
+            seeds,
+            accounts,
+            ..
+        } => {
+            let mut exprs = vec![encoded_args, gas];
+            exprs.extend(value);
+            exprs.extend(salt);
+            exprs.extend(address);
+            exprs.extend(seeds);
+
+            if let ExternalCallAccounts::Present(accounts) = accounts {
+                exprs.push(accounts);
+            }
+
+            exprs
+        }
+        Instr::ExternalCall {
+            address,
+            accounts,
+            seeds,
+            payload,
+            value,
+            gas,
+            flags,
+            ..
+        } => {
+            let mut exprs = vec![payload, value, gas];
+            exprs.extend(address);
+            exprs.extend(seeds);
+            exprs.extend(flags);
+
+            if let ExternalCallAccounts::Present(accounts) = accounts {
+                exprs.push(accounts);
+            }
+
+            exprs
+        }
+        Instr::ValueTransfer { address, value, .. } => vec![address, value],
+        Instr::EmitEvent { data, topics, .. } => {
+            let mut exprs = vec![data];
+            exprs.extend(topics.iter_mut());
+            exprs
+        }
+        Instr::WriteBuffer { buf, offset, value } => vec![buf, offset, value],
+        Instr::MemCopy {
+            source,
+            destination,
+            bytes,
+        } => vec![source, destination, bytes],
+        Instr::ReturnData { data, data_len } => vec![data, data_len],
+        Instr::AssertFailure { encoded_args: None }
+        | Instr::Branch { .. }
+        | Instr::PopMemory { .. }
+        | Instr::Nop
+        | Instr::ReturnCode { .. }
+        | Instr::Unimplemented { .. }
+        | Instr::AccountAccess { .. } => Vec::new(),
+    }
+}
+
+/// The operands of an expression
+fn operands_mut(expr: &mut Expression) -> Vec<&mut Expression> {
+    match expr {
+        Expression::Add { left, right, .. }
+        | Expression::Subtract { left, right, .. }
+        | Expression::Multiply { left, right, .. }
+        | Expression::UnsignedDivide { left, right, .. }
+        | Expression::SignedDivide { left, right, .. }
+        | Expression::UnsignedModulo { left, right, .. }
+        | Expression::SignedModulo { left, right, .. }
+        | Expression::BitwiseAnd { left, right, .. }
+        | Expression::BitwiseOr { left, right, .. }
+        | Expression::BitwiseXor { left, right, .. }
+        | Expression::ShiftLeft { left, right, .. }
+        | Expression::ShiftRight { left, right, .. }
+        | Expression::Equal { left, right, .. }
+        | Expression::NotEqual { left, right, .. }
+        | Expression::Less { left, right, .. }
+        | Expression::LessEqual { left, right, .. }
+        | Expression::More { left, right, .. }
+        | Expression::MoreEqual { left, right, .. }
+        | Expression::Power {
+            base: left,
+            exp: right,
+            ..
+        }
+        | Expression::Subscript {
+            expr: left,
+            index: right,
+            ..
+        }
+        | Expression::AdvancePointer {
+            pointer: left,
+            bytes_offset: right,
+        } => vec![left.as_mut(), right.as_mut()],
+        Expression::ZeroExt { expr, .. }
+        | Expression::SignExt { expr, .. }
+        | Expression::Trunc { expr, .. }
+        | Expression::Cast { expr, .. }
+        | Expression::BytesCast { expr, .. }
+        | Expression::Not { expr, .. }
+        | Expression::BitwiseNot { expr, .. }
+        | Expression::Negate { expr, .. }
+        | Expression::Load { expr, .. }
+        | Expression::GetRef { expr, .. }
+        | Expression::StructMember { expr, .. }
+        | Expression::StorageArrayLength { array: expr, .. }
+        | Expression::AllocDynamicBytes { size: expr, .. } => vec![expr.as_mut()],
+        Expression::Keccak256 { exprs: values, .. }
+        | Expression::Builtin { args: values, .. }
+        | Expression::StructLiteral { values, .. }
+        | Expression::ArrayLiteral { values, .. }
+        | Expression::ConstArrayLiteral { values, .. } => values.iter_mut().collect(),
+        Expression::FormatString { args, .. } => args.iter_mut().map(|(_, expr)| expr).collect(),
+        Expression::StringCompare { left, right, .. } => {
+            let mut exprs = Vec::new();
+
+            if let StringLocation::RunTime(expr) = left {
+                exprs.push(expr.as_mut());
+            }
+
+            if let StringLocation::RunTime(expr) = right {
+                exprs.push(expr.as_mut());
+            }
+
+            exprs
+        }
+        Expression::BoolLiteral { .. }
+        | Expression::BytesLiteral { .. }
+        | Expression::FunctionArg { .. }
+        | Expression::InternalFunctionCfg { .. }
+        | Expression::NumberLiteral { .. }
+        | Expression::Poison
+        | Expression::RationalNumberLiteral { .. }
+        | Expression::ReturnData { .. }
+        | Expression::Undefined { .. }
+        | Expression::Variable { .. } => Vec::new(),
+    }
+}
+
+/// Copy the expression without source locations, so that equal expressions compare equal
+fn without_locations(expr: &Expression) -> Expression {
+    let mut expr = expr.clone();
+
+    clear_locations(&mut expr);
+
+    expr
+}
+
+fn clear_locations(expr: &mut Expression) {
+    match expr {
+        Expression::Add { loc, .. }
+        | Expression::AllocDynamicBytes { loc, .. }
+        | Expression::ArrayLiteral { loc, .. }
+        | Expression::BitwiseAnd { loc, .. }
+        | Expression::BitwiseOr { loc, .. }
+        | Expression::BitwiseXor { loc, .. }
+        | Expression::BoolLiteral { loc, .. }
+        | Expression::Builtin { loc, .. }
+        | Expression::BytesCast { loc, .. }
+        | Expression::BytesLiteral { loc, .. }
+        | Expression::Cast { loc, .. }
+        | Expression::BitwiseNot { loc, .. }
+        | Expression::ConstArrayLiteral { loc, .. }
+        | Expression::UnsignedDivide { loc, .. }
+        | Expression::SignedDivide { loc, .. }
+        | Expression::Equal { loc, .. }
+        | Expression::FormatString { loc, .. }
+        | Expression::FunctionArg { loc, .. }
+        | Expression::GetRef { loc, .. }
+        | Expression::Keccak256 { loc, .. }
+        | Expression::Less { loc, .. }
+        | Expression::LessEqual { loc, .. }
+        | Expression::Load { loc, .. }
+        | Expression::UnsignedModulo { loc, .. }
+        | Expression::SignedModulo { loc, .. }
+        | Expression::More { loc, .. }
+        | Expression::MoreEqual { loc, .. }
+        | Expression::Multiply { loc, .. }
+        | Expression::Not { loc, .. }
+        | Expression::NotEqual { loc, .. }
+        | Expression::NumberLiteral { loc, .. }
+        | Expression::Power { loc, .. }
+        | Expression::RationalNumberLiteral { loc, .. }
+        | Expression::ReturnData { loc }
+        | Expression::SignExt { loc, .. }
+        | Expression::ShiftLeft { loc, .. }
+        | Expression::ShiftRight { loc, .. }
+        | Expression::StorageArrayLength { loc, .. }
+        | Expression::StringCompare { loc, .. }
+        | Expression::StructLiteral { loc, .. }
+        | Expression::StructMember { loc, .. }
+        | Expression::Subscript { loc, .. }
+        | Expression::Subtract { loc, .. }
+        | Expression::Trunc { loc, .. }
+        | Expression::Negate { loc, .. }
+        | Expression::Variable { loc, .. }
+        | Expression::ZeroExt { loc, .. } => *loc = Loc::Codegen,
+        Expression::AdvancePointer { .. }
+        | Expression::InternalFunctionCfg { .. }
+        | Expression::Poison
+        | Expression::Undefined { .. } => (),
+    }
+
+    for operand in operands_mut(expr) {
+        clear_locations(operand);
+    }
+}
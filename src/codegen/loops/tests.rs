@@ -0,0 +1,491 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(test)]
+
+use super::{loop_optimization, TripCount};
+use crate::codegen::cfg::{ASTFunction, ControlFlowGraph, Instr};
+use crate::codegen::reaching_definitions;
+use crate::codegen::{Builtin, Expression};
+use crate::sema::ast::{ArrayLength, Namespace, RetrieveType, Type};
+use crate::Target;
+use num_bigint::BigInt;
+use solang_parser::pt::Loc;
+use std::collections::BTreeSet;
+
+const I: usize = 0;
+const X: usize = 1;
+
+fn var(var_no: usize, ty: Type) -> Expression {
+    Expression::Variable {
+        loc: Loc::Codegen,
+        ty,
+        var_no,
+    }
+}
+
+fn num(value: i64, ty: Type) -> Expression {
+    Expression::NumberLiteral {
+        loc: Loc::Codegen,
+        ty,
+        value: BigInt::from(value),
+    }
+}
+
+fn array_arg() -> Expression {
+    Expression::FunctionArg {
+        loc: Loc::Codegen,
+        ty: Type::Array(Box::new(Type::Uint(64)), vec![ArrayLength::Dynamic]),
+        arg_no: 0,
+    }
+}
+
+fn array_length() -> Expression {
+    Expression::Builtin {
+        loc: Loc::Codegen,
+        tys: vec![Type::Uint(32)],
+        kind: Builtin::ArrayLength,
+        args: vec![array_arg()],
+    }
+}
+
+fn namespace() -> Namespace {
+    let mut ns = Namespace::new(Target::default_polkadot());
+    ns.next_id = 10;
+    ns
+}
+
+/// for (uint32 i = 0; i < 100; i++) { body }
+fn counting_loop(body: Vec<Instr>) -> ControlFlowGraph {
+    let mut cfg = ControlFlowGraph::new("loop".to_string(), ASTFunction::None);
+
+    let entry = 0;
+    let cond = cfg.new_basic_block("cond".to_string());
+    let body_block = cfg.new_basic_block("body".to_string());
+    let end = cfg.new_basic_block("endfor".to_string());
+
+    cfg.blocks[entry].instr = vec![
+        Instr::Set {
+            loc: Loc::Codegen,
+            res: I,
+            expr: num(0, Type::Uint(32)),
+        },
+        Instr::Branch { block: cond },
+    ];
+
+    cfg.set_phis(cond, BTreeSet::from([I, X]));
+    cfg.blocks[cond].instr = vec![Instr::BranchCond {
+        cond: Expression::Less {
+            loc: Loc::Codegen,
+            signed: false,
+            left: Box::new(var(I, Type::Uint(32))),
+            right: Box::new(num(100, Type::Uint(32))),
+        },
+        true_block: body_block,
+        false_block: end,
+    }];
+
+    cfg.blocks[body_block].instr = body;
+    cfg.blocks[body_block].instr.push(Instr::Set {
+        loc: Loc::Codegen,
+        res: I,
+        expr: Expression::Add {
+            loc: Loc::Codegen,
+            ty: Type::Uint(32),
+            overflowing: false,
+            left: Box::new(var(I, Type::Uint(32))),
+            right: Box::new(num(1, Type::Uint(32))),
+        },
+    });
+    cfg.blocks[body_block]
+        .instr
+        .push(Instr::Branch { block: cond });
+
+    cfg.blocks[end].instr = vec![Instr::Return { value: Vec::new() }];
+
+    cfg
+}
+
+fn set_x(expr: Expression) -> Instr {
+    Instr::Set {
+        loc: Loc::Codegen,
+        res: X,
+        expr,
+    }
+}
+
+#[test]
+fn find_loops() {
+    let cfg = counting_loop(Vec::new());
+
+    assert_eq!(cfg.back_edges(), vec![(2, 1)]);
+    assert_eq!(
+        cfg.predecessors(),
+        vec![vec![], vec![0, 2], vec![1], vec![1]]
+    );
+
+    let loops = cfg.loops();
+
+    assert_eq!(loops.len(), 1);
+    assert_eq!(loops[0].header, 1);
+    assert_eq!(loops[0].latches, vec![2]);
+    assert_eq!(loops[0].blocks, BTreeSet::from([1, 2]));
+    assert_eq!(loops[0].preheader(&cfg, &cfg.predecessors()), Some(0));
+}
+
+#[test]
+fn nested_loops() {
+    let mut cfg = ControlFlowGraph::new("nested".to_string(), ASTFunction::None);
+
+    for name in ["outer", "inner", "inner_body", "outer_next", "end"] {
+        cfg.new_basic_block(name.to_string());
+    }
+
+    let cond = |true_block, false_block| Instr::BranchCond {
+        cond: Expression::FunctionArg {
+            loc: Loc::Codegen,
+            ty: Type::Bool,
+            arg_no: 0,
+        },
+        true_block,
+        false_block,
+    };
+
+    cfg.blocks[0].instr = vec![Instr::Branch { block: 1 }];
+    cfg.blocks[1].instr = vec![cond(2, 5)];
+    cfg.blocks[2].instr = vec![cond(3, 4)];
+    cfg.blocks[3].instr = vec![Instr::Branch { block: 2 }];
+    cfg.blocks[4].instr = vec![Instr::Branch { block: 1 }];
+    cfg.blocks[5].instr = vec![Instr::Return { value: Vec::new() }];
+
+    let loops = cfg.loops();
+
+    // the innermost loop comes first
+    assert_eq!(loops.len(), 2);
+    assert_eq!(loops[0].header, 2);
+    assert_eq!(loops[0].blocks, BTreeSet::from([2, 3]));
+    assert_eq!(loops[1].header, 1);
+    assert_eq!(loops[1].blocks, BTreeSet::from([1, 2, 3, 4]));
+
+    // the outer loop is the only predecessor of the inner loop, but it also branches elsewhere
+    assert_eq!(loops[0].preheader(&cfg, &cfg.predecessors()), None);
+
+    let mut ns = namespace();
+    loop_optimization(&mut cfg, &mut ns);
+
+    assert_eq!(cfg.blocks.len(), 7);
+    assert_eq!(cfg.blocks[2].name, "preheader");
+    assert!(matches!(
+        cfg.blocks[1].instr[0],
+        Instr::BranchCond {
+            true_block: 2,
+            false_block: 6,
+            ..
+        }
+    ));
+    assert!(matches!(
+        cfg.blocks[2].instr[..],
+        [Instr::Branch { block: 3 }]
+    ));
+    assert!(matches!(
+        cfg.blocks[4].instr[..],
+        [Instr::Branch { block: 3 }]
+    ));
+    assert!(matches!(
+        cfg.blocks[5].instr[..],
+        [Instr::Branch { block: 1 }]
+    ));
+
+    let loops = cfg.loops();
+    let preds = cfg.predecessors();
+
+    assert_eq!(loops[0].header, 3);
+    assert_eq!(loops[0].preheader(&cfg, &preds), Some(2));
+}
+
+#[test]
+fn hoist_array_length() {
+    let mut cfg = counting_loop(vec![set_x(Expression::ZeroExt {
+        loc: Loc::Codegen,
+        ty: Type::Uint(256),
+        expr: Box::new(array_length()),
+    })]);
+    let mut ns = namespace();
+
+    loop_optimization(&mut cfg, &mut ns);
+
+    assert_eq!(ns.next_id, 11);
+    assert_eq!(cfg.vars[&10].id.name, "invariant.temp.10");
+
+    // the length is loaded in the preheader, before the branch to the loop
+    let entry = &cfg.blocks[0].instr;
+    assert_eq!(entry.len(), 3);
+    assert!(matches!(
+        &entry[1],
+        Instr::Set {
+            res: 10,
+            expr: Expression::ZeroExt { .. },
+            ..
+        }
+    ));
+    assert!(matches!(entry[2], Instr::Branch { block: 1 }));
+
+    assert!(matches!(
+        &cfg.blocks[2].instr[0],
+        Instr::Set {
+            res: X,
+            expr: Expression::Variable { var_no: 10, .. },
+            ..
+        }
+    ));
+}
+
+#[test]
+fn hoist_keccak_once() {
+    let keccak = || Expression::Keccak256 {
+        loc: Loc::Codegen,
+        ty: Type::Uint(256),
+        exprs: vec![
+            num(0, Type::Uint(256)),
+            Expression::Builtin {
+                loc: Loc::Codegen,
+                tys: vec![Type::Address(false)],
+                kind: Builtin::Sender,
+                args: Vec::new(),
+            },
+        ],
+    };
+
+    let mut cfg = counting_loop(vec![
+        set_x(keccak()),
+        Instr::Set {
+            loc: Loc::Codegen,
+            res: 2,
+            expr: keccak(),
+        },
+    ]);
+    let mut ns = namespace();
+
+    loop_optimization(&mut cfg, &mut ns);
+
+    // both expressions are replaced by the same variable
+    assert_eq!(ns.next_id, 11);
+    assert!(matches!(
+        &cfg.blocks[2].instr[..2],
+        [
+            Instr::Set {
+                expr: Expression::Variable { var_no: 10, .. },
+                ..
+            },
+            Instr::Set {
+                expr: Expression::Variable { var_no: 10, .. },
+                ..
+            }
+        ]
+    ));
+}
+
+#[test]
+fn do_not_hoist() {
+    // this might overflow, which should not happen if the loop body is never executed
+    let checked = Expression::Add {
+        loc: Loc::Codegen,
+        ty: Type::Uint(32),
+        overflowing: false,
+        left: Box::new(num(1, Type::Uint(32))),
+        right: Box::new(Expression::FunctionArg {
+            loc: Loc::Codegen,
+            ty: Type::Uint(32),
+            arg_no: 1,
+        }),
+    };
+
+    // the loop changes the length of the array
+    let push = Instr::PushMemory {
+        res: 3,
+        ty: array_arg().ty(),
+        array: 4,
+        value: Box::new(num(1, Type::Uint(64))),
+    };
+
+    let mut cfg = counting_loop(vec![
+        set_x(checked.clone()),
+        Instr::Set {
+            loc: Loc::Codegen,
+            res: 2,
+            expr: array_length(),
+        },
+        push,
+    ]);
+    let mut ns = namespace();
+
+    loop_optimization(&mut cfg, &mut ns);
+
+    assert_eq!(ns.next_id, 10);
+    assert_eq!(cfg.blocks[0].instr.len(), 2);
+    assert!(matches!(&cfg.blocks[2].instr[0], Instr::Set { expr, .. } if *expr == checked));
+    assert!(matches!(
+        &cfg.blocks[2].instr[1],
+        Instr::Set {
+            expr: Expression::Builtin {
+                kind: Builtin::ArrayLength,
+                ..
+            },
+            ..
+        }
+    ));
+}
+
+#[test]
+fn reduce_induction_variable() {
+    let mut cfg = counting_loop(vec![set_x(Expression::Multiply {
+        loc: Loc::Codegen,
+        ty: Type::Uint(64),
+        overflowing: true,
+        left: Box::new(Expression::ZeroExt {
+            loc: Loc::Codegen,
+            ty: Type::Uint(64),
+            expr: Box::new(var(I, Type::Uint(32))),
+        }),
+        right: Box::new(num(12, Type::Uint(64))),
+    })]);
+    let mut ns = namespace();
+
+    loop_optimization(&mut cfg, &mut ns);
+
+    assert_eq!(ns.next_id, 11);
+    assert_eq!(cfg.vars[&10].id.name, "induction.temp.10");
+
+    // the multiplication is done once, before the loop
+    assert!(matches!(
+        &cfg.blocks[0].instr[1],
+        Instr::Set {
+            res: 10,
+            expr: Expression::Multiply { .. },
+            ..
+        }
+    ));
+
+    let body = &cfg.blocks[2].instr;
+
+    assert!(matches!(
+        &body[0],
+        Instr::Set {
+            res: X,
+            expr: Expression::Variable { var_no: 10, .. },
+            ..
+        }
+    ));
+
+    // and the new variable is incremented along with i
+    assert!(matches!(&body[1], Instr::Set { res: I, .. }));
+    assert!(matches!(
+        &body[2],
+        Instr::Set {
+            res: 10,
+            expr: Expression::Add {
+                overflowing: true,
+                left,
+                right,
+                ..
+            },
+            ..
+        } if **left == var(10, Type::Uint(64)) && **right == num(12, Type::Uint(64))
+    ));
+
+    assert!(cfg.blocks[1].phis.as_ref().unwrap().contains(&10));
+}
+
+#[test]
+fn wrapping_extension_not_reduced() {
+    // i is extended, but the multiplication would wrap around differently if i could overflow
+    let mut cfg = counting_loop(Vec::new());
+
+    cfg.blocks[2].instr = vec![
+        set_x(Expression::Multiply {
+            loc: Loc::Codegen,
+            ty: Type::Uint(64),
+            overflowing: true,
+            left: Box::new(Expression::ZeroExt {
+                loc: Loc::Codegen,
+                ty: Type::Uint(64),
+                expr: Box::new(var(I, Type::Uint(32))),
+            }),
+            right: Box::new(num(12, Type::Uint(64))),
+        }),
+        Instr::Set {
+            loc: Loc::Codegen,
+            res: I,
+            expr: Expression::Add {
+                loc: Loc::Codegen,
+                ty: Type::Uint(32),
+                overflowing: true,
+                left: Box::new(var(I, Type::Uint(32))),
+                right: Box::new(num(1, Type::Uint(32))),
+            },
+        },
+        Instr::Branch { block: 1 },
+    ];
+
+    let mut ns = namespace();
+
+    loop_optimization(&mut cfg, &mut ns);
+
+    assert_eq!(ns.next_id, 10);
+    assert!(matches!(
+        &cfg.blocks[2].instr[0],
+        Instr::Set {
+            expr: Expression::Multiply { .. },
+            ..
+        }
+    ));
+}
+
+/// Replace the bound of the counting loop
+fn with_bound(mut cfg: ControlFlowGraph, bound: Expression) -> ControlFlowGraph {
+    if let Instr::BranchCond {
+        cond: Expression::Less { right, .. },
+        ..
+    } = &mut cfg.blocks[1].instr[0]
+    {
+        *right = Box::new(bound);
+    }
+
+    reaching_definitions::find(&mut cfg);
+
+    cfg
+}
+
+#[test]
+fn trip_count() {
+    let mut cfg = counting_loop(Vec::new());
+
+    reaching_definitions::find(&mut cfg);
+
+    let loops = cfg.loops();
+
+    assert_eq!(
+        loops[0].trip_count(&cfg),
+        TripCount::Constant(BigInt::from(100))
+    );
+
+    // i < array.length
+    let length = Expression::ZeroExt {
+        loc: Loc::Codegen,
+        ty: Type::Uint(32),
+        expr: Box::new(array_length()),
+    };
+    let cfg = with_bound(counting_loop(Vec::new()), length.clone());
+
+    assert_eq!(
+        cfg.loops()[0].trip_count(&cfg),
+        TripCount::Bounded(length, BigInt::from(0))
+    );
+
+    // the bound changes in the loop
+    let cfg = with_bound(
+        counting_loop(vec![set_x(num(5, Type::Uint(32)))]),
+        var(X, Type::Uint(32)),
+    );
+
+    assert_eq!(cfg.loops()[0].trip_count(&cfg), TripCount::Unknown);
+}
@@ -0,0 +1,263 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::induction::InductionVariables;
+use super::invariant::Invariants;
+use super::Loop;
+use crate::codegen::cfg::{ControlFlowGraph, Instr};
+use crate::codegen::Expression;
+use crate::sema::ast::Type;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{One, Signed, Zero};
+
+/// How many times the body of a loop is executed
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+pub enum TripCount {
+    /// The loop is executed at most this many times
+    Constant(BigInt),
+    /// The loop is executed at most as many times as the value of the expression, plus the
+    /// constant. The expression is evaluated before the loop is entered.
+    Bounded(Expression, BigInt),
+    /// Nothing is known about the number of iterations
+    Unknown,
+}
+
+/// The comparison which has to be true for the loop to continue, with the induction variable
+/// on the left hand side
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Less,
+    LessEqual,
+    More,
+    MoreEqual,
+    NotEqual,
+    Equal,
+}
+
+impl Comparison {
+    /// The comparison which is true when this one is false
+    fn negate(self) -> Self {
+        match self {
+            Comparison::Less => Comparison::MoreEqual,
+            Comparison::LessEqual => Comparison::More,
+            Comparison::More => Comparison::LessEqual,
+            Comparison::MoreEqual => Comparison::Less,
+            Comparison::NotEqual => Comparison::Equal,
+            Comparison::Equal => Comparison::NotEqual,
+        }
+    }
+
+    /// The same comparison with the operands swapped
+    fn swap(self) -> Self {
+        match self {
+            Comparison::Less => Comparison::More,
+            Comparison::LessEqual => Comparison::MoreEqual,
+            Comparison::More => Comparison::Less,
+            Comparison::MoreEqual => Comparison::LessEqual,
+            other => other,
+        }
+    }
+}
+
+impl Loop {
+    /// Find an upper bound for the number of times the loop is executed. This is only possible
+    /// if the loop is controlled by an induction variable which is compared with a value which
+    /// does not change in the loop. The reaching definitions of the cfg must be up to date.
+    pub fn trip_count(&self, cfg: &ControlFlowGraph) -> TripCount {
+        let ivs = InductionVariables::new(cfg, self, 0);
+        let invariants = Invariants::new(cfg, self, 0);
+
+        for block_no in std::iter::once(self.header).chain(self.latches.iter().copied()) {
+            let Some(Instr::BranchCond {
+                cond,
+                true_block,
+                false_block,
+            }) = cfg.blocks[block_no].instr.last()
+            else {
+                continue;
+            };
+
+            let continues = match (
+                self.blocks.contains(true_block),
+                self.blocks.contains(false_block),
+            ) {
+                (true, false) => true,
+                (false, true) => false,
+                _ => continue,
+            };
+
+            let Some((var_no, cmp, bound)) = comparison(cond, continues, &ivs) else {
+                continue;
+            };
+
+            if !invariants.invariant(bound) {
+                continue;
+            }
+
+            let count = count(cfg, self, &ivs, var_no, cmp, bound);
+
+            // When the test is at the end of the loop, the body is executed once before the
+            // condition is checked
+            return match count {
+                TripCount::Constant(n) if block_no != self.header => TripCount::Constant(n + 1),
+                TripCount::Bounded(expr, n) if block_no != self.header => {
+                    TripCount::Bounded(expr, n + 1)
+                }
+                count => count,
+            };
+        }
+
+        TripCount::Unknown
+    }
+}
+
+/// Decompose the loop condition into induction variable, comparison, and bound
+fn comparison<'a>(
+    cond: &'a Expression,
+    continues: bool,
+    ivs: &InductionVariables,
+) -> Option<(usize, Comparison, &'a Expression)> {
+    let (cmp, left, right) = match cond {
+        Expression::Not { expr, .. } => return comparison(expr, !continues, ivs),
+        Expression::Less { left, right, .. } => (Comparison::Less, left, right),
+        Expression::LessEqual { left, right, .. } => (Comparison::LessEqual, left, right),
+        Expression::More { left, right, .. } => (Comparison::More, left, right),
+        Expression::MoreEqual { left, right, .. } => (Comparison::MoreEqual, left, right),
+        Expression::NotEqual { left, right, .. } => (Comparison::NotEqual, left, right),
+        Expression::Equal { left, right, .. } => (Comparison::Equal, left, right),
+        _ => return None,
+    };
+
+    let cmp = if continues { cmp } else { cmp.negate() };
+
+    match (left.as_ref(), right.as_ref()) {
+        (Expression::Variable { var_no, .. }, bound) if ivs.basic.contains_key(var_no) => {
+            Some((*var_no, cmp, bound))
+        }
+        (bound, Expression::Variable { var_no, .. }) if ivs.basic.contains_key(var_no) => {
+            Some((*var_no, cmp.swap(), bound))
+        }
+        _ => None,
+    }
+}
+
+/// Count the iterations from the initial value of the induction variable until the comparison
+/// with the bound fails
+fn count(
+    cfg: &ControlFlowGraph,
+    lp: &Loop,
+    ivs: &InductionVariables,
+    var_no: usize,
+    cmp: Comparison,
+    bound: &Expression,
+) -> TripCount {
+    let basic = &ivs.basic[&var_no];
+
+    // If the variable can wrap around, the loop might never finish
+    if !basic.checked && !basic.step.abs().is_one() {
+        return TripCount::Unknown;
+    }
+
+    let init = initial_value(cfg, lp, var_no);
+    let unsigned = matches!(basic.ty, Type::Uint(_));
+    // The variable starts at zero or more, so it cannot count up further than the bound
+    let non_negative = unsigned
+        || matches!(init, Some(Expression::NumberLiteral { value, .. }) if !value.is_negative());
+
+    // whether the loop is executed for the bound itself
+    let inclusive = match cmp {
+        Comparison::Less | Comparison::NotEqual if basic.step.is_positive() => false,
+        Comparison::LessEqual if basic.step.is_positive() => true,
+        Comparison::More | Comparison::NotEqual if basic.step.is_negative() => {
+            return match (init, bound) {
+                (
+                    Some(Expression::NumberLiteral { value: from, .. }),
+                    Expression::NumberLiteral { value: to, .. },
+                ) => constant(from - to, false, &-&basic.step, cmp),
+                // counting down to zero from a value only known at runtime
+                (Some(from), Expression::NumberLiteral { value: to, .. })
+                    if to.is_zero() && unsigned =>
+                {
+                    TripCount::Bounded(from.clone(), BigInt::zero())
+                }
+                _ => TripCount::Unknown,
+            };
+        }
+        Comparison::MoreEqual if basic.step.is_negative() => {
+            return match (init, bound) {
+                (
+                    Some(Expression::NumberLiteral { value: from, .. }),
+                    Expression::NumberLiteral { value: to, .. },
+                ) => constant(from - to, true, &-&basic.step, cmp),
+                (Some(from), Expression::NumberLiteral { value: to, .. })
+                    if to.is_zero() && unsigned =>
+                {
+                    TripCount::Bounded(from.clone(), BigInt::one())
+                }
+                _ => TripCount::Unknown,
+            };
+        }
+        _ => return TripCount::Unknown,
+    };
+
+    let extra = if inclusive {
+        BigInt::one()
+    } else {
+        BigInt::zero()
+    };
+
+    match (init, bound) {
+        (
+            Some(Expression::NumberLiteral { value: from, .. }),
+            Expression::NumberLiteral { value: to, .. },
+        ) => constant(to - from, inclusive, &basic.step, cmp),
+        (_, Expression::NumberLiteral { value: to, .. })
+            if cmp != Comparison::NotEqual && non_negative =>
+        {
+            TripCount::Constant((to + extra).max(BigInt::zero()))
+        }
+        (_, to) if cmp != Comparison::NotEqual && non_negative => {
+            TripCount::Bounded(to.clone(), extra)
+        }
+        _ => TripCount::Unknown,
+    }
+}
+
+/// The number of steps needed to cover the distance
+fn constant(distance: BigInt, inclusive: bool, step: &BigInt, cmp: Comparison) -> TripCount {
+    if cmp == Comparison::NotEqual {
+        // the variable has to hit the bound exactly
+        if distance.is_negative() || !distance.is_multiple_of(step) {
+            return TripCount::Unknown;
+        }
+    }
+
+    let distance = if inclusive { distance + 1 } else { distance };
+
+    if distance.is_positive() {
+        TripCount::Constant(distance.div_ceil(step))
+    } else {
+        TripCount::Constant(BigInt::zero())
+    }
+}
+
+/// The value of the variable when the loop is entered, if there is exactly one definition outside
+/// the loop which reaches the loop header
+fn initial_value<'a>(
+    cfg: &'a ControlFlowGraph,
+    lp: &Loop,
+    var_no: usize,
+) -> Option<&'a Expression> {
+    let defs = cfg.blocks[lp.header].defs.get(&var_no)?;
+
+    let mut outside = defs.keys().filter(|def| !lp.blocks.contains(&def.block_no));
+
+    match (outside.next(), outside.next()) {
+        (Some(def), None) => match &cfg.blocks[def.block_no].instr[def.instr_no] {
+            Instr::Set { expr, .. } => Some(expr),
+            _ => None,
+        },
+        _ => None,
+    }
+}
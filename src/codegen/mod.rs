@@ -4,18 +4,25 @@ mod array_boundary;
 pub mod cfg;
 mod constant_folding;
 mod constructor;
+pub mod cost;
+mod dead_functions;
 mod dead_storage;
 pub(crate) mod dispatch;
 pub(crate) mod encoding;
-mod events;
+pub mod events;
 mod expression;
+mod inline;
+mod loops;
 pub(super) mod polkadot;
 mod reaching_definitions;
 pub mod revert;
+pub mod size_report;
 mod solana_accounts;
 mod solana_deploy;
 mod statements;
 mod storage;
+mod storage_cache;
+pub mod storage_layout;
 mod strength_reduce;
 pub(crate) mod subexpression_elimination;
 mod tests;
@@ -23,6 +30,7 @@ mod undefined_variable;
 mod unused_variable;
 pub(crate) mod vartable;
 mod vector_to_slice;
+pub mod verify;
 mod yul;
 
 use self::{
@@ -30,13 +38,16 @@ use self::{
     dispatch::function_dispatch,
     expression::expression,
     solana_accounts::account_collection::collect_accounts_from_contract,
+    storage::store_packed,
     vartable::Vartable,
 };
+use crate::abi::polkadot::MetadataVersion;
 use crate::sema::ast::{
-    FormatArg, Function, Layout, Namespace, RetrieveType, StringLocation, Type,
+    FormatArg, Function, Layout, Namespace, PackedLayout, RetrieveType, StringLocation, Type,
 };
 use crate::{sema::ast, Target};
 use std::cmp::Ordering;
+use std::collections::HashMap;
 
 use crate::codegen::cfg::ASTFunction;
 use crate::codegen::solana_accounts::account_management::manage_contract_accounts;
@@ -48,7 +59,7 @@ use crate::sema::Recurse;
 use contract_build::OptimizationPasses;
 use num_bigint::{BigInt, Sign};
 use num_rational::BigRational;
-use num_traits::{FromPrimitive, Zero};
+use num_traits::{FromPrimitive, One, Zero};
 use solang_parser::diagnostics::Diagnostic;
 use solang_parser::{pt, pt::CodeLocation};
 
@@ -101,6 +112,13 @@ pub struct Options {
     pub strength_reduce: bool,
     pub vector_to_slice: bool,
     pub common_subexpression_elimination: bool,
+    pub inline: bool,
+    pub storage_cache: bool,
+    pub loop_optimization: bool,
+    pub dead_function_elimination: bool,
+    pub pack_storage: bool,
+    /// The version of the ink! metadata on Polkadot, which decides how events are emitted
+    pub metadata_version: MetadataVersion,
     pub generate_debug_information: bool,
     pub opt_level: OptimizationLevel,
     pub log_runtime_errors: bool,
@@ -117,6 +135,12 @@ impl Default for Options {
             strength_reduce: true,
             vector_to_slice: true,
             common_subexpression_elimination: true,
+            inline: true,
+            storage_cache: true,
+            loop_optimization: true,
+            dead_function_elimination: true,
+            pack_storage: false,
+            metadata_version: MetadataVersion::default(),
             generate_debug_information: false,
             opt_level: OptimizationLevel::Default,
             log_runtime_errors: false,
@@ -134,6 +158,9 @@ pub fn codegen(ns: &mut Namespace, opt: &Options) {
         return;
     }
 
+    // the metadata and the bindings have to match the events as they are emitted
+    ns.metadata_version = opt.metadata_version;
+
     let mut contracts_done = Vec::new();
 
     contracts_done.resize(ns.contracts.len(), false);
@@ -188,10 +215,11 @@ pub fn codegen(ns: &mut Namespace, opt: &Options) {
 
 fn contract(contract_no: usize, ns: &mut Namespace, opt: &Options) {
     if !ns.diagnostics.any_errors() && ns.contracts[contract_no].instantiable {
-        layout(contract_no, ns);
+        layout(contract_no, ns, opt);
 
         let mut cfg_no = 0;
         let mut all_cfg = Vec::new();
+        let mut inline_bodies = HashMap::new();
 
         // all the functions should have a cfg_no assigned, so we can generate call instructions to the correct function
         for (_, func_cfg) in ns.contracts[contract_no].all_functions.iter_mut() {
@@ -220,6 +248,7 @@ fn contract(contract_no: usize, ns: &mut Namespace, opt: &Options) {
                 Some(function_no),
                 cfg_no,
                 &mut all_cfg,
+                &mut inline_bodies,
                 ns,
                 opt,
             )
@@ -227,7 +256,14 @@ fn contract(contract_no: usize, ns: &mut Namespace, opt: &Options) {
 
         // generate the cfg for yul functions
         for yul_func_no in ns.contracts[contract_no].yul_functions.clone() {
-            generate_yul_function_cfg(contract_no, yul_func_no, &mut all_cfg, ns, opt);
+            generate_yul_function_cfg(
+                contract_no,
+                yul_func_no,
+                &mut all_cfg,
+                &mut inline_bodies,
+                ns,
+                opt,
+            );
         }
 
         // Generate cfg for storage initializers
@@ -242,17 +278,34 @@ fn contract(contract_no: usize, ns: &mut Namespace, opt: &Options) {
             let cfg_no = all_cfg.len();
             all_cfg.push(ControlFlowGraph::placeholder());
 
-            cfg::generate_cfg(contract_no, None, cfg_no, &mut all_cfg, ns, opt);
+            cfg::generate_cfg(
+                contract_no,
+                None,
+                cfg_no,
+                &mut all_cfg,
+                &mut inline_bodies,
+                ns,
+                opt,
+            );
 
             ns.contracts[contract_no].default_constructor = Some((func, cfg_no));
         }
 
+        inline::inline(&mut all_cfg, &inline_bodies, ns, opt);
+
+        let mut dispatchers = Vec::new();
+
         for mut dispatch_cfg in function_dispatch(contract_no, &all_cfg, ns, opt) {
             optimize_and_check_cfg(&mut dispatch_cfg, ns, ASTFunction::None, opt);
+            dispatchers.push(all_cfg.len());
             all_cfg.push(dispatch_cfg);
         }
 
         ns.contracts[contract_no].cfg = all_cfg;
+
+        if opt.dead_function_elimination {
+            dead_functions::dead_function_elimination(contract_no, &dispatchers, ns);
+        }
     }
 }
 
@@ -276,6 +329,11 @@ fn storage_initializer(contract_no: usize, ns: &mut Namespace, opt: &Options) ->
 
             let value = expression(init, &mut cfg, contract_no, None, ns, &mut vartab, opt);
 
+            if layout.packed.is_some() {
+                store_packed(&pt::Loc::Codegen, layout, value, &mut cfg, &mut vartab, ns);
+                continue;
+            }
+
             cfg.add(
                 &mut vartab,
                 Instr::SetStorage {
@@ -297,9 +355,15 @@ fn storage_initializer(contract_no: usize, ns: &mut Namespace, opt: &Options) ->
 }
 
 /// Layout the contract. We determine the layout of variables and deal with overriding variables
-fn layout(contract_no: usize, ns: &mut Namespace) {
+fn layout(contract_no: usize, ns: &mut Namespace, opt: &Options) {
+    let pack_storage = opt.pack_storage && ns.target.is_polkadot();
+    // the slot of the last packed variable, and the number of bytes used in it
+    let mut packing: Option<(BigInt, u32)> = None;
+
     let mut slot = if ns.target == Target::Solana {
         BigInt::from(SOLANA_FIRST_OFFSET)
+    } else if let Some((_, base)) = &ns.contracts[contract_no].layout_base {
+        base.clone()
     } else {
         BigInt::zero()
     };
@@ -320,11 +384,41 @@ fn layout(contract_no: usize, ns: &mut Namespace) {
                     }
                 }
 
+                if let Some(size) = packed_size(&ty).filter(|_| pack_storage) {
+                    let offset = match &mut packing {
+                        Some((_, used)) if *used + size <= 32 => {
+                            *used += size;
+                            *used - size
+                        }
+                        _ => {
+                            packing = Some((slot.clone(), size));
+                            slot += 1;
+                            0
+                        }
+                    };
+
+                    ns.contracts[contract_no].layout.push(Layout {
+                        slot: packing.as_ref().unwrap().0.clone(),
+                        contract_no: base_contract_no,
+                        var_no,
+                        ty,
+                        packed: Some(PackedLayout {
+                            offset,
+                            slot_ty: Type::Uint(256),
+                        }),
+                    });
+
+                    continue;
+                }
+
+                packing = None;
+
                 ns.contracts[contract_no].layout.push(Layout {
                     slot: slot.clone(),
                     contract_no: base_contract_no,
                     var_no,
                     ty: ty.clone(),
+                    packed: None,
                 });
 
                 slot += ty.storage_slots(ns);
@@ -332,6 +426,30 @@ fn layout(contract_no: usize, ns: &mut Namespace) {
         }
     }
 
+    if pack_storage {
+        pack_layout(&mut ns.contracts[contract_no].layout);
+    }
+
+    if let Some((loc, _)) = &ns.contracts[contract_no].layout_base {
+        if slot > BigInt::one() << 256 {
+            ns.diagnostics.push(Diagnostic::error(
+                *loc,
+                "contract storage extends past the end of storage with this layout base"
+                    .to_string(),
+            ));
+        } else if ns.target.is_polkadot()
+            && !ns.contracts[contract_no].layout.is_empty()
+            && slot > BigInt::from(u32::MAX) + 1
+        {
+            // the storage layout in the ink! metadata has 32 bit keys
+            ns.diagnostics.push(Diagnostic::error(
+                *loc,
+                "contract storage does not fit in the 32 bit storage keys of the metadata with this layout base"
+                    .to_string(),
+            ));
+        }
+    }
+
     let constructors = ns.contracts[contract_no].constructors(ns);
     if !constructors.is_empty() {
         if let Some((_, exp)) = &ns.functions[constructors[0]].annotations.space {
@@ -356,6 +474,53 @@ fn layout(contract_no: usize, ns: &mut Namespace) {
     ns.contracts[contract_no].fixed_layout_size = slot;
 }
 
+/// The number of bytes a state variable occupies when packed with other variables into a single
+/// storage slot, or `None` if the type can not be packed
+pub(crate) fn packed_size(ty: &Type) -> Option<u32> {
+    match ty {
+        Type::Bool | Type::Enum(_) => Some(1),
+        Type::Int(bits) | Type::Uint(bits) if *bits < 256 => Some(*bits as u32 / 8),
+        _ => None,
+    }
+}
+
+/// Variables which ended up alone in a storage slot are not packed. For the others, set the type of
+/// the storage slot to an unsigned integer large enough to hold all the variables in it. This is
+/// rounded up to a power of two, so that the storage slot holds exactly the encoded integer.
+fn pack_layout(layout: &mut [Layout]) {
+    let mut start = 0;
+
+    while start < layout.len() {
+        let mut end = start + 1;
+
+        if layout[start].packed.is_some() {
+            while end < layout.len()
+                && layout[end].packed.is_some()
+                && layout[end].slot == layout[start].slot
+            {
+                end += 1;
+            }
+        }
+
+        let variables = &mut layout[start..end];
+
+        start = end;
+
+        if variables.len() == 1 {
+            variables[0].packed = None;
+            continue;
+        }
+
+        let last = variables.last().unwrap();
+        let used = last.packed.as_ref().unwrap().offset + packed_size(&last.ty).unwrap();
+        let slot_ty = Type::Uint(used.next_power_of_two() as u16 * 8);
+
+        for var in variables {
+            var.packed.as_mut().unwrap().slot_ty = slot_ty.clone();
+        }
+    }
+}
+
 trait LLVMName {
     fn llvm_symbol(&self, ns: &Namespace) -> String;
 }
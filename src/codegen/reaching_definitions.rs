@@ -94,6 +94,18 @@ pub fn find(cfg: &mut ControlFlowGraph) {
     }
 }
 
+/// Calculate the reaching definitions again, after an optimization pass has changed the
+/// instructions
+pub fn refresh(cfg: &mut ControlFlowGraph) {
+    for block in &mut cfg.blocks {
+        block.defs.clear();
+        block.transfers.clear();
+        block.loop_reaching_variables.clear();
+    }
+
+    find(cfg);
+}
+
 /// Instruction defs
 fn instr_transfers(block_no: usize, block: &BasicBlock) -> Vec<Vec<Transfer>> {
     let mut transfers = Vec::new();
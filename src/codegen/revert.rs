@@ -90,7 +90,7 @@ impl SolidityError {
             Self::String(expr) => {
                 let args = vec![self.selector_expression(ns), expr.clone()];
                 create_encoder(ns, false)
-                    .const_encode(&args)
+                    .const_encode(&args, ns)
                     .map(|bytes| {
                         let size = Expression::NumberLiteral {
                             loc: Codegen,
@@ -110,7 +110,7 @@ impl SolidityError {
                 let mut args = exprs.to_owned();
                 args.insert(0, self.selector_expression(ns));
                 create_encoder(ns, false)
-                    .const_encode(&args)
+                    .const_encode(&args, ns)
                     .map(|bytes| {
                         let size = Expression::NumberLiteral {
                             loc: Codegen,
@@ -133,7 +133,7 @@ impl SolidityError {
                     value: (*code as u8).into(),
                 };
                 create_encoder(ns, false)
-                    .const_encode(&[self.selector_expression(ns), code])
+                    .const_encode(&[self.selector_expression(ns), code], ns)
                     .map(|bytes| {
                         let size = Expression::NumberLiteral {
                             loc: Codegen,
@@ -0,0 +1,470 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Attribute the size of the linked binary of a contract to the Solidity functions, the
+//! dispatcher, the standard library and the data sections. The functions are found by their
+//! symbol in the linked binary: the ELF symbol table on Solana, and the name section on
+//! Polkadot. The symbols are matched with the names of the functions in the LLVM module, which
+//! are the names of the control flow graphs of the contract. Anything which is not a function or
+//! data, like headers, imports and symbol tables, is reported as metadata.
+
+mod tests;
+
+use super::cfg::ASTFunction;
+use super::dispatch::polkadot::DispatchType;
+use super::dispatch::solana::SOLANA_DISPATCH_CFG_NAME;
+use crate::sema::ast::Namespace;
+use crate::Target;
+use serde::Serialize;
+use solang_parser::pt::FunctionTy;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use wasmparser::{Name, NameSectionReader, Parser, Payload, TypeRef};
+
+/// The size of the linked binary of a contract
+#[derive(Debug, Serialize)]
+pub struct SizeReport {
+    pub contract: String,
+    pub target: String,
+    /// The size of the binary in bytes
+    pub total: usize,
+    /// Ordered by kind, and then by size with the largest first
+    pub entries: Vec<SizeEntry>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct SizeEntry {
+    pub kind: SizeKind,
+    pub name: String,
+    pub size: usize,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum SizeKind {
+    /// A Solidity or Yul function, or a modifier
+    Function,
+    /// The entry point of the contract and the code which finds the function to call
+    Dispatcher,
+    /// A function from `stdlib/*.c`
+    Stdlib,
+    /// A function generated by the compiler or llvm, like `vector_new` or `__multi3`
+    Runtime,
+    /// Constants and initialized memory
+    Data,
+    /// Headers, imports, exports and symbol tables
+    Metadata,
+}
+
+impl fmt::Display for SizeKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SizeKind::Function => write!(f, "function"),
+            SizeKind::Dispatcher => write!(f, "dispatcher"),
+            SizeKind::Stdlib => write!(f, "stdlib"),
+            SizeKind::Runtime => write!(f, "runtime"),
+            SizeKind::Data => write!(f, "data"),
+            SizeKind::Metadata => write!(f, "metadata"),
+        }
+    }
+}
+
+impl fmt::Display for SizeReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "contract {} ({}, {} bytes)",
+            self.contract, self.target, self.total
+        )?;
+
+        let width = self
+            .entries
+            .iter()
+            .map(|entry| entry.name.len())
+            .chain(std::iter::once("name".len()))
+            .max()
+            .unwrap_or_default();
+
+        writeln!(
+            f,
+            "  {:10}  {:width$}  {:>8}  {:>6}",
+            "kind", "name", "size", "%"
+        )?;
+
+        for entry in &self.entries {
+            writeln!(
+                f,
+                "  {:10}  {:width$}  {:>8}  {:>6.2}",
+                entry.kind.to_string(),
+                entry.name,
+                entry.size,
+                entry.size as f64 * 100.0 / self.total.max(1) as f64
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Attribute the size of the linked binary `code` of the contract. The names of the functions
+/// of the standard library are given by `stdlib`, since these are only known to the llvm
+/// module which is linked in.
+pub fn size_report(
+    contract_no: usize,
+    ns: &Namespace,
+    code: &[u8],
+    stdlib: &HashSet<String>,
+) -> Result<SizeReport, String> {
+    let parts = match ns.target {
+        Target::Solana => elf_parts(code)?,
+        Target::Polkadot { .. } => wasm_parts(code)?,
+        _ => {
+            return Err(format!(
+                "size report is not supported for target {}",
+                ns.target
+            ))
+        }
+    };
+
+    let functions = function_names(contract_no, ns);
+    let mut sizes: HashMap<(SizeKind, String), usize> = HashMap::new();
+
+    for part in &parts {
+        let key = match part.kind {
+            PartKind::Function => classify(&part.name, &functions, stdlib),
+            PartKind::Data => (SizeKind::Data, part.name.clone()),
+            PartKind::Metadata => (SizeKind::Metadata, part.name.clone()),
+        };
+
+        *sizes.entry(key).or_default() += part.size;
+    }
+
+    // whatever is not in a function, data or a section is headers and padding
+    let attributed: usize = parts.iter().map(|part| part.size).sum();
+
+    if attributed < code.len() {
+        *sizes
+            .entry((SizeKind::Metadata, "headers".to_owned()))
+            .or_default() += code.len() - attributed;
+    }
+
+    let mut entries: Vec<SizeEntry> = sizes
+        .into_iter()
+        .filter(|(_, size)| *size > 0)
+        .map(|((kind, name), size)| SizeEntry { kind, name, size })
+        .collect();
+
+    entries.sort_by(|a, b| {
+        a.kind
+            .cmp(&b.kind)
+            .then(b.size.cmp(&a.size))
+            .then(a.name.cmp(&b.name))
+    });
+
+    Ok(SizeReport {
+        contract: ns.contracts[contract_no].id.name.clone(),
+        target: ns.target.to_string(),
+        total: code.len(),
+        entries,
+    })
+}
+
+/// A part of the binary, found by its symbol or section
+#[derive(Debug, PartialEq, Eq)]
+struct Part {
+    kind: PartKind,
+    name: String,
+    size: usize,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum PartKind {
+    Function,
+    Data,
+    Metadata,
+}
+
+impl Part {
+    fn new(kind: PartKind, name: impl Into<String>, size: usize) -> Self {
+        Part {
+            kind,
+            name: name.into(),
+            size,
+        }
+    }
+}
+
+/// The functions of the contract by the name of their control flow graph, which is the name of
+/// the function in the llvm module
+fn function_names(contract_no: usize, ns: &Namespace) -> HashMap<&str, (SizeKind, String)> {
+    let dispatchers = [
+        SOLANA_DISPATCH_CFG_NAME.to_owned(),
+        DispatchType::Deploy.to_string(),
+        DispatchType::Call.to_string(),
+    ];
+
+    ns.contracts[contract_no]
+        .cfg
+        .iter()
+        .filter(|cfg| !cfg.is_placeholder())
+        .map(|cfg| {
+            let entry = match cfg.function_no {
+                ASTFunction::SolidityFunction(function_no) => {
+                    let func = &ns.functions[function_no];
+
+                    let name = match func.ty {
+                        FunctionTy::Fallback | FunctionTy::Receive => func.ty.to_string(),
+                        FunctionTy::Constructor if func.id.name.is_empty() => {
+                            format!("constructor{}", func.signature)
+                        }
+                        _ => func.signature.clone(),
+                    };
+
+                    (SizeKind::Function, name)
+                }
+                ASTFunction::None if dispatchers.contains(&cfg.name) => {
+                    (SizeKind::Dispatcher, cfg.name.clone())
+                }
+                ASTFunction::YulFunction(_) | ASTFunction::None => {
+                    (SizeKind::Function, cfg.name.clone())
+                }
+            };
+
+            (cfg.name.as_str(), entry)
+        })
+        .collect()
+}
+
+/// Decide what a function in the binary is by its symbol name
+fn classify(
+    symbol: &str,
+    functions: &HashMap<&str, (SizeKind, String)>,
+    stdlib: &HashSet<String>,
+) -> (SizeKind, String) {
+    // llvm renames a function foo to foo.1 if there is already a foo
+    let name = match symbol.rsplit_once('.') {
+        Some((name, no)) if !no.is_empty() && no.bytes().all(|b| b.is_ascii_digit()) => name,
+        _ => symbol,
+    };
+
+    if let Some(entry) = functions.get(name) {
+        entry.clone()
+    } else if matches!(name, "deploy" | "call" | "entrypoint") {
+        (SizeKind::Dispatcher, name.to_owned())
+    } else if stdlib.contains(name) {
+        (SizeKind::Stdlib, name.to_owned())
+    } else {
+        (SizeKind::Runtime, name.to_owned())
+    }
+}
+
+/// The parts of a Wasm module. The functions are named by the name section, if the module has
+/// one; wasm-opt removes it unless debug information is generated.
+fn wasm_parts(code: &[u8]) -> Result<Vec<Part>, String> {
+    let mut parts = Vec::new();
+    let mut imported_functions = 0;
+    let mut bodies = Vec::new();
+    let mut names = HashMap::new();
+
+    for payload in Parser::new(0).parse_all(code) {
+        let payload = payload.map_err(|err| err.to_string())?;
+
+        match &payload {
+            Payload::ImportSection(reader) => {
+                for import in reader.clone() {
+                    if let TypeRef::Func(_) = import.map_err(|err| err.to_string())?.ty {
+                        imported_functions += 1;
+                    }
+                }
+            }
+            Payload::CodeSectionEntry(body) => {
+                bodies.push(body.range().len());
+                continue;
+            }
+            Payload::DataSection(reader) => {
+                for (no, data) in reader.clone().into_iter().enumerate() {
+                    let data = data.map_err(|err| err.to_string())?;
+
+                    parts.push(Part::new(
+                        PartKind::Data,
+                        format!("data segment {no}"),
+                        data.data.len(),
+                    ));
+                }
+                continue;
+            }
+            Payload::CustomSection(reader) if reader.name() == "name" => {
+                for name in NameSectionReader::new(reader.data(), reader.data_offset()) {
+                    if let Name::Function(map) = name.map_err(|err| err.to_string())? {
+                        for naming in map {
+                            let naming = naming.map_err(|err| err.to_string())?;
+
+                            names.insert(naming.index, naming.name.to_owned());
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+
+        if let Some((id, range)) = payload.as_section() {
+            let name = match &payload {
+                Payload::CustomSection(reader) => format!("custom section {}", reader.name()),
+                _ => format!("{} section", wasm_section_name(id)),
+            };
+
+            // the code section is made up of the function bodies, which are added below
+            if id != 10 {
+                parts.push(Part::new(PartKind::Metadata, name, range.len()));
+            }
+        }
+    }
+
+    for (no, size) in bodies.into_iter().enumerate() {
+        let index = imported_functions + no as u32;
+        let name = names
+            .remove(&index)
+            .unwrap_or_else(|| format!("function[{index}]"));
+
+        parts.push(Part::new(PartKind::Function, name, size));
+    }
+
+    Ok(parts)
+}
+
+fn wasm_section_name(id: u8) -> &'static str {
+    match id {
+        1 => "type",
+        2 => "import",
+        3 => "function",
+        4 => "table",
+        5 => "memory",
+        6 => "global",
+        7 => "export",
+        8 => "start",
+        9 => "element",
+        10 => "code",
+        11 => "data",
+        12 => "data count",
+        _ => "unknown",
+    }
+}
+
+const SHT_SYMTAB: u32 = 2;
+const SHT_NOBITS: u32 = 8;
+const STT_FUNC: u8 = 2;
+
+/// The parts of a 64 bit little endian ELF shared object. The functions are found in the symbol
+/// table, and the code in the text section which is not in any function symbol is reported as
+/// runtime code.
+fn elf_parts(code: &[u8]) -> Result<Vec<Part>, String> {
+    let read = |offset: usize, len: usize| -> Result<u64, String> {
+        let bytes = code
+            .get(offset..offset + len)
+            .ok_or_else(|| "ELF file is truncated".to_owned())?;
+
+        Ok(bytes
+            .iter()
+            .rev()
+            .fold(0, |value, byte| (value << 8) | u64::from(*byte)))
+    };
+
+    if code.get(..6) != Some(b"\x7fELF\x02\x01") {
+        return Err("not a 64 bit little endian ELF file".to_owned());
+    }
+
+    let section_offset = read(0x28, 8)? as usize;
+    let section_entry_size = read(0x3a, 2)? as usize;
+    let section_count = read(0x3c, 2)? as usize;
+    let section_names = read(0x3e, 2)? as usize;
+
+    struct Section {
+        name: u32,
+        ty: u32,
+        offset: usize,
+        size: usize,
+        link: usize,
+    }
+
+    let sections = (0..section_count)
+        .map(|no| {
+            let header = section_offset + no * section_entry_size;
+
+            Ok(Section {
+                name: read(header, 4)? as u32,
+                ty: read(header + 4, 4)? as u32,
+                offset: read(header + 24, 8)? as usize,
+                size: read(header + 32, 8)? as usize,
+                link: read(header + 40, 4)? as usize,
+            })
+        })
+        .collect::<Result<Vec<Section>, String>>()?;
+
+    let string = |table: usize, offset: usize| -> Result<String, String> {
+        let start = sections
+            .get(table)
+            .ok_or_else(|| "ELF string table not found".to_owned())?
+            .offset
+            + offset;
+
+        let bytes = code
+            .get(start..)
+            .and_then(|bytes| bytes.split(|b| *b == 0).next())
+            .ok_or_else(|| "ELF string is truncated".to_owned())?;
+
+        Ok(String::from_utf8_lossy(bytes).into_owned())
+    };
+
+    let mut parts = vec![Part::new(
+        PartKind::Metadata,
+        "section headers",
+        section_count * section_entry_size,
+    )];
+    let mut text = None;
+
+    for (no, section) in sections.iter().enumerate() {
+        if no == 0 || section.ty == SHT_NOBITS {
+            continue;
+        }
+
+        let name = string(section_names, section.name as usize)?;
+
+        if name.starts_with(".text") {
+            text = Some((no, section.size));
+        } else if name.starts_with(".rodata") || name.starts_with(".data") {
+            parts.push(Part::new(PartKind::Data, name, section.size));
+        } else {
+            parts.push(Part::new(PartKind::Metadata, name, section.size));
+        }
+    }
+
+    let Some((text_no, text_size)) = text else {
+        return Ok(parts);
+    };
+
+    let mut functions_size = 0;
+
+    for section in sections.iter().filter(|section| section.ty == SHT_SYMTAB) {
+        for symbol in (section.offset..section.offset + section.size).step_by(24) {
+            let info = read(symbol + 4, 1)? as u8;
+            let section_no = read(symbol + 6, 2)? as usize;
+            let size = read(symbol + 16, 8)? as usize;
+
+            if info & 0xf == STT_FUNC && section_no == text_no && size > 0 {
+                let name = string(section.link, read(symbol, 4)? as usize)?;
+
+                functions_size += size;
+                parts.push(Part::new(PartKind::Function, name, size));
+            }
+        }
+    }
+
+    if text_size > functions_size {
+        parts.push(Part::new(
+            PartKind::Function,
+            ".text",
+            text_size - functions_size,
+        ));
+    }
+
+    Ok(parts)
+}
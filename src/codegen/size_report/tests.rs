@@ -0,0 +1,294 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(test)]
+
+use super::{size_report, SizeEntry, SizeKind};
+use crate::codegen::cfg::ASTFunction;
+use crate::codegen::Options;
+use crate::sema::ast::Namespace;
+use crate::sema::tests::resolve_and_codegen;
+use crate::Target;
+use std::collections::HashSet;
+use wasm_encoder::{
+    CodeSection, ConstExpr, DataSection, EntityType, Function, FunctionSection, ImportSection,
+    Instruction, Module, NameMap, NameSection, TypeSection,
+};
+
+/// The name of the control flow graph of the function, which is its symbol in the binary
+fn cfg_name(ns: &Namespace, name: &str) -> String {
+    ns.contracts[0]
+        .cfg
+        .iter()
+        .find(|cfg| match cfg.function_no {
+            ASTFunction::SolidityFunction(function_no) => ns.functions[function_no].id.name == name,
+            _ => false,
+        })
+        .unwrap()
+        .name
+        .clone()
+}
+
+/// A function body of the given size in bytes
+fn body(size: usize) -> Function {
+    let mut function = Function::new(vec![]);
+
+    // the body has the locals count and end instruction
+    for _ in 0..size - 2 {
+        function.instruction(&Instruction::Nop);
+    }
+
+    function.instruction(&Instruction::End);
+
+    function
+}
+
+const SRC: &str = r#"
+contract Counter {
+    uint64 count;
+
+    function inc(uint64 by) public {
+        count += by;
+    }
+
+    function get() public view returns (uint64) {
+        return count;
+    }
+}"#;
+
+#[test]
+fn polkadot() {
+    let ns = resolve_and_codegen(SRC, Target::default_polkadot(), &Options::default());
+
+    let functions = [
+        (cfg_name(&ns, "inc"), 100),
+        (format!("{}.1", cfg_name(&ns, "get")), 40),
+        ("polkadot_call_dispatch".to_owned(), 60),
+        ("call".to_owned(), 20),
+        ("__memcpy".to_owned(), 30),
+        ("vector_new".to_owned(), 10),
+    ];
+
+    let mut module = Module::new();
+
+    let mut types = TypeSection::new();
+    types.function(vec![], vec![]);
+    module.section(&types);
+
+    let mut imports = ImportSection::new();
+    imports.import("seal0", "seal_return", EntityType::Function(0));
+    module.section(&imports);
+
+    let mut function_section = FunctionSection::new();
+    for _ in &functions {
+        function_section.function(0);
+    }
+    module.section(&function_section);
+
+    let mut code = CodeSection::new();
+    for (_, size) in &functions {
+        code.function(&body(*size));
+    }
+    module.section(&code);
+
+    let mut data = DataSection::new();
+    data.active(0, &ConstExpr::i32_const(0), b"hello, world".iter().copied());
+    module.section(&data);
+
+    let mut names = NameMap::new();
+    names.append(0, "seal_return");
+    for (no, (name, _)) in functions.iter().enumerate() {
+        names.append(no as u32 + 1, name);
+    }
+    let mut name_section = NameSection::new();
+    name_section.functions(&names);
+    module.section(&name_section);
+
+    let wasm = module.finish();
+
+    let stdlib = HashSet::from(["__memcpy".to_owned()]);
+    let report = size_report(0, &ns, &wasm, &stdlib).unwrap();
+
+    assert_eq!(report.contract, "Counter");
+    assert_eq!(report.total, wasm.len());
+    assert_eq!(
+        report.entries.iter().map(|entry| entry.size).sum::<usize>(),
+        wasm.len()
+    );
+
+    let code_entries: Vec<&SizeEntry> = report
+        .entries
+        .iter()
+        .filter(|entry| entry.kind < SizeKind::Data)
+        .collect();
+
+    assert_eq!(
+        code_entries,
+        vec![
+            &SizeEntry {
+                kind: SizeKind::Function,
+                name: "inc(uint64)".to_owned(),
+                size: 100
+            },
+            &SizeEntry {
+                kind: SizeKind::Function,
+                name: "get()".to_owned(),
+                size: 40
+            },
+            &SizeEntry {
+                kind: SizeKind::Dispatcher,
+                name: "polkadot_call_dispatch".to_owned(),
+                size: 60
+            },
+            &SizeEntry {
+                kind: SizeKind::Dispatcher,
+                name: "call".to_owned(),
+                size: 20
+            },
+            &SizeEntry {
+                kind: SizeKind::Stdlib,
+                name: "__memcpy".to_owned(),
+                size: 30
+            },
+            &SizeEntry {
+                kind: SizeKind::Runtime,
+                name: "vector_new".to_owned(),
+                size: 10
+            },
+        ]
+    );
+
+    assert!(report.entries.contains(&SizeEntry {
+        kind: SizeKind::Data,
+        name: "data segment 0".to_owned(),
+        size: 12
+    }));
+    assert!(report
+        .entries
+        .iter()
+        .any(|entry| entry.kind == SizeKind::Metadata && entry.name == "custom section name"));
+
+    let table = report.to_string();
+
+    assert!(table.starts_with(&format!(
+        "contract Counter (Polkadot, {} bytes)\n",
+        wasm.len()
+    )));
+    assert!(table.contains("  function    inc(uint64)"));
+}
+
+/// Build a 64 bit ELF file with a text, rodata and symbol table section
+fn elf(functions: &[(&str, usize)], text_size: usize, rodata_size: usize) -> Vec<u8> {
+    let mut strtab = vec![0u8];
+    let mut symtab = vec![0u8; 24];
+    let mut offset = 0;
+
+    for (name, size) in functions {
+        let mut symbol = Vec::new();
+        symbol.extend((strtab.len() as u32).to_le_bytes());
+        // local function
+        symbol.push(2);
+        symbol.push(0);
+        // the text section
+        symbol.extend(1u16.to_le_bytes());
+        symbol.extend((offset as u64).to_le_bytes());
+        symbol.extend((*size as u64).to_le_bytes());
+        symtab.extend(symbol);
+
+        strtab.extend(name.as_bytes());
+        strtab.push(0);
+        offset += size;
+    }
+
+    let shstrtab = b"\0.text\0.rodata\0.symtab\0.strtab\0.shstrtab\0".to_vec();
+
+    let mut file = vec![0u8; 64];
+    file[..6].copy_from_slice(b"\x7fELF\x02\x01");
+
+    // (name, type, contents, link)
+    let sections = [
+        (1u32, 1u32, vec![0u8; text_size], 0u32),
+        (7, 1, vec![0u8; rodata_size], 0),
+        (15, 2, symtab, 4),
+        (23, 3, strtab, 0),
+        (31, 3, shstrtab, 0),
+    ];
+
+    let mut headers = vec![0u8; 64];
+
+    for (name, ty, contents, link) in sections {
+        let mut header = vec![0u8; 64];
+        header[..4].copy_from_slice(&name.to_le_bytes());
+        header[4..8].copy_from_slice(&ty.to_le_bytes());
+        header[24..32].copy_from_slice(&(file.len() as u64).to_le_bytes());
+        header[32..40].copy_from_slice(&(contents.len() as u64).to_le_bytes());
+        header[40..44].copy_from_slice(&link.to_le_bytes());
+        headers.extend(header);
+        file.extend(contents);
+    }
+
+    let section_offset = file.len() as u64;
+    file[0x28..0x30].copy_from_slice(&section_offset.to_le_bytes());
+    file[0x3a..0x3c].copy_from_slice(&64u16.to_le_bytes());
+    file[0x3c..0x3e].copy_from_slice(&6u16.to_le_bytes());
+    file[0x3e..0x40].copy_from_slice(&5u16.to_le_bytes());
+    file.extend(headers);
+
+    file
+}
+
+#[test]
+fn solana() {
+    let ns = resolve_and_codegen(SRC, Target::Solana, &Options::default());
+
+    let inc = cfg_name(&ns, "inc");
+    let so = elf(
+        &[
+            (&inc, 200),
+            ("solang_dispatch", 80),
+            ("entrypoint", 16),
+            ("sol_alloc_free_", 48),
+        ],
+        400,
+        32,
+    );
+
+    let stdlib = HashSet::from(["sol_alloc_free_".to_owned()]);
+    let report = size_report(0, &ns, &so, &stdlib).unwrap();
+
+    assert_eq!(report.total, so.len());
+    assert_eq!(
+        report.entries.iter().map(|entry| entry.size).sum::<usize>(),
+        so.len()
+    );
+
+    let entries: Vec<(SizeKind, &str, usize)> = report
+        .entries
+        .iter()
+        .map(|entry| (entry.kind, entry.name.as_str(), entry.size))
+        .collect();
+
+    assert_eq!(
+        entries[..7],
+        [
+            (SizeKind::Function, "inc(uint64)", 200),
+            (SizeKind::Dispatcher, "solang_dispatch", 80),
+            (SizeKind::Dispatcher, "entrypoint", 16),
+            (SizeKind::Stdlib, "sol_alloc_free_", 48),
+            (SizeKind::Runtime, ".text", 56),
+            (SizeKind::Data, ".rodata", 32),
+            (SizeKind::Metadata, "section headers", 384),
+        ]
+    );
+
+    assert!(size_report(0, &ns, b"\0asm", &stdlib).is_err());
+}
+
+#[test]
+fn unsupported_target() {
+    let ns = resolve_and_codegen(SRC, Target::EVM, &Options::default());
+
+    assert_eq!(
+        size_report(0, &ns, &[], &HashSet::new()).unwrap_err(),
+        "size report is not supported for target EVM"
+    );
+}
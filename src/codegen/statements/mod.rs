@@ -5,6 +5,7 @@ use super::{
     events::new_event_emitter,
     expression::{assign_single, emit_function_call, expression},
     revert::revert,
+    storage::{packed_variable, store_packed},
     unused_variable::{
         should_remove_assignment, should_remove_variable, SideEffectsCheckParameters,
     },
@@ -213,7 +214,25 @@ pub(crate) fn statement(
 
             let _ = expression(expr, cfg, contract_no, Some(func), ns, vartab, opt);
         }
-        Statement::Delete(_, ty, expr) => {
+        Statement::Delete(loc, ty, expr) => {
+            if let Some(layout) = packed_variable(expr, contract_no, ns) {
+                let value = if *ty == Type::Bool {
+                    Expression::BoolLiteral {
+                        loc: *loc,
+                        value: false,
+                    }
+                } else {
+                    Expression::NumberLiteral {
+                        loc: *loc,
+                        ty: ty.clone(),
+                        value: BigInt::zero(),
+                    }
+                };
+
+                store_packed(loc, layout, value, cfg, vartab, ns);
+                return;
+            }
+
             let var_expr = expression(expr, cfg, contract_no, Some(func), ns, vartab, opt);
 
             cfg.add(
@@ -467,3 +467,167 @@ pub fn array_pop(
         Expression::Undefined { ty }
     }
 }
+
+/// If the expression is a state variable which is packed with other variables into a single
+/// storage slot, return its layout
+pub fn packed_variable<'a>(
+    var: &ast::Expression,
+    contract_no: usize,
+    ns: &'a Namespace,
+) -> Option<&'a ast::Layout> {
+    if let ast::Expression::StorageVariable {
+        contract_no: var_contract_no,
+        var_no,
+        ..
+    } = var
+    {
+        ns.contracts[contract_no].get_packed_layout(*var_contract_no, *var_no)
+    } else {
+        None
+    }
+}
+
+/// Load a packed state variable; the entire storage slot is loaded and the variable is extracted
+/// from it
+pub fn load_packed(
+    loc: &pt::Loc,
+    layout: &ast::Layout,
+    cfg: &mut ControlFlowGraph,
+    vartab: &mut Vartable,
+    ns: &Namespace,
+) -> Expression {
+    let packed = layout.packed.as_ref().unwrap();
+
+    let storage = Expression::NumberLiteral {
+        loc: *loc,
+        ty: ns.storage_type(),
+        value: layout.slot.clone(),
+    };
+
+    let value = load_storage(loc, &packed.slot_ty, storage, cfg, vartab);
+
+    let value = if packed.offset > 0 {
+        Expression::ShiftRight {
+            loc: *loc,
+            ty: packed.slot_ty.clone(),
+            left: Box::new(value),
+            right: Box::new(Expression::NumberLiteral {
+                loc: *loc,
+                ty: packed.slot_ty.clone(),
+                value: BigInt::from(packed.offset * 8),
+            }),
+            signed: false,
+        }
+    } else {
+        value
+    };
+
+    if layout.ty == Type::Bool {
+        Expression::NotEqual {
+            loc: *loc,
+            left: Box::new(Expression::Trunc {
+                loc: *loc,
+                ty: Type::Uint(8),
+                expr: Box::new(value),
+            }),
+            right: Box::new(Expression::NumberLiteral {
+                loc: *loc,
+                ty: Type::Uint(8),
+                value: BigInt::zero(),
+            }),
+        }
+    } else {
+        Expression::Trunc {
+            loc: *loc,
+            ty: layout.ty.clone(),
+            expr: Box::new(value),
+        }
+    }
+}
+
+/// Store a packed state variable. The storage slot is loaded, so that the other variables in it
+/// are preserved, and the variable is replaced
+pub fn store_packed(
+    loc: &pt::Loc,
+    layout: &ast::Layout,
+    value: Expression,
+    cfg: &mut ControlFlowGraph,
+    vartab: &mut Vartable,
+    ns: &Namespace,
+) {
+    let packed = layout.packed.as_ref().unwrap();
+    let slot_ty = &packed.slot_ty;
+
+    let storage = Expression::NumberLiteral {
+        loc: *loc,
+        ty: ns.storage_type(),
+        value: layout.slot.clone(),
+    };
+
+    let bits = if layout.ty == Type::Bool {
+        8
+    } else {
+        layout.ty.bits(ns)
+    };
+
+    let mask: BigInt = (BigInt::one() << bits) - 1;
+    let slot_mask: BigInt = (BigInt::one() << slot_ty.bits(ns)) - 1;
+
+    // signed values must not set any of the bits of the other variables
+    let value = Expression::BitwiseAnd {
+        loc: *loc,
+        ty: slot_ty.clone(),
+        left: Box::new(Expression::ZeroExt {
+            loc: *loc,
+            ty: slot_ty.clone(),
+            expr: Box::new(value),
+        }),
+        right: Box::new(Expression::NumberLiteral {
+            loc: *loc,
+            ty: slot_ty.clone(),
+            value: mask.clone(),
+        }),
+    };
+
+    let value = if packed.offset > 0 {
+        Expression::ShiftLeft {
+            loc: *loc,
+            ty: slot_ty.clone(),
+            left: Box::new(value),
+            right: Box::new(Expression::NumberLiteral {
+                loc: *loc,
+                ty: slot_ty.clone(),
+                value: BigInt::from(packed.offset * 8),
+            }),
+        }
+    } else {
+        value
+    };
+
+    let existing = load_storage(loc, slot_ty, storage.clone(), cfg, vartab);
+
+    let value = Expression::BitwiseOr {
+        loc: *loc,
+        ty: slot_ty.clone(),
+        left: Box::new(Expression::BitwiseAnd {
+            loc: *loc,
+            ty: slot_ty.clone(),
+            left: Box::new(existing),
+            right: Box::new(Expression::NumberLiteral {
+                loc: *loc,
+                ty: slot_ty.clone(),
+                value: slot_mask ^ (mask << (packed.offset * 8)),
+            }),
+        }),
+        right: Box::new(value),
+    };
+
+    cfg.add(
+        vartab,
+        Instr::SetStorage {
+            ty: slot_ty.clone(),
+            value,
+            storage,
+        },
+    );
+}
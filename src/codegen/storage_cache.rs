@@ -0,0 +1,707 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::cfg::{ControlFlowGraph, Instr};
+use super::reaching_definitions::{self, Transfer};
+use super::vartable::{Storage, Variable};
+use crate::codegen::{Builtin, Expression};
+use crate::sema::ast::{Namespace, Type};
+use crate::Target;
+use solang_parser::pt::{Identifier, Loc};
+use std::collections::{BTreeSet, HashSet};
+
+/// A storage slot which is cached in a local variable
+struct Slot {
+    storage: Expression,
+    ty: Type,
+    /// The variable which holds the value of the slot
+    var_no: usize,
+    /// The variables used in the slot expression
+    vars: HashSet<usize>,
+}
+
+/// The state of the cache at some point in the cfg. All the values are indices into the slots.
+#[derive(Clone, Default, PartialEq, Eq)]
+struct State {
+    /// The variable of the slot holds the current value of the slot
+    cached: BTreeSet<usize>,
+    /// The variable of the slot holds a value which still needs to be written to storage
+    dirty: BTreeSet<usize>,
+}
+
+impl State {
+    /// Merge the state of an incoming edge. A slot is only cached if it cached on all incoming edges,
+    /// and it is dirty if it is dirty on any of them
+    fn join(&self, other: &State) -> State {
+        State {
+            cached: self.cached.intersection(&other.cached).cloned().collect(),
+            dirty: self.dirty.union(&other.dirty).cloned().collect(),
+        }
+    }
+}
+
+/// What should be done at the end of a block, just before branching to the next block
+#[derive(Default)]
+struct BlockExit {
+    /// slots which should be written to storage
+    flush: BTreeSet<usize>,
+    /// slots which should be loaded from storage
+    load: BTreeSet<usize>,
+}
+
+/// Keep values from contract storage in local variables for as long as possible. Every storage
+/// access is a call into the runtime, which is expensive.
+///
+/// - A load from a slot which is already cached is replaced with the variable holding its value
+/// - A store to a slot only sets the variable, and marks the slot as dirty
+/// - Dirty slots are written to storage before returning, and before any instruction which can
+///   observe contract storage, like a call to another function or contract
+/// - After any instruction which can modify contract storage, nothing is cached any more
+/// - When reverting, dirty slots are not written, as any changes to storage are discarded anyway
+///
+/// Two different slot expressions may refer to the same slot, e.g. `balances[from]` and
+/// `balances[to]`. When a slot is accessed, any other dirty slot which may be the same is written
+/// first, and storing to a slot removes those slots from the cache. When a variable used in the slot
+/// expression is modified, the slot is written and no longer cached.
+///
+/// On Solana, contract storage is account data which is accessed directly, so this is not needed.
+pub fn storage_cache(cfg: &mut ControlFlowGraph, ns: &mut Namespace) {
+    if ns.target == Target::Solana {
+        return;
+    }
+
+    reaching_definitions::refresh(cfg);
+
+    let preds = cfg.predecessors();
+    let back_edges = cfg.back_edges();
+
+    let slots = find_slots(cfg, ns);
+
+    if slots.is_empty() {
+        return;
+    }
+
+    let mut exits: Vec<BlockExit> = cfg.blocks.iter().map(|_| BlockExit::default()).collect();
+
+    // If a slot is cached at the end of a loop body, load it before the loop so that it can remain
+    // cached. Writing slots does not change what is cached, so do this first. This only ever adds
+    // more loads, so this terminates.
+    loop {
+        let entries = analyse(cfg, &slots, &exits);
+        let exit_states = exit_states(cfg, &slots, &exits, &entries);
+        let mut changed = false;
+
+        for (from, header) in &back_edges {
+            let (Some(exit), Some(entry)) = (&exit_states[*from], &entries[*header]) else {
+                continue;
+            };
+
+            for slot_no in exit.cached.difference(&entry.cached) {
+                if !slots[*slot_no].vars.is_empty() {
+                    continue;
+                }
+
+                for pred in &preds[*header] {
+                    if let Some(pred_exit) = &exit_states[*pred] {
+                        if !pred_exit.cached.contains(slot_no) {
+                            changed |= exits[*pred].load.insert(*slot_no);
+                        }
+                    }
+                }
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    // A dirty slot which is not cached at the start of a successor should be written at the end
+    // of the block
+    let entries = loop {
+        let entries = analyse(cfg, &slots, &exits);
+        let exit_states = exit_states(cfg, &slots, &exits, &entries);
+        let mut changed = false;
+
+        for (block_no, exit) in exit_states.iter().enumerate() {
+            let Some(exit) = exit else {
+                continue;
+            };
+
+            for succ in cfg.blocks[block_no].successors() {
+                let entry = entries[succ].as_ref().unwrap();
+
+                for slot_no in exit.dirty.difference(&entry.cached) {
+                    changed |= exits[block_no].flush.insert(*slot_no);
+                }
+            }
+        }
+
+        if !changed {
+            break entries;
+        }
+    };
+
+    for (block_no, entry) in entries.iter().enumerate() {
+        let Some(entry) = entry else {
+            continue;
+        };
+
+        let mut state = entry.clone();
+
+        cfg.blocks[block_no].instr = block(cfg, &slots, block_no, &exits[block_no], &mut state);
+
+        // the cached slots may have been set in any of the predecessors
+        if preds[block_no].len() > 1 && !entry.cached.is_empty() {
+            let phis = cfg.blocks[block_no].phis.get_or_insert_with(BTreeSet::new);
+
+            for slot_no in &entry.cached {
+                phis.insert(slots[*slot_no].var_no);
+            }
+        }
+    }
+
+    for slot in slots {
+        cfg.vars.insert(
+            slot.var_no,
+            Variable {
+                id: Identifier {
+                    loc: Loc::Codegen,
+                    name: format!("storage.temp.{}", slot.var_no),
+                },
+                ty: slot.ty,
+                storage: Storage::Local,
+            },
+        );
+    }
+
+    // the instructions have changed, so the reaching definitions have to be calculated again
+    reaching_definitions::refresh(cfg);
+}
+
+/// Find the slots which are worth caching: those which are accessed more than once, or in a loop
+fn find_slots(cfg: &ControlFlowGraph, ns: &mut Namespace) -> Vec<Slot> {
+    let in_loop: HashSet<usize> = cfg.loops().into_iter().flat_map(|lp| lp.blocks).collect();
+
+    // slot expression, type, number of accesses, accessed in loop, is valid
+    let mut candidates: Vec<(&Expression, &Type, usize, bool, bool)> = Vec::new();
+
+    for (block_no, block) in cfg.blocks.iter().enumerate() {
+        for instr in &block.instr {
+            let (storage, ty) = match instr {
+                Instr::LoadStorage { storage, ty, .. } | Instr::SetStorage { storage, ty, .. } => {
+                    (storage, ty)
+                }
+                _ => continue,
+            };
+
+            if !cacheable(ty) || !slot_vars(storage, &mut HashSet::new()) {
+                continue;
+            }
+
+            if let Some(candidate) = candidates.iter_mut().find(|c| slot_eq(c.0, storage)) {
+                // the same slot is accessed with different types
+                candidate.4 &= candidate.1 == ty;
+                candidate.2 += 1;
+                candidate.3 |= in_loop.contains(&block_no);
+            } else {
+                candidates.push((storage, ty, 1, in_loop.contains(&block_no), true));
+            }
+        }
+    }
+
+    candidates
+        .into_iter()
+        .filter(|(_, _, count, in_loop, valid)| *valid && (*count > 1 || *in_loop))
+        .map(|(storage, ty, ..)| {
+            let mut vars = HashSet::new();
+            slot_vars(storage, &mut vars);
+
+            let var_no = ns.next_id;
+            ns.next_id += 1;
+
+            Slot {
+                storage: storage.clone(),
+                ty: ty.clone(),
+                var_no,
+                vars,
+            }
+        })
+        .collect()
+}
+
+/// Types which can be held in a variable without any aliasing
+fn cacheable(ty: &Type) -> bool {
+    matches!(
+        ty,
+        Type::Bool
+            | Type::Int(_)
+            | Type::Uint(_)
+            | Type::Address(_)
+            | Type::Bytes(_)
+            | Type::Enum(_)
+            | Type::Contract(_)
+            | Type::Value
+    )
+}
+
+/// Can this expression be used as the slot of a cached slot. Any variables used are added to vars.
+fn slot_vars(expr: &Expression, vars: &mut HashSet<usize>) -> bool {
+    match expr {
+        Expression::NumberLiteral { .. } => true,
+        Expression::FunctionArg { ty, .. } => cacheable(ty),
+        Expression::Variable { ty, var_no, .. } => {
+            vars.insert(*var_no);
+            cacheable(ty)
+        }
+        Expression::Builtin {
+            kind: Builtin::Sender | Builtin::Origin | Builtin::Value,
+            args,
+            ..
+        }
+        | Expression::Keccak256 { exprs: args, .. } => {
+            args.iter().all(|expr| slot_vars(expr, vars))
+        }
+        Expression::ZeroExt { expr, .. }
+        | Expression::SignExt { expr, .. }
+        | Expression::Trunc { expr, .. }
+        | Expression::Cast { expr, .. } => slot_vars(expr, vars),
+        Expression::Add { left, right, .. }
+        | Expression::Subtract { left, right, .. }
+        | Expression::Multiply { left, right, .. } => {
+            slot_vars(left, vars) && slot_vars(right, vars)
+        }
+        _ => false,
+    }
+}
+
+/// Are these two slot expressions the same
+fn slot_eq(left: &Expression, right: &Expression) -> bool {
+    match (left, right) {
+        (
+            Expression::NumberLiteral {
+                ty: left_ty,
+                value: left,
+                ..
+            },
+            Expression::NumberLiteral {
+                ty: right_ty,
+                value: right,
+                ..
+            },
+        ) => left_ty == right_ty && left == right,
+        (
+            Expression::FunctionArg { arg_no: left, .. },
+            Expression::FunctionArg { arg_no: right, .. },
+        )
+        | (Expression::Variable { var_no: left, .. }, Expression::Variable { var_no: right, .. }) => {
+            left == right
+        }
+        (
+            Expression::Builtin {
+                kind: left_kind,
+                args: left,
+                ..
+            },
+            Expression::Builtin {
+                kind: right_kind,
+                args: right,
+                ..
+            },
+        ) => left_kind == right_kind && slots_eq(left, right),
+        (Expression::Keccak256 { exprs: left, .. }, Expression::Keccak256 { exprs: right, .. }) => {
+            slots_eq(left, right)
+        }
+        (
+            Expression::ZeroExt {
+                ty: left_ty,
+                expr: left,
+                ..
+            },
+            Expression::ZeroExt {
+                ty: right_ty,
+                expr: right,
+                ..
+            },
+        )
+        | (
+            Expression::SignExt {
+                ty: left_ty,
+                expr: left,
+                ..
+            },
+            Expression::SignExt {
+                ty: right_ty,
+                expr: right,
+                ..
+            },
+        )
+        | (
+            Expression::Trunc {
+                ty: left_ty,
+                expr: left,
+                ..
+            },
+            Expression::Trunc {
+                ty: right_ty,
+                expr: right,
+                ..
+            },
+        )
+        | (
+            Expression::Cast {
+                ty: left_ty,
+                expr: left,
+                ..
+            },
+            Expression::Cast {
+                ty: right_ty,
+                expr: right,
+                ..
+            },
+        ) => left_ty == right_ty && slot_eq(left, right),
+        (
+            Expression::Add {
+                ty: left_ty,
+                left: l1,
+                right: r1,
+                ..
+            },
+            Expression::Add {
+                ty: right_ty,
+                left: l2,
+                right: r2,
+                ..
+            },
+        )
+        | (
+            Expression::Subtract {
+                ty: left_ty,
+                left: l1,
+                right: r1,
+                ..
+            },
+            Expression::Subtract {
+                ty: right_ty,
+                left: l2,
+                right: r2,
+                ..
+            },
+        )
+        | (
+            Expression::Multiply {
+                ty: left_ty,
+                left: l1,
+                right: r1,
+                ..
+            },
+            Expression::Multiply {
+                ty: right_ty,
+                left: l2,
+                right: r2,
+                ..
+            },
+        ) => left_ty == right_ty && slot_eq(l1, l2) && slot_eq(r1, r2),
+        _ => false,
+    }
+}
+
+fn slots_eq(left: &[Expression], right: &[Expression]) -> bool {
+    left.len() == right.len() && left.iter().zip(right).all(|(l, r)| slot_eq(l, r))
+}
+
+/// Can these two different slot expressions refer to the same slot. Two different constant slots
+/// cannot, and neither can a constant slot and a hashed one, or two hashes of a different
+/// constant, like the same key in two different mappings.
+fn may_alias(left: &Expression, right: &Expression) -> bool {
+    match (left, right) {
+        (Expression::NumberLiteral { .. }, Expression::NumberLiteral { .. })
+        | (Expression::NumberLiteral { .. }, Expression::Keccak256 { .. })
+        | (Expression::Keccak256 { .. }, Expression::NumberLiteral { .. }) => slot_eq(left, right),
+        (Expression::Keccak256 { exprs: left, .. }, Expression::Keccak256 { exprs: right, .. }) => {
+            left.len() != right.len()
+                || !left.iter().zip(right).any(|(l, r)| {
+                    matches!(
+                        (l, r),
+                        (
+                            Expression::NumberLiteral { .. },
+                            Expression::NumberLiteral { .. }
+                        )
+                    ) && !slot_eq(l, r)
+                })
+        }
+        _ => true,
+    }
+}
+
+/// Calculate the cache state on entry to each block, given the writes and loads done at the end of
+/// the blocks. Unreachable blocks have no state.
+fn analyse(cfg: &ControlFlowGraph, slots: &[Slot], exits: &[BlockExit]) -> Vec<Option<State>> {
+    let mut entries: Vec<Option<State>> = vec![None; cfg.blocks.len()];
+    let mut blocks_todo = BTreeSet::new();
+
+    entries[0] = Some(State::default());
+    blocks_todo.insert(0);
+
+    while let Some(block_no) = blocks_todo.pop_first() {
+        let mut state = entries[block_no].clone().unwrap();
+
+        block(cfg, slots, block_no, &exits[block_no], &mut state);
+
+        for succ in cfg.blocks[block_no].successors() {
+            let entry = match &entries[succ] {
+                Some(entry) => entry.join(&state),
+                None => state.clone(),
+            };
+
+            if entries[succ].as_ref() != Some(&entry) {
+                entries[succ] = Some(entry);
+                blocks_todo.insert(succ);
+            }
+        }
+    }
+
+    entries
+}
+
+/// Calculate the cache state at the end of each block
+fn exit_states(
+    cfg: &ControlFlowGraph,
+    slots: &[Slot],
+    exits: &[BlockExit],
+    entries: &[Option<State>],
+) -> Vec<Option<State>> {
+    entries
+        .iter()
+        .enumerate()
+        .map(|(block_no, entry)| {
+            entry.as_ref().map(|entry| {
+                let mut state = entry.clone();
+                block(cfg, slots, block_no, &exits[block_no], &mut state);
+                state
+            })
+        })
+        .collect()
+}
+
+/// Step through a block, updating the state and returning the new instructions for the block
+fn block(
+    cfg: &ControlFlowGraph,
+    slots: &[Slot],
+    block_no: usize,
+    exit: &BlockExit,
+    state: &mut State,
+) -> Vec<Instr> {
+    let block = &cfg.blocks[block_no];
+    let mut out = Vec::new();
+
+    for (instr_no, instr) in block.instr.iter().enumerate() {
+        if instr_no == block.instr.len() - 1
+            && matches!(
+                instr,
+                Instr::Branch { .. } | Instr::BranchCond { .. } | Instr::Switch { .. }
+            )
+        {
+            for slot_no in &exit.flush {
+                flush(slots, *slot_no, state, &mut out);
+            }
+
+            for slot_no in &exit.load {
+                if !state.cached.contains(slot_no) {
+                    flush_aliases(
+                        slots,
+                        &slots[*slot_no].storage,
+                        Some(*slot_no),
+                        false,
+                        state,
+                        &mut out,
+                    );
+                    load(slots, *slot_no, state, &mut out);
+                }
+            }
+        }
+
+        instruction(slots, instr, &block.transfers[instr_no], state, &mut out);
+    }
+
+    out
+}
+
+/// Update the state for an instruction, and generate the instructions which replace it
+fn instruction(
+    slots: &[Slot],
+    instr: &Instr,
+    transfers: &[Transfer],
+    state: &mut State,
+    out: &mut Vec<Instr>,
+) {
+    // slots which use a variable which is modified by this instruction
+    let modified: Vec<usize> = slots
+        .iter()
+        .enumerate()
+        .filter(|(_, slot)| {
+            transfers.iter().any(|transfer| match transfer {
+                Transfer::Gen { var_no, .. }
+                | Transfer::Mod { var_no }
+                | Transfer::Copy { var_no, .. }
+                | Transfer::Kill { var_no } => slot.vars.contains(var_no),
+            })
+        })
+        .map(|(slot_no, _)| slot_no)
+        .collect();
+
+    for slot_no in &modified {
+        flush(slots, *slot_no, state, out);
+    }
+
+    let mut reads_storage = false;
+
+    instr.recurse_expressions(&mut reads_storage, |expr, reads_storage| {
+        if matches!(expr, Expression::StorageArrayLength { .. }) {
+            *reads_storage = true;
+        }
+        !*reads_storage
+    });
+
+    if reads_storage {
+        flush_all(slots, state, out);
+    }
+
+    match instr {
+        Instr::LoadStorage { res, ty, storage } => {
+            if let Some(slot_no) = lookup(slots, storage, ty) {
+                flush_aliases(slots, storage, Some(slot_no), false, state, out);
+
+                if !state.cached.contains(&slot_no) {
+                    load(slots, slot_no, state, out);
+                }
+
+                out.push(Instr::Set {
+                    loc: Loc::Codegen,
+                    res: *res,
+                    expr: Expression::Variable {
+                        loc: Loc::Codegen,
+                        ty: ty.clone(),
+                        var_no: slots[slot_no].var_no,
+                    },
+                });
+            } else if cacheable(ty) && slot_vars(storage, &mut HashSet::new()) {
+                flush_aliases(slots, storage, None, false, state, out);
+                out.push(instr.clone());
+            } else {
+                flush_all(slots, state, out);
+                out.push(instr.clone());
+            }
+        }
+        Instr::SetStorage { ty, value, storage } => {
+            if let Some(slot_no) = lookup(slots, storage, ty) {
+                flush_aliases(slots, storage, Some(slot_no), true, state, out);
+
+                out.push(Instr::Set {
+                    loc: Loc::Codegen,
+                    res: slots[slot_no].var_no,
+                    expr: value.clone(),
+                });
+
+                state.cached.insert(slot_no);
+                state.dirty.insert(slot_no);
+            } else if cacheable(ty) && slot_vars(storage, &mut HashSet::new()) {
+                flush_aliases(slots, storage, None, true, state, out);
+                out.push(instr.clone());
+            } else {
+                flush_all(slots, state, out);
+                out.push(instr.clone());
+                state.cached.clear();
+            }
+        }
+        Instr::ClearStorage { .. }
+        | Instr::SetStorageBytes { .. }
+        | Instr::PushStorage { .. }
+        | Instr::PopStorage { .. }
+        | Instr::Call { .. }
+        | Instr::Constructor { .. }
+        | Instr::ExternalCall { .. }
+        | Instr::ValueTransfer { .. }
+        | Instr::SelfDestruct { .. }
+        | Instr::AccountAccess { .. }
+        | Instr::Unimplemented { .. } => {
+            // this may read or modify storage
+            flush_all(slots, state, out);
+            out.push(instr.clone());
+            state.cached.clear();
+        }
+        Instr::Return { .. } | Instr::ReturnData { .. } | Instr::ReturnCode { .. } => {
+            flush_all(slots, state, out);
+            out.push(instr.clone());
+        }
+        _ => {
+            out.push(instr.clone());
+        }
+    }
+
+    for slot_no in modified {
+        state.cached.remove(&slot_no);
+    }
+}
+
+fn lookup(slots: &[Slot], storage: &Expression, ty: &Type) -> Option<usize> {
+    slots
+        .iter()
+        .position(|slot| slot.ty == *ty && slot_eq(&slot.storage, storage))
+}
+
+/// Load the slot from storage into its variable
+fn load(slots: &[Slot], slot_no: usize, state: &mut State, out: &mut Vec<Instr>) {
+    let slot = &slots[slot_no];
+
+    out.push(Instr::LoadStorage {
+        res: slot.var_no,
+        ty: slot.ty.clone(),
+        storage: slot.storage.clone(),
+    });
+
+    state.cached.insert(slot_no);
+}
+
+/// Write the slot to storage, if it is dirty
+fn flush(slots: &[Slot], slot_no: usize, state: &mut State, out: &mut Vec<Instr>) {
+    if state.dirty.remove(&slot_no) {
+        let slot = &slots[slot_no];
+
+        out.push(Instr::SetStorage {
+            ty: slot.ty.clone(),
+            value: Expression::Variable {
+                loc: Loc::Codegen,
+                ty: slot.ty.clone(),
+                var_no: slot.var_no,
+            },
+            storage: slot.storage.clone(),
+        });
+    }
+}
+
+fn flush_all(slots: &[Slot], state: &mut State, out: &mut Vec<Instr>) {
+    for slot_no in state.dirty.clone() {
+        flush(slots, slot_no, state, out);
+    }
+}
+
+/// Before accessing a slot, write any other slot which may be the same slot. If the slot is going
+/// to be modified, those slots are no longer cached.
+fn flush_aliases(
+    slots: &[Slot],
+    storage: &Expression,
+    slot_no: Option<usize>,
+    modify: bool,
+    state: &mut State,
+    out: &mut Vec<Instr>,
+) {
+    for other_no in state.cached.clone() {
+        if Some(other_no) != slot_no && may_alias(storage, &slots[other_no].storage) {
+            flush(slots, other_no, state, out);
+
+            if modify {
+                state.cached.remove(&other_no);
+            }
+        }
+    }
+}
@@ -0,0 +1,405 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The storage layout of a contract: where each state variable is stored, and how its type is
+//! laid out. On Solana the slot is the offset in bytes in the account data, on the other
+//! targets it is the storage slot. Variables which are packed together share a storage slot,
+//! and have an offset in bytes within the slot.
+//!
+//! The layout of an upgraded contract can be checked against the layout of the contract
+//! which is deployed, so that existing state is not reinterpreted after the upgrade.
+
+mod tests;
+
+use crate::sema::ast::{ArrayLength, Namespace, Type};
+use crate::Target;
+use num_bigint::BigInt;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StorageLayout {
+    pub contract: String,
+    pub target: String,
+    /// Either `bytes` on Solana, or `slots`
+    pub unit: String,
+    pub storage: Vec<StorageEntry>,
+    /// The types used by the storage entries, by their label
+    pub types: BTreeMap<String, StorageType>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StorageEntry {
+    /// The name of the variable or struct field
+    pub label: String,
+    /// The contract which declares the variable, or the struct which declares the field
+    pub contract: String,
+    /// The slot or offset, relative to the struct for struct fields
+    pub slot: String,
+    /// The offset in bytes within the slot, if the variable is packed
+    pub offset: u32,
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StorageType {
+    /// One of `inplace`, `mapping`, `dynamic_array` or `bytes`
+    pub encoding: String,
+    /// The size in the unit of the layout
+    pub size: String,
+    /// The fields of a struct
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<StorageEntry>,
+    /// The key of a mapping
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// The value of a mapping
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// The element of an array
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+}
+
+impl StorageType {
+    fn inplace(size: String) -> Self {
+        StorageType {
+            encoding: "inplace".into(),
+            size,
+            members: Vec::new(),
+            key: None,
+            value: None,
+            base: None,
+        }
+    }
+}
+
+impl fmt::Display for StorageLayout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "contract {} ({}, {})",
+            self.contract, self.target, self.unit
+        )?;
+
+        let slot_width = self
+            .storage
+            .iter()
+            .map(|entry| entry.slot.len())
+            .chain(std::iter::once("slot".len()))
+            .max()
+            .unwrap_or_default();
+
+        let type_width = self
+            .storage
+            .iter()
+            .map(|entry| entry.ty.len())
+            .chain(std::iter::once("type".len()))
+            .max()
+            .unwrap_or_default();
+
+        writeln!(
+            f,
+            "  {:slot_width$}  offset  {:type_width$}  variable",
+            "slot", "type"
+        )?;
+
+        for entry in &self.storage {
+            writeln!(
+                f,
+                "  {:slot_width$}  {:6}  {:type_width$}  {}.{}",
+                entry.slot, entry.offset, entry.ty, entry.contract, entry.label
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The storage layout of the contract. Codegen must have run, since it lays out the storage.
+pub fn storage_layout(contract_no: usize, ns: &Namespace) -> StorageLayout {
+    let contract = &ns.contracts[contract_no];
+
+    let mut layout = StorageLayout {
+        contract: contract.id.name.clone(),
+        target: ns.target.to_string(),
+        unit: if ns.target == Target::Solana {
+            "bytes".into()
+        } else {
+            "slots".into()
+        },
+        storage: Vec::new(),
+        types: BTreeMap::new(),
+    };
+
+    for var in &contract.layout {
+        let entry = StorageEntry {
+            label: ns.contracts[var.contract_no].variables[var.var_no]
+                .name
+                .clone(),
+            contract: ns.contracts[var.contract_no].id.name.clone(),
+            slot: var.slot.to_string(),
+            offset: var.packed.as_ref().map_or(0, |packed| packed.offset),
+            ty: add_type(&var.ty, ns, &mut layout.types),
+        };
+
+        layout.storage.push(entry);
+    }
+
+    layout
+}
+
+/// Add the type and the types it contains to the types of the layout, and return its label
+fn add_type(ty: &Type, ns: &Namespace, types: &mut BTreeMap<String, StorageType>) -> String {
+    let label = type_label(ty, ns);
+
+    if types.contains_key(&label) {
+        return label;
+    }
+
+    let size = ty.storage_slots(ns).to_string();
+
+    // a struct may contain itself through a mapping or dynamic array, so add it before its fields
+    types.insert(label.clone(), StorageType::inplace(size.clone()));
+
+    let mut storage_type = StorageType::inplace(size);
+
+    match ty {
+        Type::String | Type::DynamicBytes => {
+            storage_type.encoding = "bytes".into();
+        }
+        Type::Mapping(mapping) => {
+            storage_type.encoding = "mapping".into();
+            storage_type.key = Some(add_type(&mapping.key, ns, types));
+            storage_type.value = Some(add_type(&mapping.value, ns, types));
+        }
+        Type::Array(..) => {
+            if ty.array_length().is_none() {
+                storage_type.encoding = "dynamic_array".into();
+            }
+
+            storage_type.base = Some(add_type(&ty.array_elem(), ns, types));
+        }
+        Type::Struct(str_ty) => {
+            let def = str_ty.definition(ns);
+
+            for (field, slot) in def
+                .fields
+                .iter()
+                .filter(|field| !field.infinite_size)
+                .zip(&def.storage_offsets)
+            {
+                storage_type.members.push(StorageEntry {
+                    label: field.name_as_str().to_owned(),
+                    contract: def.id.name.clone(),
+                    slot: slot.to_string(),
+                    offset: 0,
+                    ty: add_type(&field.ty, ns, types),
+                });
+            }
+        }
+        Type::UserType(no) => {
+            storage_type.base = Some(add_type(&ns.user_types[*no].ty, ns, types));
+        }
+        _ => (),
+    }
+
+    types.insert(label.clone(), storage_type);
+
+    label
+}
+
+/// The label of a type, without the names of mapping keys and values, which do not affect the
+/// layout
+fn type_label(ty: &Type, ns: &Namespace) -> String {
+    match ty {
+        Type::Mapping(mapping) => format!(
+            "mapping({} => {})",
+            type_label(&mapping.key, ns),
+            type_label(&mapping.value, ns)
+        ),
+        Type::Array(elem_ty, dims) => format!(
+            "{}{}",
+            type_label(elem_ty, ns),
+            dims.iter()
+                .map(|len| match len {
+                    ArrayLength::Fixed(len) => format!("[{len}]"),
+                    _ => "[]".to_string(),
+                })
+                .collect::<String>()
+        ),
+        _ => ty.to_string(ns),
+    }
+}
+
+/// Check that the storage layout of the new version of a contract is compatible with the
+/// layout of the old version, so that the new version can be used with the state of the old
+/// one. New variables can be added after the existing ones, and fields can be added to structs
+/// which are the value of a mapping or the last variable. Returns the incompatible changes.
+pub fn upgrade_check(old: &StorageLayout, new: &StorageLayout) -> Vec<String> {
+    let mut checker = UpgradeChecker {
+        old: &old.types,
+        new: &new.types,
+        seen: HashSet::new(),
+        problems: Vec::new(),
+    };
+
+    if old.target != new.target {
+        checker.problems.push(format!(
+            "target changed from {} to {}",
+            old.target, new.target
+        ));
+    } else {
+        let end = old
+            .storage
+            .iter()
+            .map(|entry| slot(entry) + size(&entry.ty, &old.types))
+            .max()
+            .unwrap_or_default();
+
+        checker.entries(&old.storage, &new.storage, end, "");
+    }
+
+    checker.problems
+}
+
+struct UpgradeChecker<'a> {
+    old: &'a BTreeMap<String, StorageType>,
+    new: &'a BTreeMap<String, StorageType>,
+    /// Pairs of types which have been checked, since structs can contain themselves
+    seen: HashSet<(String, String, bool)>,
+    problems: Vec<String>,
+}
+
+impl UpgradeChecker<'_> {
+    /// Check the variables or the fields of a struct. The old entries end at the given slot.
+    fn entries(&mut self, old: &[StorageEntry], new: &[StorageEntry], end: BigInt, prefix: &str) {
+        let same = |a: &StorageEntry, b: &StorageEntry| {
+            a.label == b.label && (!prefix.is_empty() || a.contract == b.contract)
+        };
+
+        for old_entry in old {
+            let name = format!("{prefix}{}", old_entry.label);
+
+            let Some(new_entry) = new.iter().find(|new_entry| same(old_entry, new_entry)) else {
+                self.problems
+                    .push(format!("'{name}' at {} was deleted", position(old_entry)));
+                continue;
+            };
+
+            if old_entry.slot != new_entry.slot || old_entry.offset != new_entry.offset {
+                self.problems.push(format!(
+                    "'{name}' moved from {} to {}",
+                    position(old_entry),
+                    position(new_entry)
+                ));
+            }
+
+            self.ty(&old_entry.ty, &new_entry.ty, &name, true);
+        }
+
+        for new_entry in new {
+            if !old.iter().any(|old_entry| same(old_entry, new_entry)) && slot(new_entry) < end {
+                self.problems.push(format!(
+                    "'{prefix}{}' was inserted at {}, which is used by the old layout",
+                    new_entry.label,
+                    position(new_entry)
+                ));
+            }
+        }
+    }
+
+    /// Check that a value of the old type is read correctly as the new type. If the type can
+    /// grow, it may be larger than before, as long as the existing parts do not move.
+    fn ty(&mut self, old_label: &str, new_label: &str, name: &str, can_grow: bool) {
+        let (Some(old), Some(new)) = (self.old.get(old_label), self.new.get(new_label)) else {
+            self.problems
+                .push(format!("'{name}' has a type which is not described"));
+            return;
+        };
+
+        if !self
+            .seen
+            .insert((old_label.to_owned(), new_label.to_owned(), can_grow))
+        {
+            return;
+        }
+
+        let changed = format!("'{name}' changed type from {old_label} to {new_label}");
+
+        if old.encoding != new.encoding {
+            self.problems.push(changed);
+            return;
+        }
+
+        match (&old.key, &new.key, &old.base, &new.base) {
+            (Some(old_key), Some(new_key), ..) => {
+                if old_key != new_key {
+                    self.problems.push(changed);
+                    return;
+                }
+
+                let value_name = format!("{name}[]");
+
+                self.ty(
+                    old.value.as_deref().unwrap_or_default(),
+                    new.value.as_deref().unwrap_or_default(),
+                    &value_name,
+                    true,
+                );
+            }
+            (.., Some(old_base), Some(new_base)) => {
+                let base_name = format!("{name}[]");
+
+                self.ty(old_base, new_base, &base_name, false);
+            }
+            _ if old.members.is_empty() != new.members.is_empty() => {
+                self.problems.push(changed);
+                return;
+            }
+            _ if !old.members.is_empty() => {
+                let end = old.size.parse().unwrap_or_default();
+
+                self.entries(&old.members, &new.members, end, &format!("{name}."));
+            }
+            _ if old_label.starts_with("enum ") && new_label.starts_with("enum ") => {
+                // enums can have new variants, as long as they still fit
+            }
+            _ if old_label != new_label => {
+                self.problems.push(changed);
+                return;
+            }
+            _ => (),
+        }
+
+        let old_size = size(old_label, self.old);
+        let new_size = size(new_label, self.new);
+
+        // a struct which shrinks has deleted fields, which are already reported
+        if old_size != new_size && (!can_grow || (old_size > new_size && old.members.is_empty())) {
+            self.problems.push(format!(
+                "'{name}' changed size from {old_size} to {new_size}"
+            ));
+        }
+    }
+}
+
+fn size(label: &str, types: &BTreeMap<String, StorageType>) -> BigInt {
+    types
+        .get(label)
+        .and_then(|ty| ty.size.parse().ok())
+        .unwrap_or_default()
+}
+
+fn slot(entry: &StorageEntry) -> BigInt {
+    entry.slot.parse().unwrap_or_default()
+}
+
+fn position(entry: &StorageEntry) -> String {
+    if entry.offset == 0 {
+        format!("slot {}", entry.slot)
+    } else {
+        format!("slot {} offset {}", entry.slot, entry.offset)
+    }
+}
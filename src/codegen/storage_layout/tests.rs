@@ -0,0 +1,194 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(test)]
+
+use super::{storage_layout, upgrade_check, StorageLayout};
+use crate::codegen::Options;
+use crate::sema::tests::resolve_and_codegen;
+use crate::Target;
+
+fn layout(src: &str, target: Target, opt: &Options) -> StorageLayout {
+    let ns = resolve_and_codegen(src, target, opt);
+
+    let contract_no = ns.contracts.iter().rposition(|c| c.instantiable).unwrap();
+
+    storage_layout(contract_no, &ns)
+}
+
+fn check(old: &str, new: &str) -> Vec<String> {
+    let old = layout(old, Target::default_polkadot(), &Options::default());
+    let new = layout(new, Target::default_polkadot(), &Options::default());
+
+    // the old layout is read back from json
+    let old = serde_json::from_str(&serde_json::to_string(&old).unwrap()).unwrap();
+
+    upgrade_check(&old, &new)
+}
+
+#[test]
+fn polkadot() {
+    let src = r#"
+        abstract contract Base {
+            uint64 total;
+        }
+
+        contract Token is Base {
+            struct Account { uint128 balance; bool frozen; }
+
+            bool paused;
+            uint8 decimals;
+            mapping(address owner => Account) accounts;
+            int32[4] history;
+            string public name;
+        }"#;
+
+    let opt = Options {
+        pack_storage: true,
+        ..Default::default()
+    };
+
+    let layout = layout(src, Target::default_polkadot(), &opt);
+
+    assert_eq!(
+        layout.to_string(),
+        r#"contract Token (Polkadot, slots)
+  slot  offset  type                                      variable
+  0          0  uint64                                    Base.total
+  0          8  bool                                      Token.paused
+  0          9  uint8                                     Token.decimals
+  1          0  mapping(address => struct Token.Account)  Token.accounts
+  2          0  int32[4]                                  Token.history
+  6          0  string                                    Token.name
+"#
+    );
+
+    let mapping = &layout.types["mapping(address => struct Token.Account)"];
+
+    assert_eq!(mapping.encoding, "mapping");
+    assert_eq!(mapping.key.as_deref(), Some("address"));
+
+    let account = &layout.types["struct Token.Account"];
+
+    assert_eq!(account.size, "2");
+    assert_eq!(account.members[1].label, "frozen");
+    assert_eq!(account.members[1].slot, "1");
+
+    assert_eq!(layout.types["int32[4]"].base.as_deref(), Some("int32"));
+    assert_eq!(layout.types["string"].encoding, "bytes");
+}
+
+#[test]
+fn solana() {
+    let src = r#"
+        contract Counter {
+            uint8 flag;
+            uint64 count;
+            address owner;
+            uint32[] values;
+        }"#;
+
+    let layout = layout(src, Target::Solana, &Options::default());
+
+    let slots: Vec<(&str, &str)> = layout
+        .storage
+        .iter()
+        .map(|entry| (entry.label.as_str(), entry.slot.as_str()))
+        .collect();
+
+    assert_eq!(layout.unit, "bytes");
+    assert_eq!(
+        slots,
+        vec![
+            ("flag", "16"),
+            ("count", "24"),
+            ("owner", "32"),
+            ("values", "64")
+        ]
+    );
+    assert_eq!(layout.types["uint32[]"].encoding, "dynamic_array");
+}
+
+#[test]
+fn compatible_upgrade() {
+    let old = r#"
+        contract C {
+            function f() public {}
+            struct S { uint64 a; }
+            uint64 x;
+            mapping(uint32 => S) m;
+            S[] list;
+        }"#;
+
+    // new variables at the end, and a new field of a struct in a mapping
+    let new = r#"
+        contract C {
+            function f() public {}
+            struct S { uint64 a; bool b; }
+            uint64 x;
+            mapping(uint32 key => S) m;
+            uint64[] list;
+            bool y;
+        }"#;
+
+    assert_eq!(
+        check(old, new),
+        vec!["'list[]' changed type from struct C.S to uint64"]
+    );
+
+    let new = r#"
+        contract C {
+            function f() public {}
+            struct S { uint64 a; bool b; }
+            uint64 x;
+            mapping(uint32 key => S) m;
+            S[] list;
+            bool y;
+        }"#;
+
+    assert_eq!(check(old, new), vec!["'list[]' changed size from 1 to 2"]);
+
+    let new = r#"
+        contract C {
+            function f() public {}
+            struct S { uint64 a; }
+            uint64 x;
+            mapping(uint32 => S) m;
+            S[] list;
+            bool y;
+        }"#;
+
+    assert!(check(old, new).is_empty());
+}
+
+#[test]
+fn incompatible_upgrade() {
+    let old = r#"
+        contract C {
+            function f() public {}
+            uint64 a;
+            uint64 b;
+            uint32 c;
+            string d;
+        }"#;
+
+    let new = r#"
+        contract C {
+            function f() public {}
+            uint64 b;
+            uint64 a;
+            uint64 inserted;
+            uint128 c;
+        }"#;
+
+    assert_eq!(
+        check(old, new),
+        vec![
+            "'a' moved from slot 0 to slot 1",
+            "'b' moved from slot 1 to slot 0",
+            "'c' moved from slot 2 to slot 3",
+            "'c' changed type from uint32 to uint128",
+            "'d' at slot 3 was deleted",
+            "'inserted' was inserted at slot 2, which is used by the old layout",
+        ]
+    );
+}
@@ -1,13 +1,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::value::Value;
-use super::{bigint_to_bitarr, highest_set_bit, track, Bits, Variables};
+use super::{bigint_to_bitarr, track, Bits, Variables};
 use crate::codegen::Expression;
 use crate::sema::ast::RetrieveType;
 use crate::sema::ast::{Namespace, Type};
 use bitvec::prelude::*;
 use itertools::Itertools;
 use num_bigint::{BigInt, Sign};
+use num_integer::Integer;
+use num_traits::One;
 use std::collections::HashSet;
 
 pub(super) fn expression_values(
@@ -69,7 +71,7 @@ pub(super) fn expression_values(
         } => not_equal_values(left_expr, right_expr, vars, ns),
         Expression::Not { expr, .. } => not_values(expr, vars, ns),
         Expression::BitwiseNot { expr, .. } => complement_values(expr, vars, ns),
-        Expression::Variable { var_no, .. } => variable_values(*var_no, vars),
+        Expression::Variable { ty, var_no, .. } => variable_values(ty, *var_no, vars, ns),
         Expression::InternalFunctionCfg { .. } => {
             // reference to a function; ignore
             HashSet::new()
@@ -82,29 +84,31 @@ pub(super) fn expression_values(
 
             HashSet::new()
         }
-        e => {
-            let ty = e.ty();
-            let mut set = HashSet::new();
+        e => unknown_values(&e.ty(), ns),
+    }
+}
 
-            if track(&ty) {
-                // the all bits known
-                let mut known_bits = BitArray::new([!0u8; 32]);
+/// The values of an expression of the given type about which nothing is known
+pub(super) fn unknown_values(ty: &Type, ns: &Namespace) -> HashSet<Value> {
+    let mut set = HashSet::new();
 
-                let bits = ty.bits(ns) as usize;
+    if track(ty) {
+        // the all bits known
+        let mut known_bits = BitArray::new([!0u8; 32]);
 
-                // set the bits from the value to unknown
-                known_bits[0..bits].fill(false);
+        let bits = ty.bits(ns) as usize;
 
-                set.insert(Value {
-                    known_bits,
-                    value: BitArray::new([0u8; 32]),
-                    bits,
-                });
-            }
+        // set the bits from the value to unknown
+        known_bits[0..bits].fill(false);
 
-            set
-        }
+        set.insert(Value {
+            known_bits,
+            value: BitArray::new([0u8; 32]),
+            bits,
+        });
     }
+
+    set
 }
 
 fn number_literal_values(ty: &Type, v: &BigInt, ns: &Namespace) -> HashSet<Value> {
@@ -150,6 +154,7 @@ fn zero_ext_values(
         .map(|mut v| {
             let bits_before = v.bits;
             v.known_bits[bits_before..bits_after].fill(true);
+            v.value[bits_before..bits_after].fill(false);
             v.bits = bits_after;
             v
         })
@@ -206,12 +211,18 @@ fn bitwise_or_values(
     let left = expression_values(left, vars, ns);
     let right = expression_values(right, vars, ns);
 
+    // a bit is known if it is known in both, or known to be set in either
     left.iter()
         .cartesian_product(right.iter())
-        .map(|(l, r)| Value {
-            value: l.value | (r.value & r.known_bits),
-            known_bits: l.known_bits | (r.value & r.known_bits),
-            bits: l.bits,
+        .map(|(l, r)| {
+            let l_set = l.value & l.known_bits;
+            let r_set = r.value & r.known_bits;
+
+            Value {
+                value: l_set | r_set,
+                known_bits: (l.known_bits & r.known_bits) | l_set | r_set,
+                bits: l.bits,
+            }
         })
         .collect()
 }
@@ -225,15 +236,18 @@ fn bitwise_and_values(
     let left = expression_values(left, vars, ns);
     let right = expression_values(right, vars, ns);
 
-    // bitwise and
-    // value bits become 0 if right known_bit and !value
-    // known_bits because more if known_bit & !value
+    // a bit is known if it is known in both, or known to be clear in either
     left.iter()
         .cartesian_product(right.iter())
-        .map(|(l, r)| Value {
-            value: l.value & (r.known_bits & !r.value),
-            known_bits: l.known_bits | (r.known_bits & !r.value),
-            bits: l.bits,
+        .map(|(l, r)| {
+            let l_clear = !l.value & l.known_bits;
+            let r_clear = !r.value & r.known_bits;
+
+            Value {
+                value: l.value & l.known_bits & r.value & r.known_bits,
+                known_bits: (l.known_bits & r.known_bits) | l_clear | r_clear,
+                bits: l.bits,
+            }
         })
         .collect()
 }
@@ -247,17 +261,15 @@ fn bitwise_xor_values(
     let left = expression_values(left, vars, ns);
     let right = expression_values(right, vars, ns);
 
-    // bitwise and
-    // value bits become 0 if right known_bit and !value
-    // known_bits because more if known_bit & !value
     left.iter()
         .cartesian_product(right.iter())
         .map(|(l, r)| {
-            let mut value = l.value ^ r.value;
+            let known_bits = l.known_bits & r.known_bits;
+            let mut value = (l.value ^ r.value) & known_bits;
             value[l.bits..].fill(false);
             Value {
                 value,
-                known_bits: l.known_bits & r.known_bits,
+                known_bits,
                 bits: l.bits,
             }
         })
@@ -273,49 +285,15 @@ fn add_values(
 ) -> HashSet<Value> {
     let left = expression_values(left, vars, ns);
     let right = expression_values(right, vars, ns);
+    let bits = ty.bits(ns) as usize;
 
     left.iter()
         .cartesian_product(right.iter())
         .map(|(l, r)| {
-            let mut min_possible =
-                (BigInt::from_signed_bytes_le(&l.get_unsigned_min_value().into_inner())
-                    + BigInt::from_signed_bytes_le(&r.get_unsigned_min_value().into_inner()))
-                .to_signed_bytes_le();
-            let sign = if (min_possible.last().unwrap() & 0x80) != 0 {
-                u8::MAX
-            } else {
-                u8::MIN
-            };
-            min_possible.resize(32, sign);
-
-            let mut min_possible: Bits = BitArray::new(min_possible.try_into().unwrap());
-            min_possible[ty.bits(ns) as usize..].fill(false);
-
-            let mut max_possible =
-                (BigInt::from_signed_bytes_le(&l.get_unsigned_max_value().into_inner())
-                    + BigInt::from_signed_bytes_le(&r.get_unsigned_max_value().into_inner()))
-                .to_signed_bytes_le();
-            let sign = if (max_possible.last().unwrap() & 0x80) != 0 {
-                u8::MAX
-            } else {
-                u8::MIN
-            };
-            max_possible.resize(32, sign);
-
-            let mut max_possible: Bits = BitArray::new(max_possible.try_into().unwrap());
-            max_possible[ty.bits(ns) as usize..].fill(false);
-
-            let known_bits = !(min_possible ^ max_possible) & l.known_bits & r.known_bits;
-
-            if known_bits.all() {
-                assert_eq!(min_possible, max_possible);
-            }
+            let (l_min, l_max) = l.range(false);
+            let (r_min, r_max) = r.range(false);
 
-            Value {
-                value: min_possible,
-                known_bits,
-                bits: l.bits,
-            }
+            arithmetic_value(l, r, l_min + r_min, l_max + r_max, |a, b| a + b, bits)
         })
         .collect()
 }
@@ -329,45 +307,15 @@ fn subtract_values(
 ) -> HashSet<Value> {
     let left = expression_values(left, vars, ns);
     let right = expression_values(right, vars, ns);
+    let bits = ty.bits(ns) as usize;
 
     left.iter()
         .cartesian_product(right.iter())
         .map(|(l, r)| {
-            let mut min_possible =
-                (BigInt::from_signed_bytes_le(&l.get_unsigned_min_value().into_inner())
-                    - BigInt::from_signed_bytes_le(&r.get_unsigned_min_value().into_inner()))
-                .to_signed_bytes_le();
-            let sign = if (min_possible.last().unwrap() & 0x80) != 0 {
-                u8::MAX
-            } else {
-                u8::MIN
-            };
-            min_possible.resize(32, sign);
-
-            let mut min_possible: Bits = BitArray::new(min_possible.try_into().unwrap());
-            min_possible[ty.bits(ns) as usize..].fill(false);
-
-            let mut max_possible =
-                (BigInt::from_signed_bytes_le(&l.get_unsigned_max_value().into_inner())
-                    - BigInt::from_signed_bytes_le(&r.get_unsigned_max_value().into_inner()))
-                .to_signed_bytes_le();
-            let sign = if (max_possible.last().unwrap() & 0x80) != 0 {
-                u8::MAX
-            } else {
-                u8::MIN
-            };
-            max_possible.resize(32, sign);
-
-            let mut max_possible: Bits = BitArray::new(max_possible.try_into().unwrap());
-            max_possible[ty.bits(ns) as usize..].fill(false);
-
-            let known_bits = !(min_possible ^ max_possible) & l.known_bits & r.known_bits;
+            let (l_min, l_max) = l.range(false);
+            let (r_min, r_max) = r.range(false);
 
-            Value {
-                value: min_possible,
-                known_bits,
-                bits: l.bits,
-            }
+            arithmetic_value(l, r, l_min - r_max, l_max - r_min, |a, b| a - b, bits)
         })
         .collect()
 }
@@ -381,146 +329,96 @@ fn multiply_values(
 ) -> HashSet<Value> {
     let left = expression_values(left, vars, ns);
     let right = expression_values(right, vars, ns);
+    let bits = ty.bits(ns) as usize;
 
     left.iter()
         .cartesian_product(right.iter())
         .map(|(l, r)| {
-            let mut known_bits = BitArray::new([0u8; 32]);
+            let (l_min, l_max) = l.range(false);
+            let (r_min, r_max) = r.range(false);
 
-            if ty.is_signed_int(ns) {
-                match (l.sign(), r.sign()) {
-                    ((true, left_sign), (true, right_sign)) => {
-                        let left = if left_sign {
-                            l.get_signed_min_value()
-                        } else {
-                            l.get_signed_max_value()
-                        };
-
-                        let right = if right_sign {
-                            r.get_signed_min_value()
-                        } else {
-                            r.get_signed_max_value()
-                        };
-
-                        let max_possible = BigInt::from_signed_bytes_le(&left.into_inner())
-                            * BigInt::from_signed_bytes_le(&right.into_inner());
-
-                        let (sign, bs) = max_possible.to_bytes_le();
-                        let top_bit = highest_set_bit(&bs);
-
-                        let mut max_possible = max_possible.to_signed_bytes_le();
-
-                        max_possible.resize(32, if sign == Sign::Minus { u8::MAX } else { 0 });
-
-                        if l.known_bits[0..l.bits].all() && r.known_bits[0..r.bits].all() {
-                            // constants
-                            known_bits.fill(true);
-                        } else {
-                            known_bits[top_bit + 1..l.bits].fill(true);
-                        }
-
-                        Value {
-                            value: BitArray::new(max_possible.try_into().unwrap()),
-                            known_bits,
-                            bits: l.bits,
-                        }
-                    }
-                    _ => {
-                        // if we don't know either of the signs, we can't say anything about the result
-                        Value {
-                            value: BitArray::new([0u8; 32]),
-                            known_bits,
-                            bits: l.bits,
-                        }
-                    }
-                }
-            } else {
-                let mut max_possible =
-                    (BigInt::from_bytes_le(Sign::Plus, &l.get_unsigned_max_value().into_inner())
-                        * BigInt::from_bytes_le(
-                            Sign::Plus,
-                            &r.get_unsigned_max_value().into_inner(),
-                        ))
-                    .to_signed_bytes_le();
-
-                if l.known_bits[0..l.bits].all() && r.known_bits[0..r.bits].all() {
-                    // constants
-                    max_possible.resize(32, 0);
-
-                    known_bits.fill(true);
-
-                    Value {
-                        value: BitArray::new(max_possible.try_into().unwrap()),
-                        known_bits,
-                        bits: l.bits,
-                    }
-                } else {
-                    let top_bit = highest_set_bit(&max_possible);
-
-                    // one above the top bit and higher will be known (i.e. all zeros)
-                    if top_bit < l.bits {
-                        debug_assert_eq!(l.bits, r.bits);
-
-                        known_bits[top_bit + 1..l.bits].fill(true);
-                    }
-
-                    Value {
-                        value: BitArray::new([0u8; 32]),
-                        known_bits,
-                        bits: l.bits,
-                    }
-                }
-            }
+            arithmetic_value(l, r, l_min * r_min, l_max * r_max, |a, b| a * b, bits)
         })
         .collect()
 }
 
-fn more_values(
-    left: &Expression,
-    right: &Expression,
+/// Calculate the value of an add, subtract or multiply, given the smallest and largest result
+/// when the operands are treated as unsigned. This works for signed types too, since in
+/// two's complement the result has the same bits.
+fn arithmetic_value(
+    l: &Value,
+    r: &Value,
+    min: BigInt,
+    max: BigInt,
+    op: fn(&BigInt, &BigInt) -> BigInt,
+    bits: usize,
+) -> Value {
+    let modulus = BigInt::one() << bits;
+    let l_value = BigInt::from_bytes_le(Sign::Plus, &l.get_unsigned_min_value().into_inner());
+    let r_value = BigInt::from_bytes_le(Sign::Plus, &r.get_unsigned_min_value().into_inner());
+
+    if l.all_known() && r.all_known() {
+        // constants; the result wraps like it would at runtime
+        let v = op(&l_value, &r_value).mod_floor(&modulus);
+
+        return Value {
+            value: bigint_to_bitarr(&v, bits),
+            known_bits: BitArray::new([!0u8; 32]),
+            bits,
+        };
+    }
+
+    let mut known_bits: Bits = BitArray::new([0u8; 32]);
+    let mut value: Bits = BitArray::new([0u8; 32]);
+
+    // The low bits of the result only depend on the low bits of the operands, so if those
+    // are known in both, they are known in the result
+    let both_known = l.known_bits & r.known_bits;
+    let low = both_known[0..bits].first_zero().unwrap_or(bits);
+
+    if low > 0 {
+        let v = op(&l_value, &r_value).mod_floor(&(BigInt::one() << low));
+
+        value[0..low].copy_from_bitslice(&bigint_to_bitarr(&v, low)[0..low]);
+        known_bits[0..low].fill(true);
+    }
+
+    // If the result cannot wrap, then the high bits which are the same in the smallest and
+    // largest result are known
+    if min.sign() != Sign::Minus && max < modulus {
+        let min_bits = bigint_to_bitarr(&min, bits);
+        let max_bits = bigint_to_bitarr(&max, bits);
+        let differ = (min_bits ^ max_bits)[0..bits]
+            .last_one()
+            .map_or(0, |b| b + 1);
+
+        value[differ..bits].copy_from_bitslice(&min_bits[differ..bits]);
+        known_bits[differ..bits].fill(true);
+    }
+
+    Value {
+        value,
+        known_bits,
+        bits,
+    }
+}
+
+/// Compare two values, returning whether the comparison is known to be true or false
+fn compare_values(
+    left: &HashSet<Value>,
+    right: &HashSet<Value>,
     signed: bool,
-    vars: &Variables,
-    ns: &Namespace,
+    cmp: fn(&Value, &Value, signed: bool) -> Option<bool>,
 ) -> HashSet<Value> {
-    let left = expression_values(left, vars, ns);
-    let right = expression_values(right, vars, ns);
-
     left.iter()
         .cartesian_product(right.iter())
         .map(|(l, r)| {
-            // is l more than r
             let mut known_bits = BitArray::new([0u8; 32]);
             let mut value = BitArray::new([0u8; 32]);
 
-            let is_true = if signed {
-                BigInt::from_signed_bytes_le(&l.get_signed_max_value().into_inner())
-                    > BigInt::from_signed_bytes_le(&r.get_signed_min_value().into_inner())
-            } else {
-                BigInt::from_bytes_le(Sign::Plus, &l.get_unsigned_max_value().into_inner())
-                    > BigInt::from_bytes_le(Sign::Plus, &r.get_unsigned_min_value().into_inner())
-            };
-
-            if is_true {
-                // we know that this comparison is always true
+            if let Some(res) = cmp(l, r, signed) {
                 known_bits.set(0, true);
-                value.set(0, true);
-            } else {
-                // maybe the comparison is always false
-                let is_false = if signed {
-                    BigInt::from_signed_bytes_le(&l.get_signed_min_value().into_inner())
-                        <= BigInt::from_signed_bytes_le(&r.get_signed_max_value().into_inner())
-                } else {
-                    BigInt::from_bytes_le(Sign::Plus, &l.get_unsigned_min_value().into_inner())
-                        <= BigInt::from_bytes_le(
-                            Sign::Plus,
-                            &r.get_unsigned_max_value().into_inner(),
-                        )
-                };
-
-                if is_false {
-                    // we know that this comparison is always false
-                    known_bits.set(0, true);
-                }
+                value.set(0, res);
             }
 
             Value {
@@ -532,7 +430,7 @@ fn more_values(
         .collect()
 }
 
-fn more_equal_values(
+fn more_values(
     left: &Expression,
     right: &Expression,
     signed: bool,
@@ -542,51 +440,42 @@ fn more_equal_values(
     let left = expression_values(left, vars, ns);
     let right = expression_values(right, vars, ns);
 
-    left.iter()
-        .cartesian_product(right.iter())
-        .map(|(l, r)| {
-            // is l more than or equal r
-            let mut known_bits = BitArray::new([0u8; 32]);
-            let mut value = BitArray::new([0u8; 32]);
+    compare_values(&left, &right, signed, |l, r, signed| {
+        let (l_min, l_max) = l.range(signed);
+        let (r_min, r_max) = r.range(signed);
 
-            let is_true = if signed {
-                BigInt::from_signed_bytes_le(&l.get_signed_max_value().into_inner())
-                    >= BigInt::from_signed_bytes_le(&r.get_signed_min_value().into_inner())
-            } else {
-                BigInt::from_bytes_le(Sign::Plus, &l.get_unsigned_max_value().into_inner())
-                    >= BigInt::from_bytes_le(Sign::Plus, &r.get_unsigned_min_value().into_inner())
-            };
+        if l_min > r_max {
+            Some(true)
+        } else if l_max <= r_min {
+            Some(false)
+        } else {
+            None
+        }
+    })
+}
 
-            if is_true {
-                // we know that this comparison is always true
-                known_bits.set(0, true);
-                value.set(0, true);
-            } else {
-                // maybe the comparison is always false
-                let is_false = if signed {
-                    BigInt::from_signed_bytes_le(&l.get_signed_min_value().into_inner())
-                        < BigInt::from_signed_bytes_le(&r.get_signed_max_value().into_inner())
-                } else {
-                    BigInt::from_bytes_le(Sign::Plus, &l.get_unsigned_min_value().into_inner())
-                        < BigInt::from_bytes_le(
-                            Sign::Plus,
-                            &r.get_unsigned_max_value().into_inner(),
-                        )
-                };
-
-                if is_false {
-                    // we know that this comparison is always false
-                    known_bits.set(0, true);
-                }
-            }
+fn more_equal_values(
+    left: &Expression,
+    right: &Expression,
+    signed: bool,
+    vars: &Variables,
+    ns: &Namespace,
+) -> HashSet<Value> {
+    let left = expression_values(left, vars, ns);
+    let right = expression_values(right, vars, ns);
 
-            Value {
-                value,
-                known_bits,
-                bits: 1,
-            }
-        })
-        .collect()
+    compare_values(&left, &right, signed, |l, r, signed| {
+        let (l_min, l_max) = l.range(signed);
+        let (r_min, r_max) = r.range(signed);
+
+        if l_min >= r_max {
+            Some(true)
+        } else if l_max < r_min {
+            Some(false)
+        } else {
+            None
+        }
+    })
 }
 
 fn less_values(
@@ -599,51 +488,18 @@ fn less_values(
     let left = expression_values(left, vars, ns);
     let right = expression_values(right, vars, ns);
 
-    left.iter()
-        .cartesian_product(right.iter())
-        .map(|(l, r)| {
-            // is l less than r
-            let mut known_bits = BitArray::new([0u8; 32]);
-            let mut value = BitArray::new([0u8; 32]);
+    compare_values(&left, &right, signed, |l, r, signed| {
+        let (l_min, l_max) = l.range(signed);
+        let (r_min, r_max) = r.range(signed);
 
-            let is_true = if signed {
-                BigInt::from_signed_bytes_le(&l.get_signed_max_value().into_inner())
-                    < BigInt::from_signed_bytes_le(&r.get_signed_min_value().into_inner())
-            } else {
-                BigInt::from_bytes_le(Sign::Plus, &l.get_unsigned_max_value().into_inner())
-                    < BigInt::from_bytes_le(Sign::Plus, &r.get_unsigned_min_value().into_inner())
-            };
-
-            if is_true {
-                // we know that this comparison is always true
-                known_bits.set(0, true);
-                value.set(0, true);
-            } else {
-                // maybe the comparison is always false
-                let is_false = if signed {
-                    BigInt::from_signed_bytes_le(&l.get_signed_min_value().into_inner())
-                        >= BigInt::from_signed_bytes_le(&r.get_signed_max_value().into_inner())
-                } else {
-                    BigInt::from_bytes_le(Sign::Plus, &l.get_unsigned_min_value().into_inner())
-                        >= BigInt::from_bytes_le(
-                            Sign::Plus,
-                            &r.get_unsigned_max_value().into_inner(),
-                        )
-                };
-
-                if is_false {
-                    // we know that this comparison is always false
-                    known_bits.set(0, true);
-                }
-            }
-
-            Value {
-                value,
-                known_bits,
-                bits: 1,
-            }
-        })
-        .collect()
+        if l_max < r_min {
+            Some(true)
+        } else if l_min >= r_max {
+            Some(false)
+        } else {
+            None
+        }
+    })
 }
 
 fn less_equal_values(
@@ -656,51 +512,35 @@ fn less_equal_values(
     let left = expression_values(left, vars, ns);
     let right = expression_values(right, vars, ns);
 
-    left.iter()
-        .cartesian_product(right.iter())
-        .map(|(l, r)| {
-            // is l less than r
-            let mut known_bits = BitArray::new([0u8; 32]);
-            let mut value = BitArray::new([0u8; 32]);
+    compare_values(&left, &right, signed, |l, r, signed| {
+        let (l_min, l_max) = l.range(signed);
+        let (r_min, r_max) = r.range(signed);
 
-            let is_true = if signed {
-                BigInt::from_signed_bytes_le(&l.get_signed_max_value().into_inner())
-                    <= BigInt::from_signed_bytes_le(&r.get_signed_min_value().into_inner())
-            } else {
-                BigInt::from_bytes_le(Sign::Plus, &l.get_unsigned_max_value().into_inner())
-                    <= BigInt::from_bytes_le(Sign::Plus, &r.get_unsigned_min_value().into_inner())
-            };
+        if l_max <= r_min {
+            Some(true)
+        } else if l_min > r_max {
+            Some(false)
+        } else {
+            None
+        }
+    })
+}
 
-            if is_true {
-                // we know that this comparison is always true
-                known_bits.set(0, true);
-                value.set(0, true);
-            } else {
-                // maybe the comparison is always false
-                let is_false = if signed {
-                    BigInt::from_signed_bytes_le(&l.get_signed_min_value().into_inner())
-                        > BigInt::from_signed_bytes_le(&r.get_signed_max_value().into_inner())
-                } else {
-                    BigInt::from_bytes_le(Sign::Plus, &l.get_unsigned_min_value().into_inner())
-                        > BigInt::from_bytes_le(
-                            Sign::Plus,
-                            &r.get_unsigned_max_value().into_inner(),
-                        )
-                };
-
-                if is_false {
-                    // we know that this comparison is always false
-                    known_bits.set(0, true);
-                }
-            }
+/// Are the two values equal. They are not if their ranges do not overlap, or some bit known in
+/// both differs
+fn values_equal(l: &Value, r: &Value, signed: bool) -> Option<bool> {
+    let (l_min, l_max) = l.range(signed);
+    let (r_min, r_max) = r.range(signed);
 
-            Value {
-                value,
-                known_bits,
-                bits: 1,
-            }
-        })
-        .collect()
+    let bits_differ = (l.known_bits & r.known_bits & (l.value ^ r.value))[0..l.bits].any();
+
+    if l_max < r_min || r_max < l_min || bits_differ {
+        Some(false)
+    } else if l.all_known() && r.all_known() {
+        Some(true)
+    } else {
+        None
+    }
 }
 
 fn equal_values(
@@ -712,36 +552,12 @@ fn equal_values(
     let left = expression_values(left_expr, vars, ns);
     let right = expression_values(right_expr, vars, ns);
 
-    left.iter()
-        .cartesian_product(right.iter())
-        .map(|(l, r)| {
-            let mut known_bits = BitArray::new([0u8; 32]);
-            let mut value = BitArray::new([0u8; 32]);
-
-            let could_be_equal = if left_expr.ty().is_signed_int(ns) {
-                BigInt::from_signed_bytes_le(&l.get_signed_min_value().into_inner())
-                    >= BigInt::from_signed_bytes_le(&r.get_signed_max_value().into_inner())
-                    && BigInt::from_signed_bytes_le(&l.get_signed_min_value().into_inner())
-                        <= BigInt::from_signed_bytes_le(&r.get_signed_max_value().into_inner())
-            } else {
-                BigInt::from_signed_bytes_le(&l.get_unsigned_min_value().into_inner())
-                    >= BigInt::from_signed_bytes_le(&r.get_unsigned_max_value().into_inner())
-                    && BigInt::from_signed_bytes_le(&l.get_unsigned_min_value().into_inner())
-                        <= BigInt::from_signed_bytes_le(&r.get_unsigned_max_value().into_inner())
-            };
-
-            if !could_be_equal || l.all_known() && r.all_known() {
-                known_bits.set(0, true);
-                value.set(0, could_be_equal);
-            }
-
-            Value {
-                value,
-                known_bits,
-                bits: 1,
-            }
-        })
-        .collect()
+    compare_values(
+        &left,
+        &right,
+        left_expr.ty().is_signed_int(ns),
+        values_equal,
+    )
 }
 
 fn not_equal_values(
@@ -753,36 +569,12 @@ fn not_equal_values(
     let left = expression_values(left_expr, vars, ns);
     let right = expression_values(right_expr, vars, ns);
 
-    left.iter()
-        .cartesian_product(right.iter())
-        .map(|(l, r)| {
-            let mut known_bits = BitArray::new([0u8; 32]);
-            let mut value = BitArray::new([0u8; 32]);
-
-            let could_be_equal = if left_expr.ty().is_signed_int(ns) {
-                BigInt::from_signed_bytes_le(&l.get_signed_min_value().into_inner())
-                    >= BigInt::from_signed_bytes_le(&r.get_signed_max_value().into_inner())
-                    && BigInt::from_signed_bytes_le(&l.get_signed_min_value().into_inner())
-                        <= BigInt::from_signed_bytes_le(&r.get_signed_max_value().into_inner())
-            } else {
-                BigInt::from_signed_bytes_le(&l.get_unsigned_min_value().into_inner())
-                    >= BigInt::from_signed_bytes_le(&r.get_unsigned_max_value().into_inner())
-                    && BigInt::from_signed_bytes_le(&l.get_unsigned_min_value().into_inner())
-                        <= BigInt::from_signed_bytes_le(&r.get_unsigned_max_value().into_inner())
-            };
-
-            if !could_be_equal || l.all_known() && r.all_known() {
-                known_bits.set(0, true);
-                value.set(0, !could_be_equal);
-            }
-
-            Value {
-                value,
-                known_bits,
-                bits: 1,
-            }
-        })
-        .collect()
+    compare_values(
+        &left,
+        &right,
+        left_expr.ty().is_signed_int(ns),
+        |l, r, signed| values_equal(l, r, signed).map(|equal| !equal),
+    )
 }
 
 fn not_values(expr: &Expression, vars: &Variables, ns: &Namespace) -> HashSet<Value> {
@@ -806,18 +598,18 @@ fn complement_values(expr: &Expression, vars: &Variables, ns: &Namespace) -> Has
     vals.into_iter()
         .map(|mut v| {
             // just invert the known bits
-            let cmpl = !v.value & v.known_bits;
-            v.value &= v.known_bits;
-            v.value |= cmpl;
+            v.value = !v.value & v.known_bits;
+            v.value[v.bits..].fill(false);
             v
         })
         .collect()
 }
 
-fn variable_values(var_no: usize, vars: &Variables) -> HashSet<Value> {
+fn variable_values(ty: &Type, var_no: usize, vars: &Variables, ns: &Namespace) -> HashSet<Value> {
     if let Some(v) = vars.get(&var_no) {
         v.clone()
     } else {
-        HashSet::new()
+        // a variable which was set by an instruction we do not follow; nothing is known about it
+        unknown_values(ty, ns)
     }
 }
@@ -15,13 +15,15 @@ use num_traits::{One, ToPrimitive};
 use reaching_values::{reaching_values, transfer};
 use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
-use value::{get_max_signed, get_max_unsigned, is_single_constant, Value};
+use value::{get_max_signed, get_max_unsigned, get_min_unsigned, is_single_constant, Value};
 
 /**
   Strength Reduce optimization pass - replace expensive arithmetic operations with cheaper ones
 
   Currently implemented:
   - Replace 256/128 bit multiply/divide/modulo with smaller width operations
+  - Remove overflow checks from add/subtract/multiply which can never overflow
+  - Remove branches whose condition is known, like array bounds checks on an index which is in range
 
 */
 
@@ -68,7 +70,7 @@ use value::{get_max_signed, get_max_unsigned, is_single_constant, Value};
   TODO/ideas to explore
   - In the first example above, the variable i can be replaced with a 64 bit. Check each assignment to i
     and check if the result fits into 64 bit
-  - Conditions like "if (i < 100) { ... }" are not used to know the bounds of i
+  - Conditions like "if (i < 100) { ... }" are only used to know the upper bound of unsigned i
   - The pass does not work across function calls
   - Can we replace Expression::Power() with a cheaper one
   - Can we replace Expression::BitwiseAnd() with a cheaper one if either side fits into u64
@@ -207,6 +209,29 @@ fn block_reduce(
             Instr::WriteBuffer { offset, .. } => {
                 *offset = expression_reduce(offset, &vars, ns);
             }
+            Instr::BranchCond {
+                cond,
+                true_block,
+                false_block,
+            } => {
+                // If the condition is known, e.g. an array bounds check where the index is
+                // always in range, then the branch is not needed
+                let v = expression_values(cond, &vars, ns);
+
+                if v.len() == 1 {
+                    let v = v.iter().next().unwrap();
+
+                    if v.known_bits[0] {
+                        *instr = Instr::Branch {
+                            block: if v.value[0] {
+                                *true_block
+                            } else {
+                                *false_block
+                            },
+                        };
+                    }
+                }
+            }
             _ => (),
         }
 
@@ -218,6 +243,46 @@ fn block_reduce(
 fn expression_reduce(expr: &Expression, vars: &Variables, ns: &mut Namespace) -> Expression {
     let filter = |expr: &Expression, ns: &mut Namespace| -> Expression {
         match expr {
+            Expression::Add {
+                loc,
+                ty,
+                overflowing: false,
+                left,
+                right,
+            } if overflow_impossible(expr, vars, ns) => {
+                ns.hover_overrides.insert(
+                    *loc,
+                    format!("{} add overflow check removed", ty.to_string(ns)),
+                );
+
+                Expression::Add {
+                    loc: *loc,
+                    ty: ty.clone(),
+                    overflowing: true,
+                    left: left.clone(),
+                    right: right.clone(),
+                }
+            }
+            Expression::Subtract {
+                loc,
+                ty,
+                overflowing: false,
+                left,
+                right,
+            } if overflow_impossible(expr, vars, ns) => {
+                ns.hover_overrides.insert(
+                    *loc,
+                    format!("{} subtract overflow check removed", ty.to_string(ns)),
+                );
+
+                Expression::Subtract {
+                    loc: *loc,
+                    ty: ty.clone(),
+                    overflowing: true,
+                    left: left.clone(),
+                    right: right.clone(),
+                }
+            }
             Expression::Multiply {
                 loc,
                 ty,
@@ -225,6 +290,17 @@ fn expression_reduce(expr: &Expression, vars: &Variables, ns: &mut Namespace) ->
                 left,
                 right,
             } => {
+                let overflowing = if !*overflowing && overflow_impossible(expr, vars, ns) {
+                    ns.hover_overrides.insert(
+                        *loc,
+                        format!("{} multiply overflow check removed", ty.to_string(ns)),
+                    );
+
+                    &true
+                } else {
+                    overflowing
+                };
+
                 let bits = ty.bits(ns) as usize;
                 if bits >= 128 {
                     let left_values = expression_values(left, vars, ns);
@@ -329,7 +405,13 @@ fn expression_reduce(expr: &Expression, vars: &Variables, ns: &mut Namespace) ->
                     }
                 }
 
-                expr.clone()
+                Expression::Multiply {
+                    loc: *loc,
+                    ty: ty.clone(),
+                    overflowing: *overflowing,
+                    left: left.clone(),
+                    right: right.clone(),
+                }
             }
             Expression::UnsignedDivide {
                 loc,
@@ -558,6 +640,61 @@ fn expression_reduce(expr: &Expression, vars: &Variables, ns: &mut Namespace) ->
     expr.copy_filter(ns, filter)
 }
 
+/// Using the values of the operands, can we prove that the checked add, subtract or multiply never
+/// overflows. If so, the overflow check can be removed.
+fn overflow_impossible(expr: &Expression, vars: &Variables, ns: &Namespace) -> bool {
+    let (ty, left, right) = match expr {
+        Expression::Add {
+            ty, left, right, ..
+        }
+        | Expression::Subtract {
+            ty, left, right, ..
+        }
+        | Expression::Multiply {
+            ty, left, right, ..
+        } => (ty, left, right),
+        _ => return false,
+    };
+
+    let left = expression_values(left, vars, ns);
+    let right = expression_values(right, vars, ns);
+
+    if left.is_empty() || right.is_empty() {
+        return false;
+    }
+
+    let bits = ty.bits(ns) as u64;
+
+    let type_max = if ty.is_signed_int(ns) {
+        // only non-negative signed values are handled
+        if !left
+            .iter()
+            .chain(right.iter())
+            .all(|v| v.sign() == (true, false))
+        {
+            return false;
+        }
+
+        (BigInt::one() << (bits - 1)) - 1
+    } else {
+        (BigInt::one() << bits) - 1
+    };
+
+    let left_max = get_max_unsigned(&left);
+    let right_max = get_max_unsigned(&right);
+
+    match expr {
+        Expression::Add { .. } => left_max + right_max <= type_max,
+        Expression::Subtract { .. } if ty.is_signed_int(ns) => {
+            // the difference between two non-negative values always fits
+            left_max <= type_max && right_max <= type_max
+        }
+        Expression::Subtract { .. } => get_min_unsigned(&left)
+            .is_some_and(|left_min| left_min >= right_max && left_max <= type_max),
+        _ => left_max * right_max <= type_max,
+    }
+}
+
 /// This optimization pass only tracks bools and integers variables.
 /// Other types (e.g. bytes) is not relevant for strength reduce. Bools are only
 /// tracked so we can following branching after integer compare.
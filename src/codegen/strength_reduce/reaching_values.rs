@@ -1,10 +1,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use super::expression_values::expression_values;
-use super::value::Value;
+use super::expression_values::{expression_values, unknown_values};
+use super::value::{get_max_unsigned, Value};
 use super::{track, Variables, MAX_VALUES};
 use crate::codegen::cfg::{ControlFlowGraph, Instr};
-use crate::sema::ast::Namespace;
+use crate::codegen::Expression;
+use crate::sema::ast::{Namespace, Type};
+use num_traits::Zero;
 use std::collections::{HashMap, HashSet};
 
 /// Step through a block, and calculate the reaching values for all the variables
@@ -24,8 +26,12 @@ pub(super) fn reaching_values(
     if let Some(map) = block_vars.get_mut(&block_no) {
         let mut changes = false;
 
-        for (var_no, set) in vars.iter() {
-            changes |= update_map(*var_no, set, map);
+        let phis = cfg.blocks[block_no].phis.as_ref();
+
+        for (var_no, set) in vars.iter_mut() {
+            let widen = phis.is_some_and(|phis| phis.contains(var_no));
+
+            changes |= update_map(*var_no, set, map, widen);
         }
 
         if !changes {
@@ -73,11 +79,16 @@ pub(super) fn reaching_values(
                     }
                 }
 
-                // we don't know the value of the condition. Follow both paths
+                // we don't know the value of the condition. Follow both paths, with the values
+                // of any variable compared against a bound narrowed for that path
                 let mut vars_copy = vars.clone();
 
+                narrow(cond, true, &mut vars_copy, ns);
+
                 reaching_values(*true_block, cfg, &mut vars_copy, block_vars, ns);
 
+                narrow(cond, false, vars, ns);
+
                 reaching_values(*false_block, cfg, vars, block_vars, ns);
             }
             _ => (),
@@ -85,54 +96,198 @@ pub(super) fn reaching_values(
     }
 }
 
+/// If the condition of a branch compares an unsigned variable with some expression, then on the
+/// path taken the variable cannot exceed the maximum value of that expression. For example, in the
+/// body of `for (uint i = 0; i < a.length; i++)` we know that `i` fits into 32 bits.
+fn narrow(cond: &Expression, taken: bool, vars: &mut Variables, ns: &Namespace) {
+    let (var, bound, inclusive) = match (cond, taken) {
+        (Expression::Not { expr, .. }, _) => {
+            return narrow(expr, !taken, vars, ns);
+        }
+        // var < bound
+        (
+            Expression::Less {
+                signed: false,
+                left,
+                right,
+                ..
+            },
+            true,
+        )
+        | (
+            Expression::MoreEqual {
+                signed: false,
+                left,
+                right,
+                ..
+            },
+            false,
+        ) => (left, right, false),
+        // var <= bound
+        (
+            Expression::LessEqual {
+                signed: false,
+                left,
+                right,
+                ..
+            },
+            true,
+        )
+        | (
+            Expression::More {
+                signed: false,
+                left,
+                right,
+                ..
+            },
+            false,
+        ) => (left, right, true),
+        // bound > var
+        (
+            Expression::More {
+                signed: false,
+                left,
+                right,
+                ..
+            },
+            true,
+        )
+        | (
+            Expression::LessEqual {
+                signed: false,
+                left,
+                right,
+                ..
+            },
+            false,
+        ) => (right, left, false),
+        // bound >= var
+        (
+            Expression::MoreEqual {
+                signed: false,
+                left,
+                right,
+                ..
+            },
+            true,
+        )
+        | (
+            Expression::Less {
+                signed: false,
+                left,
+                right,
+                ..
+            },
+            false,
+        ) => (right, left, true),
+        _ => return,
+    };
+
+    let Expression::Variable { var_no, .. } = var.as_ref() else {
+        return;
+    };
+
+    let bound = expression_values(bound, vars, ns);
+
+    if bound.is_empty() {
+        return;
+    }
+
+    let mut max = get_max_unsigned(&bound);
+
+    if !inclusive {
+        if max.is_zero() {
+            return;
+        }
+
+        max -= 1;
+    }
+
+    if let Some(set) = vars.get(var_no) {
+        let narrowed: HashSet<Value> = set.iter().filter_map(|v| v.at_most(&max)).collect();
+
+        // if nothing is left then this path cannot be taken, but leave that to other passes
+        if !narrowed.is_empty() {
+            vars.insert(*var_no, narrowed);
+        }
+    }
+}
+
 /// Update the Variable's map based on the incoming set of values. Returns true if there was any
-/// changes in the set.
+/// changes in the set. Any incoming value which is covered by a wider value in the map (e.g. after
+/// the values were folded into a joined value) is replaced with that wider value, else the blocks
+/// which follow would never see it. If widen is set, values which are joined a second time are
+/// widened.
 /// There is a discussion to improve this function: https://github.com/hyperledger/solang/issues/934
-fn update_map(var_no: usize, set: &HashSet<Value>, map: &mut Variables) -> bool {
+fn update_map(var_no: usize, set: &mut HashSet<Value>, map: &mut Variables, widen: bool) -> bool {
     return if let Some(existing) = map.get_mut(&var_no) {
         if existing.iter().next().map_or(false, |v| v.all_unknown()) {
             // If we already think it is unknown, nothing can improve on that
+            *set = existing.clone();
             false
         } else if let Some(v) = set.iter().find(|v| v.all_unknown()) {
             // If we are merging an unknown value, set the entire value set to unknown
-            let mut set = HashSet::new();
+            let mut unknown = HashSet::new();
 
-            set.insert(v.clone());
+            unknown.insert(v.clone());
 
-            map.insert(var_no, set);
+            *set = unknown.clone();
+            map.insert(var_no, unknown);
             true
         } else {
             let mut changes = false;
-            for v in set {
-                if !existing.contains(v) {
+            let mut covered = Vec::new();
+
+            for v in set.iter() {
+                if existing.contains(v) {
+                    continue;
+                }
+
+                if let Some(e) = existing.iter().find(|e| e.subsumes(v)) {
+                    covered.push((v.clone(), e.clone()));
+                } else {
                     existing.insert(v.clone());
                     changes = true;
                 }
             }
 
-            if existing.len() > MAX_VALUES {
-                let bits = existing.iter().next().unwrap().bits;
+            for (v, e) in covered {
+                set.remove(&v);
+                set.insert(e);
+            }
 
-                let mut set = HashSet::new();
+            // Where the variable is merged, values which are not fully known and keep growing
+            // (e.g. a signed loop counter) would only lose one known bit per round, so widen
+            // them straight away
+            let growing = widen && changes && existing.iter().any(|v| !v.all_known());
 
-                set.insert(Value::unknown(bits));
+            if existing.len() > MAX_VALUES || growing {
+                // Too many values; replace them with a single value which includes them all
+                let mut joined = HashSet::new();
+
+                if growing {
+                    joined.insert(Value::join(existing.iter()).widen());
+                } else {
+                    joined.insert(Value::join(existing.iter()));
+                }
 
                 changes = true;
-                map.insert(var_no, set);
+                *set = joined.clone();
+                map.insert(var_no, joined);
             }
+
             changes
         }
     } else {
         // We have no existing set. Create one but folding unknown
 
         if set.len() > MAX_VALUES || set.iter().any(|v| v.all_unknown()) {
-            let bits = set.iter().next().unwrap().bits;
-
-            let mut set = HashSet::new();
+            let mut joined = HashSet::new();
 
-            set.insert(Value::unknown(bits));
+            joined.insert(Value::join(set.iter()));
 
-            map.insert(var_no, set);
+            *set = joined.clone();
+            map.insert(var_no, joined);
         } else {
             map.insert(var_no, set.clone());
         }
@@ -173,6 +328,23 @@ pub(super) fn transfer(instr: &Instr, vars: &mut Variables, ns: &Namespace) {
 
             vars.insert(*res, set);
         }
+        Instr::LoadStorage { res, ty, .. } => {
+            vars.insert(*res, unknown_values(ty, ns));
+        }
+        Instr::Constructor {
+            success: Some(success),
+            ..
+        }
+        | Instr::ExternalCall {
+            success: Some(success),
+            ..
+        }
+        | Instr::ValueTransfer {
+            success: Some(success),
+            ..
+        } => {
+            vars.insert(*success, unknown_values(&Type::Bool, ns));
+        }
         Instr::PopMemory { res, ty, .. } => {
             if track(ty) {
                 let mut set = HashSet::new();
@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use super::Bits;
+use super::{highest_set_bit, Bits};
 use bitvec::prelude::BitArray;
 use bitvec::prelude::Lsb0;
 use num_bigint::{BigInt, Sign};
@@ -8,8 +8,9 @@ use num_traits::Signed;
 use num_traits::Zero;
 use std::collections::HashSet;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 
-#[derive(Hash, Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Eq)]
 pub(super) struct Value {
     // which bits are known
     pub(super) known_bits: BitArray<[u8; 32], Lsb0>,
@@ -19,6 +20,24 @@ pub(super) struct Value {
     pub(super) bits: usize,
 }
 
+// Values are hashed and compared very often while calculating the reaching values, so do this on
+// the bytes rather than bit by bit
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        self.bits == other.bits
+            && self.known_bits.as_raw_slice() == other.known_bits.as_raw_slice()
+            && self.value.as_raw_slice() == other.value.as_raw_slice()
+    }
+}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.known_bits.as_raw_slice().hash(state);
+        self.value.as_raw_slice().hash(state);
+        self.bits.hash(state);
+    }
+}
+
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         if self.all_known() {
@@ -104,15 +123,26 @@ pub(super) fn get_max_unsigned(set: &HashSet<Value>) -> BigInt {
     m
 }
 
+/// Get the minimum unsigned value in a set
+pub(super) fn get_min_unsigned(set: &HashSet<Value>) -> Option<BigInt> {
+    set.iter()
+        .map(|v| BigInt::from_bytes_le(Sign::Plus, &v.get_unsigned_min_value().into_inner()))
+        .min()
+}
+
 impl Value {
     /// Calculate the unsigned min value. Higher bits than the type are 0
     pub(super) fn get_unsigned_min_value(&self) -> Bits {
-        self.value & self.known_bits
+        let mut v = self.value & self.known_bits;
+        v[self.bits..].fill(false);
+        v
     }
 
     /// Calculate the unsigned max value. Higher bits than the type are 0
     pub(super) fn get_unsigned_max_value(&self) -> Bits {
-        (BitArray::new([!0u8; 32]) & !self.known_bits) | self.value
+        let mut v = (BitArray::new([!0u8; 32]) & !self.known_bits) | (self.value & self.known_bits);
+        v[self.bits..].fill(false);
+        v
     }
 
     /// Return whether the sign is known and what value it is
@@ -129,32 +159,111 @@ impl Value {
             (false, _) => false,
         };
 
-        if !negative {
-            // we know the value is positive; same as unsigned
-            self.get_unsigned_max_value()
-        } else {
-            // the value might be negative. So, we want to know which bits are zero
-            let mut v = self.get_unsigned_min_value();
-            v[self.bits - 1..].fill(true);
-            v
-        }
+        // all the unknown bits are 1, and the value is positive if it can be
+        let mut v = self.get_unsigned_max_value();
+        v[self.bits - 1..].fill(negative);
+        v
     }
 
+    /// Calculate the signed min value
     pub(super) fn get_signed_min_value(&self) -> Bits {
         let negative = match self.sign() {
             (true, sign) => sign,
             (false, _) => true,
         };
 
-        if !negative {
-            // we know the value is positive; same as unsigned
-            self.get_unsigned_min_value()
+        // all the unknown bits are 0, and the value is negative if it can be
+        let mut v = self.get_unsigned_min_value();
+        v[self.bits - 1..].fill(negative);
+        v
+    }
+
+    /// The smallest and largest possible value, as either signed or unsigned
+    pub(super) fn range(&self, signed: bool) -> (BigInt, BigInt) {
+        if signed {
+            (
+                BigInt::from_signed_bytes_le(&self.get_signed_min_value().into_inner()),
+                BigInt::from_signed_bytes_le(&self.get_signed_max_value().into_inner()),
+            )
         } else {
-            // the value might be negative. So, we want to know which bits are zero
-            let mut v = self.get_unsigned_max_value();
-            v[self.bits - 1..].fill(true);
-            v
+            (
+                BigInt::from_bytes_le(Sign::Plus, &self.get_unsigned_min_value().into_inner()),
+                BigInt::from_bytes_le(Sign::Plus, &self.get_unsigned_max_value().into_inner()),
+            )
+        }
+    }
+
+    /// Narrow the value to those possible values which are not more than max. If none of
+    /// them are, None is returned.
+    pub(super) fn at_most(&self, max: &BigInt) -> Option<Value> {
+        let min = BigInt::from_bytes_le(Sign::Plus, &self.get_unsigned_min_value().into_inner());
+
+        if &min > max {
+            return None;
+        }
+
+        // any bits higher than the highest bit of max must be zero
+        let high = highest_set_bit(&max.to_bytes_le().1) + 1;
+
+        let mut v = self.clone();
+
+        if high < self.bits {
+            v.known_bits[high..self.bits].fill(true);
+            v.value[high..self.bits].fill(false);
         }
+
+        Some(v)
+    }
+
+    /// Does this value include all the possible values of other
+    pub(super) fn subsumes(&self, other: &Value) -> bool {
+        // every bit we know must be known in other, and have the same value. This is called
+        // for every incoming value, so work on the bytes rather than the bit array.
+        let known = self.known_bits.as_raw_slice();
+        let other_known = other.known_bits.as_raw_slice();
+        let value = self.value.as_raw_slice();
+        let other_value = other.value.as_raw_slice();
+
+        (0..self.bits.div_ceil(8)).all(|i| {
+            let mut differ = known[i] & !(other_known[i] & !(value[i] ^ other_value[i]));
+
+            if self.bits < (i + 1) * 8 {
+                differ &= (1u8 << (self.bits - i * 8)) - 1;
+            }
+
+            differ == 0
+        })
+    }
+
+    /// A single value which includes all the possible values of the given values: only the
+    /// bits which are known and the same in all of them are known
+    pub(super) fn join<'a>(mut values: impl Iterator<Item = &'a Value>) -> Value {
+        let mut res = values.next().unwrap().clone();
+
+        for v in values {
+            res.known_bits &= v.known_bits & !(res.value ^ v.value);
+        }
+
+        res.value &= res.known_bits;
+
+        res
+    }
+
+    /// Make the value include many more values, so that a value which keeps growing converges
+    /// quickly: the number of unknown low bits is doubled
+    pub(super) fn widen(&self) -> Value {
+        let Some(highest) = self.known_bits[0..self.bits].last_zero() else {
+            return self.clone();
+        };
+
+        let unknown = ((highest + 1) * 2).min(self.bits);
+
+        let mut res = self.clone();
+
+        res.known_bits[0..unknown].fill(false);
+        res.value[0..unknown].fill(false);
+
+        res
     }
 
     pub(super) fn all_known(&self) -> bool {
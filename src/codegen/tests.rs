@@ -121,3 +121,55 @@ fn test_builtin_conversion() {
         assert_eq!(codegen::Builtin::from(item), output[i]);
     }
 }
+
+#[test]
+fn mapping_storage_references() {
+    use crate::{sema::tests::resolve_and_codegen, Target};
+
+    let src = r#"
+        struct Inner {
+            uint64 v;
+            mapping(uint64 => uint64) m;
+        }
+
+        struct Outer {
+            mapping(uint64 => Inner) inner;
+            uint64 count;
+        }
+
+        library Lib {
+            function set(mapping(uint64 => Inner) storage m, uint64 k, uint64 v) internal {
+                m[k].v = v;
+                m[k].m[k] = v;
+            }
+
+            function pick(Outer storage o) internal view returns (mapping(uint64 => Inner) storage) {
+                return o.inner;
+            }
+        }
+
+        contract c {
+            mapping(uint64 => uint64) a;
+            mapping(uint64 => uint64) b;
+            Outer outer;
+
+            function sel(bool first) internal view returns (mapping(uint64 => uint64) storage) {
+                return first ? a : b;
+            }
+
+            function write(bool first, uint64 k, uint64 v) public {
+                sel(first)[k] = v;
+                Lib.set(outer.inner, k, v);
+            }
+
+            function read(uint64 k) public view returns (uint64) {
+                return Lib.pick(outer)[k].m[k];
+            }
+        }"#;
+
+    for target in [Target::default_polkadot(), Target::Solana, Target::EVM] {
+        let ns = resolve_and_codegen(src, target, &codegen::Options::default());
+
+        assert!(!ns.diagnostics.any_errors(), "{:?}", ns.diagnostics);
+    }
+}
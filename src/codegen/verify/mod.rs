@@ -0,0 +1,484 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Prove that the runtime checks of a contract never fail: asserts, arithmetic overflow,
+//! division by zero and array bounds. Each public function is translated to
+//! [SMT-LIB](https://smt-lib.org/), and a solver like [z3](https://github.com/Z3Prover/z3) is
+//! asked whether a failure can be reached. If it can, the solver gives the arguments and
+//! contract storage for which it happens.
+//!
+//! Contract invariants are given with `@custom:invariant` doc comment tags on the contract. An
+//! invariant may be assumed when a public function is called, and must hold when it returns.
+//! The constructor must establish it.
+
+mod smt;
+mod solver;
+mod tests;
+
+pub use solver::Solver;
+
+use super::cfg::{ASTFunction, ControlFlowGraph, Instr};
+use super::expression::expression;
+use super::vartable::Vartable;
+use super::Options;
+use crate::sema::ast::{Diagnostic, Function, Mutability, Namespace, Note, Tag, Type};
+use crate::sema::diagnostics::Diagnostics;
+use crate::sema::expression::resolve_expression;
+use crate::sema::expression::{ExprContext, ResolveTo};
+use crate::sema::symtable::Symtable;
+use num_bigint::BigInt;
+use num_traits::Num;
+use smt::{Encoder, Sort};
+use solang_parser::pt::{self, FunctionTy, Loc};
+use solver::Answer;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Property {
+    Assert,
+    Overflow,
+    DivisionByZero,
+    Bounds,
+    /// The invariant does not hold when the function returns. This is the position of its tag
+    /// among the invariants of the contract.
+    Invariant(usize),
+}
+
+impl Property {
+    fn description(&self) -> &'static str {
+        match self {
+            Property::Assert => "assertion failure",
+            Property::Overflow => "arithmetic overflow",
+            Property::DivisionByZero => "division by zero",
+            Property::Bounds => "array index out of bounds",
+            Property::Invariant(_) => "invariant violation",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Holds,
+    /// The property does not hold, for example with these values for the arguments and storage
+    Fails(Vec<(String, String)>),
+    /// The solver could not decide, e.g. because it ran out of time
+    Unknown,
+}
+
+/// The result of checking a property
+#[derive(Debug)]
+pub struct Verdict {
+    pub property: Property,
+    pub loc: Loc,
+    /// The public function through which the property was checked, or `None` for the default
+    /// constructor
+    pub function_no: Option<usize>,
+    pub outcome: Outcome,
+}
+
+/// The SMT-LIB script which checks the properties of a single function
+pub struct Script {
+    pub function_no: Option<usize>,
+    pub text: String,
+    checks: Vec<smt::Check>,
+    /// The names of the values of a counterexample, and their type
+    model: Vec<(String, Type)>,
+}
+
+impl Script {
+    /// Read the answer of the solver to each check
+    pub fn verdicts(&self, output: &str, ns: &Namespace) -> Vec<Verdict> {
+        let mut answers = solver::answers(output);
+
+        self.checks
+            .iter()
+            .enumerate()
+            .map(|(no, check)| {
+                let outcome = match answers.remove(&no) {
+                    Some(Answer::Unsat) => Outcome::Holds,
+                    Some(Answer::Sat(values)) => Outcome::Fails(
+                        self.model
+                            .iter()
+                            .zip(values)
+                            .map(|((name, ty), (_, value))| {
+                                (name.clone(), display_value(&value, ty, ns))
+                            })
+                            .collect(),
+                    ),
+                    _ => Outcome::Unknown,
+                };
+
+                Verdict {
+                    property: check.property,
+                    loc: check.loc,
+                    function_no: self.function_no,
+                    outcome,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Check the properties of every public function of the contract
+pub fn verify(
+    contract_no: usize,
+    ns: &mut Namespace,
+    opt: &Options,
+    solver: &Solver,
+) -> Result<Vec<Verdict>, String> {
+    let mut verdicts: Vec<Verdict> = Vec::new();
+
+    for script in scripts(contract_no, ns, opt) {
+        let output = solver.run(&script.text)?;
+
+        for verdict in script.verdicts(&output, ns) {
+            // a check in an internal function is done for every function which calls it; it
+            // only holds if it holds for all of them
+            if let Some(existing) = verdicts.iter_mut().find(|existing| {
+                existing.property == verdict.property && existing.loc == verdict.loc
+            }) {
+                match (&existing.outcome, &verdict.outcome) {
+                    (Outcome::Fails(_), _) | (_, Outcome::Holds) => (),
+                    (Outcome::Unknown, Outcome::Unknown) => (),
+                    _ => *existing = verdict,
+                }
+            } else {
+                verdicts.push(verdict);
+            }
+        }
+    }
+
+    Ok(verdicts)
+}
+
+/// Create the SMT-LIB scripts for the public functions of the contract. The contract must
+/// have been compiled with runtime errors logged, so that the checks can be recognized.
+pub fn scripts(contract_no: usize, ns: &mut Namespace, opt: &Options) -> Vec<Script> {
+    let invariants = invariants(contract_no, ns, opt);
+    let ns = &*ns;
+    let contract = &ns.contracts[contract_no];
+    let mut scripts = Vec::new();
+
+    for (cfg_no, cfg) in contract.cfg.iter().enumerate() {
+        if cfg.is_placeholder() || !(cfg.public || cfg.ty == FunctionTy::Constructor) {
+            continue;
+        }
+
+        let function_no = match cfg.function_no {
+            ASTFunction::SolidityFunction(function_no) => Some(function_no),
+            // the default constructor must establish the invariants too
+            ASTFunction::None if matches!(contract.default_constructor, Some((_, no)) if no == cfg_no) => {
+                None
+            }
+            _ => continue,
+        };
+
+        let func = function(function_no, contract_no, ns);
+        let mut encoder = Encoder::new(contract, ns);
+        let mut model = Vec::new();
+
+        let args = cfg
+            .params
+            .iter()
+            .enumerate()
+            .map(|(no, param)| {
+                let name = param
+                    .id
+                    .as_ref()
+                    .map(|id| id.name.clone())
+                    .unwrap_or_else(|| format!("arg{no}"));
+
+                let term = encoder.declare(&name, Sort::of(&param.ty, ns));
+
+                if term.sort != Sort::Opaque {
+                    encoder.model.push(term.text.clone());
+                    model.push((name, param.ty.clone()));
+                }
+
+                term
+            })
+            .collect();
+
+        let constructor = cfg.ty == FunctionTy::Constructor;
+
+        let storage = if constructor {
+            // the storage is empty, until the state variables are initialized
+            let empty = encoder.empty_storage();
+
+            match contract.initializer {
+                Some(cfg_no) => {
+                    let exits =
+                        encoder.run(&contract.cfg[cfg_no], "true", empty, Vec::new(), false);
+
+                    encoder.merge_exits(exits, &[]).1
+                }
+                None => empty,
+            }
+        } else {
+            let storage = encoder.declare_storage();
+
+            for layout in &contract.layout {
+                let var = &ns.contracts[layout.contract_no].variables[layout.var_no];
+                let sort = Sort::of(&layout.ty, ns);
+
+                if layout.packed.is_some() || !matches!(sort, Sort::Bool | Sort::BitVec(..=256)) {
+                    continue;
+                }
+
+                let term = encoder.declare(&var.name, sort);
+                let cell = match sort {
+                    Sort::Bool => format!("(ite {} (_ bv1 256) (_ bv0 256))", term.text),
+                    _ => format!("((_ zero_extend {}) {})", 256 - sort.width(), term.text),
+                };
+
+                encoder.assume(&format!(
+                    "(= (select {storage} {}) {cell})",
+                    smt::literal(&layout.slot, 256)
+                ));
+                encoder.model.push(term.text);
+                model.push((var.name.clone(), layout.ty.clone()));
+            }
+
+            for (_, invariant) in &invariants {
+                let (reach, value) = encoder.evaluate(invariant, "true", &storage);
+
+                encoder.assume(&format!("(=> {reach} {})", value.text));
+            }
+
+            storage
+        };
+
+        let exits = encoder.run(cfg, "true", storage, args, true);
+
+        if !matches!(func.mutability, Mutability::Pure(_) | Mutability::View(_))
+            && !invariants.is_empty()
+        {
+            let (reach, storage, _) = encoder.merge_exits(exits, &[]);
+
+            for (no, invariant) in &invariants {
+                let (evaluated, value) = encoder.evaluate(invariant, &reach, &storage);
+
+                encoder.check(
+                    Property::Invariant(*no),
+                    func.loc_prototype,
+                    &evaluated,
+                    &format!("(not {})", value.text),
+                );
+            }
+        }
+
+        scripts.push(Script {
+            function_no,
+            text: encoder.script,
+            checks: encoder.checks,
+            model,
+        });
+    }
+
+    scripts
+}
+
+/// Report a property which does not hold, or could not be proven
+pub fn diagnostic(verdict: &Verdict, contract_no: usize, ns: &Namespace) -> Option<Diagnostic> {
+    let func = function(verdict.function_no, contract_no, ns);
+    let description = verdict.property.description();
+    let loc = match (verdict.loc, func.loc_prototype) {
+        (Loc::File(..), _) => verdict.loc,
+        (_, Loc::File(..)) => func.loc_prototype,
+        _ => ns.contracts[contract_no].loc,
+    };
+
+    let mut notes = Vec::new();
+
+    if let Property::Invariant(no) = verdict.property {
+        if let Some((_, tag)) = invariant_tags(contract_no, ns).get(no) {
+            notes.push(Note {
+                loc: tag.loc,
+                message: format!("invariant '{}'", tag.value),
+            });
+        }
+    } else if verdict.function_no.is_some() && !within(&loc, &func.loc) {
+        notes.push(Note {
+            loc: func.loc_prototype,
+            message: format!("when called through function '{}'", func.id.name),
+        });
+    }
+
+    let mut diagnostic = match &verdict.outcome {
+        Outcome::Holds => return None,
+        Outcome::Fails(values) => {
+            let mut message = format!("{description} is possible");
+
+            if !values.is_empty() {
+                let values: Vec<String> = values
+                    .iter()
+                    .map(|(name, value)| format!("{name} = {value}"))
+                    .collect();
+
+                message.push_str(&format!(", for example when {}", values.join(", ")));
+            }
+
+            Diagnostic::error(loc, message)
+        }
+        Outcome::Unknown => Diagnostic::warning(
+            loc,
+            format!("solver cannot decide whether {description} is possible"),
+        ),
+    };
+
+    diagnostic.notes = notes;
+
+    Some(diagnostic)
+}
+
+/// The function with the given number, or the default constructor of the contract
+fn function(function_no: Option<usize>, contract_no: usize, ns: &Namespace) -> &Function {
+    match function_no {
+        Some(function_no) => &ns.functions[function_no],
+        None => {
+            &ns.contracts[contract_no]
+                .default_constructor
+                .as_ref()
+                .unwrap()
+                .0
+        }
+    }
+}
+
+fn within(loc: &Loc, outer: &Loc) -> bool {
+    match (loc, outer) {
+        (Loc::File(file_no, start, end), Loc::File(outer_file_no, outer_start, outer_end)) => {
+            file_no == outer_file_no && outer_start <= start && end <= outer_end
+        }
+        _ => false,
+    }
+}
+
+/// The `@custom:invariant` tags of the contract and its bases, with the contract which has
+/// the tag
+fn invariant_tags(contract_no: usize, ns: &Namespace) -> Vec<(usize, &Tag)> {
+    ns.contract_bases(contract_no)
+        .into_iter()
+        .flat_map(|base| {
+            ns.contracts[base]
+                .tags
+                .iter()
+                .filter(|tag| tag.tag == "custom:invariant")
+                .map(move |tag| (base, tag))
+        })
+        .collect()
+}
+
+/// Resolve the invariants of the contract, and lower each to a control flow graph which
+/// returns its value. Errors in the invariants are added to the diagnostics, and those
+/// invariants are skipped.
+fn invariants(
+    contract_no: usize,
+    ns: &mut Namespace,
+    opt: &Options,
+) -> Vec<(usize, ControlFlowGraph)> {
+    let tags: Vec<(usize, Loc, String)> = invariant_tags(contract_no, ns)
+        .into_iter()
+        .map(|(base, tag)| (base, tag.loc, tag.value.clone()))
+        .collect();
+
+    let mut invariants = Vec::new();
+
+    for (no, (base, loc, text)) in tags.into_iter().enumerate() {
+        let Loc::File(file_no, _, end) = loc else {
+            continue;
+        };
+
+        // Parse the invariant as the initializer of a constant, which is placed where the
+        // invariant is in the file, so that the locations in the expression are correct
+        let prefix = "bool constant x=";
+        let start = end - text.len();
+        let src = format!(
+            "{}{prefix}{text};",
+            " ".repeat(start.saturating_sub(prefix.len()))
+        );
+
+        let initializer = match solang_parser::parse(&src, file_no) {
+            Ok((unit, _)) => unit.0.into_iter().find_map(|part| match part {
+                pt::SourceUnitPart::VariableDefinition(def) => def.initializer,
+                _ => None,
+            }),
+            Err(errors) => {
+                for error in errors {
+                    ns.diagnostics.push(error);
+                }
+                continue;
+            }
+        };
+
+        let Some(initializer) = initializer else {
+            ns.diagnostics.push(Diagnostic::error(
+                loc,
+                "invariant is not an expression".into(),
+            ));
+            continue;
+        };
+
+        let mut context = ExprContext {
+            file_no,
+            contract_no: Some(base),
+            ..Default::default()
+        };
+        context.enter_scope();
+
+        let mut diagnostics = Diagnostics::default();
+
+        let resolved = resolve_expression::expression(
+            &initializer,
+            &mut context,
+            ns,
+            &mut Symtable::default(),
+            &mut diagnostics,
+            ResolveTo::Type(&Type::Bool),
+        )
+        .and_then(|expr| expr.cast(&loc, &Type::Bool, true, ns, &mut diagnostics));
+
+        ns.diagnostics.extend(diagnostics);
+
+        let Ok(resolved) = resolved else {
+            continue;
+        };
+
+        let mut cfg = ControlFlowGraph::new(format!("invariant {text}"), ASTFunction::None);
+        let mut vartab = Vartable::new(ns.next_id);
+
+        let value = expression(&resolved, &mut cfg, contract_no, None, ns, &mut vartab, opt);
+
+        cfg.add(&mut vartab, Instr::Return { value: vec![value] });
+
+        vartab.finalize(ns, &mut cfg);
+
+        invariants.push((no, cfg));
+    }
+
+    invariants
+}
+
+/// Show a value from the solver as a Solidity value
+fn display_value(value: &str, ty: &Type, ns: &Namespace) -> String {
+    let (digits, radix, width) = if let Some(digits) = value.strip_prefix("#x") {
+        (digits, 16, digits.len() * 4)
+    } else if let Some(digits) = value.strip_prefix("#b") {
+        (digits, 2, digits.len())
+    } else {
+        return value.to_owned();
+    };
+
+    let Ok(number) = BigInt::from_str_radix(digits, radix) else {
+        return value.to_owned();
+    };
+
+    match ty {
+        Type::UserType(no) => display_value(value, &ns.user_types[*no].ty, ns),
+        Type::Int(bits) if number.bit(*bits as u64 - 1) => {
+            (number - (BigInt::from(1) << *bits)).to_string()
+        }
+        Type::Address(_) | Type::Contract(_) | Type::Bytes(_) => {
+            format!("0x{number:0digits$x}", digits = width / 4)
+        }
+        _ => number.to_string(),
+    }
+}
@@ -0,0 +1,1336 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Translate control flow graphs to SMT-LIB. The blocks are visited in topological order, and
+//! each block gets a boolean which is true if the block is executed. The value of each variable
+//! is a term; where paths merge, a new constant is declared which is equal to the value of the
+//! variable on the path that was taken.
+//!
+//! Loops are not unrolled. Instead, the variables and storage which are modified in a loop are
+//! given arbitrary values at the loop header, so anything which is proven holds for any number
+//! of iterations. Calls to internal functions are inlined.
+
+use super::Property;
+use crate::codegen::cfg::{ASTFunction, ControlFlowGraph, Instr, InternalCallTy};
+use crate::codegen::loops::assigned;
+use crate::codegen::{Builtin, Expression};
+use crate::sema::ast::{Contract, Mutability, Namespace, RetrieveType, Type};
+use num_bigint::{BigInt, Sign};
+use num_traits::{One, Zero};
+use solang_parser::pt::{CodeLocation, Loc};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+
+/// Storage maps 256 bit slots to 256 bit values
+const STORAGE_SORT: &str = "(Array (_ BitVec 256) (_ BitVec 256))";
+
+/// Internal calls nested deeper than this are not inlined
+const MAX_INLINE_DEPTH: usize = 4;
+
+/// Builtins which give the same result when called again with the same arguments
+const STABLE_BUILTINS: &[Builtin] = &[
+    Builtin::ArrayLength,
+    Builtin::BaseFee,
+    Builtin::Blake2_128,
+    Builtin::Blake2_256,
+    Builtin::BlockCoinbase,
+    Builtin::BlockDifficulty,
+    Builtin::BlockHash,
+    Builtin::BlockNumber,
+    Builtin::Calldata,
+    Builtin::ChainId,
+    Builtin::GasLimit,
+    Builtin::Gasprice,
+    Builtin::GetAddress,
+    Builtin::Keccak256,
+    Builtin::MinimumBalance,
+    Builtin::Origin,
+    Builtin::PrevRandao,
+    Builtin::Ripemd160,
+    Builtin::Sender,
+    Builtin::Sha256,
+    Builtin::Signature,
+    Builtin::Slot,
+    Builtin::Timestamp,
+    Builtin::Value,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sort {
+    Bool,
+    BitVec(u16),
+    /// A value which is not modelled, like an array in memory. The text of the term identifies
+    /// the value, but it is not declared in the script.
+    Opaque,
+}
+
+impl Sort {
+    pub fn of(ty: &Type, ns: &Namespace) -> Sort {
+        match ty {
+            Type::Bool => Sort::Bool,
+            Type::Int(_)
+            | Type::Uint(_)
+            | Type::Bytes(_)
+            | Type::Address(_)
+            | Type::Contract(_)
+            | Type::Enum(_)
+            | Type::Value
+            | Type::StorageRef(..)
+            | Type::FunctionSelector => Sort::BitVec(ty.bits(ns)),
+            Type::UserType(no) => Sort::of(&ns.user_types[*no].ty, ns),
+            _ => Sort::Opaque,
+        }
+    }
+
+    /// The number of bits of a bit vector, or of a boolean converted to a bit vector
+    pub fn width(&self) -> u16 {
+        match self {
+            Sort::Bool => 1,
+            Sort::BitVec(width) => *width,
+            Sort::Opaque => 0,
+        }
+    }
+}
+
+impl std::fmt::Display for Sort {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Sort::Bool => write!(f, "Bool"),
+            Sort::BitVec(width) => write!(f, "(_ BitVec {width})"),
+            Sort::Opaque => write!(f, "Opaque"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term {
+    pub text: String,
+    pub sort: Sort,
+}
+
+impl Term {
+    fn bool(text: String) -> Self {
+        Term {
+            text,
+            sort: Sort::Bool,
+        }
+    }
+
+    fn bitvec(text: String, width: u16) -> Self {
+        Term {
+            text,
+            sort: Sort::BitVec(width),
+        }
+    }
+}
+
+/// A bit vector literal. Negative values are two's complement.
+pub fn literal(value: &BigInt, width: u16) -> String {
+    let modulus = BigInt::one() << width;
+    let value = ((value % &modulus) + &modulus) % &modulus;
+
+    format!("(_ bv{value} {width})")
+}
+
+/// A condition which must be false in every execution of the function
+pub struct Check {
+    pub property: Property,
+    pub loc: Loc,
+}
+
+/// A path which leaves the control flow graph through a return
+pub struct Exit {
+    pub reach: String,
+    pub storage: String,
+    pub values: Vec<Term>,
+}
+
+/// The values of the variables and the storage, at some point in the function
+#[derive(Clone)]
+struct State {
+    vars: BTreeMap<usize, Term>,
+    storage: String,
+}
+
+pub struct Encoder<'a> {
+    ns: &'a Namespace,
+    contract: &'a Contract,
+    pub script: String,
+    pub checks: Vec<Check>,
+    /// The constants whose values are shown in a counterexample
+    pub model: Vec<String>,
+    /// Are the properties of the code being checked, or is it only executed
+    checking: bool,
+    /// The arguments of the function being translated
+    args: Vec<Term>,
+    /// Is the current block executed
+    reach: String,
+    /// The control flow graphs which are being inlined
+    inlining: Vec<usize>,
+    next_id: usize,
+    /// The results of stable builtins and hashes
+    memo: HashMap<String, Term>,
+    /// The widths for which a hash function has been declared
+    hashes: BTreeSet<u16>,
+}
+
+impl<'a> Encoder<'a> {
+    pub fn new(contract: &'a Contract, ns: &'a Namespace) -> Self {
+        Encoder {
+            ns,
+            contract,
+            script: String::from("(set-option :produce-models true)\n"),
+            checks: Vec::new(),
+            model: Vec::new(),
+            checking: true,
+            args: Vec::new(),
+            reach: String::from("true"),
+            inlining: Vec::new(),
+            next_id: 0,
+            memo: HashMap::new(),
+            hashes: BTreeSet::new(),
+        }
+    }
+
+    /// A new name, which is not used anywhere else in the script
+    fn name(&mut self, hint: &str) -> String {
+        let hint: String = hint
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '$' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+
+        self.next_id += 1;
+
+        format!("{hint}.{}", self.next_id)
+    }
+
+    /// Declare a constant, which may have any value
+    pub fn declare(&mut self, hint: &str, sort: Sort) -> Term {
+        let text = self.name(hint);
+
+        if sort != Sort::Opaque {
+            writeln!(self.script, "(declare-const {text} {sort})").unwrap();
+        }
+
+        Term { text, sort }
+    }
+
+    /// Declare storage with any contents
+    pub fn declare_storage(&mut self) -> String {
+        let name = self.name("storage");
+
+        writeln!(self.script, "(declare-const {name} {STORAGE_SORT})").unwrap();
+
+        name
+    }
+
+    /// Storage in which every slot is zero
+    pub fn empty_storage(&self) -> String {
+        format!("((as const {STORAGE_SORT}) (_ bv0 256))")
+    }
+
+    /// Give a term a name, so that it is not repeated when it is used more than once
+    fn define(&mut self, hint: &str, term: Term) -> Term {
+        if term.sort == Sort::Opaque || !term.text.starts_with('(') {
+            return term;
+        }
+
+        let text = self.name(hint);
+
+        writeln!(
+            self.script,
+            "(define-fun {text} () {} {})",
+            term.sort, term.text
+        )
+        .unwrap();
+
+        Term {
+            text,
+            sort: term.sort,
+        }
+    }
+
+    pub fn assume(&mut self, cond: &str) {
+        writeln!(self.script, "(assert {cond})").unwrap();
+    }
+
+    /// Ask the solver whether the condition can be true when the code is reached. If not, the
+    /// property holds.
+    pub fn check(&mut self, property: Property, loc: Loc, reach: &str, cond: &str) {
+        let no = self.checks.len();
+
+        self.checks.push(Check { property, loc });
+
+        writeln!(self.script, "(echo \"check {no}\")").unwrap();
+        writeln!(self.script, "(push 1)").unwrap();
+        writeln!(self.script, "(assert (and {reach} {cond}))").unwrap();
+        writeln!(self.script, "(check-sat)").unwrap();
+
+        if !self.model.is_empty() {
+            writeln!(self.script, "(get-value ({}))", self.model.join(" ")).unwrap();
+        }
+
+        writeln!(self.script, "(pop 1)").unwrap();
+    }
+
+    /// A condition which is checked at runtime, after which execution continues only if it is
+    /// false
+    fn runtime_check(&mut self, property: Property, loc: Loc, cond: String) {
+        if self.checking {
+            let reach = self.reach.clone();
+
+            self.check(property, loc, &reach, &cond);
+            self.assume(&format!("(=> {reach} (not {cond}))"));
+        }
+    }
+
+    /// Translate the control flow graph, starting with the given storage. The properties are
+    /// checked if `checking` is set. Returns the paths through which the graph is left.
+    pub fn run(
+        &mut self,
+        cfg: &ControlFlowGraph,
+        reach: &str,
+        storage: String,
+        args: Vec<Term>,
+        checking: bool,
+    ) -> Vec<Exit> {
+        let saved_args = std::mem::replace(&mut self.args, args);
+        let saved_checking = std::mem::replace(&mut self.checking, checking);
+        let saved_reach = self.reach.clone();
+
+        let back_edges: HashSet<(usize, usize)> = cfg.back_edges().into_iter().collect();
+
+        // the variables and whether storage is modified by each loop, by loop header
+        let mut havoc: BTreeMap<usize, (BTreeSet<usize>, bool)> = BTreeMap::new();
+
+        for lp in cfg.loops() {
+            let (vars, storage) = havoc.entry(lp.header).or_default();
+
+            for instr in lp.blocks.iter().flat_map(|no| cfg.blocks[*no].instr.iter()) {
+                vars.extend(assigned(instr));
+                *storage |= self.writes_storage(instr);
+            }
+        }
+
+        let mut incoming: Vec<Vec<(String, State)>> = vec![Vec::new(); cfg.blocks.len()];
+        let mut branch_locs: HashMap<usize, Loc> = HashMap::new();
+        let mut exits = Vec::new();
+
+        incoming[0].push((
+            reach.to_owned(),
+            State {
+                vars: BTreeMap::new(),
+                storage,
+            },
+        ));
+
+        for block_no in order(cfg, &back_edges) {
+            let edges = std::mem::take(&mut incoming[block_no]);
+
+            if edges.is_empty() {
+                continue;
+            }
+
+            let mut state = self.merge(block_no, edges, cfg);
+
+            if let Some((vars, storage)) = havoc.get(&block_no) {
+                for var_no in vars {
+                    let term = self.variable(cfg, *var_no);
+                    state.vars.insert(*var_no, term);
+                }
+
+                if *storage {
+                    state.storage = self.declare_storage();
+                }
+            }
+
+            let block = &cfg.blocks[block_no];
+
+            if let Some(property) = failure(&block.instr) {
+                if self.checking {
+                    let loc = branch_locs.get(&block_no).cloned().unwrap_or(Loc::Codegen);
+                    let reach = self.reach.clone();
+
+                    self.check(property, loc, &reach, "true");
+                }
+
+                continue;
+            }
+
+            let mut edge = |from: usize, to: usize, guard: String, state: &State| {
+                if !back_edges.contains(&(from, to)) {
+                    incoming[to].push((guard, state.clone()));
+                }
+            };
+
+            for instr in &block.instr {
+                match instr {
+                    Instr::Branch { block } => {
+                        edge(block_no, *block, self.reach.clone(), &state);
+                    }
+                    Instr::BranchCond {
+                        cond,
+                        true_block,
+                        false_block,
+                    } => {
+                        let term = self.expr(cond, &state);
+                        let cond_term = self.condition(term);
+
+                        for target in [true_block, false_block] {
+                            branch_locs.entry(*target).or_insert(cond.loc());
+                        }
+
+                        edge(
+                            block_no,
+                            *true_block,
+                            format!("(and {} {cond_term})", self.reach),
+                            &state,
+                        );
+                        edge(
+                            block_no,
+                            *false_block,
+                            format!("(and {} (not {cond_term}))", self.reach),
+                            &state,
+                        );
+                    }
+                    Instr::Switch {
+                        cond,
+                        cases,
+                        default,
+                    } => {
+                        let value = self.expr(cond, &state);
+                        let mut others = Vec::new();
+
+                        for (case, target) in cases {
+                            let case = self.expr(case, &state);
+
+                            let matches = if value.sort != Sort::Opaque && value.sort == case.sort {
+                                format!("(= {} {})", value.text, case.text)
+                            } else {
+                                self.declare("case", Sort::Bool).text
+                            };
+
+                            edge(
+                                block_no,
+                                *target,
+                                format!("(and {} {matches})", self.reach),
+                                &state,
+                            );
+
+                            others.push(matches);
+                        }
+
+                        let guard = if others.is_empty() {
+                            self.reach.clone()
+                        } else {
+                            format!("(and {} (not (or false {})))", self.reach, others.join(" "))
+                        };
+
+                        edge(block_no, *default, guard, &state);
+                    }
+                    Instr::Return { value } => {
+                        let values = value.iter().map(|expr| self.expr(expr, &state)).collect();
+
+                        exits.push(Exit {
+                            reach: self.reach.clone(),
+                            storage: state.storage.clone(),
+                            values,
+                        });
+                    }
+                    _ => self.instr(instr, cfg, &mut state),
+                }
+            }
+        }
+
+        self.args = saved_args;
+        self.checking = saved_checking;
+        self.reach = saved_reach;
+
+        exits
+    }
+
+    /// Combine the paths which lead to the block
+    fn merge(
+        &mut self,
+        block_no: usize,
+        mut edges: Vec<(String, State)>,
+        cfg: &ControlFlowGraph,
+    ) -> State {
+        if edges.len() == 1 {
+            let (guard, state) = edges.pop().unwrap();
+
+            self.reach = self
+                .define(&format!("block{block_no}"), Term::bool(guard))
+                .text;
+
+            return state;
+        }
+
+        let guards: Vec<&str> = edges.iter().map(|(guard, _)| guard.as_str()).collect();
+
+        self.reach = self
+            .define(
+                &format!("block{block_no}"),
+                Term::bool(format!("(or {})", guards.join(" "))),
+            )
+            .text;
+
+        let var_nos: BTreeSet<usize> = edges
+            .iter()
+            .flat_map(|(_, state)| state.vars.keys().copied())
+            .collect();
+
+        let mut vars = BTreeMap::new();
+
+        for var_no in var_nos {
+            let values: Vec<(&String, &Term)> = edges
+                .iter()
+                .filter_map(|(guard, state)| state.vars.get(&var_no).map(|term| (guard, term)))
+                .collect();
+
+            let (_, first) = values[0];
+
+            if values.iter().all(|(_, term)| *term == first) {
+                vars.insert(var_no, first.clone());
+                continue;
+            }
+
+            let merged = self.variable(cfg, var_no);
+
+            if merged.sort != Sort::Opaque {
+                for (guard, term) in values {
+                    if term.sort == merged.sort {
+                        self.assume(&format!("(=> {guard} (= {} {}))", merged.text, term.text));
+                    }
+                }
+            }
+
+            vars.insert(var_no, merged);
+        }
+
+        let storage = if edges
+            .iter()
+            .all(|(_, state)| state.storage == edges[0].1.storage)
+        {
+            edges[0].1.storage.clone()
+        } else {
+            let storage = self.declare_storage();
+
+            for (guard, state) in &edges {
+                self.assume(&format!("(=> {guard} (= {storage} {}))", state.storage));
+            }
+
+            storage
+        };
+
+        State { vars, storage }
+    }
+
+    /// A new value for a variable of the control flow graph
+    fn variable(&mut self, cfg: &ControlFlowGraph, var_no: usize) -> Term {
+        match cfg.vars.get(&var_no) {
+            Some(var) => {
+                let sort = Sort::of(&var.ty, self.ns);
+                let name = var.id.name.clone();
+
+                self.declare(&name, sort)
+            }
+            None => self.declare("var", Sort::Opaque),
+        }
+    }
+
+    fn instr(&mut self, instr: &Instr, cfg: &ControlFlowGraph, state: &mut State) {
+        match instr {
+            Instr::Set { res, expr, .. } => {
+                let term = self.expr(expr, state);
+                let hint = cfg
+                    .vars
+                    .get(res)
+                    .map(|var| var.id.name.clone())
+                    .unwrap_or_default();
+                let term = self.define(&hint, term);
+
+                state.vars.insert(*res, term);
+            }
+            Instr::LoadStorage { res, ty, storage } => {
+                let slot = self.expr(storage, state);
+                let term = self.load(&state.storage, slot, ty);
+                let term = self.define("load", term);
+
+                state.vars.insert(*res, term);
+            }
+            Instr::SetStorage { value, storage, .. } => {
+                let slot = self.expr(storage, state);
+                let value = self.expr(value, state);
+
+                state.storage = self.store(&state.storage, slot, value);
+            }
+            Instr::ClearStorage { ty, storage } => {
+                let slot = self.expr(storage, state);
+
+                state.storage = match Sort::of(ty, self.ns) {
+                    Sort::BitVec(width) if width <= 256 => self.store(
+                        &state.storage,
+                        slot,
+                        Term::bitvec(literal(&BigInt::zero(), width), width),
+                    ),
+                    Sort::Bool => self.store(&state.storage, slot, Term::bool("false".into())),
+                    _ => self.declare_storage(),
+                };
+            }
+            Instr::Call {
+                res,
+                return_tys,
+                call,
+                args,
+            } => {
+                let args: Vec<Term> = args.iter().map(|arg| self.expr(arg, state)).collect();
+
+                if let Some(values) = self.inline(call, args, state) {
+                    for (var_no, value) in res.iter().zip(values) {
+                        state.vars.insert(*var_no, value);
+                    }
+                } else {
+                    for (var_no, ty) in res.iter().zip(return_tys) {
+                        let term = self.declare("result", Sort::of(ty, self.ns));
+
+                        state.vars.insert(*var_no, term);
+                    }
+
+                    if !self.is_pure_call(call) {
+                        state.storage = self.declare_storage();
+                    }
+                }
+            }
+            Instr::Store { dest, data } => {
+                self.expr(dest, state);
+                self.expr(data, state);
+            }
+            Instr::PushMemory { value, .. } => {
+                self.expr(value, state);
+                self.havoc(instr, cfg, state);
+            }
+            Instr::ExternalCall { value, .. } | Instr::ValueTransfer { value, .. } => {
+                self.expr(value, state);
+                self.havoc(instr, cfg, state);
+            }
+            Instr::EmitEvent { topics, .. } => {
+                for topic in topics {
+                    self.expr(topic, state);
+                }
+            }
+            _ => self.havoc(instr, cfg, state),
+        }
+    }
+
+    /// The instruction is not modelled, so the variables and storage it modifies may have any
+    /// value afterwards
+    fn havoc(&mut self, instr: &Instr, cfg: &ControlFlowGraph, state: &mut State) {
+        for var_no in assigned(instr) {
+            let term = self.variable(cfg, var_no);
+
+            state.vars.insert(var_no, term);
+        }
+
+        if self.writes_storage(instr) {
+            state.storage = self.declare_storage();
+        }
+    }
+
+    /// Can the instruction modify storage in a way which is not modelled. Another contract may
+    /// call back into this one, so external calls can modify storage too.
+    fn writes_storage(&self, instr: &Instr) -> bool {
+        match instr {
+            Instr::SetStorage { .. } | Instr::ClearStorage { .. } => true,
+            Instr::SetStorageBytes { .. }
+            | Instr::PushStorage { .. }
+            | Instr::PopStorage { .. }
+            | Instr::Constructor { .. }
+            | Instr::ExternalCall { .. }
+            | Instr::ValueTransfer { .. } => true,
+            Instr::Call { call, .. } => !self.is_pure_call(call),
+            _ => false,
+        }
+    }
+
+    fn is_pure_call(&self, call: &InternalCallTy) -> bool {
+        if let InternalCallTy::Static { cfg_no } = call {
+            if let ASTFunction::SolidityFunction(function_no) =
+                self.contract.cfg[*cfg_no].function_no
+            {
+                return matches!(
+                    self.ns.functions[function_no].mutability,
+                    Mutability::Pure(_) | Mutability::View(_)
+                );
+            }
+        }
+
+        false
+    }
+
+    /// Translate the body of the called function in place, and return the values it returns.
+    /// Returns `None` if the function cannot be inlined.
+    fn inline(
+        &mut self,
+        call: &InternalCallTy,
+        args: Vec<Term>,
+        state: &mut State,
+    ) -> Option<Vec<Term>> {
+        let InternalCallTy::Static { cfg_no } = call else {
+            return None;
+        };
+
+        if self.inlining.contains(cfg_no) || self.inlining.len() >= MAX_INLINE_DEPTH {
+            return None;
+        }
+
+        let contract = self.contract;
+        let callee = &contract.cfg[*cfg_no];
+
+        if callee.is_placeholder() {
+            return None;
+        }
+
+        self.inlining.push(*cfg_no);
+
+        let reach = self.reach.clone();
+        let exits = self.run(callee, &reach, state.storage.clone(), args, self.checking);
+
+        self.inlining.pop();
+
+        let sorts: Vec<Sort> = callee
+            .returns
+            .iter()
+            .map(|param| Sort::of(&param.ty, self.ns))
+            .collect();
+
+        let (reach, storage, values) = self.merge_exits(exits, &sorts);
+
+        // execution only continues if the called function returns
+        self.reach = reach;
+        state.storage = storage;
+
+        Some(values)
+    }
+
+    /// Combine the paths through which a control flow graph returns
+    pub fn merge_exits(&mut self, exits: Vec<Exit>, sorts: &[Sort]) -> (String, String, Vec<Term>) {
+        if exits.len() == 1 {
+            let exit = exits.into_iter().next().unwrap();
+
+            return (exit.reach, exit.storage, exit.values);
+        }
+
+        let reach = if exits.is_empty() {
+            String::from("false")
+        } else {
+            let guards: Vec<&str> = exits.iter().map(|exit| exit.reach.as_str()).collect();
+
+            self.define("return", Term::bool(format!("(or {})", guards.join(" "))))
+                .text
+        };
+
+        let storage = self.declare_storage();
+        let mut values = Vec::new();
+
+        for exit in &exits {
+            self.assume(&format!(
+                "(=> {} (= {storage} {}))",
+                exit.reach, exit.storage
+            ));
+        }
+
+        for (no, sort) in sorts.iter().enumerate() {
+            let value = self.declare("return", *sort);
+
+            if *sort != Sort::Opaque {
+                for exit in &exits {
+                    if let Some(term) = exit.values.get(no).filter(|term| term.sort == *sort) {
+                        self.assume(&format!(
+                            "(=> {} (= {} {}))",
+                            exit.reach, value.text, term.text
+                        ));
+                    }
+                }
+            }
+
+            values.push(value);
+        }
+
+        (reach, storage, values)
+    }
+
+    /// Evaluate a boolean expression, like an invariant, which has been lowered to a control
+    /// flow graph. Returns when the value is available, and the value.
+    pub fn evaluate(
+        &mut self,
+        cfg: &ControlFlowGraph,
+        reach: &str,
+        storage: &str,
+    ) -> (String, Term) {
+        let exits = self.run(cfg, reach, storage.to_owned(), Vec::new(), false);
+        let (reach, _, mut values) = self.merge_exits(exits, &[Sort::Bool]);
+
+        let value = values.pop().unwrap_or_else(|| Term::bool("false".into()));
+
+        (reach, value)
+    }
+
+    /// Make sure the term is a boolean
+    fn condition(&mut self, term: Term) -> String {
+        match term.sort {
+            Sort::Bool => term.text,
+            Sort::BitVec(width) => format!("(distinct {} (_ bv0 {width}))", term.text),
+            Sort::Opaque => self.declare("cond", Sort::Bool).text,
+        }
+    }
+
+    /// Convert the term to another sort
+    fn resize(&mut self, term: Term, to: Sort, signed: bool) -> Term {
+        match (term.sort, to) {
+            (from, to) if from == to => term,
+            (Sort::BitVec(from), Sort::BitVec(to)) if to > from => {
+                let ext = if signed { "sign_extend" } else { "zero_extend" };
+
+                Term::bitvec(format!("((_ {ext} {}) {})", to - from, term.text), to)
+            }
+            (Sort::BitVec(_), Sort::BitVec(to)) => {
+                Term::bitvec(format!("((_ extract {} 0) {})", to - 1, term.text), to)
+            }
+            (Sort::Bool, Sort::BitVec(to)) => {
+                Term::bitvec(format!("(ite {} (_ bv1 {to}) (_ bv0 {to}))", term.text), to)
+            }
+            (Sort::BitVec(from), Sort::Bool) => {
+                Term::bool(format!("(distinct {} (_ bv0 {from}))", term.text))
+            }
+            (_, to) => self.declare("cast", to),
+        }
+    }
+
+    fn slot(&mut self, slot: Term) -> String {
+        match slot.sort {
+            Sort::BitVec(width) if width <= 256 => self.resize(slot, Sort::BitVec(256), false).text,
+            _ => self.declare("slot", Sort::BitVec(256)).text,
+        }
+    }
+
+    fn load(&mut self, storage: &str, slot: Term, ty: &Type) -> Term {
+        let slot = self.slot(slot);
+
+        match Sort::of(ty, self.ns) {
+            Sort::Bool => Term::bool(format!(
+                "(= ((_ extract 0 0) (select {storage} {slot})) #b1)"
+            )),
+            Sort::BitVec(256) => Term::bitvec(format!("(select {storage} {slot})"), 256),
+            Sort::BitVec(width) if width < 256 => Term::bitvec(
+                format!("((_ extract {} 0) (select {storage} {slot}))", width - 1),
+                width,
+            ),
+            sort => self.declare("load", sort),
+        }
+    }
+
+    fn store(&mut self, storage: &str, slot: Term, value: Term) -> String {
+        let slot = self.slot(slot);
+
+        let value = match value.sort {
+            Sort::Bool => self.resize(value, Sort::BitVec(256), false).text,
+            Sort::BitVec(width) if width <= 256 => {
+                self.resize(value, Sort::BitVec(256), false).text
+            }
+            _ => self.declare("value", Sort::BitVec(256)).text,
+        };
+
+        let name = self.name("storage");
+
+        writeln!(
+            self.script,
+            "(define-fun {name} () {STORAGE_SORT} (store {storage} {slot} {value}))"
+        )
+        .unwrap();
+
+        name
+    }
+
+    /// An uninterpreted function, so that the hash of the same value is always the same
+    fn hash(&mut self, name: &str, args: Vec<Term>, sort: Sort) -> Term {
+        let mut width = 0u16;
+
+        for arg in &args {
+            match arg.sort {
+                Sort::BitVec(bits) => width = width.saturating_add(bits),
+                Sort::Bool => width = width.saturating_add(1),
+                Sort::Opaque => return self.declare(name, sort),
+            }
+        }
+
+        let (Sort::BitVec(result), true) = (sort, width > 0 && width < u16::MAX) else {
+            return self.declare(name, sort);
+        };
+
+        let args: Vec<String> = args
+            .into_iter()
+            .map(|arg| {
+                self.resize(arg.clone(), Sort::BitVec(arg.sort.width()), false)
+                    .text
+            })
+            .collect();
+
+        let arg = if args.len() == 1 {
+            args[0].clone()
+        } else {
+            format!("(concat {})", args.join(" "))
+        };
+
+        if self.hashes.insert(width) {
+            writeln!(
+                self.script,
+                "(declare-fun {name}_{width} ((_ BitVec {width})) (_ BitVec 256))"
+            )
+            .unwrap();
+        }
+
+        let hash = Term::bitvec(format!("({name}_{width} {arg})"), 256);
+
+        self.resize(hash, Sort::BitVec(result), false)
+    }
+
+    fn expr(&mut self, expr: &Expression, state: &State) -> Term {
+        match expr {
+            Expression::BoolLiteral { value, .. } => Term::bool(value.to_string()),
+            Expression::NumberLiteral { ty, value, .. } => match Sort::of(ty, self.ns) {
+                Sort::BitVec(width) => Term::bitvec(literal(value, width), width),
+                Sort::Bool => Term::bool((!value.is_zero()).to_string()),
+                Sort::Opaque => self.declare("number", Sort::Opaque),
+            },
+            Expression::BytesLiteral { ty, value, .. } => match Sort::of(ty, self.ns) {
+                Sort::BitVec(width) if value.len() * 8 == width as usize => Term::bitvec(
+                    literal(&BigInt::from_bytes_be(Sign::Plus, value), width),
+                    width,
+                ),
+                sort => self.declare("bytes", sort),
+            },
+            Expression::FunctionArg { ty, arg_no, .. } => match self.args.get(*arg_no) {
+                Some(term) => term.clone(),
+                None => self.declare("arg", Sort::of(ty, self.ns)),
+            },
+            Expression::Variable { ty, var_no, .. } => match state.vars.get(var_no) {
+                Some(term) => term.clone(),
+                None => self.declare("undefined", Sort::of(ty, self.ns)),
+            },
+            Expression::Add {
+                loc,
+                ty,
+                overflowing,
+                left,
+                right,
+            }
+            | Expression::Subtract {
+                loc,
+                ty,
+                overflowing,
+                left,
+                right,
+            }
+            | Expression::Multiply {
+                loc,
+                ty,
+                overflowing,
+                left,
+                right,
+            } => {
+                let left = self.expr(left, state);
+                let right = self.expr(right, state);
+                let sort = Sort::of(ty, self.ns);
+
+                let Sort::BitVec(width) = sort else {
+                    return self.declare("arith", sort);
+                };
+
+                if left.sort != sort || right.sort != sort {
+                    return self.declare("arith", sort);
+                }
+
+                let op = match expr {
+                    Expression::Add { .. } => "bvadd",
+                    Expression::Subtract { .. } => "bvsub",
+                    _ => "bvmul",
+                };
+
+                if !overflowing && matches!(ty, Type::Int(_) | Type::Uint(_)) {
+                    let cond = overflow(
+                        op,
+                        ty.is_signed_int(self.ns),
+                        &left.text,
+                        &right.text,
+                        width,
+                    );
+
+                    self.runtime_check(Property::Overflow, *loc, cond);
+                }
+
+                Term::bitvec(format!("({op} {} {})", left.text, right.text), width)
+            }
+            Expression::Negate {
+                loc,
+                ty,
+                overflowing,
+                expr: operand,
+            } => {
+                let operand = self.expr(operand, state);
+                let sort = Sort::of(ty, self.ns);
+
+                let (Sort::BitVec(width), true) = (sort, operand.sort == sort) else {
+                    return self.declare("negate", sort);
+                };
+
+                if !overflowing && matches!(ty, Type::Int(_)) {
+                    let min = literal(&(BigInt::one() << (width - 1)), width);
+
+                    self.runtime_check(
+                        Property::Overflow,
+                        *loc,
+                        format!("(= {} {min})", operand.text),
+                    );
+                }
+
+                Term::bitvec(format!("(bvneg {})", operand.text), width)
+            }
+            Expression::UnsignedDivide {
+                loc,
+                ty,
+                left,
+                right,
+            }
+            | Expression::SignedDivide {
+                loc,
+                ty,
+                left,
+                right,
+            }
+            | Expression::UnsignedModulo {
+                loc,
+                ty,
+                left,
+                right,
+            }
+            | Expression::SignedModulo {
+                loc,
+                ty,
+                left,
+                right,
+            } => {
+                let left = self.expr(left, state);
+                let right = self.expr(right, state);
+                let sort = Sort::of(ty, self.ns);
+
+                let Sort::BitVec(width) = sort else {
+                    return self.declare("divide", sort);
+                };
+
+                if left.sort != sort || right.sort != sort {
+                    return self.declare("divide", sort);
+                }
+
+                self.runtime_check(
+                    Property::DivisionByZero,
+                    *loc,
+                    format!("(= {} (_ bv0 {width}))", right.text),
+                );
+
+                let op = match expr {
+                    Expression::UnsignedDivide { .. } => "bvudiv",
+                    Expression::SignedDivide { .. } => "bvsdiv",
+                    Expression::UnsignedModulo { .. } => "bvurem",
+                    _ => "bvsrem",
+                };
+
+                Term::bitvec(format!("({op} {} {})", left.text, right.text), width)
+            }
+            Expression::Equal { left, right, .. } | Expression::NotEqual { left, right, .. } => {
+                let left = self.expr(left, state);
+                let right = self.expr(right, state);
+
+                if left.sort == Sort::Opaque || left.sort != right.sort {
+                    return self.declare("equal", Sort::Bool);
+                }
+
+                let op = if matches!(expr, Expression::Equal { .. }) {
+                    "="
+                } else {
+                    "distinct"
+                };
+
+                Term::bool(format!("({op} {} {})", left.text, right.text))
+            }
+            Expression::More {
+                signed,
+                left,
+                right,
+                ..
+            }
+            | Expression::MoreEqual {
+                signed,
+                left,
+                right,
+                ..
+            }
+            | Expression::Less {
+                signed,
+                left,
+                right,
+                ..
+            }
+            | Expression::LessEqual {
+                signed,
+                left,
+                right,
+                ..
+            } => {
+                let left = self.expr(left, state);
+                let right = self.expr(right, state);
+
+                if !matches!(left.sort, Sort::BitVec(_)) || left.sort != right.sort {
+                    return self.declare("compare", Sort::Bool);
+                }
+
+                let op = match (expr, signed) {
+                    (Expression::More { .. }, false) => "bvugt",
+                    (Expression::More { .. }, true) => "bvsgt",
+                    (Expression::MoreEqual { .. }, false) => "bvuge",
+                    (Expression::MoreEqual { .. }, true) => "bvsge",
+                    (Expression::Less { .. }, false) => "bvult",
+                    (Expression::Less { .. }, true) => "bvslt",
+                    (_, false) => "bvule",
+                    (_, true) => "bvsle",
+                };
+
+                Term::bool(format!("({op} {} {})", left.text, right.text))
+            }
+            Expression::Not { expr: operand, .. } => {
+                let operand = self.expr(operand, state);
+                let cond = self.condition(operand);
+
+                Term::bool(format!("(not {cond})"))
+            }
+            Expression::BitwiseAnd { left, right, .. }
+            | Expression::BitwiseOr { left, right, .. }
+            | Expression::BitwiseXor { left, right, .. } => {
+                let left = self.expr(left, state);
+                let right = self.expr(right, state);
+
+                if left.sort == Sort::Opaque || left.sort != right.sort {
+                    return self.declare("bitwise", expr_sort(expr, self.ns));
+                }
+
+                let op = match (expr, left.sort) {
+                    (Expression::BitwiseAnd { .. }, Sort::Bool) => "and",
+                    (Expression::BitwiseOr { .. }, Sort::Bool) => "or",
+                    (Expression::BitwiseXor { .. }, Sort::Bool) => "xor",
+                    (Expression::BitwiseAnd { .. }, _) => "bvand",
+                    (Expression::BitwiseOr { .. }, _) => "bvor",
+                    _ => "bvxor",
+                };
+
+                Term {
+                    text: format!("({op} {} {})", left.text, right.text),
+                    sort: left.sort,
+                }
+            }
+            Expression::BitwiseNot { expr: operand, .. } => {
+                let operand = self.expr(operand, state);
+
+                match operand.sort {
+                    Sort::Bool => Term::bool(format!("(not {})", operand.text)),
+                    Sort::BitVec(width) => Term::bitvec(format!("(bvnot {})", operand.text), width),
+                    Sort::Opaque => operand,
+                }
+            }
+            Expression::ShiftLeft { left, right, .. }
+            | Expression::ShiftRight { left, right, .. } => {
+                let left = self.expr(left, state);
+                let right = self.expr(right, state);
+
+                let Sort::BitVec(width) = left.sort else {
+                    return self.declare("shift", expr_sort(expr, self.ns));
+                };
+
+                let right = self.resize(right, left.sort, false);
+
+                let op = match expr {
+                    Expression::ShiftLeft { .. } => "bvshl",
+                    Expression::ShiftRight { signed: true, .. } => "bvashr",
+                    _ => "bvlshr",
+                };
+
+                Term::bitvec(format!("({op} {} {})", left.text, right.text), width)
+            }
+            Expression::ZeroExt {
+                ty, expr: operand, ..
+            }
+            | Expression::Trunc {
+                ty, expr: operand, ..
+            }
+            | Expression::Cast {
+                ty, expr: operand, ..
+            } => {
+                let operand = self.expr(operand, state);
+
+                self.resize(operand, Sort::of(ty, self.ns), false)
+            }
+            Expression::SignExt {
+                ty, expr: operand, ..
+            } => {
+                let operand = self.expr(operand, state);
+
+                self.resize(operand, Sort::of(ty, self.ns), true)
+            }
+            Expression::Keccak256 { ty, exprs, .. } => {
+                let args = exprs.iter().map(|expr| self.expr(expr, state)).collect();
+
+                self.hash("keccak256", args, Sort::of(ty, self.ns))
+            }
+            Expression::Builtin {
+                kind, tys, args, ..
+            } => {
+                let args: Vec<Term> = args.iter().map(|arg| self.expr(arg, state)).collect();
+
+                let sort = match tys.as_slice() {
+                    [ty] => Sort::of(ty, self.ns),
+                    _ => Sort::Opaque,
+                };
+
+                if !STABLE_BUILTINS.contains(kind) {
+                    return self.declare("builtin", sort);
+                }
+
+                let texts: Vec<&str> = args.iter().map(|arg| arg.text.as_str()).collect();
+                let key = format!("{kind:?}({})", texts.join(","));
+
+                if let Some(term) = self.memo.get(&key) {
+                    return term.clone();
+                }
+
+                let term = self.declare(&format!("{kind:?}"), sort);
+
+                self.memo.insert(key, term.clone());
+
+                term
+            }
+            _ => self.declare("value", expr_sort(expr, self.ns)),
+        }
+    }
+}
+
+fn expr_sort(expr: &Expression, ns: &Namespace) -> Sort {
+    match expr {
+        Expression::Poison => Sort::Opaque,
+        Expression::Builtin { tys, .. } if tys.len() != 1 => Sort::Opaque,
+        _ => Sort::of(&expr.ty(), ns),
+    }
+}
+
+/// The condition for overflow of a checked arithmetic operation
+fn overflow(op: &str, signed: bool, left: &str, right: &str, width: u16) -> String {
+    let sign = |term: &str| format!("((_ extract {0} {0}) {term})", width - 1);
+
+    match (op, signed) {
+        ("bvadd", false) => format!("(bvult (bvadd {left} {right}) {left})"),
+        ("bvsub", false) => format!("(bvult {left} {right})"),
+        ("bvmul", false) => format!(
+            "(distinct ((_ extract {} {width}) (bvmul ((_ zero_extend {width}) {left}) \
+            ((_ zero_extend {width}) {right}))) (_ bv0 {width}))",
+            width * 2 - 1
+        ),
+        ("bvadd", true) => format!(
+            "(and (= {} {}) (distinct {} {}))",
+            sign(left),
+            sign(right),
+            sign(&format!("(bvadd {left} {right})")),
+            sign(left)
+        ),
+        ("bvsub", true) => format!(
+            "(and (distinct {} {}) (distinct {} {}))",
+            sign(left),
+            sign(right),
+            sign(&format!("(bvsub {left} {right})")),
+            sign(left)
+        ),
+        _ => {
+            let product = format!(
+                "(bvmul ((_ sign_extend {width}) {left}) ((_ sign_extend {width}) {right}))"
+            );
+
+            format!(
+                "(distinct {product} ((_ sign_extend {width}) ((_ extract {} 0) {product})))",
+                width - 1
+            )
+        }
+    }
+}
+
+/// If the block ends in a failure which should never happen, which property is it. The failure
+/// is recognized by the message which is printed when runtime errors are logged.
+fn failure(instrs: &[Instr]) -> Option<Property> {
+    if !matches!(instrs.last(), Some(Instr::AssertFailure { .. })) {
+        return None;
+    }
+
+    instrs.iter().find_map(|instr| {
+        let Instr::Print {
+            expr: Expression::FormatString { args, .. },
+        } = instr
+        else {
+            return None;
+        };
+
+        let Some((_, Expression::BytesLiteral { value, .. })) = args.first() else {
+            return None;
+        };
+
+        let message = String::from_utf8_lossy(value);
+        let reason = message
+            .strip_prefix("runtime_error: ")?
+            .split(" in ")
+            .next()?;
+
+        match reason {
+            "assert failure" => Some(Property::Assert),
+            "array index out of bounds" => Some(Property::Bounds),
+            "truncated type overflows" => Some(Property::Overflow),
+            _ => None,
+        }
+    })
+}
+
+/// The blocks in topological order, ignoring the edges which loop back
+fn order(cfg: &ControlFlowGraph, back_edges: &HashSet<(usize, usize)>) -> Vec<usize> {
+    let mut visited = vec![false; cfg.blocks.len()];
+    let mut post_order = Vec::new();
+    let mut stack = vec![(0, cfg.blocks[0].successors(), 0)];
+
+    visited[0] = true;
+
+    while let Some((block_no, succs, next)) = stack.last_mut() {
+        if let Some(succ) = succs.get(*next).cloned() {
+            *next += 1;
+
+            if !visited[succ] && !back_edges.contains(&(*block_no, succ)) {
+                visited[succ] = true;
+                stack.push((succ, cfg.blocks[succ].successors(), 0));
+            }
+        } else {
+            post_order.push(*block_no);
+            stack.pop();
+        }
+    }
+
+    post_order.reverse();
+
+    post_order
+}
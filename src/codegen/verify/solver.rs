@@ -0,0 +1,203 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Run an SMT solver on a script, and read its answers. The solver must accept SMT-LIB on its
+//! standard input like `z3 -in`.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+pub struct Solver {
+    /// The solver executable, which is searched for in `PATH` if it is not a path
+    pub path: PathBuf,
+    /// Give up on a check after this many milliseconds
+    pub timeout: Option<u64>,
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Solver {
+            path: PathBuf::from("z3"),
+            timeout: None,
+        }
+    }
+}
+
+impl Solver {
+    /// Run the solver on the script, and return what it printed
+    pub fn run(&self, script: &str) -> Result<String, String> {
+        let mut command = Command::new(&self.path);
+
+        command.arg("-in").arg("-smt2");
+
+        if let Some(timeout) = self.timeout {
+            command.arg(format!("-t:{timeout}"));
+        }
+
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| format!("cannot run solver '{}': {err}", self.path.display()))?;
+
+        // write the script from another thread, so that the solver does not block on a full
+        // output pipe while it is still being given input
+        let mut stdin = child.stdin.take().unwrap();
+        let script = script.to_owned();
+        let writer = std::thread::spawn(move || stdin.write_all(script.as_bytes()));
+
+        let output = child
+            .wait_with_output()
+            .map_err(|err| format!("solver '{}' failed: {err}", self.path.display()))?;
+
+        if let Ok(Err(err)) = writer.join() {
+            return Err(format!(
+                "cannot write to solver '{}': {err}",
+                self.path.display()
+            ));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Answer {
+    /// The failure is possible, with these values
+    Sat(Vec<(String, String)>),
+    Unsat,
+    Unknown,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum SExpr {
+    Atom(String),
+    List(Vec<SExpr>),
+}
+
+impl std::fmt::Display for SExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SExpr::Atom(atom) => write!(f, "{atom}"),
+            SExpr::List(list) => {
+                write!(f, "(")?;
+
+                for (no, expr) in list.iter().enumerate() {
+                    if no > 0 {
+                        write!(f, " ")?;
+                    }
+
+                    write!(f, "{expr}")?;
+                }
+
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// Split the output of the solver into s-expressions. Unbalanced parentheses are ignored.
+fn parse(output: &str) -> Vec<SExpr> {
+    let mut stack: Vec<Vec<SExpr>> = vec![Vec::new()];
+    let mut chars = output.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '(' => stack.push(Vec::new()),
+            ')' => {
+                if stack.len() > 1 {
+                    let list = stack.pop().unwrap();
+                    stack.last_mut().unwrap().push(SExpr::List(list));
+                }
+            }
+            c if c.is_whitespace() => (),
+            '"' | '|' => {
+                let mut atom = String::from(c);
+
+                while let Some(next) = chars.next() {
+                    atom.push(next);
+
+                    if next == c {
+                        // a double quote is escaped by repeating it
+                        if c == '"' && chars.peek() == Some(&'"') {
+                            atom.push(chars.next().unwrap());
+                        } else {
+                            break;
+                        }
+                    }
+                }
+
+                stack.last_mut().unwrap().push(SExpr::Atom(atom));
+            }
+            _ => {
+                let mut atom = String::from(c);
+
+                while let Some(next) = chars.peek() {
+                    if next.is_whitespace() || *next == '(' || *next == ')' {
+                        break;
+                    }
+
+                    atom.push(chars.next().unwrap());
+                }
+
+                stack.last_mut().unwrap().push(SExpr::Atom(atom));
+            }
+        }
+    }
+
+    while stack.len() > 1 {
+        let list = stack.pop().unwrap();
+        stack.last_mut().unwrap().push(SExpr::List(list));
+    }
+
+    stack.pop().unwrap()
+}
+
+/// Read the answer to each check from the output of the solver. Each check is preceded by
+/// `check <no>`, and a satisfiable check is followed by the values of the model.
+pub fn answers(output: &str) -> BTreeMap<usize, Answer> {
+    let mut answers = BTreeMap::new();
+    let mut current = None;
+    let mut exprs = parse(output).into_iter().peekable();
+
+    while let Some(expr) = exprs.next() {
+        match expr {
+            SExpr::Atom(atom) if atom == "check" => {
+                if let Some(SExpr::Atom(no)) = exprs.peek() {
+                    current = no.parse().ok();
+                    exprs.next();
+                }
+            }
+            SExpr::Atom(atom) => {
+                let answer = match atom.as_str() {
+                    "sat" => Answer::Sat(Vec::new()),
+                    "unsat" => Answer::Unsat,
+                    "unknown" => Answer::Unknown,
+                    _ => continue,
+                };
+
+                if let Some(no) = current {
+                    answers.insert(no, answer);
+                }
+            }
+            SExpr::List(list) => {
+                let Some(Answer::Sat(values)) = current.and_then(|no| answers.get_mut(&no)) else {
+                    continue;
+                };
+
+                // errors like "model is not available" are skipped
+                for pair in list {
+                    if let SExpr::List(pair) = pair {
+                        if let [name, value] = pair.as_slice() {
+                            values.push((name.to_string(), value.to_string()));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    answers
+}
@@ -0,0 +1,284 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(test)]
+
+use super::solver::{answers, Answer};
+use super::{diagnostic, display_value, scripts, Outcome, Property, Script};
+use crate::codegen::Options;
+use crate::sema::ast::{Namespace, Type};
+use crate::sema::tests::resolve_and_codegen;
+use crate::Target;
+
+fn compile(src: &str) -> (Namespace, Options) {
+    let opt = Options {
+        log_runtime_errors: true,
+        ..Default::default()
+    };
+
+    (resolve_and_codegen(src, Target::EVM, &opt), opt)
+}
+
+/// The name of the function of each script, and the properties it checks
+fn checks(ns: &Namespace, scripts: &[Script]) -> Vec<(String, Vec<Property>)> {
+    scripts
+        .iter()
+        .map(|script| {
+            let name = script
+                .function_no
+                .map(|no| ns.functions[no].id.name.clone())
+                .unwrap_or_default();
+
+            (
+                name,
+                script.checks.iter().map(|check| check.property).collect(),
+            )
+        })
+        .collect()
+}
+
+const CONTRACT: &str = r#"
+    /// @custom:invariant total <= 100
+    contract c {
+        uint8 total;
+        int32[4] arr;
+
+        function add(uint8 a, uint8 b) public pure returns (uint8) {
+            return a + b;
+        }
+
+        function div(int16 a, int16 b) public pure returns (int16) {
+            return a / b;
+        }
+
+        function get(uint i) public view returns (int32) {
+            return arr[i];
+        }
+
+        function check(uint8 x) public pure {
+            assert(x != 7);
+        }
+
+        function set(uint8 x) public {
+            require(x <= 100);
+            total = x;
+        }
+
+        function unchecked_add(uint8 x) public {
+            unchecked {
+                total += x;
+            }
+        }
+    }"#;
+
+#[test]
+fn properties() {
+    let (mut ns, opt) = compile(CONTRACT);
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let scripts = scripts(0, &mut ns, &opt);
+
+    assert_eq!(
+        checks(&ns, &scripts),
+        vec![
+            ("add".into(), vec![Property::Overflow]),
+            ("div".into(), vec![Property::DivisionByZero]),
+            ("get".into(), vec![Property::Bounds]),
+            ("check".into(), vec![Property::Assert]),
+            ("set".into(), vec![Property::Invariant(0)]),
+            ("unchecked_add".into(), vec![Property::Invariant(0)]),
+            // the default constructor establishes the invariant
+            ("".into(), vec![Property::Invariant(0)]),
+        ]
+    );
+
+    // the invariant is assumed when a function is called
+    let set = &scripts[4].text;
+
+    assert!(set.contains("(declare-const total."));
+    assert!(set.contains("(check-sat)"));
+    assert!(set.contains("(echo \"check 0\")"));
+}
+
+#[test]
+fn loops_and_calls() {
+    let (mut ns, opt) = compile(
+        r#"
+        contract c {
+            uint64[10] values;
+
+            function sum() public view returns (uint64 total) {
+                for (uint i = 0; i < values.length; i++) {
+                    total += values[i];
+                }
+            }
+
+            function twice(uint32 x) public pure returns (uint32) {
+                return double(x);
+            }
+
+            function double(uint32 x) internal pure returns (uint32) {
+                return x * 2;
+            }
+        }"#,
+    );
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let scripts = scripts(0, &mut ns, &opt);
+
+    assert_eq!(
+        checks(&ns, &scripts),
+        vec![
+            // the bounds check and the overflow of the loop counter are removed by the optimizer
+            ("sum".into(), vec![Property::Overflow]),
+            ("twice".into(), vec![Property::Overflow]),
+            ("".into(), vec![]),
+        ]
+    );
+}
+
+#[test]
+fn invariant_errors() {
+    let (mut ns, opt) = compile(
+        r#"
+        /// @custom:invariant count >= 0
+        /// @custom:invariant missing > 1
+        /// @custom:invariant count +
+        contract c {
+            int32 count;
+
+            function inc() public {
+                count += 1;
+            }
+        }"#,
+    );
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let scripts = scripts(0, &mut ns, &opt);
+
+    let errors: Vec<&str> = ns
+        .diagnostics
+        .iter()
+        .filter(|diag| diag.level == crate::sema::ast::Level::Error)
+        .map(|diag| diag.message.as_str())
+        .collect();
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0], "'missing' not found");
+
+    // only the valid invariant is checked
+    assert_eq!(
+        checks(&ns, &scripts),
+        vec![
+            (
+                "inc".into(),
+                vec![Property::Overflow, Property::Invariant(0)]
+            ),
+            ("".into(), vec![Property::Invariant(0)]),
+        ]
+    );
+}
+
+#[test]
+fn solver_output() {
+    let output = r#"check 0
+sat
+((a.1 #xff)
+ (b.2 #x01))
+check 1
+unsat
+check 2
+unknown
+check 3
+sat
+(error "line 40 column 10: model is not available")
+"#;
+
+    let answers = answers(output);
+
+    assert_eq!(
+        answers.get(&0),
+        Some(&Answer::Sat(vec![
+            ("a.1".into(), "#xff".into()),
+            ("b.2".into(), "#x01".into())
+        ]))
+    );
+    assert_eq!(answers.get(&1), Some(&Answer::Unsat));
+    assert_eq!(answers.get(&2), Some(&Answer::Unknown));
+    assert_eq!(answers.get(&3), Some(&Answer::Sat(Vec::new())));
+    assert_eq!(answers.get(&4), None);
+}
+
+#[test]
+fn verdicts() {
+    let (mut ns, opt) = compile(CONTRACT);
+
+    let scripts = scripts(0, &mut ns, &opt);
+
+    // add(uint8 a, uint8 b)
+    let verdicts = scripts[0].verdicts(
+        "check 0\nsat\n((a.1 #xff) (b.2 #x01) (total.4 #x00))\n",
+        &ns,
+    );
+
+    assert_eq!(verdicts.len(), 1);
+    assert_eq!(verdicts[0].property, Property::Overflow);
+    assert_eq!(
+        verdicts[0].outcome,
+        Outcome::Fails(vec![
+            ("a".into(), "255".into()),
+            ("b".into(), "1".into()),
+            ("total".into(), "0".into())
+        ])
+    );
+
+    let diag = diagnostic(&verdicts[0], 0, &ns).unwrap();
+
+    assert_eq!(
+        diag.message,
+        "arithmetic overflow is possible, for example when a = 255, b = 1, total = 0"
+    );
+    assert!(diag.notes.is_empty());
+
+    // set(uint8 x)
+    let verdicts = scripts[4].verdicts("check 0\nunsat\n", &ns);
+
+    assert_eq!(verdicts[0].outcome, Outcome::Holds);
+    assert!(diagnostic(&verdicts[0], 0, &ns).is_none());
+
+    // unchecked_add(uint8 x)
+    let verdicts = scripts[5].verdicts("", &ns);
+
+    assert_eq!(verdicts[0].outcome, Outcome::Unknown);
+
+    let diag = diagnostic(&verdicts[0], 0, &ns).unwrap();
+
+    assert_eq!(
+        diag.message,
+        "solver cannot decide whether invariant violation is possible"
+    );
+    assert_eq!(diag.notes.len(), 1);
+    assert_eq!(diag.notes[0].message, "invariant 'total <= 100'");
+}
+
+#[test]
+fn values() {
+    let ns = Namespace::new(Target::EVM);
+
+    assert_eq!(display_value("#xff", &Type::Uint(8), &ns), "255");
+    assert_eq!(display_value("#xff", &Type::Int(8), &ns), "-1");
+    assert_eq!(display_value("#x7f", &Type::Int(8), &ns), "127");
+    assert_eq!(display_value("#b1", &Type::Bool, &ns), "1");
+    assert_eq!(display_value("true", &Type::Bool, &ns), "true");
+    assert_eq!(display_value("#x00ab", &Type::Bytes(2), &ns), "0x00ab");
+    assert_eq!(
+        display_value(
+            "#x000000000000000000000000000000000000beef",
+            &Type::Address(false),
+            &ns
+        ),
+        "0x000000000000000000000000000000000000beef"
+    );
+}
@@ -3,7 +3,9 @@
 use crate::{
     codegen::{
         cfg::{ControlFlowGraph, Instr},
+        expression::{code_size, load_storage},
         revert::{assert_failure, log_runtime_error, PanicCode, SolidityError},
+        storage::{load_packed, store_packed},
         vartable::Vartable,
         yul::expression::expression,
         {Builtin, Expression, Options},
@@ -138,6 +140,10 @@ pub(crate) fn process_builtin(
         // origin is the same as tx.origin and is not implemented
         | YulBuiltInFunction::Origin
         | YulBuiltInFunction::PrevRandao
+        // Functions that refer to the layout of the EVM bytecode, or to libraries linked into it
+        | YulBuiltInFunction::DataOffset
+        | YulBuiltInFunction::DataCopy
+        | YulBuiltInFunction::LinkerSymbol
         => {
             if ns.target != Target::EVM {
                 let function_ty = builtin_ty.get_prototype_info();
@@ -235,9 +241,89 @@ pub(crate) fn process_builtin(
         YulBuiltInFunction::GasLimit => {
             Expression::Builtin { loc: *loc, tys: vec![Type::Uint(64)], kind: Builtin::GasLimit, args: vec![] }
         }
+
+        YulBuiltInFunction::DataSize => {
+            if ns.target == Target::EVM {
+                // Sema will only allow this for EVM and Polkadot. This is a placeholder until correct codegen is in place
+                cfg.add(vartab, Instr::Unimplemented { reachable: true });
+                return Expression::Poison;
+            }
+
+            let no = literal_contract(&args[0], ns);
+            code_size(loc, no, ns, opt)
+        }
+
+        YulBuiltInFunction::LoadImmutable => {
+            let (var_contract_no, var_no) = literal_immutable(&args[0], contract_no, ns);
+            let var = &ns.contracts[var_contract_no].variables[var_no];
+            if let Some(layout) = ns.contracts[contract_no].get_packed_layout(var_contract_no, var_no) {
+                return load_packed(loc, layout, cfg, vartab, ns);
+            }
+
+            let storage = ns.contracts[contract_no].get_storage_slot(*loc, var_contract_no, var_no, ns, None);
+
+            load_storage(loc, &var.ty, storage, cfg, vartab)
+        }
+
+        YulBuiltInFunction::SetImmutable => {
+            let (var_contract_no, var_no) = literal_immutable(&args[1], contract_no, ns);
+            let ty = ns.contracts[var_contract_no].variables[var_no].ty.clone();
+            let storage = ns.contracts[contract_no].get_storage_slot(*loc, var_contract_no, var_no, ns, None);
+            // The offset into the code is meaningless when immutables live in contract storage
+            let value = expression(&args[2], contract_no, ns, vartab, cfg, opt).cast(&ty, ns);
+
+            if let Some(layout) = ns.contracts[contract_no].get_packed_layout(var_contract_no, var_no) {
+                store_packed(loc, layout, value, cfg, vartab, ns);
+            } else {
+                cfg.add(vartab, Instr::SetStorage { ty, value, storage });
+            }
+            Expression::Poison
+        }
+
+        YulBuiltInFunction::MemoryGuard => {
+            // Solang does not move variables to memory, so there is nothing to guard
+            expression(&args[0], contract_no, ns, vartab, cfg, opt)
+        }
     }
 }
 
+/// Find the contract named by the string literal argument of a builtin. Sema has checked it exists.
+fn literal_contract(arg: &ast::YulExpression, ns: &Namespace) -> usize {
+    let ast::YulExpression::StringLiteral(loc, name, _) = arg else {
+        unreachable!();
+    };
+
+    let id = pt::Identifier {
+        loc: *loc,
+        name: String::from_utf8_lossy(name).to_string(),
+    };
+
+    ns.resolve_contract(loc.file_no(), &id).unwrap()
+}
+
+/// Find the immutable variable named by the string literal argument of a builtin. Sema has checked
+/// it exists. Immutables are stored in contract storage, so it must be part of the storage layout.
+fn literal_immutable(
+    arg: &ast::YulExpression,
+    contract_no: usize,
+    ns: &Namespace,
+) -> (usize, usize) {
+    let ast::YulExpression::StringLiteral(_, name, _) = arg else {
+        unreachable!();
+    };
+
+    ns.contracts[contract_no]
+        .layout
+        .iter()
+        .find(|l| {
+            let var = &ns.contracts[l.contract_no].variables[l.var_no];
+
+            var.immutable && var.name.as_bytes() == name
+        })
+        .map(|l| (l.contract_no, l.var_no))
+        .unwrap()
+}
+
 /// Process arithmetic operations
 fn process_arithmetic(
     loc: &pt::Loc,
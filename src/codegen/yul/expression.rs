@@ -144,8 +144,23 @@ fn process_suffix_access(
             _ => (),
         },
         YulSuffix::Offset => match expr {
-            ast::YulExpression::StorageVariable(..)
-            | ast::YulExpression::SolidityLocalVariable(
+            ast::YulExpression::StorageVariable(_, _, var_contract_no, var_no) => {
+                // variables are only packed into storage slots on Polkadot
+                let offset = if ns.target.is_polkadot() {
+                    ns.contracts[contract_no]
+                        .get_packed_layout(*var_contract_no, *var_no)
+                        .map_or(0, |layout| layout.packed.as_ref().unwrap().offset)
+                } else {
+                    0
+                };
+
+                return Expression::NumberLiteral {
+                    loc: Loc::Codegen,
+                    ty: Type::Uint(256),
+                    value: BigInt::from(offset),
+                };
+            }
+            ast::YulExpression::SolidityLocalVariable(
                 _,
                 _,
                 Some(StorageLocation::Storage(_)),
@@ -7,11 +7,12 @@ use crate::codegen::cfg::{
 use crate::codegen::statements::LoopScopes;
 use crate::codegen::vartable::Vartable;
 use crate::codegen::yul::statements::statement;
-use crate::codegen::{Expression, Options};
+use crate::codegen::{inline, Expression, Options};
 use crate::sema::ast::Namespace;
 use crate::sema::yul::ast::InlineAssembly;
 use solang_parser::pt;
 use solang_parser::pt::FunctionTy;
+use std::collections::HashMap;
 
 mod builtin;
 mod expression;
@@ -38,13 +39,19 @@ pub(crate) fn generate_yul_function_cfg(
     contract_no: usize,
     function_no: usize,
     all_cfgs: &mut [ControlFlowGraph],
+    inline_bodies: &mut HashMap<usize, ControlFlowGraph>,
     ns: &mut Namespace,
     opt: &Options,
 ) {
     let mut cfg = yul_function_cfg(contract_no, function_no, ns, opt);
+    let cfg_no = ns.yul_functions[function_no].cfg_no;
+
+    if inline::enabled(opt) {
+        inline_bodies.insert(cfg_no, cfg.clone());
+    }
 
     optimize_and_check_cfg(&mut cfg, ns, ASTFunction::YulFunction(function_no), opt);
-    all_cfgs[ns.yul_functions[function_no].cfg_no] = cfg;
+    all_cfgs[cfg_no] = cfg;
 }
 
 /// Generate the CFG containing all the instructions from a YUL function
@@ -147,6 +147,7 @@ fn contract_constant_variable() {
         using: vec![],
         layout: vec![],
         fixed_layout_size: Default::default(),
+        layout_base: None,
         functions: vec![],
         all_functions: Default::default(),
         virtual_functions: Default::default(),
@@ -272,6 +273,7 @@ fn slot_suffix() {
         contract_no: 0,
         var_no: 0,
         ty: Type::Uint(256),
+        packed: None,
     };
     let contract = Contract {
         tags: vec![],
@@ -285,6 +287,7 @@ fn slot_suffix() {
         using: vec![],
         layout: vec![layout],
         fixed_layout_size: Default::default(),
+        layout_base: None,
         functions: vec![],
         all_functions: Default::default(),
         virtual_functions: Default::default(),
@@ -10,7 +10,7 @@ use std::str;
 
 use num_bigint::BigInt;
 use num_traits::ToPrimitive;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 #[cfg(feature = "wasm_opt")]
 use tempfile::tempdir;
 #[cfg(feature = "wasm_opt")]
@@ -307,6 +307,24 @@ impl<'a> Binary<'a> {
         }
     }
 
+    /// The names of the functions defined in the standard library, which is built from
+    /// `stdlib/*.c` and linked into the module
+    pub fn stdlib_functions(&self) -> HashSet<String> {
+        let std_lib = load_stdlib(self.context, &self.target);
+        let mut names = HashSet::new();
+        let mut func = std_lib.get_first_function();
+
+        while let Some(f) = func {
+            if f.count_basic_blocks() > 0 {
+                names.insert(f.get_name().to_str().unwrap().to_owned());
+            }
+
+            func = f.get_next_function();
+        }
+
+        names
+    }
+
     pub fn bitcode(&self, path: &Path) {
         self.module.write_bitcode_to_path(path);
     }
@@ -1243,7 +1261,10 @@ impl<'a> Binary<'a> {
             value: (code as u8).into(),
         };
         let bytes = create_encoder(ns, false)
-            .const_encode(&[SolidityError::Panic(code).selector_expression(ns), expr])
+            .const_encode(
+                &[SolidityError::Panic(code).selector_expression(ns), expr],
+                ns,
+            )
             .unwrap();
         (
             self.emit_global_string(&code.to_string(), &bytes, true),
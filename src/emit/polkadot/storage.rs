@@ -312,6 +312,11 @@ impl StorageSlot for PolkadotTarget {
 
                 let ret = self.get_storage_int(bin, function, slot_ptr, ptr_ty);
 
+                *slot = bin
+                    .builder
+                    .build_int_add(*slot, bin.number_literal(256, &BigInt::one(), ns), "int")
+                    .unwrap();
+
                 bin.builder
                     .build_int_to_ptr(
                         ret,
@@ -15,6 +15,8 @@ use std::sync::Arc;
 pub struct FileResolver {
     /// Set of import paths search for imports
     import_paths: Vec<(Option<OsString>, PathBuf)>,
+    /// Import maps which only apply to files in a context, i.e. `context:map=path`
+    context_maps: Vec<(OsString, OsString, PathBuf)>,
     /// List file by path
     cached_paths: HashMap<PathBuf, usize>,
     /// The actual file contents
@@ -31,12 +33,32 @@ pub struct ResolvedFile {
     pub path: OsString,
     /// Full path on the filesystem
     pub full_path: PathBuf,
+    /// Source unit name, i.e. the import after applying remappings. This is used for
+    /// matching the context of import maps
+    pub source_unit_name: PathBuf,
     /// Which import path was used, if any
     pub import_no: Option<usize>,
     /// The actual file contents
     pub contents: Arc<str>,
 }
 
+/// An import could not be resolved. The notes describe which import maps were
+/// applied and which files were tried.
+#[derive(Debug)]
+pub struct ResolveError {
+    pub message: String,
+    pub notes: Vec<String>,
+}
+
+impl ResolveError {
+    fn new(message: String) -> Self {
+        ResolveError {
+            message,
+            notes: Vec::new(),
+        }
+    }
+}
+
 impl FileResolver {
     /// Add import path
     pub fn add_import_path(&mut self, path: &Path) {
@@ -56,6 +78,24 @@ impl FileResolver {
         }
     }
 
+    /// Add import map which only applies to files whose source unit name starts with `context`
+    pub fn add_context_import_map(&mut self, context: OsString, map: OsString, path: PathBuf) {
+        if let Some((_, _, e)) = self
+            .context_maps
+            .iter_mut()
+            .find(|(c, m, _)| *c == context && *m == map)
+        {
+            *e = path;
+        } else {
+            self.context_maps.push((context, map, path));
+        }
+    }
+
+    /// Get the import maps which have a context
+    pub fn get_context_import_maps(&self) -> &[(OsString, OsString, PathBuf)] {
+        self.context_maps.as_slice()
+    }
+
     /// Get the import path and the optional mapping corresponding to `import_no`.
     pub fn get_import_path(&self, import_no: usize) -> Option<&(Option<OsString>, PathBuf)> {
         self.import_paths.get(import_no)
@@ -82,6 +122,7 @@ impl FileResolver {
 
         self.files.push(ResolvedFile {
             path: path.into(),
+            source_unit_name: pathbuf.clone(),
             full_path: pathbuf.clone(),
             contents: Arc::from(contents),
             import_no: None,
@@ -110,21 +151,27 @@ impl FileResolver {
     fn try_file(
         &mut self,
         filename: &OsStr,
+        source_unit_name: &Path,
         path: &Path,
         import_no: Option<usize>,
-    ) -> Result<Option<ResolvedFile>, String> {
+    ) -> Result<Option<ResolvedFile>, ResolveError> {
         // For accessing the cache, remove "." and ".." path components
         let cache_path = path.normalize();
 
         if let Some(cache) = self.cached_paths.get(&cache_path) {
             let mut file = self.files[*cache].clone();
             file.import_no = import_no;
+            file.source_unit_name = source_unit_name.to_path_buf();
             return Ok(Some(file));
         }
 
         if let Ok(full_path) = path.canonicalize() {
-            let file = self.load_file(filename, &full_path, import_no)?;
-            return Ok(Some(file.clone()));
+            let mut file = self
+                .load_file(filename, &full_path, import_no)
+                .map_err(ResolveError::new)?
+                .clone();
+            file.source_unit_name = source_unit_name.to_path_buf();
+            return Ok(Some(file));
         }
 
         Ok(None)
@@ -165,6 +212,7 @@ impl FileResolver {
 
         self.files.push(ResolvedFile {
             path: filename.into(),
+            source_unit_name: PathBuf::from(filename),
             full_path: path.to_path_buf(),
             import_no,
             contents: Arc::from(contents),
@@ -182,7 +230,7 @@ impl FileResolver {
         &mut self,
         parent: Option<&ResolvedFile>,
         filename: &OsStr,
-    ) -> Result<ResolvedFile, String> {
+    ) -> Result<ResolvedFile, ResolveError> {
         let path_filename = PathBuf::from(filename);
 
         // See https://docs.soliditylang.org/en/v0.8.17/path-resolution.html
@@ -195,50 +243,79 @@ impl FileResolver {
             if let Some(ResolvedFile {
                 import_no,
                 full_path,
+                source_unit_name,
                 ..
             }) = parent
             {
                 let curdir = PathBuf::from(".");
                 let base = full_path.parent().unwrap_or(&curdir);
                 let path = base.join(&path_filename);
+                let source_unit_name = source_unit_name
+                    .parent()
+                    .unwrap_or(&curdir)
+                    .join(&path_filename)
+                    .normalize();
 
-                if let Some(file) = self.try_file(filename, &path, *import_no)? {
+                if let Some(file) = self.try_file(filename, &source_unit_name, &path, *import_no)? {
                     // No ambiguity possible, so just return
                     return Ok(file);
                 }
             }
 
-            return Err(format!("file not found '{}'", path_filename.display()));
+            return Err(ResolveError::new(format!(
+                "file not found '{}'",
+                path_filename.display()
+            )));
         }
 
         if parent.is_none() {
-            if let Some(file) = self.try_file(filename, &path_filename, None)? {
+            if let Some(file) = self.try_file(filename, &path_filename, &path_filename, None)? {
                 return Ok(file);
             } else if path_filename.is_absolute() {
-                return Err(format!("file not found '{}'", path_filename.display()));
+                return Err(ResolveError::new(format!(
+                    "file not found '{}'",
+                    path_filename.display()
+                )));
             }
         }
 
-        // first check maps
-        let mut remapped = path_filename.clone();
+        let mut tried = Vec::new();
 
-        for import_map_no in 0..self.import_paths.len() {
-            if let (Some(mapping), target) = &self.import_paths[import_map_no].clone() {
-                if let Ok(relpath) = path_filename.strip_prefix(mapping) {
-                    remapped = target.join(relpath);
-                }
-            }
-        }
+        // first check maps
+        let path = if let Some((context, mapping, target)) = self.find_import_map(
+            parent.map(|file| file.source_unit_name.as_path()),
+            &path_filename,
+        ) {
+            // like solc, the map is a plain string prefix which is replaced by the target
+            let mut remapped = target.clone().into_os_string();
+            remapped.push(&path_filename.to_string_lossy()[mapping.to_string_lossy().len()..]);
+            let remapped = PathBuf::from(remapped);
+
+            tried.push(format!(
+                "import map '{}{}={}' maps '{}' to '{}'",
+                context
+                    .map(|context| format!("{}:", context.to_string_lossy()))
+                    .unwrap_or_default(),
+                mapping.to_string_lossy(),
+                target.display(),
+                path_filename.display(),
+                remapped.display()
+            ));
 
-        let path = remapped;
+            remapped
+        } else {
+            path_filename.clone()
+        };
 
         // walk over the import paths until we find one that resolves
         for import_no in 0..self.import_paths.len() {
             if let (None, import_path) = &self.import_paths[import_no] {
-                let path = import_path.join(&path);
+                let full_path = import_path.join(&path);
 
-                if let Some(file) = self.try_file(filename, &path, Some(import_no))? {
+                if let Some(file) = self.try_file(filename, &path, &full_path, Some(import_no))? {
                     result.push(file);
+                } else {
+                    tried.push(format!("file '{}' does not exist", full_path.display()));
                 }
             }
         }
@@ -247,25 +324,69 @@ impl FileResolver {
         // https://docs.soliditylang.org/en/v0.8.17/path-resolution.html#base-path-and-include-paths
         // "By default the base path is empty, which leaves the source unit name unchanged."
         if !self.import_paths.iter().any(|(m, _)| m.is_none()) {
-            if let Some(file) = self.try_file(filename, &path, None)? {
+            if let Some(file) = self.try_file(filename, &path, &path, None)? {
                 result.push(file);
+            } else {
+                tried.push(format!("file '{}' does not exist", path.display()));
             }
         }
 
         match result.len() {
-            0 => Err(format!("file not found '{}'", path_filename.display())),
+            0 => Err(ResolveError {
+                message: format!("file not found '{}'", path_filename.display()),
+                notes: tried,
+            }),
             1 => Ok(result.pop().unwrap()),
-            _ => Err(format!(
+            _ => Err(ResolveError::new(format!(
                 "found multiple files matching '{}': {}",
                 path_filename.display(),
                 result
                     .iter()
                     .map(|f| format!("'{}'", f.full_path.display()))
                     .join(", ")
-            )),
+            ))),
         }
     }
 
+    /// Find the import map which applies to the import. Like solc, the context and the map
+    /// are matched as string prefixes, not path components, so `lib/a` also matches `lib/ab.sol`.
+    /// The map with the longest matching context is used, then the one with the longest matching
+    /// prefix. If there is still more than one, the last one specified wins.
+    fn find_import_map(
+        &self,
+        source_unit_name: Option<&Path>,
+        filename: &Path,
+    ) -> Option<(Option<OsString>, OsString, PathBuf)> {
+        let maps = self
+            .import_paths
+            .iter()
+            .filter_map(|(mapping, target)| {
+                mapping
+                    .as_ref()
+                    .map(|mapping| (None, mapping.clone(), target.clone()))
+            })
+            .chain(
+                self.context_maps
+                    .iter()
+                    .filter(|(context, ..)| {
+                        source_unit_name.is_some_and(|name| {
+                            name.to_string_lossy()
+                                .starts_with(context.to_string_lossy().as_ref())
+                        })
+                    })
+                    .map(|(context, mapping, target)| {
+                        (Some(context.clone()), mapping.clone(), target.clone())
+                    }),
+            );
+
+        let filename = filename.to_string_lossy();
+
+        maps.filter(|(_, mapping, _)| filename.starts_with(mapping.to_string_lossy().as_ref()))
+            .max_by_key(|(context, mapping, _)| {
+                (context.as_ref().map_or(0, |c| c.len()), mapping.len())
+            })
+    }
+
     /// Get line and the target symbol's offset from loc
     pub fn get_line_and_offset_from_loc(
         &self,
@@ -1,12 +1,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod abi;
+pub mod bindings;
 pub mod codegen;
 #[cfg(feature = "llvm")]
 pub mod emit;
 pub mod file_resolver;
 #[cfg(feature = "llvm")]
 mod linker;
+pub mod lint;
 pub mod standard_json;
 
 // In Sema, we use result unit for returning early
@@ -150,7 +152,15 @@ pub fn compile(
         if contract.instantiable {
             let code = contract.emit(&ns, opts, contract_no);
 
-            let (abistr, _) = abi::generate_abi(contract_no, &ns, &code, false, &authors, version);
+            let (abistr, _) = abi::generate_abi(
+                contract_no,
+                &ns,
+                &code,
+                false,
+                &authors,
+                version,
+                abi::anchor::IdlSpec::default(),
+            );
 
             results.push((code, abistr));
         };
@@ -172,11 +182,11 @@ pub fn parse_and_resolve(
     let mut ns = sema::ast::Namespace::new(target);
 
     match resolver.resolve_file(None, filename) {
-        Err(message) => {
+        Err(err) => {
             ns.diagnostics.push(sema::ast::Diagnostic {
                 ty: sema::ast::ErrorType::ParserError,
                 level: sema::ast::Level::Error,
-                message,
+                message: err.message,
                 loc: pt::Loc::CommandLine,
                 notes: Vec::new(),
             });
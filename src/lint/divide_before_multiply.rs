@@ -0,0 +1,44 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{functions_with_body, recurse_expressions, strip_casts, Detector};
+use crate::sema::ast::{Diagnostic, Expression, Namespace};
+use crate::sema::diagnostics::Diagnostics;
+
+/// Integer division rounds down, so dividing before multiplying loses precision. For example,
+/// `a / b * c` should usually be written as `a * c / b`.
+pub struct DivideBeforeMultiply;
+
+impl Detector for DivideBeforeMultiply {
+    fn id(&self) -> &'static str {
+        "divide-before-multiply"
+    }
+
+    fn description(&self) -> &'static str {
+        "the result of a division is multiplied, which loses precision"
+    }
+
+    fn check(&self, ns: &Namespace, diagnostics: &mut Diagnostics) {
+        for func in functions_with_body(ns) {
+            recurse_expressions(func, diagnostics, multiply);
+        }
+    }
+}
+
+fn multiply(expr: &Expression, diagnostics: &mut Diagnostics) -> bool {
+    if let Expression::Multiply {
+        loc, left, right, ..
+    } = expr
+    {
+        if [left, right]
+            .iter()
+            .any(|operand| matches!(strip_casts(operand), Expression::Divide { .. }))
+        {
+            diagnostics.push(Diagnostic::warning(
+                *loc,
+                "division is performed before multiplication, which may truncate the result".into(),
+            ));
+        }
+    }
+
+    true
+}
@@ -0,0 +1,233 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Security linter for Solidity. Each detector looks for a pattern in the resolved namespace
+//! which is likely to be a bug or vulnerability, and reports it as a warning. The findings can be
+//! printed like the compiler diagnostics, or written as a SARIF log for code scanning tools.
+
+mod divide_before_multiply;
+mod reentrancy;
+mod sarif;
+mod shadowing;
+mod solana_accounts;
+mod tests;
+mod tx_origin;
+mod unbounded_loop;
+mod unchecked_call;
+mod uninitialized_storage;
+
+use crate::sema::ast::{
+    Builtin, Diagnostic, Expression, Function, Namespace, RetrieveType, Statement,
+};
+use crate::sema::diagnostics::Diagnostics;
+use crate::sema::Recurse;
+pub use sarif::sarif_log;
+use solang_parser::pt::{CodeLocation, Loc};
+
+/// A check for a particular kind of problem
+pub trait Detector {
+    /// Short name of the detector, which is used to select it on the command line and as the
+    /// rule id in SARIF output
+    fn id(&self) -> &'static str;
+
+    /// One line description of the problem the detector finds
+    fn description(&self) -> &'static str;
+
+    /// Check the namespace, and add any problems found to the diagnostics
+    fn check(&self, ns: &Namespace, diagnostics: &mut Diagnostics);
+}
+
+/// A problem found by a detector
+#[derive(Debug)]
+pub struct Finding {
+    pub detector: &'static str,
+    pub diagnostic: Diagnostic,
+}
+
+/// All the available detectors
+pub fn detectors() -> Vec<Box<dyn Detector>> {
+    vec![
+        Box::new(reentrancy::Reentrancy),
+        Box::new(tx_origin::TxOrigin),
+        Box::new(unchecked_call::UncheckedCall),
+        Box::new(shadowing::ShadowingState),
+        Box::new(uninitialized_storage::UninitializedStorage),
+        Box::new(divide_before_multiply::DivideBeforeMultiply),
+        Box::new(unbounded_loop::UnboundedLoop),
+        Box::new(solana_accounts::MissingSignerCheck),
+        Box::new(solana_accounts::MissingOwnerCheck),
+        Box::new(solana_accounts::PdaSeedsPayer),
+        Box::new(solana_accounts::CpiPrivilegeEscalation),
+    ]
+}
+
+/// Run the detectors over a namespace without errors. The findings are ordered by location.
+pub fn lint(ns: &Namespace, detectors: &[Box<dyn Detector>]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for detector in detectors {
+        let mut diagnostics = Diagnostics::default();
+
+        detector.check(ns, &mut diagnostics);
+
+        diagnostics.sort_and_dedup();
+
+        findings.extend(diagnostics.iter().map(|diagnostic| Finding {
+            detector: detector.id(),
+            diagnostic: diagnostic.clone(),
+        }));
+    }
+
+    findings.sort_by_key(|finding| finding.diagnostic.loc);
+
+    findings
+}
+
+/// The functions and modifiers with a body which was written in Solidity
+fn functions_with_body(ns: &Namespace) -> impl Iterator<Item = &Function> {
+    ns.functions
+        .iter()
+        .filter(|func| func.has_body && !func.is_accessor && func.loc.try_file_no().is_some())
+}
+
+/// Every statement in the list, including the statements nested inside them
+fn statements(stmts: &[Statement]) -> Vec<&Statement> {
+    fn collect<'a>(stmts: &'a [Statement], list: &mut Vec<&'a Statement>) {
+        for stmt in stmts {
+            list.push(stmt);
+
+            match stmt {
+                Statement::Block { statements, .. } => collect(statements, list),
+                Statement::If(_, _, _, then_, else_) => {
+                    collect(then_, list);
+                    collect(else_, list);
+                }
+                Statement::For { init, body, .. } => {
+                    collect(init, list);
+                    collect(body, list);
+                }
+                Statement::While(_, _, _, body) | Statement::DoWhile(_, _, body, _) => {
+                    collect(body, list)
+                }
+                Statement::TryCatch(_, _, try_catch) => {
+                    collect(&try_catch.ok_stmt, list);
+
+                    for clause in try_catch.errors.iter().chain(&try_catch.catch_all) {
+                        collect(&clause.stmt, list);
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    let mut list = Vec::new();
+
+    collect(stmts, &mut list);
+
+    list
+}
+
+/// The expressions which are part of the statement itself, but not of any nested statements
+fn statement_expressions(stmt: &Statement) -> Vec<&Expression> {
+    match stmt {
+        Statement::VariableDecl(_, _, _, Some(expr)) => vec![expr.as_ref()],
+        Statement::If(_, _, cond, ..) | Statement::While(_, _, cond, _) => vec![cond],
+        Statement::DoWhile(_, _, _, cond) => vec![cond],
+        Statement::For { cond, next, .. } => cond.iter().chain(next.iter()).collect(),
+        Statement::Expression(_, _, expr)
+        | Statement::Delete(_, _, expr)
+        | Statement::Return(_, Some(expr)) => vec![expr],
+        Statement::Destructure(_, fields, expr) => {
+            let mut exprs = vec![expr];
+
+            exprs.extend(fields.iter().filter_map(|field| match field {
+                crate::sema::ast::DestructureField::Expression(expr) => Some(expr),
+                _ => None,
+            }));
+
+            exprs
+        }
+        Statement::Revert { args, .. } | Statement::Emit { args, .. } => args.iter().collect(),
+        Statement::TryCatch(_, _, try_catch) => vec![&try_catch.expr],
+        _ => Vec::new(),
+    }
+}
+
+/// Visit every expression in the body of a function, and the modifier invocations
+fn recurse_expressions<T>(func: &Function, cx: &mut T, f: fn(&Expression, &mut T) -> bool) {
+    for expr in &func.modifiers {
+        expr.recurse(cx, f);
+    }
+
+    for stmt in statements(&func.body) {
+        for expr in statement_expressions(stmt) {
+            expr.recurse(cx, f);
+        }
+    }
+}
+
+/// Look through any implicit conversions
+fn strip_casts(expr: &Expression) -> &Expression {
+    match expr {
+        Expression::ZeroExt { expr, .. }
+        | Expression::SignExt { expr, .. }
+        | Expression::Trunc { expr, .. }
+        | Expression::CheckingTrunc { expr, .. }
+        | Expression::Cast { expr, .. } => strip_casts(expr),
+        _ => expr,
+    }
+}
+
+/// If the expression writes to contract storage directly, return where
+fn storage_write(expr: &Expression) -> Option<Loc> {
+    match expr {
+        Expression::Assign { left, .. }
+        | Expression::PreIncrement { expr: left, .. }
+        | Expression::PreDecrement { expr: left, .. }
+        | Expression::PostIncrement { expr: left, .. }
+        | Expression::PostDecrement { expr: left, .. } => storage_lvalue(left),
+        Expression::Builtin {
+            loc,
+            kind: Builtin::ArrayPush | Builtin::ArrayPop,
+            args,
+            ..
+        } if args[0].ty().is_contract_storage() => Some(*loc),
+        _ => None,
+    }
+}
+
+/// If the left hand side of an assignment is in contract storage, return where
+fn storage_lvalue(left: &Expression) -> Option<Loc> {
+    let mut loc = None;
+
+    left.recurse(&mut loc, storage_member);
+
+    loc
+}
+
+fn storage_member(expr: &Expression, loc: &mut Option<Loc>) -> bool {
+    match expr {
+        Expression::StructMember { expr: array, .. } | Expression::Subscript { array, .. }
+            if array.ty().is_contract_storage() =>
+        {
+            *loc = Some(expr.loc());
+            false
+        }
+        Expression::StorageVariable { .. } => {
+            *loc = Some(expr.loc());
+            false
+        }
+        _ => loc.is_none(),
+    }
+}
+
+/// The function called by an internal function call, if it is not called through a pointer
+fn internal_callee(expr: &Expression) -> Option<usize> {
+    if let Expression::InternalFunctionCall { function, .. } = expr {
+        if let Expression::InternalFunction { function_no, .. } = function.as_ref() {
+            return Some(*function_no);
+        }
+    }
+
+    None
+}
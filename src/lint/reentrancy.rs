@@ -0,0 +1,401 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    functions_with_body, internal_callee, recurse_expressions, statements, storage_lvalue,
+    storage_write, Detector,
+};
+use crate::sema::ast::{
+    CallTy, DestructureField, Diagnostic, Expression, Function, Mutability, Namespace,
+    RetrieveType, Statement, Type,
+};
+use crate::sema::diagnostics::Diagnostics;
+use crate::sema::Recurse;
+use solang_parser::pt::{CodeLocation, Loc};
+
+/// Contract storage is modified after an external call. The callee can call back into the
+/// contract before the state is updated, and observe or exploit the stale state. This is the
+/// checks-effects-interactions pattern: all state changes should be done before any calls.
+pub struct Reentrancy;
+
+impl Detector for Reentrancy {
+    fn id(&self) -> &'static str {
+        "reentrancy"
+    }
+
+    fn description(&self) -> &'static str {
+        "state is written after an external call"
+    }
+
+    fn check(&self, ns: &Namespace, diagnostics: &mut Diagnostics) {
+        let summaries = summaries(ns);
+
+        for func in functions_with_body(ns) {
+            if matches!(func.mutability, Mutability::View(_) | Mutability::Pure(_))
+                || has_reentrancy_guard(func, ns)
+            {
+                continue;
+            }
+
+            let mut walk = Walk {
+                ns,
+                summaries: &summaries,
+                called: None,
+                diagnostics,
+            };
+
+            walk.statements(&func.body);
+        }
+    }
+}
+
+/// What a function does, including the functions it calls
+#[derive(Default, Clone)]
+struct Summary {
+    calls: bool,
+    writes: bool,
+    callees: Vec<usize>,
+}
+
+fn summaries(ns: &Namespace) -> Vec<Summary> {
+    let mut summaries = vec![Summary::default(); ns.functions.len()];
+
+    for (function_no, func) in ns.functions.iter().enumerate() {
+        if !func.has_body {
+            continue;
+        }
+
+        let summary = &mut summaries[function_no];
+
+        recurse_expressions(func, summary, summarize);
+
+        summary.writes |= statements(&func.body)
+            .into_iter()
+            .any(|stmt| matches!(stmt, Statement::Delete(..)));
+    }
+
+    // propagate through internal calls until nothing changes
+    loop {
+        let mut changed = false;
+
+        for function_no in 0..summaries.len() {
+            let (calls, writes) = summaries[function_no].callees.iter().fold(
+                (summaries[function_no].calls, summaries[function_no].writes),
+                |(calls, writes), callee| {
+                    (
+                        calls || summaries[*callee].calls,
+                        writes || summaries[*callee].writes,
+                    )
+                },
+            );
+
+            let summary = &mut summaries[function_no];
+
+            if calls != summary.calls || writes != summary.writes {
+                summary.calls = calls;
+                summary.writes = writes;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break summaries;
+        }
+    }
+}
+
+fn summarize(expr: &Expression, summary: &mut Summary) -> bool {
+    if is_external_call(expr) {
+        summary.calls = true;
+    } else if storage_write(expr).is_some() {
+        summary.writes = true;
+    } else if let Some(function_no) = internal_callee(expr) {
+        summary.callees.push(function_no);
+    }
+
+    true
+}
+
+/// Walks the body of a function in execution order, remembering whether an external call may
+/// have been made on the way
+struct Walk<'a> {
+    ns: &'a Namespace,
+    summaries: &'a [Summary],
+    called: Option<Loc>,
+    diagnostics: &'a mut Diagnostics,
+}
+
+impl Walk<'_> {
+    /// Returns false if the end of the statements is not reachable
+    fn statements(&mut self, stmts: &[Statement]) -> bool {
+        for stmt in stmts {
+            if !self.statement(stmt) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn statement(&mut self, stmt: &Statement) -> bool {
+        match stmt {
+            Statement::Block { statements, .. } => return self.statements(statements),
+            Statement::VariableDecl(_, _, _, Some(expr)) => self.expression(expr),
+            Statement::Expression(_, _, expr) => self.expression(expr),
+            Statement::Delete(loc, _, expr) => {
+                self.expression(expr);
+                self.write(
+                    *loc,
+                    "state variable is deleted after an external call".into(),
+                );
+            }
+            Statement::Destructure(_, fields, expr) => {
+                self.expression(expr);
+
+                for field in fields {
+                    if let DestructureField::Expression(left) = field {
+                        self.assign(left);
+                    }
+                }
+            }
+            Statement::Emit { args, .. } => {
+                for arg in args {
+                    self.expression(arg);
+                }
+            }
+            Statement::If(_, _, cond, then_, else_) => {
+                self.expression(cond);
+
+                let before = self.called;
+                let then_reachable = self.statements(then_);
+                let after_then = self.called;
+
+                self.called = before;
+
+                let else_reachable = self.statements(else_);
+
+                self.called = match (then_reachable, else_reachable) {
+                    (true, true) => self.called.or(after_then),
+                    (true, false) => after_then,
+                    (false, true) => self.called,
+                    (false, false) => return false,
+                };
+            }
+            Statement::While(_, _, cond, body) => {
+                let before = self.called;
+
+                // the second iteration sees the calls made by the first one
+                for _ in 0..2 {
+                    self.expression(cond);
+                    self.statements(body);
+                }
+
+                self.called = self.called.or(before);
+            }
+            Statement::For {
+                init,
+                cond,
+                next,
+                body,
+                ..
+            } => {
+                if !self.statements(init) {
+                    return false;
+                }
+
+                let before = self.called;
+
+                for _ in 0..2 {
+                    if let Some(cond) = cond {
+                        self.expression(cond);
+                    }
+
+                    self.statements(body);
+
+                    if let Some(next) = next {
+                        self.expression(next);
+                    }
+                }
+
+                self.called = self.called.or(before);
+            }
+            Statement::DoWhile(_, _, body, cond) => {
+                for _ in 0..2 {
+                    self.statements(body);
+                    self.expression(cond);
+                }
+            }
+            Statement::TryCatch(_, _, try_catch) => {
+                self.expression(&try_catch.expr);
+
+                let before = self.called;
+                let mut after = None;
+                let mut reachable = false;
+
+                for stmts in std::iter::once(&try_catch.ok_stmt).chain(
+                    try_catch
+                        .errors
+                        .iter()
+                        .chain(&try_catch.catch_all)
+                        .map(|clause| &clause.stmt),
+                ) {
+                    self.called = before;
+
+                    if self.statements(stmts) {
+                        reachable = true;
+                        after = after.or(self.called);
+                    }
+                }
+
+                if !reachable {
+                    return false;
+                }
+
+                self.called = after;
+            }
+            Statement::Return(_, expr) => {
+                if let Some(expr) = expr {
+                    self.expression(expr);
+                }
+
+                return false;
+            }
+            Statement::Revert { args, .. } => {
+                for arg in args {
+                    self.expression(arg);
+                }
+
+                return false;
+            }
+            _ => (),
+        }
+
+        true
+    }
+
+    fn expression(&mut self, expr: &Expression) {
+        expr.recurse(self, visit);
+    }
+
+    /// Evaluate the left hand side of an assignment
+    fn assign(&mut self, left: &Expression) {
+        self.expression(left);
+
+        if storage_lvalue(left).is_some() {
+            self.write(
+                left.loc(),
+                "state variable is written after an external call".into(),
+            );
+        }
+    }
+
+    fn write(&mut self, loc: Loc, message: String) {
+        if let Some(call) = self.called {
+            self.diagnostics.push(Diagnostic::warning_with_note(
+                loc,
+                format!("{message}, which may allow reentrancy"),
+                call,
+                "external call".into(),
+            ));
+        }
+    }
+
+    fn call(&mut self, loc: Loc) {
+        self.called.get_or_insert(loc);
+    }
+}
+
+fn visit(expr: &Expression, walk: &mut Walk) -> bool {
+    match expr {
+        Expression::Assign { left, right, .. } => {
+            walk.expression(right);
+            walk.assign(left);
+        }
+        Expression::PreIncrement { expr: operand, .. }
+        | Expression::PreDecrement { expr: operand, .. }
+        | Expression::PostIncrement { expr: operand, .. }
+        | Expression::PostDecrement { expr: operand, .. } => walk.assign(operand),
+        Expression::Builtin { args, .. } if storage_write(expr).is_some() => {
+            for arg in args {
+                walk.expression(arg);
+            }
+
+            walk.write(
+                expr.loc(),
+                "storage array is modified after an external call".into(),
+            );
+        }
+        Expression::ExternalFunctionCall { function, args, .. } => {
+            for arg in args {
+                walk.expression(arg);
+            }
+
+            walk.expression(function);
+
+            if is_external_call(expr) {
+                walk.call(expr.loc());
+            }
+        }
+        Expression::ExternalFunctionCallRaw { address, args, .. } => {
+            walk.expression(address);
+            walk.expression(args);
+
+            if is_external_call(expr) {
+                walk.call(expr.loc());
+            }
+        }
+        Expression::InternalFunctionCall { args, .. } => {
+            for arg in args {
+                walk.expression(arg);
+            }
+
+            if let Some(function_no) = internal_callee(expr) {
+                let summary = &walk.summaries[function_no];
+
+                if summary.writes {
+                    walk.write(
+                        expr.loc(),
+                        format!(
+                            "function '{}' writes to state after an external call",
+                            walk.ns.functions[function_no].id.name
+                        ),
+                    );
+                }
+
+                if summary.calls {
+                    walk.call(expr.loc());
+                }
+            }
+        }
+        _ => return true,
+    }
+
+    false
+}
+
+/// Does the expression call another contract, which may call back into this one
+fn is_external_call(expr: &Expression) -> bool {
+    match expr {
+        Expression::ExternalFunctionCall { function, .. } => !matches!(
+            function.ty(),
+            Type::ExternalFunction {
+                mutability: Mutability::View(_) | Mutability::Pure(_),
+                ..
+            }
+        ),
+        Expression::ExternalFunctionCallRaw { ty, .. } => *ty != CallTy::Static,
+        _ => false,
+    }
+}
+
+/// A modifier like `nonReentrant` protects the function against reentrancy
+fn has_reentrancy_guard(func: &Function, ns: &Namespace) -> bool {
+    func.modifiers.iter().any(|modifier| {
+        internal_callee(modifier).is_some_and(|function_no| {
+            ns.functions[function_no]
+                .id
+                .name
+                .to_lowercase()
+                .contains("reentran")
+        })
+    })
+}
@@ -0,0 +1,169 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Findings in the [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html)
+//! format, which is understood by code scanning tools like GitHub code scanning.
+
+use super::{Detector, Finding};
+use crate::sema::ast::Namespace;
+use serde::Serialize;
+use solang_parser::pt::Loc;
+
+#[derive(Serialize)]
+pub struct SarifLog {
+    pub version: &'static str,
+    #[serde(rename = "$schema")]
+    pub schema: &'static str,
+    pub runs: Vec<Run>,
+}
+
+#[derive(Serialize)]
+pub struct Run {
+    pub tool: Tool,
+    pub results: Vec<SarifResult>,
+}
+
+#[derive(Serialize)]
+pub struct Tool {
+    pub driver: Driver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Driver {
+    pub name: &'static str,
+    pub version: &'static str,
+    pub information_uri: &'static str,
+    pub rules: Vec<Rule>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rule {
+    pub id: &'static str,
+    pub short_description: Message,
+}
+
+#[derive(Serialize)]
+pub struct Message {
+    pub text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifResult {
+    pub rule_id: &'static str,
+    pub level: &'static str,
+    pub message: Message,
+    pub locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub related_locations: Vec<Location>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    pub physical_location: PhysicalLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhysicalLocation {
+    pub artifact_location: ArtifactLocation,
+    pub region: Region,
+}
+
+#[derive(Serialize)]
+pub struct ArtifactLocation {
+    pub uri: String,
+}
+
+/// Lines and columns start at 1
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Region {
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+/// Create a single SARIF run with the findings of all the files that were linted
+pub fn sarif_log(
+    reports: &[(&Namespace, Vec<Finding>)],
+    detectors: &[Box<dyn Detector>],
+) -> SarifLog {
+    let rules = detectors
+        .iter()
+        .map(|detector| Rule {
+            id: detector.id(),
+            short_description: Message {
+                text: detector.description().into(),
+            },
+        })
+        .collect();
+
+    let mut results = Vec::new();
+
+    for (ns, findings) in reports {
+        for finding in findings {
+            let diagnostic = &finding.diagnostic;
+
+            results.push(SarifResult {
+                rule_id: finding.detector,
+                level: "warning",
+                message: Message {
+                    text: diagnostic.message.clone(),
+                },
+                locations: location(ns, &diagnostic.loc, None).into_iter().collect(),
+                related_locations: diagnostic
+                    .notes
+                    .iter()
+                    .filter_map(|note| location(ns, &note.loc, Some(&note.message)))
+                    .collect(),
+            });
+        }
+    }
+
+    SarifLog {
+        version: "2.1.0",
+        schema: "https://json.schemastore.org/sarif-2.1.0.json",
+        runs: vec![Run {
+            tool: Tool {
+                driver: Driver {
+                    name: "solang",
+                    version: env!("CARGO_PKG_VERSION"),
+                    information_uri: env!("CARGO_PKG_HOMEPAGE"),
+                    rules,
+                },
+            },
+            results,
+        }],
+    }
+}
+
+fn location(ns: &Namespace, loc: &Loc, message: Option<&String>) -> Option<Location> {
+    let Loc::File(file_no, start, end) = loc else {
+        return None;
+    };
+
+    let file = &ns.files[*file_no];
+    let (start_line, start_column) = file.offset_to_line_column(*start);
+    let (end_line, end_column) = file.offset_to_line_column(*end);
+
+    Some(Location {
+        physical_location: PhysicalLocation {
+            artifact_location: ArtifactLocation {
+                uri: file.path.to_string_lossy().replace('\\', "/"),
+            },
+            region: Region {
+                start_line: start_line + 1,
+                start_column: start_column + 1,
+                end_line: end_line + 1,
+                end_column: end_column + 1,
+            },
+        },
+        message: message.map(|text| Message { text: text.clone() }),
+    })
+}
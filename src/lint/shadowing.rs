@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{functions_with_body, Detector};
+use crate::sema::ast::{Diagnostic, Namespace};
+use crate::sema::diagnostics::Diagnostics;
+use crate::sema::symtable::VariableUsage;
+
+/// A local variable, parameter or return variable with the same name as a state variable of a
+/// base contract. Sema already warns about shadowing state variables of the contract itself, but
+/// not those which are inherited.
+pub struct ShadowingState;
+
+impl Detector for ShadowingState {
+    fn id(&self) -> &'static str {
+        "shadowing-state"
+    }
+
+    fn description(&self) -> &'static str {
+        "a variable shadows an inherited state variable"
+    }
+
+    fn check(&self, ns: &Namespace, diagnostics: &mut Diagnostics) {
+        for func in functions_with_body(ns) {
+            let Some(contract_no) = func.contract_no else {
+                continue;
+            };
+
+            for var in func.symtable.vars.values() {
+                if var.id.name.is_empty()
+                    || matches!(
+                        var.usage_type,
+                        VariableUsage::AnonymousReturnVariable | VariableUsage::YulLocalVariable
+                    )
+                {
+                    continue;
+                }
+
+                let shadowed = ns
+                    .contract_bases(contract_no)
+                    .into_iter()
+                    .filter(|base_no| *base_no != contract_no)
+                    .find_map(|base_no| {
+                        ns.contracts[base_no]
+                            .variables
+                            .iter()
+                            .find(|state| state.name == var.id.name)
+                            .map(|state| (base_no, state))
+                    });
+
+                if let Some((base_no, state)) = shadowed {
+                    diagnostics.push(Diagnostic::warning_with_note(
+                        var.id.loc,
+                        format!(
+                            "declaration of '{}' shadows state variable in base contract '{}'",
+                            var.id.name, ns.contracts[base_no].id.name
+                        ),
+                        state.loc,
+                        "previous declaration of state variable".into(),
+                    ));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,543 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Detectors for the accounts of Solana instructions. Solana programs receive all their accounts
+//! from the caller, so the program has to check that an account signed the transaction before
+//! trusting it for authorization, and that it is owned by the expected program before trusting
+//! its data. The declared accounts and their flags come from the `@signer`, `@account` etc.
+//! annotations, which is what account collection builds the IDL from.
+
+use super::{functions_with_body, internal_callee, recurse_expressions, statements};
+use super::{storage_write, strip_casts, Detector};
+use crate::sema::ast::{
+    Builtin, CallArgs, Diagnostic, Expression, ExternalCallAccounts, Function, Mutability,
+    Namespace, Statement, StructType, Type,
+};
+use crate::sema::diagnostics::Diagnostics;
+use crate::sema::solana_accounts::BuiltinAccounts;
+use crate::sema::symtable::VariableInitializer;
+use crate::sema::Recurse;
+use crate::Target;
+use solang_parser::pt::{CodeLocation, Loc};
+use std::collections::{BTreeSet, HashSet};
+
+/// Fields of the AccountInfo struct
+const KEY: usize = 0;
+const LAMPORTS: usize = 1;
+const DATA: usize = 2;
+const OWNER: usize = 3;
+const IS_SIGNER: usize = 5;
+
+/// A function writes to state or moves lamports, and relies on an account which was never checked
+/// to be a signer
+pub struct MissingSignerCheck;
+
+impl Detector for MissingSignerCheck {
+    fn id(&self) -> &'static str {
+        "missing-signer-check"
+    }
+
+    fn description(&self) -> &'static str {
+        "an account is trusted without checking that it signed the transaction"
+    }
+
+    fn check(&self, ns: &Namespace, diagnostics: &mut Diagnostics) {
+        if ns.target != Target::Solana {
+            return;
+        }
+
+        let uses = account_uses(ns);
+
+        for (func, uses) in instructions(ns, &uses) {
+            if !uses.writes {
+                continue;
+            }
+
+            let mut signers = uses.signer_checked.clone();
+
+            // the payer signs the transfer which creates the data account
+            if let Some((_, payer)) = &func.annotations.payer {
+                signers.insert(payer.clone());
+            }
+
+            for (name, loc) in &uses.key_compared {
+                if !signers.contains(name) {
+                    diagnostics.push(account_warning(
+                        func,
+                        name,
+                        *loc,
+                        format!(
+                            "account '{name}' is used for authorization, but \
+                            'tx.accounts.{name}.is_signer' is never checked"
+                        ),
+                    ));
+                }
+            }
+
+            if signers.is_empty() {
+                for (name, loc) in &uses.lamports_debited {
+                    diagnostics.push(account_warning(
+                        func,
+                        name,
+                        *loc,
+                        format!(
+                            "lamports are transferred from account '{name}', but no account \
+                            is checked to be a signer"
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// A function writes to state or moves lamports based on the data of an account, which was never
+/// checked to be owned by the expected program
+pub struct MissingOwnerCheck;
+
+impl Detector for MissingOwnerCheck {
+    fn id(&self) -> &'static str {
+        "missing-owner-check"
+    }
+
+    fn description(&self) -> &'static str {
+        "the data of an account is trusted without checking its owner"
+    }
+
+    fn check(&self, ns: &Namespace, diagnostics: &mut Diagnostics) {
+        if ns.target != Target::Solana {
+            return;
+        }
+
+        let uses = account_uses(ns);
+
+        for (func, uses) in instructions(ns, &uses) {
+            if !uses.writes {
+                continue;
+            }
+
+            for (name, loc) in &uses.data_read {
+                if !uses.owner_checked.contains(name) {
+                    diagnostics.push(account_warning(
+                        func,
+                        name,
+                        *loc,
+                        format!(
+                            "data of account '{name}' is used, but \
+                            'tx.accounts.{name}.owner' is never checked"
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// The seeds of the program derived address for a new contract do not depend on the payer, so
+/// anyone can create the account before the intended payer does
+pub struct PdaSeedsPayer;
+
+impl Detector for PdaSeedsPayer {
+    fn id(&self) -> &'static str {
+        "pda-seeds-payer"
+    }
+
+    fn description(&self) -> &'static str {
+        "the seeds of a program derived address do not bind the payer"
+    }
+
+    fn check(&self, ns: &Namespace, diagnostics: &mut Diagnostics) {
+        if ns.target != Target::Solana {
+            return;
+        }
+
+        for func in functions_with_body(ns).filter(|func| func.is_constructor()) {
+            let (Some((payer_loc, payer)), Some((seed_loc, _))) =
+                (&func.annotations.payer, func.annotations.seeds.first())
+            else {
+                continue;
+            };
+
+            // parameters which are compared with (a hash of) the payer's key
+            let mut payer_params = PayerParams {
+                payer,
+                var_nos: HashSet::new(),
+            };
+
+            recurse_expressions(func, &mut payer_params, payer_comparison);
+
+            let bound = func.annotations.seeds.iter().any(|(_, seed)| {
+                account_keys(seed).contains(payer)
+                    || variables(seed)
+                        .iter()
+                        .any(|var_no| payer_params.var_nos.contains(var_no))
+            });
+
+            if !bound {
+                diagnostics.push(Diagnostic::warning_with_note(
+                    *seed_loc,
+                    format!(
+                        "seeds of the program derived address do not depend on payer '{payer}', \
+                        so anyone can create the account first"
+                    ),
+                    *payer_loc,
+                    "payer declared here".into(),
+                ));
+            }
+        }
+    }
+}
+
+/// An account is passed to another program with more privileges than the instruction received
+/// it with
+pub struct CpiPrivilegeEscalation;
+
+impl Detector for CpiPrivilegeEscalation {
+    fn id(&self) -> &'static str {
+        "cpi-privilege-escalation"
+    }
+
+    fn description(&self) -> &'static str {
+        "an account is passed to a cross program invocation with escalated privileges"
+    }
+
+    fn check(&self, ns: &Namespace, diagnostics: &mut Diagnostics) {
+        if ns.target != Target::Solana {
+            return;
+        }
+
+        for func in functions_with_body(ns) {
+            let mut cx = CallCheck { func, diagnostics };
+
+            recurse_expressions(func, &mut cx, external_call);
+        }
+    }
+}
+
+/// How the accounts of the transaction are used by a function, including the functions and
+/// modifiers it calls
+#[derive(Default, Clone)]
+struct AccountUses {
+    signer_checked: BTreeSet<String>,
+    owner_checked: BTreeSet<String>,
+    key_compared: BTreeSet<(String, Loc)>,
+    data_read: BTreeSet<(String, Loc)>,
+    lamports_debited: BTreeSet<(String, Loc)>,
+    /// Writes to contract storage or to the lamports or data of an account
+    writes: bool,
+    callees: Vec<usize>,
+}
+
+impl AccountUses {
+    /// Add the uses of a called function; returns true if anything changed
+    fn merge(&mut self, other: &AccountUses) -> bool {
+        let before = self.len();
+
+        self.signer_checked
+            .extend(other.signer_checked.iter().cloned());
+        self.owner_checked
+            .extend(other.owner_checked.iter().cloned());
+        self.key_compared.extend(other.key_compared.iter().cloned());
+        self.data_read.extend(other.data_read.iter().cloned());
+        self.lamports_debited
+            .extend(other.lamports_debited.iter().cloned());
+
+        let writes = self.writes;
+        self.writes |= other.writes;
+
+        before != self.len() || writes != self.writes
+    }
+
+    fn len(&self) -> usize {
+        self.signer_checked.len()
+            + self.owner_checked.len()
+            + self.key_compared.len()
+            + self.data_read.len()
+            + self.lamports_debited.len()
+    }
+}
+
+fn account_uses(ns: &Namespace) -> Vec<AccountUses> {
+    let mut uses = vec![AccountUses::default(); ns.functions.len()];
+
+    for (function_no, func) in ns.functions.iter().enumerate() {
+        if !func.has_body {
+            continue;
+        }
+
+        let func_uses = &mut uses[function_no];
+
+        recurse_expressions(func, func_uses, account_use);
+
+        func_uses.writes |= statements(&func.body)
+            .into_iter()
+            .any(|stmt| matches!(stmt, Statement::Delete(..)));
+    }
+
+    loop {
+        let mut changed = false;
+
+        for function_no in 0..uses.len() {
+            for callee in uses[function_no].callees.clone() {
+                if callee != function_no {
+                    let callee_uses = uses[callee].clone();
+
+                    changed |= uses[function_no].merge(&callee_uses);
+                }
+            }
+        }
+
+        if !changed {
+            break uses;
+        }
+    }
+}
+
+/// The functions which can be called by an instruction and may modify state
+fn instructions<'a>(
+    ns: &'a Namespace,
+    uses: &'a [AccountUses],
+) -> impl Iterator<Item = (&'a Function, &'a AccountUses)> {
+    ns.functions.iter().zip(uses).filter(|(func, _)| {
+        func.has_body
+            && func.contract_no.is_some()
+            && (func.is_public() || func.is_constructor())
+            && !matches!(func.mutability, Mutability::View(_) | Mutability::Pure(_))
+            && func.loc.try_file_no().is_some()
+    })
+}
+
+fn account_use(expr: &Expression, uses: &mut AccountUses) -> bool {
+    if let Some((name, field)) = account_member(expr) {
+        match field {
+            IS_SIGNER => {
+                uses.signer_checked.insert(name.to_owned());
+            }
+            OWNER => {
+                uses.owner_checked.insert(name.to_owned());
+            }
+            DATA if name != BuiltinAccounts::DataAccount.as_str() => {
+                uses.data_read.insert((name.to_owned(), expr.loc()));
+            }
+            _ => (),
+        }
+
+        return false;
+    }
+
+    match expr {
+        Expression::Equal { loc, left, right } | Expression::NotEqual { loc, left, right } => {
+            for side in [left, right] {
+                if let Some((name, KEY)) = account_member(strip_casts(side)) {
+                    if name != BuiltinAccounts::DataAccount.as_str() {
+                        uses.key_compared.insert((name.to_owned(), *loc));
+                    }
+                }
+            }
+        }
+        Expression::Assign {
+            loc, left, right, ..
+        } => {
+            if let Some((name, field)) = account_member(left) {
+                if field == LAMPORTS && matches!(right.as_ref(), Expression::Subtract { .. }) {
+                    uses.lamports_debited.insert((name.to_owned(), *loc));
+                }
+
+                uses.writes = true;
+            } else if let Expression::Subscript { array, .. } = strip_loads(left) {
+                uses.writes |= account_member(array).is_some();
+            }
+        }
+        _ => (),
+    }
+
+    if storage_write(expr).is_some() {
+        uses.writes = true;
+    }
+
+    if let Some(function_no) = internal_callee(expr) {
+        uses.callees.push(function_no);
+    }
+
+    true
+}
+
+/// If the expression is a field of `tx.accounts.<name>`, return the name of the account and the
+/// field number
+fn account_member(expr: &Expression) -> Option<(&str, usize)> {
+    if let Expression::StructMember { expr, field, .. } = strip_loads(expr) {
+        if let Expression::NamedMember { array, name, .. } = expr.as_ref() {
+            if matches!(
+                array.as_ref(),
+                Expression::Builtin {
+                    kind: Builtin::Accounts,
+                    ..
+                }
+            ) {
+                return Some((name, *field));
+            }
+        }
+    }
+
+    None
+}
+
+fn strip_loads(expr: &Expression) -> &Expression {
+    match expr {
+        Expression::Load { expr, .. } => strip_loads(expr),
+        _ => expr,
+    }
+}
+
+/// The accounts whose key is used in the expression
+fn account_keys(expr: &Expression) -> Vec<String> {
+    let mut names = Vec::new();
+
+    expr.recurse(&mut names, |expr, names| {
+        if let Some((name, KEY)) = account_member(expr) {
+            names.push(name.to_owned());
+            return false;
+        }
+        true
+    });
+
+    names
+}
+
+/// The local variables and parameters used in the expression
+fn variables(expr: &Expression) -> Vec<usize> {
+    let mut var_nos = Vec::new();
+
+    expr.recurse(&mut var_nos, |expr, var_nos| {
+        if let Expression::Variable { var_no, .. } = expr {
+            var_nos.push(*var_no);
+        }
+        true
+    });
+
+    var_nos
+}
+
+struct PayerParams<'a> {
+    payer: &'a str,
+    var_nos: HashSet<usize>,
+}
+
+fn payer_comparison(expr: &Expression, cx: &mut PayerParams) -> bool {
+    if let Expression::Equal { left, right, .. } = expr {
+        for (key, other) in [(left, right), (right, left)] {
+            if account_keys(key).iter().any(|name| name == cx.payer) {
+                cx.var_nos.extend(variables(other));
+            }
+        }
+    }
+
+    true
+}
+
+fn account_warning(func: &Function, name: &str, loc: Loc, message: String) -> Diagnostic {
+    match func.solana_accounts.borrow().get(name) {
+        Some(account) if account.loc.try_file_no().is_some() => {
+            Diagnostic::warning_with_note(loc, message, account.loc, "account declared here".into())
+        }
+        _ => Diagnostic::warning(loc, message),
+    }
+}
+
+struct CallCheck<'a> {
+    func: &'a Function,
+    diagnostics: &'a mut Diagnostics,
+}
+
+fn external_call(expr: &Expression, cx: &mut CallCheck) -> bool {
+    let call_args = match expr {
+        Expression::ExternalFunctionCall { call_args, .. }
+        | Expression::ExternalFunctionCallRaw { call_args, .. }
+        | Expression::Constructor { call_args, .. } => call_args,
+        _ => return true,
+    };
+
+    let ExternalCallAccounts::Present(accounts) = &call_args.accounts else {
+        return true;
+    };
+
+    for meta in account_metas(accounts, cx.func) {
+        check_meta(meta, call_args, cx);
+    }
+
+    true
+}
+
+/// The AccountMeta struct literals for the accounts call argument, either written in place or
+/// assigned to a local variable when it is declared
+fn account_metas<'a>(accounts: &'a Expression, func: &'a Function) -> Vec<&'a Expression> {
+    let values = match strip_casts(accounts) {
+        Expression::ArrayLiteral { values, .. } => values,
+        Expression::Variable { var_no, .. } => match &func.symtable.vars[var_no].initializer {
+            VariableInitializer::Solidity(Some(init)) => match init.as_ref() {
+                Expression::ArrayLiteral { values, .. } => values,
+                _ => return Vec::new(),
+            },
+            _ => return Vec::new(),
+        },
+        _ => return Vec::new(),
+    };
+
+    values
+        .iter()
+        .filter(|value| {
+            matches!(
+                value,
+                Expression::StructLiteral {
+                    ty: Type::Struct(StructType::AccountMeta),
+                    ..
+                }
+            )
+        })
+        .collect()
+}
+
+fn check_meta(meta: &Expression, call_args: &CallArgs, cx: &mut CallCheck) {
+    let Expression::StructLiteral { loc, values, .. } = meta else {
+        return;
+    };
+
+    // AccountMeta fields are pubkey, is_writable, is_signer
+    let Some((name, KEY)) = account_member(&values[0].1) else {
+        return;
+    };
+
+    let Some(account) = cx.func.solana_accounts.borrow().get(name).cloned() else {
+        return;
+    };
+
+    let is_true = |expr: &Expression| matches!(expr, Expression::BoolLiteral { value: true, .. });
+
+    let mut escalated = Vec::new();
+
+    if is_true(&values[1].1) && !account.is_writer {
+        escalated.push("writable");
+    }
+
+    // a program derived address can be signed for with the seeds
+    if is_true(&values[2].1) && !account.is_signer && call_args.seeds.is_none() {
+        escalated.push("a signer");
+    }
+
+    for privilege in escalated {
+        let message = format!(
+            "account '{name}' is passed to the external call as {privilege}, but the \
+            instruction does not receive it as {privilege}"
+        );
+
+        cx.diagnostics.push(if account.loc.try_file_no().is_some() {
+            Diagnostic::warning_with_note(
+                *loc,
+                message,
+                account.loc,
+                "account declared here".into(),
+            )
+        } else {
+            Diagnostic::warning(*loc, message)
+        });
+    }
+}
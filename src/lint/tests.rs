@@ -0,0 +1,476 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(test)]
+
+use super::{detectors, lint, sarif_log};
+use crate::sema::tests::resolve;
+use crate::Target;
+
+/// The detector and message of each finding
+fn findings(src: &str) -> Vec<(&'static str, String)> {
+    target_findings(src, Target::EVM)
+}
+
+fn target_findings(src: &str, target: Target) -> Vec<(&'static str, String)> {
+    let ns = resolve(src, target);
+
+    lint(&ns, &detectors())
+        .into_iter()
+        .map(|finding| (finding.detector, finding.diagnostic.message))
+        .collect()
+}
+
+#[test]
+fn reentrancy() {
+    let found = findings(
+        r#"
+        interface Token {
+            function transfer(address to, uint amount) external returns (bool);
+            function balanceOf(address owner) external view returns (uint);
+        }
+
+        contract Bank {
+            mapping(address => uint) balances;
+            Token token;
+
+            function withdraw() public {
+                uint amount = balances[msg.sender];
+                (bool ok, ) = msg.sender.call{value: amount}("");
+                require(ok);
+                balances[msg.sender] = 0;
+            }
+
+            function safe_withdraw() public {
+                uint amount = balances[msg.sender];
+                balances[msg.sender] = 0;
+                (bool ok, ) = msg.sender.call{value: amount}("");
+                require(ok);
+            }
+
+            function view_call() public {
+                balances[msg.sender] = token.balanceOf(msg.sender);
+            }
+
+            function internal_write() public {
+                token.transfer(msg.sender, 1);
+                clear();
+            }
+
+            function clear() internal {
+                delete balances[msg.sender];
+            }
+
+            modifier nonReentrant() {
+                _;
+            }
+
+            function guarded() public nonReentrant {
+                token.transfer(msg.sender, 1);
+                balances[msg.sender] = 0;
+            }
+
+            function loop(address[] memory to) public {
+                for (uint i = 0; i < to.length; i++) {
+                    balances[to[i]] += 1;
+                    token.transfer(to[i], 1);
+                }
+            }
+
+            function branch(bool b) public {
+                if (b) {
+                    token.transfer(msg.sender, 1);
+                    return;
+                }
+                balances[msg.sender] = 1;
+            }
+        }"#,
+    );
+
+    assert_eq!(
+        found,
+        vec![
+            (
+                "reentrancy",
+                "state variable is written after an external call, which may allow reentrancy"
+                    .into()
+            ),
+            (
+                "reentrancy",
+                "function 'clear' writes to state after an external call, which may allow reentrancy"
+                    .into()
+            ),
+            (
+                "reentrancy",
+                "state variable is written after an external call, which may allow reentrancy"
+                    .into()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn tx_origin() {
+    let found = findings(
+        r#"
+        contract C {
+            address owner;
+
+            function f() public view {
+                require(tx.origin == owner);
+            }
+
+            function g() public view returns (bool) {
+                return tx.origin == msg.sender;
+            }
+        }"#,
+    );
+
+    assert_eq!(
+        found,
+        vec![(
+            "tx-origin",
+            "'tx.origin' is used for authorization; use 'msg.sender' instead".into()
+        )]
+    );
+}
+
+#[test]
+fn unchecked_call() {
+    let found = findings(
+        r#"
+        contract C {
+            function f(address payable a) public {
+                a.call("");
+                a.send(1);
+                (bool ok, ) = a.call("");
+                require(ok);
+                (bool unused, ) = a.delegatecall("");
+                bool sent = a.send(2);
+                require(sent);
+            }
+        }"#,
+    );
+
+    let messages: Vec<_> = found
+        .into_iter()
+        .filter(|(detector, _)| *detector == "unchecked-call")
+        .map(|(_, message)| message)
+        .collect();
+
+    assert_eq!(
+        messages,
+        vec![
+            "success of low-level call is not checked",
+            "return value of 'send' is not checked",
+            "success of low-level call is not checked",
+        ]
+    );
+}
+
+#[test]
+fn shadowing() {
+    let found = findings(
+        r#"
+        contract A {
+            uint total;
+        }
+
+        contract B is A {
+            function f(uint total) public pure returns (uint) {
+                return total;
+            }
+        }"#,
+    );
+
+    assert_eq!(
+        found,
+        vec![(
+            "shadowing-state",
+            "declaration of 'total' shadows state variable in base contract 'A'".into()
+        )]
+    );
+}
+
+#[test]
+fn uninitialized_storage() {
+    let found = findings(
+        r#"
+        contract C {
+            struct S { uint a; }
+            S s1;
+
+            function f() public {
+                S storage s;
+                s.a = 1;
+            }
+
+            function g() public {
+                S storage s;
+                s = s1;
+                s.a = 1;
+            }
+        }"#,
+    );
+
+    assert_eq!(
+        found,
+        vec![(
+            "uninitialized-storage",
+            "storage reference 's' is used before it is initialized".into()
+        )]
+    );
+}
+
+#[test]
+fn divide_before_multiply() {
+    let found = findings(
+        r#"
+        contract C {
+            function f(uint a, uint b, uint c) public pure returns (uint, uint) {
+                return (a / b * c, a * c / b);
+            }
+        }"#,
+    );
+
+    assert_eq!(
+        found,
+        vec![(
+            "divide-before-multiply",
+            "division is performed before multiplication, which may truncate the result".into()
+        )]
+    );
+}
+
+#[test]
+fn unbounded_loop() {
+    let found = findings(
+        r#"
+        contract C {
+            uint[] values;
+
+            function sum() public view returns (uint total) {
+                for (uint i = 0; i < values.length; i++) {
+                    total += values[i];
+                }
+            }
+
+            function bounded(uint[] memory arg) public pure returns (uint total) {
+                for (uint i = 0; i < arg.length; i++) {
+                    total += arg[i];
+                }
+            }
+        }"#,
+    );
+
+    assert_eq!(
+        found,
+        vec![(
+            "unbounded-loop",
+            "loop over storage array has no upper bound and may run out of gas".into()
+        )]
+    );
+}
+
+#[test]
+fn missing_signer_check() {
+    let found = target_findings(
+        r#"
+        contract C {
+            address owner;
+            uint64 counter;
+
+            @account(authority)
+            function no_check() external {
+                require(tx.accounts.authority.key == owner);
+                counter += 1;
+            }
+
+            @signer(authority)
+            function checked() external {
+                require(tx.accounts.authority.key == owner);
+                require(tx.accounts.authority.is_signer);
+                counter += 1;
+            }
+
+            @mutableAccount(vault)
+            @mutableAccount(to)
+            function drain(uint64 amount) external {
+                tx.accounts.vault.lamports -= amount;
+                tx.accounts.to.lamports += amount;
+            }
+        }"#,
+        Target::Solana,
+    );
+
+    assert_eq!(
+        found,
+        vec![
+            (
+                "missing-signer-check",
+                "account 'authority' is used for authorization, but \
+                'tx.accounts.authority.is_signer' is never checked"
+                    .into()
+            ),
+            (
+                "missing-signer-check",
+                "lamports are transferred from account 'vault', but no account is checked to \
+                be a signer"
+                    .into()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn missing_owner_check() {
+    let found = target_findings(
+        r#"
+        contract C {
+            uint8 last;
+
+            @account(oracle)
+            function no_check() external {
+                last = tx.accounts.oracle.data[0];
+            }
+
+            @account(oracle)
+            function checked() external {
+                require(tx.accounts.oracle.owner == address"SysvarC1ock11111111111111111111111111111111");
+                last = tx.accounts.oracle.data[0];
+            }
+
+            @account(oracle)
+            function read_only() external view returns (uint8) {
+                return tx.accounts.oracle.data[0];
+            }
+        }"#,
+        Target::Solana,
+    );
+
+    assert_eq!(
+        found,
+        vec![(
+            "missing-owner-check",
+            "data of account 'oracle' is used, but 'tx.accounts.oracle.owner' is never checked"
+                .into()
+        )]
+    );
+}
+
+#[test]
+fn pda_seeds_payer() {
+    let found = target_findings(
+        r#"
+        contract Global {
+            @payer(payer)
+            @seed("global")
+            constructor() {}
+        }
+
+        contract PerUser {
+            @payer(payer)
+            @seed("user")
+            constructor(@seed bytes user) {
+                require(keccak256(user) == keccak256(abi.encode(tx.accounts.payer.key)));
+            }
+        }"#,
+        Target::Solana,
+    );
+
+    assert_eq!(
+        found,
+        vec![(
+            "pda-seeds-payer",
+            "seeds of the program derived address do not depend on payer 'payer', so anyone \
+            can create the account first"
+                .into()
+        )]
+    );
+}
+
+#[test]
+fn cpi_privilege_escalation() {
+    let found = target_findings(
+        r#"
+        import 'solana';
+
+        contract C {
+            @account(readonly)
+            @mutableAccount(writable)
+            @account(pda)
+            function f(address program, bytes seed) external {
+                AccountMeta[2] metas = [
+                    AccountMeta({pubkey: tx.accounts.readonly.key, is_writable: true, is_signer: true}),
+                    AccountMeta({pubkey: tx.accounts.writable.key, is_writable: true, is_signer: false})
+                ];
+                program.call{accounts: metas}("");
+
+                AccountMeta[1] signed = [
+                    AccountMeta({pubkey: tx.accounts.pda.key, is_writable: false, is_signer: true})
+                ];
+                program.call{accounts: signed, seeds: [[seed]]}("");
+            }
+        }"#,
+        Target::Solana,
+    )
+    .into_iter()
+    .filter(|(detector, _)| *detector == "cpi-privilege-escalation")
+    .collect::<Vec<_>>();
+
+    assert_eq!(
+        found,
+        vec![
+            (
+                "cpi-privilege-escalation",
+                "account 'readonly' is passed to the external call as a signer, but the \
+                instruction does not receive it as a signer"
+                    .into()
+            ),
+            (
+                "cpi-privilege-escalation",
+                "account 'readonly' is passed to the external call as writable, but the \
+                instruction does not receive it as writable"
+                    .into()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn sarif() {
+    let ns = resolve(
+        r#"contract C {
+    function f(address a) public view {
+        require(tx.origin == a);
+    }
+}"#,
+        Target::EVM,
+    );
+
+    let detectors = detectors();
+    let findings = lint(&ns, &detectors);
+    let log = serde_json::to_value(sarif_log(&[(&ns, findings)], &detectors)).unwrap();
+
+    assert_eq!(log["version"], "2.1.0");
+
+    let run = &log["runs"][0];
+
+    assert_eq!(run["tool"]["driver"]["name"], "solang");
+    assert_eq!(
+        run["tool"]["driver"]["rules"].as_array().unwrap().len(),
+        detectors.len()
+    );
+
+    let result = &run["results"][0];
+
+    assert_eq!(result["ruleId"], "tx-origin");
+    assert_eq!(result["level"], "warning");
+
+    let location = &result["locations"][0]["physicalLocation"];
+
+    assert_eq!(location["artifactLocation"]["uri"], "test.sol");
+    assert_eq!(location["region"]["startLine"], 3);
+    assert_eq!(location["region"]["startColumn"], 17);
+    assert_eq!(location["region"]["endLine"], 3);
+    assert_eq!(location["region"]["endColumn"], 31);
+}
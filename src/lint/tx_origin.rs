@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{functions_with_body, recurse_expressions, strip_casts, Detector};
+use crate::sema::ast::{Builtin, Diagnostic, Expression, Namespace};
+use crate::sema::diagnostics::Diagnostics;
+
+/// Comparing `tx.origin` with an address to decide whether the caller is allowed to do something.
+/// A contract called by the authorized account can then act on its behalf.
+pub struct TxOrigin;
+
+impl Detector for TxOrigin {
+    fn id(&self) -> &'static str {
+        "tx-origin"
+    }
+
+    fn description(&self) -> &'static str {
+        "tx.origin is used for authorization"
+    }
+
+    fn check(&self, ns: &Namespace, diagnostics: &mut Diagnostics) {
+        for func in functions_with_body(ns) {
+            recurse_expressions(func, diagnostics, comparison);
+        }
+    }
+}
+
+fn comparison(expr: &Expression, diagnostics: &mut Diagnostics) -> bool {
+    if let Expression::Equal { loc, left, right } | Expression::NotEqual { loc, left, right } = expr
+    {
+        let left = strip_casts(left);
+        let right = strip_casts(right);
+
+        // tx.origin == msg.sender checks that the caller is not a contract, which is fine
+        if (is_builtin(left, Builtin::Origin) && !is_builtin(right, Builtin::Sender))
+            || (is_builtin(right, Builtin::Origin) && !is_builtin(left, Builtin::Sender))
+        {
+            diagnostics.push(Diagnostic::warning(
+                *loc,
+                "'tx.origin' is used for authorization; use 'msg.sender' instead".into(),
+            ));
+        }
+    }
+
+    true
+}
+
+fn is_builtin(expr: &Expression, builtin: Builtin) -> bool {
+    matches!(expr, Expression::Builtin { kind, .. } if *kind == builtin)
+}
@@ -0,0 +1,59 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{functions_with_body, statements, Detector};
+use crate::sema::ast::{Diagnostic, Expression, Namespace, Statement};
+use crate::sema::diagnostics::Diagnostics;
+use crate::sema::Recurse;
+
+/// A loop which iterates over the length of a storage array. Anyone who can make the array grow
+/// can make the loop run out of gas, so the function can no longer be called.
+pub struct UnboundedLoop;
+
+impl Detector for UnboundedLoop {
+    fn id(&self) -> &'static str {
+        "unbounded-loop"
+    }
+
+    fn description(&self) -> &'static str {
+        "a loop iterates over a storage array without bound"
+    }
+
+    fn check(&self, ns: &Namespace, diagnostics: &mut Diagnostics) {
+        for func in functions_with_body(ns) {
+            for stmt in statements(&func.body) {
+                let (loc, cond) = match stmt {
+                    Statement::For {
+                        loc,
+                        cond: Some(cond),
+                        ..
+                    }
+                    | Statement::While(loc, _, cond, _)
+                    | Statement::DoWhile(loc, _, _, cond) => (loc, cond),
+                    _ => continue,
+                };
+
+                let mut length = None;
+
+                cond.recurse(&mut length, storage_length);
+
+                if let Some(length_loc) = length {
+                    diagnostics.push(Diagnostic::warning_with_note(
+                        *loc,
+                        "loop over storage array has no upper bound and may run out of gas".into(),
+                        length_loc,
+                        "length of storage array".into(),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+fn storage_length(expr: &Expression, length: &mut Option<solang_parser::pt::Loc>) -> bool {
+    if let Expression::StorageArrayLength { loc, .. } = expr {
+        length.get_or_insert(*loc);
+        return false;
+    }
+
+    length.is_none()
+}
@@ -0,0 +1,67 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{functions_with_body, statements, Detector};
+use crate::sema::ast::{Builtin, DestructureField, Diagnostic, Expression, Namespace, Statement};
+use crate::sema::diagnostics::Diagnostics;
+use solang_parser::pt::CodeLocation;
+
+/// Low-level calls and `send()` do not revert when they fail; they return false. If the result
+/// is ignored, the contract continues as if the call succeeded.
+pub struct UncheckedCall;
+
+impl Detector for UncheckedCall {
+    fn id(&self) -> &'static str {
+        "unchecked-call"
+    }
+
+    fn description(&self) -> &'static str {
+        "the success of a low-level call is not checked"
+    }
+
+    fn check(&self, ns: &Namespace, diagnostics: &mut Diagnostics) {
+        for func in functions_with_body(ns) {
+            for stmt in statements(&func.body) {
+                match stmt {
+                    Statement::Expression(_, _, expr) if is_low_level(expr) => {
+                        diagnostics.push(unchecked(expr));
+                    }
+                    Statement::Destructure(_, fields, expr) if is_low_level(expr) => {
+                        let checked = match fields.first() {
+                            Some(DestructureField::VariableDecl(var_no, _)) => {
+                                func.symtable.vars[var_no].read
+                            }
+                            Some(DestructureField::Expression(_)) => true,
+                            _ => false,
+                        };
+
+                        if !checked {
+                            diagnostics.push(unchecked(expr));
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+fn is_low_level(expr: &Expression) -> bool {
+    matches!(
+        expr,
+        Expression::ExternalFunctionCallRaw { .. }
+            | Expression::Builtin {
+                kind: Builtin::PayableSend,
+                ..
+            }
+    )
+}
+
+fn unchecked(expr: &Expression) -> Diagnostic {
+    let message = if matches!(expr, Expression::Builtin { .. }) {
+        "return value of 'send' is not checked"
+    } else {
+        "success of low-level call is not checked"
+    };
+
+    Diagnostic::warning(expr.loc(), message.into())
+}
@@ -0,0 +1,117 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{functions_with_body, statement_expressions, statements, Detector};
+use crate::sema::ast::{DestructureField, Diagnostic, Expression, Namespace, Statement, Type};
+use crate::sema::diagnostics::Diagnostics;
+use crate::sema::Recurse;
+use solang_parser::pt::Loc;
+
+/// A local storage reference which is used before anything is assigned to it. Such a reference
+/// does not point to any particular state variable, so reading or writing through it is a bug.
+pub struct UninitializedStorage;
+
+impl Detector for UninitializedStorage {
+    fn id(&self) -> &'static str {
+        "uninitialized-storage"
+    }
+
+    fn description(&self) -> &'static str {
+        "a storage reference is used before it is initialized"
+    }
+
+    fn check(&self, ns: &Namespace, diagnostics: &mut Diagnostics) {
+        for func in functions_with_body(ns) {
+            let stmts = statements(&func.body);
+
+            for (pos, stmt) in stmts.iter().enumerate() {
+                let Statement::VariableDecl(decl_loc, var_no, param, None) = stmt else {
+                    continue;
+                };
+
+                if !matches!(param.ty, Type::StorageRef(..)) {
+                    continue;
+                }
+
+                // Statements are visited in source order; stop at the first assignment
+                let mut state = Usage {
+                    var_no: *var_no,
+                    assigned: false,
+                    used: None,
+                };
+
+                for stmt in &stmts[pos + 1..] {
+                    if let Statement::Destructure(_, fields, expr) = stmt {
+                        expr.recurse(&mut state, usage);
+
+                        for field in fields {
+                            if let DestructureField::Expression(left) = field {
+                                if state.assigns(left) {
+                                    state.assigned = state.used.is_none();
+                                } else {
+                                    left.recurse(&mut state, usage);
+                                }
+                            }
+                        }
+                    } else {
+                        for expr in statement_expressions(stmt) {
+                            expr.recurse(&mut state, usage);
+                        }
+                    }
+
+                    if state.assigned || state.used.is_some() {
+                        break;
+                    }
+                }
+
+                if let Some(loc) = state.used {
+                    diagnostics.push(Diagnostic::warning_with_note(
+                        loc,
+                        format!(
+                            "storage reference '{}' is used before it is initialized",
+                            func.symtable.vars[var_no].id.name
+                        ),
+                        *decl_loc,
+                        "declared without initializer".into(),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+struct Usage {
+    var_no: usize,
+    assigned: bool,
+    used: Option<Loc>,
+}
+
+impl Usage {
+    /// Is this the variable itself, on the left hand side of an assignment
+    fn assigns(&self, left: &Expression) -> bool {
+        matches!(left, Expression::Variable { var_no, .. } if *var_no == self.var_no)
+    }
+}
+
+fn usage(expr: &Expression, state: &mut Usage) -> bool {
+    if state.assigned || state.used.is_some() {
+        return false;
+    }
+
+    match expr {
+        Expression::Assign { left, right, .. } if matches!(left.as_ref(), Expression::Variable { var_no, .. } if *var_no == state.var_no) =>
+        {
+            right.recurse(state, usage);
+
+            if state.used.is_none() {
+                state.assigned = true;
+            }
+
+            false
+        }
+        Expression::Variable { loc, var_no, .. } if *var_no == state.var_no => {
+            state.used = Some(*loc);
+            false
+        }
+        _ => true,
+    }
+}
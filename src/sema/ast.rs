@@ -2,6 +2,7 @@
 
 use super::symtable::Symtable;
 use crate::abi::anchor::function_discriminator;
+use crate::abi::polkadot::MetadataVersion;
 use crate::codegen::cfg::{ControlFlowGraph, Instr};
 use crate::diagnostics::Diagnostics;
 use crate::sema::ast::ExternalCallAccounts::{AbsentArgument, NoAccount};
@@ -291,7 +292,7 @@ impl Parameter<Type> {
     }
 }
 
-#[derive(PartialEq, Eq, Clone, Hash, Debug)]
+#[derive(Eq, Clone, Debug)]
 pub enum Mutability {
     Payable(pt::Loc),
     Nonpayable(pt::Loc),
@@ -299,6 +300,20 @@ pub enum Mutability {
     Pure(pt::Loc),
 }
 
+// Ensure the loc is not used for comparison or hashing, so that function types declared
+// in different places are the same type
+impl PartialEq for Mutability {
+    fn eq(&self, other: &Mutability) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+impl hash::Hash for Mutability {
+    fn hash<H: hash::Hasher>(&self, hasher: &mut H) {
+        std::mem::discriminant(self).hash(hasher);
+    }
+}
+
 impl Mutability {
     pub fn is_default(&self) -> bool {
         matches!(self, Mutability::Nonpayable(_))
@@ -552,8 +567,10 @@ impl From<&pt::Type> for Type {
             pt::Type::String => Type::String,
             pt::Type::Rational => Type::Rational,
             pt::Type::DynamicBytes => Type::DynamicBytes,
-            // needs special casing
-            pt::Type::Function { .. } | pt::Type::Mapping { .. } => unimplemented!(),
+            // these are resolved by Namespace::resolve_type(), as they contain other types
+            pt::Type::Function { .. } | pt::Type::Mapping { .. } => {
+                unreachable!("function and mapping types need resolving")
+            }
         }
     }
 }
@@ -690,6 +707,9 @@ pub struct Namespace {
     pub address_length: usize,
     /// value length in bytes
     pub value_length: usize,
+    /// The version of the ink! metadata on Polkadot, which decides how events are emitted. This
+    /// is set from the codegen options.
+    pub metadata_version: MetadataVersion,
     pub diagnostics: Diagnostics,
     /// There is a separate namespace for functions and non-functions
     pub function_symbols: HashMap<(usize, Option<usize>, String), Symbol>,
@@ -702,6 +722,18 @@ pub struct Namespace {
     pub var_constants: HashMap<pt::Loc, codegen::Expression>,
     /// Overrides for hover in the language server
     pub hover_overrides: HashMap<pt::Loc, String>,
+    /// Functions whose body has not been resolved yet, so that their body can be resolved
+    /// when a constant expression calls them
+    pub unresolved_function_bodies: HashMap<usize, UnresolvedFunctionBody>,
+}
+
+/// The body of a function which can be resolved on demand
+#[derive(Debug)]
+pub struct UnresolvedFunctionBody {
+    pub file_no: usize,
+    pub contract_no: Option<usize>,
+    pub def: pt::FunctionDefinition,
+    pub annotations: Vec<pt::Annotation>,
 }
 
 #[derive(Debug)]
@@ -773,6 +805,17 @@ pub struct Layout {
     pub contract_no: usize,
     pub var_no: usize,
     pub ty: Type,
+    /// Set if the variable shares its storage slot with other variables
+    pub packed: Option<PackedLayout>,
+}
+
+/// The position of a variable within a storage slot, when small variables are packed together
+#[derive(Debug)]
+pub struct PackedLayout {
+    /// Offset in bytes of the variable within the storage slot
+    pub offset: u32,
+    /// Type of the entire storage slot, an unsigned integer
+    pub slot_ty: Type,
 }
 
 #[derive(Debug)]
@@ -813,6 +856,8 @@ pub struct Contract {
     pub using: Vec<Using>,
     pub layout: Vec<Layout>,
     pub fixed_layout_size: BigInt,
+    /// The first storage slot, if specified with `layout at`
+    pub layout_base: Option<(pt::Loc, BigInt)>,
     pub functions: Vec<usize>,
     pub all_functions: BTreeMap<usize, usize>,
     /// maps the name of virtual functions to a vector of overriden functions.
@@ -6,13 +6,16 @@ use super::{
     expression::{compatible_mutability, ExprContext},
     functions, statements,
     symtable::Symtable,
-    using, variables, ContractDefinition,
+    tags, using, variables, ContractDefinition,
 };
-use crate::sema::ast::SolanaAccount;
+use crate::sema::ast::{RetrieveType, SolanaAccount};
+use crate::sema::eval::eval_const_number;
 use crate::sema::expression::constructor::match_constructor_to_args;
+use crate::sema::expression::{resolve_expression::expression, ResolveTo};
+use crate::Target;
 use crate::{sema::ast::Namespace, sema::unused_variable::emit_warning_local_variable};
 use indexmap::{IndexMap, IndexSet};
-use num_bigint::BigInt;
+use num_bigint::{BigInt, Sign};
 use num_traits::Zero;
 use once_cell::unsync::OnceCell;
 use solang_parser::diagnostics::{Diagnostic, Note};
@@ -40,6 +43,7 @@ impl ast::Contract {
             using: Vec::new(),
             layout: Vec::new(),
             fixed_layout_size: BigInt::zero(),
+            layout_base: None,
             tags,
             functions: Vec::new(),
             all_functions: BTreeMap::new(),
@@ -84,11 +88,62 @@ pub fn resolve(contracts: &[ContractDefinition], file_no: usize, ns: &mut ast::N
     // Resolve base contract constructor arguments on contract definition (not constructor definitions)
     resolve_base_args(contracts, file_no, ns);
 
+    // The layout base may refer to constants declared in the contract
+    resolve_layout_base(contracts, file_no, ns);
+
+    // Constant initializers may call pure functions, so their bodies can be resolved on demand
+    // from now on
+    for body in &delayed.function_bodies {
+        if ns.functions[body.function_no].is_pure() {
+            ns.unresolved_function_bodies.insert(
+                body.function_no,
+                ast::UnresolvedFunctionBody {
+                    file_no,
+                    contract_no: Some(body.contract_no),
+                    def: pt::FunctionDefinition::clone(body.function),
+                    annotations: body.annotations.iter().map(|a| (*a).clone()).collect(),
+                },
+            );
+        }
+    }
+
+    if !delayed.variables.is_empty() {
+        // Some state variables use constants which call contract functions in their type, e.g. as
+        // an array length. Resolve the constants before declaring those state variables; the
+        // functions can only be called once the inheritance is known.
+        for contract in contracts {
+            check_inheritance(contract.contract_no, ns);
+        }
+
+        let (constants, initializers): (Vec<_>, Vec<_>) = std::mem::take(&mut delayed.initializers)
+            .into_iter()
+            .partition(|init| init.constant);
+
+        variables::resolve_initializers(&constants, file_no, ns);
+
+        delayed.initializers = initializers;
+        delayed
+            .initializers
+            .extend(variables::delayed_contract_variables(
+                &delayed.variables,
+                file_no,
+                ns,
+            ));
+
+        // check the inheritance again below, now the accessor functions of these variables exist
+        for contract in contracts {
+            ns.contracts[contract.contract_no].all_functions.clear();
+            ns.contracts[contract.contract_no].virtual_functions.clear();
+        }
+    }
+
     // Now we have all the declarations, we can handle base contracts
     for contract in contracts {
         let contract_no = contract.contract_no;
 
         check_inheritance(contract_no, ns);
+        tags::check_inheritdoc(contract_no, ns);
+        check_layout_base(contract_no, ns);
         mangle_function_names(contract_no, ns);
         verify_unique_selector(contract_no, ns);
         polkadot_requires_public_functions(contract_no, ns);
@@ -241,6 +296,106 @@ fn resolve_base_args(contracts: &[ContractDefinition], file_no: usize, ns: &mut
     ns.diagnostics.extend(diagnostics);
 }
 
+/// Resolve the `layout at <expr>` specifier, which sets the first storage slot of the contract
+fn resolve_layout_base(contracts: &[ContractDefinition], file_no: usize, ns: &mut ast::Namespace) {
+    let mut diagnostics = Diagnostics::default();
+
+    for contract in contracts {
+        let Some(layout) = contract.layout else {
+            continue;
+        };
+
+        if !matches!(contract.ty, pt::ContractTy::Contract(_)) {
+            diagnostics.push(ast::Diagnostic::error(
+                layout.loc,
+                format!(
+                    "storage layout can only be specified for contracts, not for {}",
+                    contract.ty
+                ),
+            ));
+            continue;
+        }
+
+        if ns.target == Target::Solana {
+            diagnostics.push(ast::Diagnostic::error(
+                layout.loc,
+                "storage layout base is not supported on Solana".to_string(),
+            ));
+            continue;
+        }
+
+        let mut context = ExprContext {
+            file_no,
+            contract_no: Some(contract.contract_no),
+            unchecked: true,
+            constant: true,
+            ..Default::default()
+        };
+        context.enter_scope();
+
+        let mut symtable = Symtable::default();
+
+        let Ok(base_slot) = expression(
+            &layout.base_slot,
+            &mut context,
+            ns,
+            &mut symtable,
+            &mut diagnostics,
+            ResolveTo::Type(&ast::Type::Uint(256)),
+        ) else {
+            continue;
+        };
+
+        if !matches!(base_slot.ty(), ast::Type::Uint(_) | ast::Type::Int(_)) {
+            diagnostics.push(ast::Diagnostic::error(
+                layout.base_slot.loc(),
+                "storage layout base must be an integer".to_string(),
+            ));
+            continue;
+        }
+
+        let Ok((_, value)) = eval_const_number(&base_slot, ns, &mut diagnostics) else {
+            continue;
+        };
+
+        if value.sign() == Sign::Minus || value.bits() > 256 {
+            diagnostics.push(ast::Diagnostic::error(
+                layout.base_slot.loc(),
+                format!("storage layout base {value} does not fit into 'uint256'"),
+            ));
+            continue;
+        }
+
+        ns.contracts[contract.contract_no].layout_base = Some((layout.loc, value));
+    }
+
+    ns.diagnostics.extend(diagnostics);
+}
+
+/// The storage layout base may only be specified in the most derived contract
+fn check_layout_base(contract_no: usize, ns: &mut ast::Namespace) {
+    for base_contract_no in ns.contract_bases(contract_no) {
+        if base_contract_no == contract_no {
+            continue;
+        }
+
+        if let Some((loc, _)) = &ns.contracts[base_contract_no].layout_base {
+            ns.diagnostics.push(ast::Diagnostic::error_with_note(
+                ns.contracts[contract_no].id.loc,
+                format!(
+                    "contract '{}' inherits from '{}' which specifies a storage layout; only the most derived contract may specify one",
+                    ns.contracts[contract_no].id, ns.contracts[base_contract_no].id
+                ),
+                *loc,
+                format!(
+                    "storage layout of '{}' specified here",
+                    ns.contracts[base_contract_no].id
+                ),
+            ));
+        }
+    }
+}
+
 impl ast::Namespace {
     /// base contracts in depth-first post-order
     pub fn contract_bases(&self, contract_no: usize) -> Vec<usize> {
@@ -953,6 +1108,7 @@ struct DelayedResolveFunction<'a> {
 struct ResolveLater<'a> {
     function_bodies: Vec<DelayedResolveFunction<'a>>,
     initializers: Vec<variables::DelayedResolveInitializer<'a>>,
+    variables: Vec<variables::DelayedVariable<'a>>,
 }
 
 /// Resolve functions declarations, constructor declarations, and contract variables
@@ -972,9 +1128,12 @@ fn resolve_declarations<'a>(
 
     // resolve state variables. We may need a constant to resolve the array
     // dimension of a function argument.
-    delayed
-        .initializers
-        .extend(variables::contract_variables(def, file_no, ns));
+    delayed.initializers.extend(variables::contract_variables(
+        def,
+        file_no,
+        ns,
+        &mut delayed.variables,
+    ));
 
     // resolve function signatures
     for part in &def.parts {
@@ -1057,6 +1216,13 @@ fn resolve_bodies(
         annotations,
     } in bodies
     {
+        // pure functions may have been resolved on demand already
+        if ns.functions[function_no].is_pure()
+            && ns.unresolved_function_bodies.remove(&function_no).is_none()
+        {
+            continue;
+        }
+
         if statements::resolve_function_body(
             function,
             &annotations,
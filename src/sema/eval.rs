@@ -3,7 +3,7 @@
 use super::{
     ast::{Builtin, Diagnostic, Expression, Namespace, Type},
     diagnostics::Diagnostics,
-    Recurse,
+    interpreter, Recurse,
 };
 use num_bigint::BigInt;
 use num_bigint::Sign;
@@ -180,7 +180,18 @@ pub fn eval_const_number(
             if let Some(init) = &var.initializer {
                 eval_const_number(init, ns, diagnostics)
             } else {
-                // we should have errored about this already
+                // constants which call contract functions are resolved after the declarations,
+                // otherwise we should have errored about this already
+                if var.assigned && !diagnostics.any_errors() && !ns.diagnostics.any_errors() {
+                    diagnostics.push(Diagnostic::error(
+                        expr.loc(),
+                        format!(
+                            "constant '{}' calls contract functions, so its value is not known yet",
+                            var.name
+                        ),
+                    ));
+                }
+
                 Err(EvaluationError::NotAConstant)
             }
         }
@@ -235,6 +246,26 @@ pub fn eval_const_number(
 
             Ok((*loc, value))
         }
+        Expression::InternalFunctionCall { loc, .. }
+        | Expression::UserDefinedOperator { loc, .. } => {
+            // calls to pure functions are evaluated by the interpreter
+            match interpreter::eval_const_expression(expr, None, ns) {
+                Ok(Expression::NumberLiteral { value, .. }) => Ok((*loc, value)),
+                Ok(_) => {
+                    diagnostics.push(Diagnostic::error(
+                        *loc,
+                        "expression not allowed in constant number expression".to_string(),
+                    ));
+
+                    Err(EvaluationError::NotAConstant)
+                }
+                Err(err) => {
+                    diagnostics.push(err.diagnostic(*loc, ns));
+
+                    Err(EvaluationError::NotAConstant)
+                }
+            }
+        }
         _ => {
             diagnostics.push(Diagnostic::error(
                 expr.loc(),
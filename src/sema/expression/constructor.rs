@@ -214,6 +214,67 @@ pub fn match_constructor_to_args(
     }
 }
 
+/// Check that the code of contract `no` can be referenced from the current context, and
+/// record that the current contract creates it
+pub(crate) fn contract_code_reference(
+    loc: &pt::Loc,
+    no: usize,
+    context: &ExprContext,
+    ns: &mut Namespace,
+    diagnostics: &mut Diagnostics,
+) -> Result<(), ()> {
+    if !ns.contracts[no].instantiable {
+        diagnostics.push(Diagnostic::error(
+            *loc,
+            format!(
+                "cannot construct '{}' of type '{}'",
+                ns.contracts[no].id, ns.contracts[no].ty
+            ),
+        ));
+
+        return Err(());
+    }
+
+    // This is not always in a function: e.g. contract constant:
+    // contract C {
+    //      bytes constant code = type(D).runtimeCode;
+    // }
+    if let Some(function_no) = context.function_no {
+        ns.functions[function_no].creates.push((*loc, no));
+    }
+
+    if let Some(contract_no) = context.contract_no {
+        // check for circular references
+        if no == contract_no {
+            diagnostics.push(Diagnostic::error(
+                *loc,
+                format!(
+                    "cannot construct current contract '{}'",
+                    ns.contracts[no].id
+                ),
+            ));
+            return Err(());
+        }
+
+        if circular_reference(no, contract_no, ns) {
+            diagnostics.push(Diagnostic::error(
+                *loc,
+                format!(
+                    "circular reference creating contract code for '{}'",
+                    ns.contracts[no].id
+                ),
+            ));
+            return Err(());
+        }
+
+        if !ns.contracts[contract_no].creates.contains(&no) {
+            ns.contracts[contract_no].creates.push(no);
+        }
+    }
+
+    Ok(())
+}
+
 /// check if from creates to, recursively
 pub(super) fn circular_reference(from: usize, to: usize, ns: &Namespace) -> bool {
     if ns.contracts[from].creates.contains(&to) {
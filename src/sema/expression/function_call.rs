@@ -46,14 +46,41 @@ pub(super) fn call_function_type(
         return Err(());
     }
 
-    let mut function = expression(expr, context, ns, symtable, diagnostics, ResolveTo::Unknown)?;
+    let function = expression(expr, context, ns, symtable, diagnostics, ResolveTo::Unknown)?;
 
+    call_resolved_function_type(
+        loc,
+        function,
+        args,
+        call_args,
+        call_args_loc,
+        context,
+        ns,
+        symtable,
+        diagnostics,
+        resolve_to,
+    )
+}
+
+/// Resolve a function call via an expression of function type, which has already been resolved
+fn call_resolved_function_type(
+    loc: &pt::Loc,
+    mut function: Expression,
+    args: &[pt::Expression],
+    call_args: &[&pt::NamedArgument],
+    call_args_loc: Option<pt::Loc>,
+    context: &mut ExprContext,
+    ns: &mut Namespace,
+    symtable: &mut Symtable,
+    diagnostics: &mut Diagnostics,
+    resolve_to: ResolveTo,
+) -> Result<Expression, ()> {
     let mut ty = function.ty();
 
     match ty {
         Type::StorageRef(_, real_ty) | Type::Ref(real_ty) => {
             ty = *real_ty;
-            function = function.cast(&expr.loc(), &ty, true, ns, diagnostics)?;
+            function = function.cast(&function.loc(), &ty, true, ns, diagnostics)?;
         }
         _ => (),
     };
@@ -315,14 +342,6 @@ pub fn function_call_pos_args(
         return Ok(Expression::TypeOperator { loc: *loc, ty });
     }
 
-    if context.constant {
-        diagnostics.push(Diagnostic::error(
-            *loc,
-            "cannot call function in constant expression".to_string(),
-        ));
-        return Err(());
-    }
-
     // try to resolve the arguments, give up if there are any errors
     if args.iter().fold(false, |acc, arg| {
         acc | expression(arg, context, ns, symtable, diagnostics, ResolveTo::Unknown).is_err()
@@ -1512,14 +1531,6 @@ pub(super) fn method_call_pos_args(
         return Ok(resolved_call);
     }
 
-    if context.constant {
-        diagnostics.push(Diagnostic::error(
-            *loc,
-            "cannot call function in constant expression".to_string(),
-        ));
-        return Err(());
-    }
-
     if let Some(mut path) = ns.expr_to_identifier_path(var) {
         // `path.loc` needs to be modified `func.loc`
         path.identifiers.push(func.clone());
@@ -1532,6 +1543,7 @@ pub(super) fn method_call_pos_args(
         ) {
             if let Some(callee_contract) =
                 is_solana_external_call(&list, context.contract_no, &call_args_loc, ns)
+                    .filter(|_| !context.constant)
             {
                 if let Some(resolved_call) = contract_call_pos_args(
                     &var.loc(),
@@ -1573,8 +1585,31 @@ pub(super) fn method_call_pos_args(
         }
     }
 
+    if context.constant {
+        diagnostics.push(Diagnostic::error(
+            *loc,
+            "cannot call function in constant expression".to_string(),
+        ));
+        return Err(());
+    }
+
     let var_expr = expression(var, context, ns, symtable, diagnostics, ResolveTo::Unknown)?;
 
+    if let Some(function) = function_type_field(&var_expr, func, ns) {
+        return call_resolved_function_type(
+            loc,
+            function,
+            args,
+            call_args,
+            call_args_loc,
+            context,
+            ns,
+            symtable,
+            diagnostics,
+            resolve_to,
+        );
+    }
+
     if let Some(resolved_call) =
         builtin::resolve_method_call(&var_expr, func, args, context, ns, symtable, diagnostics)?
     {
@@ -1661,6 +1696,54 @@ pub(super) fn method_call_pos_args(
     Err(())
 }
 
+/// If the expression is a struct with a field of function type by the given name, return
+/// the struct member so that the function it points to can be called
+fn function_type_field(
+    var_expr: &Expression,
+    func: &pt::Identifier,
+    ns: &Namespace,
+) -> Option<Expression> {
+    let var_ty = var_expr.ty();
+
+    let (str_ty, storage) = match &var_ty {
+        Type::StorageRef(_, ty) => (ty.as_ref(), true),
+        ty => (ty.deref_memory(), false),
+    };
+
+    let Type::Struct(str_ty) = str_ty else {
+        return None;
+    };
+
+    let (field_no, field) = str_ty
+        .definition(ns)
+        .fields
+        .iter()
+        .enumerate()
+        .find(|(_, field)| func.name == field.name_as_str())?;
+
+    if !matches!(
+        field.ty,
+        Type::InternalFunction { .. } | Type::ExternalFunction { .. }
+    ) {
+        return None;
+    }
+
+    let ty = if let (true, Type::StorageRef(immutable, _)) = (storage, &var_ty) {
+        Type::StorageRef(*immutable, Box::new(field.ty.clone()))
+    } else if field.readonly {
+        field.ty.clone()
+    } else {
+        Type::Ref(Box::new(field.ty.clone()))
+    };
+
+    Some(Expression::StructMember {
+        loc: func.loc,
+        ty,
+        expr: Box::new(var_expr.clone()),
+        field: field_no,
+    })
+}
+
 pub(super) fn method_call_named_args(
     loc: &pt::Loc,
     var: &pt::Expression,
@@ -1827,6 +1910,7 @@ pub(super) fn method_call_named_args(
         ) {
             if let Some(callee_contract) =
                 is_solana_external_call(&list, context.contract_no, &call_args_loc, ns)
+                    .filter(|_| !context.constant)
             {
                 return contract_call_named_args(
                     &var.loc(),
@@ -2301,14 +2385,6 @@ pub fn named_call_expr(
     }
 
     // not a struct literal, remove those errors and try resolving as function call
-    if context.constant {
-        diagnostics.push(Diagnostic::error(
-            *loc,
-            "cannot call function in constant expression".to_string(),
-        ));
-        return Err(());
-    }
-
     let expr = named_function_call_expr(
         loc,
         ty,
@@ -2673,7 +2749,17 @@ fn resolve_internal_call(
 ) -> Option<Expression> {
     let func = &ns.functions[function_no];
 
-    if func.contract_no != context.contract_no && func.is_private() {
+    // constant expressions are evaluated at compile time, which is only possible for pure functions
+    if context.constant && !func.is_pure() {
+        errors.push(Diagnostic::error_with_note(
+            *loc,
+            "cannot call function in constant expression".to_string(),
+            func.loc_prototype,
+            format!("{} '{}' is not declared pure", func.ty, func.id),
+        ));
+
+        return None;
+    } else if func.contract_no != context.contract_no && func.is_private() {
         errors.push(Diagnostic::error_with_note(
             *loc,
             format!("cannot call private {}", func.ty),
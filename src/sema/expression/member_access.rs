@@ -5,7 +5,7 @@ use crate::sema::ast::{
 };
 use crate::sema::builtin;
 use crate::sema::diagnostics::Diagnostics;
-use crate::sema::expression::constructor::circular_reference;
+use crate::sema::expression::constructor::contract_code_reference;
 use crate::sema::expression::function_call::function_type;
 use crate::sema::expression::integers::bigint_to_expression;
 use crate::sema::expression::resolve_expression::expression;
@@ -524,6 +524,24 @@ fn contract_constant(
         return Ok(None);
     }
 
+    // a constant declared in an imported file, like `import * as defs from "defs.sol"; defs.LIMIT`
+    if let Some(Symbol::Import(_, import_file_no)) =
+        ns.variable_symbols
+            .get(&(context.file_no, None, namespace.name.to_owned()))
+    {
+        if let Some(Symbol::Variable(_, None, var_no)) =
+            ns.variable_symbols
+                .get(&(*import_file_no, None, id.name.to_owned()))
+        {
+            return Ok(Some(Expression::ConstantVariable {
+                loc: *loc,
+                ty: ns.constants[*var_no].ty.clone(),
+                contract_no: None,
+                var_no: *var_no,
+            }));
+        }
+    }
+
     if let Some(contract_no) = ns.resolve_contract(context.file_no, namespace) {
         if let Some((var_no, var)) = ns.contracts[contract_no]
             .variables
@@ -774,54 +792,7 @@ fn type_name_expr(
         }
         "creationCode" | "runtimeCode" => {
             if let Type::Contract(no) = ty {
-                if !ns.contracts[*no].instantiable {
-                    diagnostics.push(Diagnostic::error(
-                        *loc,
-                        format!(
-                            "cannot construct '{}' of type '{}'",
-                            ns.contracts[*no].id, ns.contracts[*no].ty
-                        ),
-                    ));
-
-                    return Err(());
-                }
-
-                // This is not always in a function: e.g. contract constant:
-                // contract C {
-                //      bytes constant code = type(D).runtimeCode;
-                // }
-                if let Some(function_no) = context.function_no {
-                    ns.functions[function_no].creates.push((*loc, *no));
-                }
-
-                if let Some(contract_no) = context.contract_no {
-                    // check for circular references
-                    if *no == contract_no {
-                        diagnostics.push(Diagnostic::error(
-                            *loc,
-                            format!(
-                                "cannot construct current contract '{}'",
-                                ns.contracts[*no].id
-                            ),
-                        ));
-                        return Err(());
-                    }
-
-                    if circular_reference(*no, contract_no, ns) {
-                        diagnostics.push(Diagnostic::error(
-                            *loc,
-                            format!(
-                                "circular reference creating contract code for '{}'",
-                                ns.contracts[*no].id
-                            ),
-                        ));
-                        return Err(());
-                    }
-
-                    if !ns.contracts[contract_no].creates.contains(no) {
-                        ns.contracts[contract_no].creates.push(*no);
-                    }
-                }
+                contract_code_reference(loc, *no, context, ns, diagnostics)?;
 
                 let kind = if field.name == "runtimeCode" {
                     if ns.target == Target::EVM {
@@ -0,0 +1,1538 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compile-time interpreter for constant initializers and array lengths which call `pure`
+//! functions. The interpreter walks the resolved bodies of the called functions, so it can
+//! only evaluate calls once the function bodies have been resolved.
+
+use super::{
+    ast::{
+        ArrayLength, Builtin, DestructureField, Diagnostic, Expression, Namespace, RetrieveType,
+        Statement, StringLocation, Type,
+    },
+    diagnostics::Diagnostics,
+    eval::eval_const_number,
+    statements::resolve_function_body,
+    Recurse,
+};
+use num_bigint::{BigInt, Sign};
+use num_traits::{One, ToPrimitive, Zero};
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+use solang_parser::pt::{self, CodeLocation, Loc};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use tiny_keccak::{Hasher, Keccak};
+
+/// Maximum number of statements and expressions which are evaluated for a single constant
+pub(crate) const STEP_LIMIT: usize = 1_000_000;
+
+/// Maximum depth of nested function calls
+const CALL_DEPTH_LIMIT: usize = 256;
+
+/// A value during compile-time evaluation. Memory arrays, structs and bytes are
+/// references, so that aliasing works as it does at runtime.
+#[derive(Clone, Debug)]
+enum Value {
+    Bool(bool),
+    Number(BigInt),
+    Bytes(Rc<RefCell<Vec<u8>>>),
+    Array(Rc<RefCell<Vec<Value>>>),
+    Struct(Rc<RefCell<Vec<Value>>>),
+    Function(Option<usize>),
+    List(Vec<Value>),
+}
+
+pub(crate) enum EvalError {
+    /// The body of the function has not been resolved yet, so the evaluation can be
+    /// retried once it has been resolved
+    Unresolved(usize),
+    /// The expression cannot be evaluated at compile time
+    Fail { loc: Loc, message: String },
+}
+
+impl EvalError {
+    /// Create the diagnostic for a failed evaluation of the expression at `loc`
+    pub(crate) fn diagnostic(self, loc: Loc, ns: &Namespace) -> Diagnostic {
+        match self {
+            EvalError::Unresolved(function_no) => Diagnostic::error(
+                loc,
+                format!(
+                    "function '{}' cannot be evaluated at compile time before its body is resolved",
+                    ns.functions[function_no].id
+                ),
+            ),
+            EvalError::Fail {
+                loc: fail_loc,
+                message,
+            } if fail_loc != loc && matches!(fail_loc, Loc::File(..)) => {
+                Diagnostic::error_with_note(
+                    loc,
+                    "expression cannot be evaluated at compile time".into(),
+                    fail_loc,
+                    message,
+                )
+            }
+            EvalError::Fail { message, .. } => Diagnostic::error(
+                loc,
+                format!("expression cannot be evaluated at compile time: {message}"),
+            ),
+        }
+    }
+}
+
+fn fail<T>(loc: &Loc, message: impl Into<String>) -> Result<T, EvalError> {
+    Err(EvalError::Fail {
+        loc: *loc,
+        message: message.into(),
+    })
+}
+
+/// Control flow after executing a statement
+enum Flow {
+    Next,
+    Break,
+    Continue,
+    Return(Option<Vec<Value>>),
+}
+
+/// Location which can be assigned to
+enum Place {
+    Variable(usize),
+    Element(Rc<RefCell<Vec<Value>>>, usize),
+    Byte(Rc<RefCell<Vec<u8>>>, usize),
+}
+
+struct Interpreter<'a> {
+    ns: &'a Namespace,
+    /// Contract used for resolving virtual functions
+    contract_no: Option<usize>,
+    steps: usize,
+    depth: usize,
+    /// Local variables of each active function call
+    frames: Vec<HashMap<usize, Value>>,
+}
+
+/// Does the expression call a function, either directly or via the initializer of a constant
+pub(crate) fn calls_function(expr: &Expression, ns: &Namespace) -> bool {
+    let mut cx = (ns, false);
+
+    expr.recurse(&mut cx, |expr, (ns, found)| match expr {
+        Expression::InternalFunctionCall { .. } | Expression::UserDefinedOperator { .. } => {
+            *found = true;
+            false
+        }
+        Expression::ConstantVariable {
+            contract_no,
+            var_no,
+            ..
+        } => {
+            let var = if let Some(contract_no) = contract_no {
+                &ns.contracts[*contract_no].variables[*var_no]
+            } else {
+                &ns.constants[*var_no]
+            };
+
+            if let Some(init) = &var.initializer {
+                *found |= calls_function(init, ns);
+            }
+
+            false
+        }
+        _ => !*found,
+    });
+
+    cx.1
+}
+
+/// Evaluate an expression at compile time, and return the result as a literal expression
+pub(crate) fn eval_const_expression(
+    expr: &Expression,
+    contract_no: Option<usize>,
+    ns: &Namespace,
+) -> Result<Expression, EvalError> {
+    let mut interpreter = Interpreter {
+        ns,
+        contract_no,
+        steps: 0,
+        depth: 0,
+        frames: vec![HashMap::new()],
+    };
+
+    let value = interpreter.expression(expr)?;
+
+    to_expression(&expr.loc(), value, &expr.ty(), ns)
+}
+
+/// If the expression calls functions, evaluate it at compile time. The bodies of free functions
+/// and pure contract functions are resolved on demand. If the expression cannot be evaluated yet, because a function body
+/// is not resolved yet, the expression is returned as-is.
+pub(crate) fn fold_const_expression(
+    expr: Expression,
+    contract_no: Option<usize>,
+    ns: &mut Namespace,
+    diagnostics: &mut Diagnostics,
+) -> Expression {
+    if !calls_function(&expr, ns) {
+        return expr;
+    }
+
+    loop {
+        match eval_const_expression(&expr, contract_no, ns) {
+            Ok(res) => return res,
+            Err(EvalError::Unresolved(function_no)) => {
+                let Some(body) = ns.unresolved_function_bodies.remove(&function_no) else {
+                    return expr;
+                };
+
+                let annotations: Vec<&pt::Annotation> = body.annotations.iter().collect();
+
+                if resolve_function_body(
+                    &body.def,
+                    &annotations,
+                    body.file_no,
+                    body.contract_no,
+                    function_no,
+                    ns,
+                )
+                .is_err()
+                {
+                    return expr;
+                }
+            }
+            Err(err) => {
+                diagnostics.push(err.diagnostic(expr.loc(), ns));
+                return expr;
+            }
+        }
+    }
+}
+
+/// Once all the function bodies of the file are resolved, evaluate the constant initializers
+/// which call functions
+pub(crate) fn fold_constants(file_no: usize, ns: &mut Namespace) {
+    let mut diagnostics = Diagnostics::default();
+
+    for var_no in 0..ns.constants.len() {
+        if ns.constants[var_no].loc.try_file_no() != Some(file_no) {
+            continue;
+        }
+
+        if let Some(init) = ns.constants[var_no].initializer.clone() {
+            if calls_function(&init, ns) {
+                let res = fold_constant_initializer(init, None, ns, &mut diagnostics);
+                ns.constants[var_no].initializer = Some(res);
+            }
+        }
+    }
+
+    for contract_no in 0..ns.contracts.len() {
+        if ns.contracts[contract_no].loc.try_file_no() != Some(file_no) {
+            continue;
+        }
+
+        for var_no in 0..ns.contracts[contract_no].variables.len() {
+            let var = &ns.contracts[contract_no].variables[var_no];
+
+            if !var.constant {
+                continue;
+            }
+
+            if let Some(init) = var.initializer.clone() {
+                if calls_function(&init, ns) {
+                    let res =
+                        fold_constant_initializer(init, Some(contract_no), ns, &mut diagnostics);
+                    ns.contracts[contract_no].variables[var_no].initializer = Some(res);
+                }
+            }
+        }
+    }
+
+    ns.diagnostics.extend(diagnostics);
+}
+
+fn fold_constant_initializer(
+    init: Expression,
+    contract_no: Option<usize>,
+    ns: &mut Namespace,
+    diagnostics: &mut Diagnostics,
+) -> Expression {
+    let res = fold_const_expression(init, contract_no, ns, diagnostics);
+
+    if calls_function(&res, ns) {
+        // if the evaluation failed, this has already been reported
+        if !diagnostics.any_errors() {
+            if let Err(err) = eval_const_expression(&res, contract_no, ns) {
+                diagnostics.push(err.diagnostic(res.loc(), ns));
+            }
+        }
+    }
+
+    res
+}
+
+impl<'a> Interpreter<'a> {
+    fn step(&mut self, loc: &Loc) -> Result<(), EvalError> {
+        self.steps += 1;
+
+        if self.steps > STEP_LIMIT {
+            fail(
+                loc,
+                format!("evaluation exceeds the limit of {STEP_LIMIT} steps"),
+            )
+        } else {
+            Ok(())
+        }
+    }
+
+    fn frame(&mut self) -> &mut HashMap<usize, Value> {
+        self.frames.last_mut().unwrap()
+    }
+
+    fn call(
+        &mut self,
+        loc: &Loc,
+        function_no: usize,
+        args: Vec<Value>,
+    ) -> Result<Vec<Value>, EvalError> {
+        let func = &self.ns.functions[function_no];
+
+        if !func.is_pure() {
+            return fail(loc, format!("function '{}' is not pure", func.id));
+        }
+
+        if !func.has_body {
+            return fail(loc, format!("function '{}' has no body", func.id));
+        }
+
+        if func.body.is_empty() {
+            return Err(EvalError::Unresolved(function_no));
+        }
+
+        if !func.modifiers.is_empty() {
+            return fail(loc, format!("function '{}' has modifiers", func.id));
+        }
+
+        if self.depth >= CALL_DEPTH_LIMIT {
+            return fail(
+                loc,
+                format!("evaluation exceeds the call depth limit of {CALL_DEPTH_LIMIT}"),
+            );
+        }
+
+        let mut frame = HashMap::new();
+
+        for (var_no, arg) in func.symtable.arguments.iter().zip(args) {
+            if let Some(var_no) = var_no {
+                frame.insert(*var_no, arg);
+            }
+        }
+
+        for var_no in &func.symtable.returns {
+            let ty = &func.symtable.vars[var_no].ty;
+
+            frame.insert(*var_no, default_value(loc, ty, self.ns, &mut self.steps)?);
+        }
+
+        self.depth += 1;
+        self.frames.push(frame);
+
+        let res = self.statements(&func.body);
+
+        let frame = self.frames.pop().unwrap();
+        self.depth -= 1;
+
+        match res? {
+            Flow::Return(Some(values)) => Ok(values),
+            _ => Ok(func
+                .symtable
+                .returns
+                .iter()
+                .map(|var_no| frame[var_no].clone())
+                .collect()),
+        }
+    }
+
+    fn statements(&mut self, stmts: &[Statement]) -> Result<Flow, EvalError> {
+        for stmt in stmts {
+            match self.statement(stmt)? {
+                Flow::Next => (),
+                flow => return Ok(flow),
+            }
+        }
+
+        Ok(Flow::Next)
+    }
+
+    fn statement(&mut self, stmt: &Statement) -> Result<Flow, EvalError> {
+        self.step(&stmt.loc())?;
+
+        match stmt {
+            Statement::Block { statements, .. } => self.statements(statements),
+            Statement::VariableDecl(loc, var_no, param, init) => {
+                let value = if let Some(init) = init {
+                    self.expression(init)?
+                } else {
+                    default_value(loc, &param.ty, self.ns, &mut self.steps)?
+                };
+
+                self.frame().insert(*var_no, value);
+
+                Ok(Flow::Next)
+            }
+            Statement::If(_, _, cond, then_stmts, else_stmts) => {
+                if self.bool(cond)? {
+                    self.statements(then_stmts)
+                } else {
+                    self.statements(else_stmts)
+                }
+            }
+            Statement::While(_, _, cond, body) => {
+                while self.bool(cond)? {
+                    match self.statements(body)? {
+                        Flow::Break => break,
+                        Flow::Return(values) => return Ok(Flow::Return(values)),
+                        Flow::Next | Flow::Continue => (),
+                    }
+                }
+
+                Ok(Flow::Next)
+            }
+            Statement::DoWhile(_, _, body, cond) => {
+                loop {
+                    match self.statements(body)? {
+                        Flow::Break => break,
+                        Flow::Return(values) => return Ok(Flow::Return(values)),
+                        Flow::Next | Flow::Continue => (),
+                    }
+
+                    if !self.bool(cond)? {
+                        break;
+                    }
+                }
+
+                Ok(Flow::Next)
+            }
+            Statement::For {
+                init,
+                cond,
+                next,
+                body,
+                ..
+            } => {
+                if let flow @ Flow::Return(_) = self.statements(init)? {
+                    return Ok(flow);
+                }
+
+                loop {
+                    if let Some(cond) = cond {
+                        if !self.bool(cond)? {
+                            break;
+                        }
+                    }
+
+                    match self.statements(body)? {
+                        Flow::Break => break,
+                        Flow::Return(values) => return Ok(Flow::Return(values)),
+                        Flow::Next | Flow::Continue => (),
+                    }
+
+                    if let Some(next) = next {
+                        self.expression(next)?;
+                    }
+                }
+
+                Ok(Flow::Next)
+            }
+            Statement::Expression(_, _, expr) => {
+                self.expression(expr)?;
+
+                Ok(Flow::Next)
+            }
+            Statement::Delete(loc, ty, expr) => {
+                let place = self.place(expr)?;
+                let value = default_value(loc, ty, self.ns, &mut self.steps)?;
+
+                self.store(place, value);
+
+                Ok(Flow::Next)
+            }
+            Statement::Destructure(_, fields, expr) => {
+                let values = match self.expression(expr)? {
+                    Value::List(values) => values,
+                    value => vec![value],
+                };
+
+                for (field, value) in fields.iter().zip(values) {
+                    match field {
+                        DestructureField::None => (),
+                        DestructureField::VariableDecl(var_no, _) => {
+                            self.frame().insert(*var_no, value);
+                        }
+                        DestructureField::Expression(left) => {
+                            let place = self.place(left)?;
+
+                            self.store(place, value);
+                        }
+                    }
+                }
+
+                Ok(Flow::Next)
+            }
+            Statement::Continue(_) => Ok(Flow::Continue),
+            Statement::Break(_) => Ok(Flow::Break),
+            Statement::Return(_, None) => Ok(Flow::Return(None)),
+            Statement::Return(_, Some(expr)) => match self.expression(expr)? {
+                Value::List(values) => Ok(Flow::Return(Some(values))),
+                value => Ok(Flow::Return(Some(vec![value]))),
+            },
+            Statement::Revert {
+                loc,
+                error_no: None,
+                args,
+            } if args.len() == 1 => {
+                let reason = self.bytes(&args[0])?;
+
+                fail(loc, format!("revert: {}", String::from_utf8_lossy(&reason)))
+            }
+            Statement::Revert {
+                loc,
+                error_no: Some(error_no),
+                ..
+            } => fail(
+                loc,
+                format!("revert with error '{}'", self.ns.errors[*error_no].name),
+            ),
+            Statement::Revert { loc, .. } => fail(loc, "revert"),
+            Statement::Emit { loc, .. } => fail(loc, "cannot emit event at compile time"),
+            Statement::TryCatch(loc, ..) => fail(loc, "try catch is not supported at compile time"),
+            Statement::Underscore(loc) => fail(loc, "modifiers are not supported at compile time"),
+            Statement::Assembly(asm, _) => {
+                fail(&asm.loc, "inline assembly is not supported at compile time")
+            }
+        }
+    }
+
+    fn bool(&mut self, expr: &Expression) -> Result<bool, EvalError> {
+        match self.expression(expr)? {
+            Value::Bool(value) => Ok(value),
+            _ => fail(&expr.loc(), "expected boolean value"),
+        }
+    }
+
+    fn number(&mut self, expr: &Expression) -> Result<BigInt, EvalError> {
+        match self.expression(expr)? {
+            Value::Number(value) => Ok(value),
+            _ => fail(&expr.loc(), "expected number"),
+        }
+    }
+
+    fn bytes(&mut self, expr: &Expression) -> Result<Vec<u8>, EvalError> {
+        match self.expression(expr)? {
+            Value::Bytes(value) => Ok(value.borrow().clone()),
+            _ => fail(&expr.loc(), "expected bytes"),
+        }
+    }
+
+    fn string_location(
+        &mut self,
+        location: &StringLocation<Expression>,
+    ) -> Result<Vec<u8>, EvalError> {
+        match location {
+            StringLocation::CompileTime(value) => Ok(value.clone()),
+            StringLocation::RunTime(expr) => self.bytes(expr),
+        }
+    }
+
+    /// Evaluate arithmetic, and check for overflow unless the operation is unchecked
+    fn arithmetic(
+        &self,
+        loc: &Loc,
+        ty: &Type,
+        unchecked: bool,
+        value: BigInt,
+    ) -> Result<Value, EvalError> {
+        if unchecked {
+            Ok(Value::Number(wrap(value, ty, self.ns)))
+        } else if fits(&value, ty, self.ns) {
+            Ok(Value::Number(value))
+        } else {
+            fail(
+                loc,
+                format!(
+                    "arithmetic overflow, value {} does not fit into type {}",
+                    value,
+                    ty.to_string(self.ns)
+                ),
+            )
+        }
+    }
+
+    fn expression(&mut self, expr: &Expression) -> Result<Value, EvalError> {
+        self.step(&expr.loc())?;
+
+        match expr {
+            Expression::BoolLiteral { value, .. } => Ok(Value::Bool(*value)),
+            Expression::NumberLiteral { value, .. } => Ok(Value::Number(value.clone())),
+            Expression::BytesLiteral {
+                ty: Type::Bytes(_),
+                value,
+                ..
+            } => Ok(Value::Number(BigInt::from_bytes_be(Sign::Plus, value))),
+            Expression::BytesLiteral { value, .. } => {
+                Ok(Value::Bytes(Rc::new(RefCell::new(value.clone()))))
+            }
+            Expression::StructLiteral { values, .. } => {
+                let fields = values
+                    .iter()
+                    .map(|(_, expr)| self.expression(expr))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(Value::Struct(Rc::new(RefCell::new(fields))))
+            }
+            Expression::ArrayLiteral {
+                dimensions, values, ..
+            }
+            | Expression::ConstArrayLiteral {
+                dimensions, values, ..
+            } => {
+                let mut values = values
+                    .iter()
+                    .map(|expr| self.expression(expr))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                for dim in dimensions {
+                    values = values
+                        .chunks(*dim as usize)
+                        .map(|chunk| Value::Array(Rc::new(RefCell::new(chunk.to_vec()))))
+                        .collect();
+                }
+
+                Ok(values.pop().unwrap())
+            }
+            Expression::Add {
+                loc,
+                ty,
+                unchecked,
+                left,
+                right,
+            } => {
+                let value = self.number(left)? + self.number(right)?;
+                self.arithmetic(loc, ty, *unchecked, value)
+            }
+            Expression::Subtract {
+                loc,
+                ty,
+                unchecked,
+                left,
+                right,
+            } => {
+                let value = self.number(left)? - self.number(right)?;
+                self.arithmetic(loc, ty, *unchecked, value)
+            }
+            Expression::Multiply {
+                loc,
+                ty,
+                unchecked,
+                left,
+                right,
+            } => {
+                let value = self.number(left)? * self.number(right)?;
+                self.arithmetic(loc, ty, *unchecked, value)
+            }
+            Expression::Divide {
+                loc,
+                ty,
+                left,
+                right,
+            } => {
+                let left = self.number(left)?;
+                let right = self.number(right)?;
+
+                if right.is_zero() {
+                    return fail(loc, "division by zero");
+                }
+
+                self.arithmetic(loc, ty, false, left / right)
+            }
+            Expression::Modulo {
+                loc,
+                ty,
+                left,
+                right,
+            } => {
+                let left = self.number(left)?;
+                let right = self.number(right)?;
+
+                if right.is_zero() {
+                    return fail(loc, "division by zero");
+                }
+
+                self.arithmetic(loc, ty, false, left % right)
+            }
+            Expression::Power {
+                loc,
+                ty,
+                unchecked,
+                base,
+                exp,
+            } => {
+                let base = self.number(base)?;
+                let exp = self.number(exp)?;
+
+                if exp.sign() == Sign::Minus {
+                    return fail(loc, "power cannot take negative number as exponent");
+                }
+
+                let bits = ty.bits(self.ns) as usize;
+
+                if base.is_zero() || base.is_one() || exp.is_zero() {
+                    Ok(Value::Number(base.pow(exp.to_u32().unwrap_or(1).min(1))))
+                } else if let Some(exp) = exp.to_u32().filter(|exp| *exp as usize <= bits) {
+                    self.arithmetic(loc, ty, *unchecked, base.pow(exp))
+                } else if *unchecked {
+                    let modulus = BigInt::one() << bits;
+                    let base = ((base % &modulus) + &modulus) % &modulus;
+
+                    Ok(Value::Number(wrap(
+                        base.modpow(&exp, &modulus),
+                        ty,
+                        self.ns,
+                    )))
+                } else if base == BigInt::from(-1) {
+                    Ok(Value::Number(if (exp % 2u8).is_zero() {
+                        BigInt::one()
+                    } else {
+                        base
+                    }))
+                } else {
+                    fail(
+                        loc,
+                        format!("arithmetic overflow, power of {base} too large"),
+                    )
+                }
+            }
+            Expression::BitwiseOr {
+                ty, left, right, ..
+            } => {
+                let value = self.number(left)? | self.number(right)?;
+                Ok(Value::Number(wrap(value, ty, self.ns)))
+            }
+            Expression::BitwiseAnd {
+                ty, left, right, ..
+            } => {
+                let value = self.number(left)? & self.number(right)?;
+                Ok(Value::Number(wrap(value, ty, self.ns)))
+            }
+            Expression::BitwiseXor {
+                ty, left, right, ..
+            } => {
+                let value = self.number(left)? ^ self.number(right)?;
+                Ok(Value::Number(wrap(value, ty, self.ns)))
+            }
+            Expression::ShiftLeft {
+                ty, left, right, ..
+            } => {
+                let left = self.number(left)?;
+                let shift = self.shift(right, ty)?;
+
+                Ok(Value::Number(wrap(left << shift, ty, self.ns)))
+            }
+            Expression::ShiftRight {
+                ty, left, right, ..
+            } => {
+                let left = self.number(left)?;
+                let shift = self.shift(right, ty)?;
+
+                Ok(Value::Number(wrap(left >> shift, ty, self.ns)))
+            }
+            Expression::Variable { loc, var_no, .. } => match self.frame().get(var_no) {
+                Some(value) => Ok(value.clone()),
+                None => fail(loc, "variable not available at compile time"),
+            },
+            Expression::ConstantVariable {
+                loc,
+                contract_no,
+                var_no,
+                ..
+            } => {
+                let var = if let Some(contract_no) = contract_no {
+                    &self.ns.contracts[*contract_no].variables[*var_no]
+                } else {
+                    &self.ns.constants[*var_no]
+                };
+
+                match &var.initializer {
+                    Some(init) => {
+                        self.frames.push(HashMap::new());
+                        let res = self.expression(init);
+                        self.frames.pop();
+                        res
+                    }
+                    None => fail(loc, format!("constant '{}' has no value", var.name)),
+                }
+            }
+            Expression::Load { expr, .. } => self.expression(expr),
+            Expression::ZeroExt { to, expr, .. }
+            | Expression::SignExt { to, expr, .. }
+            | Expression::Trunc { to, expr, .. }
+            | Expression::Cast { to, expr, .. } => {
+                let from = expr.ty();
+                let value = self.expression(expr)?;
+
+                convert(&expr.loc(), value, &from, to, self.ns)
+            }
+            Expression::CheckingTrunc { loc, to, expr } => {
+                let value = self.number(expr)?;
+
+                if fits(&value, to, self.ns) {
+                    Ok(Value::Number(value))
+                } else {
+                    fail(
+                        loc,
+                        format!(
+                            "value {} does not fit into type {}",
+                            value,
+                            to.to_string(self.ns)
+                        ),
+                    )
+                }
+            }
+            Expression::BytesCast {
+                loc,
+                from,
+                to,
+                expr,
+            } => {
+                let value = self.expression(expr)?;
+
+                convert(loc, value, from, to, self.ns)
+            }
+            Expression::PreIncrement {
+                loc,
+                ty,
+                unchecked,
+                expr,
+            } => self.increment(loc, ty, *unchecked, expr, true, true),
+            Expression::PreDecrement {
+                loc,
+                ty,
+                unchecked,
+                expr,
+            } => self.increment(loc, ty, *unchecked, expr, false, true),
+            Expression::PostIncrement {
+                loc,
+                ty,
+                unchecked,
+                expr,
+            } => self.increment(loc, ty, *unchecked, expr, true, false),
+            Expression::PostDecrement {
+                loc,
+                ty,
+                unchecked,
+                expr,
+            } => self.increment(loc, ty, *unchecked, expr, false, false),
+            Expression::Assign { left, right, .. } => {
+                let value = self.expression(right)?;
+                let place = self.place(left)?;
+
+                self.store(place, value.clone());
+
+                Ok(value)
+            }
+            Expression::More { left, right, .. } => {
+                Ok(Value::Bool(self.number(left)? > self.number(right)?))
+            }
+            Expression::Less { left, right, .. } => {
+                Ok(Value::Bool(self.number(left)? < self.number(right)?))
+            }
+            Expression::MoreEqual { left, right, .. } => {
+                Ok(Value::Bool(self.number(left)? >= self.number(right)?))
+            }
+            Expression::LessEqual { left, right, .. } => {
+                Ok(Value::Bool(self.number(left)? <= self.number(right)?))
+            }
+            Expression::Equal { loc, left, right } => {
+                let left = self.expression(left)?;
+                let right = self.expression(right)?;
+
+                Ok(Value::Bool(equal(loc, &left, &right)?))
+            }
+            Expression::NotEqual { loc, left, right } => {
+                let left = self.expression(left)?;
+                let right = self.expression(right)?;
+
+                Ok(Value::Bool(!equal(loc, &left, &right)?))
+            }
+            Expression::Not { expr, .. } => Ok(Value::Bool(!self.bool(expr)?)),
+            Expression::BitwiseNot { ty, expr, .. } => {
+                let value = !self.number(expr)?;
+
+                Ok(Value::Number(wrap(value, ty, self.ns)))
+            }
+            Expression::Negate {
+                loc,
+                ty,
+                unchecked,
+                expr,
+            } => {
+                let value = -self.number(expr)?;
+
+                self.arithmetic(loc, ty, *unchecked, value)
+            }
+            Expression::ConditionalOperator {
+                cond,
+                true_option,
+                false_option,
+                ..
+            } => {
+                if self.bool(cond)? {
+                    self.expression(true_option)
+                } else {
+                    self.expression(false_option)
+                }
+            }
+            Expression::Subscript {
+                loc,
+                array_ty: Type::Bytes(len),
+                array,
+                index,
+                ..
+            } => {
+                let value = self.number(array)?;
+                let index = self.index(loc, index, *len as usize)?;
+                let shift = (*len as usize - 1 - index) * 8;
+
+                Ok(Value::Number((value >> shift) & BigInt::from(0xffu8)))
+            }
+            Expression::Subscript { .. } | Expression::StructMember { .. } => {
+                let place = self.place(expr)?;
+
+                self.load(&expr.loc(), &place)
+            }
+            Expression::AllocDynamicBytes {
+                loc,
+                ty,
+                length,
+                init,
+            } => {
+                let length = self.number(length)?;
+                let length = match length.to_usize() {
+                    Some(length) if length <= STEP_LIMIT => length,
+                    _ => return fail(loc, format!("array length {length} is too large")),
+                };
+
+                self.steps += length;
+
+                match ty {
+                    Type::String | Type::DynamicBytes => {
+                        let mut bs = init.clone().unwrap_or_default();
+                        bs.resize(length, 0);
+
+                        Ok(Value::Bytes(Rc::new(RefCell::new(bs))))
+                    }
+                    _ => {
+                        let elem = default_value(loc, &ty.array_elem(), self.ns, &mut self.steps)?;
+                        let mut elems = Vec::with_capacity(length);
+
+                        for _ in 0..length {
+                            elems.push(deep_copy(&elem));
+                        }
+
+                        Ok(Value::Array(Rc::new(RefCell::new(elems))))
+                    }
+                }
+            }
+            Expression::StringCompare { left, right, .. } => {
+                let left = self.string_location(left)?;
+                let right = self.string_location(right)?;
+
+                Ok(Value::Bool(left == right))
+            }
+            Expression::Or { left, right, .. } => {
+                Ok(Value::Bool(self.bool(left)? || self.bool(right)?))
+            }
+            Expression::And { left, right, .. } => {
+                Ok(Value::Bool(self.bool(left)? && self.bool(right)?))
+            }
+            Expression::InternalFunction {
+                function_no,
+                signature,
+                ..
+            } => Ok(Value::Function(Some(
+                self.virtual_function(*function_no, signature),
+            ))),
+            Expression::InternalFunctionCall {
+                loc,
+                returns,
+                function,
+                args,
+            } => {
+                let function_no = if let Expression::InternalFunction {
+                    function_no,
+                    signature,
+                    ..
+                } = function.as_ref()
+                {
+                    self.virtual_function(*function_no, signature)
+                } else {
+                    match self.expression(function)? {
+                        Value::Function(Some(function_no)) => function_no,
+                        _ => return fail(loc, "function pointer is not initialized"),
+                    }
+                };
+
+                let args = args
+                    .iter()
+                    .map(|arg| self.expression(arg))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let mut values = self.call(loc, function_no, args)?;
+
+                if returns.len() == 1 {
+                    Ok(values.swap_remove(0))
+                } else {
+                    Ok(Value::List(values))
+                }
+            }
+            Expression::UserDefinedOperator {
+                loc,
+                function_no,
+                args,
+                ..
+            } => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.expression(arg))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let mut values = self.call(loc, *function_no, args)?;
+
+                Ok(values.swap_remove(0))
+            }
+            Expression::List { list, .. } => Ok(Value::List(
+                list.iter()
+                    .map(|expr| self.expression(expr))
+                    .collect::<Result<Vec<_>, _>>()?,
+            )),
+            Expression::Builtin {
+                loc, kind, args, ..
+            } => self.builtin(loc, kind, args, expr),
+            _ => fail(
+                &expr.loc(),
+                "expression is not supported in compile-time evaluation",
+            ),
+        }
+    }
+
+    fn builtin(
+        &mut self,
+        loc: &Loc,
+        kind: &Builtin,
+        args: &[Expression],
+        expr: &Expression,
+    ) -> Result<Value, EvalError> {
+        match kind {
+            Builtin::ArrayLength => match self.expression(&args[0])? {
+                Value::Array(elems) => Ok(Value::Number(elems.borrow().len().into())),
+                Value::Bytes(bs) => Ok(Value::Number(bs.borrow().len().into())),
+                _ => fail(loc, "expected array"),
+            },
+            Builtin::Keccak256 => {
+                let bs = self.bytes(&args[0])?;
+                let mut hasher = Keccak::v256();
+                let mut hash = [0u8; 32];
+                hasher.update(&bs);
+                hasher.finalize(&mut hash);
+
+                Ok(Value::Number(BigInt::from_bytes_be(Sign::Plus, &hash)))
+            }
+            Builtin::Sha256 => {
+                let bs = self.bytes(&args[0])?;
+                let hash = Sha256::digest(bs);
+
+                Ok(Value::Number(BigInt::from_bytes_be(Sign::Plus, &hash)))
+            }
+            Builtin::Ripemd160 => {
+                let bs = self.bytes(&args[0])?;
+                let hash = Ripemd160::digest(bs);
+
+                Ok(Value::Number(BigInt::from_bytes_be(Sign::Plus, &hash)))
+            }
+            Builtin::Require => {
+                if self.bool(&args[0])? {
+                    Ok(Value::List(Vec::new()))
+                } else if let Some(reason) = args.get(1) {
+                    let reason = self.bytes(reason)?;
+
+                    fail(
+                        loc,
+                        format!(
+                            "require condition failed: {}",
+                            String::from_utf8_lossy(&reason)
+                        ),
+                    )
+                } else {
+                    fail(loc, "require condition failed")
+                }
+            }
+            Builtin::Assert => {
+                if self.bool(&args[0])? {
+                    Ok(Value::List(Vec::new()))
+                } else {
+                    fail(loc, "assert condition failed")
+                }
+            }
+            Builtin::AddMod | Builtin::MulMod => {
+                let x = self.number(&args[0])?;
+                let y = self.number(&args[1])?;
+                let k = self.number(&args[2])?;
+
+                if k.is_zero() {
+                    return fail(loc, "division by zero");
+                }
+
+                if *kind == Builtin::AddMod {
+                    Ok(Value::Number((x + y) % k))
+                } else {
+                    Ok(Value::Number((x * y) % k))
+                }
+            }
+            Builtin::StringConcat | Builtin::BytesConcat => {
+                let mut res = Vec::new();
+
+                for arg in args {
+                    res.extend(self.bytes(arg)?);
+                }
+
+                Ok(Value::Bytes(Rc::new(RefCell::new(res))))
+            }
+            Builtin::UserTypeWrap | Builtin::UserTypeUnwrap => self.expression(&args[0]),
+            Builtin::TypeMin | Builtin::TypeMax => {
+                match eval_const_number(expr, self.ns, &mut Diagnostics::default()) {
+                    Ok((_, value)) => Ok(Value::Number(value)),
+                    Err(_) => fail(
+                        loc,
+                        "expression is not supported in compile-time evaluation",
+                    ),
+                }
+            }
+            _ => fail(loc, "builtin is not supported in compile-time evaluation"),
+        }
+    }
+
+    fn increment(
+        &mut self,
+        loc: &Loc,
+        ty: &Type,
+        unchecked: bool,
+        expr: &Expression,
+        increment: bool,
+        pre: bool,
+    ) -> Result<Value, EvalError> {
+        let place = self.place(expr)?;
+
+        let Value::Number(old) = self.load(loc, &place)? else {
+            return fail(loc, "expected number");
+        };
+
+        let new = if increment {
+            &old + BigInt::one()
+        } else {
+            &old - BigInt::one()
+        };
+
+        let new = self.arithmetic(loc, ty, unchecked, new)?;
+
+        self.store(place, new.clone());
+
+        if pre {
+            Ok(new)
+        } else {
+            Ok(Value::Number(old))
+        }
+    }
+
+    /// Evaluate the shift amount; shifting by more than the number of bits has the same effect
+    /// as shifting by the number of bits
+    fn shift(&mut self, expr: &Expression, ty: &Type) -> Result<usize, EvalError> {
+        let shift = self.number(expr)?;
+
+        if shift.sign() == Sign::Minus {
+            return fail(&expr.loc(), format!("cannot shift by {shift}"));
+        }
+
+        let bits = ty.bits(self.ns) as usize;
+
+        Ok(shift.to_usize().unwrap_or(bits).min(bits))
+    }
+
+    fn index(&mut self, loc: &Loc, index: &Expression, length: usize) -> Result<usize, EvalError> {
+        let index = self.number(index)?;
+
+        match index.to_usize() {
+            Some(index) if index < length => Ok(index),
+            _ => fail(
+                loc,
+                format!("array index {index} out of bounds for length {length}"),
+            ),
+        }
+    }
+
+    fn virtual_function(&self, function_no: usize, signature: &Option<String>) -> usize {
+        if let (Some(signature), Some(contract_no)) = (signature, self.contract_no) {
+            if let Some(function_no) = self.ns.contracts[contract_no]
+                .virtual_functions
+                .get(signature)
+                .and_then(|functions| functions.last())
+            {
+                return *function_no;
+            }
+        }
+
+        function_no
+    }
+
+    fn place(&mut self, expr: &Expression) -> Result<Place, EvalError> {
+        match expr {
+            Expression::Variable { var_no, .. } => Ok(Place::Variable(*var_no)),
+            Expression::Subscript {
+                loc, array, index, ..
+            } => match self.expression(array)? {
+                Value::Array(elems) => {
+                    let length = elems.borrow().len();
+                    let index = self.index(loc, index, length)?;
+
+                    Ok(Place::Element(elems, index))
+                }
+                Value::Bytes(bs) => {
+                    let length = bs.borrow().len();
+                    let index = self.index(loc, index, length)?;
+
+                    Ok(Place::Byte(bs, index))
+                }
+                _ => fail(loc, "expected array"),
+            },
+            Expression::StructMember {
+                loc, expr, field, ..
+            } => match self.expression(expr)? {
+                Value::Struct(fields) => Ok(Place::Element(fields, *field)),
+                _ => fail(loc, "expected struct"),
+            },
+            _ => fail(
+                &expr.loc(),
+                "expression is not supported in compile-time evaluation",
+            ),
+        }
+    }
+
+    fn load(&mut self, loc: &Loc, place: &Place) -> Result<Value, EvalError> {
+        match place {
+            Place::Variable(var_no) => match self.frame().get(var_no) {
+                Some(value) => Ok(value.clone()),
+                None => fail(loc, "variable not available at compile time"),
+            },
+            Place::Element(elems, index) => Ok(elems.borrow()[*index].clone()),
+            Place::Byte(bs, index) => Ok(Value::Number(bs.borrow()[*index].into())),
+        }
+    }
+
+    fn store(&mut self, place: Place, value: Value) {
+        match place {
+            Place::Variable(var_no) => {
+                self.frame().insert(var_no, value);
+            }
+            Place::Element(elems, index) => {
+                elems.borrow_mut()[index] = value;
+            }
+            Place::Byte(bs, index) => {
+                if let Value::Number(value) = value {
+                    bs.borrow_mut()[index] = value.to_u8().unwrap_or_default();
+                }
+            }
+        }
+    }
+}
+
+/// Compare two values of value type
+fn equal(loc: &Loc, left: &Value, right: &Value) -> Result<bool, EvalError> {
+    match (left, right) {
+        (Value::Number(left), Value::Number(right)) => Ok(left == right),
+        (Value::Bool(left), Value::Bool(right)) => Ok(left == right),
+        (Value::Function(left), Value::Function(right)) => Ok(left == right),
+        _ => fail(
+            loc,
+            "comparison is not supported in compile-time evaluation",
+        ),
+    }
+}
+
+/// Copy a value, so that elements of a newly allocated array do not alias each other
+fn deep_copy(value: &Value) -> Value {
+    match value {
+        Value::Bytes(bs) => Value::Bytes(Rc::new(RefCell::new(bs.borrow().clone()))),
+        Value::Array(elems) => Value::Array(Rc::new(RefCell::new(
+            elems.borrow().iter().map(deep_copy).collect(),
+        ))),
+        Value::Struct(fields) => Value::Struct(Rc::new(RefCell::new(
+            fields.borrow().iter().map(deep_copy).collect(),
+        ))),
+        _ => value.clone(),
+    }
+}
+
+/// The default value of a variable of the given type
+fn default_value(
+    loc: &Loc,
+    ty: &Type,
+    ns: &Namespace,
+    steps: &mut usize,
+) -> Result<Value, EvalError> {
+    *steps += 1;
+
+    if *steps > STEP_LIMIT {
+        return fail(
+            loc,
+            format!("evaluation exceeds the limit of {STEP_LIMIT} steps"),
+        );
+    }
+
+    match ty {
+        Type::Bool => Ok(Value::Bool(false)),
+        Type::Uint(_)
+        | Type::Int(_)
+        | Type::Bytes(_)
+        | Type::Address(_)
+        | Type::Contract(_)
+        | Type::Enum(_)
+        | Type::Value => Ok(Value::Number(BigInt::zero())),
+        Type::UserType(no) => default_value(loc, &ns.user_types[*no].ty, ns, steps),
+        Type::String | Type::DynamicBytes => Ok(Value::Bytes(Rc::new(RefCell::new(Vec::new())))),
+        Type::Array(_, dims) => match dims.last() {
+            Some(ArrayLength::Fixed(length)) => {
+                let length = match length.to_usize() {
+                    Some(length) if *steps + length <= STEP_LIMIT => length,
+                    _ => return fail(loc, format!("array length {length} is too large")),
+                };
+
+                let elems = (0..length)
+                    .map(|_| default_value(loc, &ty.array_elem(), ns, steps))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(Value::Array(Rc::new(RefCell::new(elems))))
+            }
+            Some(ArrayLength::Dynamic) => Ok(Value::Array(Rc::new(RefCell::new(Vec::new())))),
+            _ => fail(loc, "type is not supported in compile-time evaluation"),
+        },
+        Type::Struct(str_ty) => {
+            let fields = str_ty
+                .definition(ns)
+                .fields
+                .iter()
+                .map(|field| default_value(loc, &field.ty, ns, steps))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(Value::Struct(Rc::new(RefCell::new(fields))))
+        }
+        Type::InternalFunction { .. } => Ok(Value::Function(None)),
+        Type::Ref(ty) => default_value(loc, ty, ns, steps),
+        _ => fail(
+            loc,
+            format!(
+                "type {} is not supported in compile-time evaluation",
+                ty.to_string(ns)
+            ),
+        ),
+    }
+}
+
+/// Returns the number of bits and signedness of an integer-like type
+fn int_type(ty: &Type, ns: &Namespace) -> Option<(usize, bool)> {
+    match ty {
+        Type::Int(bits) => Some((*bits as usize, true)),
+        Type::Uint(_)
+        | Type::Bytes(_)
+        | Type::Address(_)
+        | Type::Contract(_)
+        | Type::Enum(_)
+        | Type::Value => Some((ty.bits(ns) as usize, false)),
+        Type::UserType(no) => int_type(&ns.user_types[*no].ty, ns),
+        Type::Ref(ty) => int_type(ty, ns),
+        _ => None,
+    }
+}
+
+/// Does the value fit into the type
+fn fits(value: &BigInt, ty: &Type, ns: &Namespace) -> bool {
+    match int_type(ty, ns) {
+        Some((bits, true)) => {
+            let max = BigInt::one() << (bits - 1);
+
+            *value >= -&max && *value < max
+        }
+        Some((bits, false)) => value.sign() != Sign::Minus && *value < (BigInt::one() << bits),
+        None => true,
+    }
+}
+
+/// Wrap the value so that it fits into the type, like unchecked arithmetic does at runtime
+fn wrap(value: BigInt, ty: &Type, ns: &Namespace) -> BigInt {
+    match int_type(ty, ns) {
+        Some((bits, signed)) => {
+            let modulus = BigInt::one() << bits;
+            let value = ((value % &modulus) + &modulus) % &modulus;
+
+            if signed && value >= (BigInt::one() << (bits - 1)) {
+                value - modulus
+            } else {
+                value
+            }
+        }
+        None => value,
+    }
+}
+
+/// Convert a value to another type. Conversions between different sizes of bytesN are done
+/// with explicit shifts, so only the value needs truncating here.
+fn convert(
+    loc: &Loc,
+    value: Value,
+    from: &Type,
+    to: &Type,
+    ns: &Namespace,
+) -> Result<Value, EvalError> {
+    match (value, to.deref_any()) {
+        (Value::Number(value), Type::DynamicBytes) => {
+            let Type::Bytes(length) = from.deref_any() else {
+                return fail(
+                    loc,
+                    "conversion is not supported in compile-time evaluation",
+                );
+            };
+
+            let (_, mut bs) = value.to_bytes_be();
+
+            while bs.len() < *length as usize {
+                bs.insert(0, 0);
+            }
+
+            Ok(Value::Bytes(Rc::new(RefCell::new(bs))))
+        }
+        (Value::Bytes(bs), Type::Bytes(length)) => {
+            let mut bs = bs.borrow().clone();
+
+            bs.resize(*length as usize, 0);
+
+            Ok(Value::Number(BigInt::from_bytes_be(Sign::Plus, &bs)))
+        }
+        (Value::Number(value), to) => Ok(Value::Number(wrap(value, to, ns))),
+        (value, _) => Ok(value),
+    }
+}
+
+/// Convert the result of the evaluation to a literal expression
+fn to_expression(
+    loc: &Loc,
+    value: Value,
+    ty: &Type,
+    ns: &Namespace,
+) -> Result<Expression, EvalError> {
+    match (value, ty) {
+        (Value::Bool(value), _) => Ok(Expression::BoolLiteral { loc: *loc, value }),
+        (Value::Number(value), Type::UserType(no)) => Ok(Expression::Builtin {
+            loc: *loc,
+            tys: vec![ty.clone()],
+            kind: Builtin::UserTypeWrap,
+            args: vec![Expression::NumberLiteral {
+                loc: *loc,
+                ty: ns.user_types[*no].ty.clone(),
+                value,
+            }],
+        }),
+        (Value::Number(value), _) => Ok(Expression::NumberLiteral {
+            loc: *loc,
+            ty: ty.clone(),
+            value,
+        }),
+        (Value::Bytes(bs), _) => Ok(Expression::BytesLiteral {
+            loc: *loc,
+            ty: ty.clone(),
+            value: bs.borrow().clone(),
+        }),
+        (Value::Struct(fields), Type::Struct(str_ty)) => {
+            let def = str_ty.definition(ns);
+
+            let values = fields
+                .borrow()
+                .iter()
+                .zip(&def.fields)
+                .map(|(value, field)| Ok((None, to_expression(loc, value.clone(), &field.ty, ns)?)))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(Expression::StructLiteral {
+                loc: *loc,
+                id: pt::IdentifierPath {
+                    loc: *loc,
+                    identifiers: vec![pt::Identifier {
+                        loc: *loc,
+                        name: def.id.name.clone(),
+                    }],
+                },
+                ty: ty.clone(),
+                values,
+            })
+        }
+        (value @ Value::Array(_), Type::Array(elem_ty, dims))
+            if dims.iter().all(|dim| matches!(dim, ArrayLength::Fixed(_))) =>
+        {
+            let mut values = Vec::new();
+
+            flatten(value, &mut values);
+
+            let values = values
+                .into_iter()
+                .map(|value| to_expression(loc, value, elem_ty, ns))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let dimensions = dims
+                .iter()
+                .map(|dim| match dim {
+                    ArrayLength::Fixed(length) => length.to_u32().unwrap(),
+                    _ => unreachable!(),
+                })
+                .collect();
+
+            if elem_ty.is_reference_type(ns) {
+                Ok(Expression::ArrayLiteral {
+                    loc: *loc,
+                    ty: ty.clone(),
+                    dimensions,
+                    values,
+                })
+            } else {
+                Ok(Expression::ConstArrayLiteral {
+                    loc: *loc,
+                    ty: ty.clone(),
+                    dimensions,
+                    values,
+                })
+            }
+        }
+        _ => fail(
+            loc,
+            format!(
+                "value of type {} cannot be a compile-time constant",
+                ty.to_string(ns)
+            ),
+        ),
+    }
+}
+
+/// Flatten nested arrays, innermost elements first
+fn flatten(value: Value, values: &mut Vec<Value>) {
+    if let Value::Array(elems) = value {
+        for elem in elems.borrow().iter() {
+            flatten(elem.clone(), values);
+        }
+    } else {
+        values.push(value);
+    }
+}
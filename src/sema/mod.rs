@@ -31,6 +31,7 @@ pub mod file;
 mod format;
 mod function_annotation;
 mod functions;
+mod interpreter;
 mod mutability;
 mod namespace;
 mod pragma;
@@ -38,7 +39,7 @@ pub(crate) mod solana_accounts;
 mod statements;
 pub mod symtable;
 pub mod tags;
-mod tests;
+pub(crate) mod tests;
 mod types;
 mod unused_variable;
 mod using;
@@ -76,6 +77,7 @@ pub struct ContractDefinition<'a> {
     pub annotations: Vec<&'a pt::Annotation>,
     pub name: Option<&'a pt::Identifier>,
     pub base: Vec<pt::Base>,
+    pub layout: Option<&'a pt::StorageLayoutSpecifier>,
     pub parts: Vec<ContractPart<'a>>,
 }
 
@@ -179,14 +181,33 @@ fn sema_file(file: &ResolvedFile, resolver: &mut FileResolver, ns: &mut ast::Nam
         }
     }
 
+    // constant initializers and array lengths may call free functions, so their bodies can
+    // be resolved on demand from now on
+    for (func_no, func) in &resolve_bodies {
+        ns.unresolved_function_bodies.insert(
+            *func_no,
+            ast::UnresolvedFunctionBody {
+                file_no,
+                contract_no: None,
+                def: pt::FunctionDefinition::clone(func),
+                annotations: Vec::new(),
+            },
+        );
+    }
+
     // now resolve the contracts
     contracts::resolve(&tree.contracts, file_no, ns);
 
     // now we can resolve the body of functions outside of contracts
     for (func_no, func) in resolve_bodies {
-        let _ = statements::resolve_function_body(func, &[], file_no, None, func_no, ns);
+        if ns.unresolved_function_bodies.remove(&func_no).is_some() {
+            let _ = statements::resolve_function_body(func, &[], file_no, None, func_no, ns);
+        }
     }
 
+    // evaluate the constants which call functions, now that all function bodies are resolved
+    interpreter::fold_constants(file_no, ns);
+
     if !ns.diagnostics.any_errors() {
         for contract_no in 0..ns.contracts.len() {
             external_functions::add_external_functions(contract_no, ns);
@@ -231,41 +252,44 @@ fn resolve_import(
         | pt::Import::Rename(f, _, _) => f,
     };
 
-    let filename = match path {
-        pt::ImportPath::Filename(f) => f,
-        pt::ImportPath::Path(path) => {
-            ns.diagnostics.push(ast::Diagnostic::error(
-                path.loc,
-                "experimental import paths not supported".into(),
-            ));
-
-            return;
-        }
-    };
+    let (loc, os_filename) = match path {
+        pt::ImportPath::Filename(filename) => {
+            if filename.string.is_empty() {
+                ns.diagnostics.push(ast::Diagnostic::error(
+                    filename.loc,
+                    "import path empty".into(),
+                ));
+                return;
+            }
 
-    if filename.string.is_empty() {
-        ns.diagnostics.push(ast::Diagnostic::error(
-            filename.loc,
-            "import path empty".into(),
-        ));
-        return;
-    }
+            let (valid, bs) = unescape(
+                &filename.string,
+                filename.loc.start(),
+                filename.loc.file_no(),
+                &mut ns.diagnostics,
+            );
 
-    let (valid, bs) = unescape(
-        &filename.string,
-        filename.loc.start(),
-        filename.loc.file_no(),
-        &mut ns.diagnostics,
-    );
+            if !valid {
+                return;
+            }
 
-    if !valid {
-        return;
-    }
+            if let Some(res) = osstring_from_vec(&filename.loc, bs, ns) {
+                (filename.loc, res)
+            } else {
+                return;
+            }
+        }
+        pt::ImportPath::Path(path) => {
+            // experimental import paths like `std.stub` refer to the file `std/stub.sol`
+            let filename = path
+                .identifiers
+                .iter()
+                .map(|id| id.name.as_str())
+                .collect::<Vec<&str>>()
+                .join("/");
 
-    let os_filename = if let Some(res) = osstring_from_vec(&filename.loc, bs, ns) {
-        res
-    } else {
-        return;
+            (path.loc, OsString::from(format!("{filename}.sol")))
+        }
     };
 
     let import_file_no = if let Some(builtin_file_no) = ns
@@ -277,9 +301,15 @@ fn resolve_import(
         builtin_file_no
     } else {
         match resolver.resolve_file(parent, &os_filename) {
-            Err(message) => {
-                ns.diagnostics
-                    .push(ast::Diagnostic::error(filename.loc, message));
+            Err(err) => {
+                ns.diagnostics.push(ast::Diagnostic::error_with_notes(
+                    loc,
+                    err.message,
+                    err.notes
+                        .into_iter()
+                        .map(|message| ast::Note { loc, message })
+                        .collect(),
+                ));
 
                 return;
             }
@@ -347,7 +377,8 @@ fn resolve_import(
                         from.loc,
                         format!(
                             "import '{}' does not export '{}'",
-                            filename.string, from.name
+                            os_filename.to_string_lossy(),
+                            from.name
                         ),
                     ));
                 }
@@ -370,7 +401,7 @@ fn resolve_import(
             for (name, contract_no, symbol) in exports {
                 let new_symbol = pt::Identifier {
                     name: name.clone(),
-                    loc: filename.loc,
+                    loc,
                 };
 
                 // Only add symbol if it does not already exist with same definition
@@ -401,7 +432,7 @@ fn resolve_import(
             for (name, symbol) in exports {
                 let new_symbol = pt::Identifier {
                     name: name.clone(),
-                    loc: filename.loc,
+                    loc,
                 };
 
                 // Only add symbol if it does not already exist with same definition
@@ -499,6 +530,7 @@ fn collect_annotations_doccomments<'a>(
                 doccomments,
                 name: contract.name.as_ref(),
                 base: contract.base.clone(),
+                layout: contract.layout.as_ref(),
                 parts,
             });
 
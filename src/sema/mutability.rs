@@ -502,6 +502,12 @@ fn recurse_yul_statements(stmts: &[YulStatement], state: &mut StateCheck) {
                 } else if builtin_ty.modify_state() {
                     state.write(loc);
                 }
+                if matches!(
+                    builtin_ty,
+                    YulBuiltInFunction::SStore | YulBuiltInFunction::SetImmutable
+                ) {
+                    state.data_account |= DataAccountUsage::WRITE;
+                }
                 for arg in args {
                     arg.recurse(state, check_expression_mutability_yul);
                 }
@@ -562,10 +568,10 @@ fn check_expression_mutability_yul(expr: &YulExpression, state: &mut StateCheck)
             }
 
             match builtin_ty {
-                YulBuiltInFunction::SStore => {
+                YulBuiltInFunction::SStore | YulBuiltInFunction::SetImmutable => {
                     state.data_account |= DataAccountUsage::WRITE;
                 }
-                YulBuiltInFunction::SLoad => {
+                YulBuiltInFunction::SLoad | YulBuiltInFunction::LoadImmutable => {
                     state.data_account |= DataAccountUsage::READ;
                 }
                 _ => (),
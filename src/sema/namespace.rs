@@ -9,11 +9,11 @@ use super::{
     diagnostics::Diagnostics,
     eval::eval_const_number,
     expression::{resolve_expression::expression, ExprContext, ResolveTo},
-    resolve_params, resolve_returns,
+    interpreter, resolve_params, resolve_returns,
     symtable::Symtable,
     ArrayDimension,
 };
-use crate::Target;
+use crate::{abi::polkadot::MetadataVersion, Target};
 use itertools::Itertools;
 use num_bigint::BigInt;
 use num_traits::{Signed, Zero};
@@ -60,12 +60,14 @@ impl Namespace {
             constants: Vec::new(),
             address_length,
             value_length,
+            metadata_version: MetadataVersion::default(),
             variable_symbols: HashMap::new(),
             function_symbols: HashMap::new(),
             diagnostics: Diagnostics::default(),
             next_id: 0,
             var_constants: HashMap::new(),
             hover_overrides: HashMap::new(),
+            unresolved_function_bodies: HashMap::new(),
         };
 
         match target {
@@ -78,6 +80,11 @@ impl Namespace {
         ns
     }
 
+    /// Polkadot with ink! v5 metadata, where events are identified by their signature topic
+    pub fn is_ink_v5(&self) -> bool {
+        self.target.is_polkadot() && self.metadata_version == MetadataVersion::V5
+    }
+
     /// Add symbol to symbol table; either returns true for success, or adds an appropriate error
     pub fn add_symbol(
         &mut self,
@@ -1559,6 +1566,16 @@ impl Namespace {
             }
         }
 
+        // the array length may call pure functions
+        let mut fold_diagnostics = Diagnostics::default();
+        let size_expr =
+            interpreter::fold_const_expression(size_expr, contract_no, self, &mut fold_diagnostics);
+
+        if fold_diagnostics.any_errors() {
+            diagnostics.extend(fold_diagnostics);
+            return Err(());
+        }
+
         let n = eval_const_number(&size_expr, self, diagnostics)?;
 
         Ok(Some(n))
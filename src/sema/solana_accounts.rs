@@ -29,6 +29,23 @@ impl BuiltinAccounts {
             BuiltinAccounts::InstructionAccount => "SysvarInstruction",
         }
     }
+
+    /// The address of the account, if it is always the same
+    pub fn address(&self) -> Option<&'static str> {
+        match self {
+            BuiltinAccounts::ClockAccount => Some("SysvarC1ock11111111111111111111111111111111"),
+            BuiltinAccounts::SystemAccount => Some("11111111111111111111111111111111"),
+            BuiltinAccounts::AssociatedTokenProgram => {
+                Some("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL")
+            }
+            BuiltinAccounts::RentAccount => Some("SysvarRent111111111111111111111111111111111"),
+            BuiltinAccounts::TokenProgramId => Some("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
+            BuiltinAccounts::InstructionAccount => {
+                Some("Sysvar1nstructions1111111111111111111111111")
+            }
+            BuiltinAccounts::DataAccount => None,
+        }
+    }
 }
 
 impl ToString for BuiltinAccounts {
@@ -1,10 +1,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use super::ast::{Diagnostic, Namespace, Parameter, Tag, Type};
+use super::ast::{Diagnostic, Function, Namespace, Parameter, Tag, Type};
+use super::diagnostics::Diagnostics;
 use solang_parser::{
     doccomment::{DocComment, DocCommentTag},
     pt,
 };
+use std::borrow::Borrow;
 use std::fmt::Write;
 
 /// Resolve the tags for a type from parsed doccomment
@@ -189,6 +191,14 @@ pub fn resolve_tags(
                             tag_loc,
                             format!("custom tag '@{}' is missing a name", c.tag),
                         ));
+                    } else if custom == "invariant" {
+                        // each invariant is a separate expression, so they are not joined
+                        res.push(Tag {
+                            loc,
+                            tag: c.tag.to_owned(),
+                            no: res.iter().filter(|e| e.tag == c.tag).count(),
+                            value: c.value.to_owned(),
+                        });
                     } else {
                         add_tag(loc, &mut res, c);
                     }
@@ -220,8 +230,115 @@ fn add_tag(loc: pt::Loc, res: &mut Vec<Tag>, doc_comment: &DocCommentTag) {
     }
 }
 
+/// The tags of a function, including the tags inherited with `@inheritdoc` from the function
+/// with the same signature in the named base contract. Tags given on the function itself are
+/// not overridden.
+pub fn function_tags(function_no: usize, ns: &Namespace) -> Vec<&Tag> {
+    let func = &ns.functions[function_no];
+
+    inherit_tags(&func.tags, func.contract_no, ns, |base| {
+        base.signature == func.signature
+    })
+}
+
+/// The tags of a state variable, including the tags inherited with `@inheritdoc` from the
+/// function which its accessor function implements
+pub fn variable_tags(contract_no: usize, var_no: usize, ns: &Namespace) -> Vec<&Tag> {
+    let var = &ns.contracts[contract_no].variables[var_no];
+
+    inherit_tags(&var.tags, Some(contract_no), ns, |base| {
+        base.id.name == var.name
+    })
+}
+
+fn inherit_tags<'a>(
+    tags: &'a [Tag],
+    contract_no: Option<usize>,
+    ns: &'a Namespace,
+    overrides: impl Fn(&Function) -> bool,
+) -> Vec<&'a Tag> {
+    let mut res: Vec<&Tag> = tags.iter().collect();
+
+    let (Some(contract_no), Some(inheritdoc)) =
+        (contract_no, tags.iter().find(|e| e.tag == "inheritdoc"))
+    else {
+        return res;
+    };
+
+    if let Some(function_no) = inherited_function(contract_no, &inheritdoc.value, ns, overrides) {
+        for tag in function_tags(function_no, ns) {
+            if tag.tag != "inheritdoc" && !res.iter().any(|e| e.tag == tag.tag && e.no == tag.no) {
+                res.push(tag);
+            }
+        }
+    }
+
+    res
+}
+
+/// Find the function in the base contract named by `@inheritdoc`
+fn inherited_function(
+    contract_no: usize,
+    base_name: &str,
+    ns: &Namespace,
+    overrides: impl Fn(&Function) -> bool,
+) -> Option<usize> {
+    ns.contract_bases(contract_no)
+        .into_iter()
+        .filter(|base_no| *base_no != contract_no && ns.contracts[*base_no].id.name == base_name)
+        .flat_map(|base_no| ns.contracts[base_no].functions.iter().copied())
+        .find(|function_no| overrides(&ns.functions[*function_no]))
+}
+
+/// Check that the base contract named by `@inheritdoc` has the function which is overridden by
+/// the function or state variable
+pub(super) fn check_inheritdoc(contract_no: usize, ns: &mut Namespace) {
+    let contract = &ns.contracts[contract_no];
+    let mut diagnostics = Diagnostics::default();
+
+    for function_no in &contract.functions {
+        let func = &ns.functions[*function_no];
+
+        if let Some(tag) = func.tags.iter().find(|e| e.tag == "inheritdoc") {
+            if inherited_function(contract_no, &tag.value, ns, |base| {
+                base.signature == func.signature
+            })
+            .is_none()
+            {
+                diagnostics.push(Diagnostic::error(
+                    tag.loc,
+                    format!(
+                        "base contract '{}' in tag '@inheritdoc' has no function '{}' which is overridden",
+                        tag.value, func.signature
+                    ),
+                ));
+            }
+        }
+    }
+
+    for var in &contract.variables {
+        if let Some(tag) = var.tags.iter().find(|e| e.tag == "inheritdoc") {
+            if inherited_function(contract_no, &tag.value, ns, |base| base.id.name == var.name)
+                .is_none()
+            {
+                diagnostics.push(Diagnostic::error(
+                    tag.loc,
+                    format!(
+                        "base contract '{}' in tag '@inheritdoc' has no function '{}' which is overridden",
+                        tag.value, var.name
+                    ),
+                ));
+            }
+        }
+    }
+
+    ns.diagnostics.extend(diagnostics);
+}
+
 /// Render tags as plain text string
-pub fn render(tags: &[Tag]) -> String {
+pub fn render<T: Borrow<Tag>>(tags: &[T]) -> String {
+    let tags: Vec<&Tag> = tags.iter().map(Borrow::borrow).collect();
+
     let mut s = String::new();
 
     if let Some(tag) = tags.iter().find(|e| e.tag == "title") {
@@ -4,6 +4,7 @@
 
 mod data_account;
 
+use crate::codegen::{codegen, Options};
 use crate::sema::ast::{Expression, Parameter, Statement, TryCatch, Type};
 use crate::sema::yul::ast::InlineAssembly;
 use crate::{parse_and_resolve, sema::ast, FileResolver, Target};
@@ -19,6 +20,27 @@ pub(crate) fn parse(src: &'static str) -> ast::Namespace {
     ns
 }
 
+/// Resolve `src` as the file `test.sol` for the target, and check that there are no errors
+pub(crate) fn resolve(src: &str, target: Target) -> ast::Namespace {
+    let mut cache = FileResolver::default();
+    cache.set_file_contents("test.sol", src.to_string());
+
+    let ns = parse_and_resolve(OsStr::new("test.sol"), &mut cache, target);
+
+    assert!(!ns.diagnostics.any_errors(), "{:?}", ns.diagnostics);
+
+    ns
+}
+
+/// Resolve `src` like `resolve()`, and run codegen on it with the options
+pub(crate) fn resolve_and_codegen(src: &str, target: Target, opt: &Options) -> ast::Namespace {
+    let mut ns = resolve(src, target);
+
+    codegen(&mut ns, opt);
+
+    ns
+}
+
 #[test]
 fn test_statement_reachable() {
     let loc = Loc::File(0, 1, 2);
@@ -655,3 +677,204 @@ fn get_import_path() {
         assert_eq!(Some(&(None, examples.clone())), import_path);
     }
 }
+
+#[test]
+fn context_import_map() {
+    let mut cache = FileResolver::default();
+
+    cache.set_file_contents(
+        "main.sol",
+        r#"
+        import "lib/a/A.sol";
+        import "@oz/X.sol";
+        contract M is V5, A {}"#
+            .to_string(),
+    );
+    cache.set_file_contents(
+        "lib/a/A.sol",
+        r#"
+        import "@oz/X.sol";
+        contract A is V4 {}"#
+            .to_string(),
+    );
+    cache.set_file_contents("oz_v5/X.sol", "contract V5 {}".to_string());
+    cache.set_file_contents("lib/a/oz_v4/X.sol", "contract V4 {}".to_string());
+
+    cache.add_import_map(OsString::from("@oz"), PathBuf::from("oz_v5"));
+    cache.add_context_import_map(
+        OsString::from("lib/a/"),
+        OsString::from("@oz"),
+        PathBuf::from("lib/a/oz_v4"),
+    );
+
+    let ns = parse_and_resolve(OsStr::new("main.sol"), &mut cache, Target::EVM);
+
+    assert!(!ns.diagnostics.any_errors(), "{:?}", ns.diagnostics);
+    assert_eq!(ns.contracts.len(), 4);
+}
+
+#[test]
+fn import_map_string_prefix() {
+    let mut cache = FileResolver::default();
+
+    // like solc, contexts and maps are string prefixes, so `lib/a` matches `lib/ab/B.sol`
+    // and `@oz` matches `@oz-upgradeable/U.sol`
+    cache.set_file_contents(
+        "main.sol",
+        r#"
+        import "lib/ab/B.sol";
+        import "@oz-upgradeable/U.sol";
+        contract M is B, U {}"#
+            .to_string(),
+    );
+    cache.set_file_contents(
+        "lib/ab/B.sol",
+        r#"
+        import "@oz/X.sol";
+        contract B is V4 {}"#
+            .to_string(),
+    );
+    cache.set_file_contents("oz_v5-upgradeable/U.sol", "contract U {}".to_string());
+    cache.set_file_contents("lib/a/oz_v4/X.sol", "contract V4 {}".to_string());
+
+    cache.add_import_map(OsString::from("@oz"), PathBuf::from("oz_v5"));
+    cache.add_context_import_map(
+        OsString::from("lib/a"),
+        OsString::from("@oz/"),
+        PathBuf::from("lib/a/oz_v4/"),
+    );
+
+    let ns = parse_and_resolve(OsStr::new("main.sol"), &mut cache, Target::EVM);
+
+    assert!(!ns.diagnostics.any_errors(), "{:?}", ns.diagnostics);
+    assert_eq!(ns.contracts.len(), 4);
+
+    // without a trailing slash in the map, the rest of the import is appended as is
+    let mut cache = FileResolver::default();
+
+    cache.set_file_contents("main.sol", r#"import "@oz/X.sol";"#.to_string());
+    cache.add_import_map(OsString::from("@oz/"), PathBuf::from("oz_v5"));
+
+    let ns = parse_and_resolve(OsStr::new("main.sol"), &mut cache, Target::EVM);
+
+    let errors = ns.diagnostics.errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].notes[0].message,
+        "import map '@oz/=oz_v5' maps '@oz/X.sol' to 'oz_v5X.sol'"
+    );
+}
+
+#[test]
+fn import_map_last_wins() {
+    let mut cache = FileResolver::default();
+
+    cache.set_file_contents(
+        "main.sol",
+        r#"
+        import "lib/a/A.sol";
+        contract M is A {}"#
+            .to_string(),
+    );
+    cache.set_file_contents(
+        "lib/a/A.sol",
+        r#"
+        import "@oz/X.sol";
+        contract A is V4 {}"#
+            .to_string(),
+    );
+    cache.set_file_contents("lib/a/oz_v3/X.sol", "contract V3 {}".to_string());
+    cache.set_file_contents("lib/a/oz_v4/X.sol", "contract V4 {}".to_string());
+
+    // the same context and prefix are given twice, so the last one is used
+    cache.add_context_import_map(
+        OsString::from("lib/a/"),
+        OsString::from("@oz"),
+        PathBuf::from("lib/a/oz_v3"),
+    );
+    cache.add_context_import_map(
+        OsString::from("lib/a/"),
+        OsString::from("@oz"),
+        PathBuf::from("lib/a/oz_v4"),
+    );
+
+    assert_eq!(cache.get_context_import_maps().len(), 1);
+
+    let ns = parse_and_resolve(OsStr::new("main.sol"), &mut cache, Target::EVM);
+
+    assert!(!ns.diagnostics.any_errors(), "{:?}", ns.diagnostics);
+    assert_eq!(ns.contracts.len(), 3);
+    assert!(ns.contracts.iter().any(|c| c.id.name == "V4"));
+}
+
+#[test]
+fn experimental_import_path() {
+    let mut cache = FileResolver::default();
+
+    cache.set_file_contents(
+        "main.sol",
+        r#"
+        import std.math;
+        import * as lib from std.math;
+        import {double as twice} from std.math;
+
+        contract C {
+            function f() public pure returns (uint) {
+                return double(1) + lib.double(lib.TWO) + twice(3);
+            }
+        }"#
+        .to_string(),
+    );
+    cache.set_file_contents(
+        "lib/std/math.sol",
+        r#"
+        uint constant TWO = 2;
+        function double(uint x) pure returns (uint) { return x * 2; }"#
+            .to_string(),
+    );
+
+    // `std.math` is the file `std/math.sol`, which is remapped like any other import
+    cache.add_import_map(OsString::from("std"), PathBuf::from("lib/std"));
+
+    let ns = parse_and_resolve(OsStr::new("main.sol"), &mut cache, Target::EVM);
+
+    assert!(!ns.diagnostics.any_errors(), "{:?}", ns.diagnostics);
+
+    let mut cache = FileResolver::default();
+
+    cache.set_file_contents("main.sol", "import std.stub;".to_string());
+
+    let ns = parse_and_resolve(OsStr::new("main.sol"), &mut cache, Target::EVM);
+
+    let errors = ns.diagnostics.errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "file not found 'std/stub.sol'");
+}
+
+#[test]
+fn import_map_not_found() {
+    let mut cache = FileResolver::default();
+
+    cache.set_file_contents("main.sol", r#"import "@oz/Missing.sol";"#.to_string());
+    cache.add_import_map(OsString::from("@oz"), PathBuf::from("oz_v5"));
+    cache.add_context_import_map(
+        OsString::from("lib/a/"),
+        OsString::from("@oz"),
+        PathBuf::from("lib/a/oz_v4"),
+    );
+
+    let ns = parse_and_resolve(OsStr::new("main.sol"), &mut cache, Target::EVM);
+
+    let errors = ns.diagnostics.errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "file not found '@oz/Missing.sol'");
+    assert_eq!(errors[0].notes.len(), 2);
+    assert_eq!(
+        errors[0].notes[0].message,
+        "import map '@oz=oz_v5' maps '@oz/Missing.sol' to 'oz_v5/Missing.sol'"
+    );
+    assert_eq!(
+        errors[0].notes[1].message,
+        "file 'oz_v5/Missing.sol' does not exist"
+    );
+}
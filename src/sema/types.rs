@@ -1995,6 +1995,10 @@ impl Type {
             Type::Struct(str_ty) => str_ty.definition(ns).fields.iter().any(|f| {
                 f.ty.contains_internal_function_internal(ns, structs_visited)
             }),
+            Type::Mapping(Mapping { key, value, .. }) => {
+                key.contains_internal_function_internal(ns, structs_visited)
+                    || value.contains_internal_function_internal(ns, structs_visited)
+            }
             Type::StorageRef(_, r) | Type::Ref(r) => {
                 r.contains_internal_function_internal(ns, structs_visited)
             }
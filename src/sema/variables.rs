@@ -9,6 +9,7 @@ use super::{
     contracts::is_base,
     diagnostics::Diagnostics,
     expression::{ExprContext, ResolveTo},
+    interpreter,
     symtable::{Symtable, VariableInitializer, VariableUsage},
     tags::resolve_tags,
     ContractDefinition,
@@ -19,26 +20,56 @@ use solang_parser::{
     doccomment::DocComment,
     pt::{self, CodeLocation, OptionalCodeLocation},
 };
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 pub struct DelayedResolveInitializer<'a> {
     var_no: usize,
     contract_no: usize,
+    pub(super) constant: bool,
     initializer: &'a pt::Expression,
 }
 
+/// A state variable whose type uses a constant which calls contract functions, for example as
+/// the length of an array. It is declared once these constants are resolved.
+pub struct DelayedVariable<'a> {
+    contract: &'a ContractDefinition<'a>,
+    def: &'a pt::VariableDefinition,
+    tags: &'a [DocComment],
+}
+
 pub fn contract_variables<'a>(
     def: &'a ContractDefinition,
     file_no: usize,
     ns: &mut Namespace,
+    delayed_variables: &mut Vec<DelayedVariable<'a>>,
 ) -> Vec<DelayedResolveInitializer<'a>> {
     let mut symtable = Symtable::default();
     let mut delayed = Vec::new();
+    let mut delay_variables = false;
 
     for part in &def.parts {
         if let pt::ContractPart::VariableDefinition(ref s) = &part.part {
             annotions_not_allowed(&part.annotations, "variable", ns);
 
+            // The storage layout follows the order of the state variables, so once a state
+            // variable is delayed, the state variables which follow it are delayed too
+            if !s
+                .attrs
+                .iter()
+                .any(|attr| matches!(attr, pt::VariableAttribute::Constant(_)))
+            {
+                delay_variables |= uses_delayed_constant(&s.ty, def.contract_no, file_no, ns);
+
+                if delay_variables {
+                    delayed_variables.push(DelayedVariable {
+                        contract: def,
+                        def: s,
+                        tags: &part.doccomments,
+                    });
+                    continue;
+                }
+            }
+
             if let Some(delay) = variable_decl(
                 Some(def),
                 s,
@@ -56,6 +87,176 @@ pub fn contract_variables<'a>(
     delayed
 }
 
+/// Declare the state variables which were delayed, now that the constants they use are resolved
+pub fn delayed_contract_variables<'a>(
+    variables: &[DelayedVariable<'a>],
+    file_no: usize,
+    ns: &mut Namespace,
+) -> Vec<DelayedResolveInitializer<'a>> {
+    let mut symtable = Symtable::default();
+
+    variables
+        .iter()
+        .filter_map(|var| {
+            variable_decl(
+                Some(var.contract),
+                var.def,
+                file_no,
+                var.tags,
+                Some(var.contract.contract_no),
+                ns,
+                &mut symtable,
+            )
+        })
+        .collect()
+}
+
+/// Does the type of a state variable use a constant whose initializer calls contract functions,
+/// and has not been resolved yet
+fn uses_delayed_constant(
+    expr: &pt::Expression,
+    contract_no: usize,
+    file_no: usize,
+    ns: &Namespace,
+) -> bool {
+    let symbol = match expr {
+        pt::Expression::Variable(id) => ns.resolve_var(file_no, Some(contract_no), id, false),
+        pt::Expression::MemberAccess(_, expr, id) => match expr.as_ref() {
+            pt::Expression::Variable(name) => ns.resolve_contract(file_no, name).and_then(|no| {
+                ns.variable_symbols.get(&(
+                    ns.contracts[no].loc.file_no(),
+                    Some(no),
+                    id.name.to_owned(),
+                ))
+            }),
+            _ => None,
+        },
+        pt::Expression::ArraySubscript(_, array, index) => {
+            return uses_delayed_constant(array, contract_no, file_no, ns)
+                || index
+                    .as_ref()
+                    .is_some_and(|index| uses_delayed_constant(index, contract_no, file_no, ns));
+        }
+        pt::Expression::Type(_, pt::Type::Mapping { value, .. }) => {
+            return uses_delayed_constant(value, contract_no, file_no, ns);
+        }
+        _ => {
+            let (left, right) = expr.components();
+
+            return left.is_some_and(|expr| uses_delayed_constant(expr, contract_no, file_no, ns))
+                || right.is_some_and(|expr| uses_delayed_constant(expr, contract_no, file_no, ns));
+        }
+    };
+
+    if let Some(Symbol::Variable(_, Some(var_contract_no), var_no)) = symbol {
+        let var = &ns.contracts[*var_contract_no].variables[*var_no];
+
+        var.constant && var.assigned && var.initializer.is_none()
+    } else {
+        false
+    }
+}
+
+/// Does the initializer of a constant call a function of this contract or its bases, or a function
+/// of another contract or library. The functions of contracts are only available once all the
+/// contract declarations have been resolved.
+fn calls_contract_function(
+    contract: &ContractDefinition,
+    var: &pt::VariableDefinition,
+    file_no: usize,
+    ns: &Namespace,
+) -> bool {
+    if !var
+        .attrs
+        .iter()
+        .any(|attr| matches!(attr, pt::VariableAttribute::Constant(_)))
+    {
+        return false;
+    }
+
+    let Some(initializer) = &var.initializer else {
+        return false;
+    };
+
+    let names: HashSet<&str> = contract
+        .parts
+        .iter()
+        .filter_map(|part| match &part.part {
+            pt::ContractPart::FunctionDefinition(func) => {
+                func.name.as_ref().map(|id| id.name.as_str())
+            }
+            _ => None,
+        })
+        .collect();
+
+    calls_function_named(initializer, &names, contract.contract_no, file_no, ns)
+}
+
+fn calls_function_named(
+    expr: &pt::Expression,
+    names: &HashSet<&str>,
+    contract_no: usize,
+    file_no: usize,
+    ns: &Namespace,
+) -> bool {
+    let is_contract_function = |func: &pt::Expression| match func {
+        pt::Expression::Variable(id) => {
+            names.contains(id.name.as_str())
+                || matches!(
+                    ns.resolve_var(file_no, Some(contract_no), id, true),
+                    Some(Symbol::Function(functions)) if functions
+                        .iter()
+                        .any(|(_, function_no)| ns.functions[*function_no].contract_no.is_some())
+                )
+        }
+        pt::Expression::MemberAccess(_, expr, _) => {
+            matches!(expr.as_ref(), pt::Expression::Variable(id) if ns.resolve_contract(file_no, id).is_some())
+        }
+        _ => false,
+    };
+
+    match expr {
+        pt::Expression::FunctionCall(_, func, args) => {
+            is_contract_function(func)
+                || calls_function_named(func, names, contract_no, file_no, ns)
+                || args
+                    .iter()
+                    .any(|arg| calls_function_named(arg, names, contract_no, file_no, ns))
+        }
+        pt::Expression::NamedFunctionCall(_, func, args) => {
+            is_contract_function(func)
+                || calls_function_named(func, names, contract_no, file_no, ns)
+                || args
+                    .iter()
+                    .any(|arg| calls_function_named(&arg.expr, names, contract_no, file_no, ns))
+        }
+        pt::Expression::ConditionalOperator(_, cond, left, right) => {
+            calls_function_named(cond, names, contract_no, file_no, ns)
+                || calls_function_named(left, names, contract_no, file_no, ns)
+                || calls_function_named(right, names, contract_no, file_no, ns)
+        }
+        pt::Expression::ArraySubscript(_, array, index) => {
+            calls_function_named(array, names, contract_no, file_no, ns)
+                || index.as_ref().is_some_and(|index| {
+                    calls_function_named(index, names, contract_no, file_no, ns)
+                })
+        }
+        pt::Expression::MemberAccess(_, expr, _) => {
+            calls_function_named(expr, names, contract_no, file_no, ns)
+        }
+        pt::Expression::ArrayLiteral(_, exprs) => exprs
+            .iter()
+            .any(|expr| calls_function_named(expr, names, contract_no, file_no, ns)),
+        _ => {
+            let (left, right) = expr.components();
+
+            left.is_some_and(|expr| calls_function_named(expr, names, contract_no, file_no, ns))
+                || right
+                    .is_some_and(|expr| calls_function_named(expr, names, contract_no, file_no, ns))
+        }
+    }
+}
+
 pub fn variable_decl<'a>(
     contract: Option<&ContractDefinition>,
     def: &'a pt::VariableDefinition,
@@ -321,7 +522,12 @@ pub fn variable_decl<'a>(
 
     let mut diagnostics = Diagnostics::default();
 
-    let initializer = if constant {
+    // Constants which call functions of contracts are resolved once the functions of the
+    // contracts are available
+    let delay_constant = constant
+        && contract.is_some_and(|contract| calls_contract_function(contract, def, file_no, ns));
+
+    let initializer = if constant && !delay_constant {
         if let Some(initializer) = &def.initializer {
             let mut context = ExprContext {
                 file_no,
@@ -343,6 +549,13 @@ pub fn variable_decl<'a>(
                     // implicitly conversion to correct ty
                     match res.cast(&def.loc, &ty, true, ns, &mut diagnostics) {
                         Ok(res) => {
+                            // calls to pure functions are evaluated at compile time
+                            let res = interpreter::fold_const_expression(
+                                res,
+                                contract_no,
+                                ns,
+                                &mut diagnostics,
+                            );
                             res.check_constant_overflow(&mut diagnostics);
                             Some(res)
                         }
@@ -399,11 +612,12 @@ pub fn variable_decl<'a>(
 
         ns.contracts[contract_no].variables.push(sdecl);
 
-        if !constant {
+        if !constant || delay_constant {
             if let Some(initializer) = &def.initializer {
                 ret = Some(DelayedResolveInitializer {
                     var_no,
                     contract_no,
+                    constant,
                     initializer,
                 });
             }
@@ -787,11 +1001,16 @@ pub fn resolve_initializers(
     let mut symtable = Symtable::default();
     let mut diagnostics = Diagnostics::default();
 
+    // constants first, since the other initializers may use them
     for DelayedResolveInitializer {
         var_no,
         contract_no,
+        constant,
         initializer,
     } in initializers
+        .iter()
+        .filter(|init| init.constant)
+        .chain(initializers.iter().filter(|init| !init.constant))
     {
         let var = &ns.contracts[*contract_no].variables[*var_no];
         let ty = var.ty.clone();
@@ -799,6 +1018,7 @@ pub fn resolve_initializers(
         let mut context = ExprContext {
             file_no,
             contract_no: Some(*contract_no),
+            constant: *constant,
             ..Default::default()
         };
         context.enter_scope();
@@ -812,6 +1032,16 @@ pub fn resolve_initializers(
             ResolveTo::Type(&ty),
         ) {
             if let Ok(res) = res.cast(&initializer.loc(), &ty, true, ns, &mut diagnostics) {
+                let res = if *constant {
+                    interpreter::fold_const_expression(
+                        res,
+                        Some(*contract_no),
+                        ns,
+                        &mut diagnostics,
+                    )
+                } else {
+                    res
+                };
                 res.check_constant_overflow(&mut diagnostics);
                 ns.contracts[*contract_no].variables[*var_no].initializer = Some(res);
             }
@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::Target;
-use phf::{phf_map, phf_set};
+use phf::phf_map;
 
 pub struct YulBuiltinPrototype {
     pub name: &'static str,
@@ -106,17 +106,13 @@ pub enum YulBuiltInFunction {
     Difficulty = 74,
     GasLimit = 75,
     PrevRandao = 76,
-}
-
-// These are functions that do high level stuff in a contract and are not yet implemented.
-static UNSUPPORTED_BUILTINS: phf::Set<&'static str> = phf_set! {
-    "datasize", "dataoffset", "datacopy", "setimmutable", "loadimmutable",
-    "linkersymbol", "memoryguard"
-};
-
-/// Checks if bultin function is unsupported
-pub(crate) fn yul_unsupported_builtin(name: &str) -> bool {
-    UNSUPPORTED_BUILTINS.contains(name)
+    DataSize = 77,
+    DataOffset = 78,
+    DataCopy = 79,
+    SetImmutable = 80,
+    LoadImmutable = 81,
+    LinkerSymbol = 82,
+    MemoryGuard = 83,
 }
 
 static BUILTIN_YUL_FUNCTIONS: phf::Map<&'static str, YulBuiltInFunction> = phf_map! {
@@ -197,6 +193,13 @@ static BUILTIN_YUL_FUNCTIONS: phf::Map<&'static str, YulBuiltInFunction> = phf_m
     "difficulty" => YulBuiltInFunction::Difficulty,
     "gaslimit" => YulBuiltInFunction::GasLimit,
     "prevrandao" => YulBuiltInFunction::PrevRandao,
+    "datasize" => YulBuiltInFunction::DataSize,
+    "dataoffset" => YulBuiltInFunction::DataOffset,
+    "datacopy" => YulBuiltInFunction::DataCopy,
+    "setimmutable" => YulBuiltInFunction::SetImmutable,
+    "loadimmutable" => YulBuiltInFunction::LoadImmutable,
+    "linkersymbol" => YulBuiltInFunction::LinkerSymbol,
+    "memoryguard" => YulBuiltInFunction::MemoryGuard,
 };
 
 /// Retrieved the builtin function type from an identifier name
@@ -226,6 +229,7 @@ impl YulBuiltInFunction {
                 | YulBuiltInFunction::DelegateCall
                 | YulBuiltInFunction::Create2
                 | YulBuiltInFunction::SelfDestruct
+                | YulBuiltInFunction::SetImmutable
         )
     }
 
@@ -254,6 +258,7 @@ impl YulBuiltInFunction {
                 | YulBuiltInFunction::GasLimit
                 | YulBuiltInFunction::StaticCall
                 | YulBuiltInFunction::SLoad
+                | YulBuiltInFunction::LoadImmutable
         )
     }
 }
@@ -267,7 +272,7 @@ impl ToString for YulBuiltInFunction {
 
 // Yul built-in functions.
 // Descriptions copied and slightly modified from: https://docs.soliditylang.org/en/v0.8.12/yul.html
-static YUL_BUILTIN: [YulBuiltinPrototype; 77] =
+static YUL_BUILTIN: [YulBuiltinPrototype; 84] =
     [
         YulBuiltinPrototype {
             name: "stop",
@@ -962,6 +967,69 @@ static YUL_BUILTIN: [YulBuiltinPrototype; 77] =
             stops_execution: false,
             availability: [true, false, false],
         },
+        YulBuiltinPrototype {
+            name: "datasize",
+            no_args: 1,
+            no_returns: 1,
+            doc: "Returns the size of the code of the contract named by the literal argument",
+            ty: YulBuiltInFunction::DataSize,
+            stops_execution: false,
+            availability: [true, true, false],
+        },
+        YulBuiltinPrototype {
+            name: "dataoffset",
+            no_args: 1,
+            no_returns: 1,
+            doc: "Returns the offset of the code of the contract named by the literal argument",
+            ty: YulBuiltInFunction::DataOffset,
+            stops_execution: false,
+            availability: [true, false, false],
+        },
+        YulBuiltinPrototype {
+            name: "datacopy",
+            no_args: 3,
+            no_returns: 0,
+            doc: "datacopy(t, f, s) copies s bytes from data at position f to memory at position t",
+            ty: YulBuiltInFunction::DataCopy,
+            stops_execution: false,
+            availability: [true, false, false],
+        },
+        YulBuiltinPrototype {
+            name: "setimmutable",
+            no_args: 3,
+            no_returns: 0,
+            doc: "setimmutable(offset, \"name\", value) sets the immutable variable name to value",
+            ty: YulBuiltInFunction::SetImmutable,
+            stops_execution: false,
+            availability: [true, true, true],
+        },
+        YulBuiltinPrototype {
+            name: "loadimmutable",
+            no_args: 1,
+            no_returns: 1,
+            doc: "loadimmutable(\"name\") returns the value of the immutable variable name",
+            ty: YulBuiltInFunction::LoadImmutable,
+            stops_execution: false,
+            availability: [true, true, true],
+        },
+        YulBuiltinPrototype {
+            name: "linkersymbol",
+            no_args: 1,
+            no_returns: 1,
+            doc: "Returns the address of the library named by the literal argument",
+            ty: YulBuiltInFunction::LinkerSymbol,
+            stops_execution: false,
+            availability: [true, false, false],
+        },
+        YulBuiltinPrototype {
+            name: "memoryguard",
+            no_args: 1,
+            no_returns: 1,
+            doc: "Returns its literal argument, marking memory below it as reserved",
+            ty: YulBuiltInFunction::MemoryGuard,
+            stops_execution: false,
+            availability: [true, true, true],
+        },
     ];
 
 #[test]
@@ -2,10 +2,12 @@
 
 use crate::sema::ast::{ArrayLength, Namespace, Parameter, Symbol, Type};
 use crate::sema::diagnostics::Diagnostics;
-use crate::sema::expression::{strings::unescape, ExprContext};
+use crate::sema::expression::{
+    constructor::contract_code_reference, strings::unescape, ExprContext,
+};
 use crate::sema::symtable::{Symtable, VariableUsage};
 use crate::sema::yul::ast::{YulExpression, YulSuffix};
-use crate::sema::yul::builtin::{parse_builtin_keyword, yul_unsupported_builtin};
+use crate::sema::yul::builtin::{parse_builtin_keyword, YulBuiltInFunction};
 use crate::sema::yul::functions::FunctionsTable;
 use crate::sema::yul::types::{
     get_default_type_from_identifier, get_type_from_string, verify_type_from_expression,
@@ -294,11 +296,33 @@ fn resolve_variable_reference(
             Some(Symbol::Variable(_, Some(var_contract_no), var_no)) => {
                 let var = &ns.contracts[*var_contract_no].variables[*var_no];
                 if var.immutable {
-                    ns.diagnostics.push(Diagnostic::error(
+                    let (var_contract_no, var_no, ty) = (*var_contract_no, *var_no, var.ty.clone());
+
+                    if context.lvalue {
+                        ns.diagnostics.push(Diagnostic::error(
+                            id.loc,
+                            "immutable variables cannot be assigned in assembly. You may use 'setimmutable()'"
+                                .to_string(),
+                        ));
+                        return Err(());
+                    }
+
+                    if !immutable_accessible(id.loc, &ty, ns) {
+                        return Err(());
+                    }
+
+                    ns.contracts[var_contract_no].variables[var_no].read = true;
+
+                    // Reading an immutable is the same as `loadimmutable("name")`
+                    return Ok(YulExpression::BuiltInCall(
                         id.loc,
-                        "assembly access to immutable variables is not supported".to_string(),
+                        YulBuiltInFunction::LoadImmutable,
+                        vec![YulExpression::StringLiteral(
+                            id.loc,
+                            id.name.as_bytes().to_vec(),
+                            Type::Uint(256),
+                        )],
                     ));
-                    return Err(());
                 }
 
                 if var.constant {
@@ -364,15 +388,6 @@ pub(crate) fn resolve_function_call(
             "verbatim functions are not yet supported in Solang".to_string(),
         ));
         return Err(());
-    } else if yul_unsupported_builtin(func_call.id.name.as_str()) {
-        ns.diagnostics.push(Diagnostic::error(
-            func_call.id.loc,
-            format!(
-                "the internal EVM built-in '{}' is not yet supported",
-                func_call.id.name
-            ),
-        ));
-        return Err(());
     }
     let mut resolved_arguments: Vec<YulExpression> = Vec::with_capacity(func_call.arguments.len());
     for item in &func_call.arguments {
@@ -416,6 +431,8 @@ pub(crate) fn resolve_function_call(
             return Err(());
         }
 
+        check_literal_arguments(*built_in, &resolved_arguments, context, ns)?;
+
         let default_builtin_parameter = Parameter {
             loc: Loc::Builtin,
             id: None,
@@ -476,6 +493,144 @@ pub(crate) fn resolve_function_call(
     Err(())
 }
 
+/// Only immutables of value type fit into a yul variable
+fn immutable_accessible(loc: pt::Loc, ty: &Type, ns: &mut Namespace) -> bool {
+    if ty.is_reference_type(ns) || matches!(ty, Type::InternalFunction { .. }) {
+        ns.diagnostics.push(Diagnostic::error(
+            loc,
+            "only immutable variables of value type can be accessed in assembly".to_string(),
+        ));
+        false
+    } else {
+        true
+    }
+}
+
+/// Some builtins take a literal argument which names a contract or an immutable variable.
+/// Check that these refer to something which exists.
+fn check_literal_arguments(
+    builtin: YulBuiltInFunction,
+    args: &[YulExpression],
+    context: &mut ExprContext,
+    ns: &mut Namespace,
+) -> Result<(), ()> {
+    let arg_no = match builtin {
+        YulBuiltInFunction::MemoryGuard => {
+            if !matches!(args[0], YulExpression::NumberLiteral(..)) {
+                ns.diagnostics.push(Diagnostic::error(
+                    args[0].loc(),
+                    "the argument to 'memoryguard' must be a number literal".to_string(),
+                ));
+                return Err(());
+            }
+            return Ok(());
+        }
+        YulBuiltInFunction::DataSize
+        | YulBuiltInFunction::DataOffset
+        | YulBuiltInFunction::LinkerSymbol
+        | YulBuiltInFunction::LoadImmutable => 0,
+        YulBuiltInFunction::SetImmutable => 1,
+        _ => return Ok(()),
+    };
+
+    let name = builtin.get_prototype_info().name;
+
+    let YulExpression::StringLiteral(loc, value, _) = &args[arg_no] else {
+        ns.diagnostics.push(Diagnostic::error(
+            args[arg_no].loc(),
+            format!("the argument to '{name}' must be a string literal"),
+        ));
+        return Err(());
+    };
+
+    let id = pt::Identifier {
+        loc: *loc,
+        name: String::from_utf8_lossy(value).to_string(),
+    };
+
+    match builtin {
+        YulBuiltInFunction::DataSize | YulBuiltInFunction::DataOffset => {
+            let Some(no) = ns.resolve_contract(context.file_no, &id) else {
+                ns.diagnostics.push(Diagnostic::error(
+                    *loc,
+                    format!("contract '{}' not found", id.name),
+                ));
+                return Err(());
+            };
+
+            let mut diagnostics = Diagnostics::default();
+            let res = contract_code_reference(loc, no, context, ns, &mut diagnostics);
+            ns.diagnostics.extend(diagnostics);
+            res
+        }
+        YulBuiltInFunction::LinkerSymbol => match ns.resolve_contract(context.file_no, &id) {
+            Some(no) if ns.contracts[no].is_library() => Ok(()),
+            _ => {
+                ns.diagnostics.push(Diagnostic::error(
+                    *loc,
+                    format!("library '{}' not found", id.name),
+                ));
+                Err(())
+            }
+        },
+        _ => {
+            let var = if context.yul_function {
+                None
+            } else {
+                ns.resolve_var(context.file_no, context.contract_no, &id, false)
+            };
+
+            let Some(Symbol::Variable(_, Some(var_contract_no), var_no)) = var else {
+                ns.diagnostics.push(Diagnostic::error(
+                    *loc,
+                    format!("immutable variable '{}' not found", id.name),
+                ));
+                return Err(());
+            };
+
+            let (var_contract_no, var_no) = (*var_contract_no, *var_no);
+            let var = &ns.contracts[var_contract_no].variables[var_no];
+
+            if !var.immutable {
+                ns.diagnostics.push(Diagnostic::error_with_note(
+                    *loc,
+                    format!("'{}' is not an immutable variable", id.name),
+                    var.loc,
+                    format!("definition of '{}'", id.name),
+                ));
+                return Err(());
+            }
+
+            if !immutable_accessible(*loc, &var.ty.clone(), ns) {
+                return Err(());
+            }
+
+            if builtin == YulBuiltInFunction::SetImmutable {
+                if !context
+                    .function_no
+                    .map(|function_no| ns.functions[function_no].is_constructor())
+                    .unwrap_or(false)
+                {
+                    ns.diagnostics.push(Diagnostic::error(
+                        *loc,
+                        format!(
+                            "cannot assign to immutable '{}' outside of constructor",
+                            id.name
+                        ),
+                    ));
+                    return Err(());
+                }
+
+                ns.contracts[var_contract_no].variables[var_no].assigned = true;
+            } else {
+                ns.contracts[var_contract_no].variables[var_no].read = true;
+            }
+
+            Ok(())
+        }
+    }
+}
+
 /// Check if the provided argument is compatible with the declared parameters of a function.
 fn check_function_argument(
     parameter: &Parameter<Type>,
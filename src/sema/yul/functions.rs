@@ -5,7 +5,7 @@ use crate::sema::expression::ExprContext;
 use crate::sema::symtable::{LoopScopes, Symtable, VariableInitializer, VariableUsage};
 use crate::sema::yul::ast::YulFunction;
 use crate::sema::yul::block::resolve_yul_block;
-use crate::sema::yul::builtin::{parse_builtin_keyword, yul_unsupported_builtin};
+use crate::sema::yul::builtin::parse_builtin_keyword;
 use crate::sema::yul::types::get_type_from_string;
 use indexmap::IndexMap;
 use solang_parser::diagnostics::{ErrorType, Level, Note};
@@ -196,9 +196,7 @@ pub(crate) fn process_function_header(
             }],
         });
         return;
-    } else if parse_builtin_keyword(&func_def.id.name).is_some()
-        || yul_unsupported_builtin(&func_def.id.name)
-    {
+    } else if parse_builtin_keyword(&func_def.id.name).is_some() {
         ns.diagnostics.push(Diagnostic::error(
             func_def.loc,
             format!(
@@ -5,7 +5,7 @@ use crate::sema::expression::ExprContext;
 use crate::sema::symtable::{LoopScopes, Symtable, VariableInitializer, VariableUsage};
 use crate::sema::yul::ast::{YulExpression, YulStatement};
 use crate::sema::yul::block::resolve_yul_block;
-use crate::sema::yul::builtin::{parse_builtin_keyword, yul_unsupported_builtin};
+use crate::sema::yul::builtin::parse_builtin_keyword;
 use crate::sema::yul::expression::{check_type, resolve_function_call, resolve_yul_expression};
 use crate::sema::yul::for_loop::resolve_for_loop;
 use crate::sema::yul::functions::FunctionsTable;
@@ -238,9 +238,7 @@ fn resolve_variable_declaration(
                 }],
             });
             return Err(());
-        } else if yul_unsupported_builtin(&item.id.name)
-            || parse_builtin_keyword(&item.id.name).is_some()
-        {
+        } else if parse_builtin_keyword(&item.id.name).is_some() {
             ns.diagnostics.push(Diagnostic::error(
                 item.loc,
                 format!(
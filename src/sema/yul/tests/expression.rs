@@ -13,6 +13,7 @@ use crate::sema::yul::functions::FunctionsTable;
 use crate::sema::yul::tests::parse;
 use crate::{parse_and_resolve, sema::ast, FileResolver, Target};
 use num_bigint::BigInt;
+use solang_parser::diagnostics::Level;
 use solang_parser::pt;
 use solang_parser::pt::{
     ContractTy, HexLiteral, Identifier, Loc, StorageLocation, StringLiteral, Visibility,
@@ -531,12 +532,21 @@ fn resolve_variable_contract() {
         &mut function_table,
         &mut ns,
     );
-    assert!(res.is_err());
-    assert_eq!(ns.diagnostics.len(), 1);
+    assert!(res.is_ok());
+    assert!(ns.diagnostics.is_empty());
     assert_eq!(
-        ns.diagnostics.iter().next().unwrap().message,
-        "assembly access to immutable variables is not supported"
+        YulExpression::BuiltInCall(
+            loc,
+            YulBuiltInFunction::LoadImmutable,
+            vec![YulExpression::StringLiteral(
+                loc,
+                b"imut".to_vec(),
+                Type::Uint(256)
+            )]
+        ),
+        res.unwrap()
     );
+    assert!(ns.contracts[0].variables[2].read);
 }
 
 #[test]
@@ -591,7 +601,7 @@ fn function_call() {
     assert_eq!(ns.diagnostics.len(), 1);
     assert_eq!(
         ns.diagnostics.iter().next().unwrap().message,
-        "the internal EVM built-in 'linkersymbol' is not yet supported"
+        "builtin function 'linkersymbol' requires 1 arguments, but 0 were provided"
     );
     ns.diagnostics = Diagnostics::default();
 
@@ -1715,3 +1725,157 @@ contract foo {
 
     assert!(ns.diagnostics.contains_message("builtin 'log0' is not available for target Solana. Please, open a GitHub issue at https://github.com/hyperledger/solang/issues if there is need to support this function"));
 }
+
+#[test]
+fn immutable_builtins() {
+    let file = r#"
+contract foo {
+    uint64 immutable a;
+    bytes32 immutable b;
+    uint64 c;
+
+    constructor(uint64 x) {
+        assembly {
+            setimmutable(0, "a", x)
+            setimmutable(0, "b", memoryguard(0x80))
+        }
+    }
+
+    function get() public view returns (uint64 r, uint256 s) {
+        assembly {
+            r := add(a, loadimmutable("a"))
+            s := b
+        }
+    }
+}
+    "#;
+
+    let ns = parse(file);
+    assert!(!ns.diagnostics.any_errors());
+    assert!(!ns
+        .diagnostics
+        .contains_message("storage variable 'a' has been assigned, but never read"));
+
+    let file = r#"
+contract foo {
+    uint64 immutable a;
+    uint64 c;
+
+    constructor(uint64 x) {
+        a = x;
+    }
+
+    function set(uint64 x) public {
+        assembly {
+            setimmutable(0, "a", x)
+        }
+        assembly {
+            setimmutable(0, "c", x)
+        }
+        assembly {
+            a := x
+        }
+    }
+
+    function get(uint256 x) public view returns (uint256 r) {
+        assembly {
+            r := memoryguard(x)
+        }
+        assembly {
+            r := loadimmutable(r)
+        }
+        assembly {
+            r := loadimmutable("d")
+        }
+        assembly {
+            r := datasize("bar")
+        }
+    }
+}
+    "#;
+
+    let ns = parse(file);
+    assert!(ns
+        .diagnostics
+        .contains_message("cannot assign to immutable 'a' outside of constructor"));
+    assert!(ns
+        .diagnostics
+        .contains_message("'c' is not an immutable variable"));
+    assert!(ns.diagnostics.contains_message(
+        "immutable variables cannot be assigned in assembly. You may use 'setimmutable()'"
+    ));
+    assert!(ns
+        .diagnostics
+        .contains_message("the argument to 'memoryguard' must be a number literal"));
+    assert!(ns
+        .diagnostics
+        .contains_message("the argument to 'loadimmutable' must be a string literal"));
+    assert!(ns
+        .diagnostics
+        .contains_message("immutable variable 'd' not found"));
+    assert!(ns.diagnostics.contains_message("contract 'bar' not found"));
+}
+
+#[test]
+fn data_builtins() {
+    let file = r#"
+contract bar {
+    function f() public {}
+}
+
+contract foo {
+    function size() public returns (uint256 r) {
+        assembly {
+            r := datasize("bar")
+        }
+    }
+
+    function other() public returns (uint256 r) {
+        assembly {
+            r := datasize("foo")
+        }
+    }
+}
+    "#;
+
+    let mut cache = FileResolver::default();
+    cache.set_file_contents("test.sol", file.to_string());
+
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::Polkadot {
+            address_length: 32,
+            value_length: 16,
+        },
+    );
+
+    assert_eq!(
+        ns.diagnostics
+            .iter()
+            .filter(|d| d.level == Level::Error)
+            .count(),
+        1
+    );
+    assert!(ns
+        .diagnostics
+        .contains_message("cannot construct current contract 'foo'"));
+    assert_eq!(ns.contracts[1].creates, vec![0]);
+
+    let file = r#"
+contract foo {
+    function testing() public returns (uint256 r) {
+        assembly {
+            r := datasize("foo")
+        }
+    }
+}
+    "#;
+
+    let mut cache = FileResolver::default();
+    cache.set_file_contents("test.sol", file.to_string());
+
+    let ns = parse_and_resolve(OsStr::new("test.sol"), &mut cache, Target::Solana);
+
+    assert!(ns.diagnostics.contains_message("builtin 'datasize' is not available for target Solana. Please, open a GitHub issue at https://github.com/hyperledger/solang/issues if there is need to support this function"));
+}
@@ -424,3 +424,46 @@ fn function_without_modifier() {
         .diagnostics
         .contains_message("function can be declared 'view'"));
 }
+
+#[test]
+fn immutables() {
+    let file = r#"
+contract testTypes {
+    uint64 immutable a;
+
+    constructor() {
+        a = 1;
+    }
+
+    function testAsm() public pure returns (uint64 r) {
+        assembly {
+            r := a
+        }
+    }
+}
+    "#;
+    let ns = parse(file);
+    assert!(ns
+        .diagnostics
+        .contains_message("function declared 'pure' but this expression reads from state"));
+
+    let file = r#"
+contract testTypes {
+    uint64 immutable a;
+
+    constructor() {
+        a = 1;
+    }
+
+    function testAsm() public pure returns (uint64 r) {
+        assembly {
+            r := loadimmutable("a")
+        }
+    }
+}
+    "#;
+    let ns = parse(file);
+    assert!(ns
+        .diagnostics
+        .contains_message("function declared 'pure' but this expression reads from state"));
+}
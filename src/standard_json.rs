@@ -3,6 +3,7 @@
 //! This module defines the json format for `solang compile --standard-json`.
 
 use crate::abi::ethereum::ABI;
+use crate::abi::natspec::{DevDoc, UserDoc};
 use serde::Serialize;
 use std::collections::HashMap;
 
@@ -18,6 +19,8 @@ pub struct JsonContract {
     pub ewasm: Option<EwasmContract>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub minimum_space: Option<u32>,
+    pub userdoc: UserDoc,
+    pub devdoc: DevDoc,
 }
 
 #[derive(Serialize)]
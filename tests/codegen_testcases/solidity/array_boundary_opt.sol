@@ -1,4 +1,4 @@
-// RUN: --target polkadot --emit cfg
+// RUN: --target polkadot --emit cfg --no-loop-optimization
 
 contract Array_bound_Test {
     // BEGIN-CHECK: Array_bound_Test::Array_bound_Test::function::array_bound__uint256:
@@ -50,7 +50,8 @@ contract Array_bound_Test {
         uint256 sesa = 0;
 
 
-        // CHECK: branchcond (unsigned uint32 20 >= uint32 21), block5, block6
+        // CHECK: ty:uint32 %index.temp.43 = uint32 20
+        // CHECK: branch block6
         // CHECK: branchcond (unsigned less %i < uint256 21), block1, block4
         for (uint256 i = 0; i < a.length; i++) {
             sesa = sesa + a[20];
@@ -79,14 +80,17 @@ contract Array_bound_Test {
         int256[] vec = new int256[](10);
 
         for (int256 i = 0; i < 5; i++) {
-            // CHECK: branchcond (unsigned more %array_length.temp.49 > uint32 20), block5, block6
+            // the length of vec is never more than 15
+            // CHECK: block1: # body
+            // CHECK: branch block6
             if (vec.length > 20) {
                 break;
             }
             vec.push(3);
         }
 
-        // CHECK: branchcond (%array_length.temp.49 == uint32 15), block7, block8
+        // CHECK: block4: # endfor
+        // CHECK: branch block7
         assert(vec.length == 15);
     }
 
@@ -1,4 +1,4 @@
-// RUN: --target solana --emit cfg
+// RUN: --target solana --emit cfg --no-dead-function-elimination
 contract TestCase {
     int128[] st;
 
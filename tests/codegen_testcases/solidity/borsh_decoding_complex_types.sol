@@ -42,7 +42,7 @@ contract Testing {
         // CHECK: branchcond (unsigned %1.cse_temp <= %temp.10), block7, block8
 
         // CHECK: block6: # end_for
-        // CHECK: ty:uint32 %temp.12 = (%temp.12 - uint32 0)
+        // CHECK: ty:uint32 %temp.12 = (overflowing %temp.12 - uint32 0)
         // CHECK: branchcond (unsigned less (uint32 0 + %temp.12) < %temp.10), block11, block12
         // CHECK: block7: # inbounds
 
@@ -183,7 +183,7 @@ contract Testing {
         // CHECK: branchcond (unsigned %1.cse_temp <= %temp.32), block7, block8
 
         // CHECK: block6: # end_for
-        // CHECK: ty:uint32 %temp.34 = (%temp.34 - uint32 0)
+        // CHECK: ty:uint32 %temp.34 = (overflowing %temp.34 - uint32 0)
         // CHECK: branchcond (unsigned less (uint32 0 + %temp.34) < %temp.32), block19, block20
 
         // CHECK: block7: # inbounds
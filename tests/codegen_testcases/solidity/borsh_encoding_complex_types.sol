@@ -1,4 +1,4 @@
-// RUN: --target solana --emit cfg --no-strength-reduce
+// RUN: --target solana --emit cfg --no-strength-reduce --no-loop-optimization
 
 contract EncodingTest {
     struct NonConstantStruct {
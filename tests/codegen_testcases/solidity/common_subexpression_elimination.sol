@@ -1,4 +1,4 @@
-// RUN: --target polkadot --emit cfg
+// RUN: --target polkadot --emit cfg --no-inline
 
 // Tests control commands
 contract c1 {
@@ -124,7 +124,7 @@ contract c1 {
        	for(int i=0; i<10; i++) {
             // CHECK: ty:int256 %t = ((arg #0) - (arg #1))
 			int t = a-b;
-            // CHECK: ty:int256 %i = (%temp.187 + int256 1)
+            // CHECK: ty:int256 %i = (overflowing %temp.187 + int256 1)
 			bool e1 = t > 3;
 		}
 
@@ -0,0 +1,54 @@
+// RUN: --target solana --emit cost-report
+contract Costs {
+    uint64[] values;
+
+    // CHECK: contract Costs (Solana, compute units)
+    // CHECK: function
+    // CHECK: fixed_loop()           103
+    function fixed_loop() public pure returns (uint64 sum) {
+        for (uint64 i = 0; i < 10; i++) {
+            sum += i;
+        }
+    }
+
+    // CHECK: 36 + 24 * list.length
+    function sum(uint64[] memory list) public pure returns (uint64 s) {
+        for (uint i = 0; i < list.length; i++) {
+            s += list[i];
+        }
+    }
+
+    // CHECK: 37 + 27 * values.length
+    function sum_storage() public view returns (uint64 s) {
+        for (uint i = 0; i < values.length; i++) {
+            s += values[i];
+        }
+    }
+
+    // CHECK: 29 + 13 * m + 23 * n + 13 * m * n
+    function nested(uint64 n, uint64 m) public pure returns (uint64 s) {
+        for (uint64 i = 0; i < n; i++) {
+            for (uint64 j = 0; j < m; j++) {
+                s += i * j;
+            }
+        }
+    }
+
+    // CHECK: 15 + 12 * loop@39
+    function collatz(uint64 x) public pure returns (uint64) {
+        while (x != 1) {
+            if (x % 2 == 0) {
+                x /= 2;
+            } else {
+                x = 3 * x + 1;
+            }
+        }
+        return x;
+    }
+
+    // CHECK: unbounded
+    function fib(uint64 n) public pure returns (uint64) {
+        if (n < 2) return n;
+        return fib(n - 1) + fib(n - 2);
+    }
+}
@@ -0,0 +1,26 @@
+// RUN: --target polkadot --emit cost-report
+// READ: Weights.cost.json
+contract Weights {
+    uint64 counter;
+
+    // CHECK: "contract": "Weights",
+    // CHECK: "target": "Polkadot",
+    // CHECK: "unit": "ref_time (ps)",
+    // CHECK: "function": "increment()",
+    // CHECK: "cost": 125022000,
+    // CHECK: "unbounded": false
+    function increment() public {
+        counter += 1;
+    }
+
+    // CHECK: "function": "repeat(uint32)",
+    // CHECK: "cost": null,
+    // CHECK: "estimate": "125033000 + 13000 * times",
+    // CHECK: "symbols": [
+    // CHECK: "times"
+    function repeat(uint32 times) public {
+        for (uint32 i = 0; i < times; i++) {
+            counter += 1;
+        }
+    }
+}
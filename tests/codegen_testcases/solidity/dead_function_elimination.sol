@@ -0,0 +1,54 @@
+// RUN: --target solana --emit cfg --no-inline -v
+
+// Functions which can not be reached from the dispatcher are not part of the contract
+// CHECK: # function DeadFunctions::Math::function::double__uint64
+// CHECK: # function DeadFunctions::DeadFunctions::function::used__uint64
+// CHECK: # function DeadFunctions::DeadFunctions::function::pointer__uint64
+// CHECK: # function DeadFunctions::DeadFunctions::function::run__uint64
+// CHECK: # function solang_dispatch
+// CHECK-ABSENT: function::triple
+// CHECK-ABSENT: function::unused
+// CHECK-ABSENT: function::even
+// CHECK-ABSENT: function::odd
+
+// BEGIN-CHECK: function 'triple' has no remaining callers and has been removed from contract 'DeadFunctions'
+// CHECK: function 'unused' has no remaining callers and has been removed from contract 'DeadFunctions'
+// CHECK: function 'even' has no remaining callers and has been removed from contract 'DeadFunctions'
+// CHECK: function 'odd' has no remaining callers and has been removed from contract 'DeadFunctions'
+
+library Math {
+    function double(uint64 x) internal pure returns (uint64) {
+        return x * 2;
+    }
+
+    function triple(uint64 x) internal pure returns (uint64) {
+        return x * 3;
+    }
+}
+
+contract DeadFunctions {
+    function used(uint64 x) private pure returns (uint64) {
+        return Math.double(x) + 1;
+    }
+
+    function unused(uint64 x) private pure returns (uint64) {
+        return Math.triple(x);
+    }
+
+    function pointer(uint64 x) private pure returns (uint64) {
+        return x + 2;
+    }
+
+    function even(uint64 x) private pure returns (bool) {
+        return x == 0 || odd(x - 1);
+    }
+
+    function odd(uint64 x) private pure returns (bool) {
+        return x != 0 && even(x - 1);
+    }
+
+    function run(uint64 x) public pure returns (uint64) {
+        function(uint64) internal pure returns (uint64) f = pointer;
+        return f(used(x));
+    }
+}
@@ -1,4 +1,4 @@
-// RUN: --no-dead-storage --emit cfg --target polkadot
+// RUN: --no-dead-storage --no-storage-cache --emit cfg --target polkadot
 contract nodeadstorage {
     int a;
 
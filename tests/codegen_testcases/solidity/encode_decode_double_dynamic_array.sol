@@ -1,4 +1,4 @@
-// RUN: --target solana --emit cfg
+// RUN: --target solana --emit cfg --no-loop-optimization
 
 contract Testing {
 
@@ -61,7 +61,7 @@ contract Testing {
 	    // CHECK: branch block13
 
         // CHECK: block12: # end_for
-	    // CHECK: ty:uint32 %temp.11 = (%temp.11 - uint32 0)
+	    // CHECK: ty:uint32 %temp.11 = (overflowing %temp.11 - uint32 0)
         // CHECK: ty:bytes %b = %abi_encoded.temp.10
 	    // CHECK: return %b
 
@@ -115,7 +115,7 @@ contract Testing {
 	    // CHECK: branchcond (unsigned %1.cse_temp <= %temp.14), block7, block8
 
         // CHECK: block6: # end_for
-	    // CHECK: ty:uint32 %temp.16 = (%temp.16 - uint32 0)
+	    // CHECK: ty:uint32 %temp.16 = (overflowing %temp.16 - uint32 0)
 	    // CHECK: branchcond (unsigned less (uint32 0 + %temp.16) < %temp.14), block15, block16
 
         // CHECK: block7: # inbounds
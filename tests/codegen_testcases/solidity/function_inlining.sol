@@ -0,0 +1,76 @@
+// RUN: --target polkadot --emit cfg
+contract inlining {
+    uint64 total;
+
+    function fee(uint64 amount) internal pure returns (uint64) {
+        return (amount * 25) / 1000;
+    }
+
+    // BEGIN-CHECK: inlining::inlining::function::fixed_fee
+    function fixed_fee() public pure returns (uint64) {
+        // CHECK: = uint64 50
+        // CHECK: return %.temp.
+        // CHECK-ABSENT: call inlining::inlining::function::fee__uint64
+        return fee(2000);
+    }
+
+    function sign(int64 x) internal pure returns (int64) {
+        if (x < 0) {
+            return -1;
+        } else if (x > 0) {
+            return 1;
+        }
+        return 0;
+    }
+
+    // BEGIN-CHECK: inlining::inlining::function::signs__int64
+    function signs(int64 x) public pure returns (int64) {
+        // CHECK: branchcond (signed less (arg #0) < int64 0)
+        // CHECK: # phis: .temp.
+        // CHECK: ty:int64 %x = int64 -5
+        // CHECK: ty:int64 %.temp.18 = int64 -1
+        // CHECK: # phis: .temp.
+        // CHECK-ABSENT: call inlining::inlining::function::sign__int64
+        return sign(x) + sign(-5);
+    }
+
+    function factorial(uint64 n) internal pure returns (uint64) {
+        if (n <= 1) {
+            return 1;
+        }
+        return n * factorial(n - 1);
+    }
+
+    // BEGIN-CHECK: inlining::inlining::function::recursive
+    function recursive() public pure returns (uint64) {
+        // recursive functions are never inlined
+        // CHECK: call inlining::inlining::function::factorial__uint64 uint64 5
+        return factorial(5);
+    }
+
+    function apply(function(uint64) internal pure returns (uint64) f, uint64 v) internal pure returns (uint64) {
+        return f(v);
+    }
+
+    // BEGIN-CHECK: inlining::inlining::function::pointer
+    function pointer() public pure returns (uint64) {
+        // the function pointer call is not inlined, but apply is
+        // CHECK: = function inlining::inlining::function::fee__uint64
+        // CHECK: = call %f uint64 4000
+        return apply(fee, 4000);
+    }
+
+    modifier add_total(uint64 v) {
+        total += v;
+        _;
+    }
+
+    // BEGIN-CHECK: inlining::inlining::add__uint64::modifier0::add_total__uint64
+    function add(uint64 v) public add_total(v) {
+        // the body is inlined into the modifier
+        // CHECK: + (arg #0))
+        // CHECK: * uint64 2)
+        // CHECK-ABSENT: call inlining::inlining::function::add__uint64
+        total *= 2;
+    }
+}
@@ -1,4 +1,4 @@
-// RUN: --target polkadot --emit cfg
+// RUN: --target polkadot --emit cfg --no-dead-function-elimination
 contract Ownable {
     uint256 public _ext;
 
@@ -0,0 +1,54 @@
+// RUN: --target polkadot --emit cfg --no-inline --no-storage-cache
+
+contract LoopOptimization {
+    mapping(address => uint64) balances;
+    uint64[] values;
+
+    // BEGIN-CHECK: LoopOptimization::LoopOptimization::function::sum__uint64
+    function sum(uint64[] memory arr) public pure returns (uint64 total) {
+        // The array length is loaded once, before the loop
+        // CHECK: block0: # entry
+        // CHECK: ty:uint256 %invariant.temp.13 = (zext uint256 (builtin ArrayLength ((arg #0))))
+        // CHECK: branch block2
+        // CHECK: branchcond (unsigned %index.temp.11 >= %invariant.temp.13), block5, block6
+        // CHECK: branchcond (unsigned less %i < %invariant.temp.13), block1, block4
+        for (uint i = 0; i < arr.length; i++) {
+            total += arr[i];
+        }
+    }
+
+    // BEGIN-CHECK: LoopOptimization::LoopOptimization::function::credit__uint256
+    function credit(uint n) public view returns (uint64 total) {
+        // The loop does not write to storage, so the mapping slot is only hashed once
+        // CHECK: ty:mapping(address => uint64) storage %invariant.temp.16 = (keccak256 uint256 0, address((builtin Sender ())))
+        // CHECK: block1: # body
+        // CHECK: %temp.14 = load storage slot(%invariant.temp.16) ty:uint64
+        for (uint i = 0; i < n; i++) {
+            total += balances[msg.sender];
+        }
+    }
+
+    // BEGIN-CHECK: LoopOptimization::LoopOptimization::function::push__uint256
+    function push(uint n) public {
+        // The loop writes to storage, so nothing may be moved out of it
+        // NOT-CHECK: invariant.temp
+        for (uint i = 0; i < n; i++) {
+            values.push(uint64(i));
+            n = values.length;
+        }
+    }
+
+    // BEGIN-CHECK: LoopOptimization::LoopOptimization::function::scaled__uint32
+    function scaled(uint32 n) public pure returns (uint64 total) {
+        // The multiplication is replaced with an addition in each iteration
+        // CHECK: ty:uint64 %induction.temp.23 = (overflowing (zext uint64 %i) * uint64 12)
+        // CHECK: block1: # body
+        // CHECK: ty:uint64 %total = (%total + %induction.temp.23)
+        // CHECK: block3: # next
+        // CHECK: ty:uint32 %i = (%temp.22 + uint32 1)
+        // CHECK: ty:uint64 %induction.temp.23 = (overflowing %induction.temp.23 + uint64 12)
+        for (uint32 i = 0; i < n; i++) {
+            total += uint64(i) * 12;
+        }
+    }
+}
@@ -1,4 +1,4 @@
-// RUN: --target polkadot --emit cfg
+// RUN: --target polkadot --emit cfg --no-dead-function-elimination
 
 contract Test {
     // BEGIN-CHECK: Test::testMethod::modifier0::m1
@@ -1,4 +1,4 @@
-// RUN: --target polkadot --release --emit cfg
+// RUN: --target polkadot --release --emit cfg --no-dead-function-elimination
 
 interface IERC165 {
     function supportsInterface(bytes4 interfaceId) external view returns (bool);
@@ -1,4 +1,4 @@
-// RUN: --target polkadot --emit cfg
+// RUN: --target polkadot --emit cfg --no-inline
 contract c {
 // BEGIN-CHECK: c::function::test1
 	function test1() public pure{
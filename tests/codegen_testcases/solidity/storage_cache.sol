@@ -0,0 +1,70 @@
+// RUN: --target polkadot --emit cfg --no-inline --no-loop-optimization
+contract other {
+    function ping() public {}
+}
+
+contract cache {
+    mapping(address => uint256) balances;
+    uint64 count;
+    uint256 total;
+
+// BEGIN-CHECK: cache::function::transfer
+    function transfer(address to, uint256 amount) public {
+        require(balances[msg.sender] >= amount, "insufficient");
+        balances[msg.sender] -= amount;
+        balances[to] += amount;
+// CHECK: %storage.temp.18 = load storage slot((keccak256 uint256 0, address((builtin Sender ())))) ty:uint256
+// CHECK: ty:uint256 %temp.14 = %storage.temp.18
+        // to may be the sender, so the balance of the sender must be written first
+// CHECK: store storage slot((keccak256 uint256 0, address((builtin Sender ())))) ty:uint256 = %storage.temp.18
+// CHECK: %storage.temp.19 = load storage slot((keccak256 uint256 0, (arg #0))) ty:uint256
+// CHECK: store storage slot((keccak256 uint256 0, (arg #0))) ty:uint256 = %storage.temp.19
+// CHECK: return
+        // nothing is written when reverting
+// CHECK: block2: # doassert
+// CHECK: assert-failure: buffer: (alloc slice bytes1 uint32 17
+    }
+
+// BEGIN-CHECK: cache::function::sum
+    function sum(uint64[] memory arr) public {
+        // count is loaded before the loop, and written after it
+        for (uint i = 0; i < arr.length; i++) {
+            count += arr[i];
+        }
+// CHECK: %storage.temp.24 = load storage slot(uint256 1) ty:uint64
+// CHECK: branch block2
+// CHECK: block2: # cond
+// CHECK: # phis: i,storage.temp.24
+// CHECK: block4: # endfor
+// CHECK: store storage slot(uint256 1) ty:uint64 = %storage.temp.24
+// CHECK: return
+// CHECK: block6: # in_bounds
+// CHECK: ty:uint64 %storage.temp.24 = %temp.22
+// CHECK: branch block3
+    }
+
+// BEGIN-CHECK: cache::function::call
+    function call(other o) public {
+        // the other contract may call us and read total
+        total += 1;
+        o.ping();
+        total += 2;
+// CHECK: ty:uint256 %storage.temp.31 = %temp.26
+// CHECK: store storage slot(uint256 2) ty:uint256 = %storage.temp.31
+// CHECK: external call::regular
+// CHECK: %storage.temp.31 = load storage slot(uint256 2) ty:uint256
+// CHECK: store storage slot(uint256 2) ty:uint256 = %storage.temp.31
+    }
+
+// BEGIN-CHECK: cache::function::add
+    function add() public {
+        // the two increments only need a single load and store
+        total += 1;
+        total += 2;
+// CHECK: %storage.temp.36 = load storage slot(uint256 2) ty:uint256
+// CHECK: ty:uint256 %temp.34 = %storage.temp.36
+// CHECK-ABSENT: load storage slot(uint256 2) ty:uint256
+// CHECK: store storage slot(uint256 2) ty:uint256 = %storage.temp.36
+// CHECK: return
+    }
+}
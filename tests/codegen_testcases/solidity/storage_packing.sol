@@ -0,0 +1,57 @@
+// RUN: --target polkadot --emit cfg --pack-storage --no-storage-cache
+enum State { Active, Paused }
+
+contract packed {
+    bool paused;
+    uint8 decimals = 18;
+    int16 delta;
+    State state;
+    uint256 total;
+    // alone in its slot, so not packed
+    uint64 last;
+
+// BEGIN-CHECK: packed::function::pause
+    function pause() public {
+        paused = true;
+// CHECK: = load storage slot(uint256 0) ty:uint64
+// CHECK: & uint64 18446744073709551360) | uint64 1)
+        state = State.Paused;
+// CHECK: store storage slot(uint256 0) ty:uint64 = ((%temp.10 & uint64 18446742978492891135) | uint64 4294967296)
+    }
+
+// BEGIN-CHECK: packed::function::get
+    function get() public view returns (bool, uint8, int16, State) {
+// CHECK: load storage slot(uint256 0) ty:uint64
+// CHECK: return ((trunc uint8 %temp.11) != uint8 0), (trunc uint8 (%temp.12 >> uint64 8)), (trunc int16 (%temp.13 >> uint64 16)), (trunc enum State (%temp.14 >> uint64 32))
+        return (paused, decimals, delta, state);
+    }
+
+// BEGIN-CHECK: packed::function::dec
+    function dec() public {
+        // negative values must not overwrite the other variables in the slot
+        delta--;
+// CHECK: ty:int16 %temp.15 = (trunc int16 (%temp.16 >> uint64 16))
+// CHECK: store storage slot(uint256 0) ty:uint64 = ((%temp.18 & uint64 18446744069414649855) | (((zext uint64 %temp.17) & uint64 65535) << uint64 16))
+        delete decimals;
+// CHECK: & uint64 18446744073709486335) | uint64 0)
+    }
+
+// BEGIN-CHECK: packed::function::set_total
+    function set_total(uint256 t, uint64 l) public {
+        total = t;
+        last = l;
+// CHECK: store storage slot(uint256 1) ty:uint256 = (arg #0)
+// CHECK: store storage slot(uint256 2) ty:uint64 = (arg #1)
+    }
+
+// BEGIN-CHECK: packed::function::offset
+    function offset() public pure returns (uint r) {
+        assembly {
+            r := delta.offset
+        }
+// CHECK: return uint256 2
+    }
+
+// BEGIN-CHECK: storage_initializer
+// CHECK: & uint64 18446744073709486335) | uint64 4608)
+}
@@ -1,4 +1,4 @@
-// RUN: --target solana --emit cfg
+// RUN: --target solana --emit cfg --no-dead-function-elimination
 
 contract foo {
     struct S { int f1; }
@@ -1,4 +1,4 @@
-// RUN: --target polkadot --emit cfg
+// RUN: --target polkadot --emit cfg --no-loop-optimization
 contract test {
 /******************/
 /* Multiply tests */
@@ -10,7 +10,8 @@ contract test {
             // this multiply can be done with a 64 bit instruction
             print("i:{}".format(i * 100));
         }
-// CHECK: zext uint256 ((trunc uint64 %i) * uint64 100)
+// CHECK: zext uint256 (overflowing (trunc uint64 %i) * uint64 100)
+// CHECK: ty:uint256 %i = (overflowing %temp.
     }
 
 // BEGIN-CHECK: test::function::f2
@@ -37,7 +38,7 @@ contract test {
             if (x)
                 break;
         }
-// CHECK: (zext uint256 ((trunc uint64 (%i & uint256 255)) * uint64 100))
+// CHECK: (zext uint256 (overflowing (trunc uint64 (%i & uint256 255)) * uint64 100))
     }
 
 // BEGIN-CHECK: test::function::f4
@@ -50,10 +51,10 @@ contract test {
         }
 // CHECK: (%i << uint256 15)
         for (uint i = 0; i < 10; i++) {
-            // Do not disable overflow checks
+            // this cannot overflow, so the overflow check is removed
             print("i:{}".format(i * 32768));
         }
-// NOT CHECK: (%i << uint256 15)
+// CHECK: (%i.6 << uint256 15)
     }
 
 // BEGIN-CHECK: test::function::f5
@@ -87,11 +88,11 @@ contract test {
 
 // BEGIN-CHECK: test::function::f8
     function f8() pure public {
-        // too many values to track; (101 values)
+        // too many values to track; (101 values), but the loop condition bounds i
         for (uint i = 1e9; i < 1e9+101; i++) {
             print("i:{}".format(i / 1e6));
         }
-// CHECK: (unsigned divide %i / uint256 1000000)
+// CHECK: (zext uint256 (unsigned divide (trunc uint64 %i) / uint64 1000000))
     }
 
 
@@ -123,4 +124,55 @@ contract test {
         }
                 // CHECK: (signed modulo %i % int256 4294967297)
     }
+
+/*******************************/
+/* Overflow check elimination */
+/*******************************/
+
+// BEGIN-CHECK: test::function::f12
+    function f12(uint8 a, uint16 b) pure public returns (uint256) {
+        // the sum of small values can never overflow
+        return uint256(a) + uint256(b) * 3;
+// CHECK: return (overflowing (zext uint256 (arg #0)) + (zext uint256 (overflowing (trunc uint64 (zext uint256 (arg #1))) * uint64 3)))
+    }
+
+// BEGIN-CHECK: test::function::f13
+    function f13(uint64[] memory arr) pure public returns (uint64 sum) {
+        // the loop counter is bounded by the array length, so it cannot overflow
+        for (uint i = 0; i < arr.length; i++) {
+            sum += arr[i];
+        }
+// CHECK: ty:uint256 %i = (overflowing %temp.40 + uint256 1)
+    }
+
+// BEGIN-CHECK: test::function::f14
+    function f14(uint8 a) pure public returns (uint8) {
+        // this can overflow
+        return a + 1;
+// CHECK: return ((arg #0) + uint8 1)
+    }
+
+/***************************/
+/* Bounds check elimination */
+/***************************/
+
+// BEGIN-CHECK: test::function::f15
+    function f15(uint64[10] memory arr) pure public returns (uint64 sum) {
+        // the index is always less than the length of the array
+        for (uint i = 0; i < 10; i++) {
+            sum += arr[i];
+        }
+// CHECK: ty:uint256 %index.temp.41 = %i
+// CHECK: branch block6
+// CHECK: block6: # in_bounds
+    }
+
+// BEGIN-CHECK: test::function::f16
+    function f16(uint64[10] memory arr, uint8 n) pure public returns (uint64 sum) {
+        // the index may be out of bounds
+        for (uint i = 0; i < n; i++) {
+            sum += arr[i];
+        }
+// CHECK: branchcond (unsigned %index.temp.
+    }
 }
@@ -1,4 +1,4 @@
-// RUN: --target solana --emit cfg
+// RUN: --target solana --emit cfg --no-inline --no-loop-optimization
 
 contract testing  {
     // BEGIN-CHECK: testing::testing::function::general_test__uint64
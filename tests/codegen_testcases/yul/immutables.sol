@@ -0,0 +1,37 @@
+// RUN: --target polkadot --emit cfg
+contract bar {
+    function f() public {}
+}
+
+contract foo {
+    uint64 immutable a;
+    bytes32 immutable b;
+
+// BEGIN-CHECK: foo::foo::constructor::780cecb0
+    constructor(uint64 x) {
+        assembly {
+            // CHECK: store storage slot(uint256 0) ty:uint64 = (arg #0)
+            setimmutable(0, "a", x)
+            // CHECK: store storage slot(uint256 1) ty:bytes32 = bytes32 128
+            setimmutable(0, "b", memoryguard(0x80))
+        }
+    }
+
+// BEGIN-CHECK: foo::foo::function::get
+    function get() public view returns (uint64 r, uint256 s) {
+        assembly {
+            // CHECK: = load storage slot(uint256 0) ty:uint64
+            r := add(a, loadimmutable("a"))
+            // CHECK: = load storage slot(uint256 1) ty:bytes32
+            s := b
+        }
+    }
+
+// BEGIN-CHECK: foo::foo::function::size
+    function size() public pure returns (uint256 r) {
+        assembly {
+            // CHECK: ty:uint256 %r = uint256 
+            r := datasize("bar")
+        }
+    }
+}
@@ -1,4 +1,4 @@
-// RUN: --target solana --emit cfg -Onone --no-cse
+// RUN: --target solana --emit cfg -Onone --no-cse --no-loop-optimization
 
 contract testing {
 
@@ -135,7 +135,7 @@ contract testing {
                 let i := 1
             // CHECK: branch block1
             // CHECK: block1: # cond
-            // CHECK: branchcond (uint256 0 != (overflowing %i + uint256 1)), block3, block4
+            // CHECK: branch block3
             } add(i, 1) {
                 // CHECK: block2: # next
                 // CHECK: ty:uint256 %i = (overflowing %i - uint256 1)
@@ -319,7 +319,7 @@ contract testing {
                 // CHECK: ty:uint256 %i = uint256 1
                 let i := 1
                 // CHECK: branch block1
-                // CHECK: branchcond (unsigned less %i < uint256 10), block3, block4
+                // CHECK: branch block3
             } lt(i, 10) {
                 // CHECK: block2: # next
                 i := add(i, 1)
@@ -338,14 +338,14 @@ contract testing {
 
                     // inner for condition
                     // CHECK: block5: # cond
-                    // CHECK: branchcond (unsigned less %j < uint256 10), block7, block8
+                    // CHECK: branch block7
                     // CHECK: block6: # next
                     // CHECK: ty:uint256 %j = (overflowing %j + uint256 1)
                     // CHECK: branch block5
                     j := add(j, 1)
                 } {
                     // CHECK: block7: # body
-                    // CHECK: branchcond (unsigned more %j > uint256 5), block9, block10
+                    // CHECK: branch block10
                     if gt(j, 5) {
                         break
                     }
@@ -417,7 +417,7 @@ contract testing {
                 // CHECK: ty:uint256 %i = uint256 1
                 let i := 1
                 // CHECK: branch block1
-                // CHECK: branchcond (unsigned less %i < uint256 10), block3, block4
+                // CHECK: branch block3
             } lt(i, 10) {
                 // CHECK: block2: # next
                 i := add(i, 1)
@@ -436,14 +436,14 @@ contract testing {
 
                     // inner for condition
                     // CHECK: block5: # cond
-                    // CHECK: branchcond (unsigned less %j < uint256 10), block7, block8
+                    // CHECK: branch block7
                     // CHECK: block6: # next
                     // CHECK: ty:uint256 %j = (overflowing %j + uint256 1)
                     // CHECK: branch block5
                     j := add(j, 1)
                 } {
                     // CHECK: block7: # body
-                    // CHECK: branchcond (unsigned more %j > uint256 5), block9, block10
+                    // CHECK: branch block10
                     if gt(j, 5) {
                         continue
                     }
@@ -107,7 +107,15 @@ fn parse_file(path: PathBuf, target: Target) -> io::Result<()> {
                     }
                 };
 
-                let _ = generate_abi(contract_no, &ns, &code, false, &["unknown".into()], "0.1.0");
+                let _ = generate_abi(
+                    contract_no,
+                    &ns,
+                    &code,
+                    false,
+                    &["unknown".into()],
+                    "0.1.0",
+                    Default::default(),
+                );
             }
         }
     }
@@ -6,6 +6,9 @@ import {a as b} from x;
 
 // ---- Expect: diagnostics ----
 // error: 1:1-29: experimental solidity features are not supported
-// error: 3:8-16: experimental import paths not supported
-// error: 4:22-25: experimental import paths not supported
-// error: 5:22-23: experimental import paths not supported
+// error: 3:8-16: file not found 'std/stub.sol'
+// 	note 3:8-16: file 'std/stub.sol' does not exist
+// error: 4:22-25: file not found 'a/b.sol'
+// 	note 4:22-25: file 'a/b.sol' does not exist
+// error: 5:22-23: file not found 'x.sol'
+// 	note 5:22-23: file 'x.sol' does not exist
@@ -10,5 +10,5 @@ contract c {
 
 // ---- Expect: diagnostics ----
 // error: 1:19-23: 'LEN1' not found
-// error: 2:19-24: cannot call function in constant expression
+// error: 2:19-22: unknown function or type 'foo'
 // error: 5:20-24: 'LEN1' not found
@@ -0,0 +1,15 @@
+struct S {
+    function(uint64) internal returns (uint64) f;
+    uint64 a;
+}
+
+contract C {
+    mapping(uint64 => function(uint64) internal returns (uint64)) public byId;
+    S public s;
+    mapping(uint64 => function(uint64) internal returns (uint64)[]) public nested;
+    mapping(uint64 => function(uint64) external returns (uint64)) public ext;
+}
+// ---- Expect: diagnostics ----
+// error: 7:5-66: variable of type internal function cannot be 'public'
+// error: 8:5-6: variable of type internal function cannot be 'public'
+// error: 9:5-68: variable of type internal function cannot be 'public'
@@ -0,0 +1,52 @@
+uint constant BASE = 0x1000;
+
+abstract contract A layout at 1 {
+    uint x;
+}
+
+library L layout at 2 {}
+
+interface I layout at 3 {}
+
+contract B layout at BASE * 2 {
+    uint y;
+
+    function get() public view returns (uint) {
+        return y;
+    }
+}
+
+contract C is B {
+    function set() public {
+        y = 2;
+    }
+}
+
+contract D layout at ("slot") {
+    uint z;
+
+    function get() public view returns (uint) {
+        return z;
+    }
+}
+
+contract E layout at 2**256 {
+    uint z;
+
+    function get() public view returns (uint) {
+        return z;
+    }
+}
+
+contract F layout at BASE - 0x2000 is B {}
+// ---- Expect: diagnostics ----
+// error: 3:21-32: storage layout can only be specified for contracts, not for abstract contract
+// error: 7:11-22: storage layout can only be specified for contracts, not for library
+// error: 9:13-24: storage layout can only be specified for contracts, not for interface
+// error: 19:10-11: contract 'C' inherits from 'B' which specifies a storage layout; only the most derived contract may specify one
+// 	note 11:12-30: storage layout of 'B' specified here
+// error: 25:22-30: storage layout base must be an integer
+// error: 33:22-28: storage layout base 115792089237316195423570985008687907853269984665640564039457584007913129639936 does not fit into 'uint256'
+// error: 41:10-11: contract 'F' inherits from 'B' which specifies a storage layout; only the most derived contract may specify one
+// 	note 11:12-30: storage layout of 'B' specified here
+// error: 41:22-35: storage layout base -4096 does not fit into 'uint256'
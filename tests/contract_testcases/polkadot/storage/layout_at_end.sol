@@ -0,0 +1,11 @@
+contract C layout at 2**256 - 2 {
+    uint a;
+    uint b;
+    uint c;
+
+    function get() public view returns (uint) {
+        return a + b + c;
+    }
+}
+// ---- Expect: diagnostics ----
+// error: 1:12-32: contract storage extends past the end of storage with this layout base
@@ -0,0 +1,11 @@
+contract C layout at 2**32 - 2 {
+    uint a;
+    uint b;
+    uint c;
+
+    function get() public view returns (uint) {
+        return a + b + c;
+    }
+}
+// ---- Expect: diagnostics ----
+// error: 1:12-31: contract storage does not fit in the 32 bit storage keys of the metadata with this layout base
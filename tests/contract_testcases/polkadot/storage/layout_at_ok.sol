@@ -0,0 +1,10 @@
+contract Proxy layout at 0x1000 {
+    address implementation;
+    uint128 counter;
+
+    function get() public view returns (address) {
+        return implementation;
+    }
+}
+// ---- Expect: diagnostics ----
+// warning: 3:5-20: storage variable 'counter' has never been used
@@ -8,6 +8,4 @@
 
         abstract contract b {}
 // ---- Expect: diagnostics ----
-// warning: 6:30-31: function parameter 'x' is unused
-// warning: 6:58-59: return variable 'a' has never been assigned
-// warning: 6:66-67: return variable 'k' has never been assigned
+// error: 5:17-30: base contract 'b' in tag '@inheritdoc' has no function 'foo(int256)' which is overridden
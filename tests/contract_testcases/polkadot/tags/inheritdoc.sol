@@ -15,4 +15,5 @@ contract c is b {
 }
 
 // ---- Expect: diagnostics ----
-// error: 12:21-22: base contract 'x' not found in tag '@inheritdoc'
\ No newline at end of file
+// error: 9:9-22: base contract 'a' in tag '@inheritdoc' has no function 'v1' which is overridden
+// error: 12:21-22: base contract 'x' not found in tag '@inheritdoc'
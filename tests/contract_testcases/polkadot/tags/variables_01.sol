@@ -8,4 +8,4 @@
 
         abstract contract b {}
 // ---- Expect: diagnostics ----
-// warning: 6:13-18: storage variable 'y' has never been used
+// error: 5:17-30: base contract 'b' in tag '@inheritdoc' has no function 'y' which is overridden
@@ -0,0 +1,25 @@
+abstract contract base {
+    function width() internal pure returns (uint) {
+        return 2;
+    }
+}
+
+contract c is base {
+    uint constant N = width();
+    uint constant M = size();
+    uint64 first;
+    bool[N] public flags;
+    uint16[M][N] grid;
+    uint64 last;
+
+    function size() internal pure returns (uint) {
+        return 3;
+    }
+
+    function get() public view returns (uint, uint, uint64, uint64) {
+        return (flags.length, grid[1].length, first, last);
+    }
+}
+// ---- Expect: diagnostics ----
+// warning: 8:5-30: storage variable 'N' has been assigned, but never read
+// warning: 9:5-29: storage variable 'M' has been assigned, but never read
@@ -0,0 +1,64 @@
+function add10(uint8 x) pure returns (uint8) {
+    return x + 10;
+}
+
+function forever() pure returns (uint r) {
+    while (true) {
+        r++;
+    }
+}
+
+function fails() pure returns (uint) {
+    revert("not a constant");
+}
+
+function check(uint x) pure returns (uint) {
+    require(x > 1, "too small");
+    return x;
+}
+
+function inverse(uint x) pure returns (uint) {
+    return 1 / x;
+}
+
+function height() view returns (uint) {
+    return block.number;
+}
+
+function element(uint i) pure returns (uint) {
+    uint[2] memory a;
+    return a[i];
+}
+
+uint8 constant A = add10(250);
+uint constant B = forever();
+uint constant C = fails();
+uint constant D = check(0);
+uint constant E = inverse(0);
+uint constant F = height();
+uint constant G = element(2);
+
+contract c {
+    bool[add10(250)] flags;
+
+    function get() public pure returns (uint) {
+        return A + B + C + D + E + F + G;
+    }
+}
+// ---- Expect: diagnostics ----
+// error: 33:20-30: expression cannot be evaluated at compile time
+// 	note 2:12-18: arithmetic overflow, value 260 does not fit into type uint8
+// error: 34:19-28: expression cannot be evaluated at compile time
+// 	note 6:12-16: evaluation exceeds the limit of 1000000 steps
+// error: 35:19-26: expression cannot be evaluated at compile time
+// 	note 12:5-29: revert: not a constant
+// error: 36:19-27: expression cannot be evaluated at compile time
+// 	note 16:5-12: require condition failed: too small
+// error: 37:19-29: expression cannot be evaluated at compile time
+// 	note 21:12-17: division by zero
+// error: 38:19-27: cannot call function in constant expression
+// 	note 24:1-38: function 'height' is not declared pure
+// error: 39:19-29: expression cannot be evaluated at compile time
+// 	note 30:12-16: array index 2 out of bounds for length 2
+// error: 42:10-20: expression cannot be evaluated at compile time
+// 	note 2:12-18: arithmetic overflow, value 260 does not fit into type uint8
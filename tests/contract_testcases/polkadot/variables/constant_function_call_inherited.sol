@@ -0,0 +1,14 @@
+contract b {
+    function f() internal pure returns (uint) {
+        return 7;
+    }
+
+    function g() public pure returns (uint) {
+        return f();
+    }
+}
+
+contract c is b {
+    uint public constant X = f() + 1;
+}
+// ---- Expect: diagnostics ----
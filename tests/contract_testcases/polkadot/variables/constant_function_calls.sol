@@ -0,0 +1,42 @@
+struct Point {
+    uint8 x;
+    int16 y;
+}
+
+function mask(uint n) pure returns (uint) {
+    return (1 << n) - 1;
+}
+
+function origin() pure returns (Point memory p) {
+    p.x = 250;
+    p.y = -3;
+}
+
+function greet(string memory name) pure returns (string memory) {
+    return string.concat("hello, ", name);
+}
+
+library Math {
+    function double(uint x) internal pure returns (uint) {
+        return x * 2;
+    }
+}
+
+uint constant MASK = mask(8);
+Point constant ORIGIN = origin();
+string constant GREETING = greet("world");
+
+contract c {
+    uint constant WIDTH = width() + Math.double(MASK);
+    bytes32 constant ROLE = keccak256(bytes(GREETING));
+    bool[mask(2)] flags;
+
+    function width() internal pure returns (uint) {
+        return 4;
+    }
+
+    function get() public view returns (uint, int16, string memory, uint, bytes32, uint) {
+        return (ORIGIN.x, ORIGIN.y, GREETING, WIDTH, ROLE, flags.length);
+    }
+}
+// ---- Expect: diagnostics ----
@@ -5,4 +5,4 @@ contract test {
             uint constant y = foo() + 5;
         }
 // ---- Expect: diagnostics ----
-// error: 5:31-36: cannot call function in constant expression
+// warning: 5:13-40: storage variable 'y' has been assigned, but never read
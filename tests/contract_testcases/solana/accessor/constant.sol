@@ -3,4 +3,4 @@
             bytes32 public constant z = blockhash(1);
         }
 // ---- Expect: diagnostics ----
-// error: 3:41-53: cannot call function in constant expression
+// error: 3:41-50: unknown function or type 'blockhash'
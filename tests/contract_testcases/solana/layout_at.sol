@@ -0,0 +1,9 @@
+contract C layout at 100 {
+    uint64 a;
+
+    function get() public view returns (uint64) {
+        return a;
+    }
+}
+// ---- Expect: diagnostics ----
+// error: 1:12-25: storage layout base is not supported on Solana
@@ -13,6 +13,7 @@ use tiny_keccak::{Hasher, Keccak};
 use wasmi::core::{HostError, Trap, TrapCode};
 use wasmi::{Engine, Error, Instance, Linker, Memory, MemoryType, Module, Store};
 
+use solang::abi::polkadot::MetadataVersion;
 use solang::codegen::Options;
 use solang::file_resolver::FileResolver;
 use solang::{compile, Target};
@@ -1043,7 +1044,17 @@ pub fn build_solidity(src: &str) -> MockSubstrate {
 /// * log_ret: enable logging of host function return codes
 /// * log_err: enable logging of runtime errors
 pub fn build_solidity_with_options(src: &str, log_err: bool) -> MockSubstrate {
-    let blobs = build_wasm(src, log_err)
+    build_solidity_with_metadata_version(src, log_err, MetadataVersion::V4)
+}
+
+/// A variant of `MockSubstrate::build_solidity()` which emits events as described by the given
+/// version of the ink! metadata
+pub fn build_solidity_with_metadata_version(
+    src: &str,
+    log_err: bool,
+    metadata_version: MetadataVersion,
+) -> MockSubstrate {
+    let blobs = build_wasm_with_metadata_version(src, log_err, metadata_version)
         .iter()
         .map(|(code, abi)| WasmCode::new(abi, code))
         .collect();
@@ -1052,6 +1063,14 @@ pub fn build_solidity_with_options(src: &str, log_err: bool) -> MockSubstrate {
 }
 
 pub fn build_wasm(src: &str, log_err: bool) -> Vec<(Vec<u8>, String)> {
+    build_wasm_with_metadata_version(src, log_err, MetadataVersion::V4)
+}
+
+fn build_wasm_with_metadata_version(
+    src: &str,
+    log_err: bool,
+    metadata_version: MetadataVersion,
+) -> Vec<(Vec<u8>, String)> {
     let tmp_file = OsStr::new("test.sol");
     let mut cache = FileResolver::default();
     cache.set_file_contents(tmp_file.to_str().unwrap(), src.to_string());
@@ -1065,6 +1084,7 @@ pub fn build_wasm(src: &str, log_err: bool) -> Vec<(Vec<u8>, String)> {
             opt_level: opt.into(),
             log_runtime_errors: log_err,
             log_prints: true,
+            metadata_version,
             #[cfg(feature = "wasm_opt")]
             wasm_opt: Some(contract_build::OptimizationPasses::Z),
             ..Default::default()
@@ -1079,5 +1099,8 @@ pub fn build_wasm(src: &str, log_err: bool) -> Vec<(Vec<u8>, String)> {
 
 pub fn load_abi(s: &str) -> InkProject {
     let bundle = serde_json::from_str::<ContractMetadata>(s).unwrap();
-    serde_json::from_value::<InkProject>(serde_json::to_value(bundle.abi).unwrap()).unwrap()
+    let mut abi = serde_json::to_value(bundle.abi).unwrap();
+    // ink_metadata only loads version 4; the messages and constructors are the same in version 5
+    abi["version"] = "4".into();
+    serde_json::from_value::<InkProject>(abi).unwrap()
 }
@@ -1,13 +1,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::build_solidity;
+use crate::{build_solidity, build_solidity_with_metadata_version};
 use ink_env::{
     hash::{Blake2x256, CryptoHash},
     topics::PrefixedValue,
 };
 use ink_primitives::{AccountId, Hash};
 use parity_scale_codec::Encode;
-use solang::{file_resolver::FileResolver, Target};
+use solang::{abi::polkadot::MetadataVersion, file_resolver::FileResolver, Target};
 use std::ffi::OsStr;
 
 fn topic_hash(encoded: &[u8]) -> Hash {
@@ -20,6 +20,13 @@ fn topic_hash(encoded: &[u8]) -> Hash {
     buf.into()
 }
 
+/// The signature topic of an event with ink! v5 metadata
+fn signature_topic(signature: &str) -> Hash {
+    let mut buf = [0; 32];
+    <Blake2x256 as CryptoHash>::hash(signature.as_bytes(), &mut buf);
+    buf.into()
+}
+
 #[test]
 fn anonymous() {
     let mut runtime = build_solidity(
@@ -93,6 +100,57 @@ fn emit() {
     );
 }
 
+#[test]
+fn emit_ink_v5() {
+    let mut runtime = build_solidity_with_metadata_version(
+        r#"
+        contract a {
+            event foo(bool,uint32,int64 indexed i);
+            event bar(uint32,uint64,string indexed s);
+            event baz(bool b) anonymous;
+            function emit_event() public {
+                emit foo(true, 102, 1);
+                emit bar(0xdeadcafe, 102, "foobar");
+                emit baz(true);
+            }
+        }"#,
+        true,
+        MetadataVersion::V5,
+    );
+
+    runtime.constructor(0, Vec::new());
+    runtime.function("emit_event", Vec::new());
+
+    assert_eq!(runtime.events().len(), 3);
+
+    // the signature topic replaces the topic with the contract and event name, the index of the
+    // event is not part of the data, and the topics of the fields are not prefixed
+    let event = &runtime.events()[0];
+    assert_eq!(event.topics.len(), 2);
+    assert_eq!(event.topics[0], signature_topic("foo(bool,uint32,int64)"));
+    assert_eq!(event.topics[1], topic_hash(&1i64.encode()));
+    assert_eq!(event.data, (true, 102u32, 1i64).encode());
+
+    let event = &runtime.events()[1];
+    assert_eq!(event.topics.len(), 2);
+    assert_eq!(
+        event.topics[0],
+        signature_topic("bar(uint32,uint64,string)")
+    );
+    assert_eq!(
+        event.topics[1],
+        topic_hash(&String::from("foobar").encode())
+    );
+    assert_eq!(
+        event.data,
+        (0xdeadcafeu32, 102u64, String::from("foobar")).encode()
+    );
+
+    let event = &runtime.events()[2];
+    assert_eq!(event.topics.len(), 0);
+    assert_eq!(event.data, true.encode());
+}
+
 #[test]
 fn event_imported() {
     let mut cache = FileResolver::default();
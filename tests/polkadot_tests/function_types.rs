@@ -376,3 +376,56 @@ fn encode_decode_ext_func() {
     runtime.function("decode_call", address_of_a);
     assert_eq!(runtime.output(), 127u8.encode());
 }
+
+#[test]
+fn struct_fields_and_arrays() {
+    let mut runtime = build_solidity(
+        r##"
+        struct Op {
+            function(uint64, uint64) internal pure returns (uint64) f;
+            uint64 arg;
+        }
+
+        contract C {
+            Op op;
+            function(uint64, uint64) internal pure returns (uint64)[] fs;
+            function(uint64, uint64) internal pure returns (uint64)[2] fixedfs;
+
+            function add(uint64 a, uint64 b) internal pure returns (uint64) {
+                return a + b;
+            }
+
+            function mul(uint64 a, uint64 b) internal pure returns (uint64) {
+                return a * b;
+            }
+
+            function setup() public {
+                op = Op(add, 3);
+                fs.push(add);
+                fs.push(mul);
+                fixedfs[0] = mul;
+                fixedfs[1] = add;
+            }
+
+            function test(uint64 x) public view returns (uint64) {
+                Op memory m = Op({f: mul, arg: 2});
+                Op memory copy = op;
+                function(uint64, uint64) internal pure returns (uint64)[] memory local =
+                    new function(uint64, uint64) internal pure returns (uint64)[](2);
+                local[0] = add;
+                local[1] = op.f;
+
+                uint64 r = op.f(x, op.arg) + m.f(x, m.arg) + copy.f(x, copy.arg);
+                for (uint i = 0; i < fs.length; i++) {
+                    r += fs[i](x, 1) + fixedfs[i](x, 2) + local[i](x, 3);
+                }
+                return r;
+            }
+        }"##,
+    );
+
+    runtime.function("setup", Vec::new());
+    runtime.function("test", 5u64.encode());
+
+    assert_eq!(runtime.output(), 70u64.encode());
+}
@@ -398,3 +398,80 @@ fn test_address() {
         assert_eq!(runtime.output(), Val(val.1).encode());
     }
 }
+
+#[test]
+fn mapping_storage_references() {
+    let mut runtime = build_solidity(
+        r##"
+        struct Inner {
+            uint64 v;
+            mapping(uint64 => uint64) m;
+        }
+
+        struct Outer {
+            mapping(uint64 => Inner) inner;
+            uint64 count;
+        }
+
+        library Lib {
+            function get(mapping(uint64 => uint64) storage m, uint64 k) internal view returns (uint64) {
+                return m[k];
+            }
+
+            function set(mapping(uint64 => Inner) storage m, uint64 k, uint64 v) internal {
+                m[k].v = v;
+                m[k].m[k] = v + 1;
+            }
+
+            function pick(Outer storage o) internal view returns (mapping(uint64 => Inner) storage) {
+                return o.inner;
+            }
+        }
+
+        contract c {
+            using Lib for mapping(uint64 => uint64);
+
+            mapping(uint64 => uint64) a;
+            mapping(uint64 => uint64) b;
+            Outer outer;
+
+            function sel(bool first) internal view returns (mapping(uint64 => uint64) storage) {
+                return first ? a : b;
+            }
+
+            function sel2(bool first) internal view returns (mapping(uint64 => uint64) storage r) {
+                if (first) {
+                    r = a;
+                } else {
+                    r = b;
+                }
+            }
+
+            function write(bool first, uint64 k, uint64 v) public {
+                mapping(uint64 => uint64) storage m = sel(first);
+                m[k] = v;
+                Lib.set(outer.inner, k, v);
+                outer.count += 1;
+            }
+
+            function read(bool first, uint64 k) public view returns (uint64, uint64, uint64, uint64) {
+                return (sel(first).get(k), sel2(first)[k], Lib.pick(outer)[k].v, Lib.pick(outer)[k].m[k]);
+            }
+        }"##,
+    );
+
+    runtime.function("write", (true, 1u64, 100u64).encode());
+    runtime.function("write", (false, 2u64, 200u64).encode());
+
+    runtime.function("read", (true, 1u64).encode());
+    assert_eq!(runtime.output(), (100u64, 100u64, 100u64, 101u64).encode());
+
+    runtime.function("read", (false, 1u64).encode());
+    assert_eq!(runtime.output(), (0u64, 0u64, 100u64, 101u64).encode());
+
+    runtime.function("read", (false, 2u64).encode());
+    assert_eq!(runtime.output(), (200u64, 200u64, 200u64, 201u64).encode());
+
+    runtime.function("read", (true, 2u64).encode());
+    assert_eq!(runtime.output(), (0u64, 0u64, 200u64, 201u64).encode());
+}
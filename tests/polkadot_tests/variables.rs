@@ -50,6 +50,48 @@ fn global_constants() {
     runtime.function("test", 0u64.encode());
 }
 
+#[test]
+fn constants_calling_pure_functions() {
+    let mut runtime = build_solidity(
+        r##"
+        function mask(uint n) pure returns (uint) {
+            return (1 << n) - 1;
+        }
+
+        function fib(uint n) pure returns (uint r) {
+            uint x = 0;
+            uint y = 1;
+            for (uint i = 0; i < n; i++) {
+                (x, y) = (y, x + y);
+            }
+            r = x;
+        }
+
+        uint constant MASK = mask(8);
+
+        contract a {
+            uint64 constant FIB = uint64(fib(20)) + width();
+            string constant NAME = string.concat("fib", "20");
+            int8[mask(2)] values;
+
+            function width() internal pure returns (uint64) {
+                return 1;
+            }
+
+            function test() public view {
+                assert(MASK == 255);
+                assert(FIB == 6766);
+                assert(keccak256(bytes(NAME)) == keccak256("fib20"));
+                assert(values.length == 3);
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.function("test", Vec::new());
+}
+
 #[test]
 fn ensure_unread_storage_vars_write() {
     let mut runtime = build_solidity(
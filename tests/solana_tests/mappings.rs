@@ -1200,3 +1200,101 @@ function getArrAmt() public view returns (uint) {
         }
     );
 }
+
+#[test]
+fn mapping_storage_references() {
+    let mut vm = build_solidity(
+        r#"
+        struct Inner {
+            uint64 v;
+            mapping(uint64 => uint64) m;
+        }
+
+        struct Outer {
+            mapping(uint64 => Inner) inner;
+            uint64 count;
+        }
+
+        library Lib {
+            function get(mapping(uint64 => uint64) storage m, uint64 k) internal view returns (uint64) {
+                return m[k];
+            }
+
+            function set(mapping(uint64 => Inner) storage m, uint64 k, uint64 v) internal {
+                m[k].v = v;
+                m[k].m[k] = v + 1;
+            }
+
+            function pick(Outer storage o) internal view returns (mapping(uint64 => Inner) storage) {
+                return o.inner;
+            }
+        }
+
+        contract c {
+            using Lib for mapping(uint64 => uint64);
+
+            mapping(uint64 => uint64) a;
+            mapping(uint64 => uint64) b;
+            Outer outer;
+
+            function sel(bool first) internal view returns (mapping(uint64 => uint64) storage) {
+                return first ? a : b;
+            }
+
+            function sel2(bool first) internal view returns (mapping(uint64 => uint64) storage r) {
+                if (first) {
+                    r = a;
+                } else {
+                    r = b;
+                }
+            }
+
+            function write(bool first, uint64 k, uint64 v) public {
+                mapping(uint64 => uint64) storage m = sel(first);
+                m[k] = v;
+                Lib.set(outer.inner, k, v);
+                outer.count += 1;
+            }
+
+            function read(bool first, uint64 k) public view returns (uint64, uint64, uint64, uint64) {
+                return (sel(first).get(k), sel2(first)[k], Lib.pick(outer)[k].v, Lib.pick(outer)[k].m[k]);
+            }
+        }"#,
+    );
+
+    let data_account = vm.initialize_data_account();
+    vm.function("new")
+        .accounts(vec![("dataAccount", data_account)])
+        .call();
+
+    let uint64 = |value: u64| BorshToken::Uint {
+        width: 64,
+        value: BigInt::from(value),
+    };
+
+    for (first, k, v) in [(true, 1, 100), (false, 2, 200)] {
+        vm.function("write")
+            .arguments(&[BorshToken::Bool(first), uint64(k), uint64(v)])
+            .accounts(vec![("dataAccount", data_account)])
+            .call();
+    }
+
+    for (first, k, expected) in [
+        (true, 1, [100, 100, 100, 101]),
+        (false, 1, [0, 0, 100, 101]),
+        (false, 2, [200, 200, 200, 201]),
+        (true, 2, [0, 0, 200, 201]),
+    ] {
+        let returns = vm
+            .function("read")
+            .arguments(&[BorshToken::Bool(first), uint64(k)])
+            .accounts(vec![("dataAccount", data_account)])
+            .call()
+            .unwrap();
+
+        assert_eq!(
+            returns,
+            BorshToken::Tuple(expected.into_iter().map(uint64).collect())
+        );
+    }
+}
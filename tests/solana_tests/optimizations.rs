@@ -29,6 +29,10 @@ static NO_OPTIMIZATIONS: Lazy<Options> = Lazy::new(|| Options {
     strength_reduce: false,
     vector_to_slice: false,
     common_subexpression_elimination: false,
+    inline: false,
+    storage_cache: false,
+    loop_optimization: false,
+    dead_function_elimination: false,
     ..Default::default()
 });
 
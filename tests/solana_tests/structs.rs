@@ -78,3 +78,65 @@ fn struct_as_reference() {
         ])
     );
 }
+
+#[test]
+fn struct_function_field() {
+    let mut vm = build_solidity(
+        r#"
+        struct Op {
+            function(uint64, uint64) internal pure returns (uint64) f;
+            uint64 arg;
+        }
+
+        contract C {
+            Op op;
+            function(uint64, uint64) internal pure returns (uint64)[] fs;
+
+            function add(uint64 a, uint64 b) internal pure returns (uint64) {
+                return a + b;
+            }
+
+            function mul(uint64 a, uint64 b) internal pure returns (uint64) {
+                return a * b;
+            }
+
+            function setup() public {
+                op = Op(mul, 3);
+                fs.push(add);
+            }
+
+            function test(uint64 x) public view returns (uint64) {
+                Op memory copy = op;
+                return op.f(x, op.arg) + copy.f(x, copy.arg) + fs[0](x, 1);
+            }
+        }
+        "#,
+    );
+
+    let data_account = vm.initialize_data_account();
+    vm.function("new")
+        .accounts(vec![("dataAccount", data_account)])
+        .call();
+
+    vm.function("setup")
+        .accounts(vec![("dataAccount", data_account)])
+        .call();
+
+    let res = vm
+        .function("test")
+        .arguments(&[BorshToken::Uint {
+            width: 64,
+            value: BigInt::from(5u8),
+        }])
+        .accounts(vec![("dataAccount", data_account)])
+        .call()
+        .unwrap();
+
+    assert_eq!(
+        res,
+        BorshToken::Uint {
+            width: 64,
+            value: BigInt::from(36u8),
+        }
+    );
+}
@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::parse_and_resolve;
+use solang::sema::tags::{function_tags, variable_tags};
 use solang::Target;
 use solang_parser::pt;
 
@@ -198,7 +199,8 @@ fn functions() {
         Target::Solana,
     );
 
-    assert_eq!(ns.diagnostics.len(), 5);
+    // the inheritdoc tag is an error, since b has no function foo
+    assert_eq!(ns.diagnostics.len(), 3);
 
     let func = ns
         .functions
@@ -270,7 +272,7 @@ fn variables() {
         Target::Solana,
     );
 
-    //Variable 'y' has never been used (one item error in diagnostic)
+    // the inheritdoc tag is an error, since b has no function y
     assert_eq!(ns.diagnostics.len(), 3);
 
     assert_eq!(ns.contracts[0].variables[0].tags[0].tag, "notice");
@@ -288,3 +290,65 @@ fn variables() {
     assert_eq!(ns.contracts[0].variables[0].tags[2].value, "b");
     assert_eq!(ns.contracts[0].variables[0].tags[2].no, 0);
 }
+
+#[test]
+fn inheritdoc() {
+    let ns = parse_and_resolve(
+        r#"
+        contract c is b {
+            /// @notice overridden notice
+            /// @inheritdoc b
+            function foo(int x) public pure override returns (int) {
+                return x;
+            }
+
+            /// @inheritdoc b
+            int public override bar;
+        }
+
+        contract b {
+            /// @notice foo it
+            /// @dev developer
+            /// @param x the x
+            /// @return the result
+            function foo(int x) public pure virtual returns (int) {
+                return x + 1;
+            }
+
+            /// @notice get bar
+            function bar() external view virtual returns (int) {}
+        }"#,
+        Target::Solana,
+    );
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let foo = ns
+        .functions
+        .iter()
+        .position(|func| func.id.name == "foo" && func.contract_no == Some(0))
+        .unwrap();
+
+    let tags: Vec<(&str, usize, &str)> = function_tags(foo, &ns)
+        .iter()
+        .map(|tag| (tag.tag.as_str(), tag.no, tag.value.as_str()))
+        .collect();
+
+    assert_eq!(
+        tags,
+        vec![
+            ("notice", 0, "overridden notice"),
+            ("inheritdoc", 0, "b"),
+            ("dev", 0, "developer"),
+            ("param", 0, "the x"),
+            ("return", 0, "the result"),
+        ]
+    );
+
+    let tags: Vec<(&str, usize, &str)> = variable_tags(0, 0, &ns)
+        .iter()
+        .map(|tag| (tag.tag.as_str(), tag.no, tag.value.as_str()))
+        .collect();
+
+    assert_eq!(tags, vec![("inheritdoc", 0, "b"), ("notice", 0, "get bar")]);
+}
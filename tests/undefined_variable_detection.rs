@@ -21,6 +21,12 @@ fn parse_and_codegen(src: &'static str) -> Namespace {
         strength_reduce: false,
         vector_to_slice: false,
         common_subexpression_elimination: false,
+        inline: false,
+        storage_cache: false,
+        loop_optimization: false,
+        dead_function_elimination: false,
+        pack_storage: false,
+        metadata_version: Default::default(),
         opt_level: OptimizationLevel::Default,
         generate_debug_information: false,
         log_runtime_errors: false,